@@ -14,6 +14,13 @@ fn main() {
                 "src/protocol/libp2p/schema/identify.proto",
                 "src/protocol/libp2p/schema/kademlia.proto",
                 "src/protocol/libp2p/schema/bitswap.proto",
+                "src/protocol/libp2p/schema/autonat_v1.proto",
+                "src/protocol/libp2p/schema/autonat_v2.proto",
+                "src/protocol/libp2p/schema/relay.proto",
+                "src/protocol/libp2p/schema/dcutr.proto",
+                "src/protocol/libp2p/schema/gossipsub.proto",
+                "src/protocol/libp2p/schema/floodsub.proto",
+                "src/protocol/libp2p/schema/rendezvous.proto",
             ],
             &["src"],
         )
@@ -21,16 +21,21 @@
 //! [`Litep2p`](`crate::Litep2p`) configuration.
 
 use crate::{
-    crypto::ed25519::Keypair,
+    crypto::{self, ed25519::Keypair, keystore, pnet::PreSharedKey},
+    error::KeystoreError,
     executor::{DefaultExecutor, Executor},
     protocol::{
-        libp2p::{bitswap, identify, kademlia, ping},
+        libp2p::{
+            autonat, bitswap, dcutr, floodsub, gossipsub, identify, kademlia, perf, ping, relay,
+            rendezvous,
+        },
         mdns::Config as MdnsConfig,
         notification, request_response, UserProtocol,
     },
     transport::{
-        manager::limits::ConnectionLimitsConfig, tcp::config::Config as TcpConfig,
-        KEEP_ALIVE_TIMEOUT, MAX_PARALLEL_DIALS,
+        custom::CustomTransport, manager::limits::ConnectionLimitsConfig,
+        memory::config::Config as MemoryConfig, tcp::config::Config as TcpConfig,
+        DialSchedulerConfig, DnsConfig, KEEP_ALIVE_TIMEOUT, MAX_PARALLEL_DIALS,
     },
     types::protocol::ProtocolName,
     PeerId,
@@ -38,6 +43,8 @@ use crate::{
 
 #[cfg(feature = "quic")]
 use crate::transport::quic::config::Config as QuicConfig;
+#[cfg(unix)]
+use crate::transport::unix::config::Config as UnixConfig;
 #[cfg(feature = "webrtc")]
 use crate::transport::webrtc::config::Config as WebRtcConfig;
 #[cfg(feature = "websocket")]
@@ -45,7 +52,7 @@ use crate::transport::websocket::config::Config as WebSocketConfig;
 
 use multiaddr::Multiaddr;
 
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{collections::HashMap, path::Path, sync::Arc, time::Duration};
 
 /// Connection role.
 #[derive(Debug, Copy, Clone)]
@@ -83,9 +90,22 @@ pub struct ConfigBuilder {
     #[cfg(feature = "websocket")]
     websocket: Option<WebSocketConfig>,
 
+    /// Memory transport config.
+    memory: Option<MemoryConfig>,
+
+    /// Unix domain socket transport configuration.
+    #[cfg(unix)]
+    unix: Option<UnixConfig>,
+
+    /// User-supplied transports.
+    custom_transports: Vec<Box<dyn CustomTransport>>,
+
     /// Keypair.
     keypair: Option<Keypair>,
 
+    /// Pre-shared key of the private network.
+    pre_shared_key: Option<PreSharedKey>,
+
     /// Ping protocol config.
     ping: Option<ping::Config>,
 
@@ -98,6 +118,27 @@ pub struct ConfigBuilder {
     /// Bitswap protocol config.
     bitswap: Option<bitswap::Config>,
 
+    /// AutoNAT protocol config.
+    autonat: Option<autonat::Config>,
+
+    /// Circuit relay protocol config.
+    relay: Option<relay::Config>,
+
+    /// DCUtR protocol config.
+    dcutr: Option<dcutr::Config>,
+
+    /// Gossipsub protocol config.
+    gossipsub: Option<gossipsub::Config>,
+
+    /// Floodsub protocol config.
+    floodsub: Option<floodsub::Config>,
+
+    /// Rendezvous protocol config.
+    rendezvous: Option<rendezvous::Config>,
+
+    /// Perf protocol config.
+    perf: Option<perf::Config>,
+
     /// Notification protocols.
     notification_protocols: HashMap<ProtocolName, notification::Config>,
 
@@ -124,6 +165,12 @@ pub struct ConfigBuilder {
 
     /// Close the connection if no substreams are open within this time frame.
     keep_alive_timeout: Duration,
+
+    /// DNS resolver configuration.
+    dns: DnsConfig,
+
+    /// Dial scheduler configuration.
+    dial_scheduler: DialSchedulerConfig,
 }
 
 impl Default for ConfigBuilder {
@@ -143,11 +190,23 @@ impl ConfigBuilder {
             webrtc: None,
             #[cfg(feature = "websocket")]
             websocket: None,
+            memory: None,
+            #[cfg(unix)]
+            unix: None,
+            custom_transports: Vec::new(),
             keypair: None,
+            pre_shared_key: None,
             ping: None,
             identify: None,
             kademlia: None,
             bitswap: None,
+            autonat: None,
+            relay: None,
+            dcutr: None,
+            gossipsub: None,
+            floodsub: None,
+            rendezvous: None,
+            perf: None,
             mdns: None,
             executor: None,
             max_parallel_dials: MAX_PARALLEL_DIALS,
@@ -157,6 +216,8 @@ impl ConfigBuilder {
             known_addresses: Vec::new(),
             connection_limits: ConnectionLimitsConfig::default(),
             keep_alive_timeout: KEEP_ALIVE_TIMEOUT,
+            dns: DnsConfig::default(),
+            dial_scheduler: DialSchedulerConfig::default(),
         }
     }
 
@@ -187,6 +248,32 @@ impl ConfigBuilder {
         self
     }
 
+    /// Add memory transport configuration, enabling the transport.
+    ///
+    /// The memory transport only connects to nodes running in the same process and is meant
+    /// for testing.
+    pub fn with_memory(mut self, config: MemoryConfig) -> Self {
+        self.memory = Some(config);
+        self
+    }
+
+    /// Add Unix domain socket transport configuration, enabling the transport.
+    #[cfg(unix)]
+    pub fn with_unix(mut self, config: UnixConfig) -> Self {
+        self.unix = Some(config);
+        self
+    }
+
+    /// Add user-supplied transport.
+    ///
+    /// The transport is used to dial addresses containing the multiaddr protocol returned by
+    /// [`CustomTransport::protocol()`]. Each protocol can be handled by at most one custom
+    /// transport.
+    pub fn with_custom_transport(mut self, transport: Box<dyn CustomTransport>) -> Self {
+        self.custom_transports.push(transport);
+        self
+    }
+
     /// Add keypair.
     ///
     /// If no keypair is specified, litep2p creates a new keypair.
@@ -195,6 +282,55 @@ impl ConfigBuilder {
         self
     }
 
+    /// Add keypair read from the keystore file at `path`.
+    ///
+    /// The keystore is decrypted with `password` if it's encrypted.
+    ///
+    /// Only Ed25519 keys are supported as the identity of the local node. The keystore may hold
+    /// any key type supported by [`crate::crypto::Keypair`] but loading a secp256k1, ECDSA or RSA
+    /// key fails with [`KeystoreError::UnsupportedKeyType`].
+    pub fn with_keypair_from_keystore(
+        self,
+        path: impl AsRef<Path>,
+        password: Option<&str>,
+    ) -> crate::Result<Self> {
+        let keypair = keystore::load(path, password)?;
+        self.with_identity_keypair(keypair)
+    }
+
+    /// Add keypair read from the keystore file at `path`, generating a new keypair and saving it
+    /// to `path` if the file doesn't exist.
+    ///
+    /// The keystore is encrypted with `password` if one is provided. A generated keypair is an
+    /// Ed25519 keypair and, as with [`ConfigBuilder::with_keypair_from_keystore()`], an existing
+    /// keystore must hold an Ed25519 key.
+    pub fn with_keypair_from_keystore_or_generate(
+        self,
+        path: impl AsRef<Path>,
+        password: Option<&str>,
+    ) -> crate::Result<Self> {
+        let keypair = keystore::load_or_generate(path, password)?;
+        self.with_identity_keypair(keypair)
+    }
+
+    /// Add keypair loaded from a keystore, ensuring it can be used as the identity of the node.
+    fn with_identity_keypair(self, keypair: crypto::Keypair) -> crate::Result<Self> {
+        match keypair {
+            crypto::Keypair::Ed25519(keypair) => Ok(self.with_keypair(keypair)),
+            _ => Err(KeystoreError::UnsupportedKeyType.into()),
+        }
+    }
+
+    /// Make the node part of the private network identified by `pre_shared_key`.
+    ///
+    /// All TCP and WebSocket connections are encrypted with the pre-shared key before any
+    /// protocol is negotiated, meaning the node can only connect to peers which use the same key.
+    /// Private networks are not supported by the QUIC and WebRTC transports.
+    pub fn with_pre_shared_key(mut self, pre_shared_key: PreSharedKey) -> Self {
+        self.pre_shared_key = Some(pre_shared_key);
+        self
+    }
+
     /// Enable notification protocol.
     pub fn with_notification_protocol(mut self, config: notification::Config) -> Self {
         self.notification_protocols.insert(config.protocol_name().clone(), config);
@@ -225,6 +361,54 @@ impl ConfigBuilder {
         self
     }
 
+    /// Enable libp2p AutoNAT protocol.
+    pub fn with_libp2p_autonat(mut self, config: autonat::Config) -> Self {
+        self.autonat = Some(config);
+        self
+    }
+
+    /// Enable libp2p circuit relay protocol.
+    ///
+    /// Enabling the protocol also enables dialing and accepting relayed connections over
+    /// `/p2p-circuit` addresses.
+    pub fn with_libp2p_relay(mut self, config: relay::Config) -> Self {
+        self.relay = Some(config);
+        self
+    }
+
+    /// Enable libp2p DCUtR protocol.
+    ///
+    /// Relayed connections are upgraded to direct connections through hole punching. Hole
+    /// punching over TCP requires `reuse_port` to be enabled in the TCP transport configuration.
+    pub fn with_libp2p_dcutr(mut self, config: dcutr::Config) -> Self {
+        self.dcutr = Some(config);
+        self
+    }
+
+    /// Enable libp2p gossipsub protocol.
+    pub fn with_libp2p_gossipsub(mut self, config: gossipsub::Config) -> Self {
+        self.gossipsub = Some(config);
+        self
+    }
+
+    /// Enable libp2p floodsub protocol.
+    pub fn with_libp2p_floodsub(mut self, config: floodsub::Config) -> Self {
+        self.floodsub = Some(config);
+        self
+    }
+
+    /// Enable libp2p rendezvous protocol.
+    pub fn with_libp2p_rendezvous(mut self, config: rendezvous::Config) -> Self {
+        self.rendezvous = Some(config);
+        self
+    }
+
+    /// Enable libp2p perf protocol.
+    pub fn with_libp2p_perf(mut self, config: perf::Config) -> Self {
+        self.perf = Some(config);
+        self
+    }
+
     /// Enable request-response protocol.
     pub fn with_request_response_protocol(mut self, config: request_response::Config) -> Self {
         self.request_response_protocols.insert(config.protocol_name().clone(), config);
@@ -278,6 +462,25 @@ impl ConfigBuilder {
         self
     }
 
+    /// Set DNS resolver configuration.
+    ///
+    /// The resolver is shared by all transports and caches the resolved records for as long as
+    /// their TTL allows. It's also used to resolve `/dnsaddr` addresses.
+    pub fn with_dns(mut self, config: DnsConfig) -> Self {
+        self.dns = config;
+        self
+    }
+
+    /// Set dial scheduler configuration.
+    ///
+    /// The scheduler orders the connection attempts of a dial, preferring QUIC over TCP and
+    /// alternating between IPv6 and IPv4 addresses, and staggers them so that a slow or
+    /// unreachable address doesn't hold up the dial.
+    pub fn with_dial_scheduler(mut self, config: DialSchedulerConfig) -> Self {
+        self.dial_scheduler = config;
+        self
+    }
+
     /// Build [`Litep2pConfig`].
     pub fn build(mut self) -> Litep2pConfig {
         let keypair = match self.keypair {
@@ -287,6 +490,7 @@ impl ConfigBuilder {
 
         Litep2pConfig {
             keypair,
+            pre_shared_key: self.pre_shared_key.take(),
             tcp: self.tcp.take(),
             mdns: self.mdns.take(),
             #[cfg(feature = "quic")]
@@ -295,10 +499,21 @@ impl ConfigBuilder {
             webrtc: self.webrtc.take(),
             #[cfg(feature = "websocket")]
             websocket: self.websocket.take(),
+            memory: self.memory.take(),
+            #[cfg(unix)]
+            unix: self.unix.take(),
+            custom_transports: self.custom_transports,
             ping: self.ping.take(),
             identify: self.identify.take(),
             kademlia: self.kademlia.take(),
             bitswap: self.bitswap.take(),
+            autonat: self.autonat.take(),
+            relay: self.relay.take(),
+            dcutr: self.dcutr.take(),
+            gossipsub: self.gossipsub.take(),
+            floodsub: self.floodsub.take(),
+            rendezvous: self.rendezvous.take(),
+            perf: self.perf.take(),
             max_parallel_dials: self.max_parallel_dials,
             executor: self.executor.map_or(Arc::new(DefaultExecutor {}), |executor| executor),
             user_protocols: self.user_protocols,
@@ -307,6 +522,8 @@ impl ConfigBuilder {
             known_addresses: self.known_addresses,
             connection_limits: self.connection_limits,
             keep_alive_timeout: self.keep_alive_timeout,
+            dns: self.dns,
+            dial_scheduler: self.dial_scheduler,
         }
     }
 }
@@ -328,9 +545,22 @@ pub struct Litep2pConfig {
     #[cfg(feature = "websocket")]
     pub(crate) websocket: Option<WebSocketConfig>,
 
+    /// Memory transport config.
+    pub(crate) memory: Option<MemoryConfig>,
+
+    /// Unix domain socket transport config.
+    #[cfg(unix)]
+    pub(crate) unix: Option<UnixConfig>,
+
+    /// User-supplied transports.
+    pub(crate) custom_transports: Vec<Box<dyn CustomTransport>>,
+
     /// Keypair.
     pub(crate) keypair: Keypair,
 
+    /// Pre-shared key of the private network, if the node is part of one.
+    pub(crate) pre_shared_key: Option<PreSharedKey>,
+
     /// Ping protocol configuration, if enabled.
     pub(crate) ping: Option<ping::Config>,
 
@@ -343,6 +573,27 @@ pub struct Litep2pConfig {
     /// Bitswap protocol configuration, if enabled.
     pub(crate) bitswap: Option<bitswap::Config>,
 
+    /// AutoNAT protocol configuration, if enabled.
+    pub(crate) autonat: Option<autonat::Config>,
+
+    /// Circuit relay protocol configuration, if enabled.
+    pub(crate) relay: Option<relay::Config>,
+
+    /// DCUtR protocol configuration, if enabled.
+    pub(crate) dcutr: Option<dcutr::Config>,
+
+    /// Gossipsub protocol configuration, if enabled.
+    pub(crate) gossipsub: Option<gossipsub::Config>,
+
+    /// Floodsub protocol configuration, if enabled.
+    pub(crate) floodsub: Option<floodsub::Config>,
+
+    /// Rendezvous protocol configuration, if enabled.
+    pub(crate) rendezvous: Option<rendezvous::Config>,
+
+    /// Perf protocol configuration, if enabled.
+    pub(crate) perf: Option<perf::Config>,
+
     /// Notification protocols.
     pub(crate) notification_protocols: HashMap<ProtocolName, notification::Config>,
 
@@ -369,4 +620,10 @@ pub struct Litep2pConfig {
 
     /// Close the connection if no substreams are open within this time frame.
     pub(crate) keep_alive_timeout: Duration,
+
+    /// DNS resolver configuration.
+    pub(crate) dns: DnsConfig,
+
+    /// Dial scheduler configuration.
+    pub(crate) dial_scheduler: DialSchedulerConfig,
 }
@@ -0,0 +1,257 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! ECDSA keys with secp256r1 curve support.
+
+use crate::{
+    error::{Error, ParseError},
+    PeerId,
+};
+
+use p256::{
+    ecdsa::{
+        signature::{Signer as _, Verifier as _},
+        Signature, SigningKey, VerifyingKey,
+    },
+    pkcs8::{DecodePublicKey as _, EncodePublicKey as _},
+};
+use zeroize::Zeroize;
+
+use std::fmt;
+
+/// An ECDSA keypair generated using the secp256r1 curve.
+#[derive(Clone)]
+pub struct Keypair {
+    secret: SecretKey,
+    public: PublicKey,
+}
+
+impl Keypair {
+    /// Generate a new random ECDSA keypair.
+    pub fn generate() -> Keypair {
+        Keypair::from(SecretKey::generate())
+    }
+
+    /// Sign a message using the private key of this keypair.
+    ///
+    /// The message is hashed with SHA256 before signing and the signature is DER-encoded.
+    pub fn sign(&self, msg: &[u8]) -> Vec<u8> {
+        self.secret.sign(msg)
+    }
+
+    /// Get the public key of this keypair.
+    pub fn public(&self) -> PublicKey {
+        self.public.clone()
+    }
+
+    /// Get the secret key of this keypair.
+    pub fn secret(&self) -> SecretKey {
+        self.secret.clone()
+    }
+}
+
+impl fmt::Debug for Keypair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Keypair").field("public", &self.public).finish()
+    }
+}
+
+/// Promote an ECDSA secret key into a keypair.
+impl From<SecretKey> for Keypair {
+    fn from(secret: SecretKey) -> Keypair {
+        let public = PublicKey(*secret.0.verifying_key());
+        Keypair { secret, public }
+    }
+}
+
+/// Demote an ECDSA keypair into a secret key.
+impl From<Keypair> for SecretKey {
+    fn from(kp: Keypair) -> SecretKey {
+        kp.secret
+    }
+}
+
+/// An ECDSA secret key generated using the secp256r1 curve.
+#[derive(Clone)]
+pub struct SecretKey(SigningKey);
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretKey")
+    }
+}
+
+impl SecretKey {
+    /// Generate a new random ECDSA secret key.
+    pub fn generate() -> SecretKey {
+        SecretKey(SigningKey::random(&mut rand::rngs::OsRng))
+    }
+
+    /// Try to parse an ECDSA secret key from a byte slice containing the big-endian
+    /// secret scalar, zeroing the input on success.
+    /// If the bytes do not constitute a valid ECDSA secret key, an error is returned.
+    pub fn try_from_bytes(mut sk_bytes: impl AsMut<[u8]>) -> crate::Result<SecretKey> {
+        let sk_bytes = sk_bytes.as_mut();
+        let secret = SigningKey::from_slice(sk_bytes)
+            .map_err(|e| Error::Other(format!("Failed to parse ecdsa secret key: {e}")))?;
+        sk_bytes.zeroize();
+        Ok(SecretKey(secret))
+    }
+
+    /// Convert this secret key to a byte array containing the big-endian secret scalar.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes().into()
+    }
+
+    /// Encode the secret key into a DER-encoded SEC1 `ECPrivateKey` structure,
+    /// which is the format used in the libp2p protobuf key encoding.
+    pub fn encode_der(&self) -> Vec<u8> {
+        p256::SecretKey::from(&self.0)
+            .to_sec1_der()
+            .expect("encoding a valid secret key to succeed")
+            .to_vec()
+    }
+
+    /// Try to decode a secret key from a DER-encoded SEC1 `ECPrivateKey` structure,
+    /// zeroing the input on success.
+    pub fn try_decode_der(mut der: impl AsMut<[u8]>) -> crate::Result<SecretKey> {
+        let der = der.as_mut();
+        let secret = p256::SecretKey::from_sec1_der(der)
+            .map_err(|e| Error::Other(format!("Failed to parse ecdsa secret key: {e}")))?;
+        der.zeroize();
+        Ok(SecretKey(secret.into()))
+    }
+
+    /// Sign a message with this secret key, producing a DER-encoded ECDSA signature
+    /// over the SHA256 hash of the message.
+    pub fn sign(&self, msg: &[u8]) -> Vec<u8> {
+        let signature: Signature = self.0.sign(msg);
+        signature.to_der().as_bytes().to_vec()
+    }
+}
+
+/// An ECDSA public key.
+#[derive(Clone, Eq, PartialEq)]
+pub struct PublicKey(VerifyingKey);
+
+impl fmt::Debug for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PublicKey(asn.1 uncompressed): ")?;
+        for byte in &self.to_bytes() {
+            write!(f, "{byte:x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl PublicKey {
+    /// Verify an ECDSA signature on a message using the public key.
+    ///
+    /// The signature must be DER-encoded and computed over the SHA256 hash of the message.
+    pub fn verify(&self, msg: &[u8], sig: &[u8]) -> bool {
+        Signature::from_der(sig).and_then(|sig| self.0.verify(msg, &sig)).is_ok()
+    }
+
+    /// Convert the public key to the uncompressed SEC1 encoding of the curve point.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_encoded_point(false).as_bytes().to_vec()
+    }
+
+    /// Try to parse a public key from a byte slice containing the SEC1 encoding
+    /// of the curve point, as produced by `to_bytes`.
+    pub fn try_from_bytes(k: &[u8]) -> Result<PublicKey, ParseError> {
+        VerifyingKey::from_sec1_bytes(k)
+            .map_err(|_| ParseError::InvalidPublicKey)
+            .map(PublicKey)
+    }
+
+    /// Encode the public key into a DER-encoded X.509 `SubjectPublicKeyInfo` structure,
+    /// which is the format used in the libp2p protobuf key encoding.
+    pub fn encode_der(&self) -> Vec<u8> {
+        self.0
+            .to_public_key_der()
+            .expect("encoding a valid public key to succeed")
+            .into_vec()
+    }
+
+    /// Try to decode a public key from a DER-encoded X.509 `SubjectPublicKeyInfo` structure.
+    pub fn try_decode_der(k: &[u8]) -> Result<PublicKey, ParseError> {
+        VerifyingKey::from_public_key_der(k)
+            .map_err(|_| ParseError::InvalidPublicKey)
+            .map(PublicKey)
+    }
+
+    /// Convert public key to `PeerId`.
+    pub fn to_peer_id(&self) -> PeerId {
+        crate::crypto::PublicKey::Ecdsa(self.clone()).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ecdsa_secret_from_bytes() {
+        let sk1 = SecretKey::generate();
+        let mut sk_bytes = sk1.to_bytes();
+        let sk2 = SecretKey::try_from_bytes(&mut sk_bytes).unwrap();
+
+        assert_eq!(sk1.to_bytes(), sk2.to_bytes());
+        assert_eq!(sk_bytes, [0u8; 32]);
+    }
+
+    #[test]
+    fn ecdsa_secret_der_encode_decode() {
+        let sk1 = SecretKey::generate();
+        let mut der = sk1.encode_der();
+        let sk2 = SecretKey::try_decode_der(&mut der).unwrap();
+
+        assert_eq!(sk1.to_bytes(), sk2.to_bytes());
+        assert!(der.iter().all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn ecdsa_public_key_encode_decode() {
+        let pk = Keypair::generate().public();
+
+        assert_eq!(PublicKey::try_from_bytes(&pk.to_bytes()).unwrap(), pk);
+        assert_eq!(PublicKey::try_decode_der(&pk.encode_der()).unwrap(), pk);
+        assert!(PublicKey::try_decode_der(&pk.to_bytes()).is_err());
+    }
+
+    #[test]
+    fn ecdsa_signature() {
+        let kp = Keypair::generate();
+        let pk = kp.public();
+
+        let msg = "hello world".as_bytes();
+        let sig = kp.sign(msg);
+        assert!(pk.verify(msg, &sig));
+
+        let mut invalid_sig = sig.clone();
+        invalid_sig[10..13].copy_from_slice(&[10, 23, 42]);
+        assert!(!pk.verify(msg, &invalid_sig));
+
+        let invalid_msg = "h3ll0 w0rld".as_bytes();
+        assert!(!pk.verify(invalid_msg, &sig));
+    }
+}
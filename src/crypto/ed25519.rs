@@ -63,6 +63,23 @@ impl Keypair {
             .map_err(|e| Error::Other(format!("Failed to parse ed25519 keypair: {e}")))
     }
 
+    /// Encode the keypair into a protobuf structure for storage.
+    ///
+    /// The encoding is compatible with the `PrivateKey` message of other libp2p implementations.
+    pub fn to_protobuf_encoding(&self) -> Vec<u8> {
+        crate::crypto::Keypair::Ed25519(self.clone()).to_protobuf_encoding()
+    }
+
+    /// Decode a keypair from a protobuf structure, e.g. read from storage.
+    ///
+    /// Returns an error if the protobuf structure doesn't contain an Ed25519 private key.
+    pub fn from_protobuf_encoding(bytes: &[u8]) -> Result<Keypair, ParseError> {
+        match crate::crypto::Keypair::from_protobuf_encoding(bytes)? {
+            crate::crypto::Keypair::Ed25519(keypair) => Ok(keypair),
+            keypair => Err(ParseError::UnknownKeyType(keypair.key_type() as i32)),
+        }
+    }
+
     /// Sign a message using the private key of this keypair.
     pub fn sign(&self, msg: &[u8]) -> Vec<u8> {
         self.0.sign(msg).to_bytes().to_vec()
@@ -0,0 +1,453 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Keystore files for node identity keys.
+//!
+//! A keystore file holds the identity keypair of a node in the libp2p protobuf `PrivateKey`
+//! format. The key can optionally be encrypted with a password, in which case the encryption key
+//! is derived from the password with scrypt and the private key is sealed with
+//! ChaCha20-Poly1305.
+//!
+//! The file starts with an 8-byte magic value, followed by a version byte and the key format:
+//!
+//! ```text
+//! plaintext: magic (8) | version (1) | 0x00 | protobuf
+//! encrypted: magic (8) | version (1) | 0x01 | log_n (1) | r (4) | p (4) | salt (32) | nonce (12) | ciphertext
+//! ```
+//!
+//! Everything preceding the ciphertext of an encrypted keystore is authenticated as associated
+//! data, so a modified header or modified encryption parameters are detected when the key is
+//! decrypted.
+//!
+//! Keys of all types supported by [`Keypair`] can be stored, but only Ed25519 keys can be used as
+//! the identity of the local node.
+
+use crate::{
+    crypto::{ed25519, Keypair},
+    error::KeystoreError,
+};
+
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use rand::RngCore;
+use zeroize::Zeroize;
+
+use std::{fs, io::Write, path::Path};
+
+/// Logging target for the file.
+const LOG_TARGET: &str = "litep2p::crypto::keystore";
+
+/// Magic value identifying a keystore file.
+const MAGIC: &[u8; 8] = b"litep2p\x00";
+
+/// Current version of the keystore format.
+const VERSION: u8 = 1u8;
+
+/// The private key is stored without encryption.
+const FORMAT_PLAINTEXT: u8 = 0u8;
+
+/// The private key is encrypted with a key derived from a password.
+const FORMAT_ENCRYPTED: u8 = 1u8;
+
+/// Default scrypt cost parameter (`N = 2^15`).
+const SCRYPT_LOG_N: u8 = 15u8;
+
+/// Default scrypt block size.
+const SCRYPT_R: u32 = 8u32;
+
+/// Default scrypt parallelization parameter.
+const SCRYPT_P: u32 = 1u32;
+
+/// Maximum accepted scrypt cost parameter so a crafted file can't exhaust the memory.
+const MAX_SCRYPT_LOG_N: u8 = 20u8;
+
+/// Size of the scrypt salt.
+const SALT_LEN: usize = 32usize;
+
+/// Size of the ChaCha20-Poly1305 nonce.
+const NONCE_LEN: usize = 12usize;
+
+/// Size of the header common to all formats.
+const HEADER_LEN: usize = MAGIC.len() + 2;
+
+/// Size of the encryption parameters following the header.
+const ENCRYPTION_PARAMS_LEN: usize = 1 + 4 + 4 + SALT_LEN + NONCE_LEN;
+
+/// Encode `keypair` into the keystore format, encrypting it if `password` is provided.
+pub fn encode(keypair: &Keypair, password: Option<&str>) -> Result<Vec<u8>, KeystoreError> {
+    encode_with_cost(keypair, password, SCRYPT_LOG_N)
+}
+
+/// Decode keypair from the keystore format, decrypting it with `password` if it's encrypted.
+pub fn decode(bytes: &[u8], password: Option<&str>) -> Result<Keypair, KeystoreError> {
+    if bytes.len() < HEADER_LEN || &bytes[..MAGIC.len()] != MAGIC {
+        return Err(KeystoreError::InvalidFormat);
+    }
+
+    let version = bytes[MAGIC.len()];
+    if version != VERSION {
+        return Err(KeystoreError::UnsupportedVersion(version));
+    }
+
+    let mut private_key = match bytes[MAGIC.len() + 1] {
+        FORMAT_PLAINTEXT => bytes[HEADER_LEN..].to_vec(),
+        FORMAT_ENCRYPTED => {
+            let password = password.ok_or(KeystoreError::PasswordRequired)?;
+            decrypt(&bytes[..HEADER_LEN], &bytes[HEADER_LEN..], password)?
+        }
+        _ => return Err(KeystoreError::InvalidFormat),
+    };
+
+    let keypair =
+        Keypair::from_protobuf_encoding(&private_key).map_err(|_| KeystoreError::InvalidKey);
+    private_key.zeroize();
+
+    keypair
+}
+
+/// Write `keypair` into a keystore file at `path`, encrypting it if `password` is provided.
+///
+/// An existing file is overwritten. On Unix, the file is only readable by the owner.
+pub fn save(
+    path: impl AsRef<Path>,
+    keypair: &Keypair,
+    password: Option<&str>,
+) -> Result<(), KeystoreError> {
+    let path = path.as_ref();
+    let bytes = encode(keypair, password)?;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path)?;
+    file.write_all(&bytes)?;
+    file.sync_all()?;
+
+    tracing::debug!(target: LOG_TARGET, ?path, encrypted = password.is_some(), "keypair saved");
+
+    Ok(())
+}
+
+/// Read keypair from the keystore file at `path`, decrypting it with `password` if it's
+/// encrypted.
+pub fn load(path: impl AsRef<Path>, password: Option<&str>) -> Result<Keypair, KeystoreError> {
+    let path = path.as_ref();
+    let bytes = fs::read(path)?;
+
+    tracing::debug!(target: LOG_TARGET, ?path, "load keypair");
+
+    decode(&bytes, password)
+}
+
+/// Read keypair from the keystore file at `path`, or generate a new Ed25519 keypair and save it
+/// to `path` if the file doesn't exist.
+pub fn load_or_generate(
+    path: impl AsRef<Path>,
+    password: Option<&str>,
+) -> Result<Keypair, KeystoreError> {
+    let path = path.as_ref();
+
+    if path.exists() {
+        return load(path, password);
+    }
+
+    tracing::info!(target: LOG_TARGET, ?path, "keystore doesn't exist, generate new keypair");
+
+    let keypair = Keypair::Ed25519(ed25519::Keypair::generate());
+    save(path, &keypair, password)?;
+
+    Ok(keypair)
+}
+
+/// Encode `keypair` into the keystore format using `log_n` as the scrypt cost parameter.
+fn encode_with_cost(
+    keypair: &Keypair,
+    password: Option<&str>,
+    log_n: u8,
+) -> Result<Vec<u8>, KeystoreError> {
+    let mut private_key = keypair.to_protobuf_encoding();
+    let mut bytes = Vec::with_capacity(HEADER_LEN + ENCRYPTION_PARAMS_LEN + private_key.len() + 16);
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(VERSION);
+
+    let result = match password {
+        None => {
+            bytes.push(FORMAT_PLAINTEXT);
+            bytes.extend_from_slice(&private_key);
+            Ok(bytes)
+        }
+        Some(password) => {
+            bytes.push(FORMAT_ENCRYPTED);
+            encrypt(&bytes, &private_key, password, log_n).map(|encrypted| {
+                bytes.extend_from_slice(&encrypted);
+                bytes
+            })
+        }
+    };
+    private_key.zeroize();
+
+    result
+}
+
+/// Derive the encryption key from `password`.
+fn derive_key(
+    password: &str,
+    salt: &[u8],
+    log_n: u8,
+    r: u32,
+    p: u32,
+) -> Result<[u8; 32], KeystoreError> {
+    let params = scrypt::Params::new(log_n, r, p, 32).map_err(|_| KeystoreError::InvalidFormat)?;
+    let mut key = [0u8; 32];
+
+    scrypt::scrypt(password.as_bytes(), salt, &params, &mut key)
+        .map_err(|_| KeystoreError::InvalidFormat)?;
+
+    Ok(key)
+}
+
+/// Get the associated data of an encrypted keystore, consisting of `header` followed by the
+/// encryption parameters `params`.
+fn associated_data(header: &[u8], params: &[u8]) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(header.len() + params.len());
+    aad.extend_from_slice(header);
+    aad.extend_from_slice(params);
+
+    aad
+}
+
+/// Encrypt `plaintext` with a key derived from `password`, authenticating `header` and the
+/// encryption parameters.
+///
+/// Returns the encryption parameters followed by the ciphertext.
+fn encrypt(
+    header: &[u8],
+    plaintext: &[u8],
+    password: &str,
+    log_n: u8,
+) -> Result<Vec<u8>, KeystoreError> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let mut bytes = Vec::with_capacity(ENCRYPTION_PARAMS_LEN + plaintext.len() + 16);
+    bytes.push(log_n);
+    bytes.extend_from_slice(&SCRYPT_R.to_be_bytes());
+    bytes.extend_from_slice(&SCRYPT_P.to_be_bytes());
+    bytes.extend_from_slice(&salt);
+    bytes.extend_from_slice(&nonce);
+
+    let mut key = derive_key(password, &salt, log_n, SCRYPT_R, SCRYPT_P)?;
+    let ciphertext = ChaCha20Poly1305::new(Key::from_slice(&key))
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad: &associated_data(header, &bytes),
+            },
+        )
+        .map_err(|_| KeystoreError::InvalidKey);
+    key.zeroize();
+
+    bytes.extend_from_slice(&ciphertext?);
+
+    Ok(bytes)
+}
+
+/// Decrypt the private key using a key derived from `password`.
+///
+/// `bytes` contains the encryption parameters followed by the ciphertext, both `header` and the
+/// encryption parameters are authenticated.
+fn decrypt(header: &[u8], bytes: &[u8], password: &str) -> Result<Vec<u8>, KeystoreError> {
+    if bytes.len() < ENCRYPTION_PARAMS_LEN {
+        return Err(KeystoreError::InvalidFormat);
+    }
+
+    let log_n = bytes[0];
+    let r = u32::from_be_bytes(bytes[1..5].try_into().expect("to succeed"));
+    let p = u32::from_be_bytes(bytes[5..9].try_into().expect("to succeed"));
+    let (salt, rest) = bytes[9..].split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    if log_n > MAX_SCRYPT_LOG_N || r != SCRYPT_R || p != SCRYPT_P {
+        return Err(KeystoreError::InvalidFormat);
+    }
+
+    let mut key = derive_key(password, salt, log_n, r, p)?;
+    let plaintext = ChaCha20Poly1305::new(Key::from_slice(&key))
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: &associated_data(header, &bytes[..ENCRYPTION_PARAMS_LEN]),
+            },
+        )
+        .map_err(|_| KeystoreError::DecryptionFailed);
+    key.zeroize();
+
+    plaintext
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cheap scrypt cost parameter so the tests run fast in debug builds.
+    const TEST_LOG_N: u8 = 4u8;
+
+    #[test]
+    fn plaintext_keystore() {
+        let keypair = Keypair::generate_secp256k1();
+        let bytes = encode(&keypair, None).unwrap();
+
+        assert_eq!(decode(&bytes, None).unwrap().public(), keypair.public());
+        assert_eq!(
+            decode(&bytes, Some("password")).unwrap().public(),
+            keypair.public()
+        );
+    }
+
+    #[test]
+    fn encrypted_keystore() {
+        let keypair = Keypair::generate_ed25519();
+        let bytes = encode_with_cost(&keypair, Some("password"), TEST_LOG_N).unwrap();
+
+        assert_eq!(
+            decode(&bytes, Some("password")).unwrap().public(),
+            keypair.public()
+        );
+        assert_eq!(
+            decode(&bytes, Some("hunter2")).unwrap_err(),
+            KeystoreError::DecryptionFailed
+        );
+        assert_eq!(
+            decode(&bytes, None).unwrap_err(),
+            KeystoreError::PasswordRequired
+        );
+
+        let mut tampered = bytes.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert_eq!(
+            decode(&tampered, Some("password")).unwrap_err(),
+            KeystoreError::DecryptionFailed
+        );
+    }
+
+    #[test]
+    fn encrypted_keystore_header_is_authenticated() {
+        let keypair = Keypair::generate_ed25519();
+        let bytes = encode_with_cost(&keypair, Some("password"), TEST_LOG_N).unwrap();
+        let (header, encrypted) = bytes.split_at(HEADER_LEN);
+
+        assert!(decrypt(header, encrypted, "password").is_ok());
+
+        let mut tampered = header.to_vec();
+        tampered[0] ^= 1;
+        assert_eq!(
+            decrypt(&tampered, encrypted, "password").unwrap_err(),
+            KeystoreError::DecryptionFailed
+        );
+    }
+
+    #[test]
+    fn invalid_keystore() {
+        let keypair = Keypair::generate_ecdsa();
+        let bytes = encode(&keypair, None).unwrap();
+
+        assert_eq!(
+            decode(&bytes[..4], None).unwrap_err(),
+            KeystoreError::InvalidFormat
+        );
+        assert_eq!(
+            decode(&keypair.to_protobuf_encoding(), None).unwrap_err(),
+            KeystoreError::InvalidFormat
+        );
+
+        let mut version = bytes.clone();
+        version[MAGIC.len()] = 2;
+        assert_eq!(
+            decode(&version, None).unwrap_err(),
+            KeystoreError::UnsupportedVersion(2)
+        );
+
+        let mut key = bytes.clone();
+        key.truncate(bytes.len() - 1);
+        assert_eq!(decode(&key, None).unwrap_err(), KeystoreError::InvalidKey);
+    }
+
+    #[test]
+    fn load_or_generate_keystore() {
+        let path = std::env::temp_dir().join(format!("litep2p-keystore-{}", rand::random::<u64>()));
+
+        assert_eq!(
+            load(&path, None).unwrap_err(),
+            KeystoreError::Io(std::io::ErrorKind::NotFound)
+        );
+
+        let keypair = load_or_generate(&path, None).unwrap();
+        assert!(std::matches!(keypair, Keypair::Ed25519(_)));
+        assert_eq!(
+            load_or_generate(&path, None).unwrap().public(),
+            keypair.public()
+        );
+        assert_eq!(load(&path, None).unwrap().public(), keypair.public());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn config_builder_keystore() {
+        let path = std::env::temp_dir().join(format!("litep2p-keystore-{}", rand::random::<u64>()));
+
+        let config = crate::config::ConfigBuilder::new()
+            .with_keypair_from_keystore_or_generate(&path, None)
+            .unwrap()
+            .build();
+        let loaded = crate::config::ConfigBuilder::new()
+            .with_keypair_from_keystore(&path, None)
+            .unwrap()
+            .build();
+        assert_eq!(config.keypair.public(), loaded.keypair.public());
+
+        save(&path, &Keypair::generate_secp256k1(), None).unwrap();
+        assert!(std::matches!(
+            crate::config::ConfigBuilder::new().with_keypair_from_keystore(&path, None),
+            Err(crate::Error::Keystore(KeystoreError::UnsupportedKeyType))
+        ));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
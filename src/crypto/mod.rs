@@ -23,19 +23,165 @@
 
 use crate::{error::ParseError, peer_id::*};
 
+use zeroize::Zeroize;
+
+pub mod ecdsa;
 pub mod ed25519;
+pub mod keystore;
 pub(crate) mod noise;
-#[cfg(feature = "quic")]
+pub mod pnet;
+pub mod rsa;
+pub mod secp256k1;
+#[cfg(any(feature = "quic", feature = "tls"))]
 pub(crate) mod tls;
 pub(crate) mod keys_proto {
     include!(concat!(env!("OUT_DIR"), "/keys_proto.rs"));
 }
 
+/// Identity keypair of a node.
+#[derive(Debug, Clone)]
+pub enum Keypair {
+    /// An Ed25519 keypair.
+    Ed25519(ed25519::Keypair),
+
+    /// A Secp256k1 keypair.
+    Secp256k1(secp256k1::Keypair),
+
+    /// An ECDSA keypair using the secp256r1 curve.
+    Ecdsa(ecdsa::Keypair),
+}
+
+impl Keypair {
+    /// Generate a new Ed25519 keypair.
+    pub fn generate_ed25519() -> Keypair {
+        Keypair::Ed25519(ed25519::Keypair::generate())
+    }
+
+    /// Generate a new Secp256k1 keypair.
+    pub fn generate_secp256k1() -> Keypair {
+        Keypair::Secp256k1(secp256k1::Keypair::generate())
+    }
+
+    /// Generate a new ECDSA keypair using the secp256r1 curve.
+    pub fn generate_ecdsa() -> Keypair {
+        Keypair::Ecdsa(ecdsa::Keypair::generate())
+    }
+
+    /// Sign a message using the private key of this keypair, producing
+    /// a signature that can be verified using the corresponding public key.
+    pub fn sign(&self, msg: &[u8]) -> Vec<u8> {
+        match self {
+            Keypair::Ed25519(pair) => pair.sign(msg),
+            Keypair::Secp256k1(pair) => pair.sign(msg),
+            Keypair::Ecdsa(pair) => pair.sign(msg),
+        }
+    }
+
+    /// Get the public key of this keypair.
+    pub fn public(&self) -> PublicKey {
+        match self {
+            Keypair::Ed25519(pair) => PublicKey::Ed25519(pair.public()),
+            Keypair::Secp256k1(pair) => PublicKey::Secp256k1(pair.public()),
+            Keypair::Ecdsa(pair) => PublicKey::Ecdsa(pair.public()),
+        }
+    }
+
+    /// Get the `PeerId` derived from the public key of this keypair.
+    pub fn to_peer_id(&self) -> PeerId {
+        self.public().to_peer_id()
+    }
+
+    /// Get the protobuf key type of this keypair.
+    pub(crate) fn key_type(&self) -> keys_proto::KeyType {
+        match self {
+            Keypair::Ed25519(_) => keys_proto::KeyType::Ed25519,
+            Keypair::Secp256k1(_) => keys_proto::KeyType::Secp256k1,
+            Keypair::Ecdsa(_) => keys_proto::KeyType::Ecdsa,
+        }
+    }
+
+    /// Encode the private key into a protobuf structure for storage.
+    ///
+    /// The encoding is compatible with the `PrivateKey` message of other libp2p implementations.
+    pub fn to_protobuf_encoding(&self) -> Vec<u8> {
+        use prost::Message;
+
+        let data = match self {
+            Keypair::Ed25519(pair) => pair.to_bytes().to_vec(),
+            Keypair::Secp256k1(pair) => pair.secret().to_bytes().to_vec(),
+            Keypair::Ecdsa(pair) => pair.secret().encode_der(),
+        };
+        let private_key = keys_proto::PrivateKey {
+            r#type: self.key_type() as i32,
+            data,
+        };
+
+        let mut buf = Vec::with_capacity(private_key.encoded_len());
+        private_key.encode(&mut buf).expect("Vec<u8> provides capacity as needed");
+        buf
+    }
+
+    /// Decode a private key from a protobuf structure, e.g. read from storage.
+    pub fn from_protobuf_encoding(bytes: &[u8]) -> Result<Keypair, ParseError> {
+        use prost::Message;
+
+        let mut private_key = keys_proto::PrivateKey::decode(bytes)?;
+        let key_type = keys_proto::KeyType::try_from(private_key.r#type)
+            .map_err(|_| ParseError::UnknownKeyType(private_key.r#type))?;
+
+        let keypair = match key_type {
+            keys_proto::KeyType::Ed25519 =>
+                ed25519::Keypair::try_from_bytes(&mut private_key.data).map(Keypair::Ed25519),
+            keys_proto::KeyType::Secp256k1 =>
+                secp256k1::SecretKey::try_from_bytes(&mut private_key.data)
+                    .map(|secret| Keypair::Secp256k1(secret.into())),
+            keys_proto::KeyType::Ecdsa => ecdsa::SecretKey::try_decode_der(&mut private_key.data)
+                .map(|secret| Keypair::Ecdsa(secret.into())),
+            keys_proto::KeyType::Rsa => {
+                private_key.data.zeroize();
+                return Err(ParseError::UnknownKeyType(key_type as i32));
+            }
+        };
+        private_key.data.zeroize();
+
+        keypair.map_err(|_| ParseError::InvalidPrivateKey)
+    }
+}
+
+impl From<ed25519::Keypair> for Keypair {
+    fn from(keypair: ed25519::Keypair) -> Self {
+        Keypair::Ed25519(keypair)
+    }
+}
+
+impl From<secp256k1::Keypair> for Keypair {
+    fn from(keypair: secp256k1::Keypair) -> Self {
+        Keypair::Secp256k1(keypair)
+    }
+}
+
+impl From<ecdsa::Keypair> for Keypair {
+    fn from(keypair: ecdsa::Keypair) -> Self {
+        Keypair::Ecdsa(keypair)
+    }
+}
+
 /// The public key of a node's identity keypair.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum PublicKey {
     /// A public Ed25519 key.
     Ed25519(ed25519::PublicKey),
+
+    /// A public Secp256k1 key.
+    Secp256k1(secp256k1::PublicKey),
+
+    /// A public ECDSA key.
+    Ecdsa(ecdsa::PublicKey),
+
+    /// A public RSA key.
+    ///
+    /// RSA keys can only be used to verify signatures of remote peers.
+    Rsa(rsa::PublicKey),
 }
 
 impl PublicKey {
@@ -48,6 +194,9 @@ impl PublicKey {
         use PublicKey::*;
         match self {
             Ed25519(pk) => pk.verify(msg, sig),
+            Secp256k1(pk) => pk.verify(msg, sig),
+            Ecdsa(pk) => pk.verify(msg, sig),
+            Rsa(pk) => pk.verify(msg, sig),
         }
     }
 
@@ -86,6 +235,18 @@ impl From<&PublicKey> for keys_proto::PublicKey {
                 r#type: keys_proto::KeyType::Ed25519 as i32,
                 data: key.to_bytes().to_vec(),
             },
+            PublicKey::Secp256k1(key) => keys_proto::PublicKey {
+                r#type: keys_proto::KeyType::Secp256k1 as i32,
+                data: key.to_bytes().to_vec(),
+            },
+            PublicKey::Ecdsa(key) => keys_proto::PublicKey {
+                r#type: keys_proto::KeyType::Ecdsa as i32,
+                data: key.encode_der(),
+            },
+            PublicKey::Rsa(key) => keys_proto::PublicKey {
+                r#type: keys_proto::KeyType::Rsa as i32,
+                data: key.encode_x509(),
+            },
         }
     }
 }
@@ -97,10 +258,15 @@ impl TryFrom<keys_proto::PublicKey> for PublicKey {
         let key_type = keys_proto::KeyType::try_from(pubkey.r#type)
             .map_err(|_| ParseError::UnknownKeyType(pubkey.r#type))?;
 
-        if key_type == keys_proto::KeyType::Ed25519 {
-            Ok(ed25519::PublicKey::try_from_bytes(&pubkey.data).map(PublicKey::Ed25519)?)
-        } else {
-            Err(ParseError::UnknownKeyType(key_type as i32))
+        match key_type {
+            keys_proto::KeyType::Ed25519 =>
+                ed25519::PublicKey::try_from_bytes(&pubkey.data).map(PublicKey::Ed25519),
+            keys_proto::KeyType::Secp256k1 =>
+                secp256k1::PublicKey::try_from_bytes(&pubkey.data).map(PublicKey::Secp256k1),
+            keys_proto::KeyType::Ecdsa =>
+                ecdsa::PublicKey::try_decode_der(&pubkey.data).map(PublicKey::Ecdsa),
+            keys_proto::KeyType::Rsa =>
+                rsa::PublicKey::try_decode_x509(&pubkey.data).map(PublicKey::Rsa),
         }
     }
 }
@@ -110,3 +276,126 @@ impl From<ed25519::PublicKey> for PublicKey {
         PublicKey::Ed25519(public_key)
     }
 }
+
+impl From<secp256k1::PublicKey> for PublicKey {
+    fn from(public_key: secp256k1::PublicKey) -> Self {
+        PublicKey::Secp256k1(public_key)
+    }
+}
+
+impl From<ecdsa::PublicKey> for PublicKey {
+    fn from(public_key: ecdsa::PublicKey) -> Self {
+        PublicKey::Ecdsa(public_key)
+    }
+}
+
+impl From<rsa::PublicKey> for PublicKey {
+    fn from(public_key: rsa::PublicKey) -> Self {
+        PublicKey::Rsa(public_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn public_key_protobuf_encode_decode() {
+        for keypair in [
+            Keypair::generate_ed25519(),
+            Keypair::generate_secp256k1(),
+            Keypair::generate_ecdsa(),
+        ] {
+            let public = keypair.public();
+            let decoded =
+                PublicKey::from_protobuf_encoding(&public.to_protobuf_encoding()).unwrap();
+
+            assert_eq!(decoded, public);
+            assert_eq!(decoded.to_peer_id(), keypair.to_peer_id());
+            assert!(public.verify(b"hello world", &keypair.sign(b"hello world")));
+        }
+    }
+
+    #[test]
+    fn private_key_protobuf_encode_decode() {
+        for keypair in [
+            Keypair::generate_ed25519(),
+            Keypair::generate_secp256k1(),
+            Keypair::generate_ecdsa(),
+        ] {
+            let decoded = Keypair::from_protobuf_encoding(&keypair.to_protobuf_encoding()).unwrap();
+
+            assert_eq!(decoded.public(), keypair.public());
+            assert_eq!(
+                decoded.to_protobuf_encoding(),
+                keypair.to_protobuf_encoding()
+            );
+        }
+
+        let ed25519 = ed25519::Keypair::generate();
+        let decoded =
+            ed25519::Keypair::from_protobuf_encoding(&ed25519.to_protobuf_encoding()).unwrap();
+        assert_eq!(decoded.public(), ed25519.public());
+        assert_eq!(
+            ed25519::Keypair::from_protobuf_encoding(
+                &Keypair::generate_secp256k1().to_protobuf_encoding()
+            )
+            .unwrap_err(),
+            ParseError::UnknownKeyType(keys_proto::KeyType::Secp256k1 as i32)
+        );
+    }
+
+    #[test]
+    fn invalid_private_key_protobuf() {
+        use prost::Message;
+
+        let encode = |key_type: keys_proto::KeyType, data: Vec<u8>| {
+            keys_proto::PrivateKey {
+                r#type: key_type as i32,
+                data,
+            }
+            .encode_to_vec()
+        };
+
+        assert_eq!(
+            Keypair::from_protobuf_encoding(&encode(keys_proto::KeyType::Ed25519, vec![1u8; 32]))
+                .unwrap_err(),
+            ParseError::InvalidPrivateKey
+        );
+        assert_eq!(
+            Keypair::from_protobuf_encoding(&encode(keys_proto::KeyType::Ecdsa, vec![1u8; 32]))
+                .unwrap_err(),
+            ParseError::InvalidPrivateKey
+        );
+        assert_eq!(
+            Keypair::from_protobuf_encoding(&encode(keys_proto::KeyType::Rsa, vec![1u8; 32]))
+                .unwrap_err(),
+            ParseError::UnknownKeyType(keys_proto::KeyType::Rsa as i32)
+        );
+    }
+
+    #[test]
+    fn rsa_public_key_protobuf_encode_decode() {
+        let public = PublicKey::Rsa(
+            rsa::PublicKey::try_decode_x509(include_bytes!("test_assets/rsa-2048.spki.der"))
+                .unwrap(),
+        );
+        let decoded = PublicKey::from_protobuf_encoding(&public.to_protobuf_encoding()).unwrap();
+
+        assert_eq!(decoded, public);
+        assert!(decoded.to_peer_id().to_base58().starts_with("Qm"));
+        assert!(decoded.verify(b"hello world", include_bytes!("test_assets/rsa-2048.sig")));
+    }
+
+    #[test]
+    fn peer_id_is_hashed_for_large_keys() {
+        // secp256k1 keys fit into an identity multihash, ECDSA keys are hashed with SHA256
+        let secp256k1 = Keypair::generate_secp256k1().to_peer_id();
+        let ecdsa = Keypair::generate_ecdsa().to_peer_id();
+
+        assert_eq!(secp256k1.as_ref().code(), 0x00);
+        assert_eq!(ecdsa.as_ref().code(), 0x12);
+        assert!(secp256k1.to_base58().starts_with("16U"));
+        assert!(ecdsa.to_base58().starts_with("Qm"));
+    }
+}
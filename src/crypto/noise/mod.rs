@@ -73,6 +73,69 @@ pub const MAX_FRAME_LEN: usize = MAX_NOISE_MSG_LEN - NOISE_EXTRA_ENCRYPT_SPACE;
 /// Logging target for the file.
 const LOG_TARGET: &str = "litep2p::crypto::noise";
 
+/// Noise extensions exchanged in the handshake payload.
+///
+/// See <https://github.com/libp2p/specs/blob/master/noise/README.md#noise-extensions>.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct NoiseExtensions {
+    /// Stream muxers supported by the node, in order of preference.
+    pub stream_muxers: Vec<String>,
+
+    /// Multihashes of the certificates of the node's WebTransport listeners.
+    pub webtransport_certhashes: Vec<Vec<u8>>,
+}
+
+impl NoiseExtensions {
+    /// Create new [`NoiseExtensions`] which advertise `stream_muxers`.
+    pub fn with_stream_muxers(stream_muxers: &[&str]) -> Self {
+        Self {
+            stream_muxers: stream_muxers.iter().map(|muxer| muxer.to_string()).collect(),
+            webtransport_certhashes: Vec::new(),
+        }
+    }
+
+    /// Check if there are no extensions to advertise.
+    pub fn is_empty(&self) -> bool {
+        self.stream_muxers.is_empty() && self.webtransport_certhashes.is_empty()
+    }
+
+    /// Select the stream muxer agreed on in the handshake.
+    ///
+    /// The muxer is the first muxer of the initiator which the responder also supports. If either
+    /// peer didn't advertise any muxers, `None` is returned and the muxer must be negotiated
+    /// with multistream-select.
+    pub fn select_stream_muxer(&self, remote: &NoiseExtensions, role: Role) -> Option<String> {
+        let (initiator, responder) = match role {
+            Role::Dialer => (self, remote),
+            Role::Listener => (remote, self),
+        };
+
+        initiator
+            .stream_muxers
+            .iter()
+            .find(|muxer| responder.stream_muxers.contains(muxer))
+            .cloned()
+    }
+}
+
+impl From<handshake_schema::NoiseExtensions> for NoiseExtensions {
+    fn from(extensions: handshake_schema::NoiseExtensions) -> Self {
+        Self {
+            stream_muxers: extensions.stream_muxers,
+            webtransport_certhashes: extensions.webtransport_certhashes,
+        }
+    }
+}
+
+impl From<NoiseExtensions> for handshake_schema::NoiseExtensions {
+    fn from(extensions: NoiseExtensions) -> Self {
+        Self {
+            stream_muxers: extensions.stream_muxers,
+            webtransport_certhashes: extensions.webtransport_certhashes,
+        }
+    }
+}
+
 #[derive(Debug)]
 #[allow(clippy::large_enum_variant)]
 enum NoiseState {
@@ -104,13 +167,15 @@ impl NoiseContext {
         keypair: snow::Keypair,
         id_keys: &Keypair,
         role: Role,
+        extensions: NoiseExtensions,
     ) -> Result<Self, NegotiationError> {
         let noise_payload = handshake_schema::NoiseHandshakePayload {
             identity_key: Some(PublicKey::Ed25519(id_keys.public()).to_protobuf_encoding()),
             identity_sig: Some(
                 id_keys.sign(&[STATIC_KEY_DOMAIN.as_bytes(), keypair.public.as_ref()].concat()),
             ),
-            ..Default::default()
+            // don't send the extensions at all if there's nothing to advertise
+            extensions: (!extensions.is_empty()).then(|| extensions.into()),
         };
 
         let mut payload = Vec::with_capacity(noise_payload.encoded_len());
@@ -124,7 +189,11 @@ impl NoiseContext {
         })
     }
 
-    pub fn new(keypair: &Keypair, role: Role) -> Result<Self, NegotiationError> {
+    pub fn new(
+        keypair: &Keypair,
+        role: Role,
+        extensions: NoiseExtensions,
+    ) -> Result<Self, NegotiationError> {
         tracing::trace!(target: LOG_TARGET, ?role, "create new noise configuration");
 
         let builder: Builder<'_> = Builder::with_resolver(
@@ -140,31 +209,42 @@ impl NoiseContext {
             Role::Listener => builder.local_private_key(static_key).build_responder()?,
         };
 
-        Self::assemble(noise, dh_keypair, keypair, role)
+        Self::assemble(noise, dh_keypair, keypair, role, extensions)
     }
 
     /// Create new [`NoiseContext`] with prologue.
+    ///
+    /// `role` is the role of the local node in the Noise handshake.
     #[cfg(feature = "webrtc")]
-    pub fn with_prologue(id_keys: &Keypair, prologue: Vec<u8>) -> Result<Self, NegotiationError> {
+    pub fn with_prologue(
+        id_keys: &Keypair,
+        prologue: Vec<u8>,
+        role: Role,
+    ) -> Result<Self, NegotiationError> {
         let noise: Builder<'_> = Builder::with_resolver(
             NOISE_PARAMETERS.parse().expect("qed; Valid noise pattern"),
             Box::new(protocol::Resolver),
         );
 
         let keypair = noise.generate_keypair()?;
+        let noise = noise.local_private_key(&keypair.private).prologue(&prologue);
 
-        let noise = noise
-            .local_private_key(&keypair.private)
-            .prologue(&prologue)
-            .build_initiator()?;
+        let noise = match role {
+            Role::Dialer => noise.build_initiator()?,
+            Role::Listener => noise.build_responder()?,
+        };
 
-        Self::assemble(noise, keypair, id_keys, Role::Dialer)
+        Self::assemble(noise, keypair, id_keys, role, NoiseExtensions::default())
     }
 
-    /// Get remote public key from the received Noise payload.
+    /// Read a length-prefixed handshake message and return the payload it carried.
     #[cfg(feature = "webrtc")]
-    pub fn get_remote_public_key(&mut self, reply: &[u8]) -> Result<PublicKey, NegotiationError> {
-        let (len_slice, reply) = reply.split_at(2);
+    fn read_prefixed_message(&mut self, message: &[u8]) -> Result<Vec<u8>, NegotiationError> {
+        if message.len() < 2 {
+            return Err(NegotiationError::ParseError(ParseError::InvalidData));
+        }
+
+        let (len_slice, message) = message.split_at(2);
         let len = u16::from_be_bytes(
             len_slice
                 .try_into()
@@ -174,14 +254,30 @@ impl NoiseContext {
         let mut buffer = vec![0u8; len];
 
         let NoiseState::Handshake(ref mut noise) = self.noise else {
-            tracing::error!(target: LOG_TARGET, "invalid state to read the second handshake message");
+            tracing::error!(target: LOG_TARGET, "invalid state to read handshake message");
             debug_assert!(false);
             return Err(NegotiationError::StateMismatch);
         };
 
-        let res = noise.read_message(reply, &mut buffer)?;
+        let res = noise.read_message(message, &mut buffer)?;
         buffer.truncate(res);
 
+        Ok(buffer)
+    }
+
+    /// Read the first handshake message sent by the initiator.
+    ///
+    /// The message doesn't carry a payload and is only read to advance the handshake.
+    #[cfg(feature = "webrtc")]
+    pub fn read_first_message(&mut self, message: &[u8]) -> Result<(), NegotiationError> {
+        self.read_prefixed_message(message).map(|_| ())
+    }
+
+    /// Get remote public key from the received Noise payload.
+    #[cfg(feature = "webrtc")]
+    pub fn get_remote_public_key(&mut self, reply: &[u8]) -> Result<PublicKey, NegotiationError> {
+        let buffer = self.read_prefixed_message(reply)?;
+
         let payload = handshake_schema::NoiseHandshakePayload::decode(buffer.as_slice())
             .map_err(|err| NegotiationError::ParseError(err.into()))?;
 
@@ -338,7 +434,7 @@ enum WriteState {
     },
 }
 
-pub struct NoiseSocket<S: AsyncRead + AsyncWrite + Unpin> {
+pub struct NoiseSocket<S> {
     io: S,
     noise: NoiseContext,
     current_frame_size: Option<usize>,
@@ -703,16 +799,21 @@ fn parse_and_verify_peer_id(
 }
 
 /// Perform Noise handshake.
+///
+/// `extensions` are advertised to the remote peer and the extensions advertised by the remote peer
+/// are returned. If the remote peer didn't advertise any extensions, the returned extensions are
+/// empty.
 pub async fn handshake<S: AsyncRead + AsyncWrite + Unpin>(
     mut io: S,
     keypair: &Keypair,
     role: Role,
+    extensions: NoiseExtensions,
     max_read_ahead_factor: usize,
     max_write_buffer_size: usize,
-) -> Result<(NoiseSocket<S>, PeerId), NegotiationError> {
+) -> Result<(NoiseSocket<S>, PeerId, NoiseExtensions), NegotiationError> {
     tracing::debug!(target: LOG_TARGET, ?role, "start noise handshake");
 
-    let mut noise = NoiseContext::new(keypair, role)?;
+    let mut noise = NoiseContext::new(keypair, role, extensions)?;
     let payload = match role {
         Role::Dialer => {
             // write initial message
@@ -753,9 +854,13 @@ pub async fn handshake<S: AsyncRead + AsyncWrite + Unpin>(
         }
     };
 
+    let remote_extensions: NoiseExtensions =
+        payload.extensions.clone().map(Into::into).unwrap_or_default();
     let dh_remote_pubkey = noise.get_handshake_dh_remote_pubkey()?;
     let peer = parse_and_verify_peer_id(payload, dh_remote_pubkey)?;
 
+    tracing::trace!(target: LOG_TARGET, ?role, ?peer, ?remote_extensions, "noise handshake done");
+
     Ok((
         NoiseSocket::new(
             io,
@@ -764,6 +869,7 @@ pub async fn handshake<S: AsyncRead + AsyncWrite + Unpin>(
             max_write_buffer_size,
         ),
         peer,
+        remote_extensions,
     ))
 }
 
@@ -807,6 +913,7 @@ mod tests {
                 io1,
                 &keypair1,
                 Role::Dialer,
+                NoiseExtensions::default(),
                 MAX_READ_AHEAD_FACTOR,
                 MAX_WRITE_BUFFER_SIZE
             ),
@@ -814,6 +921,7 @@ mod tests {
                 io2,
                 &keypair2,
                 Role::Listener,
+                NoiseExtensions::default(),
                 MAX_READ_AHEAD_FACTOR,
                 MAX_WRITE_BUFFER_SIZE
             )
@@ -822,6 +930,8 @@ mod tests {
 
         assert_eq!(res1.1, peer2_id);
         assert_eq!(res2.1, peer1_id);
+        assert!(res1.2.is_empty());
+        assert!(res2.2.is_empty());
 
         // verify the connection works by reading a string
         let mut buf = vec![0u8; 512];
@@ -831,6 +941,74 @@ mod tests {
         assert_eq!(std::str::from_utf8(&buf[..sent]), Ok("hello, world"));
     }
 
+    async fn exchange_extensions(
+        dialer: NoiseExtensions,
+        listener: NoiseExtensions,
+    ) -> (NoiseExtensions, NoiseExtensions) {
+        let (io1, io2) = futures_ringbuf::Endpoint::pair(4096, 4096);
+        let (keypair1, keypair2) = (Keypair::generate(), Keypair::generate());
+
+        let (res1, res2) = tokio::join!(
+            handshake(io1, &keypair1, Role::Dialer, dialer, 5, 2),
+            handshake(io2, &keypair2, Role::Listener, listener, 5, 2),
+        );
+
+        (res1.unwrap().2, res2.unwrap().2)
+    }
+
+    #[tokio::test]
+    async fn stream_muxer_negotiated_in_handshake() {
+        let dialer = NoiseExtensions::with_stream_muxers(&["/mplex/6.7.0", "/yamux/1.0.0"]);
+        let listener = NoiseExtensions {
+            stream_muxers: vec!["/yamux/1.0.0".to_string(), "/mplex/6.7.0".to_string()],
+            webtransport_certhashes: vec![vec![0x12, 0x20, 0x01]],
+        };
+
+        let (from_listener, from_dialer) =
+            exchange_extensions(dialer.clone(), listener.clone()).await;
+
+        assert_eq!(from_listener, listener);
+        assert_eq!(from_dialer, dialer);
+
+        // the initiator's preference wins
+        assert_eq!(
+            dialer.select_stream_muxer(&from_listener, Role::Dialer),
+            Some("/mplex/6.7.0".to_string())
+        );
+        assert_eq!(
+            listener.select_stream_muxer(&from_dialer, Role::Listener),
+            Some("/mplex/6.7.0".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn remote_without_extensions() {
+        let dialer = NoiseExtensions::with_stream_muxers(&["/yamux/1.0.0"]);
+
+        let (from_listener, from_dialer) =
+            exchange_extensions(dialer.clone(), NoiseExtensions::default()).await;
+
+        assert!(from_listener.is_empty());
+        assert_eq!(from_dialer, dialer);
+        assert_eq!(
+            dialer.select_stream_muxer(&from_listener, Role::Dialer),
+            None
+        );
+        assert_eq!(
+            NoiseExtensions::default().select_stream_muxer(&from_dialer, Role::Listener),
+            None
+        );
+    }
+
+    #[test]
+    fn no_common_stream_muxer() {
+        let local = NoiseExtensions::with_stream_muxers(&["/yamux/1.0.0"]);
+        let remote = NoiseExtensions::with_stream_muxers(&["/mplex/6.7.0"]);
+
+        assert_eq!(local.select_stream_muxer(&remote, Role::Dialer), None);
+        assert_eq!(local.select_stream_muxer(&remote, Role::Listener), None);
+    }
+
     #[test]
     fn invalid_peer_id_schema() {
         let payload = handshake_schema::NoiseHandshakePayload {
@@ -843,4 +1021,36 @@ mod tests {
             _ => panic!("invalid error"),
         }
     }
+
+    #[test]
+    fn verify_non_ed25519_identity() {
+        let dh_remote_pubkey = [1u8; 32];
+
+        for keypair in [
+            crate::crypto::Keypair::generate_secp256k1(),
+            crate::crypto::Keypair::generate_ecdsa(),
+        ] {
+            let signature =
+                keypair.sign(&[STATIC_KEY_DOMAIN.as_bytes(), &dh_remote_pubkey].concat());
+            let payload = handshake_schema::NoiseHandshakePayload {
+                identity_key: Some(keypair.public().to_protobuf_encoding()),
+                identity_sig: Some(signature.clone()),
+                extensions: None,
+            };
+            assert_eq!(
+                parse_and_verify_peer_id(payload, &dh_remote_pubkey).unwrap(),
+                keypair.to_peer_id()
+            );
+
+            let payload = handshake_schema::NoiseHandshakePayload {
+                identity_key: Some(keypair.public().to_protobuf_encoding()),
+                identity_sig: Some(signature),
+                extensions: None,
+            };
+            match parse_and_verify_peer_id(payload, &[2u8; 32]).unwrap_err() {
+                NegotiationError::BadSignature => {}
+                _ => panic!("invalid error"),
+            }
+        }
+    }
 }
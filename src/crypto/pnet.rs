@@ -0,0 +1,457 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Private networks (pnet).
+//!
+//! Nodes of a private network share a 32-byte key and encrypt every connection with an XSalsa20
+//! stream cipher keyed with it, before any other protocol is negotiated. Nodes that don't know
+//! the key are unable to negotiate anything over the connection.
+//!
+//! See <https://github.com/libp2p/specs/blob/master/pnet/Private-Networks-PSK-V1.md>.
+//!
+//! The key is stored in the following format:
+//!
+//! ```text
+//! /key/swarm/psk/1.0.0/
+//! /base16/
+//! <64 hexadecimal characters>
+//! ```
+
+use crate::{
+    error::{NegotiationError, PreSharedKeyError},
+    multistream_select::{
+        NegotiationError as MultiStreamNegotiationError, ProtocolError as MultiStreamProtocolError,
+    },
+};
+
+use futures::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use rand::RngCore;
+use salsa20::{
+    cipher::{KeyIvInit, StreamCipher},
+    XSalsa20,
+};
+use zeroize::Zeroize;
+
+use std::{
+    fmt, fs, io,
+    path::Path,
+    pin::Pin,
+    str::FromStr,
+    task::{Context, Poll},
+};
+
+/// Logging target for the file.
+const LOG_TARGET: &str = "litep2p::crypto::pnet";
+
+/// Key type header of the pre-shared key file.
+const KEY_TYPE: &str = "/key/swarm/psk/1.0.0/";
+
+/// Key encoding header of the pre-shared key file.
+const KEY_ENCODING: &str = "/base16/";
+
+/// Size of the pre-shared key.
+const KEY_LEN: usize = 32usize;
+
+/// Size of the XSalsa20 nonce.
+const NONCE_LEN: usize = 24usize;
+
+/// Pre-shared key of a private network.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PreSharedKey([u8; KEY_LEN]);
+
+impl PreSharedKey {
+    /// Create new [`PreSharedKey`] from raw bytes.
+    pub fn new(key: [u8; KEY_LEN]) -> Self {
+        Self(key)
+    }
+
+    /// Generate new random [`PreSharedKey`].
+    pub fn generate() -> Self {
+        let mut key = [0u8; KEY_LEN];
+        rand::thread_rng().fill_bytes(&mut key);
+
+        Self(key)
+    }
+
+    /// Read [`PreSharedKey`] from the pre-shared key file at `path`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, PreSharedKeyError> {
+        let contents = fs::read_to_string(path.as_ref()).map_err(|error| {
+            tracing::debug!(
+                target: LOG_TARGET,
+                path = ?path.as_ref(),
+                ?error,
+                "failed to read pre-shared key file",
+            );
+            PreSharedKeyError::Io(error.kind())
+        })?;
+
+        contents.parse()
+    }
+}
+
+impl FromStr for PreSharedKey {
+    type Err = PreSharedKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().map(str::trim);
+
+        if lines.next() != Some(KEY_TYPE) {
+            return Err(PreSharedKeyError::InvalidKeyType);
+        }
+
+        if lines.next() != Some(KEY_ENCODING) {
+            return Err(PreSharedKeyError::InvalidEncoding);
+        }
+
+        let encoded = lines.next().ok_or(PreSharedKeyError::InvalidKey)?.as_bytes();
+        if encoded.len() != KEY_LEN * 2 {
+            return Err(PreSharedKeyError::InvalidKey);
+        }
+
+        let mut key = [0u8; KEY_LEN];
+        for (byte, chunk) in key.iter_mut().zip(encoded.chunks_exact(2)) {
+            let chunk = std::str::from_utf8(chunk).map_err(|_| PreSharedKeyError::InvalidKey)?;
+            *byte = u8::from_str_radix(chunk, 16).map_err(|_| PreSharedKeyError::InvalidKey)?;
+        }
+
+        Ok(Self(key))
+    }
+}
+
+impl fmt::Display for PreSharedKey {
+    /// Format the key in the pre-shared key file format.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{KEY_TYPE}")?;
+        writeln!(f, "{KEY_ENCODING}")?;
+
+        for byte in &self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for PreSharedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PreSharedKey").field(&"<redacted>").finish()
+    }
+}
+
+impl Drop for PreSharedKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+/// Stream ciphers of a private network connection.
+struct Ciphers {
+    /// Cipher for outbound data, keyed with the local nonce.
+    write: XSalsa20,
+
+    /// Cipher for inbound data, keyed with the remote nonce.
+    read: XSalsa20,
+
+    /// Encrypted data that hasn't been written to the underlying stream yet.
+    write_buffer: Vec<u8>,
+}
+
+/// Connection which is encrypted with the pre-shared key of a private network.
+///
+/// If the node is not part of a private network, the data is passed through unmodified.
+pub(crate) struct PnetStream<S> {
+    /// Underlying stream.
+    io: S,
+
+    /// Stream ciphers, if the connection is encrypted.
+    ciphers: Option<Ciphers>,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> PnetStream<S> {
+    /// Poll the underlying stream until all buffered encrypted data has been written.
+    fn poll_write_buffer(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let Some(ciphers) = self.ciphers.as_mut() else {
+            return Poll::Ready(Ok(()));
+        };
+
+        while !ciphers.write_buffer.is_empty() {
+            match futures::ready!(Pin::new(&mut self.io).poll_write(cx, &ciphers.write_buffer))? {
+                0 => return Poll::Ready(Err(io::ErrorKind::WriteZero.into())),
+                nwritten => {
+                    ciphers.write_buffer.drain(..nwritten);
+                }
+            }
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> AsyncRead for PnetStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = Pin::into_inner(self);
+        let nread = futures::ready!(Pin::new(&mut this.io).poll_read(cx, buf))?;
+
+        if let Some(ciphers) = this.ciphers.as_mut() {
+            ciphers.read.apply_keystream(&mut buf[..nread]);
+        }
+
+        Poll::Ready(Ok(nread))
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> AsyncWrite for PnetStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = Pin::into_inner(self);
+
+        if this.ciphers.is_none() {
+            return Pin::new(&mut this.io).poll_write(cx, buf);
+        }
+
+        // the keystream has already been applied to the buffered data so it must be written
+        // out before more data can be encrypted
+        futures::ready!(this.poll_write_buffer(cx))?;
+
+        let ciphers = this.ciphers.as_mut().expect("ciphers to exist; qed");
+        ciphers.write_buffer.extend_from_slice(buf);
+        ciphers.write.apply_keystream(&mut ciphers.write_buffer);
+
+        // the data is now owned by `PnetStream`, attempt to write it but don't wait for it
+        if let Poll::Ready(Err(error)) = this.poll_write_buffer(cx) {
+            return Poll::Ready(Err(error));
+        }
+
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = Pin::into_inner(self);
+
+        futures::ready!(this.poll_write_buffer(cx))?;
+        Pin::new(&mut this.io).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = Pin::into_inner(self);
+
+        futures::ready!(this.poll_write_buffer(cx))?;
+        Pin::new(&mut this.io).poll_close(cx)
+    }
+}
+
+/// Wrap `io` in the private network stream cipher.
+///
+/// Both peers send a random nonce to each other, after which all data written to the connection
+/// is encrypted with the local nonce and all data read from it is decrypted with the remote
+/// nonce. If `pre_shared_key` is `None`, `io` is returned as-is.
+pub(crate) async fn handshake<S: AsyncRead + AsyncWrite + Unpin>(
+    mut io: S,
+    pre_shared_key: Option<&PreSharedKey>,
+) -> Result<PnetStream<S>, NegotiationError> {
+    let Some(pre_shared_key) = pre_shared_key else {
+        return Ok(PnetStream { io, ciphers: None });
+    };
+
+    tracing::trace!(target: LOG_TARGET, "start pnet handshake");
+
+    let mut local_nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut local_nonce);
+
+    io.write_all(&local_nonce).await?;
+    io.flush().await?;
+
+    // a peer outside of the private network treats the nonce as a protocol violation and closes
+    // the connection without sending its own nonce, leaving the nonce possibly unread which
+    // resets the connection
+    let mut remote_nonce = [0u8; NONCE_LEN];
+    io.read_exact(&mut remote_nonce).await.map_err(|error| match error.kind() {
+        io::ErrorKind::UnexpectedEof | io::ErrorKind::ConnectionReset =>
+            NegotiationError::PreSharedKeyMismatch,
+        kind => NegotiationError::IoError(kind),
+    })?;
+
+    let key = (&pre_shared_key.0).into();
+
+    Ok(PnetStream {
+        io,
+        ciphers: Some(Ciphers {
+            write: XSalsa20::new(key, &local_nonce.into()),
+            read: XSalsa20::new(key, &remote_nonce.into()),
+            write_buffer: Vec::new(),
+        }),
+    })
+}
+
+/// Check if `error`, returned by the security protocol negotiation of a private network
+/// connection, was caused by the remote peer using a different pre-shared key.
+///
+/// With mismatching keys, both peers decrypt garbage: either the received multistream-select
+/// message can't be parsed or the remote peer closes the connection after failing to parse the
+/// local node's message. Any other error, such as a timeout or an unsupported protocol, is
+/// unrelated to the pre-shared key.
+pub(crate) fn is_key_mismatch(error: &NegotiationError) -> bool {
+    let is_garbled = |kind: io::ErrorKind| {
+        std::matches!(
+            kind,
+            io::ErrorKind::UnexpectedEof | io::ErrorKind::InvalidData
+        )
+    };
+
+    match error {
+        NegotiationError::MultistreamSelectError(MultiStreamNegotiationError::ProtocolError(
+            error,
+        )) => match error {
+            MultiStreamProtocolError::InvalidMessage
+            | MultiStreamProtocolError::InvalidProtocol => true,
+            MultiStreamProtocolError::IoError(error) => is_garbled(error.kind()),
+            _ => false,
+        },
+        NegotiationError::IoError(kind) => is_garbled(*kind),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_ringbuf::Endpoint;
+
+    #[test]
+    fn encode_and_decode_key() {
+        let key = PreSharedKey::generate();
+
+        assert_eq!(key.to_string().parse::<PreSharedKey>().unwrap(), key);
+    }
+
+    #[test]
+    fn decode_key_file() {
+        let key = "/key/swarm/psk/1.0.0/\n/base16/\n\
+            6189c5cf0b87fb800c1a9feeda73c6ab5e998db48fb9e6a978575c770ceef683\n";
+
+        let key = key.parse::<PreSharedKey>().unwrap();
+        assert_eq!(key.0[..4], [0x61, 0x89, 0xc5, 0xcf]);
+    }
+
+    #[test]
+    fn invalid_key_file() {
+        assert_eq!(
+            "/key/swarm/psk/2.0.0/\n/base16/\n00".parse::<PreSharedKey>(),
+            Err(PreSharedKeyError::InvalidKeyType)
+        );
+        assert_eq!(
+            "/key/swarm/psk/1.0.0/\n/base64/\n00".parse::<PreSharedKey>(),
+            Err(PreSharedKeyError::InvalidEncoding)
+        );
+        assert_eq!(
+            "/key/swarm/psk/1.0.0/\n/base16/\n00".parse::<PreSharedKey>(),
+            Err(PreSharedKeyError::InvalidKey)
+        );
+        assert_eq!(
+            format!("/key/swarm/psk/1.0.0/\n/base16/\n{}", "zz".repeat(32)).parse::<PreSharedKey>(),
+            Err(PreSharedKeyError::InvalidKey)
+        );
+    }
+
+    #[tokio::test]
+    async fn exchange_data() {
+        let key = PreSharedKey::generate();
+        let (dialer, listener) = Endpoint::pair(64, 64);
+
+        let (dialer, listener) = tokio::join!(
+            handshake(dialer, Some(&key)),
+            handshake(listener, Some(&key))
+        );
+        let (mut dialer, mut listener) = (dialer.unwrap(), listener.unwrap());
+
+        let payload = vec![0x13u8; 1024];
+        let ((), received) = tokio::join!(
+            async {
+                dialer.write_all(&payload).await.unwrap();
+                dialer.flush().await.unwrap();
+            },
+            async {
+                let mut buffer = vec![0u8; 1024];
+                listener.read_exact(&mut buffer).await.unwrap();
+                buffer
+            },
+        );
+
+        assert_eq!(received, payload);
+    }
+
+    #[tokio::test]
+    async fn mismatching_keys() {
+        let (dialer, listener) = Endpoint::pair(64, 64);
+
+        let (dialer_key, listener_key) = (PreSharedKey::generate(), PreSharedKey::generate());
+
+        let (dialer, listener) = tokio::join!(
+            handshake(dialer, Some(&dialer_key)),
+            handshake(listener, Some(&listener_key))
+        );
+        let (mut dialer, mut listener) = (dialer.unwrap(), listener.unwrap());
+
+        let payload = vec![0x13u8; 32];
+        dialer.write_all(&payload).await.unwrap();
+        dialer.flush().await.unwrap();
+
+        let mut buffer = vec![0u8; 32];
+        listener.read_exact(&mut buffer).await.unwrap();
+        assert_ne!(buffer, payload);
+    }
+
+    #[test]
+    fn key_mismatch_errors() {
+        let protocol_error = |error| {
+            NegotiationError::MultistreamSelectError(MultiStreamNegotiationError::ProtocolError(
+                error,
+            ))
+        };
+
+        assert!(is_key_mismatch(&protocol_error(
+            MultiStreamProtocolError::InvalidMessage
+        )));
+        assert!(is_key_mismatch(&protocol_error(
+            MultiStreamProtocolError::IoError(io::ErrorKind::UnexpectedEof.into())
+        )));
+        assert!(is_key_mismatch(&NegotiationError::IoError(
+            io::ErrorKind::InvalidData
+        )));
+
+        assert!(!is_key_mismatch(&NegotiationError::Timeout));
+        assert!(!is_key_mismatch(&protocol_error(
+            MultiStreamProtocolError::ProtocolNotSupported
+        )));
+        assert!(!is_key_mismatch(&protocol_error(
+            MultiStreamProtocolError::IoError(io::ErrorKind::ConnectionReset.into())
+        )));
+        assert!(!is_key_mismatch(&NegotiationError::MultistreamSelectError(
+            MultiStreamNegotiationError::Failed
+        )));
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! RSA public keys.
+//!
+//! Only the verification of signatures produced by remote peers is supported. RSA keys can't be
+//! used as the identity of the local node.
+
+use crate::{error::ParseError, PeerId};
+
+use ring::signature::{UnparsedPublicKey, RSA_PKCS1_2048_8192_SHA256};
+use yasna::models::ObjectIdentifier;
+
+use std::fmt;
+
+/// Object identifier of `rsaEncryption` as defined in [RFC 8017](https://www.rfc-editor.org/rfc/rfc8017#appendix-A.1).
+const RSA_ENCRYPTION_OID: [u64; 7] = [1, 2, 840, 113549, 1, 1, 1];
+
+/// Minimum size of the modulus in bits, as mandated by the libp2p specification.
+const MIN_MODULUS_BITS: usize = 2048;
+
+/// Maximum size of the modulus in bits, as mandated by the libp2p specification.
+const MAX_MODULUS_BITS: usize = 16384;
+
+/// An RSA public key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PublicKey(Vec<u8>);
+
+impl fmt::Debug for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PublicKey(PKCS1): ")?;
+        for byte in &self.0 {
+            write!(f, "{byte:x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl PublicKey {
+    /// Verify an RSA signature on a message using the public key.
+    ///
+    /// The signature must be an RSASSA-PKCS1-v1_5 signature computed over the SHA256 hash of the
+    /// message.
+    pub fn verify(&self, msg: &[u8], sig: &[u8]) -> bool {
+        UnparsedPublicKey::new(&RSA_PKCS1_2048_8192_SHA256, &self.0)
+            .verify(msg, sig)
+            .is_ok()
+    }
+
+    /// Get the public key as a DER-encoded PKCS#1 `RSAPublicKey` structure.
+    pub fn to_pkcs1(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+
+    /// Try to decode a public key from a DER-encoded PKCS#1 `RSAPublicKey` structure.
+    pub fn try_decode_pkcs1(k: &[u8]) -> Result<PublicKey, ParseError> {
+        let modulus = yasna::parse_der(k, |reader| {
+            reader.read_sequence(|reader| {
+                let (modulus, positive) = reader.next().read_bigint_bytes()?;
+                let (_exponent, _) = reader.next().read_bigint_bytes()?;
+
+                Ok(positive.then_some(modulus))
+            })
+        })
+        .map_err(|_| ParseError::InvalidPublicKey)?
+        .ok_or(ParseError::InvalidPublicKey)?;
+
+        let modulus_bits = modulus.len() * 8 - modulus[0].leading_zeros() as usize;
+        if !(MIN_MODULUS_BITS..=MAX_MODULUS_BITS).contains(&modulus_bits) {
+            return Err(ParseError::InvalidPublicKey);
+        }
+
+        Ok(PublicKey(k.to_vec()))
+    }
+
+    /// Encode the public key into a DER-encoded X.509 `SubjectPublicKeyInfo` structure,
+    /// which is the format used in the libp2p protobuf key encoding.
+    pub fn encode_x509(&self) -> Vec<u8> {
+        yasna::construct_der(|writer| {
+            writer.write_sequence(|writer| {
+                writer.next().write_sequence(|writer| {
+                    writer.next().write_oid(&ObjectIdentifier::from_slice(&RSA_ENCRYPTION_OID));
+                    writer.next().write_null();
+                });
+                writer.next().write_bitvec_bytes(&self.0, self.0.len() * 8);
+            })
+        })
+    }
+
+    /// Try to decode a public key from a DER-encoded X.509 `SubjectPublicKeyInfo` structure.
+    pub fn try_decode_x509(k: &[u8]) -> Result<PublicKey, ParseError> {
+        let (algorithm, key, bits) = yasna::parse_der(k, |reader| {
+            reader.read_sequence(|reader| {
+                let algorithm = reader.next().read_sequence(|reader| {
+                    let algorithm = reader.next().read_oid()?;
+                    reader.next().read_null()?;
+
+                    Ok(algorithm)
+                })?;
+                let (key, bits) = reader.next().read_bitvec_bytes()?;
+
+                Ok((algorithm, key, bits))
+            })
+        })
+        .map_err(|_| ParseError::InvalidPublicKey)?;
+
+        if algorithm.components().as_slice() != RSA_ENCRYPTION_OID || bits != key.len() * 8 {
+            return Err(ParseError::InvalidPublicKey);
+        }
+
+        PublicKey::try_decode_pkcs1(&key)
+    }
+
+    /// Convert public key to `PeerId`.
+    pub fn to_peer_id(&self) -> PeerId {
+        crate::crypto::PublicKey::Rsa(self.clone()).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8] = include_bytes!("test_assets/rsa-2048.spki.der");
+    const SIGNATURE: &[u8] = include_bytes!("test_assets/rsa-2048.sig");
+
+    #[test]
+    fn rsa_x509_encode_decode() {
+        let key = PublicKey::try_decode_x509(KEY).unwrap();
+
+        assert_eq!(key.encode_x509(), KEY);
+        assert_eq!(PublicKey::try_decode_pkcs1(&key.to_pkcs1()).unwrap(), key);
+        assert!(PublicKey::try_decode_x509(&key.to_pkcs1()).is_err());
+        assert!(PublicKey::try_decode_pkcs1(KEY).is_err());
+    }
+
+    #[test]
+    fn rsa_signature() {
+        let key = PublicKey::try_decode_x509(KEY).unwrap();
+
+        assert!(key.verify(b"hello world", SIGNATURE));
+        assert!(!key.verify(b"h3ll0 w0rld", SIGNATURE));
+
+        let mut invalid_sig = SIGNATURE.to_vec();
+        invalid_sig[10..13].copy_from_slice(&[10, 23, 42]);
+        assert!(!key.verify(b"hello world", &invalid_sig));
+    }
+
+    #[test]
+    fn rsa_small_modulus_rejected() {
+        // 1024-bit modulus with exponent 65537
+        let key = yasna::construct_der(|writer| {
+            writer.write_sequence(|writer| {
+                writer.next().write_bigint_bytes(&[0xff; 128], true);
+                writer.next().write_u32(65537);
+            })
+        });
+
+        assert_eq!(
+            PublicKey::try_decode_pkcs1(&key),
+            Err(ParseError::InvalidPublicKey)
+        );
+    }
+}
@@ -0,0 +1,221 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Secp256k1 keys.
+
+use crate::{
+    error::{Error, ParseError},
+    PeerId,
+};
+
+use libsecp256k1::{Message, Signature};
+use sha2::{Digest as _, Sha256};
+use zeroize::Zeroize;
+
+use std::fmt;
+
+/// A Secp256k1 keypair.
+#[derive(Clone)]
+pub struct Keypair {
+    secret: SecretKey,
+    public: PublicKey,
+}
+
+impl Keypair {
+    /// Generate a new random Secp256k1 keypair.
+    pub fn generate() -> Keypair {
+        Keypair::from(SecretKey::generate())
+    }
+
+    /// Sign a message using the private key of this keypair.
+    ///
+    /// The message is hashed with SHA256 before signing and the signature is DER-encoded.
+    pub fn sign(&self, msg: &[u8]) -> Vec<u8> {
+        self.secret.sign(msg)
+    }
+
+    /// Get the public key of this keypair.
+    pub fn public(&self) -> PublicKey {
+        self.public.clone()
+    }
+
+    /// Get the secret key of this keypair.
+    pub fn secret(&self) -> SecretKey {
+        self.secret.clone()
+    }
+}
+
+impl fmt::Debug for Keypair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Keypair").field("public", &self.public).finish()
+    }
+}
+
+/// Promote a Secp256k1 secret key into a keypair.
+impl From<SecretKey> for Keypair {
+    fn from(secret: SecretKey) -> Keypair {
+        let public = PublicKey(libsecp256k1::PublicKey::from_secret_key(&secret.0));
+        Keypair { secret, public }
+    }
+}
+
+/// Demote a Secp256k1 keypair into a secret key.
+impl From<Keypair> for SecretKey {
+    fn from(kp: Keypair) -> SecretKey {
+        kp.secret
+    }
+}
+
+/// A Secp256k1 secret key.
+#[derive(Clone)]
+pub struct SecretKey(libsecp256k1::SecretKey);
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretKey")
+    }
+}
+
+impl SecretKey {
+    /// Generate a new random Secp256k1 secret key.
+    pub fn generate() -> SecretKey {
+        SecretKey(libsecp256k1::SecretKey::random(&mut rand::thread_rng()))
+    }
+
+    /// Try to parse a Secp256k1 secret key from a byte slice
+    /// containing the actual key, zeroing the input on success.
+    /// If the bytes do not constitute a valid Secp256k1 secret key, an error is
+    /// returned.
+    pub fn try_from_bytes(mut sk_bytes: impl AsMut<[u8]>) -> crate::Result<SecretKey> {
+        let sk_bytes = sk_bytes.as_mut();
+        let secret = libsecp256k1::SecretKey::parse_slice(&*sk_bytes)
+            .map_err(|e| Error::Other(format!("Failed to parse secp256k1 secret key: {e}")))?;
+        sk_bytes.zeroize();
+        Ok(SecretKey(secret))
+    }
+
+    /// Convert this secret key to a byte array.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.serialize()
+    }
+
+    /// Sign a message with this secret key, producing a DER-encoded ECDSA signature
+    /// over the SHA256 hash of the message.
+    pub fn sign(&self, msg: &[u8]) -> Vec<u8> {
+        let digest: [u8; 32] = Sha256::digest(msg).into();
+        let message = Message::parse(&digest);
+
+        libsecp256k1::sign(&message, &self.0).0.serialize_der().as_ref().to_vec()
+    }
+}
+
+/// A Secp256k1 public key.
+#[derive(Eq, PartialEq, Clone)]
+pub struct PublicKey(libsecp256k1::PublicKey);
+
+impl fmt::Debug for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PublicKey(compressed): ")?;
+        for byte in &self.to_bytes() {
+            write!(f, "{byte:x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl PublicKey {
+    /// Verify the Secp256k1 signature on a message using the public key.
+    ///
+    /// The signature must be DER-encoded and computed over the SHA256 hash of the message.
+    pub fn verify(&self, msg: &[u8], sig: &[u8]) -> bool {
+        let digest: [u8; 32] = Sha256::digest(msg).into();
+        let message = Message::parse(&digest);
+
+        Signature::parse_der(sig)
+            .map(|sig| libsecp256k1::verify(&message, &sig, &self.0))
+            .unwrap_or(false)
+    }
+
+    /// Convert the public key to a byte array in compressed form, i.e.
+    /// where one coordinate is represented by a single bit.
+    pub fn to_bytes(&self) -> [u8; 33] {
+        self.0.serialize_compressed()
+    }
+
+    /// Convert the public key to a byte array in uncompressed form.
+    pub fn to_bytes_uncompressed(&self) -> [u8; 65] {
+        self.0.serialize()
+    }
+
+    /// Try to parse a public key from a byte slice containing the compressed
+    /// form of the key, as produced by `to_bytes`.
+    pub fn try_from_bytes(k: &[u8]) -> Result<PublicKey, ParseError> {
+        libsecp256k1::PublicKey::parse_slice(k, Some(libsecp256k1::PublicKeyFormat::Compressed))
+            .map_err(|_| ParseError::InvalidPublicKey)
+            .map(PublicKey)
+    }
+
+    /// Convert public key to `PeerId`.
+    pub fn to_peer_id(&self) -> PeerId {
+        crate::crypto::PublicKey::Secp256k1(self.clone()).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secp256k1_secret_from_bytes() {
+        let sk1 = SecretKey::generate();
+        let mut sk_bytes = sk1.to_bytes();
+        let sk2 = SecretKey::try_from_bytes(&mut sk_bytes).unwrap();
+
+        assert_eq!(sk1.to_bytes(), sk2.to_bytes());
+        assert_eq!(sk_bytes, [0u8; 32]);
+    }
+
+    #[test]
+    fn secp256k1_public_key_encode_decode() {
+        let pk = Keypair::generate().public();
+        let decoded = PublicKey::try_from_bytes(&pk.to_bytes()).unwrap();
+
+        assert_eq!(pk, decoded);
+        assert!(PublicKey::try_from_bytes(&pk.to_bytes_uncompressed()).is_err());
+    }
+
+    #[test]
+    fn secp256k1_signature() {
+        let kp = Keypair::generate();
+        let pk = kp.public();
+
+        let msg = "hello world".as_bytes();
+        let sig = kp.sign(msg);
+        assert!(pk.verify(msg, &sig));
+
+        let mut invalid_sig = sig.clone();
+        invalid_sig[10..13].copy_from_slice(&[10, 23, 42]);
+        assert!(!pk.verify(msg, &invalid_sig));
+
+        let invalid_msg = "h3ll0 w0rld".as_bytes();
+        assert!(!pk.verify(invalid_msg, &sig));
+    }
+}
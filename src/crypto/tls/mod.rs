@@ -27,27 +27,51 @@
 
 use crate::{crypto::ed25519::Keypair, PeerId};
 
+use rustls::{
+    client::ResolvesClientCert,
+    crypto::{ring, CryptoProvider},
+    pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer},
+    server::{ClientHello, ResolvesServerCert},
+    sign::CertifiedKey,
+    SignatureScheme,
+};
+
+#[cfg(feature = "tls")]
+use crate::{config::Role, error::NegotiationError};
+#[cfg(feature = "tls")]
+use futures::{AsyncRead, AsyncWrite};
+#[cfg(feature = "tls")]
+use rustls::pki_types::ServerName;
+#[cfg(feature = "tls")]
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+#[cfg(feature = "tls")]
+use tokio_util::compat::{Compat, FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt};
+
+#[cfg(feature = "tls")]
+use std::net::{IpAddr, Ipv4Addr};
 use std::sync::Arc;
 
 pub mod certificate;
 mod verifier;
 
+/// Logging target for the file.
+#[cfg(feature = "tls")]
+const LOG_TARGET: &str = "litep2p::crypto::tls";
+
 const P2P_ALPN: [u8; 6] = *b"libp2p";
 
 /// Create a TLS server configuration for litep2p.
 pub fn make_server_config(
     keypair: &Keypair,
 ) -> Result<rustls::ServerConfig, certificate::GenError> {
-    let (certificate, private_key) = certificate::generate(keypair)?;
+    let provider = crypto_provider();
+    let certified_key = certified_key(keypair, &provider)?;
 
-    let mut crypto = rustls::ServerConfig::builder()
-        .with_cipher_suites(verifier::CIPHERSUITES)
-        .with_safe_default_kx_groups()
+    let mut crypto = rustls::ServerConfig::builder_with_provider(provider)
         .with_protocol_versions(verifier::PROTOCOL_VERSIONS)
         .expect("Cipher suites and kx groups are configured; qed")
         .with_client_cert_verifier(Arc::new(verifier::Libp2pCertificateVerifier::new()))
-        .with_single_cert(vec![certificate], private_key)
-        .expect("Server cert key DER is valid; qed");
+        .with_cert_resolver(Arc::new(AlwaysResolvesCert(certified_key)));
     crypto.alpn_protocols = vec![P2P_ALPN.to_vec()];
 
     Ok(crypto)
@@ -58,19 +82,159 @@ pub fn make_client_config(
     keypair: &Keypair,
     remote_peer_id: Option<PeerId>,
 ) -> Result<rustls::ClientConfig, certificate::GenError> {
-    let (certificate, private_key) = certificate::generate(keypair)?;
+    let provider = crypto_provider();
+    let certified_key = certified_key(keypair, &provider)?;
 
-    let mut crypto = rustls::ClientConfig::builder()
-        .with_cipher_suites(verifier::CIPHERSUITES)
-        .with_safe_default_kx_groups()
+    let mut crypto = rustls::ClientConfig::builder_with_provider(provider)
         .with_protocol_versions(verifier::PROTOCOL_VERSIONS)
         .expect("Cipher suites and kx groups are configured; qed")
+        .dangerous()
         .with_custom_certificate_verifier(Arc::new(
             verifier::Libp2pCertificateVerifier::with_remote_peer_id(remote_peer_id),
         ))
-        .with_single_cert(vec![certificate], private_key)
-        .expect("Client cert key DER is valid; qed");
+        .with_client_cert_resolver(Arc::new(AlwaysResolvesCert(certified_key)));
     crypto.alpn_protocols = vec![P2P_ALPN.to_vec()];
 
     Ok(crypto)
 }
+
+/// Create a crypto provider which only supports the TLS 1.3 cipher suites of the verifier.
+fn crypto_provider() -> Arc<CryptoProvider> {
+    Arc::new(CryptoProvider {
+        cipher_suites: verifier::CIPHERSUITES.to_vec(),
+        ..ring::default_provider()
+    })
+}
+
+/// Generate the libp2p certificate of `keypair` and its signing key.
+///
+/// `rustls` would reject the critical libp2p extension if it parsed the certificate itself, so
+/// the certificate is always presented through [`AlwaysResolvesCert`].
+fn certified_key(
+    keypair: &Keypair,
+    provider: &CryptoProvider,
+) -> Result<Arc<CertifiedKey>, certificate::GenError> {
+    let (certificate, private_key) = certificate::generate(&keypair.clone().into())?;
+    let signing_key = provider
+        .key_provider
+        .load_private_key(PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(private_key)))
+        .expect("Cert key DER is valid; qed");
+
+    Ok(Arc::new(CertifiedKey::new(
+        vec![CertificateDer::from(certificate)],
+        signing_key,
+    )))
+}
+
+/// Certificate resolver which always presents the same libp2p certificate.
+#[derive(Debug)]
+struct AlwaysResolvesCert(Arc<CertifiedKey>);
+
+impl ResolvesClientCert for AlwaysResolvesCert {
+    fn resolve(
+        &self,
+        _root_hint_subjects: &[&[u8]],
+        _sigschemes: &[SignatureScheme],
+    ) -> Option<Arc<CertifiedKey>> {
+        Some(Arc::clone(&self.0))
+    }
+
+    fn has_certs(&self) -> bool {
+        true
+    }
+}
+
+impl ResolvesServerCert for AlwaysResolvesCert {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(Arc::clone(&self.0))
+    }
+}
+
+/// Connection secured with TLS.
+#[cfg(feature = "tls")]
+pub(crate) type TlsStream<S> = Compat<tokio_rustls::TlsStream<Compat<S>>>;
+
+/// Perform TLS handshake over `io`.
+///
+/// The remote peer is authenticated using the libp2p certificate extension and if
+/// `dialed_peer` is specified, the certificate must belong to it.
+#[cfg(feature = "tls")]
+pub(crate) async fn handshake<S: AsyncRead + AsyncWrite + Unpin>(
+    io: S,
+    keypair: &Keypair,
+    role: Role,
+    dialed_peer: Option<PeerId>,
+) -> Result<(TlsStream<S>, PeerId), NegotiationError> {
+    tracing::debug!(target: LOG_TARGET, ?role, "start tls handshake");
+
+    let stream: tokio_rustls::TlsStream<Compat<S>> = match role {
+        Role::Dialer => {
+            let config = make_client_config(keypair, dialed_peer).map_err(|error| {
+                tracing::error!(target: LOG_TARGET, ?error, "failed to create client config");
+                NegotiationError::IoError(std::io::ErrorKind::Other)
+            })?;
+
+            // the server name is not used by the libp2p certificate verifier
+            let server_name = ServerName::IpAddress(IpAddr::V4(Ipv4Addr::UNSPECIFIED).into());
+
+            TlsConnector::from(Arc::new(config))
+                .connect(server_name, io.compat())
+                .await?
+                .into()
+        }
+        Role::Listener => {
+            let config = make_server_config(keypair).map_err(|error| {
+                tracing::error!(target: LOG_TARGET, ?error, "failed to create server config");
+                NegotiationError::IoError(std::io::ErrorKind::Other)
+            })?;
+
+            TlsAcceptor::from(Arc::new(config)).accept(io.compat()).await?.into()
+        }
+    };
+
+    let certificate = match stream.get_ref().1.peer_certificates() {
+        Some([certificate]) => certificate,
+        _ => return Err(NegotiationError::PeerIdMissing),
+    };
+    let peer = certificate::parse(certificate)
+        .map_err(|_| NegotiationError::PeerIdMissing)?
+        .peer_id();
+
+    tracing::trace!(target: LOG_TARGET, ?role, ?peer, "tls handshake done");
+
+    Ok((stream.compat(), peer))
+}
+
+#[cfg(all(test, feature = "tls"))]
+mod tests {
+    use super::*;
+    use tokio::net::{TcpListener, TcpStream};
+
+    #[tokio::test]
+    async fn handshake_fails_for_wrong_peer() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let (Ok(dialer), Ok((listener, _))) =
+            tokio::join!(TcpStream::connect(address), listener.accept())
+        else {
+            panic!("failed to establish connection");
+        };
+
+        let dialer_keypair = Keypair::generate();
+        let listener_keypair = Keypair::generate();
+
+        let (dialer, listener) = tokio::join!(
+            handshake(
+                dialer.compat(),
+                &dialer_keypair,
+                Role::Dialer,
+                Some(PeerId::random())
+            ),
+            handshake(listener.compat(), &listener_keypair, Role::Listener, None),
+        );
+
+        assert!(dialer.is_err());
+        assert!(listener.is_err());
+    }
+}
@@ -26,7 +26,7 @@
 use crate::{
     protocol::Direction,
     transport::manager::limits::ConnectionLimitsError,
-    types::{protocol::ProtocolName, ConnectionId, SubstreamId},
+    types::{protocol::ProtocolName, ConnectionId, ListenerId, SubstreamId},
     PeerId,
 };
 
@@ -82,8 +82,8 @@ pub enum Error {
     DnsAddressResolutionFailed,
     #[error("Transport error: `{0}`")]
     TransportError(String),
-    #[cfg(feature = "quic")]
     #[error("Failed to generate certificate: `{0}`")]
+    #[cfg(any(feature = "quic", feature = "tls"))]
     CertificateGeneration(#[from] crate::crypto::tls::certificate::GenError),
     #[error("Invalid data")]
     InvalidData,
@@ -122,10 +122,16 @@ pub enum Error {
     ChannelClogged,
     #[error("Connection doesn't exist: `{0:?}`")]
     ConnectionDoesntExist(ConnectionId),
+    #[error("Listener doesn't exist: `{0:?}`")]
+    ListenerDoesntExist(ListenerId),
     #[error("Exceeded connection limits `{0:?}`")]
     ConnectionLimit(ConnectionLimitsError),
     #[error("Failed to dial peer immediately")]
     ImmediateDialError(#[from] ImmediateDialError),
+    #[error("Keystore error: `{0}`")]
+    Keystore(#[from] KeystoreError),
+    #[error("Pre-shared key error: `{0}`")]
+    PreSharedKey(#[from] PreSharedKeyError),
 }
 
 /// Error type for address parsing.
@@ -174,6 +180,9 @@ pub enum ParseError {
     ///  - Length of the public key is not represented by 2 bytes (WebRTC specific).
     #[error("Invalid public key")]
     InvalidPublicKey,
+    /// The private key bytes are invalid and cannot be parsed.
+    #[error("Invalid private key")]
+    InvalidPrivateKey,
     /// The provided date has an invalid format.
     ///
     /// This error is protocol specific.
@@ -193,6 +202,8 @@ pub enum SubstreamError {
     IoError(ErrorKind),
     #[error("yamux error: `{0}`")]
     YamuxError(crate::yamux::ConnectionError, Direction),
+    #[error("mplex error: `{0}`")]
+    MplexError(crate::mplex::ConnectionError, Direction),
     #[error("Failed to read from substream, substream id `{0:?}`")]
     ReadFailure(Option<SubstreamId>),
     #[error("Failed to write to substream, substream id `{0:?}`")]
@@ -262,6 +273,8 @@ impl PartialEq for SubstreamError {
                     _ => false,
                 }
             }
+            (Self::MplexError(lhs, lhs_1), Self::MplexError(rhs, rhs_1)) =>
+                lhs == rhs && lhs_1 == rhs_1,
 
             (Self::ReadFailure(lhs), Self::ReadFailure(rhs)) => lhs == rhs,
             (Self::WriteFailure(lhs), Self::WriteFailure(rhs)) => lhs == rhs,
@@ -302,6 +315,10 @@ pub enum NegotiationError {
     /// address.
     #[error("Peer ID mismatch: expected `{0}`, got `{1}`")]
     PeerIdMismatch(PeerId, PeerId),
+    /// The connection could not be negotiated over the private network, most likely because
+    /// the remote peer doesn't use the same pre-shared key.
+    #[error("Remote peer is not part of the private network")]
+    PreSharedKeyMismatch,
     /// Error specific to the QUIC transport.
     #[cfg(feature = "quic")]
     #[error("QUIC error: `{0}`")]
@@ -367,6 +384,9 @@ pub enum DialError {
     /// An error occurred during the negotiation process.
     #[error("Negotiation error: `{0}`")]
     NegotiationError(#[from] NegotiationError),
+    /// Failed to open a circuit through a relay.
+    #[error("Relay error: `{0}`")]
+    RelayError(#[from] RelayError),
 }
 
 /// Dialing resulted in an immediate error before performing any network operations.
@@ -407,6 +427,171 @@ pub enum QuicError {
     ConnectError(#[from] quinn::ConnectError),
 }
 
+/// Error reported by a circuit relay or while communicating with it.
+#[derive(Debug, thiserror::Error, Copy, Clone, PartialEq, Eq)]
+pub enum RelayError {
+    /// The relay refused to make a reservation.
+    #[error("Reservation refused")]
+    ReservationRefused,
+    /// The relay has reached its reservation or circuit limits.
+    #[error("Resource limit exceeded")]
+    ResourceLimitExceeded,
+    /// The relay or the destination refused the request.
+    #[error("Permission denied")]
+    PermissionDenied,
+    /// The relay failed to reach the destination.
+    #[error("Connection to the destination failed")]
+    ConnectionFailed,
+    /// The destination doesn't have a reservation with the relay.
+    #[error("Destination has no reservation")]
+    NoReservation,
+    /// The remote sent a malformed or unexpected message.
+    #[error("Protocol violation")]
+    ProtocolViolation,
+    /// The relay couldn't be reached or the substream to it failed.
+    #[error("Relay unreachable")]
+    RelayUnreachable,
+}
+
+/// Error during a direct connection upgrade of a relayed connection.
+#[derive(Debug, thiserror::Error, Copy, Clone, PartialEq, Eq)]
+pub enum DcutrError {
+    /// The remote peer sent a malformed or unexpected message.
+    #[error("Protocol violation")]
+    ProtocolViolation,
+    /// The remote peer didn't advertise any address the local node can dial.
+    #[error("No dialable addresses")]
+    NoAddresses,
+    /// The substream to the remote peer couldn't be opened or it was closed.
+    #[error("Substream closed")]
+    SubstreamClosed,
+    /// The remote peer didn't answer in time or the direct connection wasn't established in
+    /// time.
+    #[error("Timeout")]
+    Timeout,
+}
+
+/// Error while publishing a gossipsub message.
+#[derive(Debug, thiserror::Error, Copy, Clone, PartialEq, Eq)]
+pub enum GossipsubError {
+    /// The message has already been published or received.
+    #[error("Duplicate message")]
+    Duplicate,
+    /// No peers subscribed to the topic were found to publish the message to.
+    #[error("Insufficient peers")]
+    InsufficientPeers,
+    /// The message exceeds the maximum transmit size.
+    #[error("Message too large")]
+    MessageTooLarge,
+    /// The gossipsub task has been closed.
+    #[error("Gossipsub task closed")]
+    TaskClosed,
+}
+
+/// Error during a rendezvous request.
+#[derive(Debug, thiserror::Error, Copy, Clone, PartialEq, Eq)]
+pub enum RendezvousError {
+    /// The namespace is invalid.
+    #[error("Invalid namespace")]
+    InvalidNamespace,
+    /// The signed peer record is invalid.
+    #[error("Invalid signed peer record")]
+    InvalidSignedPeerRecord,
+    /// The requested TTL is outside of the bounds accepted by the rendezvous point.
+    #[error("Invalid TTL")]
+    InvalidTtl,
+    /// The pagination cookie is invalid.
+    #[error("Invalid cookie")]
+    InvalidCookie,
+    /// The rendezvous point refused the request.
+    #[error("Not authorized")]
+    NotAuthorized,
+    /// The rendezvous point failed to handle the request.
+    #[error("Internal error")]
+    InternalError,
+    /// The rendezvous point is unavailable.
+    #[error("Rendezvous point unavailable")]
+    Unavailable,
+    /// The response of the rendezvous point was invalid.
+    #[error("Invalid response")]
+    InvalidResponse,
+    /// Failed to connect to the rendezvous point or to send the request.
+    #[error("Connection failed")]
+    ConnectionFailed,
+    /// The rendezvous point didn't respond in time.
+    #[error("Request timed out")]
+    Timeout,
+    /// The rendezvous task has been closed.
+    #[error("Rendezvous task closed")]
+    TaskClosed,
+}
+
+/// Error during a perf run.
+#[derive(Debug, thiserror::Error, Copy, Clone, PartialEq, Eq)]
+pub enum PerfError {
+    /// Failed to dial the remote peer.
+    #[error("Failed to dial peer")]
+    DialFailure,
+    /// Failed to open substream to the remote peer.
+    #[error("Failed to open substream")]
+    SubstreamOpenFailure,
+    /// I/O error while transferring data.
+    #[error("I/O error: `{0}`")]
+    Io(io::ErrorKind),
+    /// The remote peer sent a different number of bytes than was requested.
+    #[error("Expected `{expected}` bytes, received `{received}`")]
+    InvalidResponseLength {
+        /// Requested number of bytes.
+        expected: u64,
+        /// Received number of bytes.
+        received: u64,
+    },
+}
+
+/// Error while reading or writing a keystore file.
+#[derive(Debug, thiserror::Error, Copy, Clone, PartialEq, Eq)]
+pub enum KeystoreError {
+    /// I/O error while accessing the keystore file.
+    #[error("I/O error: `{0}`")]
+    Io(io::ErrorKind),
+    /// The keystore file is malformed.
+    #[error("Invalid keystore format")]
+    InvalidFormat,
+    /// The keystore file was created by an unsupported version of the format.
+    #[error("Unsupported keystore version: `{0}`")]
+    UnsupportedVersion(u8),
+    /// The keystore file is encrypted but no password was provided.
+    #[error("Keystore is encrypted but no password was provided")]
+    PasswordRequired,
+    /// The keystore file could not be decrypted, either because the password is invalid or the
+    /// file has been tampered with.
+    #[error("Failed to decrypt keystore")]
+    DecryptionFailed,
+    /// The keystore file doesn't contain a valid private key.
+    #[error("Invalid private key")]
+    InvalidKey,
+    /// The key type is not supported as the identity of the local node.
+    #[error("Unsupported key type")]
+    UnsupportedKeyType,
+}
+
+/// Error while reading a pre-shared key of a private network.
+#[derive(Debug, thiserror::Error, Copy, Clone, PartialEq, Eq)]
+pub enum PreSharedKeyError {
+    /// I/O error while accessing the pre-shared key file.
+    #[error("I/O error: `{0}`")]
+    Io(io::ErrorKind),
+    /// The key type header is missing or not supported.
+    #[error("Invalid key type")]
+    InvalidKeyType,
+    /// The key encoding header is missing or not supported.
+    #[error("Invalid key encoding")]
+    InvalidEncoding,
+    /// The key is not a valid base16-encoded 32-byte key.
+    #[error("Invalid pre-shared key")]
+    InvalidKey,
+}
+
 /// Error during DNS resolution.
 #[derive(Debug, thiserror::Error, PartialEq)]
 pub enum DnsError {
@@ -438,6 +623,18 @@ impl From<io::Error> for SubstreamError {
     }
 }
 
+impl From<io::Error> for PerfError {
+    fn from(error: io::Error) -> PerfError {
+        PerfError::Io(error.kind())
+    }
+}
+
+impl From<io::Error> for KeystoreError {
+    fn from(error: io::Error) -> KeystoreError {
+        KeystoreError::Io(error.kind())
+    }
+}
+
 impl From<io::Error> for DialError {
     fn from(error: io::Error) -> Self {
         DialError::NegotiationError(NegotiationError::IoError(error.kind()))
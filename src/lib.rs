@@ -33,13 +33,20 @@ use crate::{
     config::Litep2pConfig,
     error::DialError,
     protocol::{
-        libp2p::{bitswap::Bitswap, identify::Identify, kademlia::Kademlia, ping::Ping},
+        libp2p::{
+            autonat::AutoNat, bitswap::Bitswap, dcutr::Dcutr, floodsub::Floodsub,
+            gossipsub::Gossipsub, identify::Identify, kademlia::Kademlia, perf::Perf, ping::Ping,
+            relay::Relay, rendezvous::Rendezvous,
+        },
         mdns::Mdns,
         notification::NotificationProtocol,
         request_response::RequestResponseProtocol,
     },
     transport::{
+        common::dns::DnsResolver,
         manager::{SupportedTransport, TransportManager},
+        memory::MemoryTransport,
+        relay::{config::Config as RelayTransportConfig, RelayTransport},
         tcp::TcpTransport,
         TransportBuilder, TransportEvent,
     },
@@ -47,6 +54,8 @@ use crate::{
 
 #[cfg(feature = "quic")]
 use crate::transport::quic::QuicTransport;
+#[cfg(unix)]
+use crate::transport::unix::UnixTransport;
 #[cfg(feature = "webrtc")]
 use crate::transport::webrtc::WebRtcTransport;
 #[cfg(feature = "websocket")]
@@ -55,9 +64,12 @@ use crate::transport::websocket::WebSocketTransport;
 use multiaddr::{Multiaddr, Protocol};
 use multihash::Multihash;
 use transport::Endpoint;
-use types::ConnectionId;
+use types::{ConnectionId, ListenerId};
 
-use std::{collections::HashSet, sync::Arc};
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::Arc,
+};
 
 pub use bandwidth::BandwidthSink;
 pub use error::Error;
@@ -72,6 +84,7 @@ pub mod config;
 pub mod crypto;
 pub mod error;
 pub mod executor;
+pub mod mplex;
 pub mod protocol;
 pub mod substream;
 pub mod transport;
@@ -133,6 +146,30 @@ pub enum Litep2pEvent {
         /// Depending on the transport, the address might be different for each error.
         errors: Vec<(Multiaddr, DialError)>,
     },
+
+    /// Listener is reachable at a new address.
+    ///
+    /// Reported for listeners started with [`Litep2p::listen_on()`] and when a network interface
+    /// address appears for a listener bound to an unspecified address.
+    NewListenAddress {
+        /// ID of the listener.
+        listener_id: ListenerId,
+
+        /// Listen address.
+        address: Multiaddr,
+    },
+
+    /// Listener is no longer reachable at the address.
+    ///
+    /// Reported when the listener is stopped with [`Litep2p::stop_listening()`] or when a
+    /// network interface address of a listener bound to an unspecified address disappears.
+    ListenAddressExpired {
+        /// ID of the listener.
+        listener_id: ListenerId,
+
+        /// Listen address.
+        address: Multiaddr,
+    },
 }
 
 /// [`Litep2p`] object.
@@ -148,6 +185,9 @@ pub struct Litep2p {
 
     /// Bandwidth sink.
     bandwidth_sink: BandwidthSink,
+
+    /// Pending events.
+    pending_events: VecDeque<Litep2pEvent>,
 }
 
 impl Litep2p {
@@ -165,6 +205,27 @@ impl Litep2p {
             litep2p_config.max_parallel_dials,
             litep2p_config.connection_limits,
         );
+        transport_manager.set_dns_resolver(DnsResolver::new(litep2p_config.dns.clone()));
+        transport_manager.set_dial_scheduler_config(litep2p_config.dial_scheduler.clone());
+
+        // all connections of a private network must be encrypted with the pre-shared key
+        if let Some(pre_shared_key) = litep2p_config.pre_shared_key.take() {
+            #[cfg(feature = "quic")]
+            if litep2p_config.quic.is_some() {
+                return Err(Error::NotSupported(
+                    "QUIC transport doesn't support private networks".to_string(),
+                ));
+            }
+
+            #[cfg(feature = "webrtc")]
+            if litep2p_config.webrtc.is_some() {
+                return Err(Error::NotSupported(
+                    "WebRTC transport doesn't support private networks".to_string(),
+                ));
+            }
+
+            transport_manager.set_pre_shared_key(pre_shared_key);
+        }
 
         // add known addresses to `TransportManager`, if any exist
         if !litep2p_config.known_addresses.is_empty() {
@@ -310,6 +371,181 @@ impl Litep2p {
             }));
         }
 
+        // start autonat protocol event loop if enabled
+        if let Some(autonat_config) = litep2p_config.autonat.take() {
+            tracing::debug!(
+                target: LOG_TARGET,
+                protocol = ?autonat_config.protocol,
+                "enable libp2p autonat protocol",
+            );
+
+            let service = transport_manager.register_protocol(
+                autonat_config.protocol.clone(),
+                autonat_config.fallback_names.clone(),
+                autonat_config.codec,
+                litep2p_config.keep_alive_timeout,
+            );
+            let mut dial_back_service = transport_manager.register_protocol(
+                autonat_config.dial_back_protocol.clone(),
+                Vec::new(),
+                autonat_config.codec,
+                litep2p_config.keep_alive_timeout,
+            );
+            dial_back_service.report_secondary_connections();
+
+            litep2p_config.executor.run(Box::pin(async move {
+                AutoNat::new(service, dial_back_service, autonat_config).run().await
+            }));
+        }
+
+        // start relay protocol event loop if enabled
+        let mut relay_transport_config = None;
+        if let Some(relay_config) = litep2p_config.relay.take() {
+            tracing::debug!(
+                target: LOG_TARGET,
+                hop = ?relay_config.hop_protocol,
+                stop = ?relay_config.stop_protocol,
+                "enable libp2p circuit relay protocol",
+            );
+
+            let hop_service = transport_manager.register_protocol(
+                relay_config.hop_protocol.clone(),
+                Vec::new(),
+                relay_config.codec,
+                litep2p_config.keep_alive_timeout,
+            );
+            let stop_service = transport_manager.register_protocol(
+                relay_config.stop_protocol.clone(),
+                Vec::new(),
+                relay_config.codec,
+                litep2p_config.keep_alive_timeout,
+            );
+            let (request_tx, request_rx) = tokio::sync::mpsc::channel(DEFAULT_CHANNEL_SIZE);
+            let (circuit_tx, circuit_rx) = tokio::sync::mpsc::channel(DEFAULT_CHANNEL_SIZE);
+            relay_transport_config = Some(RelayTransportConfig::new(request_tx, circuit_rx));
+
+            litep2p_config.executor.run(Box::pin(async move {
+                Relay::new(
+                    hop_service,
+                    stop_service,
+                    relay_config,
+                    request_rx,
+                    circuit_tx,
+                )
+                .run()
+                .await
+            }));
+        }
+
+        // start dcutr protocol event loop if enabled
+        if let Some(dcutr_config) = litep2p_config.dcutr.take() {
+            tracing::debug!(
+                target: LOG_TARGET,
+                protocol = ?dcutr_config.protocol,
+                "enable libp2p dcutr protocol",
+            );
+
+            let mut service = transport_manager.register_protocol(
+                dcutr_config.protocol.clone(),
+                Vec::new(),
+                dcutr_config.codec,
+                litep2p_config.keep_alive_timeout,
+            );
+            service.report_secondary_connections();
+
+            litep2p_config.executor.run(Box::pin(async move {
+                Dcutr::new(service, dcutr_config).run().await
+            }));
+        }
+
+        // start gossipsub protocol event loop if enabled
+        if let Some(gossipsub_config) = litep2p_config.gossipsub.take() {
+            tracing::debug!(
+                target: LOG_TARGET,
+                protocol_names = ?gossipsub_config.protocol_names,
+                "enable libp2p gossipsub protocol",
+            );
+
+            let main_protocol =
+                gossipsub_config.protocol_names.first().expect("protocol name to exist");
+            let fallback_names = gossipsub_config.protocol_names.iter().skip(1).cloned().collect();
+
+            let service = transport_manager.register_protocol(
+                main_protocol.clone(),
+                fallback_names,
+                gossipsub_config.codec,
+                litep2p_config.keep_alive_timeout,
+            );
+            let keypair = litep2p_config.keypair.clone();
+
+            litep2p_config.executor.run(Box::pin(async move {
+                Gossipsub::new(service, gossipsub_config, keypair).run().await
+            }));
+        }
+
+        // start floodsub protocol event loop if enabled
+        if let Some(floodsub_config) = litep2p_config.floodsub.take() {
+            tracing::debug!(
+                target: LOG_TARGET,
+                protocol = ?floodsub_config.protocol,
+                "enable libp2p floodsub protocol",
+            );
+
+            let service = transport_manager.register_protocol(
+                floodsub_config.protocol.clone(),
+                Vec::new(),
+                floodsub_config.codec,
+                litep2p_config.keep_alive_timeout,
+            );
+
+            litep2p_config.executor.run(Box::pin(async move {
+                Floodsub::new(service, floodsub_config, local_peer_id).run().await
+            }));
+        }
+
+        // start rendezvous protocol event loop if enabled
+        if let Some(rendezvous_config) = litep2p_config.rendezvous.take() {
+            tracing::debug!(
+                target: LOG_TARGET,
+                protocol = ?rendezvous_config.protocol,
+                server = ?rendezvous_config.server,
+                "enable libp2p rendezvous protocol",
+            );
+
+            let service = transport_manager.register_protocol(
+                rendezvous_config.protocol.clone(),
+                Vec::new(),
+                rendezvous_config.codec,
+                litep2p_config.keep_alive_timeout,
+            );
+            let keypair = litep2p_config.keypair.clone();
+
+            litep2p_config.executor.run(Box::pin(async move {
+                Rendezvous::new(service, rendezvous_config, keypair).run().await
+            }));
+        }
+
+        // start perf protocol event loop if enabled
+        if let Some(perf_config) = litep2p_config.perf.take() {
+            tracing::debug!(
+                target: LOG_TARGET,
+                protocol = ?perf_config.protocol,
+                server = ?perf_config.server,
+                "enable libp2p perf protocol",
+            );
+
+            let service = transport_manager.register_protocol(
+                perf_config.protocol.clone(),
+                Vec::new(),
+                perf_config.codec,
+                litep2p_config.keep_alive_timeout,
+            );
+
+            litep2p_config.executor.run(Box::pin(async move {
+                Perf::new(service, perf_config).run().await
+            }));
+        }
+
         // enable tcp transport if the config exists
         if let Some(config) = litep2p_config.tcp.take() {
             let handle = transport_manager.transport_handle(Arc::clone(&litep2p_config.executor));
@@ -378,9 +614,75 @@ impl Litep2p {
                 .register_transport(SupportedTransport::WebSocket, Box::new(transport));
         }
 
+        // enable memory transport if the config exists
+        if let Some(config) = litep2p_config.memory.take() {
+            let handle = transport_manager.transport_handle(Arc::clone(&litep2p_config.executor));
+            let (transport, transport_listen_addresses) =
+                <MemoryTransport as TransportBuilder>::new(handle, config)?;
+
+            for address in transport_listen_addresses {
+                transport_manager.register_listen_address(address.clone());
+                listen_addresses.push(address.with(Protocol::P2p(
+                    Multihash::from_bytes(&local_peer_id.to_bytes()).unwrap(),
+                )));
+            }
+
+            transport_manager.register_transport(SupportedTransport::Memory, Box::new(transport));
+        }
+
+        // enable unix domain socket transport if the config exists
+        #[cfg(unix)]
+        if let Some(config) = litep2p_config.unix.take() {
+            let handle = transport_manager.transport_handle(Arc::clone(&litep2p_config.executor));
+            let (transport, transport_listen_addresses) =
+                <UnixTransport as TransportBuilder>::new(handle, config)?;
+
+            for address in transport_listen_addresses {
+                transport_manager.register_listen_address(address.clone());
+                listen_addresses.push(address.with(Protocol::P2p(
+                    Multihash::from_bytes(&local_peer_id.to_bytes()).unwrap(),
+                )));
+            }
+
+            transport_manager.register_transport(SupportedTransport::Unix, Box::new(transport));
+        }
+
+        // enable user-supplied transports
+        for transport in std::mem::take(&mut litep2p_config.custom_transports) {
+            let protocol = transport.protocol();
+            if transport_manager
+                .installed_transports()
+                .any(|transport| transport == &SupportedTransport::Custom(protocol))
+            {
+                return Err(Error::Other(format!(
+                    "multiple custom transports for `/{protocol}`"
+                )));
+            }
+
+            let handle = transport_manager.transport_handle(Arc::clone(&litep2p_config.executor));
+            let (transport, transport_listen_addresses) = transport.build(handle)?;
+
+            for address in transport_listen_addresses {
+                transport_manager.register_listen_address(address.clone());
+                listen_addresses.push(address.with(Protocol::P2p(
+                    Multihash::from_bytes(&local_peer_id.to_bytes()).unwrap(),
+                )));
+            }
+
+            transport_manager.register_transport(SupportedTransport::Custom(protocol), transport);
+        }
+
+        // enable relay transport if the relay protocol is enabled
+        if let Some(config) = relay_transport_config.take() {
+            let handle = transport_manager.transport_handle(Arc::clone(&litep2p_config.executor));
+            let (transport, _) = <RelayTransport as TransportBuilder>::new(handle, config)?;
+
+            transport_manager.register_transport(SupportedTransport::Relay, Box::new(transport));
+        }
+
         // enable mdns if the config exists
         if let Some(config) = litep2p_config.mdns.take() {
-            let mdns = Mdns::new(transport_handle, config, listen_addresses.clone())?;
+            let mdns = Mdns::new(transport_handle, config)?;
 
             litep2p_config.executor.run(Box::pin(async move {
                 let _ = mdns.start().await;
@@ -414,6 +716,7 @@ impl Litep2p {
             bandwidth_sink,
             listen_addresses,
             transport_manager,
+            pending_events: VecDeque::new(),
         })
     }
 
@@ -444,7 +747,26 @@ impl Litep2p {
             .webrtc
             .is_some()
             .then(|| supported_transports.insert(SupportedTransport::WebRtc));
+        config
+            .memory
+            .is_some()
+            .then(|| supported_transports.insert(SupportedTransport::Memory));
+        #[cfg(unix)]
+        config
+            .unix
+            .is_some()
+            .then(|| supported_transports.insert(SupportedTransport::Unix));
+        supported_transports.extend(
+            config
+                .custom_transports
+                .iter()
+                .map(|transport| SupportedTransport::Custom(transport.protocol())),
+        );
 
+        config
+            .relay
+            .is_some()
+            .then(|| supported_transports.insert(SupportedTransport::Relay));
         supported_transports
     }
 
@@ -463,6 +785,59 @@ impl Litep2p {
         self.listen_addresses.iter()
     }
 
+    /// Start listening on `address`.
+    ///
+    /// The addresses the listener is reachable at are reported with
+    /// [`Litep2pEvent::NewListenAddress`] and advertised by the identify protocol until the
+    /// listener is stopped with [`Litep2p::stop_listening()`].
+    ///
+    /// Listeners can be started at runtime only for TCP and WebSocket addresses, and for custom
+    /// transports which implement [`Transport::listen_on()`](transport::Transport::listen_on).
+    /// QUIC, WebRTC, memory and Unix socket transports listen only on the addresses given in
+    /// their configuration and return [`Error::NotSupported`] for other addresses.
+    pub fn listen_on(&mut self, address: Multiaddr) -> crate::Result<ListenerId> {
+        let (listener_id, addresses) = self.transport_manager.listen_on(address)?;
+
+        for address in addresses {
+            let address = address.with(Protocol::P2p(
+                Multihash::from_bytes(&self.local_peer_id.to_bytes()).unwrap(),
+            ));
+
+            self.listen_addresses.push(address.clone());
+            self.pending_events.push_back(Litep2pEvent::NewListenAddress {
+                listener_id,
+                address,
+            });
+        }
+
+        Ok(listener_id)
+    }
+
+    /// Stop the listener started with [`Litep2p::listen_on()`].
+    ///
+    /// The addresses of the listener are reported with [`Litep2pEvent::ListenAddressExpired`] and
+    /// removed from the listen and public addresses of the node.
+    ///
+    /// Listeners started from the transport configuration can't be stopped and return
+    /// [`Error::ListenerDoesntExist`].
+    pub fn stop_listening(&mut self, listener_id: ListenerId) -> crate::Result<()> {
+        let addresses = self.transport_manager.stop_listening(listener_id)?;
+
+        for address in addresses {
+            let address = address.with(Protocol::P2p(
+                Multihash::from_bytes(&self.local_peer_id.to_bytes()).unwrap(),
+            ));
+
+            self.listen_addresses.retain(|listen_address| listen_address != &address);
+            self.pending_events.push_back(Litep2pEvent::ListenAddressExpired {
+                listener_id,
+                address,
+            });
+        }
+
+        Ok(())
+    }
+
     /// Get handle to bandwidth sink.
     pub fn bandwidth_sink(&self) -> BandwidthSink {
         self.bandwidth_sink.clone()
@@ -494,6 +869,10 @@ impl Litep2p {
     ///
     /// This function must be called in order for litep2p to make progress.
     pub async fn next_event(&mut self) -> Option<Litep2pEvent> {
+        if let Some(event) = self.pending_events.pop_front() {
+            return Some(event);
+        }
+
         loop {
             match self.transport_manager.next().await? {
                 TransportEvent::ConnectionEstablished { peer, endpoint, .. } =>
@@ -512,6 +891,34 @@ impl Litep2p {
                 TransportEvent::OpenFailure { errors, .. } => {
                     return Some(Litep2pEvent::ListDialFailures { errors });
                 }
+                TransportEvent::NewListenAddress {
+                    listener_id,
+                    address,
+                } => {
+                    let address = address.with(Protocol::P2p(
+                        Multihash::from_bytes(&self.local_peer_id.to_bytes()).unwrap(),
+                    ));
+
+                    self.listen_addresses.push(address.clone());
+                    return Some(Litep2pEvent::NewListenAddress {
+                        listener_id,
+                        address,
+                    });
+                }
+                TransportEvent::ListenAddressExpired {
+                    listener_id,
+                    address,
+                } => {
+                    let address = address.with(Protocol::P2p(
+                        Multihash::from_bytes(&self.local_peer_id.to_bytes()).unwrap(),
+                    ));
+
+                    self.listen_addresses.retain(|listen_address| listen_address != &address);
+                    return Some(Litep2pEvent::ListenAddressExpired {
+                        listener_id,
+                        address,
+                    });
+                }
                 _ => {}
             }
         }
@@ -526,6 +933,8 @@ mod tests {
         types::protocol::ProtocolName,
         Litep2p, Litep2pEvent, PeerId,
     };
+    #[cfg(any(feature = "quic", feature = "webrtc"))]
+    use crate::{crypto::pnet::PreSharedKey, Error};
     use multiaddr::{Multiaddr, Protocol};
     use multihash::Multihash;
     use std::net::Ipv4Addr;
@@ -663,4 +1072,34 @@ mod tests {
             _ => panic!("invalid event received"),
         }
     }
+
+    #[cfg(feature = "quic")]
+    #[tokio::test]
+    async fn private_network_refused_for_quic() {
+        let config = ConfigBuilder::new()
+            .with_tcp(Default::default())
+            .with_quic(Default::default())
+            .with_pre_shared_key(PreSharedKey::generate())
+            .build();
+
+        assert!(std::matches!(
+            Litep2p::new(config),
+            Err(Error::NotSupported(_))
+        ));
+    }
+
+    #[cfg(feature = "webrtc")]
+    #[tokio::test]
+    async fn private_network_refused_for_webrtc() {
+        let config = ConfigBuilder::new()
+            .with_tcp(Default::default())
+            .with_webrtc(Default::default())
+            .with_pre_shared_key(PreSharedKey::generate())
+            .build();
+
+        assert!(std::matches!(
+            Litep2p::new(config),
+            Err(Error::NotSupported(_))
+        ));
+    }
 }
@@ -0,0 +1,325 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! mplex frame codec.
+//!
+//! Each frame consists of an unsigned varint header, an unsigned varint payload length and the
+//! payload. The header holds the stream number shifted left by three bits and the frame flag in
+//! the lowest three bits.
+
+use crate::mplex::ConnectionError;
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+/// Maximum size of a frame payload, as per the specification.
+pub const MAX_FRAME_SIZE: usize = 1024 * 1024;
+
+/// A new stream is opened.
+const NEW_STREAM: u64 = 0;
+
+/// Data sent by the peer which didn't open the stream.
+const MESSAGE_RECEIVER: u64 = 1;
+
+/// Data sent by the peer which opened the stream.
+const MESSAGE_INITIATOR: u64 = 2;
+
+/// Stream closed by the peer which didn't open it.
+const CLOSE_RECEIVER: u64 = 3;
+
+/// Stream closed by the peer which opened it.
+const CLOSE_INITIATOR: u64 = 4;
+
+/// Stream reset by the peer which didn't open it.
+const RESET_RECEIVER: u64 = 5;
+
+/// Stream reset by the peer which opened it.
+const RESET_INITIATOR: u64 = 6;
+
+/// Stream identifier.
+///
+/// Both peers allocate stream numbers independently so the number alone doesn't identify the
+/// stream, it must be accompanied with the information about which peer opened the stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StreamId {
+    /// Stream number.
+    num: u64,
+
+    /// Whether the stream was opened by the local node.
+    outbound: bool,
+}
+
+impl StreamId {
+    /// Create new [`StreamId`] for a stream opened by the local node.
+    pub(crate) fn outbound(num: u64) -> Self {
+        Self {
+            num,
+            outbound: true,
+        }
+    }
+
+    /// Create new [`StreamId`] for a stream opened by the remote node.
+    pub(crate) fn inbound(num: u64) -> Self {
+        Self {
+            num,
+            outbound: false,
+        }
+    }
+
+    /// Get stream number.
+    pub fn num(&self) -> u64 {
+        self.num
+    }
+
+    /// Check if the stream was opened by the local node.
+    pub fn is_outbound(&self) -> bool {
+        self.outbound
+    }
+}
+
+/// mplex frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Frame {
+    /// Open new stream.
+    Open { stream_id: StreamId },
+
+    /// Send data over the stream.
+    Data { stream_id: StreamId, data: Bytes },
+
+    /// Close the stream for writing.
+    Close { stream_id: StreamId },
+
+    /// Reset the stream.
+    Reset { stream_id: StreamId },
+}
+
+/// mplex frame codec.
+#[derive(Debug, Default)]
+pub(crate) struct Codec;
+
+impl Decoder for Codec {
+    type Item = Frame;
+    type Error = ConnectionError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let (header, rest) = match unsigned_varint::decode::u64(src) {
+            Ok(result) => result,
+            Err(unsigned_varint::decode::Error::Insufficient) => return Ok(None),
+            Err(_) => return Err(ConnectionError::InvalidFrame),
+        };
+        let (len, rest) = match unsigned_varint::decode::usize(rest) {
+            Ok(result) => result,
+            Err(unsigned_varint::decode::Error::Insufficient) => return Ok(None),
+            Err(_) => return Err(ConnectionError::InvalidFrame),
+        };
+
+        if len > MAX_FRAME_SIZE {
+            return Err(ConnectionError::FrameTooLarge(len));
+        }
+
+        if rest.len() < len {
+            let missing = len - rest.len();
+            src.reserve(missing);
+            return Ok(None);
+        }
+
+        let header_len = src.len() - rest.len();
+        src.advance(header_len);
+        let data = src.split_to(len).freeze();
+        let num = header >> 3;
+
+        // the flag tells which peer sent the frame so if the sender is the receiver of the
+        // stream, the stream was opened by the local node
+        let frame = match header & 0b111 {
+            NEW_STREAM => Frame::Open {
+                stream_id: StreamId::inbound(num),
+            },
+            MESSAGE_RECEIVER => Frame::Data {
+                stream_id: StreamId::outbound(num),
+                data,
+            },
+            MESSAGE_INITIATOR => Frame::Data {
+                stream_id: StreamId::inbound(num),
+                data,
+            },
+            CLOSE_RECEIVER => Frame::Close {
+                stream_id: StreamId::outbound(num),
+            },
+            CLOSE_INITIATOR => Frame::Close {
+                stream_id: StreamId::inbound(num),
+            },
+            RESET_RECEIVER => Frame::Reset {
+                stream_id: StreamId::outbound(num),
+            },
+            RESET_INITIATOR => Frame::Reset {
+                stream_id: StreamId::inbound(num),
+            },
+            _ => return Err(ConnectionError::InvalidFrame),
+        };
+
+        Ok(Some(frame))
+    }
+}
+
+impl Encoder<Frame> for Codec {
+    type Error = ConnectionError;
+
+    fn encode(&mut self, frame: Frame, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let (stream_id, flag, data) = match frame {
+            Frame::Open { stream_id } => (stream_id, NEW_STREAM, Bytes::new()),
+            Frame::Data { stream_id, data } => match stream_id.outbound {
+                true => (stream_id, MESSAGE_INITIATOR, data),
+                false => (stream_id, MESSAGE_RECEIVER, data),
+            },
+            Frame::Close { stream_id } => match stream_id.outbound {
+                true => (stream_id, CLOSE_INITIATOR, Bytes::new()),
+                false => (stream_id, CLOSE_RECEIVER, Bytes::new()),
+            },
+            Frame::Reset { stream_id } => match stream_id.outbound {
+                true => (stream_id, RESET_INITIATOR, Bytes::new()),
+                false => (stream_id, RESET_RECEIVER, Bytes::new()),
+            },
+        };
+
+        if data.len() > MAX_FRAME_SIZE {
+            return Err(ConnectionError::FrameTooLarge(data.len()));
+        }
+
+        let mut header = unsigned_varint::encode::u64_buffer();
+        let header = unsigned_varint::encode::u64(stream_id.num << 3 | flag, &mut header);
+        let mut len = unsigned_varint::encode::usize_buffer();
+        let len = unsigned_varint::encode::usize(data.len(), &mut len);
+
+        dst.reserve(header.len() + len.len() + data.len());
+        dst.put_slice(header);
+        dst.put_slice(len);
+        dst.put_slice(&data);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(frame: Frame) -> BytesMut {
+        let mut buffer = BytesMut::new();
+        Codec.encode(frame, &mut buffer).unwrap();
+        buffer
+    }
+
+    #[test]
+    fn encode_data_frame() {
+        let buffer = encode(Frame::Data {
+            stream_id: StreamId::outbound(17),
+            data: Bytes::from_static(b"hello"),
+        });
+
+        assert_eq!(
+            &buffer[..],
+            &[(17 << 3 | 2) as u8, 0x01, 5, b'h', b'e', b'l', b'l', b'o']
+        );
+    }
+
+    #[test]
+    fn frame_is_received_by_the_other_side() {
+        // frame sent by the peer which opened the stream refers to an inbound stream for the
+        // receiving peer and vice versa
+        for (sent, received) in [
+            (
+                Frame::Open {
+                    stream_id: StreamId::outbound(1),
+                },
+                Frame::Open {
+                    stream_id: StreamId::inbound(1),
+                },
+            ),
+            (
+                Frame::Data {
+                    stream_id: StreamId::outbound(2),
+                    data: Bytes::from_static(b"data"),
+                },
+                Frame::Data {
+                    stream_id: StreamId::inbound(2),
+                    data: Bytes::from_static(b"data"),
+                },
+            ),
+            (
+                Frame::Close {
+                    stream_id: StreamId::inbound(3),
+                },
+                Frame::Close {
+                    stream_id: StreamId::outbound(3),
+                },
+            ),
+            (
+                Frame::Reset {
+                    stream_id: StreamId::outbound(4),
+                },
+                Frame::Reset {
+                    stream_id: StreamId::inbound(4),
+                },
+            ),
+        ] {
+            let mut buffer = encode(sent);
+            assert_eq!(Codec.decode(&mut buffer).unwrap(), Some(received));
+            assert!(buffer.is_empty());
+        }
+    }
+
+    #[test]
+    fn partial_frame() {
+        let buffer = encode(Frame::Data {
+            stream_id: StreamId::inbound(1337),
+            data: Bytes::from(vec![0xaa; 300]),
+        });
+
+        for i in 0..buffer.len() {
+            let mut partial = BytesMut::from(&buffer[..i]);
+            assert_eq!(Codec.decode(&mut partial).unwrap(), None);
+        }
+
+        let mut buffer = buffer;
+        assert!(Codec.decode(&mut buffer).unwrap().is_some());
+    }
+
+    #[test]
+    fn frame_too_large() {
+        let mut len = unsigned_varint::encode::usize_buffer();
+        let mut buffer = BytesMut::from(&[0x02][..]);
+        buffer.put_slice(unsigned_varint::encode::usize(MAX_FRAME_SIZE + 1, &mut len));
+
+        assert_eq!(
+            Codec.decode(&mut buffer),
+            Err(ConnectionError::FrameTooLarge(MAX_FRAME_SIZE + 1))
+        );
+    }
+
+    #[test]
+    fn invalid_flag() {
+        let mut buffer = BytesMut::from(&[0x07, 0x00][..]);
+
+        assert_eq!(
+            Codec.decode(&mut buffer),
+            Err(ConnectionError::InvalidFrame)
+        );
+    }
+}
@@ -0,0 +1,849 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use crate::mplex::{
+    codec::{Codec, Frame, StreamId},
+    Config, ConnectionError,
+};
+
+use bytes::{Buf, Bytes};
+use futures::{
+    channel::{mpsc, oneshot},
+    AsyncRead, AsyncWrite, Sink, SinkExt, StreamExt,
+};
+use parking_lot::Mutex;
+use tokio_util::{
+    codec::Framed,
+    compat::{Compat, FuturesAsyncReadCompatExt},
+};
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt, io,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+};
+
+/// Logging target for the file.
+const LOG_TARGET: &str = "litep2p::mplex";
+
+/// Size of the command channel shared by [`Control`] and the streams of the connection.
+const COMMAND_CHANNEL_SIZE: usize = 256;
+
+/// Maximum number of frames waiting to be sent before [`Connection`] stops reading from the
+/// socket.
+///
+/// Frames such as resets are queued in response to inbound frames, so if the remote node doesn't
+/// read what is sent to it, reading is paused until the queue has been drained.
+const MAX_PENDING_FRAMES: usize = 64;
+
+/// Command sent to [`Connection`].
+#[derive(Debug)]
+enum Command {
+    /// Open new outbound stream.
+    OpenStream(oneshot::Sender<Result<Stream, ConnectionError>>),
+
+    /// Send data over the stream.
+    Data(StreamId, Bytes),
+
+    /// Close the stream for writing.
+    Close(StreamId),
+
+    /// Reset the stream.
+    Reset(StreamId),
+
+    /// Close the connection.
+    CloseConnection(oneshot::Sender<()>),
+}
+
+/// State shared between [`Stream`] and [`Connection`].
+#[derive(Debug, Default)]
+struct Shared {
+    /// Received data which hasn't been read yet.
+    buffer: VecDeque<Bytes>,
+
+    /// Number of bytes in `buffer`.
+    buffered: usize,
+
+    /// Remote node has closed the stream for writing.
+    remote_closed: bool,
+
+    /// Stream has been reset or the connection was closed.
+    reset: bool,
+
+    /// Waker of the reading task.
+    waker: Option<Waker>,
+}
+
+impl Shared {
+    /// Wake up the reading task, if any.
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Stream state kept by [`Connection`].
+struct StreamState {
+    /// State shared with [`Stream`].
+    shared: Arc<Mutex<Shared>>,
+
+    /// Local node has closed the stream for writing.
+    local_closed: bool,
+}
+
+/// Connection state.
+enum State {
+    /// Connection is open.
+    Open,
+
+    /// Connection is being closed.
+    Closing(oneshot::Sender<()>),
+
+    /// Connection has been closed.
+    Closed,
+}
+
+/// mplex connection.
+///
+/// The connection must be polled for inbound streams in order to make progress on any of its
+/// streams, outbound streams are opened through [`Control`].
+pub struct Connection<T> {
+    /// Framed I/O.
+    io: Framed<Compat<T>, Codec>,
+
+    /// mplex configuration.
+    config: Config,
+
+    /// Connection state.
+    state: State,
+
+    /// Number of the next outbound stream.
+    next_stream_num: u64,
+
+    /// Open streams.
+    streams: HashMap<StreamId, StreamState>,
+
+    /// TX channel given to [`Control`] and streams.
+    sender: mpsc::Sender<Command>,
+
+    /// RX channel for receiving commands from [`Control`] and streams.
+    commands: mpsc::Receiver<Command>,
+
+    /// Frames waiting to be sent.
+    pending_frames: VecDeque<Frame>,
+}
+
+impl<T: AsyncRead + AsyncWrite + Unpin> Connection<T> {
+    /// Create new [`Connection`].
+    pub fn new(io: T, config: Config) -> Self {
+        let (sender, commands) = mpsc::channel(COMMAND_CHANNEL_SIZE);
+
+        Self {
+            io: Framed::new(io.compat(), Codec),
+            config,
+            state: State::Open,
+            next_stream_num: 0u64,
+            streams: HashMap::new(),
+            sender,
+            commands,
+            pending_frames: VecDeque::new(),
+        }
+    }
+
+    /// Get [`Control`] for the connection.
+    pub fn control(&self) -> Control {
+        Control {
+            sender: self.sender.clone(),
+        }
+    }
+
+    /// Create new stream and start tracking it.
+    fn new_stream(&mut self, stream_id: StreamId) -> Stream {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+
+        self.streams.insert(
+            stream_id,
+            StreamState {
+                shared: Arc::clone(&shared),
+                local_closed: false,
+            },
+        );
+
+        Stream {
+            stream_id,
+            shared,
+            sender: self.sender.clone(),
+            split_send_size: self.config.split_send_size,
+            local_closed: false,
+        }
+    }
+
+    /// Reset all streams and mark the connection as closed.
+    fn terminate(&mut self) {
+        for (_, state) in self.streams.drain() {
+            let mut shared = state.shared.lock();
+            shared.reset = true;
+            shared.wake();
+        }
+
+        self.state = State::Closed;
+        self.commands.close();
+        self.pending_frames.clear();
+    }
+
+    /// Terminate the connection because of an error.
+    fn on_error(
+        &mut self,
+        error: ConnectionError,
+    ) -> Poll<Option<Result<Stream, ConnectionError>>> {
+        tracing::debug!(target: LOG_TARGET, ?error, "connection failed");
+
+        self.terminate();
+        Poll::Ready(Some(Err(error)))
+    }
+
+    /// Handle command received from [`Control`] or one of the streams.
+    fn on_command(&mut self, command: Command) {
+        match command {
+            Command::OpenStream(reply) => {
+                if self.streams.len() >= self.config.max_substreams {
+                    let _ = reply.send(Err(ConnectionError::TooManyStreams));
+                    return;
+                }
+
+                let stream_id = StreamId::outbound(self.next_stream_num);
+                self.next_stream_num += 1;
+                self.pending_frames.push_back(Frame::Open { stream_id });
+
+                let _ = reply.send(Ok(self.new_stream(stream_id)));
+            }
+            Command::Data(stream_id, data) =>
+                if self.streams.contains_key(&stream_id) {
+                    self.pending_frames.push_back(Frame::Data { stream_id, data });
+                },
+            Command::Close(stream_id) => {
+                let remote_closed = match self.streams.get_mut(&stream_id) {
+                    Some(state) => {
+                        state.local_closed = true;
+                        state.shared.lock().remote_closed
+                    }
+                    None => return,
+                };
+
+                self.pending_frames.push_back(Frame::Close { stream_id });
+
+                if remote_closed {
+                    self.streams.remove(&stream_id);
+                }
+            }
+            Command::Reset(stream_id) =>
+                if self.streams.remove(&stream_id).is_some() {
+                    self.pending_frames.push_back(Frame::Reset { stream_id });
+                },
+            Command::CloseConnection(reply) => {
+                self.state = State::Closing(reply);
+            }
+        }
+    }
+
+    /// Handle frame received from the remote node.
+    ///
+    /// Returns a [`Stream`] if the remote node opened a new stream.
+    fn on_frame(&mut self, frame: Frame) -> Option<Stream> {
+        match frame {
+            Frame::Open { stream_id } => {
+                if self.streams.contains_key(&stream_id) {
+                    tracing::debug!(target: LOG_TARGET, ?stream_id, "stream already exists");
+                    return None;
+                }
+
+                if self.streams.len() >= self.config.max_substreams {
+                    tracing::debug!(
+                        target: LOG_TARGET,
+                        ?stream_id,
+                        "too many streams, reset inbound stream",
+                    );
+
+                    self.pending_frames.push_back(Frame::Reset { stream_id });
+                    return None;
+                }
+
+                Some(self.new_stream(stream_id))
+            }
+            Frame::Data { stream_id, data } => {
+                let Some(state) = self.streams.get(&stream_id) else {
+                    tracing::trace!(target: LOG_TARGET, ?stream_id, "data for unknown stream");
+                    return None;
+                };
+
+                if data.is_empty() {
+                    return None;
+                }
+
+                let mut shared = state.shared.lock();
+
+                if shared.buffered + data.len() <= self.config.max_buffer_size {
+                    shared.buffered += data.len();
+                    shared.buffer.push_back(data);
+                    shared.wake();
+                    return None;
+                }
+
+                tracing::debug!(target: LOG_TARGET, ?stream_id, "stream buffer full, reset stream");
+
+                shared.reset = true;
+                shared.wake();
+                drop(shared);
+
+                self.streams.remove(&stream_id);
+                self.pending_frames.push_back(Frame::Reset { stream_id });
+                None
+            }
+            Frame::Close { stream_id } => {
+                let local_closed = match self.streams.get(&stream_id) {
+                    Some(state) => {
+                        let mut shared = state.shared.lock();
+                        shared.remote_closed = true;
+                        shared.wake();
+
+                        state.local_closed
+                    }
+                    None => return None,
+                };
+
+                if local_closed {
+                    self.streams.remove(&stream_id);
+                }
+                None
+            }
+            Frame::Reset { stream_id } => {
+                if let Some(state) = self.streams.remove(&stream_id) {
+                    let mut shared = state.shared.lock();
+                    shared.reset = true;
+                    shared.wake();
+                }
+                None
+            }
+        }
+    }
+
+    /// Send pending frames to the remote node and flush the connection.
+    fn poll_send_frames(&mut self, cx: &mut Context<'_>) -> Result<(), ConnectionError> {
+        while !self.pending_frames.is_empty() {
+            match Sink::<Frame>::poll_ready(Pin::new(&mut self.io), cx) {
+                Poll::Ready(Ok(())) => {
+                    let frame = self.pending_frames.pop_front().expect("frame to exist");
+                    self.io.start_send_unpin(frame)?;
+                }
+                Poll::Ready(Err(error)) => return Err(error),
+                Poll::Pending => return Ok(()),
+            }
+        }
+
+        match Sink::<Frame>::poll_flush(Pin::new(&mut self.io), cx) {
+            Poll::Ready(Err(error)) => Err(error),
+            _ => Ok(()),
+        }
+    }
+
+    /// Send remaining frames and close the connection.
+    fn poll_close(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Stream, ConnectionError>>> {
+        if let Err(error) = self.poll_send_frames(cx) {
+            return self.on_error(error);
+        }
+
+        if !self.pending_frames.is_empty() {
+            return Poll::Pending;
+        }
+
+        match Sink::<Frame>::poll_close(Pin::new(&mut self.io), cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(result) => {
+                if let State::Closing(reply) = std::mem::replace(&mut self.state, State::Closed) {
+                    let _ = reply.send(());
+                }
+
+                if let Err(error) = result {
+                    tracing::debug!(target: LOG_TARGET, ?error, "failed to close connection");
+                }
+
+                self.terminate();
+                Poll::Ready(None)
+            }
+        }
+    }
+
+    /// Poll the connection for inbound streams.
+    fn poll_next_stream(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Stream, ConnectionError>>> {
+        loop {
+            match self.state {
+                State::Open => {}
+                State::Closing(_) => return self.poll_close(cx),
+                State::Closed => return Poll::Ready(None),
+            }
+
+            if let Err(error) = self.poll_send_frames(cx) {
+                return self.on_error(error);
+            }
+
+            let mut progress = false;
+
+            // commands are processed only after all pending frames have been sent which
+            // applies backpressure to the streams
+            if self.pending_frames.is_empty() {
+                if let Poll::Ready(Some(command)) = self.commands.poll_next_unpin(cx) {
+                    self.on_command(command);
+                    progress = true;
+                }
+            }
+
+            // stop reading from the socket if the remote node isn't reading the frames sent to it,
+            // the connection is polled again once the pending frames can be sent
+            if self.pending_frames.len() >= MAX_PENDING_FRAMES {
+                return Poll::Pending;
+            }
+
+            match self.io.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(frame))) => {
+                    if let Some(stream) = self.on_frame(frame) {
+                        return Poll::Ready(Some(Ok(stream)));
+                    }
+                    progress = true;
+                }
+                Poll::Ready(Some(Err(error))) => return self.on_error(error),
+                Poll::Ready(None) => {
+                    tracing::debug!(target: LOG_TARGET, "connection closed by remote");
+
+                    self.terminate();
+                    return Poll::Ready(None);
+                }
+                Poll::Pending => {}
+            }
+
+            if !progress {
+                return Poll::Pending;
+            }
+        }
+    }
+}
+
+impl<T: AsyncRead + AsyncWrite + Unpin> futures::Stream for Connection<T> {
+    type Item = Result<Stream, ConnectionError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_next_stream(cx)
+    }
+}
+
+/// mplex [`Connection`] controller.
+///
+/// [`Control`] can be cloned and shared between tasks and threads.
+#[derive(Debug, Clone)]
+pub struct Control {
+    /// TX channel for sending commands to [`Connection`].
+    sender: mpsc::Sender<Command>,
+}
+
+impl Control {
+    /// Open a new stream to the remote.
+    pub async fn open_stream(&mut self) -> Result<Stream, ConnectionError> {
+        let (tx, rx) = oneshot::channel();
+
+        self.sender
+            .send(Command::OpenStream(tx))
+            .await
+            .map_err(|_| ConnectionError::Closed)?;
+        rx.await.map_err(|_| ConnectionError::Closed)?
+    }
+
+    /// Close the connection.
+    pub async fn close(&mut self) -> Result<(), ConnectionError> {
+        let (tx, rx) = oneshot::channel();
+
+        if self.sender.send(Command::CloseConnection(tx)).await.is_err() {
+            // the receiver is closed which means the connection is already closed
+            return Ok(());
+        }
+
+        let _ = rx.await;
+        Ok(())
+    }
+}
+
+/// mplex stream.
+///
+/// Dropping the stream before it has been closed by both peers resets it.
+pub struct Stream {
+    /// Stream ID.
+    stream_id: StreamId,
+
+    /// State shared with [`Connection`].
+    shared: Arc<Mutex<Shared>>,
+
+    /// TX channel for sending commands to [`Connection`].
+    sender: mpsc::Sender<Command>,
+
+    /// Maximum size of a data frame.
+    split_send_size: usize,
+
+    /// Local node has closed the stream for writing.
+    local_closed: bool,
+}
+
+impl Stream {
+    /// Get stream ID.
+    pub fn id(&self) -> StreamId {
+        self.stream_id
+    }
+}
+
+impl fmt::Debug for Stream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stream").field("stream_id", &self.stream_id).finish()
+    }
+}
+
+impl AsyncRead for Stream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        let mut shared = self.shared.lock();
+
+        if let Some(data) = shared.buffer.front_mut() {
+            let nread = std::cmp::min(data.len(), buf.len());
+            buf[..nread].copy_from_slice(&data[..nread]);
+            data.advance(nread);
+
+            let empty = data.is_empty();
+            shared.buffered -= nread;
+
+            if empty {
+                shared.buffer.pop_front();
+            }
+
+            return Poll::Ready(Ok(nread));
+        }
+
+        if shared.reset {
+            return Poll::Ready(Err(io::ErrorKind::ConnectionReset.into()));
+        }
+
+        if shared.remote_closed {
+            return Poll::Ready(Ok(0));
+        }
+
+        shared.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl AsyncWrite for Stream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        if this.local_closed {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+
+        if this.shared.lock().reset {
+            return Poll::Ready(Err(io::ErrorKind::ConnectionReset.into()));
+        }
+
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        futures::ready!(this.sender.poll_ready(cx))
+            .map_err(|_| io::Error::from(io::ErrorKind::ConnectionReset))?;
+
+        let nwritten = std::cmp::min(buf.len(), this.split_send_size);
+        this.sender
+            .start_send(Command::Data(
+                this.stream_id,
+                Bytes::copy_from_slice(&buf[..nwritten]),
+            ))
+            .map_err(|_| io::Error::from(io::ErrorKind::ConnectionReset))?;
+
+        Poll::Ready(Ok(nwritten))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        if this.local_closed {
+            return Poll::Ready(Ok(()));
+        }
+
+        futures::ready!(this.sender.poll_ready(cx))
+            .map_err(|_| io::Error::from(io::ErrorKind::ConnectionReset))?;
+        this.sender
+            .start_send(Command::Close(this.stream_id))
+            .map_err(|_| io::Error::from(io::ErrorKind::ConnectionReset))?;
+        this.local_closed = true;
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Drop for Stream {
+    fn drop(&mut self) {
+        {
+            let shared = self.shared.lock();
+
+            if shared.reset || (self.local_closed && shared.remote_closed) {
+                return;
+            }
+        }
+
+        // use a fresh sender so the reset is queued even if `self.sender` is waiting for capacity
+        let _ = self.sender.clone().try_send(Command::Reset(self.stream_id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{AsyncReadExt, AsyncWriteExt};
+    use futures_ringbuf::Endpoint;
+
+    /// Create connected mplex connections and spawn the responder's connection into a background
+    /// task which forwards inbound streams to the returned channel.
+    fn connections(
+        config: Config,
+    ) -> (
+        Connection<Endpoint>,
+        Control,
+        tokio::sync::mpsc::Receiver<Stream>,
+    ) {
+        let (io1, io2) = Endpoint::pair(64 * 1024, 64 * 1024);
+        let connection1 = Connection::new(io1, config.clone());
+        let control = connection1.control();
+        let mut connection2 = Connection::new(io2, config);
+        let (tx, rx) = tokio::sync::mpsc::channel(64);
+
+        tokio::spawn(async move {
+            while let Some(Ok(stream)) = connection2.next().await {
+                let _ = tx.send(stream).await;
+            }
+        });
+
+        (connection1, control, rx)
+    }
+
+    /// Drive the connection in a background task.
+    fn drive(mut connection: Connection<Endpoint>) {
+        tokio::spawn(async move { while connection.next().await.is_some() {} });
+    }
+
+    #[tokio::test]
+    async fn open_stream_and_exchange_data() {
+        let (connection, mut control, mut inbound) = connections(Config::default());
+        drive(connection);
+
+        let mut outbound = control.open_stream().await.unwrap();
+        outbound.write_all(b"hello, world").await.unwrap();
+
+        let mut stream = inbound.recv().await.unwrap();
+        assert!(!stream.id().is_outbound());
+
+        let mut buffer = [0u8; 12];
+        stream.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b"hello, world");
+
+        stream.write_all(b"goodbye").await.unwrap();
+        let mut buffer = [0u8; 7];
+        outbound.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b"goodbye");
+    }
+
+    #[tokio::test]
+    async fn large_write_is_split() {
+        let (connection, mut control, mut inbound) = connections(Config {
+            split_send_size: 1024,
+            ..Default::default()
+        });
+        drive(connection);
+
+        let data = (0..16 * 1024).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+        let mut outbound = control.open_stream().await.unwrap();
+        outbound.write_all(&data).await.unwrap();
+        outbound.close().await.unwrap();
+
+        let mut stream = inbound.recv().await.unwrap();
+        let mut received = Vec::new();
+        stream.read_to_end(&mut received).await.unwrap();
+        assert_eq!(received, data);
+    }
+
+    #[tokio::test]
+    async fn half_closed_stream_can_be_written_to() {
+        let (connection, mut control, mut inbound) = connections(Config::default());
+        drive(connection);
+
+        let mut outbound = control.open_stream().await.unwrap();
+        outbound.write_all(b"request").await.unwrap();
+        outbound.close().await.unwrap();
+
+        let mut stream = inbound.recv().await.unwrap();
+        let mut request = Vec::new();
+        stream.read_to_end(&mut request).await.unwrap();
+        assert_eq!(request, b"request");
+
+        stream.write_all(b"response").await.unwrap();
+        stream.close().await.unwrap();
+
+        let mut response = Vec::new();
+        outbound.read_to_end(&mut response).await.unwrap();
+        assert_eq!(response, b"response");
+    }
+
+    #[tokio::test]
+    async fn dropped_stream_is_reset() {
+        let (connection, mut control, mut inbound) = connections(Config::default());
+        drive(connection);
+
+        let outbound = control.open_stream().await.unwrap();
+        let mut stream = inbound.recv().await.unwrap();
+        drop(outbound);
+
+        let mut buffer = [0u8; 1];
+        assert_eq!(
+            stream.read(&mut buffer).await.unwrap_err().kind(),
+            io::ErrorKind::ConnectionReset
+        );
+    }
+
+    #[tokio::test]
+    async fn too_many_outbound_streams() {
+        let (connection, mut control, _inbound) = connections(Config {
+            max_substreams: 2,
+            ..Default::default()
+        });
+        drive(connection);
+
+        let _stream1 = control.open_stream().await.unwrap();
+        let _stream2 = control.open_stream().await.unwrap();
+
+        assert_eq!(
+            control.open_stream().await.unwrap_err(),
+            ConnectionError::TooManyStreams
+        );
+    }
+
+    #[tokio::test]
+    async fn closed_connection_resets_streams() {
+        let (connection, mut control, mut inbound) = connections(Config::default());
+        drive(connection);
+
+        let _outbound = control.open_stream().await.unwrap();
+        let mut stream = inbound.recv().await.unwrap();
+        control.close().await.unwrap();
+
+        let mut buffer = [0u8; 1];
+        assert_eq!(
+            stream.read(&mut buffer).await.unwrap_err().kind(),
+            io::ErrorKind::ConnectionReset
+        );
+        assert_eq!(
+            control.open_stream().await.unwrap_err(),
+            ConnectionError::Closed
+        );
+    }
+
+    #[tokio::test]
+    async fn reading_stops_when_remote_does_not_read() {
+        let (io1, io2) = Endpoint::pair(1024, 1024);
+        let mut connection = Connection::new(
+            io1,
+            Config {
+                max_substreams: 0,
+                ..Default::default()
+            },
+        );
+        let sent = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+        // remote node opens streams which are all reset but never reads the resets
+        tokio::spawn({
+            let sent = Arc::clone(&sent);
+
+            async move {
+                let mut io = Framed::new(io2.compat(), Codec);
+
+                for i in 0..100_000 {
+                    let stream_id = StreamId::outbound(i);
+                    if io.send(Frame::Open { stream_id }).await.is_err() {
+                        return;
+                    }
+                    sent.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                }
+            }
+        });
+
+        let _ = tokio::time::timeout(std::time::Duration::from_secs(1), connection.next()).await;
+
+        assert!(connection.pending_frames.len() <= MAX_PENDING_FRAMES);
+        assert!(sent.load(std::sync::atomic::Ordering::Relaxed) < 100_000);
+    }
+
+    #[tokio::test]
+    async fn buffer_is_bounded_in_bytes() {
+        let (connection, mut control, mut inbound) = connections(Config {
+            max_buffer_size: 1024,
+            ..Default::default()
+        });
+        drive(connection);
+
+        let mut outbound = control.open_stream().await.unwrap();
+        let mut stream = inbound.recv().await.unwrap();
+
+        outbound.write_all(&[0u8; 1024]).await.unwrap();
+        outbound.write_all(&[0u8; 1]).await.unwrap();
+
+        let mut buffer = vec![0u8; 1024];
+        stream.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(
+            stream.read(&mut buffer).await.unwrap_err().kind(),
+            io::ErrorKind::ConnectionReset
+        );
+    }
+}
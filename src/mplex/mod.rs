@@ -0,0 +1,106 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Implementation of the [mplex specification][1].
+//!
+//! mplex is a simple stream multiplexer without flow control which is still used by some older
+//! libp2p implementations. Yamux should be preferred when both peers support it.
+//!
+//! The API mirrors the one of [`crate::yamux`]:
+//!
+//! - [`Connection`], which wraps the underlying I/O resource and yields inbound streams,
+//! - [`Stream`], which implements [`futures::io::AsyncRead`] and [`futures::io::AsyncWrite`], and
+//! - [`Control`], to asynchronously open streams and close the [`Connection`].
+//!
+//! [1]: https://github.com/libp2p/specs/tree/master/mplex
+
+#![forbid(unsafe_code)]
+
+mod codec;
+mod connection;
+
+pub use codec::{StreamId, MAX_FRAME_SIZE};
+pub use connection::{Connection, Control, Stream};
+
+/// Protocol name of mplex.
+pub const PROTOCOL_NAME: &str = "/mplex/6.7.0";
+
+/// mplex configuration.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Maximum number of simultaneously open substreams.
+    ///
+    /// Inbound substreams opened over the limit are reset and opening outbound substreams fails
+    /// with [`ConnectionError::TooManyStreams`].
+    pub max_substreams: usize,
+
+    /// Maximum number of unread bytes buffered for a substream.
+    ///
+    /// mplex has no flow control so a substream which isn't read fast enough is reset once the
+    /// limit is exceeded.
+    ///
+    /// The memory used for receive buffers of a connection is bounded by
+    /// `max_substreams * max_buffer_size`, which is 128 MiB with the default configuration. The
+    /// default is [`MAX_FRAME_SIZE`] so that a single frame of any valid size can be received.
+    pub max_buffer_size: usize,
+
+    /// Maximum size of a data frame sent by the local node.
+    pub split_send_size: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_substreams: 128,
+            max_buffer_size: MAX_FRAME_SIZE,
+            split_send_size: 8 * 1024,
+        }
+    }
+}
+
+/// mplex connection error.
+#[derive(Debug, thiserror::Error, Clone, PartialEq, Eq)]
+pub enum ConnectionError {
+    /// I/O error.
+    #[error("I/O error: `{0}`")]
+    Io(std::io::ErrorKind),
+
+    /// Received frame is malformed.
+    #[error("Invalid frame")]
+    InvalidFrame,
+
+    /// Frame exceeds the maximum frame size.
+    #[error("Frame too large: `{0}`")]
+    FrameTooLarge(usize),
+
+    /// Connection has been closed.
+    #[error("Connection closed")]
+    Closed,
+
+    /// Maximum number of substreams has been reached.
+    #[error("Too many streams")]
+    TooManyStreams,
+}
+
+impl From<std::io::Error> for ConnectionError {
+    fn from(error: std::io::Error) -> Self {
+        ConnectionError::Io(error.kind())
+    }
+}
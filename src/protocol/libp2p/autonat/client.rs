@@ -0,0 +1,245 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Client side of AutoNAT: dial requests sent to servers and dial-backs received from them.
+
+use crate::{
+    error::Error,
+    protocol::libp2p::autonat::{read_message, schema, send_message, Reachability},
+    substream::Substream,
+    PeerId,
+};
+
+use multiaddr::Multiaddr;
+use parking_lot::Mutex;
+
+use std::{collections::HashMap, sync::Arc};
+
+/// Size of a single dial data chunk sent to the server.
+const DIAL_DATA_CHUNK_SIZE: usize = 4096;
+
+/// Results received for an address during a probe.
+#[derive(Debug, Default)]
+pub(super) struct Tally {
+    /// How many servers were able to dial the address.
+    reachable: usize,
+
+    /// How many servers failed to dial the address.
+    unreachable: usize,
+}
+
+impl Tally {
+    /// Record the result reported by a server.
+    ///
+    /// Returns the reachability of the address once `confirmations` servers agree on it.
+    pub(super) fn record(
+        &mut self,
+        reachability: Reachability,
+        confirmations: usize,
+    ) -> Option<Reachability> {
+        let count = match reachability {
+            Reachability::Public => {
+                self.reachable += 1;
+                self.reachable
+            }
+            Reachability::Private => {
+                self.unreachable += 1;
+                self.unreachable
+            }
+            Reachability::Unknown => return None,
+        };
+
+        (count >= confirmations).then_some(reachability)
+    }
+}
+
+/// Ask the server to dial `address` using AutoNAT v2.
+///
+/// [`Reachability::Public`] is returned if the server reported a successful dial. The caller
+/// must still verify that the dial-back with `nonce` was received.
+pub(super) async fn dial_request(
+    mut substream: Substream,
+    address: Multiaddr,
+    nonce: u64,
+    max_dial_data: usize,
+) -> crate::Result<Reachability> {
+    use schema::v2::{
+        dial_response::ResponseStatus, message::Msg, DialDataResponse, DialRequest, DialStatus,
+        Message,
+    };
+
+    send_message(
+        &mut substream,
+        &Message {
+            msg: Some(Msg::DialRequest(DialRequest {
+                addrs: vec![address.to_vec()],
+                nonce,
+            })),
+        },
+    )
+    .await?;
+
+    let mut dial_data_sent = false;
+
+    loop {
+        match read_message::<Message>(&mut substream).await?.msg {
+            Some(Msg::DialDataRequest(request)) => {
+                let num_bytes = usize::try_from(request.num_bytes).unwrap_or(usize::MAX);
+
+                if dial_data_sent || request.addr_idx != 0 || num_bytes > max_dial_data {
+                    return Err(Error::InputRejected);
+                }
+                dial_data_sent = true;
+
+                let mut remaining = num_bytes;
+                while remaining > 0 {
+                    let size = remaining.min(DIAL_DATA_CHUNK_SIZE);
+                    let message = Message {
+                        msg: Some(Msg::DialDataResponse(DialDataResponse {
+                            data: vec![0u8; size],
+                        })),
+                    };
+
+                    send_message(&mut substream, &message).await?;
+                    remaining -= size;
+                }
+            }
+            Some(Msg::DialResponse(response)) => {
+                let _ = substream.close().await;
+
+                return Ok(match (response.status(), response.dial_status()) {
+                    (ResponseStatus::Ok, DialStatus::Ok) => Reachability::Public,
+                    (ResponseStatus::Ok, DialStatus::EDialError) => Reachability::Private,
+                    _ => Reachability::Unknown,
+                });
+            }
+            _ => return Err(Error::InvalidData),
+        }
+    }
+}
+
+/// Ask the server to dial `address` using AutoNAT v1.
+pub(super) async fn dial_request_v1(
+    mut substream: Substream,
+    local_peer_id: PeerId,
+    address: Multiaddr,
+) -> crate::Result<Reachability> {
+    use schema::v1::{
+        message::{Dial, MessageType, PeerInfo, ResponseStatus},
+        Message,
+    };
+
+    send_message(
+        &mut substream,
+        &Message {
+            r#type: Some(MessageType::Dial as i32),
+            dial: Some(Dial {
+                peer: Some(PeerInfo {
+                    id: Some(local_peer_id.to_bytes()),
+                    addrs: vec![address.to_vec()],
+                }),
+            }),
+            dial_response: None,
+        },
+    )
+    .await?;
+
+    let message = read_message::<Message>(&mut substream).await?;
+    let _ = substream.close().await;
+
+    if message.r#type() != MessageType::DialResponse {
+        return Err(Error::InvalidData);
+    }
+    let response = message.dial_response.ok_or(Error::InvalidData)?;
+
+    Ok(match response.status() {
+        ResponseStatus::Ok => Reachability::Public,
+        ResponseStatus::EDialError => Reachability::Private,
+        _ => Reachability::Unknown,
+    })
+}
+
+/// Handle a dial-back performed by a server.
+///
+/// The nonce is marked as verified before the dial-back is acknowledged so it's visible by
+/// the time the server sends its dial response.
+pub(super) async fn dial_back(
+    mut substream: Substream,
+    nonces: Arc<Mutex<HashMap<u64, bool>>>,
+) -> crate::Result<()> {
+    use schema::v2::{dial_back_response::DialBackStatus, DialBack, DialBackResponse};
+
+    let DialBack { nonce } = read_message::<DialBack>(&mut substream).await?;
+
+    let known = match nonces.lock().get_mut(&nonce) {
+        Some(verified) => {
+            *verified = true;
+            true
+        }
+        None => false,
+    };
+
+    if !known {
+        return Err(Error::InvalidData);
+    }
+
+    send_message(
+        &mut substream,
+        &DialBackResponse {
+            status: DialBackStatus::Ok as i32,
+        },
+    )
+    .await?;
+    let _ = substream.close().await;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tally_requires_confirmations() {
+        let mut tally = Tally::default();
+
+        assert_eq!(tally.record(Reachability::Public, 2), None);
+        assert_eq!(tally.record(Reachability::Unknown, 2), None);
+        assert_eq!(tally.record(Reachability::Private, 2), None);
+        assert_eq!(
+            tally.record(Reachability::Public, 2),
+            Some(Reachability::Public)
+        );
+        assert_eq!(
+            tally.record(Reachability::Private, 2),
+            Some(Reachability::Private)
+        );
+    }
+
+    #[test]
+    fn single_confirmation() {
+        let mut tally = Tally::default();
+
+        assert_eq!(
+            tally.record(Reachability::Private, 1),
+            Some(Reachability::Private)
+        );
+    }
+}
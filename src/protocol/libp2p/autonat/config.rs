@@ -0,0 +1,260 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use crate::{
+    codec::ProtocolCodec,
+    protocol::libp2p::autonat::{AutoNatCommand, AutoNatEvent, AutoNatHandle, Reachability},
+    types::protocol::ProtocolName,
+    DEFAULT_CHANNEL_SIZE,
+};
+
+use multiaddr::Multiaddr;
+use parking_lot::RwLock;
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+/// AutoNAT v2 dial request protocol name.
+pub const PROTOCOL_NAME: &str = "/libp2p/autonat/2/dial-request";
+
+/// AutoNAT v2 dial-back protocol name.
+pub const DIAL_BACK_PROTOCOL_NAME: &str = "/libp2p/autonat/2/dial-back";
+
+/// AutoNAT v1 protocol name, used as a fallback for the dial request protocol.
+pub const PROTOCOL_NAME_V1: &str = "/libp2p/autonat/1.0.0";
+
+/// Maximum size for AutoNAT payloads.
+const MAX_PAYLOAD_SIZE: usize = 8192;
+
+/// Default interval between reachability probes.
+const PROBE_INTERVAL: Duration = Duration::from_secs(90);
+
+/// Default number of servers queried for each address during a probe.
+const SERVERS_PER_PROBE: usize = 3;
+
+/// Default number of agreeing servers needed to conclude reachability of an address.
+const CONFIRMATIONS: usize = 2;
+
+/// Default maximum number of bytes the client is willing to send to a server requesting dial
+/// data.
+const MAX_DIAL_DATA: usize = 100_000;
+
+/// Default maximum number of dial-backs the server performs concurrently.
+const MAX_CONCURRENT_DIAL_BACKS: usize = 16;
+
+/// Default maximum number of dial requests the server accepts from a peer within
+/// [`REQUEST_THROTTLE_PERIOD`].
+const MAX_REQUESTS_PER_PEER: usize = 3;
+
+/// Default period over which the server counts the dial requests of a peer.
+const REQUEST_THROTTLE_PERIOD: Duration = Duration::from_secs(60);
+
+/// AutoNAT configuration.
+pub struct Config {
+    /// Dial request protocol name.
+    pub(crate) protocol: ProtocolName,
+
+    /// Fallback names of the dial request protocol.
+    pub(crate) fallback_names: Vec<ProtocolName>,
+
+    /// Dial-back protocol name.
+    pub(crate) dial_back_protocol: ProtocolName,
+
+    /// Codec used by the protocol.
+    pub(crate) codec: ProtocolCodec,
+
+    /// Interval between reachability probes.
+    pub(super) probe_interval: Duration,
+
+    /// Number of servers queried for each address during a probe.
+    pub(super) servers_per_probe: usize,
+
+    /// Number of agreeing servers needed to conclude reachability of an address.
+    pub(super) confirmations: usize,
+
+    /// Maximum number of bytes sent to a server requesting dial data.
+    pub(super) max_dial_data: usize,
+
+    /// Whether the server side of the protocol is enabled.
+    pub(super) server: bool,
+
+    /// Whether the server dials only globally routable addresses.
+    pub(super) only_global_ips: bool,
+
+    /// Maximum number of dial-backs the server performs concurrently.
+    pub(super) max_concurrent_dial_backs: usize,
+
+    /// Maximum number of dial requests the server accepts from a peer within
+    /// `request_throttle_period`.
+    pub(super) max_requests_per_peer: usize,
+
+    /// Period over which the server counts the dial requests of a peer.
+    pub(super) request_throttle_period: Duration,
+
+    /// TX channel for sending events to the user protocol.
+    pub(super) event_tx: Sender<AutoNatEvent>,
+
+    /// RX channel for receiving commands from the user.
+    pub(super) cmd_rx: Receiver<AutoNatCommand>,
+
+    /// Reachability of local addresses, shared with [`AutoNatHandle`].
+    pub(super) reachability: Arc<RwLock<HashMap<Multiaddr, Reachability>>>,
+}
+
+/// AutoNAT configuration builder.
+pub struct ConfigBuilder {
+    /// Interval between reachability probes.
+    probe_interval: Duration,
+
+    /// Number of servers queried for each address during a probe.
+    servers_per_probe: usize,
+
+    /// Number of agreeing servers needed to conclude reachability of an address.
+    confirmations: usize,
+
+    /// Maximum number of bytes sent to a server requesting dial data.
+    max_dial_data: usize,
+
+    /// Whether the server side of the protocol is enabled.
+    server: bool,
+
+    /// Whether the server dials only globally routable addresses.
+    only_global_ips: bool,
+
+    /// Maximum number of dial-backs the server performs concurrently.
+    max_concurrent_dial_backs: usize,
+
+    /// Maximum number of dial requests the server accepts from a peer.
+    max_requests_per_peer: usize,
+
+    /// Period over which the server counts the dial requests of a peer.
+    request_throttle_period: Duration,
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigBuilder {
+    /// Create new [`ConfigBuilder`] with the client enabled and the server disabled.
+    pub fn new() -> Self {
+        Self {
+            probe_interval: PROBE_INTERVAL,
+            servers_per_probe: SERVERS_PER_PROBE,
+            confirmations: CONFIRMATIONS,
+            max_dial_data: MAX_DIAL_DATA,
+            server: false,
+            only_global_ips: true,
+            max_concurrent_dial_backs: MAX_CONCURRENT_DIAL_BACKS,
+            max_requests_per_peer: MAX_REQUESTS_PER_PEER,
+            request_throttle_period: REQUEST_THROTTLE_PERIOD,
+        }
+    }
+
+    /// Set interval between reachability probes.
+    pub fn with_probe_interval(mut self, probe_interval: Duration) -> Self {
+        self.probe_interval = probe_interval;
+        self
+    }
+
+    /// Set how many connected servers are asked to dial each address during a probe.
+    pub fn with_servers_per_probe(mut self, servers_per_probe: usize) -> Self {
+        self.servers_per_probe = servers_per_probe.max(1);
+        self
+    }
+
+    /// Set how many servers must agree before an address is considered public or private.
+    pub fn with_confirmations(mut self, confirmations: usize) -> Self {
+        self.confirmations = confirmations.max(1);
+        self
+    }
+
+    /// Set maximum number of bytes sent to a server which requests dial data before dialing
+    /// an address.
+    ///
+    /// Requests asking for more data are aborted.
+    pub fn with_max_dial_data(mut self, max_dial_data: usize) -> Self {
+        self.max_dial_data = max_dial_data;
+        self
+    }
+
+    /// Enable the server side of the protocol, dialing back addresses requested by remote
+    /// peers.
+    ///
+    /// The dial-back is performed over a new connection so if the transport reuses the listen
+    /// port for outbound connections, a dial-back to a peer which is connected to that same
+    /// listen port fails.
+    pub fn with_server(mut self) -> Self {
+        self.server = true;
+        self
+    }
+
+    /// Set whether the server dials only globally routable IP addresses.
+    ///
+    /// Enabled by default, disabling it allows testing the protocol on local networks.
+    pub fn with_only_global_ips(mut self, only_global_ips: bool) -> Self {
+        self.only_global_ips = only_global_ips;
+        self
+    }
+
+    /// Set maximum number of dial-backs the server performs concurrently.
+    pub fn with_max_concurrent_dial_backs(mut self, max_concurrent_dial_backs: usize) -> Self {
+        self.max_concurrent_dial_backs = max_concurrent_dial_backs;
+        self
+    }
+
+    /// Set how many dial requests the server accepts from a single peer within `period`.
+    pub fn with_peer_request_limit(mut self, max_requests: usize, period: Duration) -> Self {
+        self.max_requests_per_peer = max_requests;
+        self.request_throttle_period = period;
+        self
+    }
+
+    /// Build [`Config`].
+    pub fn build(self) -> (Config, AutoNatHandle) {
+        let (event_tx, event_rx) = channel(DEFAULT_CHANNEL_SIZE);
+        let (cmd_tx, cmd_rx) = channel(DEFAULT_CHANNEL_SIZE);
+        let reachability = Arc::new(RwLock::new(HashMap::new()));
+
+        (
+            Config {
+                protocol: ProtocolName::from(PROTOCOL_NAME),
+                fallback_names: vec![ProtocolName::from(PROTOCOL_NAME_V1)],
+                dial_back_protocol: ProtocolName::from(DIAL_BACK_PROTOCOL_NAME),
+                codec: ProtocolCodec::UnsignedVarint(Some(MAX_PAYLOAD_SIZE)),
+                probe_interval: self.probe_interval,
+                servers_per_probe: self.servers_per_probe,
+                confirmations: self.confirmations,
+                max_dial_data: self.max_dial_data,
+                server: self.server,
+                only_global_ips: self.only_global_ips,
+                max_concurrent_dial_backs: self.max_concurrent_dial_backs,
+                max_requests_per_peer: self.max_requests_per_peer,
+                request_throttle_period: self.request_throttle_period,
+                event_tx,
+                cmd_rx,
+                reachability: Arc::clone(&reachability),
+            },
+            AutoNatHandle::new(event_rx, cmd_tx, reachability),
+        )
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! AutoNAT handle for communicating with the AutoNAT protocol implementation.
+
+use crate::PeerId;
+
+use multiaddr::Multiaddr;
+use parking_lot::RwLock;
+use tokio::sync::mpsc::{Receiver, Sender};
+
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+/// Reachability of a local address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reachability {
+    /// Remote peers were able to dial the address.
+    Public,
+
+    /// Remote peers failed to dial the address.
+    Private,
+
+    /// Reachability of the address is not known.
+    Unknown,
+}
+
+/// Events emitted by the AutoNAT protocol.
+#[derive(Debug)]
+pub enum AutoNatEvent {
+    /// A server answered a dial request of the local node.
+    DialResult {
+        /// Peer ID of the server.
+        server: PeerId,
+
+        /// Tested address.
+        address: Multiaddr,
+
+        /// Reachability reported by the server.
+        ///
+        /// [`Reachability::Unknown`] if the server refused to dial the address.
+        reachability: Reachability,
+    },
+
+    /// Reachability of a local address changed.
+    ReachabilityChanged {
+        /// Local address.
+        address: Multiaddr,
+
+        /// New reachability of the address.
+        reachability: Reachability,
+    },
+}
+
+/// Commands sent from the user to `AutoNat`.
+#[derive(Debug)]
+pub(crate) enum AutoNatCommand {
+    /// Probe reachability of local addresses immediately.
+    Probe,
+}
+
+/// Handle for communicating with the AutoNAT protocol.
+pub struct AutoNatHandle {
+    /// RX channel for receiving AutoNAT events.
+    event_rx: Receiver<AutoNatEvent>,
+
+    /// TX channel for sending commands to `AutoNat`.
+    cmd_tx: Sender<AutoNatCommand>,
+
+    /// Reachability of local addresses, shared with `AutoNat`.
+    reachability: Arc<RwLock<HashMap<Multiaddr, Reachability>>>,
+}
+
+impl AutoNatHandle {
+    /// Create new [`AutoNatHandle`].
+    pub(super) fn new(
+        event_rx: Receiver<AutoNatEvent>,
+        cmd_tx: Sender<AutoNatCommand>,
+        reachability: Arc<RwLock<HashMap<Multiaddr, Reachability>>>,
+    ) -> Self {
+        Self {
+            event_rx,
+            cmd_tx,
+            reachability,
+        }
+    }
+
+    /// Probe reachability of local addresses without waiting for the probe interval.
+    pub async fn probe(&self) {
+        let _ = self.cmd_tx.send(AutoNatCommand::Probe).await;
+    }
+
+    /// Get reachability of a local `address`.
+    pub fn reachability(&self, address: &Multiaddr) -> Reachability {
+        self.reachability.read().get(address).copied().unwrap_or(Reachability::Unknown)
+    }
+
+    /// Get all local addresses with a known reachability.
+    pub fn addresses(&self) -> HashMap<Multiaddr, Reachability> {
+        self.reachability.read().clone()
+    }
+}
+
+impl futures::Stream for AutoNatHandle {
+    type Item = AutoNatEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.event_rx).poll_recv(cx)
+    }
+}
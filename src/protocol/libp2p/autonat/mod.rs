@@ -0,0 +1,1025 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! [`AutoNAT v2`](https://github.com/libp2p/specs/blob/master/autonat/autonat-v2.md)
+//! implementation with [`AutoNAT v1`](https://github.com/libp2p/specs/blob/master/autonat/autonat-v1.md)
+//! as a fallback.
+//!
+//! The client periodically asks connected peers to dial its listen and public addresses and
+//! concludes the reachability of each address once enough servers agree. Addresses found to be
+//! public are added to the public addresses of the node and addresses found to be private are
+//! removed from them.
+//!
+//! The server dials the requested address over a new connection. Requests are rate limited and
+//! the client must send extra data before the server dials an IP address which doesn't match
+//! the observed address of the client.
+
+use crate::{
+    error::{Error, SubstreamError},
+    protocol::{Direction, TransportEvent, TransportService},
+    substream::Substream,
+    transport::{common::ip::ip_address, Endpoint},
+    types::{ConnectionId, SubstreamId},
+    utils::futures_stream::FuturesStream,
+    PeerId,
+};
+
+use futures::{future::BoxFuture, StreamExt};
+use multiaddr::{Multiaddr, Protocol};
+use parking_lot::{Mutex, RwLock};
+use prost::Message;
+use rand::{seq::IteratorRandom, Rng};
+use tokio::sync::mpsc::{Receiver, Sender};
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+pub use config::{Config, ConfigBuilder, DIAL_BACK_PROTOCOL_NAME, PROTOCOL_NAME, PROTOCOL_NAME_V1};
+pub(crate) use handle::AutoNatCommand;
+pub use handle::{AutoNatEvent, AutoNatHandle, Reachability};
+
+mod client;
+mod config;
+mod handle;
+mod server;
+
+mod schema {
+    pub(super) mod v1 {
+        include!(concat!(env!("OUT_DIR"), "/autonat_v1.rs"));
+    }
+
+    pub(super) mod v2 {
+        include!(concat!(env!("OUT_DIR"), "/autonat_v2.rs"));
+    }
+}
+
+/// Log target for the file.
+const LOG_TARGET: &str = "litep2p::autonat";
+
+/// Timeout for a dial request sent to a server, including the dial-back.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Timeout for reading a dial request and the dial data from a client.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Timeout for the dial-back performed by the server.
+const DIAL_BACK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Minimum number of bytes requested from a client before dialing an unobserved IP.
+const MIN_DIAL_DATA: u64 = 30_000;
+
+/// Maximum number of bytes requested from a client before dialing an unobserved IP.
+const MAX_DIAL_DATA: u64 = 100_000;
+
+/// Version of the dial request protocol negotiated for a substream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Version {
+    /// AutoNAT v1.
+    V1,
+
+    /// AutoNAT v2.
+    V2,
+}
+
+impl Version {
+    /// Get the version of a substream from the negotiated fallback protocol.
+    fn from_fallback(fallback: &Option<crate::types::protocol::ProtocolName>) -> Self {
+        match fallback {
+            Some(_) => Self::V1,
+            None => Self::V2,
+        }
+    }
+}
+
+/// Remove `/p2p/...` from `address`.
+fn strip_peer_id(address: &Multiaddr) -> Multiaddr {
+    address
+        .iter()
+        .filter(|protocol| !matches!(protocol, Protocol::P2p(_)))
+        .collect()
+}
+
+/// Read and decode the next message from `substream`.
+async fn read_message<T: Message + Default>(substream: &mut Substream) -> crate::Result<T> {
+    match substream.next().await {
+        None => Err(Error::SubstreamError(SubstreamError::ReadFailure(None))),
+        Some(Err(error)) => Err(error.into()),
+        Some(Ok(payload)) => Ok(T::decode(payload.freeze())?),
+    }
+}
+
+/// Encode and send `message` over `substream`.
+async fn send_message(substream: &mut Substream, message: &impl Message) -> crate::Result<()> {
+    substream.send_framed(message.encode_to_vec().into()).await.map_err(From::from)
+}
+
+/// Result of a dial request sent to a server.
+struct DialResult {
+    /// Peer ID of the server.
+    peer: PeerId,
+
+    /// Tested address.
+    address: Multiaddr,
+
+    /// Nonce expected over the dial-back connection, `None` for AutoNAT v1.
+    nonce: Option<u64>,
+
+    /// Reachability reported by the server.
+    result: crate::Result<Reachability>,
+}
+
+/// Dial-back requested by a client.
+struct PendingDialBack {
+    /// Negotiated protocol version.
+    version: Version,
+
+    /// Substream of the dial request.
+    substream: Substream,
+
+    /// Nonce sent over the dial-back connection.
+    nonce: u64,
+
+    /// Index of the dialed address in the request.
+    addr_idx: u32,
+
+    /// Dialed address.
+    address: Multiaddr,
+
+    /// Dial-back connection, once established.
+    connection_id: Option<ConnectionId>,
+}
+
+/// Progress of the dial requests handled by the server.
+enum ServerEvent {
+    /// Dial request was read from the client.
+    RequestReceived {
+        peer: PeerId,
+        version: Version,
+        substream: Substream,
+        request: server::DialRequest,
+    },
+
+    /// Client sent the requested dial data.
+    DialDataReceived {
+        peer: PeerId,
+        version: Version,
+        substream: Substream,
+        nonce: u64,
+        addr_idx: u32,
+        address: Multiaddr,
+    },
+
+    /// Nonce was sent over the dial-back connection.
+    DialBackFinished {
+        peer: PeerId,
+        nonce: u64,
+        result: crate::Result<()>,
+    },
+
+    /// Dial-back didn't finish in time.
+    DialBackTimeout { peer: PeerId, nonce: u64 },
+
+    /// Failed to read from the client.
+    Failed { peer: PeerId, error: Error },
+}
+
+/// AutoNAT protocol.
+pub(crate) struct AutoNat {
+    /// Transport service of the dial request protocol.
+    service: TransportService,
+
+    /// Transport service of the dial-back protocol.
+    dial_back_service: TransportService,
+
+    /// TX channel for sending events to the user protocol.
+    event_tx: Sender<AutoNatEvent>,
+
+    /// RX channel for receiving commands from the user.
+    cmd_rx: Receiver<AutoNatCommand>,
+
+    /// Reachability of local addresses, shared with [`AutoNatHandle`].
+    reachability: Arc<RwLock<HashMap<Multiaddr, Reachability>>>,
+
+    /// Interval between reachability probes.
+    probe_interval: Duration,
+
+    /// Number of servers queried for each address during a probe.
+    servers_per_probe: usize,
+
+    /// Number of agreeing servers needed to conclude reachability of an address.
+    confirmations: usize,
+
+    /// Maximum number of bytes sent to a server requesting dial data.
+    max_dial_data: usize,
+
+    /// Whether the server side of the protocol is enabled.
+    server: bool,
+
+    /// Whether the server dials only globally routable addresses.
+    only_global_ips: bool,
+
+    /// Maximum number of dial requests the server handles concurrently.
+    max_concurrent_dial_backs: usize,
+
+    /// Maximum number of dial requests the server accepts from a peer within
+    /// `request_throttle_period`.
+    max_requests_per_peer: usize,
+
+    /// Period over which the server counts the dial requests of a peer.
+    request_throttle_period: Duration,
+
+    /// Connected peers and the endpoints of their connections.
+    peers: HashMap<PeerId, Endpoint>,
+
+    /// Peers which don't support the dial request protocol.
+    unsupported: HashSet<PeerId>,
+
+    /// Addresses waiting to be probed and the servers already asked to dial them.
+    probe_queue: VecDeque<(Multiaddr, HashSet<PeerId>)>,
+
+    /// Servers with a dial request in progress.
+    ///
+    /// Servers handle one request of a peer at a time so requests for different addresses are
+    /// sent to the same server one after another.
+    busy: HashSet<PeerId>,
+
+    /// Outbound dial request substreams which are being opened.
+    pending_outbound: HashMap<SubstreamId, (PeerId, Multiaddr)>,
+
+    /// Results received for each address during the current probe.
+    tallies: HashMap<Multiaddr, client::Tally>,
+
+    /// Nonces of pending dial requests and whether the dial-back was received.
+    nonces: Arc<Mutex<HashMap<u64, bool>>>,
+
+    /// Pending dial requests sent to servers.
+    pending_requests: FuturesStream<BoxFuture<'static, DialResult>>,
+
+    /// Pending dial-backs received from servers.
+    pending_dial_backs: FuturesStream<BoxFuture<'static, ()>>,
+
+    /// Peers whose dial request is being handled by the server.
+    active: HashSet<PeerId>,
+
+    /// When the recent dial requests of each peer were received.
+    request_history: HashMap<PeerId, VecDeque<Instant>>,
+
+    /// Dial-backs in progress, at most one per peer.
+    dial_backs: HashMap<PeerId, PendingDialBack>,
+
+    /// Outbound dial-back substreams which are being opened.
+    dial_back_substreams: HashMap<SubstreamId, PeerId>,
+
+    /// Pending server operations.
+    pending_server: FuturesStream<BoxFuture<'static, ServerEvent>>,
+
+    /// Pending responses sent to clients.
+    pending_responses: FuturesStream<BoxFuture<'static, ()>>,
+}
+
+impl AutoNat {
+    /// Create new [`AutoNat`] protocol.
+    pub(crate) fn new(
+        service: TransportService,
+        dial_back_service: TransportService,
+        config: Config,
+    ) -> Self {
+        Self {
+            service,
+            dial_back_service,
+            event_tx: config.event_tx,
+            cmd_rx: config.cmd_rx,
+            reachability: config.reachability,
+            probe_interval: config.probe_interval,
+            servers_per_probe: config.servers_per_probe,
+            confirmations: config.confirmations,
+            max_dial_data: config.max_dial_data,
+            server: config.server,
+            only_global_ips: config.only_global_ips,
+            max_concurrent_dial_backs: config.max_concurrent_dial_backs,
+            max_requests_per_peer: config.max_requests_per_peer,
+            request_throttle_period: config.request_throttle_period,
+            peers: HashMap::new(),
+            unsupported: HashSet::new(),
+            probe_queue: VecDeque::new(),
+            busy: HashSet::new(),
+            pending_outbound: HashMap::new(),
+            tallies: HashMap::new(),
+            nonces: Arc::new(Mutex::new(HashMap::new())),
+            pending_requests: FuturesStream::new(),
+            pending_dial_backs: FuturesStream::new(),
+            active: HashSet::new(),
+            request_history: HashMap::new(),
+            dial_backs: HashMap::new(),
+            dial_back_substreams: HashMap::new(),
+            pending_server: FuturesStream::new(),
+            pending_responses: FuturesStream::new(),
+        }
+    }
+
+    /// Connection established to remote peer.
+    fn on_connection_established(&mut self, peer: PeerId, endpoint: Endpoint) {
+        tracing::trace!(target: LOG_TARGET, ?peer, ?endpoint, "connection established");
+
+        self.peers.insert(peer, endpoint);
+        self.schedule_probes();
+    }
+
+    /// Connection closed to remote peer.
+    fn on_connection_closed(&mut self, peer: PeerId) {
+        tracing::trace!(target: LOG_TARGET, ?peer, "connection closed");
+
+        self.peers.remove(&peer);
+        self.unsupported.remove(&peer);
+        self.busy.remove(&peer);
+
+        if self.dial_backs.remove(&peer).is_some() {
+            self.active.remove(&peer);
+        }
+    }
+
+    /// Probe reachability of local addresses.
+    fn probe(&mut self) {
+        let addresses = self
+            .service
+            .listen_addresses()
+            .into_iter()
+            .chain(self.service.public_addresses().get_addresses())
+            .map(|address| strip_peer_id(&address))
+            .filter(|address| {
+                let local_peer_id = self.service.local_peer_id();
+                self.service
+                    .supported_transport(&address.clone().with(Protocol::P2p(local_peer_id.into())))
+            })
+            .collect::<HashSet<_>>();
+
+        tracing::trace!(target: LOG_TARGET, ?addresses, "probe reachability");
+
+        self.tallies = addresses
+            .iter()
+            .map(|address| (address.clone(), client::Tally::default()))
+            .collect();
+        self.probe_queue = addresses.into_iter().map(|address| (address, HashSet::new())).collect();
+        self.schedule_probes();
+    }
+
+    /// Send dial requests for the queued addresses to servers which don't have a request in
+    /// progress.
+    ///
+    /// Addresses stay queued until enough servers have been asked to dial them so servers which
+    /// connect after the probe started are asked too.
+    fn schedule_probes(&mut self) {
+        for (address, asked) in self.probe_queue.iter_mut() {
+            let available = self
+                .peers
+                .keys()
+                .filter(|peer| {
+                    !self.unsupported.contains(peer)
+                        && !self.busy.contains(peer)
+                        && !asked.contains(peer)
+                })
+                .copied()
+                .choose_multiple(
+                    &mut rand::thread_rng(),
+                    self.servers_per_probe.saturating_sub(asked.len()),
+                );
+
+            for peer in available {
+                asked.insert(peer);
+
+                match self.service.open_substream(peer) {
+                    Ok(substream_id) => {
+                        self.busy.insert(peer);
+                        self.pending_outbound.insert(substream_id, (peer, address.clone()));
+                    }
+                    Err(error) => tracing::debug!(
+                        target: LOG_TARGET,
+                        ?peer,
+                        ?error,
+                        "failed to open substream",
+                    ),
+                }
+            }
+        }
+
+        let servers_per_probe = self.servers_per_probe;
+        self.probe_queue.retain(|(_, asked)| asked.len() < servers_per_probe);
+    }
+
+    /// Outbound dial request substream opened.
+    fn on_outbound_substream(
+        &mut self,
+        substream_id: SubstreamId,
+        version: Version,
+        substream: Substream,
+    ) {
+        let Some((peer, address)) = self.pending_outbound.remove(&substream_id) else {
+            tracing::debug!(target: LOG_TARGET, ?substream_id, "unknown outbound substream");
+            return;
+        };
+
+        tracing::trace!(target: LOG_TARGET, ?peer, ?address, ?version, "send dial request");
+
+        match version {
+            Version::V2 => {
+                let nonce = rand::random::<u64>();
+                let max_dial_data = self.max_dial_data;
+                self.nonces.lock().insert(nonce, false);
+
+                self.pending_requests.push(Box::pin(async move {
+                    let result = match tokio::time::timeout(
+                        REQUEST_TIMEOUT,
+                        client::dial_request(substream, address.clone(), nonce, max_dial_data),
+                    )
+                    .await
+                    {
+                        Err(_) => Err(Error::Timeout),
+                        Ok(result) => result,
+                    };
+
+                    DialResult {
+                        peer,
+                        address,
+                        nonce: Some(nonce),
+                        result,
+                    }
+                }));
+            }
+            Version::V1 => {
+                let local_peer_id = self.service.local_peer_id();
+
+                self.pending_requests.push(Box::pin(async move {
+                    let result = match tokio::time::timeout(
+                        REQUEST_TIMEOUT,
+                        client::dial_request_v1(substream, local_peer_id, address.clone()),
+                    )
+                    .await
+                    {
+                        Err(_) => Err(Error::Timeout),
+                        Ok(result) => result,
+                    };
+
+                    DialResult {
+                        peer,
+                        address,
+                        nonce: None,
+                        result,
+                    }
+                }));
+            }
+        }
+    }
+
+    /// Failed to open outbound dial request substream.
+    fn on_substream_open_failure(&mut self, substream_id: SubstreamId, error: SubstreamError) {
+        let Some((peer, address)) = self.pending_outbound.remove(&substream_id) else {
+            return;
+        };
+
+        tracing::debug!(
+            target: LOG_TARGET,
+            ?peer,
+            ?address,
+            ?error,
+            "failed to open dial request substream",
+        );
+
+        if matches!(error, SubstreamError::NegotiationError(_)) {
+            self.unsupported.insert(peer);
+        }
+
+        self.busy.remove(&peer);
+        self.schedule_probes();
+    }
+
+    /// Dial request answered by a server.
+    async fn on_dial_result(&mut self, result: DialResult) {
+        let DialResult {
+            peer,
+            address,
+            nonce,
+            result,
+        } = result;
+
+        self.busy.remove(&peer);
+        self.schedule_probes();
+
+        let verified = nonce.map_or(true, |nonce| {
+            self.nonces.lock().remove(&nonce).unwrap_or(false)
+        });
+
+        let reachability = match result {
+            Ok(Reachability::Public) if !verified => {
+                tracing::debug!(
+                    target: LOG_TARGET,
+                    ?peer,
+                    ?address,
+                    "server reported successful dial without a dial-back",
+                );
+                Reachability::Unknown
+            }
+            Ok(reachability) => reachability,
+            Err(error) => {
+                tracing::debug!(
+                    target: LOG_TARGET,
+                    ?peer,
+                    ?address,
+                    ?error,
+                    "dial request failed",
+                );
+                Reachability::Unknown
+            }
+        };
+
+        tracing::trace!(target: LOG_TARGET, ?peer, ?address, ?reachability, "dial result");
+
+        let _ = self
+            .event_tx
+            .send(AutoNatEvent::DialResult {
+                server: peer,
+                address: address.clone(),
+                reachability,
+            })
+            .await;
+
+        let Some(tally) = self.tallies.get_mut(&address) else {
+            return;
+        };
+
+        if let Some(reachability) = tally.record(reachability, self.confirmations) {
+            self.on_reachability(address, reachability).await;
+        }
+    }
+
+    /// Reachability of a local address concluded.
+    async fn on_reachability(&mut self, address: Multiaddr, reachability: Reachability) {
+        if self.reachability.write().insert(address.clone(), reachability) == Some(reachability) {
+            return;
+        }
+
+        tracing::debug!(target: LOG_TARGET, ?address, ?reachability, "reachability changed");
+
+        let public_addresses = self.service.public_addresses();
+        match reachability {
+            Reachability::Public => {
+                let _ = public_addresses.add_address(address.clone());
+            }
+            Reachability::Private => {
+                public_addresses.remove_address(
+                    &address.clone().with(Protocol::P2p(self.service.local_peer_id().into())),
+                );
+            }
+            Reachability::Unknown => {}
+        }
+
+        let _ = self
+            .event_tx
+            .send(AutoNatEvent::ReachabilityChanged {
+                address,
+                reachability,
+            })
+            .await;
+    }
+
+    /// Inbound dial-back substream opened by a server.
+    fn on_dial_back_substream(&mut self, peer: PeerId, substream: Substream) {
+        tracing::trace!(target: LOG_TARGET, ?peer, "dial-back received");
+
+        let nonces = Arc::clone(&self.nonces);
+        self.pending_dial_backs.push(Box::pin(async move {
+            match tokio::time::timeout(READ_TIMEOUT, client::dial_back(substream, nonces)).await {
+                Err(_) => tracing::debug!(target: LOG_TARGET, ?peer, "dial-back timed out"),
+                Ok(Err(error)) =>
+                    tracing::debug!(target: LOG_TARGET, ?peer, ?error, "invalid dial-back"),
+                Ok(Ok(())) => {}
+            }
+        }));
+    }
+
+    /// Check if the server accepts a new dial request from `peer`.
+    fn accept_request(&mut self, peer: PeerId) -> bool {
+        if self.active.contains(&peer) || self.active.len() >= self.max_concurrent_dial_backs {
+            return false;
+        }
+
+        let now = Instant::now();
+        let period = self.request_throttle_period;
+
+        self.request_history.retain(|_, history| {
+            while history.front().is_some_and(|received| now.duration_since(*received) > period) {
+                history.pop_front();
+            }
+
+            !history.is_empty()
+        });
+
+        let history = self.request_history.entry(peer).or_default();
+        if history.len() >= self.max_requests_per_peer {
+            return false;
+        }
+        history.push_back(now);
+
+        true
+    }
+
+    /// Inbound dial request substream opened by a client.
+    fn on_inbound_substream(&mut self, peer: PeerId, version: Version, mut substream: Substream) {
+        if !self.server {
+            tracing::trace!(target: LOG_TARGET, ?peer, "server disabled, ignore dial request");
+            return;
+        }
+
+        if !self.accept_request(peer) {
+            tracing::debug!(target: LOG_TARGET, ?peer, "reject dial request");
+
+            self.respond(substream, version, server::Response::Rejected);
+            return;
+        }
+
+        self.active.insert(peer);
+        self.pending_server.push(Box::pin(async move {
+            match tokio::time::timeout(
+                READ_TIMEOUT,
+                server::read_request(&mut substream, peer, version),
+            )
+            .await
+            {
+                Err(_) => ServerEvent::Failed {
+                    peer,
+                    error: Error::Timeout,
+                },
+                Ok(Err(error)) => ServerEvent::Failed { peer, error },
+                Ok(Ok(request)) => ServerEvent::RequestReceived {
+                    peer,
+                    version,
+                    substream,
+                    request,
+                },
+            }
+        }));
+    }
+
+    /// Send `response` to the client.
+    fn respond(&mut self, substream: Substream, version: Version, response: server::Response) {
+        self.pending_responses.push(Box::pin(async move {
+            if let Err(error) = server::send_response(substream, version, response).await {
+                tracing::debug!(target: LOG_TARGET, ?error, "failed to send dial response");
+            }
+        }));
+    }
+
+    /// Dial request read from a client.
+    fn on_request_received(
+        &mut self,
+        peer: PeerId,
+        version: Version,
+        mut substream: Substream,
+        request: server::DialRequest,
+    ) {
+        let observed = self.peers.get(&peer).and_then(|endpoint| ip_address(endpoint.address()));
+
+        let selected = server::select_request_address(
+            version,
+            &request.addresses,
+            self.only_global_ips,
+            observed,
+            |address| {
+                self.service
+                    .supported_transport(&address.clone().with(Protocol::P2p(peer.into())))
+            },
+        );
+
+        let Some((addr_idx, address)) = selected else {
+            tracing::debug!(target: LOG_TARGET, ?peer, "no dialable address in dial request");
+
+            self.active.remove(&peer);
+            self.respond(substream, version, server::Response::Refused);
+            return;
+        };
+
+        if observed.is_some() && ip_address(&address) == observed {
+            return self.dial_back(peer, version, substream, request.nonce, addr_idx, address);
+        }
+
+        let num_bytes = rand::thread_rng().gen_range(MIN_DIAL_DATA..=MAX_DIAL_DATA);
+        let nonce = request.nonce;
+
+        tracing::trace!(target: LOG_TARGET, ?peer, ?address, ?num_bytes, "request dial data");
+
+        self.pending_server.push(Box::pin(async move {
+            match tokio::time::timeout(
+                READ_TIMEOUT,
+                server::request_dial_data(&mut substream, addr_idx, num_bytes),
+            )
+            .await
+            {
+                Err(_) => ServerEvent::Failed {
+                    peer,
+                    error: Error::Timeout,
+                },
+                Ok(Err(error)) => ServerEvent::Failed { peer, error },
+                Ok(Ok(())) => ServerEvent::DialDataReceived {
+                    peer,
+                    version,
+                    substream,
+                    nonce,
+                    addr_idx,
+                    address,
+                },
+            }
+        }));
+    }
+
+    /// Dial `address` of `peer` over a new connection.
+    fn dial_back(
+        &mut self,
+        peer: PeerId,
+        version: Version,
+        substream: Substream,
+        nonce: u64,
+        addr_idx: u32,
+        address: Multiaddr,
+    ) {
+        tracing::trace!(target: LOG_TARGET, ?peer, ?address, "dial back");
+
+        if let Err(error) = self
+            .dial_back_service
+            .dial_secondary_address(address.clone().with(Protocol::P2p(peer.into())))
+        {
+            tracing::debug!(target: LOG_TARGET, ?peer, ?address, ?error, "failed to dial back");
+
+            self.active.remove(&peer);
+            self.respond(substream, version, server::Response::Refused);
+            return;
+        }
+
+        self.dial_backs.insert(
+            peer,
+            PendingDialBack {
+                version,
+                substream,
+                nonce,
+                addr_idx,
+                address,
+                connection_id: None,
+            },
+        );
+        self.pending_server.push(Box::pin(async move {
+            tokio::time::sleep(DIAL_BACK_TIMEOUT).await;
+            ServerEvent::DialBackTimeout { peer, nonce }
+        }));
+    }
+
+    /// Finish the dial-back of `peer` and send the result to the client.
+    fn finish_dial_back(&mut self, peer: PeerId, status: server::DialStatus) {
+        let Some(dial_back) = self.dial_backs.remove(&peer) else {
+            return;
+        };
+
+        tracing::trace!(
+            target: LOG_TARGET,
+            ?peer,
+            address = ?dial_back.address,
+            ?status,
+            "dial-back finished",
+        );
+
+        // the dial-back connection is no longer needed and keeping it open would prevent
+        // dialing the peer back for its next request
+        if let Some(connection_id) = dial_back.connection_id {
+            let _ = self.dial_back_service.close_connection(peer, connection_id);
+        }
+
+        self.active.remove(&peer);
+        self.respond(
+            dial_back.substream,
+            dial_back.version,
+            server::Response::Dialed {
+                addr_idx: dial_back.addr_idx,
+                address: dial_back.address,
+                status,
+            },
+        );
+    }
+
+    /// Handle progress of a dial request handled by the server.
+    fn on_server_event(&mut self, event: ServerEvent) {
+        match event {
+            ServerEvent::RequestReceived {
+                peer,
+                version,
+                substream,
+                request,
+            } => self.on_request_received(peer, version, substream, request),
+            ServerEvent::DialDataReceived {
+                peer,
+                version,
+                substream,
+                nonce,
+                addr_idx,
+                address,
+            } => self.dial_back(peer, version, substream, nonce, addr_idx, address),
+            ServerEvent::DialBackFinished {
+                peer,
+                nonce,
+                result,
+            } =>
+                if self.dial_backs.get(&peer).is_some_and(|dial_back| dial_back.nonce == nonce) {
+                    let status = match result {
+                        Ok(()) => server::DialStatus::Ok,
+                        Err(error) => {
+                            tracing::debug!(target: LOG_TARGET, ?peer, ?error, "dial-back failed");
+                            server::DialStatus::EDialBackError
+                        }
+                    };
+
+                    self.finish_dial_back(peer, status);
+                },
+            ServerEvent::DialBackTimeout { peer, nonce } => {
+                if self.dial_backs.get(&peer).is_some_and(|dial_back| dial_back.nonce == nonce) {
+                    self.finish_dial_back(peer, server::DialStatus::EDialError);
+                }
+            }
+            ServerEvent::Failed { peer, error } => {
+                tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to handle dial request");
+
+                self.active.remove(&peer);
+            }
+        }
+    }
+
+    /// Handle event received from the dial-back transport service.
+    fn on_dial_back_event(&mut self, event: TransportEvent) {
+        match event {
+            TransportEvent::ConnectionEstablished {
+                peer,
+                endpoint:
+                    Endpoint::Dialer {
+                        address,
+                        connection_id,
+                    },
+            } => {
+                let Some(dial_back) = self.dial_backs.get_mut(&peer) else {
+                    return;
+                };
+
+                if strip_peer_id(&address) != dial_back.address {
+                    return;
+                }
+                dial_back.connection_id = Some(connection_id);
+
+                match dial_back.version {
+                    Version::V1 => self.finish_dial_back(peer, server::DialStatus::Ok),
+                    Version::V2 => match self
+                        .dial_back_service
+                        .open_substream_on_connection(peer, connection_id)
+                    {
+                        Ok(substream_id) => {
+                            self.dial_back_substreams.insert(substream_id, peer);
+                        }
+                        Err(error) => {
+                            tracing::debug!(
+                                target: LOG_TARGET,
+                                ?peer,
+                                ?error,
+                                "failed to open dial-back substream",
+                            );
+                            self.finish_dial_back(peer, server::DialStatus::EDialBackError);
+                        }
+                    },
+                }
+            }
+            TransportEvent::DialFailure { peer, addresses } => {
+                let failed = self.dial_backs.get(&peer).is_some_and(|dial_back| {
+                    addresses.iter().any(|address| strip_peer_id(address) == dial_back.address)
+                });
+
+                if failed {
+                    self.finish_dial_back(peer, server::DialStatus::EDialError);
+                }
+            }
+            TransportEvent::SubstreamOpened {
+                peer,
+                direction,
+                substream,
+                ..
+            } => match direction {
+                Direction::Inbound => self.on_dial_back_substream(peer, substream),
+                Direction::Outbound(substream_id) => {
+                    if self.dial_back_substreams.remove(&substream_id).is_none() {
+                        return;
+                    }
+
+                    let Some(nonce) = self.dial_backs.get(&peer).map(|dial_back| dial_back.nonce)
+                    else {
+                        return;
+                    };
+
+                    self.pending_server.push(Box::pin(async move {
+                        let result = match tokio::time::timeout(
+                            READ_TIMEOUT,
+                            server::dial_back(substream, nonce),
+                        )
+                        .await
+                        {
+                            Err(_) => Err(Error::Timeout),
+                            Ok(result) => result,
+                        };
+
+                        ServerEvent::DialBackFinished {
+                            peer,
+                            nonce,
+                            result,
+                        }
+                    }));
+                }
+            },
+            TransportEvent::SubstreamOpenFailure { substream, error } => {
+                if let Some(peer) = self.dial_back_substreams.remove(&substream) {
+                    tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to open dial-back substream");
+                    self.finish_dial_back(peer, server::DialStatus::EDialBackError);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Start [`AutoNat`] event loop.
+    pub async fn run(mut self) {
+        tracing::debug!(target: LOG_TARGET, server = ?self.server, "starting autonat event loop");
+
+        let mut probe_interval = tokio::time::interval_at(
+            tokio::time::Instant::now() + self.probe_interval,
+            self.probe_interval,
+        );
+        probe_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                event = self.service.next() => match event {
+                    None => {
+                        tracing::warn!(target: LOG_TARGET, "transport service stream ended, terminating autonat event loop");
+                        return
+                    },
+                    Some(TransportEvent::ConnectionEstablished { peer, endpoint }) => {
+                        self.on_connection_established(peer, endpoint);
+                    }
+                    Some(TransportEvent::ConnectionClosed { peer }) => {
+                        self.on_connection_closed(peer);
+                    }
+                    Some(TransportEvent::SubstreamOpened {
+                        peer,
+                        fallback,
+                        direction,
+                        substream,
+                        ..
+                    }) => match direction {
+                        Direction::Inbound =>
+                            self.on_inbound_substream(peer, Version::from_fallback(&fallback), substream),
+                        Direction::Outbound(substream_id) =>
+                            self.on_outbound_substream(substream_id, Version::from_fallback(&fallback), substream),
+                    },
+                    Some(TransportEvent::SubstreamOpenFailure { substream, error }) => {
+                        self.on_substream_open_failure(substream, error);
+                    }
+                    Some(TransportEvent::DialFailure { .. }) => {}
+                },
+                event = self.dial_back_service.next() => match event {
+                    None => {
+                        tracing::warn!(target: LOG_TARGET, "dial-back transport service stream ended, terminating autonat event loop");
+                        return
+                    },
+                    Some(event) => self.on_dial_back_event(event),
+                },
+                Some(command) = self.cmd_rx.recv() => match command {
+                    AutoNatCommand::Probe => self.probe(),
+                },
+                _ = probe_interval.tick() => self.probe(),
+                Some(result) = self.pending_requests.next() => self.on_dial_result(result).await,
+                Some(event) = self.pending_server.next() => self.on_server_event(event),
+                _ = self.pending_dial_backs.next() => {}
+                _ = self.pending_responses.next() => {}
+            }
+        }
+    }
+}
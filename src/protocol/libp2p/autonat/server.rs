@@ -0,0 +1,358 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Server side of AutoNAT: dial requests received from clients and dial-backs performed for
+//! them.
+
+use crate::{
+    error::Error,
+    protocol::libp2p::autonat::{read_message, schema, send_message, strip_peer_id, Version},
+    substream::Substream,
+    transport::common::ip::{ip_address, is_global},
+    PeerId,
+};
+
+use multiaddr::Multiaddr;
+
+use std::net::IpAddr;
+
+pub(super) use schema::v2::DialStatus;
+
+/// Maximum size of a dial data chunk accepted from the client.
+const MAX_DIAL_DATA_CHUNK_SIZE: usize = 4096;
+
+/// Dial request received from a client.
+#[derive(Debug)]
+pub(super) struct DialRequest {
+    /// Addresses the client wants to be dialed on, in order of preference.
+    pub(super) addresses: Vec<Vec<u8>>,
+
+    /// Nonce sent back to the client over the dial-back connection.
+    pub(super) nonce: u64,
+}
+
+/// Response sent to a client.
+#[derive(Debug)]
+pub(super) enum Response {
+    /// Request was rejected because of rate limits.
+    Rejected,
+
+    /// None of the addresses can be dialed.
+    Refused,
+
+    /// An address was dialed.
+    Dialed {
+        /// Index of the dialed address in the request.
+        addr_idx: u32,
+
+        /// Dialed address.
+        address: Multiaddr,
+
+        /// Result of the dial.
+        status: DialStatus,
+    },
+}
+
+/// Select the first address of `addresses` that the server is willing to dial.
+///
+/// Only IP addresses are accepted. If `only_global_ips` is set, the address must be globally
+/// routable and if `observed` is set, the address must use that IP.
+pub(super) fn select_address(
+    addresses: &[Vec<u8>],
+    only_global_ips: bool,
+    observed: Option<IpAddr>,
+    supported_transport: impl Fn(&Multiaddr) -> bool,
+) -> Option<(u32, Multiaddr)> {
+    addresses.iter().enumerate().find_map(|(index, address)| {
+        let address = strip_peer_id(&Multiaddr::try_from(address.clone()).ok()?);
+        let ip = ip_address(&address)?;
+
+        if only_global_ips && !is_global(ip) {
+            return None;
+        }
+
+        if observed.is_some_and(|observed| observed != ip) {
+            return None;
+        }
+
+        supported_transport(&address).then_some((u32::try_from(index).ok()?, address))
+    })
+}
+
+/// Select the address of a dial request of `version` that the server dials.
+///
+/// AutoNAT v1 has no dial data step, so only the observed IP of the client is dialed and requests
+/// of clients without an observed IP, such as relayed clients, are refused.
+pub(super) fn select_request_address(
+    version: Version,
+    addresses: &[Vec<u8>],
+    only_global_ips: bool,
+    observed: Option<IpAddr>,
+    supported_transport: impl Fn(&Multiaddr) -> bool,
+) -> Option<(u32, Multiaddr)> {
+    let observed = match version {
+        Version::V1 => Some(observed?),
+        Version::V2 => None,
+    };
+
+    select_address(addresses, only_global_ips, observed, supported_transport)
+}
+
+/// Read dial request of `peer`.
+pub(super) async fn read_request(
+    substream: &mut Substream,
+    peer: PeerId,
+    version: Version,
+) -> crate::Result<DialRequest> {
+    match version {
+        Version::V2 => {
+            use schema::v2::{message::Msg, Message};
+
+            match read_message::<Message>(substream).await?.msg {
+                Some(Msg::DialRequest(request)) => Ok(DialRequest {
+                    addresses: request.addrs,
+                    nonce: request.nonce,
+                }),
+                _ => Err(Error::InvalidData),
+            }
+        }
+        Version::V1 => {
+            use schema::v1::{message::MessageType, Message};
+
+            let message = read_message::<Message>(substream).await?;
+
+            if message.r#type() != MessageType::Dial {
+                return Err(Error::InvalidData);
+            }
+
+            let info = message.dial.and_then(|dial| dial.peer).ok_or(Error::InvalidData)?;
+
+            if info.id.as_ref().is_some_and(|id| id != &peer.to_bytes()) {
+                return Err(Error::InvalidData);
+            }
+
+            Ok(DialRequest {
+                addresses: info.addrs,
+                nonce: rand::random(),
+            })
+        }
+    }
+}
+
+/// Request `num_bytes` of dial data for the address at `addr_idx` and read it.
+pub(super) async fn request_dial_data(
+    substream: &mut Substream,
+    addr_idx: u32,
+    num_bytes: u64,
+) -> crate::Result<()> {
+    use schema::v2::{message::Msg, DialDataRequest, Message};
+
+    send_message(
+        substream,
+        &Message {
+            msg: Some(Msg::DialDataRequest(DialDataRequest {
+                addr_idx,
+                num_bytes,
+            })),
+        },
+    )
+    .await?;
+
+    let mut received = 0u64;
+
+    while received < num_bytes {
+        match read_message::<Message>(substream).await?.msg {
+            Some(Msg::DialDataResponse(response))
+                if response.data.len() <= MAX_DIAL_DATA_CHUNK_SIZE =>
+                received += response.data.len() as u64,
+            _ => return Err(Error::InvalidData),
+        }
+    }
+
+    Ok(())
+}
+
+/// Send `nonce` to the client over the dial-back connection.
+pub(super) async fn dial_back(mut substream: Substream, nonce: u64) -> crate::Result<()> {
+    use schema::v2::{dial_back_response::DialBackStatus, DialBack, DialBackResponse};
+
+    send_message(&mut substream, &DialBack { nonce }).await?;
+
+    let response = read_message::<DialBackResponse>(&mut substream).await?;
+    let _ = substream.close().await;
+
+    match response.status() {
+        DialBackStatus::Ok => Ok(()),
+    }
+}
+
+/// Send `response` to the client and close the substream.
+pub(super) async fn send_response(
+    mut substream: Substream,
+    version: Version,
+    response: Response,
+) -> crate::Result<()> {
+    match version {
+        Version::V2 => {
+            use schema::v2::{dial_response::ResponseStatus, message::Msg, Message};
+
+            let (status, addr_idx, dial_status) = match response {
+                Response::Rejected => (ResponseStatus::ERequestRejected, 0, DialStatus::Unused),
+                Response::Refused => (ResponseStatus::EDialRefused, 0, DialStatus::Unused),
+                Response::Dialed {
+                    addr_idx, status, ..
+                } => (ResponseStatus::Ok, addr_idx, status),
+            };
+
+            send_message(
+                &mut substream,
+                &Message {
+                    msg: Some(Msg::DialResponse(schema::v2::DialResponse {
+                        status: status as i32,
+                        addr_idx,
+                        dial_status: dial_status as i32,
+                    })),
+                },
+            )
+            .await?;
+        }
+        Version::V1 => {
+            use schema::v1::{
+                message::{DialResponse, MessageType, ResponseStatus},
+                Message,
+            };
+
+            let (status, addr) = match response {
+                Response::Rejected | Response::Refused => (ResponseStatus::EDialRefused, None),
+                Response::Dialed {
+                    address,
+                    status: DialStatus::Ok,
+                    ..
+                } => (ResponseStatus::Ok, Some(address.to_vec())),
+                Response::Dialed { .. } => (ResponseStatus::EDialError, None),
+            };
+
+            send_message(
+                &mut substream,
+                &Message {
+                    r#type: Some(MessageType::DialResponse as i32),
+                    dial: None,
+                    dial_response: Some(DialResponse {
+                        status: Some(status as i32),
+                        status_text: None,
+                        addr,
+                    }),
+                },
+            )
+            .await?;
+        }
+    }
+
+    let _ = substream.close().await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use multiaddr::Protocol;
+
+    #[test]
+    fn address_selection() {
+        let addresses = [
+            "/dns/example.com/tcp/30333",
+            "/ip4/127.0.0.1/tcp/30333",
+            "/ip4/1.1.1.1/udp/30333/quic-v1",
+            "/ip4/1.1.1.1/tcp/30333",
+            "/ip4/8.8.8.8/tcp/30333",
+        ]
+        .into_iter()
+        .map(|address| address.parse::<Multiaddr>().unwrap().to_vec())
+        .chain(std::iter::once(vec![0xff, 0xff]))
+        .collect::<Vec<_>>();
+
+        let tcp_only = |address: &Multiaddr| {
+            address.iter().any(|protocol| matches!(protocol, Protocol::Tcp(_)))
+        };
+
+        // the loopback address is skipped
+        assert_eq!(
+            select_address(&addresses, true, None, tcp_only),
+            Some((3, "/ip4/1.1.1.1/tcp/30333".parse().unwrap()))
+        );
+        assert_eq!(
+            select_address(&addresses, false, None, tcp_only),
+            Some((1, "/ip4/127.0.0.1/tcp/30333".parse().unwrap()))
+        );
+
+        // the address must match the observed IP
+        assert_eq!(
+            select_address(&addresses, true, Some("8.8.8.8".parse().unwrap()), tcp_only),
+            Some((4, "/ip4/8.8.8.8/tcp/30333".parse().unwrap()))
+        );
+        assert_eq!(
+            select_address(&addresses, true, Some("9.9.9.9".parse().unwrap()), tcp_only),
+            None
+        );
+    }
+
+    #[test]
+    fn v1_request_without_observed_ip_is_refused() {
+        let addresses = ["/ip4/1.1.1.1/tcp/30333", "/ip4/8.8.8.8/tcp/30333"]
+            .into_iter()
+            .map(|address| address.parse::<Multiaddr>().unwrap().to_vec())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            select_request_address(Version::V1, &addresses, true, None, |_| true),
+            None
+        );
+        assert_eq!(
+            select_request_address(
+                Version::V1,
+                &addresses,
+                true,
+                Some("8.8.8.8".parse().unwrap()),
+                |_| true
+            ),
+            Some((1, "/ip4/8.8.8.8/tcp/30333".parse().unwrap()))
+        );
+
+        // v2 clients prove their address with dial data instead
+        assert_eq!(
+            select_request_address(Version::V2, &addresses, true, None, |_| true),
+            Some((0, "/ip4/1.1.1.1/tcp/30333".parse().unwrap()))
+        );
+    }
+
+    #[test]
+    fn peer_id_is_stripped() {
+        let peer = PeerId::random();
+        let address = "/ip4/1.1.1.1/tcp/30333"
+            .parse::<Multiaddr>()
+            .unwrap()
+            .with(Protocol::P2p(peer.into()));
+
+        assert_eq!(
+            select_address(&[address.to_vec()], true, None, |_| true),
+            Some((0, "/ip4/1.1.1.1/tcp/30333".parse().unwrap()))
+        );
+    }
+}
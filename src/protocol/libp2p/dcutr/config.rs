@@ -0,0 +1,105 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use crate::{
+    codec::ProtocolCodec, protocol::libp2p::dcutr::DcutrEvent, types::protocol::ProtocolName,
+    DEFAULT_CHANNEL_SIZE,
+};
+
+use futures::Stream;
+use tokio::sync::mpsc::{channel, Sender};
+use tokio_stream::wrappers::ReceiverStream;
+
+/// DCUtR protocol name.
+pub const PROTOCOL_NAME: &str = "/libp2p/dcutr";
+
+/// Maximum size for DCUtR messages.
+const MAX_PAYLOAD_SIZE: usize = 4096;
+
+/// Default maximum number of hole punching attempts made for a relayed connection.
+const MAX_ATTEMPTS: usize = 3;
+
+/// DCUtR configuration.
+pub struct Config {
+    /// Protocol name.
+    pub(crate) protocol: ProtocolName,
+
+    /// Codec used by the protocol.
+    pub(crate) codec: ProtocolCodec,
+
+    /// Maximum number of hole punching attempts made for a relayed connection.
+    pub(super) max_attempts: usize,
+
+    /// TX channel for sending events to the user protocol.
+    pub(super) tx_event: Sender<DcutrEvent>,
+}
+
+impl Config {
+    /// Create new [`Config`] with default values.
+    ///
+    /// Returns a config that is given to `Litep2pConfig` and an event stream for
+    /// [`DcutrEvent`]s.
+    pub fn default() -> (Self, Box<dyn Stream<Item = DcutrEvent> + Send + Unpin>) {
+        ConfigBuilder::new().build()
+    }
+}
+
+/// DCUtR configuration builder.
+pub struct ConfigBuilder {
+    /// Maximum number of hole punching attempts made for a relayed connection.
+    max_attempts: usize,
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigBuilder {
+    /// Create new default [`Config`] which can be modified by the user.
+    pub fn new() -> Self {
+        Self {
+            max_attempts: MAX_ATTEMPTS,
+        }
+    }
+
+    /// Set maximum number of hole punching attempts made for a relayed connection before the
+    /// upgrade is considered failed.
+    pub fn with_max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Build [`Config`].
+    pub fn build(self) -> (Config, Box<dyn Stream<Item = DcutrEvent> + Send + Unpin>) {
+        let (tx_event, rx_event) = channel(DEFAULT_CHANNEL_SIZE);
+
+        (
+            Config {
+                protocol: ProtocolName::from(PROTOCOL_NAME),
+                codec: ProtocolCodec::UnsignedVarint(Some(MAX_PAYLOAD_SIZE)),
+                max_attempts: self.max_attempts,
+                tx_event,
+            },
+            Box::new(ReceiverStream::new(rx_event)),
+        )
+    }
+}
@@ -0,0 +1,527 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! [`DCUtR`](https://github.com/libp2p/specs/blob/master/relay/DCUtR.md) implementation.
+//!
+//! Upgrades relayed connections to direct connections through hole punching. The peer which
+//! accepted the relayed connection initiates the upgrade by sending its addresses to the remote
+//! peer over the relayed connection. The remote peer answers with its own addresses after which
+//! the initiator measures the round-trip time and tells the remote peer to start dialing. The
+//! remote peer dials right away and the initiator dials half a round-trip time later so that the
+//! dials of both peers cross each other. All exchanged addresses are dialed at the same moment.
+//! TCP dials of the initiator are negotiated as the listener of the resulting simultaneous open.
+//! Instead of dialing the QUIC addresses of the remote peer, the initiator sends them random UDP
+//! packets to open holes in the NATs for the dials of the remote peer.
+//!
+//! Once the direct connection is established, it replaces the relayed connection as the primary
+//! connection of the peer.
+
+use crate::{
+    config::Role,
+    error::DcutrError,
+    protocol::{Direction, TransportEvent, TransportService},
+    substream::Substream,
+    transport::Endpoint,
+    types::SubstreamId,
+    utils::futures_stream::FuturesStream,
+    PeerId,
+};
+
+use futures::{future::BoxFuture, StreamExt};
+use multiaddr::{Multiaddr, Protocol};
+use prost::Message;
+use tokio::{sync::mpsc::Sender, time::Instant};
+
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+pub use config::{Config, ConfigBuilder, PROTOCOL_NAME};
+
+mod config;
+
+mod schema {
+    include!(concat!(env!("OUT_DIR"), "/dcutr.rs"));
+}
+
+/// Log target for the file.
+const LOG_TARGET: &str = "litep2p::dcutr";
+
+/// Timeout for exchanging the addresses with the remote peer.
+const EXCHANGE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long the direct connection is waited for after the peers have started dialing.
+const UPGRADE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Events emitted by the DCUtR protocol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DcutrEvent {
+    /// Relayed connection to the peer was upgraded to a direct connection.
+    ConnectionUpgraded {
+        /// Peer ID.
+        peer: PeerId,
+
+        /// Address of the direct connection.
+        address: Multiaddr,
+    },
+
+    /// The relayed connection initiated by the peer couldn't be upgraded to a direct connection.
+    ///
+    /// Only reported by the initiator of the upgrade, after all attempts have failed.
+    UpgradeFailed {
+        /// Peer ID.
+        peer: PeerId,
+
+        /// Error of the last attempt.
+        error: DcutrError,
+    },
+}
+
+/// Read the next message from `substream`, checking that it's of type `expected`.
+async fn read_message(
+    substream: &mut Substream,
+    expected: schema::hole_punch::Type,
+) -> Result<Vec<Multiaddr>, DcutrError> {
+    let payload = match substream.next().await {
+        None | Some(Err(_)) => return Err(DcutrError::SubstreamClosed),
+        Some(Ok(payload)) => payload,
+    };
+    let message =
+        schema::HolePunch::decode(payload.freeze()).map_err(|_| DcutrError::ProtocolViolation)?;
+
+    if message.r#type != expected as i32 {
+        return Err(DcutrError::ProtocolViolation);
+    }
+
+    Ok(message
+        .obs_addrs
+        .into_iter()
+        .filter_map(|address| Multiaddr::try_from(address).ok())
+        .collect())
+}
+
+/// Send message of type `message_type` with `addresses` over `substream`.
+async fn send_message(
+    substream: &mut Substream,
+    message_type: schema::hole_punch::Type,
+    addresses: &[Multiaddr],
+) -> Result<(), DcutrError> {
+    let message = schema::HolePunch {
+        r#type: message_type as i32,
+        obs_addrs: addresses.iter().map(|address| address.to_vec()).collect(),
+    };
+
+    substream
+        .send_framed(message.encode_to_vec().into())
+        .await
+        .map_err(|_| DcutrError::SubstreamClosed)
+}
+
+/// Exchange addresses with the remote peer as the initiator of the upgrade.
+///
+/// Returns the addresses of the remote peer once it's time to dial them.
+async fn initiate(
+    mut substream: Substream,
+    addresses: Vec<Multiaddr>,
+) -> Result<Vec<Multiaddr>, DcutrError> {
+    use schema::hole_punch::Type;
+
+    let remote_addresses = tokio::time::timeout(EXCHANGE_TIMEOUT, async {
+        send_message(&mut substream, Type::Connect, &addresses).await?;
+        let started = Instant::now();
+        let remote_addresses = read_message(&mut substream, Type::Connect).await?;
+        let rtt = started.elapsed();
+        send_message(&mut substream, Type::Sync, &[]).await?;
+
+        tracing::trace!(target: LOG_TARGET, ?rtt, ?remote_addresses, "addresses exchanged");
+
+        Ok::<_, DcutrError>((remote_addresses, rtt))
+    })
+    .await
+    .map_err(|_| DcutrError::Timeout)?;
+
+    let (remote_addresses, rtt) = remote_addresses?;
+    substream.close().await;
+
+    // the remote peer dials as soon as it receives the sync message
+    tokio::time::sleep(rtt / 2).await;
+
+    Ok(remote_addresses)
+}
+
+/// Exchange addresses with the initiator of the upgrade.
+///
+/// Returns the addresses of the initiator once it's time to dial them.
+async fn respond(
+    mut substream: Substream,
+    addresses: Vec<Multiaddr>,
+) -> Result<Vec<Multiaddr>, DcutrError> {
+    use schema::hole_punch::Type;
+
+    tokio::time::timeout(EXCHANGE_TIMEOUT, async {
+        let remote_addresses = read_message(&mut substream, Type::Connect).await?;
+        send_message(&mut substream, Type::Connect, &addresses).await?;
+        read_message(&mut substream, Type::Sync).await?;
+
+        Ok(remote_addresses)
+    })
+    .await
+    .map_err(|_| DcutrError::Timeout)?
+}
+
+/// Upgrade of a relayed connection in progress.
+struct Upgrade {
+    /// Current attempt.
+    attempt: usize,
+
+    /// Whether the local node initiated the upgrade.
+    initiator: bool,
+}
+
+/// DCUtR protocol.
+pub(crate) struct Dcutr {
+    /// Transport service.
+    service: TransportService,
+
+    /// TX channel for sending events to the user protocol.
+    tx: Sender<DcutrEvent>,
+
+    /// Maximum number of hole punching attempts made for a relayed connection.
+    max_attempts: usize,
+
+    /// Peers with a direct connection.
+    direct: HashSet<PeerId>,
+
+    /// Upgrades in progress.
+    upgrades: HashMap<PeerId, Upgrade>,
+
+    /// Upgrades waiting for the substream to open.
+    pending_outbound: HashMap<SubstreamId, PeerId>,
+
+    /// Pending address exchanges and the role the local node dials in.
+    pending_exchanges:
+        FuturesStream<BoxFuture<'static, (PeerId, Role, Result<Vec<Multiaddr>, DcutrError>)>>,
+
+    /// Pending upgrade timeouts.
+    timeouts: FuturesStream<BoxFuture<'static, (PeerId, usize)>>,
+}
+
+impl Dcutr {
+    /// Create new [`Dcutr`] protocol.
+    pub(crate) fn new(service: TransportService, config: Config) -> Self {
+        Self {
+            service,
+            tx: config.tx_event,
+            max_attempts: config.max_attempts,
+            direct: HashSet::new(),
+            upgrades: HashMap::new(),
+            pending_outbound: HashMap::new(),
+            pending_exchanges: FuturesStream::new(),
+            timeouts: FuturesStream::new(),
+        }
+    }
+
+    /// Addresses of the local node advertised to the remote peer.
+    fn local_addresses(&self) -> Vec<Multiaddr> {
+        let mut seen = HashSet::new();
+
+        self.service
+            .public_addresses()
+            .get_addresses()
+            .into_iter()
+            .chain(self.service.listen_addresses())
+            .filter(|address| {
+                !address.iter().any(|protocol| std::matches!(protocol, Protocol::P2pCircuit))
+            })
+            .map(|mut address| {
+                if std::matches!(address.iter().last(), Some(Protocol::P2p(_))) {
+                    address.pop();
+                }
+                address
+            })
+            .filter(|address| seen.insert(address.clone()))
+            .collect()
+    }
+
+    /// Connection established to remote peer.
+    async fn on_connection_established(&mut self, peer: PeerId, endpoint: Endpoint) {
+        tracing::trace!(target: LOG_TARGET, ?peer, ?endpoint, "connection established");
+
+        if !endpoint.is_relayed() {
+            self.direct.insert(peer);
+
+            if self.upgrades.remove(&peer).is_some() {
+                tracing::debug!(target: LOG_TARGET, ?peer, ?endpoint, "connection upgraded");
+
+                let _ = self
+                    .tx
+                    .send(DcutrEvent::ConnectionUpgraded {
+                        peer,
+                        address: endpoint.address().clone(),
+                    })
+                    .await;
+            }
+
+            return;
+        }
+
+        // the peer which accepted the relayed connection initiates the upgrade
+        if endpoint.is_listener() && !self.direct.contains(&peer) {
+            self.upgrades.insert(
+                peer,
+                Upgrade {
+                    attempt: 0usize,
+                    initiator: true,
+                },
+            );
+            self.start_attempt(peer).await;
+        }
+    }
+
+    /// Connection closed to remote peer.
+    fn on_connection_closed(&mut self, peer: PeerId) {
+        tracing::trace!(target: LOG_TARGET, ?peer, "connection closed");
+
+        self.direct.remove(&peer);
+        self.upgrades.remove(&peer);
+    }
+
+    /// Start the next upgrade attempt by opening a substream to `peer`.
+    async fn start_attempt(&mut self, peer: PeerId) {
+        let Some(upgrade) = self.upgrades.get_mut(&peer) else {
+            return;
+        };
+        upgrade.attempt += 1;
+
+        tracing::debug!(
+            target: LOG_TARGET,
+            ?peer,
+            attempt = upgrade.attempt,
+            "start connection upgrade",
+        );
+
+        match self.service.open_substream(peer) {
+            Ok(substream_id) => {
+                self.pending_outbound.insert(substream_id, peer);
+            }
+            Err(error) => {
+                tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to open substream");
+
+                self.upgrades.remove(&peer);
+                let _ = self
+                    .tx
+                    .send(DcutrEvent::UpgradeFailed {
+                        peer,
+                        error: DcutrError::SubstreamClosed,
+                    })
+                    .await;
+            }
+        }
+    }
+
+    /// Upgrade attempt failed, retry if attempts remain.
+    async fn on_attempt_failed(&mut self, peer: PeerId, error: DcutrError) {
+        let Some(upgrade) = self.upgrades.get(&peer) else {
+            return;
+        };
+
+        tracing::debug!(
+            target: LOG_TARGET,
+            ?peer,
+            attempt = upgrade.attempt,
+            ?error,
+            "connection upgrade attempt failed",
+        );
+
+        if !upgrade.initiator {
+            self.upgrades.remove(&peer);
+            return;
+        }
+
+        // a substream that can't be opened means the peer doesn't support the protocol
+        if upgrade.attempt < self.max_attempts && error != DcutrError::SubstreamClosed {
+            return self.start_attempt(peer).await;
+        }
+
+        self.upgrades.remove(&peer);
+        let _ = self.tx.send(DcutrEvent::UpgradeFailed { peer, error }).await;
+    }
+
+    /// Outbound substream opened, exchange addresses as the initiator.
+    async fn on_outbound_substream(&mut self, substream_id: SubstreamId, substream: Substream) {
+        let Some(peer) = self.pending_outbound.remove(&substream_id) else {
+            tracing::warn!(target: LOG_TARGET, ?substream_id, "unknown outbound substream");
+            return;
+        };
+
+        let addresses = self.local_addresses();
+        if addresses.is_empty() {
+            return self.on_attempt_failed(peer, DcutrError::NoAddresses).await;
+        }
+
+        self.pending_exchanges.push(Box::pin(async move {
+            (peer, Role::Listener, initiate(substream, addresses).await)
+        }));
+    }
+
+    /// Failed to open outbound substream.
+    async fn on_substream_open_failure(&mut self, substream_id: SubstreamId) {
+        if let Some(peer) = self.pending_outbound.remove(&substream_id) {
+            self.on_attempt_failed(peer, DcutrError::SubstreamClosed).await;
+        }
+    }
+
+    /// Inbound substream opened, exchange addresses with the initiator.
+    fn on_inbound_substream(&mut self, peer: PeerId, substream: Substream) {
+        tracing::trace!(target: LOG_TARGET, ?peer, "inbound upgrade request");
+
+        let upgrade = self.upgrades.entry(peer).or_insert(Upgrade {
+            attempt: 0usize,
+            initiator: false,
+        });
+        upgrade.attempt += 1;
+
+        let addresses = self.local_addresses();
+        self.pending_exchanges.push(Box::pin(async move {
+            (peer, Role::Dialer, respond(substream, addresses).await)
+        }));
+    }
+
+    /// Addresses were exchanged with the remote peer, dial the peer in `role`.
+    async fn on_exchange(
+        &mut self,
+        peer: PeerId,
+        role: Role,
+        result: Result<Vec<Multiaddr>, DcutrError>,
+    ) {
+        let addresses = match result {
+            Ok(addresses) => addresses,
+            Err(error) => return self.on_attempt_failed(peer, error).await,
+        };
+        let Some(attempt) = self.upgrades.get(&peer).map(|upgrade| upgrade.attempt) else {
+            return;
+        };
+
+        if self.direct.contains(&peer) {
+            tracing::trace!(target: LOG_TARGET, ?peer, "peer already connected directly");
+            return;
+        }
+
+        let addresses = addresses
+            .into_iter()
+            .filter(|address| {
+                !address.iter().any(|protocol| std::matches!(protocol, Protocol::P2pCircuit))
+            })
+            .map(|address| match address.iter().last() {
+                Some(Protocol::P2p(_)) => address,
+                _ => address.with(Protocol::P2p(peer.into())),
+            })
+            .filter(|address| {
+                PeerId::try_from_multiaddr(address) == Some(peer)
+                    && self.service.supported_transport(address)
+            })
+            .collect::<Vec<_>>();
+
+        if addresses.is_empty() {
+            return self.on_attempt_failed(peer, DcutrError::NoAddresses).await;
+        }
+
+        // QUIC connections can't be opened by both peers at once so instead of dialing the QUIC
+        // addresses, the initiator punches holes for the dials of the remote peer
+        let (punch, dial): (Vec<_>, Vec<_>) = addresses.into_iter().partition(|address| {
+            std::matches!(role, Role::Listener)
+                && address.iter().any(|protocol| std::matches!(protocol, Protocol::QuicV1))
+        });
+
+        tracing::debug!(target: LOG_TARGET, ?peer, ?dial, ?punch, ?role, "dial peer");
+
+        if !punch.is_empty() {
+            if let Err(error) = self.service.hole_punch(punch) {
+                tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to punch holes");
+            }
+        }
+
+        if !dial.is_empty() {
+            if let Err(error) = self.service.simultaneous_open(dial, role) {
+                tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to dial peer");
+            }
+        }
+
+        self.timeouts.push(Box::pin(async move {
+            tokio::time::sleep(UPGRADE_TIMEOUT).await;
+            (peer, attempt)
+        }));
+    }
+
+    /// Direct connection wasn't established in time.
+    async fn on_upgrade_timeout(&mut self, peer: PeerId, attempt: usize) {
+        match self.upgrades.get(&peer) {
+            Some(upgrade) if upgrade.attempt == attempt =>
+                self.on_attempt_failed(peer, DcutrError::Timeout).await,
+            _ => {}
+        }
+    }
+
+    /// Handle event received from the transport service.
+    async fn on_service_event(&mut self, event: TransportEvent) {
+        match event {
+            TransportEvent::ConnectionEstablished { peer, endpoint } =>
+                self.on_connection_established(peer, endpoint).await,
+            TransportEvent::ConnectionClosed { peer } => self.on_connection_closed(peer),
+            TransportEvent::SubstreamOpened {
+                peer,
+                direction,
+                substream,
+                ..
+            } => match direction {
+                Direction::Inbound => self.on_inbound_substream(peer, substream),
+                Direction::Outbound(substream_id) =>
+                    self.on_outbound_substream(substream_id, substream).await,
+            },
+            TransportEvent::SubstreamOpenFailure { substream, error } => {
+                tracing::debug!(target: LOG_TARGET, ?substream, ?error, "failed to open substream");
+                self.on_substream_open_failure(substream).await;
+            }
+            TransportEvent::DialFailure { .. } => {}
+        }
+    }
+
+    /// Start [`Dcutr`] event loop.
+    pub async fn run(mut self) {
+        tracing::debug!(target: LOG_TARGET, "starting dcutr event loop");
+
+        loop {
+            tokio::select! {
+                event = self.service.next() => match event {
+                    None => {
+                        tracing::warn!(target: LOG_TARGET, "transport service stream ended, terminating dcutr event loop");
+                        return
+                    },
+                    Some(event) => self.on_service_event(event).await,
+                },
+                Some((peer, role, result)) = self.pending_exchanges.next() =>
+                    self.on_exchange(peer, role, result).await,
+                Some((peer, attempt)) = self.timeouts.next() =>
+                    self.on_upgrade_timeout(peer, attempt).await,
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use crate::{
+    codec::ProtocolCodec,
+    protocol::libp2p::floodsub::{PubsubCommand, PubsubEvent, PubsubHandle},
+    types::protocol::ProtocolName,
+    DEFAULT_CHANNEL_SIZE,
+};
+
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+use std::time::Duration;
+
+/// Floodsub protocol name.
+pub const PROTOCOL_NAME: &str = "/floodsub/1.0.0";
+
+/// Default maximum size of an RPC.
+const MAX_TRANSMIT_SIZE: usize = 65536;
+
+/// Default time the IDs of received messages are remembered for.
+const SEEN_TTL: Duration = Duration::from_secs(120);
+
+/// Floodsub configuration.
+pub struct Config {
+    /// Protocol name.
+    pub(crate) protocol: ProtocolName,
+
+    /// Codec used by the protocol.
+    pub(crate) codec: ProtocolCodec,
+
+    /// Maximum size of an RPC.
+    pub(crate) max_transmit_size: usize,
+
+    /// Time the IDs of received messages are remembered for.
+    pub(crate) seen_ttl: Duration,
+
+    /// TX channel for sending events to the user.
+    pub(crate) event_tx: Sender<PubsubEvent>,
+
+    /// RX channel for receiving commands from the user.
+    pub(crate) cmd_rx: Receiver<PubsubCommand>,
+}
+
+impl Config {
+    /// Create new [`Config`] with default values.
+    ///
+    /// Returns a config that is given to `Litep2pConfig` and a [`PubsubHandle`].
+    pub fn default() -> (Self, PubsubHandle) {
+        ConfigBuilder::new().build()
+    }
+}
+
+/// Floodsub configuration builder.
+pub struct ConfigBuilder {
+    /// Maximum size of an RPC.
+    max_transmit_size: usize,
+
+    /// Time the IDs of received messages are remembered for.
+    seen_ttl: Duration,
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigBuilder {
+    /// Create new default [`Config`] which can be modified by the user.
+    pub fn new() -> Self {
+        Self {
+            max_transmit_size: MAX_TRANSMIT_SIZE,
+            seen_ttl: SEEN_TTL,
+        }
+    }
+
+    /// Set maximum size of an RPC.
+    pub fn with_max_transmit_size(mut self, max_transmit_size: usize) -> Self {
+        self.max_transmit_size = max_transmit_size;
+        self
+    }
+
+    /// Set how long the IDs of received messages are remembered for.
+    ///
+    /// A message received again within this time is considered a duplicate and is neither
+    /// reported to the user nor forwarded to other peers.
+    pub fn with_seen_ttl(mut self, seen_ttl: Duration) -> Self {
+        self.seen_ttl = seen_ttl;
+        self
+    }
+
+    /// Build [`Config`].
+    pub fn build(self) -> (Config, PubsubHandle) {
+        let (event_tx, event_rx) = channel(DEFAULT_CHANNEL_SIZE);
+        let (cmd_tx, cmd_rx) = channel(DEFAULT_CHANNEL_SIZE);
+
+        (
+            Config {
+                protocol: ProtocolName::from(PROTOCOL_NAME),
+                codec: ProtocolCodec::UnsignedVarint(Some(self.max_transmit_size)),
+                max_transmit_size: self.max_transmit_size,
+                seen_ttl: self.seen_ttl,
+                event_tx,
+                cmd_rx,
+            },
+            PubsubHandle::new(event_rx, cmd_tx),
+        )
+    }
+}
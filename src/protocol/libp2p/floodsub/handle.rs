@@ -0,0 +1,147 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Handle for communicating with the floodsub protocol implementation.
+
+use crate::PeerId;
+
+use tokio::sync::mpsc::{Receiver, Sender};
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Events emitted by the pubsub protocol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PubsubEvent {
+    /// Message was received on a topic the local node is subscribed to.
+    Message {
+        /// Topic.
+        topic: String,
+
+        /// Peer which published the message.
+        source: PeerId,
+
+        /// Message payload.
+        data: Vec<u8>,
+    },
+
+    /// Remote peer subscribed to a topic.
+    Subscribed {
+        /// Peer ID.
+        peer: PeerId,
+
+        /// Topic.
+        topic: String,
+    },
+
+    /// Remote peer unsubscribed from a topic.
+    Unsubscribed {
+        /// Peer ID.
+        peer: PeerId,
+
+        /// Topic.
+        topic: String,
+    },
+}
+
+/// Commands sent from the user to the pubsub protocol.
+#[derive(Debug)]
+pub(crate) enum PubsubCommand {
+    /// Subscribe to topic.
+    Subscribe {
+        /// Topic.
+        topic: String,
+    },
+
+    /// Unsubscribe from topic.
+    Unsubscribe {
+        /// Topic.
+        topic: String,
+    },
+
+    /// Publish message to topic.
+    Publish {
+        /// Topic.
+        topic: String,
+
+        /// Message payload.
+        data: Vec<u8>,
+    },
+}
+
+/// Handle for communicating with the pubsub protocol.
+pub struct PubsubHandle {
+    /// RX channel for receiving pubsub events.
+    event_rx: Receiver<PubsubEvent>,
+
+    /// TX channel for sending commands to the pubsub protocol.
+    cmd_tx: Sender<PubsubCommand>,
+}
+
+impl PubsubHandle {
+    /// Create new [`PubsubHandle`].
+    pub(crate) fn new(event_rx: Receiver<PubsubEvent>, cmd_tx: Sender<PubsubCommand>) -> Self {
+        Self { event_rx, cmd_tx }
+    }
+
+    /// Subscribe to `topic`.
+    pub async fn subscribe(&self, topic: impl Into<String>) {
+        let _ = self
+            .cmd_tx
+            .send(PubsubCommand::Subscribe {
+                topic: topic.into(),
+            })
+            .await;
+    }
+
+    /// Unsubscribe from `topic`.
+    pub async fn unsubscribe(&self, topic: impl Into<String>) {
+        let _ = self
+            .cmd_tx
+            .send(PubsubCommand::Unsubscribe {
+                topic: topic.into(),
+            })
+            .await;
+    }
+
+    /// Publish `data` to `topic`.
+    ///
+    /// The message is sent to all connected peers subscribed to `topic`. The local node doesn't
+    /// have to be subscribed to the topic itself.
+    pub async fn publish(&self, topic: impl Into<String>, data: Vec<u8>) {
+        let _ = self
+            .cmd_tx
+            .send(PubsubCommand::Publish {
+                topic: topic.into(),
+                data,
+            })
+            .await;
+    }
+}
+
+impl futures::Stream for PubsubHandle {
+    type Item = PubsubEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.event_rx).poll_recv(cx)
+    }
+}
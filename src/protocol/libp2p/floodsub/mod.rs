@@ -0,0 +1,547 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! [Floodsub](https://github.com/libp2p/specs/blob/master/pubsub/README.md) implementation.
+//!
+//! Each message is sent to all connected peers subscribed to the topic of the message and the
+//! peers forward the message once to their own subscribed peers. The IDs of received messages
+//! are remembered for a while so duplicate messages are neither reported nor forwarded again.
+//!
+//! A new outbound substream is opened for each RPC while inbound substreams are read until the
+//! remote peer closes them.
+
+use crate::{
+    error::SubstreamError,
+    protocol::{Direction, TransportEvent, TransportService},
+    substream::{Substream, SubstreamSet},
+    types::SubstreamId,
+    utils::futures_stream::FuturesStream,
+    PeerId,
+};
+
+use bytes::{Bytes, BytesMut};
+use futures::{future::BoxFuture, StreamExt};
+use prost::Message;
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::{Duration, Instant},
+};
+
+pub use config::{Config, ConfigBuilder, PROTOCOL_NAME};
+pub use handle::{PubsubEvent, PubsubHandle};
+
+pub(crate) use handle::PubsubCommand;
+
+mod config;
+mod handle;
+
+mod schema {
+    include!(concat!(env!("OUT_DIR"), "/floodsub.rs"));
+}
+
+/// Log target for the file.
+const LOG_TARGET: &str = "litep2p::floodsub";
+
+/// Size of the sequence number of a published message.
+const SEQUENCE_NUMBER_SIZE: usize = 20;
+
+/// Cache of the IDs of received messages.
+struct SeenCache {
+    /// Seen message IDs.
+    seen: HashSet<Vec<u8>>,
+
+    /// Message IDs in the order they were seen.
+    order: VecDeque<(Instant, Vec<u8>)>,
+
+    /// How long the message IDs are remembered.
+    ttl: Duration,
+}
+
+impl SeenCache {
+    /// Create new [`SeenCache`].
+    fn new(ttl: Duration) -> Self {
+        Self {
+            seen: HashSet::new(),
+            order: VecDeque::new(),
+            ttl,
+        }
+    }
+
+    /// Mark the message as seen, forgetting the message IDs which have expired.
+    ///
+    /// Returns `false` if the message had already been seen.
+    fn insert(&mut self, id: Vec<u8>) -> bool {
+        let now = Instant::now();
+
+        while let Some((seen_at, _)) = self.order.front() {
+            if now.duration_since(*seen_at) < self.ttl {
+                break;
+            }
+
+            let (_, id) = self.order.pop_front().expect("entry to exist");
+            self.seen.remove(&id);
+        }
+
+        if !self.seen.insert(id.clone()) {
+            return false;
+        }
+        self.order.push_back((now, id));
+
+        true
+    }
+}
+
+/// ID of a message, formed from the source and the sequence number of the message.
+fn message_id(message: &schema::Message) -> Vec<u8> {
+    let mut id = message.from.clone().unwrap_or_default();
+    id.extend_from_slice(message.seqno.as_deref().unwrap_or_default());
+    id
+}
+
+/// Floodsub protocol.
+pub(crate) struct Floodsub {
+    /// Transport service.
+    service: TransportService,
+
+    /// Floodsub configuration.
+    config: Config,
+
+    /// Local peer ID.
+    local_peer_id: PeerId,
+
+    /// Connected peers and the topics they are subscribed to.
+    peers: HashMap<PeerId, HashSet<String>>,
+
+    /// Peers subscribed to each topic.
+    topic_peers: HashMap<String, HashSet<PeerId>>,
+
+    /// Topics the local node is subscribed to.
+    subscriptions: HashSet<String>,
+
+    /// Outbound substreams being opened and the RPCs to send over them.
+    pending_outbound: HashMap<SubstreamId, (PeerId, Bytes)>,
+
+    /// Pending sends.
+    pending_sends: FuturesStream<BoxFuture<'static, (PeerId, Result<(), SubstreamError>)>>,
+
+    /// Inbound substreams.
+    inbound: SubstreamSet<(PeerId, usize), Substream>,
+
+    /// Index of the next inbound substream.
+    next_inbound_index: usize,
+
+    /// Cache of seen message IDs.
+    seen: SeenCache,
+}
+
+impl Floodsub {
+    /// Create new [`Floodsub`] protocol.
+    pub(crate) fn new(service: TransportService, config: Config, local_peer_id: PeerId) -> Self {
+        Self {
+            seen: SeenCache::new(config.seen_ttl),
+            service,
+            config,
+            local_peer_id,
+            peers: HashMap::new(),
+            topic_peers: HashMap::new(),
+            subscriptions: HashSet::new(),
+            pending_outbound: HashMap::new(),
+            pending_sends: FuturesStream::new(),
+            inbound: SubstreamSet::new(),
+            next_inbound_index: 0usize,
+        }
+    }
+
+    /// Open a substream to `peer` for sending `rpc`.
+    fn send_rpc(&mut self, peer: PeerId, rpc: &schema::Rpc) {
+        let payload = rpc.encode_to_vec();
+
+        if payload.len() > self.config.max_transmit_size {
+            tracing::warn!(
+                target: LOG_TARGET,
+                ?peer,
+                size = payload.len(),
+                "rpc exceeds maximum transmit size",
+            );
+            return;
+        }
+
+        match self.service.open_substream(peer) {
+            Ok(substream_id) => {
+                self.pending_outbound.insert(substream_id, (peer, payload.into()));
+            }
+            Err(error) => {
+                tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to open substream");
+            }
+        }
+    }
+
+    /// Send `message` to the peers subscribed to any of its topics, except `exclude`.
+    fn send_message(&mut self, message: schema::Message, exclude: &[PeerId]) {
+        let peers = message
+            .topic_ids
+            .iter()
+            .filter_map(|topic| self.topic_peers.get(topic))
+            .flatten()
+            .filter(|peer| !exclude.contains(peer))
+            .copied()
+            .collect::<HashSet<_>>();
+
+        if peers.is_empty() {
+            tracing::trace!(target: LOG_TARGET, topics = ?message.topic_ids, "no peers to send message to");
+            return;
+        }
+
+        let rpc = schema::Rpc {
+            publish: vec![message],
+            ..Default::default()
+        };
+
+        for peer in peers {
+            self.send_rpc(peer, &rpc);
+        }
+    }
+
+    /// Connection established to remote peer.
+    fn on_connection_established(&mut self, peer: PeerId) {
+        tracing::trace!(target: LOG_TARGET, ?peer, "connection established");
+
+        if self.peers.insert(peer, HashSet::new()).is_some() || self.subscriptions.is_empty() {
+            return;
+        }
+
+        let subscriptions = self
+            .subscriptions
+            .iter()
+            .map(|topic| schema::rpc::SubOpts {
+                subscribe: Some(true),
+                topic_id: Some(topic.clone()),
+            })
+            .collect();
+
+        self.send_rpc(
+            peer,
+            &schema::Rpc {
+                subscriptions,
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Connection closed to remote peer.
+    fn on_connection_closed(&mut self, peer: PeerId) {
+        tracing::trace!(target: LOG_TARGET, ?peer, "connection closed");
+
+        let Some(topics) = self.peers.remove(&peer) else {
+            return;
+        };
+
+        for topic in topics {
+            if let Some(peers) = self.topic_peers.get_mut(&topic) {
+                peers.remove(&peer);
+
+                if peers.is_empty() {
+                    self.topic_peers.remove(&topic);
+                }
+            }
+        }
+    }
+
+    /// Outbound substream opened, send the pending RPC and close the substream.
+    fn on_outbound_substream(&mut self, substream_id: SubstreamId, mut substream: Substream) {
+        let Some((peer, payload)) = self.pending_outbound.remove(&substream_id) else {
+            tracing::warn!(target: LOG_TARGET, ?substream_id, "unknown outbound substream");
+            return;
+        };
+
+        self.pending_sends.push(Box::pin(async move {
+            if let Err(error) = substream.send_framed(payload).await {
+                return (peer, Err(error));
+            }
+            substream.close().await;
+
+            (peer, Ok(()))
+        }));
+    }
+
+    /// Failed to open outbound substream.
+    fn on_substream_open_failure(&mut self, substream_id: SubstreamId, error: SubstreamError) {
+        if let Some((peer, _)) = self.pending_outbound.remove(&substream_id) {
+            tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to open substream");
+        }
+    }
+
+    /// Inbound substream opened.
+    fn on_inbound_substream(&mut self, peer: PeerId, substream: Substream) {
+        tracing::trace!(target: LOG_TARGET, ?peer, "inbound substream opened");
+
+        self.inbound.insert((peer, self.next_inbound_index), substream);
+        self.next_inbound_index = self.next_inbound_index.wrapping_add(1);
+    }
+
+    /// Data received over an inbound substream of `peer`.
+    async fn on_inbound_data(
+        &mut self,
+        key: (PeerId, usize),
+        result: Result<BytesMut, SubstreamError>,
+    ) {
+        let (peer, _) = key;
+
+        let payload = match result {
+            Ok(payload) => payload,
+            Err(error) => {
+                tracing::trace!(target: LOG_TARGET, ?peer, ?error, "inbound substream closed");
+                self.inbound.remove(&key);
+                return;
+            }
+        };
+
+        match schema::Rpc::decode(payload.freeze()) {
+            Ok(rpc) => {
+                self.on_subscriptions(peer, rpc.subscriptions).await;
+
+                for message in rpc.publish {
+                    self.on_message(peer, message).await;
+                }
+            }
+            Err(error) => tracing::debug!(target: LOG_TARGET, ?peer, ?error, "invalid rpc"),
+        }
+    }
+
+    /// Subscriptions of `peer` changed.
+    async fn on_subscriptions(&mut self, peer: PeerId, subscriptions: Vec<schema::rpc::SubOpts>) {
+        for subscription in subscriptions {
+            let Some(topic) = subscription.topic_id else {
+                continue;
+            };
+            let Some(topics) = self.peers.get_mut(&peer) else {
+                return;
+            };
+
+            let event = if subscription.subscribe.unwrap_or(false) {
+                if !topics.insert(topic.clone()) {
+                    continue;
+                }
+                self.topic_peers.entry(topic.clone()).or_default().insert(peer);
+
+                PubsubEvent::Subscribed { peer, topic }
+            } else {
+                if !topics.remove(&topic) {
+                    continue;
+                }
+
+                if let Some(peers) = self.topic_peers.get_mut(&topic) {
+                    peers.remove(&peer);
+
+                    if peers.is_empty() {
+                        self.topic_peers.remove(&topic);
+                    }
+                }
+
+                PubsubEvent::Unsubscribed { peer, topic }
+            };
+
+            tracing::trace!(target: LOG_TARGET, ?event, "subscription changed");
+            let _ = self.config.event_tx.send(event).await;
+        }
+    }
+
+    /// Message received from `propagation_source`.
+    async fn on_message(&mut self, propagation_source: PeerId, message: schema::Message) {
+        let Some(source) = message.from.as_deref().and_then(|from| PeerId::from_bytes(from).ok())
+        else {
+            tracing::debug!(target: LOG_TARGET, ?propagation_source, "message without valid source");
+            return;
+        };
+
+        if !self.seen.insert(message_id(&message)) {
+            tracing::trace!(target: LOG_TARGET, ?propagation_source, "duplicate message");
+            return;
+        }
+
+        tracing::trace!(
+            target: LOG_TARGET,
+            ?propagation_source,
+            ?source,
+            topics = ?message.topic_ids,
+            "message received",
+        );
+
+        for topic in message.topic_ids.iter().filter(|topic| self.subscriptions.contains(*topic)) {
+            let _ = self
+                .config
+                .event_tx
+                .send(PubsubEvent::Message {
+                    topic: topic.clone(),
+                    source,
+                    data: message.data.clone().unwrap_or_default(),
+                })
+                .await;
+        }
+
+        self.send_message(message, &[propagation_source, source]);
+    }
+
+    /// Subscribe to `topic` and announce the subscription to connected peers.
+    fn on_subscribe(&mut self, topic: String) {
+        if self.subscriptions.insert(topic.clone()) {
+            self.announce_subscription(topic, true);
+        }
+    }
+
+    /// Unsubscribe from `topic` and announce the change to connected peers.
+    fn on_unsubscribe(&mut self, topic: String) {
+        if self.subscriptions.remove(&topic) {
+            self.announce_subscription(topic, false);
+        }
+    }
+
+    /// Announce a subscription change to all connected peers.
+    fn announce_subscription(&mut self, topic: String, subscribe: bool) {
+        tracing::debug!(target: LOG_TARGET, ?topic, ?subscribe, "subscription changed");
+
+        let rpc = schema::Rpc {
+            subscriptions: vec![schema::rpc::SubOpts {
+                subscribe: Some(subscribe),
+                topic_id: Some(topic),
+            }],
+            ..Default::default()
+        };
+
+        for peer in self.peers.keys().copied().collect::<Vec<_>>() {
+            self.send_rpc(peer, &rpc);
+        }
+    }
+
+    /// Publish `data` to `topic`.
+    fn on_publish(&mut self, topic: String, data: Vec<u8>) {
+        let message = schema::Message {
+            from: Some(self.local_peer_id.to_bytes()),
+            data: Some(data),
+            seqno: Some(rand::random::<[u8; SEQUENCE_NUMBER_SIZE]>().to_vec()),
+            topic_ids: vec![topic],
+        };
+        self.seen.insert(message_id(&message));
+
+        self.send_message(message, &[]);
+    }
+
+    /// Handle command received from the user.
+    fn on_command(&mut self, command: PubsubCommand) {
+        match command {
+            PubsubCommand::Subscribe { topic } => self.on_subscribe(topic),
+            PubsubCommand::Unsubscribe { topic } => self.on_unsubscribe(topic),
+            PubsubCommand::Publish { topic, data } => self.on_publish(topic, data),
+        }
+    }
+
+    /// Handle event received from the transport service.
+    fn on_service_event(&mut self, event: TransportEvent) {
+        match event {
+            TransportEvent::ConnectionEstablished { peer, .. } =>
+                self.on_connection_established(peer),
+            TransportEvent::ConnectionClosed { peer } => self.on_connection_closed(peer),
+            TransportEvent::SubstreamOpened {
+                peer,
+                direction,
+                substream,
+                ..
+            } => match direction {
+                Direction::Inbound => self.on_inbound_substream(peer, substream),
+                Direction::Outbound(substream_id) =>
+                    self.on_outbound_substream(substream_id, substream),
+            },
+            TransportEvent::SubstreamOpenFailure { substream, error } =>
+                self.on_substream_open_failure(substream, error),
+            TransportEvent::DialFailure { .. } => {}
+        }
+    }
+
+    /// Start [`Floodsub`] event loop.
+    pub async fn run(mut self) {
+        tracing::debug!(target: LOG_TARGET, "starting floodsub event loop");
+
+        loop {
+            tokio::select! {
+                event = self.service.next() => match event {
+                    None => {
+                        tracing::warn!(target: LOG_TARGET, "transport service stream ended, terminating floodsub event loop");
+                        return
+                    },
+                    Some(event) => self.on_service_event(event),
+                },
+                command = self.config.cmd_rx.recv() => match command {
+                    None => {
+                        tracing::debug!(target: LOG_TARGET, "user handle dropped, terminating floodsub event loop");
+                        return
+                    },
+                    Some(command) => self.on_command(command),
+                },
+                Some((key, result)) = self.inbound.next() =>
+                    self.on_inbound_data(key, result).await,
+                Some((peer, result)) = self.pending_sends.next() =>
+                    if let Err(error) = result {
+                        tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to send rpc");
+                    },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicate_messages_are_detected() {
+        let mut cache = SeenCache::new(Duration::from_secs(60));
+
+        assert!(cache.insert(vec![1, 2, 3]));
+        assert!(!cache.insert(vec![1, 2, 3]));
+        assert!(cache.insert(vec![1, 2, 4]));
+    }
+
+    #[test]
+    fn seen_messages_expire() {
+        let mut cache = SeenCache::new(Duration::from_millis(50));
+
+        assert!(cache.insert(vec![1]));
+        std::thread::sleep(Duration::from_millis(100));
+
+        assert!(cache.insert(vec![1]));
+        assert_eq!(cache.order.len(), 1);
+    }
+
+    #[test]
+    fn message_id_combines_source_and_sequence_number() {
+        let source = PeerId::random();
+        let message = schema::Message {
+            from: Some(source.to_bytes()),
+            seqno: Some(vec![7u8; SEQUENCE_NUMBER_SIZE]),
+            topic_ids: vec!["topic".to_owned()],
+            ..Default::default()
+        };
+
+        let mut expected = source.to_bytes();
+        expected.extend_from_slice(&[7u8; SEQUENCE_NUMBER_SIZE]);
+        assert_eq!(message_id(&message), expected);
+    }
+}
@@ -0,0 +1,360 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use crate::{
+    codec::ProtocolCodec,
+    protocol::libp2p::gossipsub::{
+        default_message_id, GossipsubCommand, GossipsubEvent, GossipsubHandle, MessageIdFn,
+        PeerScoreParams, PeerScoreThresholds,
+    },
+    types::protocol::ProtocolName,
+    DEFAULT_CHANNEL_SIZE,
+};
+
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+use std::time::Duration;
+
+/// Gossipsub v1.1 protocol name.
+pub const PROTOCOL_NAME: &str = "/meshsub/1.1.0";
+
+/// Gossipsub v1.0 protocol name, supported as a fallback.
+pub const PROTOCOL_NAME_V1_0: &str = "/meshsub/1.0.0";
+
+/// Default maximum size of an RPC.
+const MAX_TRANSMIT_SIZE: usize = 65536;
+
+/// Default target number of peers in the mesh of a topic.
+const MESH_N: usize = 6;
+
+/// Default minimum number of peers in the mesh of a topic.
+const MESH_N_LOW: usize = 5;
+
+/// Default maximum number of peers in the mesh of a topic.
+const MESH_N_HIGH: usize = 12;
+
+/// Default minimum number of peers gossip is emitted to.
+const GOSSIP_LAZY: usize = 6;
+
+/// Default share of the peers outside of the mesh gossip is emitted to.
+const GOSSIP_FACTOR: f64 = 0.25;
+
+/// Default heartbeat interval.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Default time after which the fanout of a topic the local node hasn't published to is removed.
+const FANOUT_TTL: Duration = Duration::from_secs(60);
+
+/// Default number of heartbeats messages are cached for.
+const HISTORY_LENGTH: usize = 5;
+
+/// Default number of heartbeats the IDs of cached messages are gossiped for.
+const HISTORY_GOSSIP: usize = 3;
+
+/// Default time the IDs of seen messages are remembered.
+const DUPLICATE_CACHE_TIME: Duration = Duration::from_secs(60);
+
+/// Default backoff before a pruned peer may be grafted again.
+const PRUNE_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Default maximum number of message IDs requested from a peer in a heartbeat.
+const MAX_IHAVE_LENGTH: usize = 5000;
+
+/// Default maximum number of times a message is sent to the same peer in response to `IWANT`.
+const GOSSIP_RETRANSMISSION: usize = 3;
+
+/// Gossipsub configuration.
+pub struct Config {
+    /// Protocol names, the first being the main protocol.
+    pub(crate) protocol_names: Vec<ProtocolName>,
+
+    /// Codec used by the protocol.
+    pub(crate) codec: ProtocolCodec,
+
+    /// Maximum size of an RPC.
+    pub(super) max_transmit_size: usize,
+
+    /// Target number of peers in the mesh of a topic.
+    pub(super) mesh_n: usize,
+
+    /// Minimum number of peers in the mesh of a topic.
+    pub(super) mesh_n_low: usize,
+
+    /// Maximum number of peers in the mesh of a topic.
+    pub(super) mesh_n_high: usize,
+
+    /// Minimum number of peers gossip is emitted to.
+    pub(super) gossip_lazy: usize,
+
+    /// Share of the peers outside of the mesh gossip is emitted to.
+    pub(super) gossip_factor: f64,
+
+    /// Heartbeat interval.
+    pub(super) heartbeat_interval: Duration,
+
+    /// Time after which the fanout of a topic the local node hasn't published to is removed.
+    pub(super) fanout_ttl: Duration,
+
+    /// Number of heartbeats messages are cached for.
+    pub(super) history_length: usize,
+
+    /// Number of heartbeats the IDs of cached messages are gossiped for.
+    pub(super) history_gossip: usize,
+
+    /// Time the IDs of seen messages are remembered.
+    pub(super) duplicate_cache_time: Duration,
+
+    /// Backoff before a pruned peer may be grafted again.
+    pub(super) prune_backoff: Duration,
+
+    /// Maximum number of message IDs requested from a peer in a heartbeat.
+    pub(super) max_ihave_length: usize,
+
+    /// Maximum number of times a message is sent to the same peer in response to `IWANT`.
+    pub(super) gossip_retransmission: usize,
+
+    /// Whether published messages are sent to all peers subscribed to the topic.
+    pub(super) flood_publish: bool,
+
+    /// Peer score parameters and thresholds, if peer scoring is enabled.
+    pub(super) peer_score: Option<(PeerScoreParams, PeerScoreThresholds)>,
+
+    /// Function used to compute message IDs.
+    pub(super) message_id_fn: MessageIdFn,
+
+    /// TX channel for sending events to the user protocol.
+    pub(super) event_tx: Sender<GossipsubEvent>,
+
+    /// RX channel for receiving commands from the user.
+    pub(super) cmd_rx: Receiver<GossipsubCommand>,
+}
+
+/// Gossipsub configuration builder.
+pub struct ConfigBuilder {
+    /// Maximum size of an RPC.
+    max_transmit_size: usize,
+
+    /// Target number of peers in the mesh of a topic.
+    mesh_n: usize,
+
+    /// Minimum number of peers in the mesh of a topic.
+    mesh_n_low: usize,
+
+    /// Maximum number of peers in the mesh of a topic.
+    mesh_n_high: usize,
+
+    /// Minimum number of peers gossip is emitted to.
+    gossip_lazy: usize,
+
+    /// Share of the peers outside of the mesh gossip is emitted to.
+    gossip_factor: f64,
+
+    /// Heartbeat interval.
+    heartbeat_interval: Duration,
+
+    /// Time after which the fanout of a topic the local node hasn't published to is removed.
+    fanout_ttl: Duration,
+
+    /// Number of heartbeats messages are cached for.
+    history_length: usize,
+
+    /// Number of heartbeats the IDs of cached messages are gossiped for.
+    history_gossip: usize,
+
+    /// Time the IDs of seen messages are remembered.
+    duplicate_cache_time: Duration,
+
+    /// Backoff before a pruned peer may be grafted again.
+    prune_backoff: Duration,
+
+    /// Whether published messages are sent to all peers subscribed to the topic.
+    flood_publish: bool,
+
+    /// Peer score parameters and thresholds, if peer scoring is enabled.
+    peer_score: Option<(PeerScoreParams, PeerScoreThresholds)>,
+
+    /// Function used to compute message IDs.
+    message_id_fn: MessageIdFn,
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigBuilder {
+    /// Create new [`ConfigBuilder`] with the default parameters of the gossipsub specification.
+    pub fn new() -> Self {
+        Self {
+            max_transmit_size: MAX_TRANSMIT_SIZE,
+            mesh_n: MESH_N,
+            mesh_n_low: MESH_N_LOW,
+            mesh_n_high: MESH_N_HIGH,
+            gossip_lazy: GOSSIP_LAZY,
+            gossip_factor: GOSSIP_FACTOR,
+            heartbeat_interval: HEARTBEAT_INTERVAL,
+            fanout_ttl: FANOUT_TTL,
+            history_length: HISTORY_LENGTH,
+            history_gossip: HISTORY_GOSSIP,
+            duplicate_cache_time: DUPLICATE_CACHE_TIME,
+            prune_backoff: PRUNE_BACKOFF,
+            flood_publish: true,
+            peer_score: None,
+            message_id_fn: default_message_id,
+        }
+    }
+
+    /// Set maximum size of an RPC.
+    pub fn with_max_transmit_size(mut self, max_transmit_size: usize) -> Self {
+        self.max_transmit_size = max_transmit_size;
+        self
+    }
+
+    /// Set target, minimum and maximum number of peers in the mesh of a topic.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mesh_n_low <= mesh_n <= mesh_n_high` doesn't hold.
+    pub fn with_mesh_params(
+        mut self,
+        mesh_n: usize,
+        mesh_n_low: usize,
+        mesh_n_high: usize,
+    ) -> Self {
+        assert!(
+            mesh_n_low <= mesh_n && mesh_n <= mesh_n_high,
+            "invalid mesh parameters"
+        );
+
+        self.mesh_n = mesh_n;
+        self.mesh_n_low = mesh_n_low;
+        self.mesh_n_high = mesh_n_high;
+        self
+    }
+
+    /// Set minimum number of peers and the share of the peers outside of the mesh gossip is
+    /// emitted to.
+    pub fn with_gossip_params(mut self, gossip_lazy: usize, gossip_factor: f64) -> Self {
+        self.gossip_lazy = gossip_lazy;
+        self.gossip_factor = gossip_factor;
+        self
+    }
+
+    /// Set heartbeat interval.
+    pub fn with_heartbeat_interval(mut self, heartbeat_interval: Duration) -> Self {
+        self.heartbeat_interval = heartbeat_interval;
+        self
+    }
+
+    /// Set time after which the fanout of a topic the local node hasn't published to is removed.
+    pub fn with_fanout_ttl(mut self, fanout_ttl: Duration) -> Self {
+        self.fanout_ttl = fanout_ttl;
+        self
+    }
+
+    /// Set number of heartbeats messages are cached for and the number of heartbeats their IDs
+    /// are gossiped for.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `history_gossip` is greater than `history_length`.
+    pub fn with_history(mut self, history_length: usize, history_gossip: usize) -> Self {
+        assert!(
+            history_gossip <= history_length,
+            "invalid history parameters"
+        );
+
+        self.history_length = history_length;
+        self.history_gossip = history_gossip;
+        self
+    }
+
+    /// Set time the IDs of seen messages are remembered.
+    pub fn with_duplicate_cache_time(mut self, duplicate_cache_time: Duration) -> Self {
+        self.duplicate_cache_time = duplicate_cache_time;
+        self
+    }
+
+    /// Set backoff before a pruned peer may be grafted again.
+    pub fn with_prune_backoff(mut self, prune_backoff: Duration) -> Self {
+        self.prune_backoff = prune_backoff;
+        self
+    }
+
+    /// Set whether published messages are sent to all peers subscribed to the topic instead of
+    /// only the mesh peers.
+    pub fn with_flood_publish(mut self, flood_publish: bool) -> Self {
+        self.flood_publish = flood_publish;
+        self
+    }
+
+    /// Enable peer scoring.
+    pub fn with_peer_score(
+        mut self,
+        params: PeerScoreParams,
+        thresholds: PeerScoreThresholds,
+    ) -> Self {
+        self.peer_score = Some((params, thresholds));
+        self
+    }
+
+    /// Set function used to compute message IDs.
+    pub fn with_message_id_fn(mut self, message_id_fn: MessageIdFn) -> Self {
+        self.message_id_fn = message_id_fn;
+        self
+    }
+
+    /// Build [`Config`].
+    pub fn build(self) -> (Config, GossipsubHandle) {
+        let (event_tx, event_rx) = channel(DEFAULT_CHANNEL_SIZE);
+        let (cmd_tx, cmd_rx) = channel(DEFAULT_CHANNEL_SIZE);
+
+        (
+            Config {
+                protocol_names: vec![
+                    ProtocolName::from(PROTOCOL_NAME),
+                    ProtocolName::from(PROTOCOL_NAME_V1_0),
+                ],
+                codec: ProtocolCodec::UnsignedVarint(Some(self.max_transmit_size)),
+                max_transmit_size: self.max_transmit_size,
+                mesh_n: self.mesh_n,
+                mesh_n_low: self.mesh_n_low,
+                mesh_n_high: self.mesh_n_high,
+                gossip_lazy: self.gossip_lazy,
+                gossip_factor: self.gossip_factor,
+                heartbeat_interval: self.heartbeat_interval,
+                fanout_ttl: self.fanout_ttl,
+                history_length: self.history_length,
+                history_gossip: self.history_gossip,
+                duplicate_cache_time: self.duplicate_cache_time,
+                prune_backoff: self.prune_backoff,
+                max_ihave_length: MAX_IHAVE_LENGTH,
+                gossip_retransmission: GOSSIP_RETRANSMISSION,
+                flood_publish: self.flood_publish,
+                peer_score: self.peer_score,
+                message_id_fn: self.message_id_fn,
+                event_tx,
+                cmd_rx,
+            },
+            GossipsubHandle::new(event_rx, cmd_tx),
+        )
+    }
+}
@@ -0,0 +1,155 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Gossipsub handle for communicating with the gossipsub protocol implementation.
+
+use crate::{
+    error::GossipsubError,
+    protocol::libp2p::gossipsub::{GossipsubMessage, MessageId, TopicHash},
+    PeerId,
+};
+
+use tokio::sync::{
+    mpsc::{Receiver, Sender},
+    oneshot,
+};
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Events emitted by the gossipsub protocol.
+#[derive(Debug, Clone)]
+pub enum GossipsubEvent {
+    /// Message was received on a topic the local node is subscribed to.
+    Message {
+        /// Peer which forwarded the message to the local node.
+        propagation_source: PeerId,
+
+        /// Message ID.
+        message_id: MessageId,
+
+        /// Message.
+        message: GossipsubMessage,
+    },
+
+    /// Remote peer subscribed to a topic.
+    Subscribed {
+        /// Peer ID.
+        peer: PeerId,
+
+        /// Topic.
+        topic: TopicHash,
+    },
+
+    /// Remote peer unsubscribed from a topic.
+    Unsubscribed {
+        /// Peer ID.
+        peer: PeerId,
+
+        /// Topic.
+        topic: TopicHash,
+    },
+}
+
+/// Commands sent from the user to `Gossipsub`.
+#[derive(Debug)]
+pub(crate) enum GossipsubCommand {
+    /// Subscribe to topic.
+    Subscribe {
+        /// Topic.
+        topic: TopicHash,
+    },
+
+    /// Unsubscribe from topic.
+    Unsubscribe {
+        /// Topic.
+        topic: TopicHash,
+    },
+
+    /// Publish message to topic.
+    Publish {
+        /// Topic.
+        topic: TopicHash,
+
+        /// Message payload.
+        data: Vec<u8>,
+
+        /// TX channel for sending the result of the publish.
+        tx: oneshot::Sender<Result<MessageId, GossipsubError>>,
+    },
+}
+
+/// Handle for communicating with the gossipsub protocol.
+pub struct GossipsubHandle {
+    /// RX channel for receiving gossipsub events.
+    event_rx: Receiver<GossipsubEvent>,
+
+    /// TX channel for sending commands to `Gossipsub`.
+    cmd_tx: Sender<GossipsubCommand>,
+}
+
+impl GossipsubHandle {
+    /// Create new [`GossipsubHandle`].
+    pub(super) fn new(
+        event_rx: Receiver<GossipsubEvent>,
+        cmd_tx: Sender<GossipsubCommand>,
+    ) -> Self {
+        Self { event_rx, cmd_tx }
+    }
+
+    /// Subscribe to `topic`.
+    pub async fn subscribe(&self, topic: TopicHash) {
+        let _ = self.cmd_tx.send(GossipsubCommand::Subscribe { topic }).await;
+    }
+
+    /// Unsubscribe from `topic`.
+    pub async fn unsubscribe(&self, topic: TopicHash) {
+        let _ = self.cmd_tx.send(GossipsubCommand::Unsubscribe { topic }).await;
+    }
+
+    /// Publish `data` to `topic`.
+    ///
+    /// The local node doesn't have to be subscribed to the topic. Returns the ID of the message
+    /// once it has been queued for sending to the remote peers.
+    pub async fn publish(
+        &self,
+        topic: TopicHash,
+        data: Vec<u8>,
+    ) -> Result<MessageId, GossipsubError> {
+        let (tx, rx) = oneshot::channel();
+
+        self.cmd_tx
+            .send(GossipsubCommand::Publish { topic, data, tx })
+            .await
+            .map_err(|_| GossipsubError::TaskClosed)?;
+
+        rx.await.map_err(|_| GossipsubError::TaskClosed)?
+    }
+}
+
+impl futures::Stream for GossipsubHandle {
+    type Item = GossipsubEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.event_rx).poll_recv(cx)
+    }
+}
@@ -0,0 +1,276 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Caches of received and published messages.
+
+use crate::{
+    protocol::libp2p::gossipsub::{schema, MessageId, TopicHash},
+    PeerId,
+};
+
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+/// Cached message.
+struct CachedMessage {
+    /// Message.
+    message: schema::Message,
+
+    /// How many times each peer has requested the message.
+    iwant_counts: HashMap<PeerId, usize>,
+}
+
+/// Message cache.
+///
+/// Holds the messages of the last `history_length` heartbeats so they can be sent to peers
+/// requesting them with `IWANT`. The IDs of the messages of the last `history_gossip`
+/// heartbeats are advertised to peers outside of the mesh with `IHAVE`.
+pub(super) struct MessageCache {
+    /// Cached messages.
+    messages: HashMap<MessageId, CachedMessage>,
+
+    /// Message IDs and topics of each heartbeat window, the most recent window first.
+    history: VecDeque<Vec<(MessageId, TopicHash)>>,
+
+    /// Number of windows whose message IDs are gossiped.
+    history_gossip: usize,
+}
+
+impl MessageCache {
+    /// Create new [`MessageCache`].
+    pub(super) fn new(history_length: usize, history_gossip: usize) -> Self {
+        let mut history = VecDeque::with_capacity(history_length);
+        history.resize_with(history_length.max(1), Vec::new);
+
+        Self {
+            messages: HashMap::new(),
+            history,
+            history_gossip,
+        }
+    }
+
+    /// Insert `message` into the cache.
+    ///
+    /// Returns `false` if the message was already cached.
+    pub(super) fn put(&mut self, id: MessageId, message: schema::Message) -> bool {
+        if self.messages.contains_key(&id) {
+            return false;
+        }
+
+        let topic = TopicHash::from(message.topic.clone());
+        self.messages.insert(
+            id.clone(),
+            CachedMessage {
+                message,
+                iwant_counts: HashMap::new(),
+            },
+        );
+        self.history.front_mut().expect("history to exist").push((id, topic));
+
+        true
+    }
+
+    /// Get cached message.
+    #[cfg(test)]
+    pub(super) fn get(&self, id: &MessageId) -> Option<&schema::Message> {
+        self.messages.get(id).map(|cached| &cached.message)
+    }
+
+    /// Get cached message requested by `peer` and the number of times the peer has requested it.
+    pub(super) fn get_for_peer(
+        &mut self,
+        id: &MessageId,
+        peer: PeerId,
+    ) -> Option<(&schema::Message, usize)> {
+        let cached = self.messages.get_mut(id)?;
+        let count = cached.iwant_counts.entry(peer).or_default();
+        *count += 1;
+
+        Some((&cached.message, *count))
+    }
+
+    /// Get IDs of the recent messages of `topic` that should be advertised to other peers.
+    pub(super) fn gossip_ids(&self, topic: &TopicHash) -> Vec<MessageId> {
+        self.history
+            .iter()
+            .take(self.history_gossip)
+            .flatten()
+            .filter(|(_, message_topic)| message_topic == topic)
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// Start a new heartbeat window, removing the messages of the oldest window.
+    pub(super) fn shift(&mut self) {
+        if let Some(window) = self.history.pop_back() {
+            for (id, _) in window {
+                self.messages.remove(&id);
+            }
+        }
+
+        self.history.push_front(Vec::new());
+    }
+}
+
+/// Cache of the IDs of seen messages.
+pub(super) struct SeenCache {
+    /// Seen message IDs.
+    seen: HashMap<MessageId, Instant>,
+
+    /// Message IDs in the order they were seen.
+    order: VecDeque<(Instant, MessageId)>,
+
+    /// How long the message IDs are remembered.
+    ttl: Duration,
+}
+
+impl SeenCache {
+    /// Create new [`SeenCache`].
+    pub(super) fn new(ttl: Duration) -> Self {
+        Self {
+            seen: HashMap::new(),
+            order: VecDeque::new(),
+            ttl,
+        }
+    }
+
+    /// Check if the message has been seen.
+    pub(super) fn contains(&self, id: &MessageId) -> bool {
+        self.seen.contains_key(id)
+    }
+
+    /// Mark the message as seen.
+    ///
+    /// Returns `false` if the message had already been seen.
+    pub(super) fn insert(&mut self, id: MessageId) -> bool {
+        if self.seen.contains_key(&id) {
+            return false;
+        }
+
+        let now = Instant::now();
+        self.seen.insert(id.clone(), now);
+        self.order.push_back((now, id));
+
+        true
+    }
+
+    /// Forget the message IDs which were seen more than `ttl` ago.
+    pub(super) fn expire(&mut self) {
+        let now = Instant::now();
+
+        while let Some((seen_at, _)) = self.order.front() {
+            if now.duration_since(*seen_at) < self.ttl {
+                break;
+            }
+
+            let (_, id) = self.order.pop_front().expect("entry to exist");
+            self.seen.remove(&id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(topic: &str, data: u8) -> (MessageId, schema::Message) {
+        (
+            MessageId::new(&[data]),
+            schema::Message {
+                data: Some(vec![data]),
+                topic: topic.to_owned(),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn messages_expire_after_history_length() {
+        let mut cache = MessageCache::new(3, 2);
+        let (id, message) = message("topic", 1);
+
+        assert!(cache.put(id.clone(), message.clone()));
+        assert!(!cache.put(id.clone(), message));
+
+        cache.shift();
+        cache.shift();
+        assert!(cache.get(&id).is_some());
+
+        cache.shift();
+        assert!(cache.get(&id).is_none());
+    }
+
+    #[test]
+    fn gossip_only_recent_windows() {
+        let mut cache = MessageCache::new(5, 2);
+        let topic = TopicHash::from("topic");
+
+        let (id1, message1) = message("topic", 1);
+        cache.put(id1.clone(), message1);
+        cache.shift();
+
+        let (id2, message2) = message("topic", 2);
+        cache.put(id2.clone(), message2);
+        let (id3, message3) = message("other", 3);
+        cache.put(id3, message3);
+
+        let mut ids = cache.gossip_ids(&topic);
+        ids.sort();
+        assert_eq!(ids, vec![id1.clone(), id2.clone()]);
+
+        cache.shift();
+        assert_eq!(cache.gossip_ids(&topic), vec![id2]);
+
+        // the message is no longer gossiped but can still be requested
+        assert!(cache.get(&id1).is_some());
+    }
+
+    #[test]
+    fn iwant_requests_are_counted() {
+        let mut cache = MessageCache::new(5, 3);
+        let (id, message) = message("topic", 1);
+        let peer = PeerId::random();
+        cache.put(id.clone(), message);
+
+        assert_eq!(cache.get_for_peer(&id, peer).unwrap().1, 1);
+        assert_eq!(cache.get_for_peer(&id, peer).unwrap().1, 2);
+        assert_eq!(cache.get_for_peer(&id, PeerId::random()).unwrap().1, 1);
+        assert!(cache.get_for_peer(&MessageId::new(&[2]), peer).is_none());
+    }
+
+    #[test]
+    fn seen_messages_expire() {
+        let mut cache = SeenCache::new(Duration::from_millis(50));
+        let id = MessageId::new(&[1]);
+
+        assert!(cache.insert(id.clone()));
+        assert!(!cache.insert(id.clone()));
+
+        cache.expire();
+        assert!(cache.contains(&id));
+
+        std::thread::sleep(Duration::from_millis(60));
+        cache.expire();
+        assert!(!cache.contains(&id));
+        assert!(cache.insert(id));
+    }
+}
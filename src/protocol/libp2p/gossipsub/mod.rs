@@ -0,0 +1,1155 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! [Gossipsub v1.1](https://github.com/libp2p/specs/blob/master/pubsub/gossipsub/gossipsub-v1.1.md)
+//! implementation.
+//!
+//! Messages are propagated through a mesh of `D` peers maintained for each subscribed topic.
+//! The mesh is repaired on each heartbeat by grafting and pruning peers, and the IDs of recently
+//! seen messages are gossiped to peers outside of the mesh which may request the full messages.
+//! Messages are signed with the keypair of the local node and messages with an invalid
+//! signature are rejected.
+//!
+//! Each peer has one long-lived outbound substream for sending RPCs and one inbound substream
+//! for receiving them.
+
+use crate::{
+    crypto::{ed25519::Keypair, PublicKey},
+    error::{GossipsubError, SubstreamError},
+    protocol::{Direction, TransportEvent, TransportService},
+    substream::{Substream, SubstreamSet},
+    types::SubstreamId,
+    utils::futures_stream::FuturesStream,
+    PeerId,
+};
+
+use bytes::{Bytes, BytesMut};
+use futures::{future::BoxFuture, StreamExt};
+use prost::Message;
+use rand::seq::{IteratorRandom, SliceRandom};
+
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+pub use config::{Config, ConfigBuilder, PROTOCOL_NAME, PROTOCOL_NAME_V1_0};
+pub use handle::{GossipsubEvent, GossipsubHandle};
+pub use score::{PeerScoreParams, PeerScoreThresholds};
+pub use types::{default_message_id, GossipsubMessage, MessageId, MessageIdFn, TopicHash};
+
+pub(crate) use handle::GossipsubCommand;
+
+use mcache::{MessageCache, SeenCache};
+use score::PeerScore;
+use types::{sign_message, verify_message};
+
+mod config;
+mod handle;
+mod mcache;
+mod score;
+mod types;
+
+mod schema {
+    include!(concat!(env!("OUT_DIR"), "/gossipsub.rs"));
+}
+
+/// Log target for the file.
+const LOG_TARGET: &str = "litep2p::gossipsub";
+
+/// Outbound substream of a peer.
+#[allow(clippy::large_enum_variant)]
+enum OutboundState {
+    /// Substream is being opened.
+    Opening,
+
+    /// Substream is open and idle.
+    Open(Substream),
+
+    /// Queued RPCs are being sent over the substream.
+    Sending,
+
+    /// Substream couldn't be opened or it failed.
+    Closed,
+}
+
+/// Peer context.
+struct PeerContext {
+    /// Topics the peer is subscribed to.
+    topics: HashSet<TopicHash>,
+
+    /// Outbound substream.
+    outbound: OutboundState,
+
+    /// Encoded RPCs waiting to be sent.
+    queue: Vec<Bytes>,
+}
+
+/// Create RPC carrying a single subscription change.
+fn subscription_rpc(topic: &TopicHash, subscribe: bool) -> schema::Rpc {
+    schema::Rpc {
+        subscriptions: vec![schema::rpc::SubOpts {
+            subscribe: Some(subscribe),
+            topic_id: Some(topic.as_str().to_owned()),
+        }],
+        ..Default::default()
+    }
+}
+
+/// Gossipsub protocol.
+pub(crate) struct Gossipsub {
+    /// Transport service.
+    service: TransportService,
+
+    /// Gossipsub configuration.
+    config: Config,
+
+    /// Keypair used to sign published messages.
+    keypair: Keypair,
+
+    /// Local peer ID.
+    local_peer_id: PeerId,
+
+    /// Connected peers.
+    peers: HashMap<PeerId, PeerContext>,
+
+    /// Outbound substreams being opened.
+    pending_outbound: HashMap<SubstreamId, PeerId>,
+
+    /// Inbound substreams.
+    inbound: SubstreamSet<PeerId, Substream>,
+
+    /// Pending sends, returning the outbound substream once the RPCs have been sent.
+    pending_sends: FuturesStream<BoxFuture<'static, (PeerId, Result<Substream, SubstreamError>)>>,
+
+    /// Peers subscribed to each topic.
+    topic_peers: HashMap<TopicHash, HashSet<PeerId>>,
+
+    /// Topics the local node is subscribed to.
+    subscriptions: HashSet<TopicHash>,
+
+    /// Mesh peers of the subscribed topics.
+    mesh: HashMap<TopicHash, HashSet<PeerId>>,
+
+    /// Peers the messages of unsubscribed topics are published to.
+    fanout: HashMap<TopicHash, HashSet<PeerId>>,
+
+    /// When the local node last published to each fanout topic.
+    fanout_last_published: HashMap<TopicHash, Instant>,
+
+    /// Peers which may not be grafted to the mesh of a topic before the backoff expires.
+    backoffs: HashMap<TopicHash, HashMap<PeerId, Instant>>,
+
+    /// Cache of recent messages.
+    mcache: MessageCache,
+
+    /// Cache of seen message IDs.
+    seen: SeenCache,
+
+    /// Peer scores, if peer scoring is enabled.
+    score: Option<PeerScore>,
+
+    /// Peer score thresholds.
+    thresholds: PeerScoreThresholds,
+
+    /// Sequence number of the last published message.
+    sequence_number: u64,
+}
+
+impl Gossipsub {
+    /// Create new [`Gossipsub`] protocol.
+    pub(crate) fn new(service: TransportService, mut config: Config, keypair: Keypair) -> Self {
+        let (score, thresholds) = match config.peer_score.take() {
+            Some((params, thresholds)) => (Some(PeerScore::new(params)), thresholds),
+            None => (None, PeerScoreThresholds::default()),
+        };
+
+        Self {
+            local_peer_id: PeerId::from_public_key(&PublicKey::Ed25519(keypair.public())),
+            mcache: MessageCache::new(config.history_length, config.history_gossip),
+            seen: SeenCache::new(config.duplicate_cache_time),
+            sequence_number: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0u64, |duration| duration.as_nanos() as u64),
+            service,
+            config,
+            keypair,
+            peers: HashMap::new(),
+            pending_outbound: HashMap::new(),
+            inbound: SubstreamSet::new(),
+            pending_sends: FuturesStream::new(),
+            topic_peers: HashMap::new(),
+            subscriptions: HashSet::new(),
+            mesh: HashMap::new(),
+            fanout: HashMap::new(),
+            fanout_last_published: HashMap::new(),
+            backoffs: HashMap::new(),
+            score,
+            thresholds,
+        }
+    }
+
+    /// Get score of `peer`.
+    fn score(&self, peer: &PeerId) -> f64 {
+        self.score.as_ref().map_or(0.0, |score| score.score(peer))
+    }
+
+    /// Check if RPCs can be sent to `peer`.
+    fn is_writable(&self, peer: &PeerId) -> bool {
+        self.peers
+            .get(peer)
+            .is_some_and(|context| !std::matches!(context.outbound, OutboundState::Closed))
+    }
+
+    /// Check if `peer` may not be grafted to the mesh of `topic`.
+    fn in_backoff(&self, topic: &TopicHash, peer: &PeerId) -> bool {
+        self.backoffs.get(topic).is_some_and(|backoffs| backoffs.contains_key(peer))
+    }
+
+    /// Prevent `peer` from being grafted to the mesh of `topic` for `backoff`.
+    fn add_backoff(&mut self, topic: &TopicHash, peer: PeerId, backoff: Duration) {
+        let until = Instant::now() + backoff;
+        let entry = self.backoffs.entry(topic.clone()).or_default().entry(peer).or_insert(until);
+        *entry = (*entry).max(until);
+    }
+
+    /// Select up to `count` random peers subscribed to `topic` with a score of at least
+    /// `min_score`, excluding `exclude`.
+    fn select_peers(
+        &self,
+        topic: &TopicHash,
+        exclude: &HashSet<PeerId>,
+        min_score: f64,
+        count: usize,
+        respect_backoff: bool,
+    ) -> Vec<PeerId> {
+        self.topic_peers
+            .get(topic)
+            .into_iter()
+            .flatten()
+            .filter(|peer| !exclude.contains(peer) && self.is_writable(peer))
+            .filter(|peer| !respect_backoff || !self.in_backoff(topic, peer))
+            .filter(|peer| self.score(peer) >= min_score)
+            .copied()
+            .choose_multiple(&mut rand::thread_rng(), count)
+    }
+
+    /// `PRUNE` control message for `topic`.
+    fn prune_message(&self, topic: &TopicHash) -> schema::ControlPrune {
+        schema::ControlPrune {
+            topic_id: Some(topic.as_str().to_owned()),
+            peers: Vec::new(),
+            backoff: Some(self.config.prune_backoff.as_secs()),
+        }
+    }
+
+    /// Queue `rpc` for sending to `peer`.
+    fn send_rpc(&mut self, peer: PeerId, rpc: schema::Rpc) {
+        let payload = rpc.encode_to_vec();
+
+        if payload.len() > self.config.max_transmit_size {
+            tracing::warn!(
+                target: LOG_TARGET,
+                ?peer,
+                size = payload.len(),
+                "rpc exceeds maximum transmit size",
+            );
+            return;
+        }
+
+        match self.peers.get_mut(&peer) {
+            Some(context) if !std::matches!(context.outbound, OutboundState::Closed) => {
+                context.queue.push(payload.into());
+                self.flush(peer);
+            }
+            _ => tracing::trace!(target: LOG_TARGET, ?peer, "cannot send rpc to peer"),
+        }
+    }
+
+    /// Queue `control` for sending to `peer`.
+    fn send_control(&mut self, peer: PeerId, control: schema::ControlMessage) {
+        self.send_rpc(
+            peer,
+            schema::Rpc {
+                control: Some(control),
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Send queued RPCs of `peer` if the outbound substream is idle.
+    fn flush(&mut self, peer: PeerId) {
+        let Some(context) = self.peers.get_mut(&peer) else {
+            return;
+        };
+
+        if context.queue.is_empty() {
+            return;
+        }
+
+        match std::mem::replace(&mut context.outbound, OutboundState::Sending) {
+            OutboundState::Open(mut substream) => {
+                let rpcs = std::mem::take(&mut context.queue);
+
+                self.pending_sends.push(Box::pin(async move {
+                    for rpc in rpcs {
+                        if let Err(error) = substream.send_framed(rpc).await {
+                            return (peer, Err(error));
+                        }
+                    }
+
+                    (peer, Ok(substream))
+                }));
+            }
+            state => context.outbound = state,
+        }
+    }
+
+    /// Outbound substream of `peer` failed, remove the peer from the meshes of all topics.
+    fn on_outbound_closed(&mut self, peer: PeerId) {
+        if let Some(context) = self.peers.get_mut(&peer) {
+            context.outbound = OutboundState::Closed;
+            context.queue.clear();
+        }
+
+        for (topic, mesh) in self.mesh.iter_mut() {
+            if mesh.remove(&peer) {
+                if let Some(score) = self.score.as_mut() {
+                    score.prune(&peer, topic);
+                }
+            }
+        }
+        for fanout in self.fanout.values_mut() {
+            fanout.remove(&peer);
+        }
+    }
+
+    /// Connection established to remote peer.
+    fn on_connection_established(&mut self, peer: PeerId) {
+        tracing::trace!(target: LOG_TARGET, ?peer, "connection established");
+
+        if self.peers.contains_key(&peer) {
+            return;
+        }
+
+        let outbound = match self.service.open_substream(peer) {
+            Ok(substream_id) => {
+                self.pending_outbound.insert(substream_id, peer);
+                OutboundState::Opening
+            }
+            Err(error) => {
+                tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to open substream");
+                OutboundState::Closed
+            }
+        };
+
+        self.peers.insert(
+            peer,
+            PeerContext {
+                topics: HashSet::new(),
+                outbound,
+                queue: Vec::new(),
+            },
+        );
+        if let Some(score) = self.score.as_mut() {
+            score.add_peer(peer);
+        }
+
+        if !self.subscriptions.is_empty() {
+            let subscriptions = self
+                .subscriptions
+                .iter()
+                .map(|topic| schema::rpc::SubOpts {
+                    subscribe: Some(true),
+                    topic_id: Some(topic.as_str().to_owned()),
+                })
+                .collect();
+
+            self.send_rpc(
+                peer,
+                schema::Rpc {
+                    subscriptions,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
+    /// Connection closed to remote peer.
+    fn on_connection_closed(&mut self, peer: PeerId) {
+        tracing::trace!(target: LOG_TARGET, ?peer, "connection closed");
+
+        let Some(context) = self.peers.remove(&peer) else {
+            return;
+        };
+
+        for topic in context.topics {
+            if let Some(peers) = self.topic_peers.get_mut(&topic) {
+                peers.remove(&peer);
+
+                if peers.is_empty() {
+                    self.topic_peers.remove(&topic);
+                }
+            }
+        }
+        for mesh in self.mesh.values_mut() {
+            mesh.remove(&peer);
+        }
+        for fanout in self.fanout.values_mut() {
+            fanout.remove(&peer);
+        }
+
+        self.inbound.remove(&peer);
+        if let Some(score) = self.score.as_mut() {
+            score.remove_peer(&peer);
+        }
+    }
+
+    /// Outbound substream opened.
+    fn on_outbound_substream(&mut self, substream_id: SubstreamId, substream: Substream) {
+        let Some(peer) = self.pending_outbound.remove(&substream_id) else {
+            tracing::warn!(target: LOG_TARGET, ?substream_id, "unknown outbound substream");
+            return;
+        };
+
+        match self.peers.get_mut(&peer) {
+            Some(context) => {
+                context.outbound = OutboundState::Open(substream);
+                self.flush(peer);
+            }
+            None => tracing::trace!(target: LOG_TARGET, ?peer, "peer disconnected"),
+        }
+    }
+
+    /// Failed to open outbound substream.
+    fn on_substream_open_failure(&mut self, substream_id: SubstreamId, error: SubstreamError) {
+        let Some(peer) = self.pending_outbound.remove(&substream_id) else {
+            return;
+        };
+
+        tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to open substream");
+        self.on_outbound_closed(peer);
+    }
+
+    /// Inbound substream opened, replacing the previous inbound substream of the peer.
+    fn on_inbound_substream(&mut self, peer: PeerId, substream: Substream) {
+        tracing::trace!(target: LOG_TARGET, ?peer, "inbound substream opened");
+
+        self.inbound.remove(&peer);
+        self.inbound.insert(peer, substream);
+    }
+
+    /// RPCs sent to `peer`.
+    fn on_send_complete(&mut self, peer: PeerId, result: Result<Substream, SubstreamError>) {
+        match result {
+            Ok(substream) =>
+                if let Some(context) = self.peers.get_mut(&peer) {
+                    context.outbound = OutboundState::Open(substream);
+                    self.flush(peer);
+                },
+            Err(error) => {
+                tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to send rpc");
+                self.on_outbound_closed(peer);
+            }
+        }
+    }
+
+    /// Data received over the inbound substream of `peer`.
+    async fn on_inbound_data(&mut self, peer: PeerId, result: Result<BytesMut, SubstreamError>) {
+        let payload = match result {
+            Ok(payload) => payload,
+            Err(error) => {
+                tracing::trace!(target: LOG_TARGET, ?peer, ?error, "inbound substream closed");
+                self.inbound.remove(&peer);
+                return;
+            }
+        };
+
+        match schema::Rpc::decode(payload.freeze()) {
+            Ok(rpc) => self.on_rpc(peer, rpc).await,
+            Err(error) => tracing::debug!(target: LOG_TARGET, ?peer, ?error, "invalid rpc"),
+        }
+    }
+
+    /// RPC received from `peer`.
+    async fn on_rpc(&mut self, peer: PeerId, rpc: schema::Rpc) {
+        if self.score(&peer) < self.thresholds.graylist_threshold {
+            tracing::trace!(target: LOG_TARGET, ?peer, "ignore rpc from graylisted peer");
+            return;
+        }
+
+        self.on_subscriptions(peer, rpc.subscriptions).await;
+
+        for message in rpc.publish {
+            self.on_message(peer, message).await;
+        }
+
+        if let Some(control) = rpc.control {
+            self.on_control(peer, control);
+        }
+    }
+
+    /// Subscriptions of `peer` changed.
+    async fn on_subscriptions(&mut self, peer: PeerId, subscriptions: Vec<schema::rpc::SubOpts>) {
+        for subscription in subscriptions {
+            let Some(topic) = subscription.topic_id.map(TopicHash::from) else {
+                continue;
+            };
+            let Some(context) = self.peers.get_mut(&peer) else {
+                return;
+            };
+
+            let event = if subscription.subscribe.unwrap_or(false) {
+                if !context.topics.insert(topic.clone()) {
+                    continue;
+                }
+                self.topic_peers.entry(topic.clone()).or_default().insert(peer);
+
+                // graft the peer right away if the mesh of the topic isn't full
+                let graft = self.mesh.get(&topic).is_some_and(|mesh| {
+                    mesh.len() < self.config.mesh_n_low
+                        && self.is_writable(&peer)
+                        && !self.in_backoff(&topic, &peer)
+                        && self.score(&peer) >= 0.0
+                });
+
+                if graft {
+                    self.graft(peer, topic.clone());
+                }
+
+                GossipsubEvent::Subscribed { peer, topic }
+            } else {
+                if !context.topics.remove(&topic) {
+                    continue;
+                }
+
+                if let Some(peers) = self.topic_peers.get_mut(&topic) {
+                    peers.remove(&peer);
+
+                    if peers.is_empty() {
+                        self.topic_peers.remove(&topic);
+                    }
+                }
+                if self.mesh.get_mut(&topic).is_some_and(|mesh| mesh.remove(&peer)) {
+                    if let Some(score) = self.score.as_mut() {
+                        score.prune(&peer, &topic);
+                    }
+                }
+                if let Some(fanout) = self.fanout.get_mut(&topic) {
+                    fanout.remove(&peer);
+                }
+
+                GossipsubEvent::Unsubscribed { peer, topic }
+            };
+
+            tracing::trace!(target: LOG_TARGET, ?event, "subscription changed");
+            let _ = self.config.event_tx.send(event).await;
+        }
+    }
+
+    /// Add `peer` to the mesh of `topic` and send `GRAFT` to it.
+    fn graft(&mut self, peer: PeerId, topic: TopicHash) {
+        tracing::trace!(target: LOG_TARGET, ?peer, ?topic, "graft peer");
+
+        self.mesh.entry(topic.clone()).or_default().insert(peer);
+        if let Some(score) = self.score.as_mut() {
+            score.graft(peer, topic.clone());
+        }
+
+        self.send_control(
+            peer,
+            schema::ControlMessage {
+                graft: vec![schema::ControlGraft {
+                    topic_id: Some(topic.into_string()),
+                }],
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Message received from `propagation_source`.
+    async fn on_message(&mut self, propagation_source: PeerId, raw: schema::Message) {
+        let topic = TopicHash::from(raw.topic.clone());
+
+        if !self.subscriptions.contains(&topic) {
+            tracing::trace!(target: LOG_TARGET, ?topic, "message for unsubscribed topic");
+            return;
+        }
+
+        let Some(message) = verify_message(&raw) else {
+            tracing::debug!(
+                target: LOG_TARGET,
+                peer = ?propagation_source,
+                "invalid message received",
+            );
+
+            if let Some(score) = self.score.as_mut() {
+                score.invalid_message(propagation_source);
+            }
+            return;
+        };
+
+        if message.source == self.local_peer_id {
+            return;
+        }
+
+        let message_id = (self.config.message_id_fn)(&message);
+        if !self.seen.insert(message_id.clone()) {
+            tracing::trace!(target: LOG_TARGET, ?message_id, "duplicate message");
+            return;
+        }
+
+        tracing::trace!(
+            target: LOG_TARGET,
+            peer = ?propagation_source,
+            ?message_id,
+            "message received",
+        );
+
+        if let Some(score) = self.score.as_mut() {
+            score.first_message_delivery(propagation_source);
+        }
+        self.mcache.put(message_id.clone(), raw.clone());
+
+        let recipients = self
+            .mesh
+            .get(&topic)
+            .into_iter()
+            .flatten()
+            .filter(|peer| **peer != propagation_source && **peer != message.source)
+            .copied()
+            .collect::<Vec<_>>();
+
+        for peer in recipients {
+            self.send_rpc(
+                peer,
+                schema::Rpc {
+                    publish: vec![raw.clone()],
+                    ..Default::default()
+                },
+            );
+        }
+
+        let _ = self
+            .config
+            .event_tx
+            .send(GossipsubEvent::Message {
+                propagation_source,
+                message_id,
+                message,
+            })
+            .await;
+    }
+
+    /// Control message received from `peer`.
+    fn on_control(&mut self, peer: PeerId, control: schema::ControlMessage) {
+        let gossip_allowed = self.score(&peer) >= self.thresholds.gossip_threshold;
+        let mut response = schema::Rpc::default();
+        let mut response_control = schema::ControlMessage::default();
+
+        if gossip_allowed {
+            let mut wanted = HashSet::new();
+
+            for ihave in control.ihave {
+                let Some(topic) = ihave.topic_id.map(TopicHash::from) else {
+                    continue;
+                };
+
+                if !self.subscriptions.contains(&topic) {
+                    continue;
+                }
+
+                for id in ihave.message_ids {
+                    if wanted.len() >= self.config.max_ihave_length {
+                        break;
+                    }
+
+                    let id = MessageId::from(id);
+                    if !self.seen.contains(&id) {
+                        wanted.insert(id);
+                    }
+                }
+            }
+
+            if !wanted.is_empty() {
+                tracing::trace!(target: LOG_TARGET, ?peer, ?wanted, "request messages");
+
+                response_control.iwant.push(schema::ControlIWant {
+                    message_ids: wanted.into_iter().map(|id| id.as_bytes().to_vec()).collect(),
+                });
+            }
+
+            for id in control.iwant.into_iter().flat_map(|iwant| iwant.message_ids) {
+                let id = MessageId::from(id);
+
+                match self.mcache.get_for_peer(&id, peer) {
+                    Some((message, count)) if count <= self.config.gossip_retransmission =>
+                        response.publish.push(message.clone()),
+                    Some(_) => tracing::debug!(
+                        target: LOG_TARGET,
+                        ?peer,
+                        ?id,
+                        "message requested too many times",
+                    ),
+                    None => {}
+                }
+            }
+        }
+
+        for graft in control.graft {
+            let Some(topic) = graft.topic_id.map(TopicHash::from) else {
+                continue;
+            };
+
+            if !self.subscriptions.contains(&topic) {
+                response_control.prune.push(self.prune_message(&topic));
+                continue;
+            }
+
+            if self.mesh.get(&topic).is_some_and(|mesh| mesh.contains(&peer)) {
+                continue;
+            }
+
+            if self.in_backoff(&topic, &peer) {
+                tracing::debug!(target: LOG_TARGET, ?peer, ?topic, "graft during backoff");
+
+                if let Some(score) = self.score.as_mut() {
+                    score.add_penalty(peer);
+                }
+                response_control.prune.push(self.prune_message(&topic));
+                continue;
+            }
+
+            if self.score(&peer) < 0.0 {
+                response_control.prune.push(self.prune_message(&topic));
+                continue;
+            }
+
+            tracing::trace!(target: LOG_TARGET, ?peer, ?topic, "peer grafted");
+
+            self.mesh.entry(topic.clone()).or_default().insert(peer);
+            if let Some(score) = self.score.as_mut() {
+                score.graft(peer, topic);
+            }
+        }
+
+        for prune in control.prune {
+            let Some(topic) = prune.topic_id.map(TopicHash::from) else {
+                continue;
+            };
+
+            tracing::trace!(target: LOG_TARGET, ?peer, ?topic, "peer pruned");
+
+            if self.mesh.get_mut(&topic).is_some_and(|mesh| mesh.remove(&peer)) {
+                if let Some(score) = self.score.as_mut() {
+                    score.prune(&peer, &topic);
+                }
+            }
+
+            let backoff = prune.backoff.map_or(self.config.prune_backoff, Duration::from_secs);
+            self.add_backoff(&topic, peer, backoff);
+        }
+
+        if response_control != schema::ControlMessage::default() {
+            response.control = Some(response_control);
+        }
+
+        if response != schema::Rpc::default() {
+            self.send_rpc(peer, response);
+        }
+    }
+
+    /// Subscribe to `topic` and build its mesh.
+    fn on_subscribe(&mut self, topic: TopicHash) {
+        if !self.subscriptions.insert(topic.clone()) {
+            return;
+        }
+
+        tracing::debug!(target: LOG_TARGET, ?topic, "subscribe to topic");
+
+        let peers = self.peers.keys().copied().collect::<Vec<_>>();
+        for peer in peers {
+            self.send_rpc(peer, subscription_rpc(&topic, true));
+        }
+
+        // peers of the fanout are preferred for the mesh
+        let mut mesh = self.fanout.remove(&topic).unwrap_or_default();
+        self.fanout_last_published.remove(&topic);
+        mesh.retain(|peer| self.score(peer) >= 0.0 && !self.in_backoff(&topic, peer));
+
+        if mesh.len() < self.config.mesh_n {
+            let count = self.config.mesh_n - mesh.len();
+            let peers = self.select_peers(&topic, &mesh, 0.0, count, true);
+
+            mesh.extend(peers);
+        }
+
+        self.mesh.insert(topic.clone(), HashSet::new());
+        for peer in mesh {
+            self.graft(peer, topic.clone());
+        }
+    }
+
+    /// Unsubscribe from `topic` and prune its mesh.
+    fn on_unsubscribe(&mut self, topic: TopicHash) {
+        if !self.subscriptions.remove(&topic) {
+            return;
+        }
+
+        tracing::debug!(target: LOG_TARGET, ?topic, "unsubscribe from topic");
+
+        let peers = self.peers.keys().copied().collect::<Vec<_>>();
+        for peer in peers {
+            self.send_rpc(peer, subscription_rpc(&topic, false));
+        }
+
+        for peer in self.mesh.remove(&topic).unwrap_or_default() {
+            if let Some(score) = self.score.as_mut() {
+                score.prune(&peer, &topic);
+            }
+            self.add_backoff(&topic, peer, self.config.prune_backoff);
+
+            let prune = self.prune_message(&topic);
+            self.send_control(
+                peer,
+                schema::ControlMessage {
+                    prune: vec![prune],
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
+    /// Publish `data` to `topic`.
+    fn on_publish(&mut self, topic: TopicHash, data: Vec<u8>) -> Result<MessageId, GossipsubError> {
+        self.sequence_number = self.sequence_number.wrapping_add(1);
+
+        let message = GossipsubMessage {
+            source: self.local_peer_id,
+            data,
+            sequence_number: self.sequence_number,
+            topic: topic.clone(),
+        };
+        let rpc = schema::Rpc {
+            publish: vec![sign_message(
+                &self.keypair,
+                &topic,
+                message.data.clone(),
+                message.sequence_number,
+            )],
+            ..Default::default()
+        };
+
+        if rpc.encoded_len() > self.config.max_transmit_size {
+            return Err(GossipsubError::MessageTooLarge);
+        }
+
+        let message_id = (self.config.message_id_fn)(&message);
+        if self.seen.contains(&message_id) {
+            return Err(GossipsubError::Duplicate);
+        }
+
+        let recipients = if self.config.flood_publish {
+            self.topic_peers
+                .get(&topic)
+                .into_iter()
+                .flatten()
+                .filter(|peer| {
+                    self.is_writable(peer) && self.score(peer) >= self.thresholds.publish_threshold
+                })
+                .copied()
+                .collect::<HashSet<_>>()
+        } else if let Some(mesh) = self.mesh.get(&topic) {
+            mesh.clone()
+        } else {
+            let mut fanout = self.fanout.remove(&topic).unwrap_or_default();
+
+            if fanout.is_empty() {
+                let peers = self.select_peers(
+                    &topic,
+                    &fanout,
+                    self.thresholds.publish_threshold,
+                    self.config.mesh_n,
+                    false,
+                );
+                fanout.extend(peers);
+            }
+
+            self.fanout.insert(topic.clone(), fanout.clone());
+            self.fanout_last_published.insert(topic.clone(), Instant::now());
+            fanout
+        };
+
+        if recipients.is_empty() {
+            return Err(GossipsubError::InsufficientPeers);
+        }
+
+        tracing::trace!(
+            target: LOG_TARGET,
+            ?topic,
+            ?message_id,
+            peers = recipients.len(),
+            "publish message",
+        );
+
+        self.seen.insert(message_id.clone());
+        self.mcache.put(message_id.clone(), rpc.publish[0].clone());
+
+        for peer in recipients {
+            self.send_rpc(peer, rpc.clone());
+        }
+
+        Ok(message_id)
+    }
+
+    /// Maintain the meshes and fanouts, and emit gossip.
+    fn on_heartbeat(&mut self) {
+        if let Some(score) = self.score.as_mut() {
+            score.refresh();
+        }
+
+        let now = Instant::now();
+        for backoffs in self.backoffs.values_mut() {
+            backoffs.retain(|_, until| *until > now);
+        }
+        self.backoffs.retain(|_, backoffs| !backoffs.is_empty());
+
+        let mut grafts = Vec::new();
+        let mut prunes = Vec::new();
+
+        // repair the meshes
+        let topics = self.mesh.keys().cloned().collect::<Vec<_>>();
+        for topic in topics {
+            let mut mesh = self.mesh.remove(&topic).unwrap_or_default();
+
+            mesh.retain(|peer| {
+                let keep = self.score(peer) >= 0.0;
+                if !keep {
+                    prunes.push((*peer, topic.clone()));
+                }
+                keep
+            });
+
+            if mesh.len() < self.config.mesh_n_low {
+                let count = self.config.mesh_n - mesh.len();
+
+                for peer in self.select_peers(&topic, &mesh, 0.0, count, true) {
+                    mesh.insert(peer);
+                    grafts.push((peer, topic.clone()));
+                }
+            }
+
+            if mesh.len() > self.config.mesh_n_high {
+                // keep the peers with the highest scores, in random order for equal scores
+                let mut peers = mesh.iter().copied().collect::<Vec<_>>();
+                peers.shuffle(&mut rand::thread_rng());
+                peers.sort_by(|a, b| {
+                    self.score(b).partial_cmp(&self.score(a)).unwrap_or(Ordering::Equal)
+                });
+
+                for peer in peers.into_iter().skip(self.config.mesh_n) {
+                    mesh.remove(&peer);
+                    prunes.push((peer, topic.clone()));
+                }
+            }
+
+            self.mesh.insert(topic, mesh);
+        }
+
+        // expire fanouts the local node hasn't published to and top up the remaining ones
+        let fanout_ttl = self.config.fanout_ttl;
+        let expired = self
+            .fanout_last_published
+            .iter()
+            .filter(|(_, published)| now.duration_since(**published) > fanout_ttl)
+            .map(|(topic, _)| topic.clone())
+            .collect::<Vec<_>>();
+
+        for topic in expired {
+            self.fanout_last_published.remove(&topic);
+            self.fanout.remove(&topic);
+        }
+
+        let topics = self.fanout.keys().cloned().collect::<Vec<_>>();
+        for topic in topics {
+            let mut fanout = self.fanout.remove(&topic).unwrap_or_default();
+
+            fanout.retain(|peer| {
+                self.topic_peers.get(&topic).is_some_and(|peers| peers.contains(peer))
+                    && self.score(peer) >= self.thresholds.publish_threshold
+            });
+
+            if fanout.len() < self.config.mesh_n {
+                let count = self.config.mesh_n - fanout.len();
+                let peers = self.select_peers(
+                    &topic,
+                    &fanout,
+                    self.thresholds.publish_threshold,
+                    count,
+                    false,
+                );
+                fanout.extend(peers);
+            }
+
+            self.fanout.insert(topic, fanout);
+        }
+
+        // emit gossip to peers outside of the meshes and fanouts
+        let mut controls = HashMap::<PeerId, schema::ControlMessage>::new();
+        let topics = self.mesh.keys().chain(self.fanout.keys()).cloned().collect::<HashSet<_>>();
+
+        for topic in topics {
+            let mut ids = self.mcache.gossip_ids(&topic);
+            if ids.is_empty() {
+                continue;
+            }
+
+            if ids.len() > self.config.max_ihave_length {
+                ids.shuffle(&mut rand::thread_rng());
+                ids.truncate(self.config.max_ihave_length);
+            }
+
+            let exclude = self
+                .mesh
+                .get(&topic)
+                .into_iter()
+                .chain(self.fanout.get(&topic))
+                .flatten()
+                .copied()
+                .collect::<HashSet<_>>();
+            let candidates = self
+                .topic_peers
+                .get(&topic)
+                .map_or(0usize, |peers| peers.len().saturating_sub(exclude.len()));
+            let count = self
+                .config
+                .gossip_lazy
+                .max((self.config.gossip_factor * candidates as f64) as usize);
+
+            for peer in self.select_peers(
+                &topic,
+                &exclude,
+                self.thresholds.gossip_threshold,
+                count,
+                false,
+            ) {
+                controls.entry(peer).or_default().ihave.push(schema::ControlIHave {
+                    topic_id: Some(topic.as_str().to_owned()),
+                    message_ids: ids.iter().map(|id| id.as_bytes().to_vec()).collect(),
+                });
+            }
+        }
+
+        for (peer, topic) in grafts {
+            tracing::trace!(target: LOG_TARGET, ?peer, ?topic, "graft peer");
+
+            if let Some(score) = self.score.as_mut() {
+                score.graft(peer, topic.clone());
+            }
+            controls.entry(peer).or_default().graft.push(schema::ControlGraft {
+                topic_id: Some(topic.into_string()),
+            });
+        }
+
+        for (peer, topic) in prunes {
+            tracing::trace!(target: LOG_TARGET, ?peer, ?topic, "prune peer");
+
+            if let Some(score) = self.score.as_mut() {
+                score.prune(&peer, &topic);
+            }
+            self.add_backoff(&topic, peer, self.config.prune_backoff);
+
+            let prune = self.prune_message(&topic);
+            controls.entry(peer).or_default().prune.push(prune);
+        }
+
+        for (peer, control) in controls {
+            self.send_control(peer, control);
+        }
+
+        self.mcache.shift();
+        self.seen.expire();
+    }
+
+    /// Handle command received from the user.
+    fn on_command(&mut self, command: GossipsubCommand) {
+        match command {
+            GossipsubCommand::Subscribe { topic } => self.on_subscribe(topic),
+            GossipsubCommand::Unsubscribe { topic } => self.on_unsubscribe(topic),
+            GossipsubCommand::Publish { topic, data, tx } => {
+                let _ = tx.send(self.on_publish(topic, data));
+            }
+        }
+    }
+
+    /// Handle event received from the transport service.
+    fn on_service_event(&mut self, event: TransportEvent) {
+        match event {
+            TransportEvent::ConnectionEstablished { peer, .. } =>
+                self.on_connection_established(peer),
+            TransportEvent::ConnectionClosed { peer } => self.on_connection_closed(peer),
+            TransportEvent::SubstreamOpened {
+                peer,
+                direction,
+                substream,
+                ..
+            } => match direction {
+                Direction::Inbound => self.on_inbound_substream(peer, substream),
+                Direction::Outbound(substream_id) =>
+                    self.on_outbound_substream(substream_id, substream),
+            },
+            TransportEvent::SubstreamOpenFailure { substream, error } =>
+                self.on_substream_open_failure(substream, error),
+            TransportEvent::DialFailure { .. } => {}
+        }
+    }
+
+    /// Start [`Gossipsub`] event loop.
+    pub async fn run(mut self) {
+        tracing::debug!(target: LOG_TARGET, "starting gossipsub event loop");
+
+        let mut heartbeat = tokio::time::interval(self.config.heartbeat_interval);
+
+        loop {
+            tokio::select! {
+                event = self.service.next() => match event {
+                    None => {
+                        tracing::warn!(target: LOG_TARGET, "transport service stream ended, terminating gossipsub event loop");
+                        return
+                    },
+                    Some(event) => self.on_service_event(event),
+                },
+                command = self.config.cmd_rx.recv() => match command {
+                    None => {
+                        tracing::debug!(target: LOG_TARGET, "user handle dropped, terminating gossipsub event loop");
+                        return
+                    },
+                    Some(command) => self.on_command(command),
+                },
+                Some((peer, result)) = self.inbound.next() =>
+                    self.on_inbound_data(peer, result).await,
+                Some((peer, result)) = self.pending_sends.next() =>
+                    self.on_send_complete(peer, result),
+                _ = heartbeat.tick() => self.on_heartbeat(),
+            }
+        }
+    }
+}
@@ -0,0 +1,371 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Peer scoring.
+//!
+//! Implements the time in mesh (P1), first message deliveries (P2), invalid messages (P4) and
+//! behaviour penalty (P7) components of the
+//! [gossipsub v1.1 peer score](https://github.com/libp2p/specs/blob/master/pubsub/gossipsub/gossipsub-v1.1.md#peer-scoring).
+//! The parameters are shared by all topics.
+
+use crate::{protocol::libp2p::gossipsub::TopicHash, PeerId};
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// Peer score parameters.
+#[derive(Debug, Clone)]
+pub struct PeerScoreParams {
+    /// Weight of the time the peer has spent in the mesh of a topic.
+    pub time_in_mesh_weight: f64,
+
+    /// Time in mesh is counted in multiples of this quantum.
+    pub time_in_mesh_quantum: Duration,
+
+    /// Maximum value of the time in mesh counter, per topic.
+    pub time_in_mesh_cap: f64,
+
+    /// Weight of the messages first delivered by the peer.
+    pub first_message_deliveries_weight: f64,
+
+    /// Decay of the first message deliveries counter.
+    pub first_message_deliveries_decay: f64,
+
+    /// Maximum value of the first message deliveries counter.
+    pub first_message_deliveries_cap: f64,
+
+    /// Weight of the invalid messages sent by the peer, applied to the square of the counter.
+    ///
+    /// Must be negative.
+    pub invalid_message_deliveries_weight: f64,
+
+    /// Decay of the invalid messages counter.
+    pub invalid_message_deliveries_decay: f64,
+
+    /// Weight of the behaviour penalty, applied to the square of the penalty exceeding
+    /// `behaviour_penalty_threshold`.
+    ///
+    /// Must be negative.
+    pub behaviour_penalty_weight: f64,
+
+    /// Behaviour penalty which is tolerated before it affects the score.
+    pub behaviour_penalty_threshold: f64,
+
+    /// Decay of the behaviour penalty.
+    pub behaviour_penalty_decay: f64,
+
+    /// Interval at which the counters are decayed.
+    pub decay_interval: Duration,
+
+    /// Counters below this value are reset to zero when decayed.
+    pub decay_to_zero: f64,
+
+    /// How long the score of a disconnected peer is remembered.
+    pub retain_score: Duration,
+}
+
+impl Default for PeerScoreParams {
+    fn default() -> Self {
+        Self {
+            time_in_mesh_weight: 0.01,
+            time_in_mesh_quantum: Duration::from_secs(1),
+            time_in_mesh_cap: 3600.0,
+            first_message_deliveries_weight: 1.0,
+            first_message_deliveries_decay: 0.5,
+            first_message_deliveries_cap: 2000.0,
+            invalid_message_deliveries_weight: -100.0,
+            invalid_message_deliveries_decay: 0.3,
+            behaviour_penalty_weight: -10.0,
+            behaviour_penalty_threshold: 0.0,
+            behaviour_penalty_decay: 0.2,
+            decay_interval: Duration::from_secs(1),
+            decay_to_zero: 0.1,
+            retain_score: Duration::from_secs(3600),
+        }
+    }
+}
+
+/// Peer score thresholds.
+#[derive(Debug, Clone)]
+pub struct PeerScoreThresholds {
+    /// Gossip isn't sent to or accepted from peers below this score.
+    pub gossip_threshold: f64,
+
+    /// Messages published by the local node aren't flooded to peers below this score.
+    pub publish_threshold: f64,
+
+    /// All messages of peers below this score are ignored.
+    pub graylist_threshold: f64,
+}
+
+impl Default for PeerScoreThresholds {
+    fn default() -> Self {
+        Self {
+            gossip_threshold: -10.0,
+            publish_threshold: -50.0,
+            graylist_threshold: -80.0,
+        }
+    }
+}
+
+/// Score counters of a peer.
+#[derive(Debug, Default)]
+struct PeerStats {
+    /// When the peer joined the mesh of each topic.
+    mesh_since: HashMap<TopicHash, Instant>,
+
+    /// First message deliveries counter.
+    first_message_deliveries: f64,
+
+    /// Invalid messages counter.
+    invalid_message_deliveries: f64,
+
+    /// Behaviour penalty counter.
+    behaviour_penalty: f64,
+
+    /// When the stats of the disconnected peer are forgotten.
+    expires: Option<Instant>,
+}
+
+/// Peer scores.
+pub(super) struct PeerScore {
+    /// Score parameters.
+    params: PeerScoreParams,
+
+    /// Score counters of each peer.
+    stats: HashMap<PeerId, PeerStats>,
+
+    /// When the counters were last decayed.
+    last_decay: Instant,
+}
+
+impl PeerScore {
+    /// Create new [`PeerScore`].
+    pub(super) fn new(params: PeerScoreParams) -> Self {
+        Self {
+            params,
+            stats: HashMap::new(),
+            last_decay: Instant::now(),
+        }
+    }
+
+    /// Get score of `peer`.
+    pub(super) fn score(&self, peer: &PeerId) -> f64 {
+        let Some(stats) = self.stats.get(peer) else {
+            return 0.0;
+        };
+        let params = &self.params;
+        let now = Instant::now();
+
+        let time_in_mesh = stats
+            .mesh_since
+            .values()
+            .map(|since| {
+                let quanta = now.duration_since(*since).as_secs_f64()
+                    / params.time_in_mesh_quantum.as_secs_f64();
+                quanta.min(params.time_in_mesh_cap)
+            })
+            .sum::<f64>();
+        let excess_penalty =
+            (stats.behaviour_penalty - params.behaviour_penalty_threshold).max(0.0);
+
+        time_in_mesh * params.time_in_mesh_weight
+            + stats.first_message_deliveries * params.first_message_deliveries_weight
+            + stats.invalid_message_deliveries.powi(2) * params.invalid_message_deliveries_weight
+            + excess_penalty.powi(2) * params.behaviour_penalty_weight
+    }
+
+    /// Peer connected.
+    pub(super) fn add_peer(&mut self, peer: PeerId) {
+        self.stats.entry(peer).or_default().expires = None;
+    }
+
+    /// Peer disconnected, remember its score for `retain_score`.
+    pub(super) fn remove_peer(&mut self, peer: &PeerId) {
+        if let Some(stats) = self.stats.get_mut(peer) {
+            stats.mesh_since.clear();
+            stats.expires = Some(Instant::now() + self.params.retain_score);
+        }
+    }
+
+    /// Peer was added to the mesh of `topic`.
+    pub(super) fn graft(&mut self, peer: PeerId, topic: TopicHash) {
+        self.stats.entry(peer).or_default().mesh_since.insert(topic, Instant::now());
+    }
+
+    /// Peer was removed from the mesh of `topic`.
+    pub(super) fn prune(&mut self, peer: &PeerId, topic: &TopicHash) {
+        if let Some(stats) = self.stats.get_mut(peer) {
+            stats.mesh_since.remove(topic);
+        }
+    }
+
+    /// Peer was the first to deliver a valid message.
+    pub(super) fn first_message_delivery(&mut self, peer: PeerId) {
+        let stats = self.stats.entry(peer).or_default();
+        stats.first_message_deliveries =
+            (stats.first_message_deliveries + 1.0).min(self.params.first_message_deliveries_cap);
+    }
+
+    /// Peer sent an invalid message.
+    pub(super) fn invalid_message(&mut self, peer: PeerId) {
+        self.stats.entry(peer).or_default().invalid_message_deliveries += 1.0;
+    }
+
+    /// Peer misbehaved.
+    pub(super) fn add_penalty(&mut self, peer: PeerId) {
+        self.stats.entry(peer).or_default().behaviour_penalty += 1.0;
+    }
+
+    /// Decay the counters if `decay_interval` has passed and forget expired peers.
+    pub(super) fn refresh(&mut self) {
+        let now = Instant::now();
+        if now.duration_since(self.last_decay) < self.params.decay_interval {
+            return;
+        }
+        self.last_decay = now;
+
+        let params = &self.params;
+        let decay = |counter: &mut f64, factor: f64| {
+            *counter *= factor;
+            if *counter < params.decay_to_zero {
+                *counter = 0.0;
+            }
+        };
+
+        self.stats.retain(|_, stats| {
+            decay(
+                &mut stats.first_message_deliveries,
+                params.first_message_deliveries_decay,
+            );
+            decay(
+                &mut stats.invalid_message_deliveries,
+                params.invalid_message_deliveries_decay,
+            );
+            decay(&mut stats.behaviour_penalty, params.behaviour_penalty_decay);
+
+            stats.expires.map_or(true, |expires| expires > now)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> PeerScoreParams {
+        PeerScoreParams {
+            decay_interval: Duration::ZERO,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn unknown_peer_has_zero_score() {
+        let score = PeerScore::new(params());
+
+        assert_eq!(score.score(&PeerId::random()), 0.0);
+    }
+
+    #[test]
+    fn first_deliveries_increase_score() {
+        let mut score = PeerScore::new(params());
+        let peer = PeerId::random();
+
+        score.add_peer(peer);
+        score.first_message_delivery(peer);
+        score.first_message_delivery(peer);
+        assert_eq!(score.score(&peer), 2.0);
+
+        score.refresh();
+        assert_eq!(score.score(&peer), 1.0);
+    }
+
+    #[test]
+    fn invalid_messages_and_penalties_decrease_score() {
+        let mut score = PeerScore::new(params());
+        let peer = PeerId::random();
+
+        score.add_peer(peer);
+        score.invalid_message(peer);
+        assert_eq!(score.score(&peer), -100.0);
+
+        score.invalid_message(peer);
+        assert_eq!(score.score(&peer), -400.0);
+
+        let other = PeerId::random();
+        score.add_peer(other);
+        score.add_penalty(other);
+        score.add_penalty(other);
+        assert_eq!(score.score(&other), -40.0);
+
+        // decayed counters which drop below `decay_to_zero` are reset
+        for _ in 0..10 {
+            score.refresh();
+        }
+        assert_eq!(score.score(&peer), 0.0);
+        assert_eq!(score.score(&other), 0.0);
+    }
+
+    #[test]
+    fn time_in_mesh_increases_score() {
+        let mut score = PeerScore::new(PeerScoreParams {
+            time_in_mesh_quantum: Duration::from_millis(10),
+            ..params()
+        });
+        let peer = PeerId::random();
+        let topic = TopicHash::from("topic");
+
+        score.add_peer(peer);
+        score.graft(peer, topic.clone());
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(score.score(&peer) > 0.0);
+
+        score.prune(&peer, &topic);
+        assert_eq!(score.score(&peer), 0.0);
+    }
+
+    #[test]
+    fn score_retained_after_disconnect() {
+        let mut score = PeerScore::new(params());
+        let peer = PeerId::random();
+
+        score.add_peer(peer);
+        score.invalid_message(peer);
+        score.remove_peer(&peer);
+        assert_eq!(score.score(&peer), -100.0);
+
+        // reconnecting doesn't reset the score
+        score.add_peer(peer);
+        assert_eq!(score.score(&peer), -100.0);
+
+        let mut score = PeerScore::new(PeerScoreParams {
+            retain_score: Duration::ZERO,
+            ..params()
+        });
+        score.add_peer(peer);
+        score.invalid_message(peer);
+        score.remove_peer(&peer);
+        score.refresh();
+        assert_eq!(score.score(&peer), 0.0);
+    }
+}
@@ -0,0 +1,258 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Gossipsub types.
+
+use crate::{
+    crypto::{ed25519::Keypair, PublicKey},
+    protocol::libp2p::gossipsub::schema,
+    PeerId,
+};
+
+use multihash::Multihash;
+use prost::Message;
+
+use std::fmt;
+
+/// Prefix of the data signed by the publisher of a message.
+const SIGNING_PREFIX: &[u8] = b"libp2p-pubsub:";
+
+/// Multihash code of identity hashes.
+const IDENTITY_CODE: u64 = 0x00;
+
+/// Gossipsub topic.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TopicHash(String);
+
+impl TopicHash {
+    /// Create new [`TopicHash`] from a raw topic string.
+    pub fn from_raw(topic: impl Into<String>) -> Self {
+        Self(topic.into())
+    }
+
+    /// Get the topic as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Convert the topic into a string.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl From<&str> for TopicHash {
+    fn from(topic: &str) -> Self {
+        Self(topic.to_owned())
+    }
+}
+
+impl From<String> for TopicHash {
+    fn from(topic: String) -> Self {
+        Self(topic)
+    }
+}
+
+impl fmt::Display for TopicHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Message ID.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MessageId(Vec<u8>);
+
+impl MessageId {
+    /// Create new [`MessageId`].
+    pub fn new(id: &[u8]) -> Self {
+        Self(id.to_vec())
+    }
+
+    /// Get the message ID as bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<Vec<u8>> for MessageId {
+    fn from(id: Vec<u8>) -> Self {
+        Self(id)
+    }
+}
+
+impl From<String> for MessageId {
+    fn from(id: String) -> Self {
+        Self(id.into_bytes())
+    }
+}
+
+/// Gossipsub message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GossipsubMessage {
+    /// Peer which published the message.
+    pub source: PeerId,
+
+    /// Message payload.
+    pub data: Vec<u8>,
+
+    /// Sequence number of the message, unique for the publisher.
+    pub sequence_number: u64,
+
+    /// Topic the message was published to.
+    pub topic: TopicHash,
+}
+
+/// Function used to compute the ID of a message.
+pub type MessageIdFn = fn(&GossipsubMessage) -> MessageId;
+
+/// Default message ID function which concatenates the publisher and the sequence number.
+///
+/// This is the message ID used by other libp2p implementations by default.
+pub fn default_message_id(message: &GossipsubMessage) -> MessageId {
+    MessageId::from(format!(
+        "{}{}",
+        message.source.to_base58(),
+        message.sequence_number
+    ))
+}
+
+/// Data signed by the publisher of `message`.
+fn signing_payload(message: &schema::Message) -> Vec<u8> {
+    let unsigned = schema::Message {
+        signature: None,
+        key: None,
+        ..message.clone()
+    };
+
+    let mut payload = SIGNING_PREFIX.to_vec();
+    payload.extend_from_slice(&unsigned.encode_to_vec());
+    payload
+}
+
+/// Create a message signed with `keypair`.
+///
+/// The public key is not included as it's inlined in the peer ID of ed25519 keys.
+pub(super) fn sign_message(
+    keypair: &Keypair,
+    topic: &TopicHash,
+    data: Vec<u8>,
+    sequence_number: u64,
+) -> schema::Message {
+    let source = PeerId::from_public_key(&PublicKey::Ed25519(keypair.public()));
+    let mut message = schema::Message {
+        from: Some(source.to_bytes()),
+        data: Some(data),
+        seqno: Some(sequence_number.to_be_bytes().to_vec()),
+        topic: topic.as_str().to_owned(),
+        signature: None,
+        key: None,
+    };
+    message.signature = Some(keypair.sign(&signing_payload(&message)));
+
+    message
+}
+
+/// Public key of `source`, either from the message or inlined in the peer ID.
+fn source_public_key(source: &PeerId, key: Option<&[u8]>) -> Option<PublicKey> {
+    match key {
+        Some(key) => {
+            let public_key = PublicKey::from_protobuf_encoding(key).ok()?;
+            (PeerId::from_public_key(&public_key) == *source).then_some(public_key)
+        }
+        None => {
+            let multihash: &Multihash = source.as_ref();
+
+            (multihash.code() == IDENTITY_CODE)
+                .then(|| PublicKey::from_protobuf_encoding(multihash.digest()).ok())
+                .flatten()
+        }
+    }
+}
+
+/// Verify the signature of `message` and convert it into a [`GossipsubMessage`].
+///
+/// Returns `None` if the message is malformed or the signature is invalid.
+pub(super) fn verify_message(message: &schema::Message) -> Option<GossipsubMessage> {
+    let source = PeerId::from_bytes(message.from.as_ref()?).ok()?;
+    let sequence_number = u64::from_be_bytes(message.seqno.as_ref()?.as_slice().try_into().ok()?);
+    let public_key = source_public_key(&source, message.key.as_deref())?;
+
+    if !public_key.verify(&signing_payload(message), message.signature.as_ref()?) {
+        return None;
+    }
+
+    Some(GossipsubMessage {
+        source,
+        data: message.data.clone().unwrap_or_default(),
+        sequence_number,
+        topic: TopicHash::from(message.topic.clone()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signed_message_verifies() {
+        let keypair = Keypair::generate();
+        let topic = TopicHash::from("topic");
+        let message = sign_message(&keypair, &topic, vec![1, 2, 3], 1337);
+        let verified = verify_message(&message).unwrap();
+
+        assert_eq!(
+            verified.source,
+            PeerId::from_public_key(&PublicKey::Ed25519(keypair.public()))
+        );
+        assert_eq!(verified.data, vec![1, 2, 3]);
+        assert_eq!(verified.sequence_number, 1337);
+        assert_eq!(verified.topic, topic);
+    }
+
+    #[test]
+    fn tampered_message_rejected() {
+        let keypair = Keypair::generate();
+        let mut message = sign_message(&keypair, &TopicHash::from("topic"), vec![1, 2, 3], 1);
+        message.data = Some(vec![1, 2, 4]);
+
+        assert!(verify_message(&message).is_none());
+    }
+
+    #[test]
+    fn message_from_other_peer_rejected() {
+        let keypair = Keypair::generate();
+        let mut message = sign_message(&keypair, &TopicHash::from("topic"), vec![1, 2, 3], 1);
+        message.from = Some(
+            PeerId::from_public_key(&PublicKey::Ed25519(Keypair::generate().public())).to_bytes(),
+        );
+
+        assert!(verify_message(&message).is_none());
+    }
+
+    #[test]
+    fn unsigned_message_rejected() {
+        let keypair = Keypair::generate();
+        let mut message = sign_message(&keypair, &TopicHash::from("topic"), vec![1, 2, 3], 1);
+        message.signature = None;
+
+        assert!(verify_message(&message).is_none());
+    }
+}
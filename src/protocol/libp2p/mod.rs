@@ -20,7 +20,14 @@
 
 //! Supported [`libp2p`](https://libp2p.io/) protocols.
 
+pub mod autonat;
 pub mod bitswap;
+pub mod dcutr;
+pub mod floodsub;
+pub mod gossipsub;
 pub mod identify;
 pub mod kademlia;
+pub mod perf;
 pub mod ping;
+pub mod relay;
+pub mod rendezvous;
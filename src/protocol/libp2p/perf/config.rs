@@ -0,0 +1,125 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use crate::{
+    codec::ProtocolCodec,
+    protocol::libp2p::perf::{PerfCommand, PerfEvent, PerfHandle},
+    types::protocol::ProtocolName,
+    DEFAULT_CHANNEL_SIZE,
+};
+
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+use std::time::Duration;
+
+/// Perf protocol name.
+pub const PROTOCOL_NAME: &str = "/perf/1.0.0";
+
+/// Default interval between progress reports of a run.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Perf configuration.
+pub struct Config {
+    /// Protocol name.
+    pub(crate) protocol: ProtocolName,
+
+    /// Codec used by the protocol.
+    pub(crate) codec: ProtocolCodec,
+
+    /// Whether the local node serves perf runs of remote peers.
+    pub(crate) server: bool,
+
+    /// Interval between progress reports of a run.
+    pub(crate) progress_interval: Duration,
+
+    /// TX channel for sending events to the user.
+    pub(crate) event_tx: Sender<PerfEvent>,
+
+    /// RX channel for receiving commands from the user.
+    pub(crate) cmd_rx: Receiver<PerfCommand>,
+}
+
+impl Config {
+    /// Create new [`Config`] with default values.
+    ///
+    /// The perf server is disabled. Returns a config that is given to `Litep2pConfig` and a
+    /// [`PerfHandle`].
+    pub fn default() -> (Self, PerfHandle) {
+        ConfigBuilder::new().build()
+    }
+}
+
+/// Perf configuration builder.
+pub struct ConfigBuilder {
+    /// Whether the local node serves perf runs of remote peers.
+    server: bool,
+
+    /// Interval between progress reports of a run.
+    progress_interval: Duration,
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigBuilder {
+    /// Create new default [`Config`] which can be modified by the user.
+    pub fn new() -> Self {
+        Self {
+            server: false,
+            progress_interval: PROGRESS_INTERVAL,
+        }
+    }
+
+    /// Serve perf runs of remote peers.
+    ///
+    /// The server sends back as many bytes as the remote peer requests, so it should only be
+    /// enabled on nodes used for benchmarking.
+    pub fn with_server(mut self, server: bool) -> Self {
+        self.server = server;
+        self
+    }
+
+    /// Set interval between progress reports of a run.
+    pub fn with_progress_interval(mut self, progress_interval: Duration) -> Self {
+        self.progress_interval = progress_interval;
+        self
+    }
+
+    /// Build [`Config`].
+    pub fn build(self) -> (Config, PerfHandle) {
+        let (event_tx, event_rx) = channel(DEFAULT_CHANNEL_SIZE);
+        let (cmd_tx, cmd_rx) = channel(DEFAULT_CHANNEL_SIZE);
+
+        (
+            Config {
+                protocol: ProtocolName::from(PROTOCOL_NAME),
+                codec: ProtocolCodec::Unspecified,
+                server: self.server,
+                progress_interval: self.progress_interval,
+                event_tx,
+                cmd_rx,
+            },
+            PerfHandle::new(event_rx, cmd_tx),
+        )
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Handle for communicating with the perf protocol implementation.
+
+use crate::{error::PerfError, PeerId};
+
+use tokio::sync::mpsc::{Receiver, Sender};
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// ID of a perf run.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct RunId(usize);
+
+/// Parameters of a perf run.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RunParams {
+    /// Number of bytes uploaded to the remote peer.
+    pub to_send: u64,
+
+    /// Number of bytes downloaded from the remote peer.
+    pub to_receive: u64,
+}
+
+/// Progress of an ongoing perf run.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RunProgress {
+    /// Bytes uploaded so far.
+    pub sent: u64,
+
+    /// Bytes downloaded so far.
+    pub received: u64,
+
+    /// Time elapsed since the run started.
+    pub elapsed: Duration,
+}
+
+/// Statistics of a completed perf run.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RunStats {
+    /// Bytes uploaded.
+    pub sent: u64,
+
+    /// Bytes downloaded.
+    pub received: u64,
+
+    /// Time it took to upload the data.
+    pub upload: Duration,
+
+    /// Time it took to download the data, measured from the end of the upload.
+    pub download: Duration,
+}
+
+impl RunStats {
+    /// Upload throughput in bytes per second.
+    pub fn upload_throughput(&self) -> f64 {
+        self.sent as f64 / self.upload.as_secs_f64()
+    }
+
+    /// Download throughput in bytes per second.
+    pub fn download_throughput(&self) -> f64 {
+        self.received as f64 / self.download.as_secs_f64()
+    }
+}
+
+/// Events emitted by the perf protocol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PerfEvent {
+    /// Intermediate progress of a run.
+    Progress {
+        /// Run ID.
+        run: RunId,
+
+        /// Remote peer.
+        peer: PeerId,
+
+        /// Progress of the run.
+        progress: RunProgress,
+    },
+
+    /// Run completed.
+    Finished {
+        /// Run ID.
+        run: RunId,
+
+        /// Remote peer.
+        peer: PeerId,
+
+        /// Statistics of the run.
+        stats: RunStats,
+    },
+
+    /// Run failed.
+    Failed {
+        /// Run ID.
+        run: RunId,
+
+        /// Remote peer.
+        peer: PeerId,
+
+        /// Error.
+        error: PerfError,
+    },
+}
+
+/// Commands sent from the user to `Perf`.
+#[derive(Debug)]
+pub(crate) enum PerfCommand {
+    /// Start a perf run.
+    Run {
+        /// Run ID.
+        run: RunId,
+
+        /// Remote peer.
+        peer: PeerId,
+
+        /// Run parameters.
+        params: RunParams,
+    },
+}
+
+/// Handle for communicating with the perf protocol.
+pub struct PerfHandle {
+    /// RX channel for receiving perf events.
+    event_rx: Receiver<PerfEvent>,
+
+    /// TX channel for sending commands to `Perf`.
+    cmd_tx: Sender<PerfCommand>,
+
+    /// ID of the next run.
+    next_run_id: usize,
+}
+
+impl PerfHandle {
+    /// Create new [`PerfHandle`].
+    pub(super) fn new(event_rx: Receiver<PerfEvent>, cmd_tx: Sender<PerfCommand>) -> Self {
+        Self {
+            event_rx,
+            cmd_tx,
+            next_run_id: 0usize,
+        }
+    }
+
+    /// Start a perf run against `peer`.
+    ///
+    /// The peer is dialed if it's not connected and it must have enabled the perf server.
+    /// The progress and the result of the run are reported as [`PerfEvent`]s.
+    pub async fn run(&mut self, peer: PeerId, params: RunParams) -> RunId {
+        let run = RunId(self.next_run_id);
+        self.next_run_id += 1;
+
+        let _ = self.cmd_tx.send(PerfCommand::Run { run, peer, params }).await;
+
+        run
+    }
+}
+
+impl futures::Stream for PerfHandle {
+    type Item = PerfEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.event_rx).poll_recv(cx)
+    }
+}
@@ -0,0 +1,392 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! [Perf](https://github.com/libp2p/specs/blob/master/perf/perf.md) implementation.
+//!
+//! The client opens a substream and sends the number of bytes it wants to download as a
+//! big-endian `u64`, followed by the uploaded data. Once the client has closed its side of the
+//! substream, the server sends back the requested number of bytes and closes the substream.
+//!
+//! The server is opt-in. Together with [`BandwidthSink`](crate::BandwidthSink), the protocol
+//! can be used to benchmark the throughput of each transport.
+
+use crate::{
+    error::{ImmediateDialError, PerfError, SubstreamError},
+    protocol::{Direction, TransportEvent, TransportService},
+    substream::Substream,
+    types::SubstreamId,
+    utils::futures_stream::FuturesStream,
+    PeerId,
+};
+
+use futures::{future::BoxFuture, StreamExt};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    sync::mpsc::Sender,
+};
+
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    time::{Duration, Instant},
+};
+
+pub use config::{Config, ConfigBuilder, PROTOCOL_NAME};
+pub use handle::{PerfEvent, PerfHandle, RunId, RunParams, RunProgress, RunStats};
+
+pub(crate) use handle::PerfCommand;
+
+mod config;
+mod handle;
+
+/// Log target for the file.
+const LOG_TARGET: &str = "litep2p::perf";
+
+/// Size of the buffer used for sending and receiving data.
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// Reports the progress of a run to the user.
+struct ProgressReporter {
+    /// Run ID.
+    run: RunId,
+
+    /// Remote peer.
+    peer: PeerId,
+
+    /// TX channel for sending events to the user.
+    event_tx: Sender<PerfEvent>,
+
+    /// Interval between progress reports.
+    interval: Duration,
+
+    /// When the run started.
+    started: Instant,
+
+    /// When the progress was last reported.
+    reported: Instant,
+}
+
+impl ProgressReporter {
+    /// Report progress if `interval` has passed since the previous report.
+    ///
+    /// Progress reports are dropped if the user isn't keeping up so they don't slow down the run.
+    fn report(&mut self, sent: u64, received: u64) {
+        let now = Instant::now();
+
+        if now.duration_since(self.reported) < self.interval {
+            return;
+        }
+        self.reported = now;
+
+        let _ = self.event_tx.try_send(PerfEvent::Progress {
+            run: self.run,
+            peer: self.peer,
+            progress: RunProgress {
+                sent,
+                received,
+                elapsed: now.duration_since(self.started),
+            },
+        });
+    }
+}
+
+/// Upload `params.to_send` bytes to the server and download `params.to_receive` bytes from it.
+async fn run_client(
+    mut substream: Substream,
+    params: RunParams,
+    mut reporter: ProgressReporter,
+) -> Result<RunStats, PerfError> {
+    let started = reporter.started;
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    let mut sent = 0u64;
+    let mut received = 0u64;
+
+    substream.write_all(&params.to_receive.to_be_bytes()).await?;
+
+    while sent < params.to_send {
+        let size = std::cmp::min(BUFFER_SIZE as u64, params.to_send - sent) as usize;
+
+        substream.write_all(&buffer[..size]).await?;
+        sent += size as u64;
+        reporter.report(sent, received);
+    }
+    substream.shutdown().await?;
+    let upload = started.elapsed();
+
+    loop {
+        match substream.read(&mut buffer).await? {
+            0 => break,
+            size => {
+                received += size as u64;
+                reporter.report(sent, received);
+            }
+        }
+    }
+    let download = started.elapsed() - upload;
+
+    if received != params.to_receive {
+        return Err(PerfError::InvalidResponseLength {
+            expected: params.to_receive,
+            received,
+        });
+    }
+
+    Ok(RunStats {
+        sent,
+        received,
+        upload,
+        download,
+    })
+}
+
+/// Receive the data uploaded by the client and send back the number of bytes it requested.
+///
+/// Returns the number of bytes received and sent.
+async fn run_server(mut substream: Substream) -> io::Result<(u64, u64)> {
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    let mut received = 0u64;
+    let mut sent = 0u64;
+
+    let to_send = substream.read_u64().await?;
+
+    loop {
+        match substream.read(&mut buffer).await? {
+            0 => break,
+            size => received += size as u64,
+        }
+    }
+
+    buffer.fill(0u8);
+    while sent < to_send {
+        let size = std::cmp::min(BUFFER_SIZE as u64, to_send - sent) as usize;
+
+        substream.write_all(&buffer[..size]).await?;
+        sent += size as u64;
+    }
+    substream.shutdown().await?;
+
+    Ok((received, sent))
+}
+
+/// Perf protocol.
+pub(crate) struct Perf {
+    /// Transport service.
+    service: TransportService,
+
+    /// Perf configuration.
+    config: Config,
+
+    /// Connected peers.
+    connected: HashSet<PeerId>,
+
+    /// Runs waiting for the connection to the remote peer to be established.
+    pending_dials: HashMap<PeerId, Vec<(RunId, RunParams)>>,
+
+    /// Runs waiting for an outbound substream.
+    pending_outbound: HashMap<SubstreamId, (RunId, PeerId, RunParams)>,
+
+    /// Ongoing runs of the local node.
+    runs: FuturesStream<BoxFuture<'static, (RunId, PeerId, Result<RunStats, PerfError>)>>,
+
+    /// Ongoing runs served for remote peers.
+    served: FuturesStream<BoxFuture<'static, (PeerId, io::Result<(u64, u64)>)>>,
+}
+
+impl Perf {
+    /// Create new [`Perf`] protocol.
+    pub(crate) fn new(service: TransportService, config: Config) -> Self {
+        Self {
+            service,
+            config,
+            connected: HashSet::new(),
+            pending_dials: HashMap::new(),
+            pending_outbound: HashMap::new(),
+            runs: FuturesStream::new(),
+            served: FuturesStream::new(),
+        }
+    }
+
+    /// Report result of a run to the user.
+    async fn on_run_finished(
+        &mut self,
+        run: RunId,
+        peer: PeerId,
+        result: Result<RunStats, PerfError>,
+    ) {
+        tracing::debug!(target: LOG_TARGET, ?run, ?peer, ?result, "perf run finished");
+
+        let event = match result {
+            Ok(stats) => PerfEvent::Finished { run, peer, stats },
+            Err(error) => PerfEvent::Failed { run, peer, error },
+        };
+
+        let _ = self.config.event_tx.send(event).await;
+    }
+
+    /// Start run requested by the user, dialing the peer if it's not connected.
+    async fn on_run(&mut self, run: RunId, peer: PeerId, params: RunParams) {
+        tracing::debug!(target: LOG_TARGET, ?run, ?peer, ?params, "start perf run");
+
+        if !self.connected.contains(&peer) {
+            match self.service.dial(&peer) {
+                Ok(()) | Err(ImmediateDialError::AlreadyConnected) =>
+                    self.pending_dials.entry(peer).or_default().push((run, params)),
+                Err(error) => {
+                    tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to dial peer");
+                    self.on_run_finished(run, peer, Err(PerfError::DialFailure)).await;
+                }
+            }
+            return;
+        }
+
+        match self.service.open_substream(peer) {
+            Ok(substream_id) => {
+                self.pending_outbound.insert(substream_id, (run, peer, params));
+            }
+            Err(error) => {
+                tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to open substream");
+                self.on_run_finished(run, peer, Err(PerfError::SubstreamOpenFailure)).await;
+            }
+        }
+    }
+
+    /// Connection established to remote peer, start the runs waiting for the connection.
+    async fn on_connection_established(&mut self, peer: PeerId) {
+        tracing::trace!(target: LOG_TARGET, ?peer, "connection established");
+
+        self.connected.insert(peer);
+
+        for (run, params) in self.pending_dials.remove(&peer).unwrap_or_default() {
+            self.on_run(run, peer, params).await;
+        }
+    }
+
+    /// Failed to dial remote peer.
+    async fn on_dial_failure(&mut self, peer: PeerId) {
+        tracing::debug!(target: LOG_TARGET, ?peer, "failed to dial peer");
+
+        for (run, _) in self.pending_dials.remove(&peer).unwrap_or_default() {
+            self.on_run_finished(run, peer, Err(PerfError::DialFailure)).await;
+        }
+    }
+
+    /// Outbound substream opened, start the run.
+    fn on_outbound_substream(&mut self, substream_id: SubstreamId, substream: Substream) {
+        let Some((run, peer, params)) = self.pending_outbound.remove(&substream_id) else {
+            tracing::warn!(target: LOG_TARGET, ?substream_id, "unknown outbound substream");
+            return;
+        };
+
+        let now = Instant::now();
+        let reporter = ProgressReporter {
+            run,
+            peer,
+            event_tx: self.config.event_tx.clone(),
+            interval: self.config.progress_interval,
+            started: now,
+            reported: now,
+        };
+
+        self.runs.push(Box::pin(async move {
+            (run, peer, run_client(substream, params, reporter).await)
+        }));
+    }
+
+    /// Failed to open outbound substream.
+    async fn on_substream_open_failure(
+        &mut self,
+        substream_id: SubstreamId,
+        error: SubstreamError,
+    ) {
+        let Some((run, peer, _)) = self.pending_outbound.remove(&substream_id) else {
+            return;
+        };
+
+        tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to open substream");
+        self.on_run_finished(run, peer, Err(PerfError::SubstreamOpenFailure)).await;
+    }
+
+    /// Inbound substream opened, serve the run if the server is enabled.
+    fn on_inbound_substream(&mut self, peer: PeerId, substream: Substream) {
+        if !self.config.server {
+            tracing::trace!(target: LOG_TARGET, ?peer, "perf server disabled, reject run");
+            return;
+        }
+
+        tracing::trace!(target: LOG_TARGET, ?peer, "serve perf run");
+        self.served.push(Box::pin(async move { (peer, run_server(substream).await) }));
+    }
+
+    /// Handle event received from the transport service.
+    async fn on_service_event(&mut self, event: TransportEvent) {
+        match event {
+            TransportEvent::SubstreamOpened {
+                peer,
+                direction,
+                substream,
+                ..
+            } => match direction {
+                Direction::Inbound => self.on_inbound_substream(peer, substream),
+                Direction::Outbound(substream_id) =>
+                    self.on_outbound_substream(substream_id, substream),
+            },
+            TransportEvent::SubstreamOpenFailure { substream, error } =>
+                self.on_substream_open_failure(substream, error).await,
+            TransportEvent::ConnectionEstablished { peer, .. } =>
+                self.on_connection_established(peer).await,
+            TransportEvent::ConnectionClosed { peer } => {
+                self.connected.remove(&peer);
+            }
+            TransportEvent::DialFailure { peer, .. } => self.on_dial_failure(peer).await,
+        }
+    }
+
+    /// Start [`Perf`] event loop.
+    pub async fn run(mut self) {
+        tracing::debug!(target: LOG_TARGET, server = ?self.config.server, "starting perf event loop");
+
+        loop {
+            tokio::select! {
+                event = self.service.next() => match event {
+                    None => {
+                        tracing::warn!(target: LOG_TARGET, "transport service stream ended, terminating perf event loop");
+                        return
+                    },
+                    Some(event) => self.on_service_event(event).await,
+                },
+                command = self.config.cmd_rx.recv() => match command {
+                    None => {
+                        tracing::debug!(target: LOG_TARGET, "user handle dropped, terminating perf event loop");
+                        return
+                    },
+                    Some(PerfCommand::Run { run, peer, params }) => self.on_run(run, peer, params).await,
+                },
+                Some((run, peer, result)) = self.runs.next() =>
+                    self.on_run_finished(run, peer, result).await,
+                Some((peer, result)) = self.served.next() => match result {
+                    Ok((received, sent)) =>
+                        tracing::debug!(target: LOG_TARGET, ?peer, ?received, ?sent, "perf run served"),
+                    Err(error) =>
+                        tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to serve perf run"),
+                },
+            }
+        }
+    }
+}
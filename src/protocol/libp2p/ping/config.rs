@@ -19,7 +19,9 @@
 // DEALINGS IN THE SOFTWARE.
 
 use crate::{
-    codec::ProtocolCodec, protocol::libp2p::ping::PingEvent, types::protocol::ProtocolName,
+    codec::ProtocolCodec,
+    protocol::libp2p::ping::{PingEvent, PingHandle},
+    types::protocol::ProtocolName,
     DEFAULT_CHANNEL_SIZE,
 };
 
@@ -27,6 +29,8 @@ use futures::Stream;
 use tokio::sync::mpsc::{channel, Sender};
 use tokio_stream::wrappers::ReceiverStream;
 
+use std::time::Duration;
+
 /// IPFS Ping protocol name as a string.
 pub const PROTOCOL_NAME: &str = "/ipfs/ping/1.0.0";
 
@@ -36,6 +40,12 @@ const PING_PAYLOAD_SIZE: usize = 32;
 /// Maximum PING failures.
 const MAX_FAILURES: usize = 3;
 
+/// How many round-trip time samples are kept per peer.
+const LATENCY_WINDOW: usize = 16;
+
+/// Smoothing factor of the round-trip time moving average.
+const LATENCY_ALPHA: f64 = 0.25;
+
 /// Ping configuration.
 pub struct Config {
     /// Protocol name.
@@ -49,6 +59,14 @@ pub struct Config {
 
     /// TX channel for sending events to the user protocol.
     pub(crate) tx_event: Sender<PingEvent>,
+
+    /// Interval between consecutive pings of a connected peer.
+    ///
+    /// If `None`, each peer is pinged only once after the connection is established.
+    pub(crate) ping_interval: Option<Duration>,
+
+    /// Handle for recording latency statistics.
+    pub(crate) handle: PingHandle,
 }
 
 impl Config {
@@ -64,10 +82,17 @@ impl Config {
                 max_failures: MAX_FAILURES,
                 protocol: ProtocolName::from(PROTOCOL_NAME),
                 codec: ProtocolCodec::Identity(PING_PAYLOAD_SIZE),
+                ping_interval: None,
+                handle: PingHandle::new(LATENCY_WINDOW, LATENCY_ALPHA),
             },
             Box::new(ReceiverStream::new(rx_event)),
         )
     }
+
+    /// Get [`PingHandle`] for querying the latency statistics of connected peers.
+    pub fn handle(&self) -> PingHandle {
+        self.handle.clone()
+    }
 }
 
 /// Ping configuration builder.
@@ -80,6 +105,15 @@ pub struct ConfigBuilder {
 
     /// Maximum failures before the peer is considered unreachable.
     max_failures: usize,
+
+    /// Interval between consecutive pings of a connected peer.
+    ping_interval: Option<Duration>,
+
+    /// How many round-trip time samples are kept per peer.
+    latency_window: usize,
+
+    /// Smoothing factor of the round-trip time moving average.
+    latency_alpha: f64,
 }
 
 impl Default for ConfigBuilder {
@@ -95,6 +129,9 @@ impl ConfigBuilder {
             max_failures: MAX_FAILURES,
             protocol: ProtocolName::from(PROTOCOL_NAME),
             codec: ProtocolCodec::Identity(PING_PAYLOAD_SIZE),
+            ping_interval: None,
+            latency_window: LATENCY_WINDOW,
+            latency_alpha: LATENCY_ALPHA,
         }
     }
 
@@ -104,6 +141,32 @@ impl ConfigBuilder {
         self
     }
 
+    /// Ping connected peers periodically.
+    ///
+    /// By default each peer is pinged only once after the connection is established which
+    /// produces a single latency sample per connection.
+    pub fn with_ping_interval(mut self, ping_interval: Duration) -> Self {
+        self.ping_interval = Some(ping_interval);
+        self
+    }
+
+    /// Set how many round-trip time samples are kept per peer.
+    ///
+    /// The minimum, maximum and jitter reported by [`PingHandle`] are calculated over this
+    /// window.
+    pub fn with_latency_window(mut self, latency_window: usize) -> Self {
+        self.latency_window = latency_window;
+        self
+    }
+
+    /// Set the smoothing factor, within `(0, 1]`, of the round-trip time moving average.
+    ///
+    /// Higher values give more weight to recent samples.
+    pub fn with_latency_smoothing(mut self, latency_alpha: f64) -> Self {
+        self.latency_alpha = latency_alpha;
+        self
+    }
+
     /// Build [`Config`].
     pub fn build(self) -> (Config, Box<dyn Stream<Item = PingEvent> + Send + Unpin>) {
         let (tx_event, rx_event) = channel(DEFAULT_CHANNEL_SIZE);
@@ -114,6 +177,8 @@ impl ConfigBuilder {
                 max_failures: self.max_failures,
                 protocol: self.protocol,
                 codec: self.codec,
+                ping_interval: self.ping_interval,
+                handle: PingHandle::new(self.latency_window, self.latency_alpha),
             },
             Box::new(ReceiverStream::new(rx_event)),
         )
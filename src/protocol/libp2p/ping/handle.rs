@@ -0,0 +1,249 @@
+// Copyright 2023 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Ping handle for querying the latency statistics collected by the ping protocol.
+
+use crate::PeerId;
+
+use parking_lot::RwLock;
+
+use std::{
+    collections::{hash_map::Entry, HashMap, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+
+/// Latency statistics of a connected peer.
+///
+/// `min`, `max` and `jitter` are calculated over the most recent samples, bounded by the
+/// configured window size. The exponentially weighted moving average is updated with every
+/// sample and therefore also reflects samples which have already left the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyStats {
+    /// Most recent round-trip time.
+    pub last: Duration,
+
+    /// Minimum round-trip time within the window.
+    pub min: Duration,
+
+    /// Maximum round-trip time within the window.
+    pub max: Duration,
+
+    /// Exponentially weighted moving average of the round-trip time.
+    pub ewma: Duration,
+
+    /// Mean absolute difference between consecutive round-trip times within the window.
+    pub jitter: Duration,
+
+    /// Number of samples within the window.
+    pub samples: usize,
+}
+
+/// Latency samples of a peer.
+#[derive(Debug)]
+struct PeerLatency {
+    /// Most recent samples, oldest first.
+    window: VecDeque<Duration>,
+
+    /// Exponentially weighted moving average, in seconds.
+    ewma: f64,
+}
+
+impl PeerLatency {
+    /// Create new [`PeerLatency`] from the first sample.
+    fn new(rtt: Duration, window_size: usize) -> Self {
+        let mut window = VecDeque::with_capacity(window_size);
+        window.push_back(rtt);
+
+        Self {
+            window,
+            ewma: rtt.as_secs_f64(),
+        }
+    }
+
+    /// Record new sample.
+    fn record(&mut self, rtt: Duration, window_size: usize, alpha: f64) {
+        if self.window.len() >= window_size {
+            self.window.pop_front();
+        }
+        self.window.push_back(rtt);
+        self.ewma = alpha * rtt.as_secs_f64() + (1f64 - alpha) * self.ewma;
+    }
+
+    /// Calculate statistics over the current window.
+    fn stats(&self) -> LatencyStats {
+        let last = *self.window.back().expect("window to contain at least one sample");
+        let min = self.window.iter().min().copied().unwrap_or(last);
+        let max = self.window.iter().max().copied().unwrap_or(last);
+
+        let jitter = match self.window.len() {
+            0 | 1 => Duration::ZERO,
+            len => {
+                let total = self
+                    .window
+                    .iter()
+                    .zip(self.window.iter().skip(1))
+                    .map(|(previous, next)| previous.abs_diff(*next))
+                    .sum::<Duration>();
+
+                total / (len - 1) as u32
+            }
+        };
+
+        LatencyStats {
+            last,
+            min,
+            max,
+            ewma: Duration::from_secs_f64(self.ewma),
+            jitter,
+            samples: self.window.len(),
+        }
+    }
+}
+
+/// Handle for querying the latency statistics of connected peers.
+///
+/// The statistics of a peer are discarded once the peer disconnects.
+#[derive(Debug, Clone)]
+pub struct PingHandle {
+    /// Latency samples of connected peers.
+    peers: Arc<RwLock<HashMap<PeerId, PeerLatency>>>,
+
+    /// How many samples are kept per peer.
+    window_size: usize,
+
+    /// Smoothing factor of the moving average.
+    alpha: f64,
+}
+
+impl PingHandle {
+    /// Create new [`PingHandle`].
+    pub(super) fn new(window_size: usize, alpha: f64) -> Self {
+        Self {
+            peers: Arc::new(RwLock::new(HashMap::new())),
+            window_size: window_size.max(1),
+            alpha: alpha.clamp(f64::EPSILON, 1f64),
+        }
+    }
+
+    /// Record round-trip time measured with `peer`.
+    pub(super) fn record(&self, peer: PeerId, rtt: Duration) {
+        match self.peers.write().entry(peer) {
+            Entry::Occupied(mut entry) => entry.get_mut().record(rtt, self.window_size, self.alpha),
+            Entry::Vacant(entry) => {
+                entry.insert(PeerLatency::new(rtt, self.window_size));
+            }
+        }
+    }
+
+    /// Discard statistics of a disconnected `peer`.
+    pub(super) fn remove(&self, peer: &PeerId) {
+        self.peers.write().remove(peer);
+    }
+
+    /// Get latency statistics of `peer`.
+    ///
+    /// Returns `None` if the peer is not connected or it hasn't been pinged yet.
+    pub fn latency(&self, peer: &PeerId) -> Option<LatencyStats> {
+        self.peers.read().get(peer).map(PeerLatency::stats)
+    }
+
+    /// Get latency statistics of all pinged peers, ordered from the lowest moving average to the
+    /// highest.
+    pub fn peers_by_latency(&self) -> Vec<(PeerId, LatencyStats)> {
+        let mut peers = self
+            .peers
+            .read()
+            .iter()
+            .map(|(peer, latency)| (*peer, latency.stats()))
+            .collect::<Vec<_>>();
+
+        peers.sort_by_key(|(_, stats)| stats.ewma);
+        peers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_sample() {
+        let handle = PingHandle::new(4, 0.5);
+        let peer = PeerId::random();
+
+        assert!(handle.latency(&peer).is_none());
+
+        handle.record(peer, Duration::from_millis(100));
+
+        assert_eq!(
+            handle.latency(&peer),
+            Some(LatencyStats {
+                last: Duration::from_millis(100),
+                min: Duration::from_millis(100),
+                max: Duration::from_millis(100),
+                ewma: Duration::from_millis(100),
+                jitter: Duration::ZERO,
+                samples: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn window_is_bounded() {
+        let handle = PingHandle::new(3, 0.5);
+        let peer = PeerId::random();
+
+        for rtt in [10, 40, 20, 30] {
+            handle.record(peer, Duration::from_millis(rtt));
+        }
+
+        let stats = handle.latency(&peer).unwrap();
+
+        // the first sample has left the window
+        assert_eq!(stats.samples, 3);
+        assert_eq!(stats.last, Duration::from_millis(30));
+        assert_eq!(stats.min, Duration::from_millis(20));
+        assert_eq!(stats.max, Duration::from_millis(40));
+
+        // |40 - 20| + |20 - 30| over two intervals
+        assert_eq!(stats.jitter, Duration::from_millis(15));
+
+        // 10 -> 25 -> 22.5 -> 26.25
+        assert_eq!(stats.ewma.as_micros(), 26_250);
+    }
+
+    #[test]
+    fn peers_ordered_by_latency() {
+        let handle = PingHandle::new(4, 0.5);
+        let slow = PeerId::random();
+        let fast = PeerId::random();
+
+        handle.record(slow, Duration::from_millis(250));
+        handle.record(fast, Duration::from_millis(20));
+
+        let peers = handle.peers_by_latency().into_iter().map(|(peer, _)| peer).collect::<Vec<_>>();
+        assert_eq!(peers, vec![fast, slow]);
+
+        handle.remove(&fast);
+        assert!(handle.latency(&fast).is_none());
+        assert_eq!(handle.peers_by_latency().len(), 1);
+    }
+}
@@ -37,8 +37,10 @@ use std::{
 };
 
 pub use config::{Config, ConfigBuilder};
+pub use handle::{LatencyStats, PingHandle};
 
 mod config;
+mod handle;
 
 // TODO: https://github.com/paritytech/litep2p/issues/132 let the user handle max failures
 
@@ -77,6 +79,12 @@ pub(crate) struct Ping {
 
     /// Pending inbound substreams.
     pending_inbound: FuturesUnordered<BoxFuture<'static, crate::Result<()>>>,
+
+    /// Interval between consecutive pings of a connected peer.
+    ping_interval: Option<Duration>,
+
+    /// Handle for recording latency statistics.
+    handle: PingHandle,
 }
 
 impl Ping {
@@ -89,6 +97,8 @@ impl Ping {
             pending_outbound: FuturesUnordered::new(),
             pending_inbound: FuturesUnordered::new(),
             _max_failures: config.max_failures,
+            ping_interval: config.ping_interval,
+            handle: config.handle,
         }
     }
 
@@ -107,6 +117,18 @@ impl Ping {
         tracing::trace!(target: LOG_TARGET, ?peer, "connection closed");
 
         self.peers.remove(&peer);
+        self.handle.remove(&peer);
+    }
+
+    /// Ping all connected peers.
+    fn on_ping_interval(&mut self) {
+        tracing::trace!(target: LOG_TARGET, peers = ?self.peers.len(), "ping connected peers");
+
+        for peer in &self.peers {
+            if let Err(error) = self.service.open_substream(*peer) {
+                tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to open substream");
+            }
+        }
     }
 
     /// Handle outbound substream.
@@ -167,8 +189,23 @@ impl Ping {
     pub async fn run(mut self) {
         tracing::debug!(target: LOG_TARGET, "starting ping event loop");
 
+        let mut interval = self.ping_interval.map(|period| {
+            let mut interval =
+                tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            interval
+        });
+
         loop {
             tokio::select! {
+                _ = async {
+                    match interval.as_mut() {
+                        Some(interval) => interval.tick().await,
+                        None => futures::future::pending().await,
+                    }
+                } => {
+                    self.on_ping_interval();
+                }
                 event = self.service.next() => match event {
                     Some(TransportEvent::ConnectionEstablished { peer, .. }) => {
                         let _ = self.on_connection_established(peer);
@@ -196,6 +233,10 @@ impl Ping {
                 event = self.pending_outbound.next(), if !self.pending_outbound.is_empty() => {
                     match event {
                         Some(Ok((peer, elapsed))) => {
+                            if self.peers.contains(&peer) {
+                                self.handle.record(peer, elapsed);
+                            }
+
                             let _ = self
                                 .tx
                                 .send(PingEvent::Ping {
@@ -0,0 +1,147 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Client side of the circuit relay: reservations and circuits opened through relays.
+
+use crate::{
+    error::RelayError,
+    protocol::libp2p::relay::{read_message, schema, send_message, status_to_result},
+    substream::Substream,
+    PeerId,
+};
+
+use multiaddr::Multiaddr;
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Reservation accepted by a relay.
+#[derive(Debug)]
+pub(super) struct Reservation {
+    /// How long until the reservation expires.
+    pub(super) expires_in: Duration,
+
+    /// Addresses of the relay reported in the reservation.
+    pub(super) addresses: Vec<Multiaddr>,
+}
+
+/// Ask the relay to make a reservation for the local node.
+pub(super) async fn reserve(mut substream: Substream) -> Result<Reservation, RelayError> {
+    use schema::{hop_message::Type, HopMessage};
+
+    send_message(
+        &mut substream,
+        &HopMessage {
+            r#type: Type::Reserve as i32,
+            peer: None,
+            reservation: None,
+            limit: None,
+            status: None,
+        },
+    )
+    .await?;
+
+    let message = read_message::<HopMessage>(&mut substream).await?;
+    let _ = substream.close().await;
+
+    if message.r#type() != Type::Status {
+        return Err(RelayError::ProtocolViolation);
+    }
+    status_to_result(message.status())?;
+
+    let reservation = message.reservation.ok_or(RelayError::ProtocolViolation)?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+
+    Ok(Reservation {
+        expires_in: Duration::from_secs(reservation.expire).saturating_sub(now),
+        addresses: reservation
+            .addrs
+            .into_iter()
+            .filter_map(|address| Multiaddr::try_from(address).ok())
+            .collect(),
+    })
+}
+
+/// Ask the relay to open a circuit to `destination`.
+///
+/// On success, the returned substream carries the raw circuit.
+pub(super) async fn connect(
+    mut substream: Substream,
+    destination: PeerId,
+) -> Result<Substream, RelayError> {
+    use schema::{hop_message::Type, HopMessage, Peer};
+
+    send_message(
+        &mut substream,
+        &HopMessage {
+            r#type: Type::Connect as i32,
+            peer: Some(Peer {
+                id: destination.to_bytes(),
+                addrs: Vec::new(),
+            }),
+            reservation: None,
+            limit: None,
+            status: None,
+        },
+    )
+    .await?;
+
+    let message = read_message::<HopMessage>(&mut substream).await?;
+
+    if message.r#type() != Type::Status {
+        return Err(RelayError::ProtocolViolation);
+    }
+    status_to_result(message.status())?;
+
+    Ok(substream)
+}
+
+/// Accept a circuit opened by a relay.
+///
+/// Returns the peer ID of the peer which opened the circuit and the substream carrying it.
+pub(super) async fn accept_circuit(
+    mut substream: Substream,
+) -> Result<(PeerId, Substream), RelayError> {
+    use schema::{stop_message::Type, Status, StopMessage};
+
+    let message = read_message::<StopMessage>(&mut substream).await?;
+
+    let source = match (message.r#type(), message.peer) {
+        (Type::Connect, Some(peer)) => PeerId::from_bytes(&peer.id).ok(),
+        _ => None,
+    };
+
+    let (status, result) = match source {
+        Some(source) => (Status::Ok, Ok(source)),
+        None => (Status::MalformedMessage, Err(RelayError::ProtocolViolation)),
+    };
+
+    send_message(
+        &mut substream,
+        &StopMessage {
+            r#type: Type::Status as i32,
+            peer: None,
+            limit: None,
+            status: Some(status as i32),
+        },
+    )
+    .await?;
+
+    result.map(|source| (source, substream))
+}
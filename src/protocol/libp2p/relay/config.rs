@@ -0,0 +1,199 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use crate::{
+    codec::ProtocolCodec,
+    protocol::libp2p::relay::{RelayCommand, RelayEvent, RelayHandle},
+    types::protocol::ProtocolName,
+    DEFAULT_CHANNEL_SIZE,
+};
+
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+use std::time::Duration;
+
+/// Hop protocol name, used by clients to talk to relays.
+pub const HOP_PROTOCOL_NAME: &str = "/libp2p/circuit/relay/0.2.0/hop";
+
+/// Stop protocol name, used by relays to open circuits to the destination peers.
+pub const STOP_PROTOCOL_NAME: &str = "/libp2p/circuit/relay/0.2.0/stop";
+
+/// Maximum size for relay messages.
+const MAX_PAYLOAD_SIZE: usize = 4096;
+
+/// Default duration of a reservation.
+const RESERVATION_DURATION: Duration = Duration::from_secs(60 * 60);
+
+/// Default maximum number of reservations the relay holds.
+const MAX_RESERVATIONS: usize = 128;
+
+/// Default maximum number of circuits the relay keeps open.
+const MAX_CIRCUITS: usize = 16;
+
+/// Default maximum number of circuits the relay keeps open for a single peer.
+const MAX_CIRCUITS_PER_PEER: usize = 4;
+
+/// Default maximum duration of a circuit.
+const CIRCUIT_DURATION: Duration = Duration::from_secs(2 * 60);
+
+/// Default maximum number of bytes relayed in each direction of a circuit.
+const CIRCUIT_DATA: u64 = 1 << 17;
+
+/// Circuit relay configuration.
+pub struct Config {
+    /// Hop protocol name.
+    pub(crate) hop_protocol: ProtocolName,
+
+    /// Stop protocol name.
+    pub(crate) stop_protocol: ProtocolName,
+
+    /// Codec used by the protocol.
+    pub(crate) codec: ProtocolCodec,
+
+    /// Whether the relay side of the protocol is enabled.
+    pub(super) server: bool,
+
+    /// Duration of the reservations made with the relay.
+    pub(super) reservation_duration: Duration,
+
+    /// Maximum number of reservations the relay holds.
+    pub(super) max_reservations: usize,
+
+    /// Maximum number of circuits the relay keeps open.
+    pub(super) max_circuits: usize,
+
+    /// Maximum number of circuits the relay keeps open for a single peer.
+    pub(super) max_circuits_per_peer: usize,
+
+    /// Maximum duration of a circuit.
+    pub(super) circuit_duration: Duration,
+
+    /// Maximum number of bytes relayed in each direction of a circuit.
+    pub(super) circuit_data: u64,
+
+    /// TX channel for sending events to the user protocol.
+    pub(super) event_tx: Sender<RelayEvent>,
+
+    /// RX channel for receiving commands from the user.
+    pub(super) cmd_rx: Receiver<RelayCommand>,
+}
+
+/// Circuit relay configuration builder.
+pub struct ConfigBuilder {
+    /// Whether the relay side of the protocol is enabled.
+    server: bool,
+
+    /// Duration of the reservations made with the relay.
+    reservation_duration: Duration,
+
+    /// Maximum number of reservations the relay holds.
+    max_reservations: usize,
+
+    /// Maximum number of circuits the relay keeps open.
+    max_circuits: usize,
+
+    /// Maximum number of circuits the relay keeps open for a single peer.
+    max_circuits_per_peer: usize,
+
+    /// Maximum duration of a circuit.
+    circuit_duration: Duration,
+
+    /// Maximum number of bytes relayed in each direction of a circuit.
+    circuit_data: u64,
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigBuilder {
+    /// Create new [`ConfigBuilder`] with the client enabled and the relay disabled.
+    pub fn new() -> Self {
+        Self {
+            server: false,
+            reservation_duration: RESERVATION_DURATION,
+            max_reservations: MAX_RESERVATIONS,
+            max_circuits: MAX_CIRCUITS,
+            max_circuits_per_peer: MAX_CIRCUITS_PER_PEER,
+            circuit_duration: CIRCUIT_DURATION,
+            circuit_data: CIRCUIT_DATA,
+        }
+    }
+
+    /// Enable the relay side of the protocol, accepting reservations and relaying circuits
+    /// for remote peers.
+    pub fn with_server(mut self) -> Self {
+        self.server = true;
+        self
+    }
+
+    /// Set duration of the reservations made with the relay.
+    pub fn with_reservation_duration(mut self, reservation_duration: Duration) -> Self {
+        self.reservation_duration = reservation_duration;
+        self
+    }
+
+    /// Set maximum number of reservations the relay holds.
+    pub fn with_max_reservations(mut self, max_reservations: usize) -> Self {
+        self.max_reservations = max_reservations;
+        self
+    }
+
+    /// Set maximum number of circuits the relay keeps open, in total and for a single peer.
+    pub fn with_max_circuits(mut self, max_circuits: usize, max_circuits_per_peer: usize) -> Self {
+        self.max_circuits = max_circuits;
+        self.max_circuits_per_peer = max_circuits_per_peer;
+        self
+    }
+
+    /// Set how long a circuit is kept open and how many bytes are relayed in each direction
+    /// before the relay closes it.
+    pub fn with_circuit_limits(mut self, duration: Duration, data: u64) -> Self {
+        self.circuit_duration = duration;
+        self.circuit_data = data;
+        self
+    }
+
+    /// Build [`Config`].
+    pub fn build(self) -> (Config, RelayHandle) {
+        let (event_tx, event_rx) = channel(DEFAULT_CHANNEL_SIZE);
+        let (cmd_tx, cmd_rx) = channel(DEFAULT_CHANNEL_SIZE);
+
+        (
+            Config {
+                hop_protocol: ProtocolName::from(HOP_PROTOCOL_NAME),
+                stop_protocol: ProtocolName::from(STOP_PROTOCOL_NAME),
+                codec: ProtocolCodec::UnsignedVarint(Some(MAX_PAYLOAD_SIZE)),
+                server: self.server,
+                reservation_duration: self.reservation_duration,
+                max_reservations: self.max_reservations,
+                max_circuits: self.max_circuits,
+                max_circuits_per_peer: self.max_circuits_per_peer,
+                circuit_duration: self.circuit_duration,
+                circuit_data: self.circuit_data,
+                event_tx,
+                cmd_rx,
+            },
+            RelayHandle::new(event_rx, cmd_tx),
+        )
+    }
+}
@@ -0,0 +1,144 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Circuit relay handle for communicating with the relay protocol implementation.
+
+use crate::{
+    error::{AddressError, Error, RelayError},
+    PeerId,
+};
+
+use multiaddr::Multiaddr;
+use tokio::sync::mpsc::{Receiver, Sender};
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Events emitted by the relay protocol.
+#[derive(Debug)]
+pub enum RelayEvent {
+    /// Relay accepted or renewed a reservation of the local node.
+    ///
+    /// The circuit addresses have been added to the public addresses of the node.
+    ReservationAccepted {
+        /// Peer ID of the relay.
+        relay: PeerId,
+
+        /// Circuit addresses through which the local node can be reached.
+        addresses: Vec<Multiaddr>,
+    },
+
+    /// Failed to make or renew a reservation with the relay.
+    ReservationFailed {
+        /// Peer ID of the relay.
+        relay: PeerId,
+
+        /// Error.
+        error: RelayError,
+    },
+
+    /// Reservation with the relay was closed.
+    ///
+    /// The circuit addresses have been removed from the public addresses of the node.
+    ReservationClosed {
+        /// Peer ID of the relay.
+        relay: PeerId,
+    },
+
+    /// Local relay opened a circuit between two peers.
+    CircuitOpened {
+        /// Peer which requested the circuit.
+        source: PeerId,
+
+        /// Peer to which the circuit was opened.
+        destination: PeerId,
+    },
+
+    /// Local relay closed a circuit between two peers.
+    CircuitClosed {
+        /// Peer which requested the circuit.
+        source: PeerId,
+
+        /// Peer to which the circuit was opened.
+        destination: PeerId,
+    },
+}
+
+/// Commands sent from the user to `Relay`.
+#[derive(Debug)]
+pub(crate) enum RelayCommand {
+    /// Make a reservation with the relay at `address`.
+    Reserve {
+        /// Address of the relay.
+        address: Multiaddr,
+    },
+
+    /// Stop renewing the reservation with `relay`.
+    CancelReservation {
+        /// Peer ID of the relay.
+        relay: PeerId,
+    },
+}
+
+/// Handle for communicating with the relay protocol.
+pub struct RelayHandle {
+    /// RX channel for receiving relay events.
+    event_rx: Receiver<RelayEvent>,
+
+    /// TX channel for sending commands to `Relay`.
+    cmd_tx: Sender<RelayCommand>,
+}
+
+impl RelayHandle {
+    /// Create new [`RelayHandle`].
+    pub(super) fn new(event_rx: Receiver<RelayEvent>, cmd_tx: Sender<RelayCommand>) -> Self {
+        Self { event_rx, cmd_tx }
+    }
+
+    /// Make a reservation with the relay at `address`, dialing the relay if needed.
+    ///
+    /// `address` must end in the peer ID of the relay. The reservation is renewed until it's
+    /// cancelled or the connection to the relay is closed.
+    pub async fn reserve(&self, address: Multiaddr) -> crate::Result<()> {
+        if PeerId::try_from_multiaddr(&address).is_none() {
+            return Err(Error::AddressError(AddressError::PeerIdMissing));
+        }
+
+        self.cmd_tx
+            .send(RelayCommand::Reserve { address })
+            .await
+            .map_err(|_| Error::EssentialTaskClosed)
+    }
+
+    /// Cancel the reservation with `relay`.
+    pub async fn cancel_reservation(&self, relay: PeerId) {
+        let _ = self.cmd_tx.send(RelayCommand::CancelReservation { relay }).await;
+    }
+}
+
+impl futures::Stream for RelayHandle {
+    type Item = RelayEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.event_rx).poll_recv(cx)
+    }
+}
@@ -0,0 +1,1104 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! [`Circuit Relay v2`](https://github.com/libp2p/specs/blob/master/relay/circuit-v2.md)
+//! implementation.
+//!
+//! The client makes reservations with relays, advertising `<relay address>/p2p-circuit`
+//! addresses for as long as the reservation is held, and opens circuits through relays for the
+//! relay transport when a `/p2p/<relay>/p2p-circuit/p2p/<destination>` address is dialed.
+//!
+//! The relay accepts reservations and relays circuits between its clients. Both the number of
+//! reservations and circuits and the duration and size of each circuit are limited.
+
+use crate::{
+    error::{ImmediateDialError, RelayError, SubstreamError},
+    protocol::{Direction, TransportEvent, TransportService},
+    substream::Substream,
+    transport::{
+        relay::{CircuitRequest, InboundCircuit},
+        Endpoint,
+    },
+    types::SubstreamId,
+    utils::futures_stream::FuturesStream,
+    PeerId,
+};
+
+use futures::{future::BoxFuture, StreamExt};
+use multiaddr::{Multiaddr, Protocol};
+use prost::Message;
+use tokio::{
+    sync::{
+        mpsc::{Receiver, Sender},
+        oneshot,
+    },
+    time::Instant,
+};
+
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    time::Duration,
+};
+
+pub use config::{Config, ConfigBuilder, HOP_PROTOCOL_NAME, STOP_PROTOCOL_NAME};
+pub(crate) use handle::RelayCommand;
+pub use handle::{RelayEvent, RelayHandle};
+
+mod client;
+mod config;
+mod handle;
+mod server;
+
+mod schema {
+    include!(concat!(env!("OUT_DIR"), "/relay.rs"));
+}
+
+/// Log target for the file.
+const LOG_TARGET: &str = "litep2p::relay";
+
+/// Timeout for a request sent to a relay, including the time the relay takes to reach the
+/// destination.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Timeout for reading a request and exchanging messages with the destination of a circuit.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Read and decode the next message from `substream`.
+async fn read_message<T: Message + Default>(substream: &mut Substream) -> Result<T, RelayError> {
+    match substream.next().await {
+        None | Some(Err(_)) => Err(RelayError::RelayUnreachable),
+        Some(Ok(payload)) => T::decode(payload.freeze()).map_err(|_| RelayError::ProtocolViolation),
+    }
+}
+
+/// Encode and send `message` over `substream`.
+async fn send_message(substream: &mut Substream, message: &impl Message) -> Result<(), RelayError> {
+    substream
+        .send_framed(message.encode_to_vec().into())
+        .await
+        .map_err(|_| RelayError::RelayUnreachable)
+}
+
+/// Convert status received from the remote peer into a result.
+fn status_to_result(status: schema::Status) -> Result<(), RelayError> {
+    use schema::Status;
+
+    match status {
+        Status::Ok => Ok(()),
+        Status::ReservationRefused => Err(RelayError::ReservationRefused),
+        Status::ResourceLimitExceeded => Err(RelayError::ResourceLimitExceeded),
+        Status::PermissionDenied => Err(RelayError::PermissionDenied),
+        Status::ConnectionFailed => Err(RelayError::ConnectionFailed),
+        Status::NoReservation => Err(RelayError::NoReservation),
+        Status::Unused | Status::MalformedMessage | Status::UnexpectedMessage =>
+            Err(RelayError::ProtocolViolation),
+    }
+}
+
+/// Request sent to a relay over the hop protocol.
+enum OutboundRequest {
+    /// Make or renew a reservation.
+    Reserve {
+        /// Address the relay was dialed at.
+        address: Multiaddr,
+    },
+
+    /// Open a circuit to `destination`.
+    Connect {
+        /// Peer ID of the destination.
+        destination: PeerId,
+
+        /// TX channel for returning the circuit to the transport.
+        tx: oneshot::Sender<Result<Substream, RelayError>>,
+    },
+}
+
+/// Reservation held by the local node.
+struct Reservation {
+    /// Address the relay was dialed at.
+    address: Multiaddr,
+
+    /// Circuit addresses added to the public addresses of the node.
+    addresses: Vec<Multiaddr>,
+
+    /// When the reservation should be renewed.
+    renew_at: Instant,
+}
+
+/// Circuit waiting for the stop substream to the destination to open.
+struct PendingCircuit {
+    /// Peer which requested the circuit.
+    source: PeerId,
+
+    /// Peer to which the circuit is opened.
+    destination: PeerId,
+
+    /// Hop substream of the source.
+    substream: Substream,
+}
+
+/// Progress of the requests handled by the relay.
+#[allow(clippy::large_enum_variant)]
+enum ServerEvent {
+    /// Hop request was read from a client.
+    HopRequest {
+        peer: PeerId,
+        substream: Substream,
+        request: Result<server::HopRequest, RelayError>,
+    },
+
+    /// Destination accepted the circuit.
+    CircuitEstablished {
+        source: PeerId,
+        destination: PeerId,
+        source_substream: Substream,
+        destination_substream: Substream,
+    },
+
+    /// Circuit was closed or it couldn't be established.
+    CircuitClosed {
+        source: PeerId,
+        destination: PeerId,
+        established: bool,
+    },
+}
+
+/// Circuit relay protocol.
+pub(crate) struct Relay {
+    /// Transport service of the hop protocol.
+    hop_service: TransportService,
+
+    /// Transport service of the stop protocol.
+    stop_service: TransportService,
+
+    /// TX channel for sending events to the user protocol.
+    event_tx: Sender<RelayEvent>,
+
+    /// RX channel for receiving commands from the user.
+    cmd_rx: Receiver<RelayCommand>,
+
+    /// RX channel for receiving circuit requests from the relay transport.
+    request_rx: Receiver<CircuitRequest>,
+
+    /// TX channel for sending inbound circuits to the relay transport.
+    circuit_tx: Sender<InboundCircuit>,
+
+    /// Whether the relay side of the protocol is enabled.
+    server: bool,
+
+    /// Duration of the reservations made with the relay.
+    reservation_duration: Duration,
+
+    /// Maximum number of reservations the relay holds.
+    max_reservations: usize,
+
+    /// Maximum number of circuits the relay keeps open.
+    max_circuits: usize,
+
+    /// Maximum number of circuits the relay keeps open for a single peer.
+    max_circuits_per_peer: usize,
+
+    /// Limits of the circuits relayed by the local node.
+    limit: server::Limit,
+
+    /// Connected peers and the endpoints of their connections.
+    peers: HashMap<PeerId, Endpoint>,
+
+    /// Requests waiting for the connection to the relay to open.
+    pending_dials: HashMap<PeerId, Vec<OutboundRequest>>,
+
+    /// Requests waiting for the hop substream to open.
+    pending_outbound: HashMap<SubstreamId, (PeerId, OutboundRequest)>,
+
+    /// Reservations held by the local node.
+    reservations: HashMap<PeerId, Reservation>,
+
+    /// Pending reservation requests sent to relays.
+    pending_reservations: FuturesStream<
+        BoxFuture<'static, (PeerId, Multiaddr, Result<client::Reservation, RelayError>)>,
+    >,
+
+    /// Pending reservation renewals.
+    renewals: FuturesStream<BoxFuture<'static, PeerId>>,
+
+    /// Reservations held by the relay and when they expire.
+    accepted_reservations: HashMap<PeerId, Instant>,
+
+    /// Pending reservation expirations.
+    expirations: FuturesStream<BoxFuture<'static, PeerId>>,
+
+    /// Number of circuits the relay keeps open.
+    num_circuits: usize,
+
+    /// Number of circuits the relay keeps open for each peer.
+    peer_circuits: HashMap<PeerId, usize>,
+
+    /// Circuits waiting for the stop substream to open.
+    pending_circuits: HashMap<SubstreamId, PendingCircuit>,
+
+    /// Pending relay operations.
+    pending_server: FuturesStream<BoxFuture<'static, ServerEvent>>,
+
+    /// Pending tasks which don't report back, such as responses and circuits opened for the
+    /// relay transport.
+    pending_tasks: FuturesStream<BoxFuture<'static, ()>>,
+}
+
+impl Relay {
+    /// Create new [`Relay`] protocol.
+    pub(crate) fn new(
+        hop_service: TransportService,
+        stop_service: TransportService,
+        config: Config,
+        request_rx: Receiver<CircuitRequest>,
+        circuit_tx: Sender<InboundCircuit>,
+    ) -> Self {
+        Self {
+            hop_service,
+            stop_service,
+            event_tx: config.event_tx,
+            cmd_rx: config.cmd_rx,
+            request_rx,
+            circuit_tx,
+            server: config.server,
+            reservation_duration: config.reservation_duration,
+            max_reservations: config.max_reservations,
+            max_circuits: config.max_circuits,
+            max_circuits_per_peer: config.max_circuits_per_peer,
+            limit: server::Limit {
+                duration: config.circuit_duration,
+                data: config.circuit_data,
+            },
+            peers: HashMap::new(),
+            pending_dials: HashMap::new(),
+            pending_outbound: HashMap::new(),
+            reservations: HashMap::new(),
+            pending_reservations: FuturesStream::new(),
+            renewals: FuturesStream::new(),
+            accepted_reservations: HashMap::new(),
+            expirations: FuturesStream::new(),
+            num_circuits: 0usize,
+            peer_circuits: HashMap::new(),
+            pending_circuits: HashMap::new(),
+            pending_server: FuturesStream::new(),
+            pending_tasks: FuturesStream::new(),
+        }
+    }
+
+    /// Connection established to remote peer.
+    fn on_connection_established(&mut self, peer: PeerId, endpoint: Endpoint) {
+        tracing::trace!(target: LOG_TARGET, ?peer, ?endpoint, "connection established");
+
+        self.peers.insert(peer, endpoint);
+
+        for request in self.pending_dials.remove(&peer).unwrap_or_default() {
+            self.open_request(peer, request);
+        }
+    }
+
+    /// Connection closed to remote peer.
+    async fn on_connection_closed(&mut self, peer: PeerId) {
+        tracing::trace!(target: LOG_TARGET, ?peer, "connection closed");
+
+        self.peers.remove(&peer);
+
+        if self.accepted_reservations.remove(&peer).is_some() {
+            tracing::debug!(target: LOG_TARGET, ?peer, "client disconnected, reservation dropped");
+            self.hop_service.release_connection(peer);
+        }
+
+        if self.remove_reservation(peer) {
+            let _ = self.event_tx.send(RelayEvent::ReservationClosed { relay: peer }).await;
+        }
+    }
+
+    /// Failed to dial `peer`.
+    async fn on_dial_failure(&mut self, peer: PeerId) {
+        for request in self.pending_dials.remove(&peer).unwrap_or_default() {
+            self.fail_request(peer, request, RelayError::RelayUnreachable).await;
+        }
+    }
+
+    /// Send `request` to `relay`, dialing it first if needed.
+    async fn send_request(&mut self, relay: PeerId, address: Multiaddr, request: OutboundRequest) {
+        if self.peers.contains_key(&relay) {
+            return self.open_request(relay, request);
+        }
+
+        if let Some(requests) = self.pending_dials.get_mut(&relay) {
+            return requests.push(request);
+        }
+
+        match self.hop_service.dial_address(address) {
+            Ok(()) | Err(ImmediateDialError::AlreadyConnected) => {
+                self.pending_dials.insert(relay, vec![request]);
+            }
+            Err(error) => {
+                tracing::debug!(target: LOG_TARGET, ?relay, ?error, "failed to dial relay");
+                self.fail_request(relay, request, RelayError::RelayUnreachable).await;
+            }
+        }
+    }
+
+    /// Open hop substream to `relay` for `request`.
+    fn open_request(&mut self, relay: PeerId, request: OutboundRequest) {
+        match self.hop_service.open_substream(relay) {
+            Ok(substream_id) => {
+                self.pending_outbound.insert(substream_id, (relay, request));
+            }
+            Err(error) => {
+                tracing::debug!(target: LOG_TARGET, ?relay, ?error, "failed to open hop substream");
+
+                // `open_substream()` fails only if the connection was closed in which case the
+                // request is failed once the connection closed event is received
+                self.pending_dials.entry(relay).or_default().push(request);
+            }
+        }
+    }
+
+    /// Fail `request` sent to `relay`.
+    async fn fail_request(&mut self, relay: PeerId, request: OutboundRequest, error: RelayError) {
+        match request {
+            OutboundRequest::Reserve { .. } => {
+                if self.remove_reservation(relay) {
+                    tracing::debug!(target: LOG_TARGET, ?relay, ?error, "failed to renew reservation");
+                }
+
+                let _ = self.event_tx.send(RelayEvent::ReservationFailed { relay, error }).await;
+            }
+            OutboundRequest::Connect { tx, .. } => {
+                let _ = tx.send(Err(error));
+            }
+        }
+    }
+
+    /// Remove the reservation held with `relay` and its addresses.
+    ///
+    /// Returns `true` if the reservation existed.
+    fn remove_reservation(&mut self, relay: PeerId) -> bool {
+        let Some(reservation) = self.reservations.remove(&relay) else {
+            return false;
+        };
+
+        let public_addresses = self.hop_service.public_addresses();
+        for address in &reservation.addresses {
+            public_addresses.remove_address(address);
+        }
+        self.hop_service.release_connection(relay);
+
+        true
+    }
+
+    /// Handle command received from the user.
+    async fn on_command(&mut self, command: RelayCommand) {
+        match command {
+            RelayCommand::Reserve { address } => {
+                let Some(relay) = PeerId::try_from_multiaddr(&address) else {
+                    tracing::debug!(target: LOG_TARGET, ?address, "relay peer ID missing");
+                    return;
+                };
+
+                tracing::debug!(target: LOG_TARGET, ?relay, ?address, "make reservation");
+
+                self.send_request(relay, address.clone(), OutboundRequest::Reserve { address })
+                    .await;
+            }
+            RelayCommand::CancelReservation { relay } =>
+                if self.remove_reservation(relay) {
+                    tracing::debug!(target: LOG_TARGET, ?relay, "reservation cancelled");
+
+                    let _ = self.event_tx.send(RelayEvent::ReservationClosed { relay }).await;
+                },
+        }
+    }
+
+    /// Handle circuit request received from the relay transport.
+    async fn on_circuit_request(&mut self, request: CircuitRequest) {
+        let CircuitRequest {
+            relay: address,
+            destination,
+            tx,
+        } = request;
+
+        let Some(relay) = PeerId::try_from_multiaddr(&address) else {
+            let _ = tx.send(Err(RelayError::RelayUnreachable));
+            return;
+        };
+
+        tracing::trace!(target: LOG_TARGET, ?relay, ?destination, "open circuit");
+
+        self.send_request(relay, address, OutboundRequest::Connect { destination, tx })
+            .await;
+    }
+
+    /// Hop substream to a relay opened.
+    fn on_outbound_hop_substream(&mut self, substream_id: SubstreamId, substream: Substream) {
+        let Some((relay, request)) = self.pending_outbound.remove(&substream_id) else {
+            tracing::warn!(target: LOG_TARGET, ?substream_id, "unknown outbound hop substream");
+            return;
+        };
+
+        match request {
+            OutboundRequest::Reserve { address } =>
+                self.pending_reservations.push(Box::pin(async move {
+                    let result =
+                        match tokio::time::timeout(REQUEST_TIMEOUT, client::reserve(substream))
+                            .await
+                        {
+                            Err(_) => Err(RelayError::RelayUnreachable),
+                            Ok(result) => result,
+                        };
+
+                    (relay, address, result)
+                })),
+            OutboundRequest::Connect { destination, tx } =>
+                self.pending_tasks.push(Box::pin(async move {
+                    let result = match tokio::time::timeout(
+                        REQUEST_TIMEOUT,
+                        client::connect(substream, destination),
+                    )
+                    .await
+                    {
+                        Err(_) => Err(RelayError::RelayUnreachable),
+                        Ok(result) => result,
+                    };
+
+                    if let Err(error) = &result {
+                        tracing::debug!(
+                            target: LOG_TARGET,
+                            ?relay,
+                            ?destination,
+                            ?error,
+                            "failed to open circuit",
+                        );
+                    }
+
+                    let _ = tx.send(result);
+                })),
+        }
+    }
+
+    /// Failed to open hop substream to a relay.
+    async fn on_hop_substream_open_failure(
+        &mut self,
+        substream_id: SubstreamId,
+        error: SubstreamError,
+    ) {
+        let Some((relay, request)) = self.pending_outbound.remove(&substream_id) else {
+            return;
+        };
+
+        tracing::debug!(target: LOG_TARGET, ?relay, ?error, "failed to open hop substream");
+
+        let error = match error {
+            SubstreamError::NegotiationError(_) => RelayError::PermissionDenied,
+            _ => RelayError::RelayUnreachable,
+        };
+        self.fail_request(relay, request, error).await;
+    }
+
+    /// Relay answered a reservation request.
+    async fn on_reservation(
+        &mut self,
+        relay: PeerId,
+        address: Multiaddr,
+        result: Result<client::Reservation, RelayError>,
+    ) {
+        let reservation = match result {
+            Ok(reservation) => reservation,
+            Err(error) => {
+                tracing::debug!(target: LOG_TARGET, ?relay, ?error, "reservation failed");
+                return self.fail_request(relay, OutboundRequest::Reserve { address }, error).await;
+            }
+        };
+
+        let local_peer_id = self.hop_service.local_peer_id();
+        let public_addresses = self.hop_service.public_addresses();
+        let addresses = std::iter::once(address.clone())
+            .chain(reservation.addresses)
+            .filter(|address| PeerId::try_from_multiaddr(address) == Some(relay))
+            .filter(|address| !address.iter().any(|protocol| protocol == Protocol::P2pCircuit))
+            .map(|address| {
+                address.with(Protocol::P2pCircuit).with(Protocol::P2p(local_peer_id.into()))
+            })
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        if let Some(previous) = self.reservations.get(&relay) {
+            for address in previous.addresses.iter().filter(|address| !addresses.contains(address))
+            {
+                public_addresses.remove_address(address);
+            }
+        }
+        for address in &addresses {
+            let _ = public_addresses.add_address(address.clone());
+        }
+
+        let renew_in = reservation.expires_in * 3 / 4;
+        let renew_at = Instant::now() + renew_in;
+
+        tracing::debug!(
+            target: LOG_TARGET,
+            ?relay,
+            ?addresses,
+            ?renew_in,
+            "reservation accepted",
+        );
+
+        self.reservations.insert(
+            relay,
+            Reservation {
+                address,
+                addresses: addresses.clone(),
+                renew_at,
+            },
+        );
+        self.hop_service.retain_connection(relay);
+        self.renewals.push(Box::pin(async move {
+            tokio::time::sleep_until(renew_at).await;
+            relay
+        }));
+
+        let _ = self.event_tx.send(RelayEvent::ReservationAccepted { relay, addresses }).await;
+    }
+
+    /// Renew the reservation held with `relay`.
+    async fn on_renewal(&mut self, relay: PeerId) {
+        let address = match self.reservations.get(&relay) {
+            Some(reservation) if reservation.renew_at <= Instant::now() =>
+                reservation.address.clone(),
+            _ => return,
+        };
+
+        tracing::trace!(target: LOG_TARGET, ?relay, "renew reservation");
+
+        self.send_request(relay, address.clone(), OutboundRequest::Reserve { address })
+            .await;
+    }
+
+    /// Stop substream opened by a relay.
+    fn on_inbound_stop_substream(&mut self, relay: PeerId, substream: Substream) {
+        if !self.reservations.contains_key(&relay) {
+            tracing::debug!(
+                target: LOG_TARGET,
+                ?relay,
+                "circuit opened by a relay without a reservation",
+            );
+            return;
+        }
+
+        let circuit_tx = self.circuit_tx.clone();
+        self.pending_tasks.push(Box::pin(async move {
+            match tokio::time::timeout(READ_TIMEOUT, client::accept_circuit(substream)).await {
+                Err(_) => tracing::debug!(target: LOG_TARGET, ?relay, "circuit timed out"),
+                Ok(Err(error)) =>
+                    tracing::debug!(target: LOG_TARGET, ?relay, ?error, "invalid circuit"),
+                Ok(Ok((source, substream))) => {
+                    tracing::trace!(target: LOG_TARGET, ?relay, ?source, "circuit accepted");
+
+                    let _ = circuit_tx
+                        .send(InboundCircuit {
+                            relay,
+                            source,
+                            substream,
+                        })
+                        .await;
+                }
+            }
+        }));
+    }
+
+    /// Hop substream opened by a client.
+    fn on_inbound_hop_substream(&mut self, peer: PeerId, mut substream: Substream) {
+        self.pending_server.push(Box::pin(async move {
+            let request = match tokio::time::timeout(
+                READ_TIMEOUT,
+                server::read_request(&mut substream),
+            )
+            .await
+            {
+                Err(_) => Err(RelayError::RelayUnreachable),
+                Ok(request) => request,
+            };
+
+            ServerEvent::HopRequest {
+                peer,
+                substream,
+                request,
+            }
+        }));
+    }
+
+    /// Reject hop request with `status`.
+    fn reject(&mut self, peer: PeerId, mut substream: Substream, status: server::Status) {
+        tracing::debug!(target: LOG_TARGET, ?peer, ?status, "reject hop request");
+
+        self.pending_tasks.push(Box::pin(async move {
+            let _ = server::respond(&mut substream, status, None).await;
+            let _ = substream.close().await;
+        }));
+    }
+
+    /// Get relay addresses reported to the clients in reservations.
+    fn relay_addresses(&self) -> Vec<Multiaddr> {
+        let local_peer_id = self.hop_service.local_peer_id();
+
+        self.hop_service
+            .listen_addresses()
+            .into_iter()
+            .chain(self.hop_service.public_addresses().get_addresses())
+            .filter(|address| PeerId::try_from_multiaddr(address) == Some(local_peer_id))
+            .filter(|address| !address.iter().any(|protocol| protocol == Protocol::P2pCircuit))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Check if the connection to `peer` is relayed.
+    fn is_relayed(&self, peer: &PeerId) -> bool {
+        self.peers.get(peer).is_some_and(|endpoint| endpoint.is_relayed())
+    }
+
+    /// Handle reservation request of `peer`.
+    fn on_reserve(&mut self, peer: PeerId, substream: Substream) {
+        if self.is_relayed(&peer) {
+            return self.reject(peer, substream, server::Status::ReservationRefused);
+        }
+
+        if !self.accepted_reservations.contains_key(&peer)
+            && self.accepted_reservations.len() >= self.max_reservations
+        {
+            return self.reject(peer, substream, server::Status::ResourceLimitExceeded);
+        }
+
+        let expires_at = Instant::now() + self.reservation_duration;
+        tracing::debug!(target: LOG_TARGET, ?peer, "accept reservation");
+
+        self.accepted_reservations.insert(peer, expires_at);
+        self.hop_service.retain_connection(peer);
+        self.expirations.push(Box::pin(async move {
+            tokio::time::sleep_until(expires_at).await;
+            peer
+        }));
+
+        let duration = self.reservation_duration;
+        let addresses = self.relay_addresses();
+        let limit = self.limit;
+
+        self.pending_tasks.push(Box::pin(async move {
+            if let Err(error) =
+                server::accept_reservation(substream, duration, addresses, limit).await
+            {
+                tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to accept reservation");
+            }
+        }));
+    }
+
+    /// Reservation held by the relay may have expired.
+    fn on_expiration(&mut self, peer: PeerId) {
+        match self.accepted_reservations.get(&peer) {
+            Some(expires_at) if *expires_at <= Instant::now() => {
+                tracing::debug!(target: LOG_TARGET, ?peer, "reservation expired");
+
+                self.accepted_reservations.remove(&peer);
+                self.hop_service.release_connection(peer);
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle circuit request from `source` to `destination`.
+    fn on_connect(&mut self, source: PeerId, destination: PeerId, substream: Substream) {
+        if self.is_relayed(&source) {
+            return self.reject(source, substream, server::Status::PermissionDenied);
+        }
+
+        if !self.accepted_reservations.contains_key(&destination) {
+            return self.reject(source, substream, server::Status::NoReservation);
+        }
+
+        let peer_circuits = |peer| self.peer_circuits.get(peer).copied().unwrap_or(0usize);
+        if self.num_circuits >= self.max_circuits
+            || peer_circuits(&source) >= self.max_circuits_per_peer
+            || peer_circuits(&destination) >= self.max_circuits_per_peer
+        {
+            return self.reject(source, substream, server::Status::ResourceLimitExceeded);
+        }
+
+        match self.stop_service.open_substream(destination) {
+            Ok(substream_id) => {
+                tracing::trace!(target: LOG_TARGET, ?source, ?destination, "open stop substream");
+
+                self.num_circuits += 1;
+                *self.peer_circuits.entry(source).or_default() += 1;
+                *self.peer_circuits.entry(destination).or_default() += 1;
+
+                self.pending_circuits.insert(
+                    substream_id,
+                    PendingCircuit {
+                        source,
+                        destination,
+                        substream,
+                    },
+                );
+            }
+            Err(error) => {
+                tracing::debug!(
+                    target: LOG_TARGET,
+                    ?destination,
+                    ?error,
+                    "failed to open stop substream",
+                );
+                self.reject(source, substream, server::Status::ConnectionFailed);
+            }
+        }
+    }
+
+    /// Stop substream to the destination of a circuit opened.
+    fn on_outbound_stop_substream(&mut self, substream_id: SubstreamId, mut substream: Substream) {
+        let Some(PendingCircuit {
+            source,
+            destination,
+            substream: mut source_substream,
+        }) = self.pending_circuits.remove(&substream_id)
+        else {
+            tracing::warn!(target: LOG_TARGET, ?substream_id, "unknown outbound stop substream");
+            return;
+        };
+
+        let limit = self.limit;
+        self.pending_server.push(Box::pin(async move {
+            let result = match tokio::time::timeout(
+                READ_TIMEOUT,
+                server::connect_destination(&mut substream, source, limit),
+            )
+            .await
+            {
+                Err(_) => Err(RelayError::ConnectionFailed),
+                Ok(result) => result,
+            };
+
+            let status = match result {
+                Ok(()) => server::Status::Ok,
+                Err(error) => {
+                    tracing::debug!(
+                        target: LOG_TARGET,
+                        ?source,
+                        ?destination,
+                        ?error,
+                        "destination refused circuit",
+                    );
+                    server::Status::ConnectionFailed
+                }
+            };
+
+            match server::respond(&mut source_substream, status, Some(limit)).await {
+                Ok(()) if status == server::Status::Ok => ServerEvent::CircuitEstablished {
+                    source,
+                    destination,
+                    source_substream,
+                    destination_substream: substream,
+                },
+                _ => ServerEvent::CircuitClosed {
+                    source,
+                    destination,
+                    established: false,
+                },
+            }
+        }));
+    }
+
+    /// Failed to open stop substream to the destination of a circuit.
+    fn on_stop_substream_open_failure(&mut self, substream_id: SubstreamId, error: SubstreamError) {
+        let Some(PendingCircuit {
+            source,
+            destination,
+            substream,
+        }) = self.pending_circuits.remove(&substream_id)
+        else {
+            return;
+        };
+
+        tracing::debug!(
+            target: LOG_TARGET,
+            ?source,
+            ?destination,
+            ?error,
+            "failed to open stop substream",
+        );
+
+        self.close_circuit(source, destination);
+        self.reject(source, substream, server::Status::ConnectionFailed);
+    }
+
+    /// Release the circuit slots held by a circuit between `source` and `destination`.
+    fn close_circuit(&mut self, source: PeerId, destination: PeerId) {
+        self.num_circuits = self.num_circuits.saturating_sub(1);
+
+        for peer in [source, destination] {
+            if let Entry::Occupied(mut entry) = self.peer_circuits.entry(peer) {
+                *entry.get_mut() -= 1;
+
+                if *entry.get() == 0 {
+                    entry.remove();
+                }
+            }
+        }
+    }
+
+    /// Handle event of a request handled by the relay.
+    async fn on_server_event(&mut self, event: ServerEvent) {
+        match event {
+            ServerEvent::HopRequest {
+                peer,
+                substream,
+                request,
+            } => match request {
+                Err(error) => {
+                    tracing::debug!(target: LOG_TARGET, ?peer, ?error, "invalid hop request");
+                    self.reject(peer, substream, server::Status::MalformedMessage);
+                }
+                Ok(_) if !self.server =>
+                    self.reject(peer, substream, server::Status::PermissionDenied),
+                Ok(server::HopRequest::Reserve) => self.on_reserve(peer, substream),
+                Ok(server::HopRequest::Connect { destination }) =>
+                    self.on_connect(peer, destination, substream),
+            },
+            ServerEvent::CircuitEstablished {
+                source,
+                destination,
+                source_substream,
+                destination_substream,
+            } => {
+                tracing::debug!(target: LOG_TARGET, ?source, ?destination, "circuit opened");
+
+                let limit = self.limit;
+                self.pending_server.push(Box::pin(async move {
+                    server::relay_data(source_substream, destination_substream, limit).await;
+
+                    ServerEvent::CircuitClosed {
+                        source,
+                        destination,
+                        established: true,
+                    }
+                }));
+
+                let _ = self
+                    .event_tx
+                    .send(RelayEvent::CircuitOpened {
+                        source,
+                        destination,
+                    })
+                    .await;
+            }
+            ServerEvent::CircuitClosed {
+                source,
+                destination,
+                established,
+            } => {
+                self.close_circuit(source, destination);
+
+                if established {
+                    tracing::debug!(target: LOG_TARGET, ?source, ?destination, "circuit closed");
+
+                    let _ = self
+                        .event_tx
+                        .send(RelayEvent::CircuitClosed {
+                            source,
+                            destination,
+                        })
+                        .await;
+                }
+            }
+        }
+    }
+
+    /// Handle event received from the hop transport service.
+    async fn on_hop_event(&mut self, event: TransportEvent) {
+        match event {
+            TransportEvent::ConnectionEstablished { peer, endpoint } =>
+                self.on_connection_established(peer, endpoint),
+            TransportEvent::ConnectionClosed { peer } => self.on_connection_closed(peer).await,
+            TransportEvent::DialFailure { peer, .. } => self.on_dial_failure(peer).await,
+            TransportEvent::SubstreamOpened {
+                peer,
+                direction,
+                substream,
+                ..
+            } => match direction {
+                Direction::Inbound => self.on_inbound_hop_substream(peer, substream),
+                Direction::Outbound(substream_id) =>
+                    self.on_outbound_hop_substream(substream_id, substream),
+            },
+            TransportEvent::SubstreamOpenFailure { substream, error } =>
+                self.on_hop_substream_open_failure(substream, error).await,
+        }
+    }
+
+    /// Handle event received from the stop transport service.
+    fn on_stop_event(&mut self, event: TransportEvent) {
+        match event {
+            TransportEvent::SubstreamOpened {
+                peer,
+                direction,
+                substream,
+                ..
+            } => match direction {
+                Direction::Inbound => self.on_inbound_stop_substream(peer, substream),
+                Direction::Outbound(substream_id) =>
+                    self.on_outbound_stop_substream(substream_id, substream),
+            },
+            TransportEvent::SubstreamOpenFailure { substream, error } =>
+                self.on_stop_substream_open_failure(substream, error),
+            _ => {}
+        }
+    }
+
+    /// Start [`Relay`] event loop.
+    pub async fn run(mut self) {
+        tracing::debug!(target: LOG_TARGET, server = ?self.server, "starting relay event loop");
+
+        loop {
+            tokio::select! {
+                event = self.hop_service.next() => match event {
+                    None => {
+                        tracing::warn!(target: LOG_TARGET, "hop transport service stream ended, terminating relay event loop");
+                        return
+                    },
+                    Some(event) => self.on_hop_event(event).await,
+                },
+                event = self.stop_service.next() => match event {
+                    None => {
+                        tracing::warn!(target: LOG_TARGET, "stop transport service stream ended, terminating relay event loop");
+                        return
+                    },
+                    Some(event) => self.on_stop_event(event),
+                },
+                Some(command) = self.cmd_rx.recv() => self.on_command(command).await,
+                Some(request) = self.request_rx.recv() => self.on_circuit_request(request).await,
+                Some((relay, address, result)) = self.pending_reservations.next() =>
+                    self.on_reservation(relay, address, result).await,
+                Some(relay) = self.renewals.next() => self.on_renewal(relay).await,
+                Some(peer) = self.expirations.next() => self.on_expiration(peer),
+                Some(event) = self.pending_server.next() => self.on_server_event(event).await,
+                _ = self.pending_tasks.next() => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_conversion() {
+        use schema::Status;
+
+        assert_eq!(status_to_result(Status::Ok), Ok(()));
+        assert_eq!(
+            status_to_result(Status::ReservationRefused),
+            Err(RelayError::ReservationRefused)
+        );
+        assert_eq!(
+            status_to_result(Status::ResourceLimitExceeded),
+            Err(RelayError::ResourceLimitExceeded)
+        );
+        assert_eq!(
+            status_to_result(Status::PermissionDenied),
+            Err(RelayError::PermissionDenied)
+        );
+        assert_eq!(
+            status_to_result(Status::ConnectionFailed),
+            Err(RelayError::ConnectionFailed)
+        );
+        assert_eq!(
+            status_to_result(Status::NoReservation),
+            Err(RelayError::NoReservation)
+        );
+
+        for status in [
+            Status::Unused,
+            Status::MalformedMessage,
+            Status::UnexpectedMessage,
+        ] {
+            assert_eq!(status_to_result(status), Err(RelayError::ProtocolViolation));
+        }
+    }
+
+    #[tokio::test]
+    async fn relay_continues_after_half_close() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let (mut source, relay_source) = tokio::io::duplex(64);
+        let (mut destination, relay_destination) = tokio::io::duplex(64);
+        let limit = server::Limit {
+            duration: Duration::from_secs(10),
+            data: 1024,
+        };
+        let relay = tokio::spawn(server::relay_data(relay_source, relay_destination, limit));
+
+        // source stops sending, closing its write half
+        source.write_all(b"hello").await.unwrap();
+        source.shutdown().await.unwrap();
+
+        let mut received = Vec::new();
+        destination.read_to_end(&mut received).await.unwrap();
+        assert_eq!(received, b"hello");
+
+        // destination keeps sending after the source has closed its write half
+        destination.write_all(b"world").await.unwrap();
+        destination.shutdown().await.unwrap();
+
+        let mut received = Vec::new();
+        source.read_to_end(&mut received).await.unwrap();
+        assert_eq!(received, b"world");
+
+        tokio::time::timeout(Duration::from_secs(5), relay).await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn data_limit_closes_one_direction() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let (mut source, relay_source) = tokio::io::duplex(64);
+        let (mut destination, relay_destination) = tokio::io::duplex(64);
+        let limit = server::Limit {
+            duration: Duration::from_secs(10),
+            data: 4,
+        };
+        tokio::spawn(server::relay_data(relay_source, relay_destination, limit));
+
+        // only the first `limit.data` bytes are relayed before the direction is closed
+        source.write_all(b"hello").await.unwrap();
+
+        let mut received = Vec::new();
+        destination.read_to_end(&mut received).await.unwrap();
+        assert_eq!(received, b"hell");
+
+        // the other direction still relays data
+        destination.write_all(b"hi").await.unwrap();
+
+        let mut received = [0u8; 2];
+        source.read_exact(&mut received).await.unwrap();
+        assert_eq!(&received, b"hi");
+    }
+}
@@ -0,0 +1,199 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Relay side of the circuit relay: reservations held and circuits relayed for clients.
+
+use crate::{
+    error::RelayError,
+    protocol::libp2p::relay::{read_message, schema, send_message, status_to_result},
+    substream::Substream,
+    PeerId,
+};
+
+use multiaddr::Multiaddr;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub(super) use schema::Status;
+
+/// Limits of a relayed circuit.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Limit {
+    /// Maximum duration of the circuit.
+    pub(super) duration: Duration,
+
+    /// Maximum number of bytes relayed in each direction.
+    pub(super) data: u64,
+}
+
+impl From<Limit> for schema::Limit {
+    fn from(limit: Limit) -> Self {
+        schema::Limit {
+            duration: Some(u32::try_from(limit.duration.as_secs()).unwrap_or(u32::MAX)),
+            data: Some(limit.data),
+        }
+    }
+}
+
+/// Request received from a client over the hop protocol.
+#[derive(Debug)]
+pub(super) enum HopRequest {
+    /// Make a reservation for the client.
+    Reserve,
+
+    /// Open a circuit to `destination`.
+    Connect {
+        /// Peer ID of the destination.
+        destination: PeerId,
+    },
+}
+
+/// Read a hop request from the client.
+pub(super) async fn read_request(substream: &mut Substream) -> Result<HopRequest, RelayError> {
+    use schema::{hop_message::Type, HopMessage};
+
+    let message = read_message::<HopMessage>(substream).await?;
+
+    match message.r#type() {
+        Type::Reserve => Ok(HopRequest::Reserve),
+        Type::Connect => message
+            .peer
+            .and_then(|peer| PeerId::from_bytes(&peer.id).ok())
+            .map(|destination| HopRequest::Connect { destination })
+            .ok_or(RelayError::ProtocolViolation),
+        Type::Status => Err(RelayError::ProtocolViolation),
+    }
+}
+
+/// Send hop status to the client.
+pub(super) async fn respond(
+    substream: &mut Substream,
+    status: Status,
+    limit: Option<Limit>,
+) -> Result<(), RelayError> {
+    use schema::{hop_message::Type, HopMessage};
+
+    send_message(
+        substream,
+        &HopMessage {
+            r#type: Type::Status as i32,
+            peer: None,
+            reservation: None,
+            limit: limit.map(From::from),
+            status: Some(status as i32),
+        },
+    )
+    .await
+}
+
+/// Accept the reservation of the client.
+pub(super) async fn accept_reservation(
+    mut substream: Substream,
+    duration: Duration,
+    addresses: Vec<Multiaddr>,
+    limit: Limit,
+) -> Result<(), RelayError> {
+    use schema::{hop_message::Type, HopMessage, Reservation};
+
+    let expire = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default() + duration;
+
+    send_message(
+        &mut substream,
+        &HopMessage {
+            r#type: Type::Status as i32,
+            peer: None,
+            reservation: Some(Reservation {
+                expire: expire.as_secs(),
+                addrs: addresses.iter().map(|address| address.to_vec()).collect(),
+                voucher: None,
+            }),
+            limit: Some(limit.into()),
+            status: Some(Status::Ok as i32),
+        },
+    )
+    .await?;
+    let _ = substream.close().await;
+
+    Ok(())
+}
+
+/// Ask the destination to accept a circuit from `source`.
+pub(super) async fn connect_destination(
+    substream: &mut Substream,
+    source: PeerId,
+    limit: Limit,
+) -> Result<(), RelayError> {
+    use schema::{stop_message::Type, Peer, StopMessage};
+
+    send_message(
+        substream,
+        &StopMessage {
+            r#type: Type::Connect as i32,
+            peer: Some(Peer {
+                id: source.to_bytes(),
+                addrs: Vec::new(),
+            }),
+            limit: Some(limit.into()),
+            status: None,
+        },
+    )
+    .await?;
+
+    let message = read_message::<StopMessage>(substream).await?;
+
+    if message.r#type() != Type::Status {
+        return Err(RelayError::ProtocolViolation);
+    }
+
+    status_to_result(message.status())
+}
+
+/// Relay data between the source and the destination of a circuit until both sides have closed
+/// their substreams or the circuit reaches its duration limit.
+///
+/// Both directions are relayed independently: once one side stops sending, or its direction
+/// reaches the data limit, the write half of the other side is closed while data keeps flowing
+/// in the opposite direction.
+pub(super) async fn relay_data<S, D>(source: S, destination: D, limit: Limit)
+where
+    S: AsyncRead + AsyncWrite,
+    D: AsyncRead + AsyncWrite,
+{
+    let (source_read, mut source_write) = tokio::io::split(source);
+    let (destination_read, mut destination_write) = tokio::io::split(destination);
+
+    let mut source_read = source_read.take(limit.data);
+    let mut destination_read = destination_read.take(limit.data);
+
+    let upstream = async {
+        let _ = tokio::io::copy(&mut source_read, &mut destination_write).await;
+        let _ = destination_write.shutdown().await;
+    };
+    let downstream = async {
+        let _ = tokio::io::copy(&mut destination_read, &mut source_write).await;
+        let _ = source_write.shutdown().await;
+    };
+
+    let _ = tokio::time::timeout(limit.duration, async {
+        tokio::join!(upstream, downstream);
+    })
+    .await;
+}
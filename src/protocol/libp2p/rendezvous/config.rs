@@ -0,0 +1,166 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use crate::{
+    codec::ProtocolCodec,
+    protocol::libp2p::rendezvous::{RendezvousCommand, RendezvousHandle},
+    types::protocol::ProtocolName,
+    DEFAULT_CHANNEL_SIZE,
+};
+
+use tokio::sync::mpsc::{channel, Receiver};
+
+use std::time::Duration;
+
+/// Rendezvous protocol name.
+pub const PROTOCOL_NAME: &str = "/rendezvous/1.0.0";
+
+/// Maximum size of a rendezvous message.
+const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// Default minimum TTL of a registration.
+const MIN_TTL: Duration = Duration::from_secs(2 * 60 * 60);
+
+/// Default maximum TTL of a registration.
+const MAX_TTL: Duration = Duration::from_secs(72 * 60 * 60);
+
+/// TTL of a registration if the peer didn't request one.
+const DEFAULT_TTL: Duration = Duration::from_secs(2 * 60 * 60);
+
+/// Default timeout for rendezvous requests.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Rendezvous configuration.
+pub struct Config {
+    /// Protocol name.
+    pub(crate) protocol: ProtocolName,
+
+    /// Codec used by the protocol.
+    pub(crate) codec: ProtocolCodec,
+
+    /// Whether the local node acts as a rendezvous point.
+    pub(crate) server: bool,
+
+    /// Minimum TTL of a registration.
+    pub(crate) min_ttl: Duration,
+
+    /// Maximum TTL of a registration.
+    pub(crate) max_ttl: Duration,
+
+    /// TTL of a registration if the peer didn't request one.
+    pub(crate) default_ttl: Duration,
+
+    /// Timeout for requests.
+    pub(crate) request_timeout: Duration,
+
+    /// RX channel for receiving commands from the user.
+    pub(crate) cmd_rx: Receiver<RendezvousCommand>,
+}
+
+impl Config {
+    /// Create new [`Config`] with default values.
+    ///
+    /// The local node acts only as a rendezvous client. Returns a config that is given to
+    /// `Litep2pConfig` and a [`RendezvousHandle`].
+    pub fn default() -> (Self, RendezvousHandle) {
+        ConfigBuilder::new().build()
+    }
+}
+
+/// Rendezvous configuration builder.
+pub struct ConfigBuilder {
+    /// Whether the local node acts as a rendezvous point.
+    server: bool,
+
+    /// Minimum TTL of a registration.
+    min_ttl: Duration,
+
+    /// Maximum TTL of a registration.
+    max_ttl: Duration,
+
+    /// Timeout for requests.
+    request_timeout: Duration,
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigBuilder {
+    /// Create new default [`Config`] which can be modified by the user.
+    pub fn new() -> Self {
+        Self {
+            server: false,
+            min_ttl: MIN_TTL,
+            max_ttl: MAX_TTL,
+            request_timeout: REQUEST_TIMEOUT,
+        }
+    }
+
+    /// Act as a rendezvous point which accepts registrations from other peers.
+    ///
+    /// By default the local node acts only as a rendezvous client.
+    pub fn with_server(mut self, server: bool) -> Self {
+        self.server = server;
+        self
+    }
+
+    /// Set the minimum and maximum TTL of the registrations accepted by the rendezvous point.
+    ///
+    /// Registrations with a TTL outside of these bounds are rejected. Registrations without a
+    /// TTL are given the default TTL of two hours, clamped to the bounds.
+    pub fn with_ttl_bounds(mut self, min_ttl: Duration, max_ttl: Duration) -> Self {
+        assert!(
+            min_ttl <= max_ttl,
+            "minimum ttl must not exceed maximum ttl"
+        );
+
+        self.min_ttl = min_ttl;
+        self.max_ttl = max_ttl;
+        self
+    }
+
+    /// Set timeout for rendezvous requests.
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    /// Build [`Config`].
+    pub fn build(self) -> (Config, RendezvousHandle) {
+        let (cmd_tx, cmd_rx) = channel(DEFAULT_CHANNEL_SIZE);
+
+        (
+            Config {
+                protocol: ProtocolName::from(PROTOCOL_NAME),
+                codec: ProtocolCodec::UnsignedVarint(Some(MAX_MESSAGE_SIZE)),
+                server: self.server,
+                min_ttl: self.min_ttl,
+                max_ttl: self.max_ttl,
+                default_ttl: DEFAULT_TTL,
+                request_timeout: self.request_timeout,
+                cmd_rx,
+            },
+            RendezvousHandle::new(cmd_tx),
+        )
+    }
+}
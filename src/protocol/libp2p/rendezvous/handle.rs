@@ -0,0 +1,187 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Handle for communicating with the rendezvous protocol implementation.
+
+use crate::{error::RendezvousError, PeerId};
+
+use multiaddr::Multiaddr;
+use tokio::sync::{mpsc::Sender, oneshot};
+
+use std::time::Duration;
+
+/// Opaque pagination cookie returned by a rendezvous point.
+///
+/// Passing the cookie to [`RendezvousHandle::discover()`] returns only the registrations made
+/// after the previous discovery.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cookie(pub(super) Vec<u8>);
+
+/// Registration of a peer, discovered from a rendezvous point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Registration {
+    /// Namespace of the registration.
+    pub namespace: String,
+
+    /// Registered peer.
+    pub peer: PeerId,
+
+    /// Addresses of the peer, verified from its signed peer record.
+    pub addresses: Vec<Multiaddr>,
+
+    /// TTL of the registration.
+    pub ttl: Duration,
+}
+
+/// Commands sent from the user to `Rendezvous`.
+#[derive(Debug)]
+pub(crate) enum RendezvousCommand {
+    /// Register to a namespace.
+    Register {
+        /// Rendezvous point.
+        rendezvous_point: PeerId,
+
+        /// Namespace.
+        namespace: String,
+
+        /// Requested TTL.
+        ttl: Option<Duration>,
+
+        /// TX channel for sending the TTL granted by the rendezvous point.
+        tx: oneshot::Sender<Result<Duration, RendezvousError>>,
+    },
+
+    /// Unregister from a namespace.
+    Unregister {
+        /// Rendezvous point.
+        rendezvous_point: PeerId,
+
+        /// Namespace.
+        namespace: String,
+
+        /// TX channel for sending the result of the unregistration.
+        tx: oneshot::Sender<Result<(), RendezvousError>>,
+    },
+
+    /// Discover peers registered to a namespace.
+    Discover {
+        /// Rendezvous point.
+        rendezvous_point: PeerId,
+
+        /// Namespace, or all namespaces if `None`.
+        namespace: Option<String>,
+
+        /// Maximum number of registrations to return.
+        limit: Option<u64>,
+
+        /// Cookie of the previous discovery.
+        cookie: Option<Cookie>,
+
+        /// TX channel for sending the discovered registrations.
+        tx: oneshot::Sender<Result<(Vec<Registration>, Cookie), RendezvousError>>,
+    },
+}
+
+/// Handle for communicating with the rendezvous protocol.
+#[derive(Debug, Clone)]
+pub struct RendezvousHandle {
+    /// TX channel for sending commands to `Rendezvous`.
+    cmd_tx: Sender<RendezvousCommand>,
+}
+
+impl RendezvousHandle {
+    /// Create new [`RendezvousHandle`].
+    pub(super) fn new(cmd_tx: Sender<RendezvousCommand>) -> Self {
+        Self { cmd_tx }
+    }
+
+    /// Send `command` to `Rendezvous` and wait for the result.
+    async fn request<T>(
+        &self,
+        command: impl FnOnce(oneshot::Sender<Result<T, RendezvousError>>) -> RendezvousCommand,
+    ) -> Result<T, RendezvousError> {
+        let (tx, rx) = oneshot::channel();
+
+        self.cmd_tx.send(command(tx)).await.map_err(|_| RendezvousError::TaskClosed)?;
+
+        rx.await.map_err(|_| RendezvousError::TaskClosed)?
+    }
+
+    /// Register the local node to `namespace` of `rendezvous_point`.
+    ///
+    /// The registration carries a signed peer record with the listen and public addresses of
+    /// the local node. If `ttl` is `None`, the default TTL of the rendezvous point is used.
+    /// Returns the TTL granted by the rendezvous point.
+    ///
+    /// The rendezvous point is dialed if the local node isn't connected to it.
+    pub async fn register(
+        &self,
+        rendezvous_point: PeerId,
+        namespace: impl Into<String>,
+        ttl: Option<Duration>,
+    ) -> Result<Duration, RendezvousError> {
+        self.request(|tx| RendezvousCommand::Register {
+            rendezvous_point,
+            namespace: namespace.into(),
+            ttl,
+            tx,
+        })
+        .await
+    }
+
+    /// Unregister the local node from `namespace` of `rendezvous_point`.
+    ///
+    /// Returns once the request has been sent as the rendezvous point doesn't respond to it.
+    pub async fn unregister(
+        &self,
+        rendezvous_point: PeerId,
+        namespace: impl Into<String>,
+    ) -> Result<(), RendezvousError> {
+        self.request(|tx| RendezvousCommand::Unregister {
+            rendezvous_point,
+            namespace: namespace.into(),
+            tx,
+        })
+        .await
+    }
+
+    /// Discover peers registered to `namespace` of `rendezvous_point`, or to all of its
+    /// namespaces if `namespace` is `None`.
+    ///
+    /// The addresses of the discovered peers are added to the known addresses of the local
+    /// node so the peers can be dialed by their peer IDs. The returned [`Cookie`] can be passed
+    /// to the next call to only discover the peers registered since.
+    pub async fn discover(
+        &self,
+        rendezvous_point: PeerId,
+        namespace: Option<String>,
+        limit: Option<u64>,
+        cookie: Option<Cookie>,
+    ) -> Result<(Vec<Registration>, Cookie), RendezvousError> {
+        self.request(|tx| RendezvousCommand::Discover {
+            rendezvous_point,
+            namespace,
+            limit,
+            cookie,
+            tx,
+        })
+        .await
+    }
+}
@@ -0,0 +1,628 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! [Rendezvous](https://github.com/libp2p/specs/blob/master/rendezvous/README.md) implementation.
+//!
+//! Peers register to namespaces of a rendezvous point with signed peer records carrying their
+//! addresses, and other peers discover the registered peers from the rendezvous point. The
+//! addresses of the discovered peers are added to the transport manager so the peers can be
+//! dialed by their peer IDs.
+//!
+//! The local node always acts as a rendezvous client and, if enabled, also as a rendezvous point.
+//! Each request is sent over its own substream.
+
+use crate::{
+    crypto::{ed25519::Keypair, PublicKey},
+    error::{RendezvousError, SubstreamError},
+    protocol::{Direction, TransportEvent, TransportService},
+    substream::Substream,
+    types::SubstreamId,
+    utils::futures_stream::FuturesStream,
+    PeerId,
+};
+
+use futures::{future::BoxFuture, StreamExt};
+use multiaddr::Protocol;
+use prost::Message;
+use tokio::sync::oneshot;
+
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+pub use config::{Config, ConfigBuilder, PROTOCOL_NAME};
+pub use handle::{Cookie, Registration, RendezvousHandle};
+
+pub(crate) use handle::RendezvousCommand;
+
+use record::{sign_record, verify_record};
+use server::Registrations;
+
+mod config;
+mod handle;
+mod record;
+mod server;
+
+mod schema {
+    include!(concat!(env!("OUT_DIR"), "/rendezvous.rs"));
+}
+
+/// Log target for the file.
+const LOG_TARGET: &str = "litep2p::rendezvous";
+
+/// Message type.
+type MessageType = schema::message::MessageType;
+
+/// Response status.
+type ResponseStatus = schema::message::ResponseStatus;
+
+/// Convert the response status of a rendezvous point into a result.
+fn check_status(status: Option<i32>) -> Result<(), RendezvousError> {
+    match status.map(ResponseStatus::try_from) {
+        Some(Ok(ResponseStatus::Ok)) => Ok(()),
+        Some(Ok(ResponseStatus::EInvalidNamespace)) => Err(RendezvousError::InvalidNamespace),
+        Some(Ok(ResponseStatus::EInvalidSignedPeerRecord)) =>
+            Err(RendezvousError::InvalidSignedPeerRecord),
+        Some(Ok(ResponseStatus::EInvalidTtl)) => Err(RendezvousError::InvalidTtl),
+        Some(Ok(ResponseStatus::EInvalidCookie)) => Err(RendezvousError::InvalidCookie),
+        Some(Ok(ResponseStatus::ENotAuthorized)) => Err(RendezvousError::NotAuthorized),
+        Some(Ok(ResponseStatus::EInternalError)) => Err(RendezvousError::InternalError),
+        Some(Ok(ResponseStatus::EUnavailable)) => Err(RendezvousError::Unavailable),
+        Some(Err(_)) | None => Err(RendezvousError::InvalidResponse),
+    }
+}
+
+/// Pending request of the local node.
+enum RequestKind {
+    /// Register request.
+    Register {
+        /// TX channel for sending the TTL granted by the rendezvous point.
+        tx: oneshot::Sender<Result<Duration, RendezvousError>>,
+    },
+
+    /// Unregister request.
+    Unregister {
+        /// TX channel for sending the result of the unregistration.
+        tx: oneshot::Sender<Result<(), RendezvousError>>,
+    },
+
+    /// Discover request.
+    Discover {
+        /// TX channel for sending the discovered registrations.
+        tx: oneshot::Sender<Result<(Vec<Registration>, Cookie), RendezvousError>>,
+    },
+}
+
+impl RequestKind {
+    /// Report failure of the request to the user.
+    fn fail(self, error: RendezvousError) {
+        match self {
+            Self::Register { tx } => {
+                let _ = tx.send(Err(error));
+            }
+            Self::Unregister { tx } => {
+                let _ = tx.send(Err(error));
+            }
+            Self::Discover { tx } => {
+                let _ = tx.send(Err(error));
+            }
+        }
+    }
+}
+
+/// Request waiting to be sent to a rendezvous point.
+struct PendingRequest {
+    /// Request kind.
+    kind: RequestKind,
+
+    /// Request message.
+    message: schema::Message,
+}
+
+/// Rendezvous protocol.
+pub(crate) struct Rendezvous {
+    /// Transport service.
+    service: TransportService,
+
+    /// Rendezvous configuration.
+    config: Config,
+
+    /// Keypair used to sign peer records.
+    keypair: Keypair,
+
+    /// Local peer ID.
+    local_peer_id: PeerId,
+
+    /// Connected peers.
+    connected: HashSet<PeerId>,
+
+    /// Requests waiting for a connection to the rendezvous point.
+    pending_dials: HashMap<PeerId, Vec<PendingRequest>>,
+
+    /// Requests waiting for an outbound substream.
+    pending_outbound: HashMap<SubstreamId, PendingRequest>,
+
+    /// Requests waiting for a response.
+    pending_responses: FuturesStream<
+        BoxFuture<
+            'static,
+            (
+                RequestKind,
+                Result<Option<schema::Message>, RendezvousError>,
+            ),
+        >,
+    >,
+
+    /// Inbound requests being read.
+    pending_inbound:
+        FuturesStream<BoxFuture<'static, Option<(PeerId, Substream, schema::Message)>>>,
+
+    /// Responses being sent.
+    pending_sends: FuturesStream<BoxFuture<'static, ()>>,
+
+    /// Registrations, if the local node acts as a rendezvous point.
+    registrations: Option<Registrations>,
+}
+
+impl Rendezvous {
+    /// Create new [`Rendezvous`] protocol.
+    pub(crate) fn new(service: TransportService, config: Config, keypair: Keypair) -> Self {
+        Self {
+            local_peer_id: PeerId::from_public_key(&PublicKey::Ed25519(keypair.public())),
+            registrations: config
+                .server
+                .then(|| Registrations::new(config.min_ttl, config.max_ttl, config.default_ttl)),
+            service,
+            config,
+            keypair,
+            connected: HashSet::new(),
+            pending_dials: HashMap::new(),
+            pending_outbound: HashMap::new(),
+            pending_responses: FuturesStream::new(),
+            pending_inbound: FuturesStream::new(),
+            pending_sends: FuturesStream::new(),
+        }
+    }
+
+    /// Send `request` to `peer`, dialing the peer if it's not connected.
+    fn send_request(&mut self, peer: PeerId, request: PendingRequest) {
+        if !self.connected.contains(&peer) {
+            match self.service.dial(&peer) {
+                Ok(()) => self.pending_dials.entry(peer).or_default().push(request),
+                Err(error) => {
+                    tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to dial peer");
+                    request.kind.fail(RendezvousError::ConnectionFailed);
+                }
+            }
+            return;
+        }
+
+        match self.service.open_substream(peer) {
+            Ok(substream_id) => {
+                self.pending_outbound.insert(substream_id, request);
+            }
+            Err(error) => {
+                tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to open substream");
+                request.kind.fail(RendezvousError::ConnectionFailed);
+            }
+        }
+    }
+
+    /// Connection established to remote peer.
+    fn on_connection_established(&mut self, peer: PeerId) {
+        tracing::trace!(target: LOG_TARGET, ?peer, "connection established");
+
+        self.connected.insert(peer);
+
+        for request in self.pending_dials.remove(&peer).unwrap_or_default() {
+            self.send_request(peer, request);
+        }
+    }
+
+    /// Connection closed to remote peer.
+    fn on_connection_closed(&mut self, peer: PeerId) {
+        tracing::trace!(target: LOG_TARGET, ?peer, "connection closed");
+
+        self.connected.remove(&peer);
+    }
+
+    /// Failed to dial remote peer.
+    fn on_dial_failure(&mut self, peer: PeerId) {
+        tracing::debug!(target: LOG_TARGET, ?peer, "failed to dial rendezvous point");
+
+        for request in self.pending_dials.remove(&peer).unwrap_or_default() {
+            request.kind.fail(RendezvousError::ConnectionFailed);
+        }
+    }
+
+    /// Outbound substream opened, send the request and read the response if there is one.
+    fn on_outbound_substream(&mut self, substream_id: SubstreamId, mut substream: Substream) {
+        let Some(PendingRequest { kind, message }) = self.pending_outbound.remove(&substream_id)
+        else {
+            tracing::warn!(target: LOG_TARGET, ?substream_id, "unknown outbound substream");
+            return;
+        };
+
+        let expects_response = !std::matches!(kind, RequestKind::Unregister { .. });
+        let request_timeout = self.config.request_timeout;
+
+        self.pending_responses.push(Box::pin(async move {
+            let result = tokio::time::timeout(request_timeout, async move {
+                substream
+                    .send_framed(message.encode_to_vec().into())
+                    .await
+                    .map_err(|_| RendezvousError::ConnectionFailed)?;
+
+                if !expects_response {
+                    substream.close().await;
+                    return Ok(None);
+                }
+
+                match substream.next().await {
+                    Some(Ok(response)) => schema::Message::decode(response.freeze())
+                        .map(Some)
+                        .map_err(|_| RendezvousError::InvalidResponse),
+                    _ => Err(RendezvousError::ConnectionFailed),
+                }
+            })
+            .await
+            .unwrap_or(Err(RendezvousError::Timeout));
+
+            (kind, result)
+        }));
+    }
+
+    /// Failed to open outbound substream.
+    fn on_substream_open_failure(&mut self, substream_id: SubstreamId, error: SubstreamError) {
+        let Some(request) = self.pending_outbound.remove(&substream_id) else {
+            return;
+        };
+
+        tracing::debug!(target: LOG_TARGET, ?substream_id, ?error, "failed to open substream");
+        request.kind.fail(RendezvousError::ConnectionFailed);
+    }
+
+    /// Response received from a rendezvous point.
+    fn on_response(
+        &mut self,
+        kind: RequestKind,
+        result: Result<Option<schema::Message>, RendezvousError>,
+    ) {
+        match kind {
+            RequestKind::Register { tx } => {
+                let result = result.and_then(|message| {
+                    let response = message
+                        .and_then(|message| message.register_response)
+                        .ok_or(RendezvousError::InvalidResponse)?;
+                    check_status(response.status)?;
+
+                    Ok(Duration::from_secs(response.ttl.unwrap_or_default()))
+                });
+
+                let _ = tx.send(result);
+            }
+            RequestKind::Unregister { tx } => {
+                let _ = tx.send(result.map(|_| ()));
+            }
+            RequestKind::Discover { tx } => {
+                let result = result.and_then(|message| {
+                    let response = message
+                        .and_then(|message| message.discover_response)
+                        .ok_or(RendezvousError::InvalidResponse)?;
+                    check_status(response.status)?;
+
+                    Ok((
+                        self.on_registrations(response.registrations),
+                        Cookie(response.cookie.unwrap_or_default()),
+                    ))
+                });
+
+                let _ = tx.send(result);
+            }
+        }
+    }
+
+    /// Verify discovered registrations and add the addresses of the registered peers to the
+    /// transport manager.
+    fn on_registrations(
+        &mut self,
+        registrations: Vec<schema::message::Register>,
+    ) -> Vec<Registration> {
+        registrations
+            .into_iter()
+            .filter_map(|registration| {
+                let Some((peer, addresses)) =
+                    registration.signed_peer_record.as_deref().and_then(verify_record)
+                else {
+                    tracing::debug!(
+                        target: LOG_TARGET,
+                        namespace = ?registration.ns,
+                        "discovered registration with invalid signed peer record",
+                    );
+                    return None;
+                };
+
+                if peer != self.local_peer_id {
+                    self.service.add_known_address(&peer, addresses.iter().cloned());
+                }
+
+                Some(Registration {
+                    namespace: registration.ns.unwrap_or_default(),
+                    peer,
+                    addresses,
+                    ttl: Duration::from_secs(registration.ttl.unwrap_or_default()),
+                })
+            })
+            .collect()
+    }
+
+    /// Inbound substream opened, read the request if the local node is a rendezvous point.
+    fn on_inbound_substream(&mut self, peer: PeerId, mut substream: Substream) {
+        if self.registrations.is_none() {
+            tracing::trace!(target: LOG_TARGET, ?peer, "rendezvous point disabled, reject request");
+            return;
+        }
+
+        let request_timeout = self.config.request_timeout;
+
+        self.pending_inbound.push(Box::pin(async move {
+            match tokio::time::timeout(request_timeout, substream.next()).await {
+                Ok(Some(Ok(request))) => match schema::Message::decode(request.freeze()) {
+                    Ok(message) => Some((peer, substream, message)),
+                    Err(error) => {
+                        tracing::debug!(target: LOG_TARGET, ?peer, ?error, "invalid request");
+                        None
+                    }
+                },
+                _ => None,
+            }
+        }));
+    }
+
+    /// Request received from `peer`.
+    fn on_request(&mut self, peer: PeerId, mut substream: Substream, message: schema::Message) {
+        let Some(registrations) = self.registrations.as_mut() else {
+            return;
+        };
+
+        let response = match message.r#type.map(MessageType::try_from) {
+            Some(Ok(MessageType::Register)) => {
+                let register = message.register.unwrap_or_default();
+                let namespace = register.ns.unwrap_or_default();
+                let record = register.signed_peer_record.unwrap_or_default();
+
+                let result = match verify_record(&record) {
+                    Some((record_peer, _)) if record_peer == peer => registrations.add(
+                        namespace.clone(),
+                        peer,
+                        record,
+                        register.ttl.map(Duration::from_secs),
+                    ),
+                    Some(_) => Err(ResponseStatus::ENotAuthorized),
+                    None => Err(ResponseStatus::EInvalidSignedPeerRecord),
+                };
+
+                tracing::trace!(target: LOG_TARGET, ?peer, ?namespace, ?result, "register request");
+
+                let (status, ttl) = match result {
+                    Ok(ttl) => (ResponseStatus::Ok, Some(ttl.as_secs())),
+                    Err(status) => (status, None),
+                };
+
+                schema::Message {
+                    r#type: Some(MessageType::RegisterResponse as i32),
+                    register_response: Some(schema::message::RegisterResponse {
+                        status: Some(status as i32),
+                        status_text: None,
+                        ttl,
+                    }),
+                    ..Default::default()
+                }
+            }
+            Some(Ok(MessageType::Unregister)) => {
+                let namespace = message.unregister.and_then(|unregister| unregister.ns);
+                tracing::trace!(target: LOG_TARGET, ?peer, ?namespace, "unregister request");
+
+                registrations.remove(&namespace.unwrap_or_default(), &peer);
+                return;
+            }
+            Some(Ok(MessageType::Discover)) => {
+                let discover = message.discover.unwrap_or_default();
+                let namespace = discover.ns.filter(|namespace| !namespace.is_empty());
+
+                tracing::trace!(target: LOG_TARGET, ?peer, ?namespace, "discover request");
+
+                let response = match registrations.discover(
+                    namespace.as_deref(),
+                    discover.limit,
+                    discover.cookie.as_deref(),
+                ) {
+                    Ok((registrations, cookie)) => schema::message::DiscoverResponse {
+                        registrations,
+                        cookie: Some(cookie),
+                        status: Some(ResponseStatus::Ok as i32),
+                        status_text: None,
+                    },
+                    Err(status) => schema::message::DiscoverResponse {
+                        status: Some(status as i32),
+                        ..Default::default()
+                    },
+                };
+
+                schema::Message {
+                    r#type: Some(MessageType::DiscoverResponse as i32),
+                    discover_response: Some(response),
+                    ..Default::default()
+                }
+            }
+            _ => {
+                tracing::debug!(target: LOG_TARGET, ?peer, r#type = ?message.r#type, "unexpected request");
+                return;
+            }
+        };
+
+        self.pending_sends.push(Box::pin(async move {
+            let _ = substream.send_framed(response.encode_to_vec().into()).await;
+            substream.close().await;
+        }));
+    }
+
+    /// Create signed peer record advertising the listen and public addresses of the local node.
+    fn local_record(&self) -> Vec<u8> {
+        let addresses = self
+            .service
+            .listen_addresses()
+            .into_iter()
+            .chain(self.service.public_addresses().get_addresses())
+            .map(|mut address| {
+                if std::matches!(address.iter().last(), Some(Protocol::P2p(_))) {
+                    address.pop();
+                }
+                address
+            })
+            .collect::<HashSet<_>>();
+        let seq = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0u64, |duration| duration.as_secs());
+
+        sign_record(&self.keypair, addresses.into_iter(), seq)
+    }
+
+    /// Handle command received from the user.
+    fn on_command(&mut self, command: RendezvousCommand) {
+        let (peer, request) = match command {
+            RendezvousCommand::Register {
+                rendezvous_point,
+                namespace,
+                ttl,
+                tx,
+            } => (
+                rendezvous_point,
+                PendingRequest {
+                    kind: RequestKind::Register { tx },
+                    message: schema::Message {
+                        r#type: Some(MessageType::Register as i32),
+                        register: Some(schema::message::Register {
+                            ns: Some(namespace),
+                            signed_peer_record: Some(self.local_record()),
+                            ttl: ttl.map(|ttl| ttl.as_secs()),
+                        }),
+                        ..Default::default()
+                    },
+                },
+            ),
+            RendezvousCommand::Unregister {
+                rendezvous_point,
+                namespace,
+                tx,
+            } => (
+                rendezvous_point,
+                PendingRequest {
+                    kind: RequestKind::Unregister { tx },
+                    message: schema::Message {
+                        r#type: Some(MessageType::Unregister as i32),
+                        unregister: Some(schema::message::Unregister {
+                            ns: Some(namespace),
+                            id: None,
+                        }),
+                        ..Default::default()
+                    },
+                },
+            ),
+            RendezvousCommand::Discover {
+                rendezvous_point,
+                namespace,
+                limit,
+                cookie,
+                tx,
+            } => (
+                rendezvous_point,
+                PendingRequest {
+                    kind: RequestKind::Discover { tx },
+                    message: schema::Message {
+                        r#type: Some(MessageType::Discover as i32),
+                        discover: Some(schema::message::Discover {
+                            ns: namespace,
+                            limit,
+                            cookie: cookie.map(|cookie| cookie.0),
+                        }),
+                        ..Default::default()
+                    },
+                },
+            ),
+        };
+
+        self.send_request(peer, request);
+    }
+
+    /// Handle event received from the transport service.
+    fn on_service_event(&mut self, event: TransportEvent) {
+        match event {
+            TransportEvent::ConnectionEstablished { peer, .. } =>
+                self.on_connection_established(peer),
+            TransportEvent::ConnectionClosed { peer } => self.on_connection_closed(peer),
+            TransportEvent::SubstreamOpened {
+                peer,
+                direction,
+                substream,
+                ..
+            } => match direction {
+                Direction::Inbound => self.on_inbound_substream(peer, substream),
+                Direction::Outbound(substream_id) =>
+                    self.on_outbound_substream(substream_id, substream),
+            },
+            TransportEvent::SubstreamOpenFailure { substream, error } =>
+                self.on_substream_open_failure(substream, error),
+            TransportEvent::DialFailure { peer, .. } => self.on_dial_failure(peer),
+        }
+    }
+
+    /// Start [`Rendezvous`] event loop.
+    pub async fn run(mut self) {
+        tracing::debug!(target: LOG_TARGET, server = ?self.config.server, "starting rendezvous event loop");
+
+        loop {
+            tokio::select! {
+                event = self.service.next() => match event {
+                    None => {
+                        tracing::warn!(target: LOG_TARGET, "transport service stream ended, terminating rendezvous event loop");
+                        return
+                    },
+                    Some(event) => self.on_service_event(event),
+                },
+                command = self.config.cmd_rx.recv() => match command {
+                    None => {
+                        tracing::debug!(target: LOG_TARGET, "user handle dropped, terminating rendezvous event loop");
+                        return
+                    },
+                    Some(command) => self.on_command(command),
+                },
+                Some((kind, result)) = self.pending_responses.next() =>
+                    self.on_response(kind, result),
+                Some(request) = self.pending_inbound.next() =>
+                    if let Some((peer, substream, message)) = request {
+                        self.on_request(peer, substream, message);
+                    },
+                Some(()) = self.pending_sends.next() => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Signed peer records carried in rendezvous registrations.
+
+use crate::{
+    crypto::{ed25519::Keypair, PublicKey},
+    protocol::libp2p::rendezvous::schema,
+    PeerId,
+};
+
+use multiaddr::{Multiaddr, Protocol};
+use prost::Message;
+
+/// Domain separation string of peer record envelopes.
+const DOMAIN: &str = "libp2p-routing-state";
+
+/// Payload type of peer records.
+const PAYLOAD_TYPE: &[u8] = b"/libp2p/routing-state-record";
+
+/// Multicodec of peer records, used as the payload type by some implementations.
+const PAYLOAD_TYPE_MULTICODEC: &[u8] = &[0x03, 0x01];
+
+/// Create the buffer signed by the envelope, consisting of the length-prefixed domain,
+/// payload type and payload.
+fn signature_payload(payload_type: &[u8], payload: &[u8]) -> Vec<u8> {
+    let mut buffer = Vec::new();
+
+    for field in [DOMAIN.as_bytes(), payload_type, payload] {
+        let mut length = unsigned_varint::encode::usize_buffer();
+        buffer.extend_from_slice(unsigned_varint::encode::usize(field.len(), &mut length));
+        buffer.extend_from_slice(field);
+    }
+
+    buffer
+}
+
+/// Create signed envelope of a peer record advertising `addresses` of the local node.
+pub(super) fn sign_record(
+    keypair: &Keypair,
+    addresses: impl Iterator<Item = Multiaddr>,
+    seq: u64,
+) -> Vec<u8> {
+    let public = PublicKey::Ed25519(keypair.public());
+    let payload = schema::PeerRecord {
+        peer_id: Some(public.to_peer_id().to_bytes()),
+        seq: Some(seq),
+        addresses: addresses
+            .map(|address| schema::peer_record::AddressInfo {
+                multiaddr: Some(address.to_vec()),
+            })
+            .collect(),
+    }
+    .encode_to_vec();
+
+    schema::Envelope {
+        public_key: Some(public.to_protobuf_encoding()),
+        payload_type: Some(PAYLOAD_TYPE.to_vec()),
+        signature: Some(keypair.sign(&signature_payload(PAYLOAD_TYPE, &payload))),
+        payload: Some(payload),
+    }
+    .encode_to_vec()
+}
+
+/// Verify signed envelope of a peer record and return the peer ID and addresses of the record.
+///
+/// The addresses are returned without the `/p2p/<peer>` suffix.
+pub(super) fn verify_record(envelope: &[u8]) -> Option<(PeerId, Vec<Multiaddr>)> {
+    let envelope = schema::Envelope::decode(envelope).ok()?;
+    let public = PublicKey::from_protobuf_encoding(envelope.public_key.as_deref()?).ok()?;
+    let payload_type = envelope.payload_type.as_deref()?;
+    let payload = envelope.payload.as_deref()?;
+
+    if payload_type != PAYLOAD_TYPE && payload_type != PAYLOAD_TYPE_MULTICODEC {
+        return None;
+    }
+
+    if !public.verify(
+        &signature_payload(payload_type, payload),
+        envelope.signature.as_deref()?,
+    ) {
+        return None;
+    }
+
+    let record = schema::PeerRecord::decode(payload).ok()?;
+    let peer = PeerId::from_bytes(record.peer_id.as_deref()?).ok()?;
+
+    if peer != public.to_peer_id() {
+        return None;
+    }
+
+    let addresses = record
+        .addresses
+        .into_iter()
+        .filter_map(|info| Multiaddr::try_from(info.multiaddr?).ok())
+        .map(|mut address| {
+            if std::matches!(address.iter().last(), Some(Protocol::P2p(_))) {
+                address.pop();
+            }
+            address
+        })
+        .collect();
+
+    Some((peer, addresses))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signed_record_verifies() {
+        let keypair = Keypair::generate();
+        let address: Multiaddr = "/ip4/127.0.0.1/tcp/8888".parse().unwrap();
+
+        let envelope = sign_record(&keypair, std::iter::once(address.clone()), 1);
+        let (peer, addresses) = verify_record(&envelope).unwrap();
+
+        assert_eq!(peer, PublicKey::Ed25519(keypair.public()).to_peer_id());
+        assert_eq!(addresses, vec![address]);
+    }
+
+    #[test]
+    fn tampered_record_is_rejected() {
+        let keypair = Keypair::generate();
+        let envelope = sign_record(&keypair, std::iter::empty(), 1);
+
+        let mut envelope = schema::Envelope::decode(envelope.as_slice()).unwrap();
+        envelope.payload = Some(
+            schema::PeerRecord {
+                peer_id: Some(PeerId::random().to_bytes()),
+                seq: Some(1),
+                addresses: Vec::new(),
+            }
+            .encode_to_vec(),
+        );
+
+        assert!(verify_record(&envelope.encode_to_vec()).is_none());
+    }
+}
@@ -0,0 +1,304 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Registrations stored by a rendezvous point.
+
+use crate::{protocol::libp2p::rendezvous::schema, PeerId};
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
+/// Response status.
+type ResponseStatus = schema::message::ResponseStatus;
+
+/// Maximum length of a namespace.
+const MAX_NAMESPACE_LENGTH: usize = 255;
+
+/// Maximum number of registrations returned in one discover response.
+pub(super) const MAX_DISCOVER_LIMIT: u64 = 1000;
+
+/// Registration of a peer.
+struct Registration {
+    /// Namespace.
+    namespace: String,
+
+    /// Registered peer.
+    peer: PeerId,
+
+    /// Signed peer record of the peer.
+    record: Vec<u8>,
+
+    /// TTL of the registration.
+    ttl: Duration,
+
+    /// When the registration expires.
+    expires: Instant,
+}
+
+/// Registrations of a rendezvous point.
+pub(super) struct Registrations {
+    /// Registrations, ordered by their IDs.
+    ///
+    /// A peer that registers again gets a new ID, so the pagination cookies, which contain the
+    /// ID of the last returned registration, also cover the refreshed registrations.
+    registrations: BTreeMap<u64, Registration>,
+
+    /// IDs of the registrations of each namespace and peer.
+    ids: HashMap<(String, PeerId), u64>,
+
+    /// ID of the next registration.
+    next_id: u64,
+
+    /// Minimum TTL of a registration.
+    min_ttl: Duration,
+
+    /// Maximum TTL of a registration.
+    max_ttl: Duration,
+
+    /// TTL of a registration if the peer didn't request one.
+    default_ttl: Duration,
+}
+
+impl Registrations {
+    /// Create new [`Registrations`].
+    pub(super) fn new(min_ttl: Duration, max_ttl: Duration, default_ttl: Duration) -> Self {
+        Self {
+            registrations: BTreeMap::new(),
+            ids: HashMap::new(),
+            next_id: 0u64,
+            min_ttl,
+            max_ttl,
+            default_ttl: default_ttl.clamp(min_ttl, max_ttl),
+        }
+    }
+
+    /// Register `peer` to `namespace` and return the TTL of the registration.
+    pub(super) fn add(
+        &mut self,
+        namespace: String,
+        peer: PeerId,
+        record: Vec<u8>,
+        ttl: Option<Duration>,
+    ) -> Result<Duration, ResponseStatus> {
+        if namespace.is_empty() || namespace.len() > MAX_NAMESPACE_LENGTH {
+            return Err(ResponseStatus::EInvalidNamespace);
+        }
+
+        let ttl = ttl.unwrap_or(self.default_ttl);
+        if ttl < self.min_ttl || ttl > self.max_ttl {
+            return Err(ResponseStatus::EInvalidTtl);
+        }
+
+        self.remove(&namespace, &peer);
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.ids.insert((namespace.clone(), peer), id);
+        self.registrations.insert(
+            id,
+            Registration {
+                namespace,
+                peer,
+                record,
+                ttl,
+                expires: Instant::now() + ttl,
+            },
+        );
+
+        Ok(ttl)
+    }
+
+    /// Remove the registration of `peer` from `namespace`.
+    pub(super) fn remove(&mut self, namespace: &str, peer: &PeerId) {
+        if let Some(id) = self.ids.remove(&(namespace.to_owned(), *peer)) {
+            self.registrations.remove(&id);
+        }
+    }
+
+    /// Get up to `limit` registrations of `namespace`, or of all namespaces if `namespace` is
+    /// `None`, which were made after the registrations returned with `cookie`.
+    ///
+    /// Returns the registrations and the cookie for fetching the next registrations.
+    pub(super) fn discover(
+        &mut self,
+        namespace: Option<&str>,
+        limit: Option<u64>,
+        cookie: Option<&[u8]>,
+    ) -> Result<(Vec<schema::message::Register>, Vec<u8>), ResponseStatus> {
+        if namespace.is_some_and(|namespace| namespace.len() > MAX_NAMESPACE_LENGTH) {
+            return Err(ResponseStatus::EInvalidNamespace);
+        }
+
+        let start = match cookie {
+            None => 0u64,
+            Some(cookie) => {
+                let (id, cookie_namespace) =
+                    cookie.split_first_chunk::<8>().ok_or(ResponseStatus::EInvalidCookie)?;
+
+                if cookie_namespace != namespace.unwrap_or_default().as_bytes() {
+                    return Err(ResponseStatus::EInvalidCookie);
+                }
+
+                u64::from_be_bytes(*id)
+            }
+        };
+        let limit = limit.unwrap_or(MAX_DISCOVER_LIMIT).min(MAX_DISCOVER_LIMIT) as usize;
+
+        self.expire();
+
+        let mut next = start;
+        let registrations = self
+            .registrations
+            .range(start..)
+            .filter(|(_, registration)| {
+                namespace.map_or(true, |namespace| registration.namespace == namespace)
+            })
+            .take(limit)
+            .map(|(id, registration)| {
+                next = id + 1;
+
+                schema::message::Register {
+                    ns: Some(registration.namespace.clone()),
+                    signed_peer_record: Some(registration.record.clone()),
+                    ttl: Some(registration.ttl.as_secs()),
+                }
+            })
+            .collect();
+
+        let mut cookie = next.to_be_bytes().to_vec();
+        cookie.extend_from_slice(namespace.unwrap_or_default().as_bytes());
+
+        Ok((registrations, cookie))
+    }
+
+    /// Remove expired registrations.
+    fn expire(&mut self) {
+        let now = Instant::now();
+
+        self.registrations.retain(|_, registration| {
+            if registration.expires > now {
+                return true;
+            }
+
+            self.ids.remove(&(registration.namespace.clone(), registration.peer));
+            false
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registrations() -> Registrations {
+        Registrations::new(
+            Duration::from_millis(10),
+            Duration::from_secs(60),
+            Duration::from_secs(30),
+        )
+    }
+
+    #[test]
+    fn invalid_registrations_are_rejected() {
+        let mut registrations = registrations();
+
+        assert_eq!(
+            registrations.add(String::new(), PeerId::random(), Vec::new(), None),
+            Err(ResponseStatus::EInvalidNamespace),
+        );
+        assert_eq!(
+            registrations.add("a".repeat(256), PeerId::random(), Vec::new(), None),
+            Err(ResponseStatus::EInvalidNamespace),
+        );
+        assert_eq!(
+            registrations.add(
+                "ns".to_owned(),
+                PeerId::random(),
+                Vec::new(),
+                Some(Duration::from_secs(61))
+            ),
+            Err(ResponseStatus::EInvalidTtl),
+        );
+        assert_eq!(
+            registrations.add("ns".to_owned(), PeerId::random(), Vec::new(), None),
+            Ok(Duration::from_secs(30)),
+        );
+    }
+
+    #[test]
+    fn discover_paginates_with_cookie() {
+        let mut registrations = registrations();
+
+        for _ in 0..3 {
+            registrations.add("ns".to_owned(), PeerId::random(), Vec::new(), None).unwrap();
+        }
+        registrations
+            .add("other".to_owned(), PeerId::random(), Vec::new(), None)
+            .unwrap();
+
+        let (page, cookie) = registrations.discover(Some("ns"), Some(2), None).unwrap();
+        assert_eq!(page.len(), 2);
+
+        let (page, cookie) = registrations.discover(Some("ns"), Some(2), Some(&cookie)).unwrap();
+        assert_eq!(page.len(), 1);
+
+        // new registration is returned with the cookie of the previous response
+        registrations.add("ns".to_owned(), PeerId::random(), Vec::new(), None).unwrap();
+        let (page, _) = registrations.discover(Some("ns"), None, Some(&cookie)).unwrap();
+        assert_eq!(page.len(), 1);
+
+        // cookie of another namespace is rejected
+        assert_eq!(
+            registrations.discover(Some("other"), None, Some(&cookie)).map(|_| ()),
+            Err(ResponseStatus::EInvalidCookie),
+        );
+
+        let (page, _) = registrations.discover(None, None, None).unwrap();
+        assert_eq!(page.len(), 5);
+    }
+
+    #[test]
+    fn registrations_expire_and_can_be_removed() {
+        let mut registrations = registrations();
+        let peer1 = PeerId::random();
+        let peer2 = PeerId::random();
+
+        registrations
+            .add(
+                "ns".to_owned(),
+                peer1,
+                Vec::new(),
+                Some(Duration::from_millis(10)),
+            )
+            .unwrap();
+        registrations.add("ns".to_owned(), peer2, Vec::new(), None).unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+
+        let (page, _) = registrations.discover(Some("ns"), None, None).unwrap();
+        assert_eq!(page.len(), 1);
+
+        registrations.remove("ns", &peer2);
+        let (page, _) = registrations.discover(Some("ns"), None, None).unwrap();
+        assert!(page.is_empty());
+        assert!(registrations.ids.is_empty());
+    }
+}
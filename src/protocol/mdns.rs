@@ -41,7 +41,6 @@ use std::{
     collections::HashSet,
     net,
     net::{IpAddr, Ipv4Addr, SocketAddr},
-    sync::Arc,
     time::Duration,
 };
 
@@ -101,7 +100,7 @@ pub(crate) struct Mdns {
     event_tx: Sender<MdnsEvent>,
 
     /// Handle to `TransportManager`.
-    _transport_handle: TransportManagerHandle,
+    transport_handle: TransportManagerHandle,
 
     // Username.
     username: String,
@@ -112,9 +111,6 @@ pub(crate) struct Mdns {
     /// Buffer for incoming messages.
     receive_buffer: Vec<u8>,
 
-    /// Listen addresses.
-    listen_addresses: Vec<Arc<str>>,
-
     /// Discovered addresses.
     discovered: HashSet<Multiaddr>,
 }
@@ -122,9 +118,8 @@ pub(crate) struct Mdns {
 impl Mdns {
     /// Create new [`Mdns`].
     pub(crate) fn new(
-        _transport_handle: TransportManagerHandle,
+        transport_handle: TransportManagerHandle,
         config: Config,
-        listen_addresses: Vec<Multiaddr>,
     ) -> crate::Result<Self> {
         let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
         socket.set_reuse_address(true)?;
@@ -142,7 +137,7 @@ impl Mdns {
         query_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
 
         Ok(Self {
-            _transport_handle,
+            transport_handle,
             event_tx: config.tx,
             next_query_id: 1337u16,
             discovered: HashSet::new(),
@@ -154,10 +149,6 @@ impl Mdns {
                 .map(char::from)
                 .collect(),
             socket: UdpSocket::from_std(net::UdpSocket::from(socket))?,
-            listen_addresses: listen_addresses
-                .into_iter()
-                .map(|address| format!("dnsaddr={address}").into())
-                .collect(),
         })
     }
 
@@ -196,6 +187,18 @@ impl Mdns {
     fn on_inbound_request(&self, packet: Packet) -> Option<Vec<u8>> {
         tracing::debug!(target: LOG_TARGET, ?packet, "handle inbound request");
 
+        // listen addresses are read on each request as they change when listeners are
+        // started/stopped or when network interfaces change
+        let listen_addresses = self
+            .transport_handle
+            .listen_addresses()
+            .into_iter()
+            .filter(|address| {
+                std::matches!(address.iter().last(), Some(multiaddr::Protocol::P2p(_)))
+            })
+            .map(|address| format!("dnsaddr={address}"))
+            .collect::<Vec<_>>();
+
         let mut packet = Packet::new_reply(packet.id());
         let srv_name = Name::new_unchecked(SERVICE_NAME);
 
@@ -206,7 +209,7 @@ impl Mdns {
             RData::PTR(PTR(Name::new_unchecked(&self.username))),
         ));
 
-        for address in &self.listen_addresses {
+        for address in &listen_addresses {
             let mut record = TXT::new();
             record.add_string(address).expect("valid string");
 
@@ -349,7 +352,7 @@ mod tests {
             .try_init();
 
         let (config1, mut stream1) = Config::new(Duration::from_secs(5));
-        let (_manager1, handle1) = TransportManager::new(
+        let (mut manager1, handle1) = TransportManager::new(
             Keypair::generate(),
             HashSet::new(),
             BandwidthSink::new(),
@@ -357,22 +360,12 @@ mod tests {
             ConnectionLimitsConfig::default(),
         );
 
-        let mdns1 = Mdns::new(
-            handle1,
-            config1,
-            vec![
-                "/ip6/::1/tcp/8888/p2p/12D3KooWNP463TyS3vUpmekjjZ2dg7xy1WHNMM7MqfsMevMTaaaa"
-                    .parse()
-                    .unwrap(),
-                "/ip4/127.0.0.1/tcp/8888/p2p/12D3KooWNP463TyS3vUpmekjjZ2dg7xy1WHNMM7MqfsMevMTaaaa"
-                    .parse()
-                    .unwrap(),
-            ],
-        )
-        .unwrap();
+        manager1.register_listen_address("/ip6/::1/tcp/8888".parse().unwrap());
+        manager1.register_listen_address("/ip4/127.0.0.1/tcp/8888".parse().unwrap());
+        let mdns1 = Mdns::new(handle1, config1).unwrap();
 
         let (config2, mut stream2) = Config::new(Duration::from_secs(5));
-        let (_manager1, handle2) = TransportManager::new(
+        let (mut manager2, handle2) = TransportManager::new(
             Keypair::generate(),
             HashSet::new(),
             BandwidthSink::new(),
@@ -380,19 +373,9 @@ mod tests {
             ConnectionLimitsConfig::default(),
         );
 
-        let mdns2 = Mdns::new(
-            handle2,
-            config2,
-            vec![
-                "/ip6/::1/tcp/9999/p2p/12D3KooWNP463TyS3vUpmekjjZ2dg7xy1WHNMM7MqfsMevMTbbbb"
-                    .parse()
-                    .unwrap(),
-                "/ip4/127.0.0.1/tcp/9999/p2p/12D3KooWNP463TyS3vUpmekjjZ2dg7xy1WHNMM7MqfsMevMTbbbb"
-                    .parse()
-                    .unwrap(),
-            ],
-        )
-        .unwrap();
+        manager2.register_listen_address("/ip6/::1/tcp/9999".parse().unwrap());
+        manager2.register_listen_address("/ip4/127.0.0.1/tcp/9999".parse().unwrap());
+        let mdns2 = Mdns::new(handle2, config2).unwrap();
 
         tokio::spawn(mdns1.start());
         tokio::spawn(mdns2.start());
@@ -41,13 +41,14 @@ use futures::{stream::FuturesUnordered, Stream, StreamExt};
 use multiaddr::Multiaddr;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 
-#[cfg(any(feature = "quic", feature = "webrtc", feature = "websocket"))]
-use std::sync::atomic::Ordering;
 use std::{
     collections::HashMap,
     fmt::Debug,
     pin::Pin,
-    sync::{atomic::AtomicUsize, Arc},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
     task::{Context, Poll},
 };
 
@@ -222,7 +223,6 @@ pub struct ProtocolSet {
     mgr_tx: Sender<TransportManagerEvent>,
     connection: ConnectionHandle,
     rx: Receiver<ProtocolCommand>,
-    #[allow(unused)]
     next_substream_id: Arc<AtomicUsize>,
     fallback_names: HashMap<ProtocolName, ProtocolName>,
 }
@@ -263,7 +263,6 @@ impl ProtocolSet {
     }
 
     /// Get next substream ID.
-    #[cfg(any(feature = "quic", feature = "webrtc", feature = "websocket"))]
     pub fn next_substream_id(&self) -> SubstreamId {
         SubstreamId::from(self.next_substream_id.fetch_add(1usize, Ordering::Relaxed))
     }
@@ -99,6 +99,10 @@ impl From<SubstreamError> for RejectReason {
             SubstreamError::YamuxError(crate::yamux::ConnectionError::Io(error), _)
                 if error.kind() == ErrorKind::NotConnected =>
                 RejectReason::ConnectionClosed,
+            SubstreamError::MplexError(
+                crate::mplex::ConnectionError::Io(ErrorKind::NotConnected),
+                _,
+            ) => RejectReason::ConnectionClosed,
             SubstreamError::NegotiationError(crate::error::NegotiationError::IoError(error))
                 if error == ErrorKind::NotConnected =>
                 RejectReason::ConnectionClosed,
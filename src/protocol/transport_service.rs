@@ -20,6 +20,7 @@
 
 use crate::{
     addresses::PublicAddresses,
+    config::Role,
     error::{Error, ImmediateDialError, SubstreamError},
     protocol::{connection::ConnectionHandle, InnerTransportEvent, TransportEvent},
     transport::{manager::TransportManagerHandle, Endpoint},
@@ -54,7 +55,8 @@ const LOG_TARGET: &str = "litep2p::transport-service";
 /// may be open if local and remote opened connections at the same time.
 ///
 /// Secondary connection may be promoted to a primary connection if the primary connections closes
-/// while the secondary connections remains open.
+/// while the secondary connections remains open. A direct secondary connection is promoted to the
+/// primary connection as soon as it's established if the primary connection is relayed.
 #[derive(Debug)]
 struct ConnectionContext {
     /// Primary connection.
@@ -62,17 +64,36 @@ struct ConnectionContext {
 
     /// Secondary connection, if it exists.
     secondary: Option<ConnectionHandle>,
+
+    /// Connections relayed through a circuit relay.
+    relayed: HashSet<ConnectionId>,
 }
 
 impl ConnectionContext {
     /// Create new [`ConnectionContext`].
-    fn new(primary: ConnectionHandle) -> Self {
+    fn new(primary: ConnectionHandle, relayed: bool) -> Self {
         Self {
+            relayed: relayed.then(|| *primary.connection_id()).into_iter().collect(),
             primary,
             secondary: None,
         }
     }
 
+    /// Store the `secondary` connection, replacing the primary connection with it if the primary
+    /// connection is relayed and the secondary connection is direct.
+    fn set_secondary(&mut self, secondary: ConnectionHandle, relayed: bool) {
+        if relayed {
+            self.relayed.insert(*secondary.connection_id());
+        }
+        self.secondary = Some(secondary);
+
+        if !relayed && self.relayed.contains(self.primary.connection_id()) {
+            if let Some(secondary) = self.secondary.as_mut() {
+                std::mem::swap(&mut self.primary, secondary);
+            }
+        }
+    }
+
     /// Downgrade connection to non-active which means it will be closed
     /// if there are no substreams open over it.
     fn downgrade(&mut self, connection_id: &ConnectionId) {
@@ -287,6 +308,12 @@ pub struct TransportService {
 
     /// Close the connection if no substreams are open within this time frame.
     keep_alive_tracker: KeepAliveTracker,
+
+    /// Report established secondary connections to the protocol.
+    report_secondary_connections: bool,
+
+    /// Peers whose connections are kept open regardless of the keep-alive timeout.
+    retained: HashSet<PeerId>,
 }
 
 impl TransportService {
@@ -313,11 +340,50 @@ impl TransportService {
                 next_substream_id,
                 connections: HashMap::new(),
                 keep_alive_tracker,
+                report_secondary_connections: false,
+                retained: HashSet::new(),
             },
             tx,
         )
     }
 
+    /// Report established secondary connections to the protocol.
+    ///
+    /// By default only the first connection of a peer is reported. If enabled, the protocol
+    /// receives [`TransportEvent::ConnectionEstablished`] for the secondary connection as well
+    /// and can use the endpoint to open substreams over that connection with
+    /// [`TransportService::open_substream_on_connection()`].
+    pub(crate) fn report_secondary_connections(&mut self) {
+        self.report_secondary_connections = true;
+    }
+
+    /// Keep the connection to `peer` open even if no substreams are open over it.
+    ///
+    /// The connection stays open until [`TransportService::release_connection()`] is called
+    /// or either of the peers closes it.
+    pub(crate) fn retain_connection(&mut self, peer: PeerId) {
+        if !self.retained.insert(peer) {
+            return;
+        }
+
+        if let Some(context) = self.connections.get_mut(&peer) {
+            let connection_id = *context.primary.connection_id();
+            context.try_upgrade(&connection_id);
+        }
+    }
+
+    /// Stop keeping the connection to `peer` open, restarting its keep-alive timeout.
+    pub(crate) fn release_connection(&mut self, peer: PeerId) {
+        if !self.retained.remove(&peer) {
+            return;
+        }
+
+        if let Some(context) = self.connections.get(&peer) {
+            self.keep_alive_tracker
+                .substream_activity(peer, *context.primary.connection_id());
+        }
+    }
+
     /// Get the list of public addresses of the node.
     pub fn public_addresses(&self) -> PublicAddresses {
         self.transport_handle.public_addresses()
@@ -360,13 +426,15 @@ impl TransportService {
                 None => {
                     self.keep_alive_tracker.on_connection_established(peer, connection_id);
 
-                    context.secondary = Some(handle);
+                    context.set_secondary(handle, endpoint.is_relayed());
 
-                    None
+                    self.report_secondary_connections
+                        .then_some(TransportEvent::ConnectionEstablished { peer, endpoint })
                 }
             },
             None => {
-                self.connections.insert(peer, ConnectionContext::new(handle));
+                self.connections
+                    .insert(peer, ConnectionContext::new(handle, endpoint.is_relayed()));
 
                 self.keep_alive_tracker.on_connection_established(peer, connection_id);
 
@@ -394,6 +462,7 @@ impl TransportService {
             debug_assert!(false);
             return None;
         };
+        context.relayed.remove(&connection_id);
 
         // if the primary connection was closed, check if there exist a secondary connection
         // and if it does, convert the secondary connection a primary connection
@@ -463,6 +532,43 @@ impl TransportService {
         self.transport_handle.dial_address(address)
     }
 
+    /// Dial peer using a `Multiaddr`, opening a secondary connection if the peer is already
+    /// connected.
+    ///
+    /// Call fails if the address is not in correct format or the peer already has a secondary
+    /// connection open.
+    pub(crate) fn dial_secondary_address(
+        &mut self,
+        address: Multiaddr,
+    ) -> Result<(), ImmediateDialError> {
+        self.transport_handle.dial_secondary_address(address)
+    }
+
+    /// Dial `addresses` of a peer at once as one side of a simultaneous open, opening a secondary
+    /// connection if the peer is already connected.
+    ///
+    /// The connection is negotiated in `role` by the transports supporting simultaneous opens.
+    pub(crate) fn simultaneous_open(
+        &mut self,
+        addresses: Vec<Multiaddr>,
+        role: Role,
+    ) -> Result<(), ImmediateDialError> {
+        self.transport_handle.simultaneous_open(addresses, role)
+    }
+
+    /// Punch holes towards `addresses` of a peer for the peer to dial the local node through.
+    pub(crate) fn hole_punch(
+        &mut self,
+        addresses: Vec<Multiaddr>,
+    ) -> Result<(), ImmediateDialError> {
+        self.transport_handle.hole_punch(addresses)
+    }
+
+    /// Check if `address` is supported by one of the enabled transports.
+    pub(crate) fn supported_transport(&self, address: &Multiaddr) -> bool {
+        self.transport_handle.supported_transport(address)
+    }
+
     /// Add one or more addresses for `peer`.
     ///
     /// The list is filtered for duplicates and unsupported transports.
@@ -520,6 +626,53 @@ impl TransportService {
             .map(|_| substream_id)
     }
 
+    /// Open substream to `peer` over a specific connection.
+    ///
+    /// Call fails if `connection_id` is neither the primary nor the secondary connection of
+    /// `peer` or if the channel towards the connection is clogged.
+    pub(crate) fn open_substream_on_connection(
+        &mut self,
+        peer: PeerId,
+        connection_id: ConnectionId,
+    ) -> Result<SubstreamId, SubstreamError> {
+        let context =
+            self.connections.get_mut(&peer).ok_or(SubstreamError::PeerDoesNotExist(peer))?;
+
+        let connection = match context.primary.connection_id() == &connection_id {
+            true => &mut context.primary,
+            false => context
+                .secondary
+                .as_mut()
+                .filter(|handle| handle.connection_id() == &connection_id)
+                .ok_or(SubstreamError::ConnectionClosed)?,
+        };
+
+        let permit = connection.try_get_permit().ok_or(SubstreamError::ConnectionClosed)?;
+        let substream_id =
+            SubstreamId::from(self.next_substream_id.fetch_add(1usize, Ordering::Relaxed));
+
+        tracing::trace!(
+            target: LOG_TARGET,
+            ?peer,
+            protocol = %self.protocol,
+            ?substream_id,
+            ?connection_id,
+            "open substream over connection",
+        );
+
+        self.keep_alive_tracker.substream_activity(peer, connection_id);
+        connection.try_upgrade();
+
+        connection
+            .open_substream(
+                self.protocol.clone(),
+                self.fallback_names.clone(),
+                substream_id,
+                permit,
+            )
+            .map(|_| substream_id)
+    }
+
     /// Forcibly close the connection, even if other protocols have substreams open over it.
     pub fn force_close(&mut self, peer: PeerId) -> crate::Result<()> {
         let connection =
@@ -540,6 +693,33 @@ impl TransportService {
         connection.primary.force_close()
     }
 
+    /// Forcibly close a specific connection of `peer`, leaving its other connection open.
+    pub(crate) fn close_connection(
+        &mut self,
+        peer: PeerId,
+        connection_id: ConnectionId,
+    ) -> crate::Result<()> {
+        let context = self.connections.get_mut(&peer).ok_or(Error::PeerDoesntExist(peer))?;
+
+        tracing::trace!(
+            target: LOG_TARGET,
+            ?peer,
+            protocol = %self.protocol,
+            ?connection_id,
+            "forcibly closing connection",
+        );
+
+        match context.primary.connection_id() == &connection_id {
+            true => context.primary.force_close(),
+            false => context
+                .secondary
+                .as_mut()
+                .filter(|handle| handle.connection_id() == &connection_id)
+                .ok_or(Error::ConnectionDoesntExist(connection_id))?
+                .force_close(),
+        }
+    }
+
     /// Get local peer ID.
     pub fn local_peer_id(&self) -> PeerId {
         self.local_peer_id
@@ -606,6 +786,17 @@ impl Stream for TransportService {
         while let Poll::Ready(Some((peer, connection_id))) =
             self.keep_alive_tracker.poll_next_unpin(cx)
         {
+            if self.retained.contains(&peer) {
+                tracing::trace!(
+                    target: LOG_TARGET,
+                    ?peer,
+                    ?connection_id,
+                    protocol = ?protocol_name,
+                    "keep-alive timeout over, connection retained",
+                );
+                continue;
+            }
+
             if let Some(context) = self.connections.get_mut(&peer) {
                 tracing::debug!(
                     target: LOG_TARGET,
@@ -722,6 +913,101 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn direct_connection_replaces_relayed_primary() {
+        let (mut service, sender, _) = transport_service();
+        let peer = PeerId::random();
+        let relayed_address: Multiaddr = format!(
+            "/ip4/127.0.0.1/tcp/8888/p2p/{}/p2p-circuit",
+            PeerId::random()
+        )
+        .parse()
+        .unwrap();
+
+        // register relayed connection
+        let (cmd_tx1, mut cmd_rx1) = channel(64);
+        sender
+            .send(InnerTransportEvent::ConnectionEstablished {
+                peer,
+                connection: ConnectionId::from(0usize),
+                endpoint: Endpoint::dialer(relayed_address, ConnectionId::from(0usize)),
+                sender: ConnectionHandle::new(ConnectionId::from(0usize), cmd_tx1),
+            })
+            .await
+            .unwrap();
+
+        if let Some(TransportEvent::ConnectionEstablished { endpoint, .. }) = service.next().await {
+            assert!(endpoint.is_relayed());
+        } else {
+            panic!("expected event from `TransportService`");
+        };
+
+        // register direct connection
+        let (cmd_tx2, mut cmd_rx2) = channel(64);
+        sender
+            .send(InnerTransportEvent::ConnectionEstablished {
+                peer,
+                connection: ConnectionId::from(1usize),
+                endpoint: Endpoint::listener(Multiaddr::empty(), ConnectionId::from(1usize)),
+                sender: ConnectionHandle::new(ConnectionId::from(1usize), cmd_tx2),
+            })
+            .await
+            .unwrap();
+
+        futures::future::poll_fn(|cx| match service.poll_next_unpin(cx) {
+            std::task::Poll::Ready(_) => panic!("didn't expect event from `TransportService`"),
+            std::task::Poll::Pending => std::task::Poll::Ready(()),
+        })
+        .await;
+
+        let context = service.connections.get(&peer).unwrap();
+        assert_eq!(context.primary.connection_id(), &ConnectionId::from(1usize));
+        assert_eq!(
+            context.secondary.as_ref().unwrap().connection_id(),
+            &ConnectionId::from(0usize)
+        );
+
+        // the protocol opens substreams over the direct connection
+        let substream_id = service.open_substream(peer).unwrap();
+        match cmd_rx2.try_recv() {
+            Ok(ProtocolCommand::OpenSubstream {
+                substream_id: opened_substream_id,
+                ..
+            }) => assert_eq!(opened_substream_id, substream_id),
+            _ => panic!("expected `ProtocolCommand::OpenSubstream` over the direct connection"),
+        }
+        assert!(cmd_rx1.try_recv().is_err());
+
+        // close the relayed connection, the direct connection is kept
+        sender
+            .send(InnerTransportEvent::ConnectionClosed {
+                peer,
+                connection: ConnectionId::from(0usize),
+            })
+            .await
+            .unwrap();
+
+        futures::future::poll_fn(|cx| match service.poll_next_unpin(cx) {
+            std::task::Poll::Ready(_) => panic!("didn't expect event from `TransportService`"),
+            std::task::Poll::Pending => std::task::Poll::Ready(()),
+        })
+        .await;
+
+        let context = service.connections.get(&peer).unwrap();
+        assert_eq!(context.primary.connection_id(), &ConnectionId::from(1usize));
+        assert!(context.secondary.is_none());
+        assert!(context.relayed.is_empty());
+
+        let substream_id = service.open_substream(peer).unwrap();
+        match cmd_rx2.try_recv() {
+            Ok(ProtocolCommand::OpenSubstream {
+                substream_id: opened_substream_id,
+                ..
+            }) => assert_eq!(opened_substream_id, substream_id),
+            _ => panic!("expected `ProtocolCommand::OpenSubstream` over the direct connection"),
+        }
+    }
+
     #[tokio::test]
     async fn tertiary_connection_ignored() {
         let (mut service, sender, _) = transport_service();
@@ -1165,6 +1451,51 @@ mod tests {
         assert_eq!(service.keep_alive_tracker.last_activity.len(), 0);
     }
 
+    #[tokio::test]
+    async fn retained_connection_is_not_downgraded() {
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .try_init();
+
+        let (mut service, sender, _) = transport_service();
+        let peer = PeerId::random();
+
+        let (cmd_tx1, _cmd_rx1) = channel(64);
+        sender
+            .send(InnerTransportEvent::ConnectionEstablished {
+                peer,
+                connection: ConnectionId::from(1337usize),
+                endpoint: Endpoint::dialer(Multiaddr::empty(), ConnectionId::from(1337usize)),
+                sender: ConnectionHandle::new(ConnectionId::from(1337usize), cmd_tx1),
+            })
+            .await
+            .unwrap();
+
+        assert!(std::matches!(
+            service.next().await,
+            Some(TransportEvent::ConnectionEstablished { .. })
+        ));
+        service.retain_connection(peer);
+
+        poll_service(&mut service).await;
+        tokio::time::sleep(KEEP_ALIVE_TIMEOUT + std::time::Duration::from_secs(1)).await;
+        poll_service(&mut service).await;
+
+        // the keep-alive timeout expired but the connection is still active
+        assert!(service.connections.get(&peer).unwrap().primary.is_active());
+        assert_eq!(service.keep_alive_tracker.last_activity.len(), 0);
+
+        // releasing the connection restarts the keep-alive timeout
+        service.release_connection(peer);
+        assert_eq!(service.keep_alive_tracker.last_activity.len(), 1);
+
+        poll_service(&mut service).await;
+        tokio::time::sleep(KEEP_ALIVE_TIMEOUT + std::time::Duration::from_secs(1)).await;
+        poll_service(&mut service).await;
+
+        assert!(!service.connections.get(&peer).unwrap().primary.is_active());
+    }
+
     #[tokio::test]
     async fn keep_alive_timeout_reset_when_user_opens_substream() {
         let _ = tracing_subscriber::fmt()
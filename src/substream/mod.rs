@@ -22,7 +22,11 @@
 //! Substream-related helper code.
 
 use crate::{
-    codec::ProtocolCodec, error::SubstreamError, transport::tcp, types::SubstreamId, PeerId,
+    codec::ProtocolCodec,
+    error::SubstreamError,
+    transport::{relay, tcp},
+    types::SubstreamId,
+    PeerId,
 };
 
 #[cfg(feature = "quic")]
@@ -53,6 +57,7 @@ macro_rules! poll_flush {
     ($substream:expr, $cx:ident) => {{
         match $substream {
             SubstreamType::Tcp(substream) => Pin::new(substream).poll_flush($cx),
+            SubstreamType::Relay(substream) => Pin::new(substream).poll_flush($cx),
             #[cfg(feature = "websocket")]
             SubstreamType::WebSocket(substream) => Pin::new(substream).poll_flush($cx),
             #[cfg(feature = "quic")]
@@ -69,6 +74,7 @@ macro_rules! poll_write {
     ($substream:expr, $cx:ident, $frame:expr) => {{
         match $substream {
             SubstreamType::Tcp(substream) => Pin::new(substream).poll_write($cx, $frame),
+            SubstreamType::Relay(substream) => Pin::new(substream).poll_write($cx, $frame),
             #[cfg(feature = "websocket")]
             SubstreamType::WebSocket(substream) => Pin::new(substream).poll_write($cx, $frame),
             #[cfg(feature = "quic")]
@@ -85,6 +91,7 @@ macro_rules! poll_read {
     ($substream:expr, $cx:ident, $buffer:expr) => {{
         match $substream {
             SubstreamType::Tcp(substream) => Pin::new(substream).poll_read($cx, $buffer),
+            SubstreamType::Relay(substream) => Pin::new(substream).poll_read($cx, $buffer),
             #[cfg(feature = "websocket")]
             SubstreamType::WebSocket(substream) => Pin::new(substream).poll_read($cx, $buffer),
             #[cfg(feature = "quic")]
@@ -101,6 +108,7 @@ macro_rules! poll_shutdown {
     ($substream:expr, $cx:ident) => {{
         match $substream {
             SubstreamType::Tcp(substream) => Pin::new(substream).poll_shutdown($cx),
+            SubstreamType::Relay(substream) => Pin::new(substream).poll_shutdown($cx),
             #[cfg(feature = "websocket")]
             SubstreamType::WebSocket(substream) => Pin::new(substream).poll_shutdown($cx),
             #[cfg(feature = "quic")]
@@ -165,6 +173,7 @@ macro_rules! check_size {
 /// Substream type.
 enum SubstreamType {
     Tcp(tcp::Substream),
+    Relay(relay::Substream),
     #[cfg(feature = "websocket")]
     WebSocket(websocket::Substream),
     #[cfg(feature = "quic")]
@@ -179,6 +188,7 @@ impl fmt::Debug for SubstreamType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Tcp(_) => write!(f, "Tcp"),
+            Self::Relay(_) => write!(f, "Relay"),
             #[cfg(feature = "websocket")]
             Self::WebSocket(_) => write!(f, "WebSocket"),
             #[cfg(feature = "quic")]
@@ -274,6 +284,18 @@ impl Substream {
         Self::new(peer, substream_id, SubstreamType::Tcp(substream), codec)
     }
 
+    /// Create new [`Substream`] for a relayed connection.
+    pub(crate) fn new_relay(
+        peer: PeerId,
+        substream_id: SubstreamId,
+        substream: relay::Substream,
+        codec: ProtocolCodec,
+    ) -> Self {
+        tracing::trace!(target: LOG_TARGET, ?peer, ?codec, "create new substream for relay");
+
+        Self::new(peer, substream_id, SubstreamType::Relay(substream), codec)
+    }
+
     /// Create new [`Substream`] for WebSocket.
     #[cfg(feature = "websocket")]
     pub(crate) fn new_websocket(
@@ -339,6 +361,7 @@ impl Substream {
     pub async fn close(self) {
         let _ = match self.substream {
             SubstreamType::Tcp(mut substream) => substream.shutdown().await,
+            SubstreamType::Relay(mut substream) => substream.shutdown().await,
             #[cfg(feature = "websocket")]
             SubstreamType::WebSocket(mut substream) => substream.shutdown().await,
             #[cfg(feature = "quic")]
@@ -427,6 +450,13 @@ impl Substream {
                 ProtocolCodec::UnsignedVarint(max_size) =>
                     Self::send_unsigned_varint_payload(substream, bytes, max_size).await,
             },
+            SubstreamType::Relay(ref mut substream) => match self.codec {
+                ProtocolCodec::Unspecified => panic!("codec is unspecified"),
+                ProtocolCodec::Identity(payload_size) =>
+                    Self::send_identity_payload(substream, payload_size, bytes).await,
+                ProtocolCodec::UnsignedVarint(max_size) =>
+                    Self::send_unsigned_varint_payload(substream, bytes, max_size).await,
+            },
             #[cfg(feature = "websocket")]
             SubstreamType::WebSocket(ref mut substream) => match self.codec {
                 ProtocolCodec::Unspecified => panic!("codec is unspecified"),
@@ -645,6 +675,13 @@ impl Stream for Substream {
                                                 }
 
                                                 this.offset = 0;
+
+                                                // empty frames carry no payload to read, e.g.,
+                                                // protobuf messages with only default values
+                                                if size == 0 {
+                                                    return Poll::Ready(Some(Ok(BytesMut::new())));
+                                                }
+
                                                 this.current_frame_size = Some(size);
                                                 this.read_buffer = BytesMut::zeroed(size);
                                             }
@@ -1067,4 +1104,33 @@ mod tests {
             assert!(futures::poll!(set.next()).is_pending());
         }
     }
+
+    #[cfg(feature = "webrtc")]
+    #[tokio::test]
+    async fn empty_unsigned_varint_frames_are_read() {
+        use crate::transport::webrtc::WebRtcMessage;
+
+        let (substream, handle) = webrtc::Substream::new();
+        let mut substream = Substream::new_webrtc(
+            PeerId::random(),
+            SubstreamId::from(0usize),
+            substream,
+            ProtocolCodec::UnsignedVarint(None),
+        );
+
+        // an empty frame followed by a frame of three bytes
+        handle
+            .on_message(WebRtcMessage {
+                payload: Some(vec![0u8, 3u8, 1u8, 2u8, 3u8]),
+                flags: None,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(substream.next().await.unwrap().unwrap(), BytesMut::new());
+        assert_eq!(
+            substream.next().await.unwrap().unwrap(),
+            BytesMut::from(&[1u8, 2u8, 3u8][..])
+        );
+    }
 }
@@ -0,0 +1,616 @@
+// Copyright 2024 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! DNS resolver shared between the transports.
+
+use crate::{error::DnsError, transport::common::listener::DnsType};
+
+use hickory_resolver::{
+    config::{LookupIpStrategy, NameServerConfig, Protocol, ResolverConfig, ResolverOpts},
+    TokioAsyncResolver,
+};
+use multiaddr::{Multiaddr, Protocol as MultiaddrProtocol};
+
+use std::{
+    collections::VecDeque,
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+/// Logging target for the file.
+const LOG_TARGET: &str = "litep2p::transport::dns";
+
+/// Prefix of the TXT records holding the addresses of a `/dnsaddr` domain.
+const DNSADDR_PREFIX: &str = "dnsaddr=";
+
+/// DNS resolver configuration.
+#[derive(Debug, Clone)]
+pub struct DnsConfig {
+    /// Nameservers used to resolve addresses.
+    ///
+    /// Each nameserver is queried both over UDP and TCP. If no nameservers are specified,
+    /// the nameservers of `hickory-resolver`'s default configuration are used.
+    ///
+    /// Defaults to an empty list.
+    pub nameservers: Vec<SocketAddr>,
+
+    /// Maximum number of records kept in the cache.
+    ///
+    /// Records are cached for as long as their TTL allows.
+    ///
+    /// Defaults to `32`.
+    pub cache_size: usize,
+
+    /// Timeout for a single DNS query.
+    ///
+    /// Defaults to 5 seconds.
+    pub timeout: Duration,
+
+    /// Timeout for resolving an address.
+    ///
+    /// Bounds all queries needed to resolve an address, including the recursive lookups of a
+    /// `/dnsaddr` address. Dials of DNS addresses fail if the address can't be resolved within
+    /// the timeout, independent of the connection open timeout of the transport.
+    ///
+    /// Defaults to 10 seconds.
+    pub resolution_timeout: Duration,
+
+    /// Maximum number of TXT lookups performed when resolving a `/dnsaddr` address.
+    ///
+    /// `/dnsaddr` records may point to other `/dnsaddr` domains and the limit prevents a
+    /// misconfigured or malicious domain from causing an unbounded number of lookups.
+    ///
+    /// Defaults to `32`.
+    pub max_dnsaddr_lookups: usize,
+}
+
+impl Default for DnsConfig {
+    fn default() -> Self {
+        Self {
+            nameservers: Vec::new(),
+            cache_size: 32,
+            timeout: Duration::from_secs(5),
+            resolution_timeout: Duration::from_secs(10),
+            max_dnsaddr_lookups: 32,
+        }
+    }
+}
+
+/// DNS resolver.
+///
+/// The resolver is shared between all transports of a node so that the cache of resolved
+/// records is shared as well.
+#[derive(Clone)]
+pub(crate) struct DnsResolver {
+    /// Resolver.
+    resolver: TokioAsyncResolver,
+
+    /// Maximum number of TXT lookups performed when resolving a `/dnsaddr` address.
+    max_dnsaddr_lookups: usize,
+
+    /// Timeout for resolving an address.
+    resolution_timeout: Duration,
+}
+
+impl Default for DnsResolver {
+    fn default() -> Self {
+        Self::new(DnsConfig::default())
+    }
+}
+
+impl DnsResolver {
+    /// Create new [`DnsResolver`].
+    pub(crate) fn new(config: DnsConfig) -> Self {
+        let resolver_config = match config.nameservers.is_empty() {
+            true => ResolverConfig::default(),
+            false => {
+                let mut resolver_config = ResolverConfig::new();
+
+                for address in config.nameservers {
+                    resolver_config.add_name_server(NameServerConfig::new(address, Protocol::Udp));
+                    resolver_config.add_name_server(NameServerConfig::new(address, Protocol::Tcp));
+                }
+
+                resolver_config
+            }
+        };
+
+        let mut options = ResolverOpts::default();
+        options.cache_size = config.cache_size;
+        options.timeout = config.timeout;
+        options.ip_strategy = LookupIpStrategy::Ipv4AndIpv6;
+
+        Self {
+            resolver: TokioAsyncResolver::tokio(resolver_config, options),
+            max_dnsaddr_lookups: config.max_dnsaddr_lookups,
+            resolution_timeout: config.resolution_timeout,
+        }
+    }
+
+    /// Resolve `host` into IP addresses allowed by `dns_type`.
+    pub(crate) async fn lookup_ip(
+        &self,
+        host: &str,
+        dns_type: DnsType,
+    ) -> Result<Vec<IpAddr>, DnsError> {
+        let lookup = match tokio::time::timeout(
+            self.resolution_timeout,
+            self.resolver.lookup_ip(host),
+        )
+        .await
+        {
+            Ok(Ok(lookup)) => lookup,
+            Ok(Err(error)) => {
+                tracing::debug!(
+                    target: LOG_TARGET,
+                    ?error,
+                    "failed to resolve DNS address `{}`",
+                    host
+                );
+
+                return Err(DnsError::ResolveError(host.to_string()));
+            }
+            Err(_) => {
+                tracing::debug!(
+                    target: LOG_TARGET,
+                    "failed to resolve DNS address `{}` within timeout",
+                    host
+                );
+
+                return Err(DnsError::ResolveError(host.to_string()));
+            }
+        };
+
+        let addresses = lookup
+            .iter()
+            .filter(|ip| match dns_type {
+                DnsType::Dns => true,
+                DnsType::Dns4 => ip.is_ipv4(),
+                DnsType::Dns6 => ip.is_ipv6(),
+            })
+            .collect::<Vec<_>>();
+
+        if addresses.is_empty() {
+            tracing::debug!(
+                target: LOG_TARGET,
+                "Multiaddr DNS type does not match IP version `{}`",
+                host
+            );
+            return Err(DnsError::IpVersionMismatch);
+        }
+
+        Ok(addresses)
+    }
+
+    /// Resolve `/dnsaddr` address into the addresses it points to.
+    ///
+    /// The TXT records of `_dnsaddr.<domain>` are resolved recursively until the records no
+    /// longer point to other `/dnsaddr` domains. Only the records which end with the same
+    /// protocols as `address` does after `/dnsaddr` are kept, e.g., if `address` ends with
+    /// `/p2p/<peer>`, only the addresses of `<peer>` are returned.
+    ///
+    /// `address` is returned as-is if it's not a `/dnsaddr` address.
+    pub(crate) async fn resolve_dnsaddr(
+        &self,
+        address: Multiaddr,
+    ) -> Result<Vec<Multiaddr>, DnsError> {
+        match tokio::time::timeout(
+            self.resolution_timeout,
+            self.resolve_dnsaddr_inner(&address),
+        )
+        .await
+        {
+            Err(_) => {
+                tracing::debug!(
+                    target: LOG_TARGET,
+                    ?address,
+                    "failed to resolve `/dnsaddr` address within timeout",
+                );
+
+                Err(DnsError::ResolveError(address.to_string()))
+            }
+            Ok(result) => result,
+        }
+    }
+
+    /// Resolve `/dnsaddr` address without a timeout.
+    async fn resolve_dnsaddr_inner(&self, address: &Multiaddr) -> Result<Vec<Multiaddr>, DnsError> {
+        // records pointing to other `/dnsaddr` domains are followed as-is, the suffix of
+        // `address` only filters the addresses they finally resolve into
+        let suffix = address.iter().skip(1).collect::<Vec<_>>();
+        let mut pending = VecDeque::from([address.clone()]);
+        let mut resolved = Vec::new();
+        let mut lookups = 0usize;
+
+        while let Some(next) = pending.pop_front() {
+            let Some(MultiaddrProtocol::Dnsaddr(domain)) = next.iter().next() else {
+                resolved.push(next);
+                continue;
+            };
+
+            if lookups == self.max_dnsaddr_lookups {
+                tracing::debug!(
+                    target: LOG_TARGET,
+                    ?address,
+                    limit = ?self.max_dnsaddr_lookups,
+                    "`/dnsaddr` lookup limit reached",
+                );
+                continue;
+            }
+            lookups += 1;
+
+            let lookup = match self.resolver.txt_lookup(format!("_dnsaddr.{domain}")).await {
+                Ok(lookup) => lookup,
+                Err(error) => {
+                    tracing::debug!(
+                        target: LOG_TARGET,
+                        ?error,
+                        "failed to resolve `/dnsaddr` domain `{}`",
+                        domain
+                    );
+                    continue;
+                }
+            };
+
+            for data in lookup.iter().flat_map(|record| record.iter()) {
+                let Some(record) = std::str::from_utf8(data)
+                    .ok()
+                    .and_then(|record| record.strip_prefix(DNSADDR_PREFIX))
+                else {
+                    continue;
+                };
+
+                match record.parse::<Multiaddr>() {
+                    Ok(record)
+                        if std::matches!(
+                            record.iter().next(),
+                            Some(MultiaddrProtocol::Dnsaddr(_))
+                        ) || ends_with(&record, &suffix) =>
+                        pending.push_back(record),
+                    Ok(_) => {}
+                    Err(error) => tracing::debug!(
+                        target: LOG_TARGET,
+                        ?error,
+                        ?record,
+                        "invalid `/dnsaddr` record",
+                    ),
+                }
+            }
+        }
+
+        if resolved.is_empty() {
+            return Err(DnsError::ResolveError(address.to_string()));
+        }
+
+        Ok(resolved)
+    }
+}
+
+/// Check if `address` ends with `suffix`.
+fn ends_with(address: &Multiaddr, suffix: &[MultiaddrProtocol<'_>]) -> bool {
+    let protocols = address.iter().collect::<Vec<_>>();
+
+    protocols.len() >= suffix.len() && protocols[protocols.len() - suffix.len()..] == *suffix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PeerId;
+    use simple_dns::{
+        rdata::{RData, A, AAAA, TXT},
+        Packet, ResourceRecord, CLASS, QTYPE, TYPE,
+    };
+    use std::{
+        collections::HashMap,
+        net::{Ipv4Addr, Ipv6Addr},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+    use tokio::net::UdpSocket;
+
+    /// DNS record served by the test nameserver.
+    enum Record {
+        A(Ipv4Addr),
+        Aaaa(Ipv6Addr),
+        Txt(String),
+    }
+
+    /// Start nameserver answering queries from `records` with records of `ttl`.
+    ///
+    /// Returns the address of the nameserver and the number of queries it has received.
+    async fn nameserver(
+        records: Vec<(&'static str, Record)>,
+        ttl: u32,
+    ) -> (SocketAddr, Arc<AtomicUsize>) {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let address = socket.local_addr().unwrap();
+        let queries = Arc::new(AtomicUsize::new(0usize));
+        let counter = queries.clone();
+
+        let mut zone = HashMap::<&'static str, Vec<Record>>::new();
+        for (name, record) in records {
+            zone.entry(name).or_default().push(record);
+        }
+
+        tokio::spawn(async move {
+            let mut buffer = vec![0u8; 4096];
+
+            while let Ok((nread, peer)) = socket.recv_from(&mut buffer).await {
+                let Ok(query) = Packet::parse(&buffer[..nread]) else {
+                    continue;
+                };
+                counter.fetch_add(1usize, Ordering::Relaxed);
+
+                let mut reply = Packet::new_reply(query.id());
+                reply.questions = query.questions.clone();
+
+                for question in &query.questions {
+                    let name = question.qname.to_string();
+                    let Some(records) = zone.get(name.trim_end_matches('.')) else {
+                        continue;
+                    };
+
+                    for record in records {
+                        let rdata = match (record, question.qtype) {
+                            (Record::A(ip), QTYPE::TYPE(TYPE::A)) => RData::A(A::from(*ip)),
+                            (Record::Aaaa(ip), QTYPE::TYPE(TYPE::AAAA)) =>
+                                RData::AAAA(AAAA::from(*ip)),
+                            (Record::Txt(text), QTYPE::TYPE(TYPE::TXT)) => {
+                                let mut txt = TXT::new();
+                                txt.add_string(text).unwrap();
+                                RData::TXT(txt)
+                            }
+                            _ => continue,
+                        };
+
+                        reply.answers.push(ResourceRecord::new(
+                            question.qname.clone(),
+                            CLASS::IN,
+                            ttl,
+                            rdata,
+                        ));
+                    }
+                }
+
+                let _ = socket.send_to(&reply.build_bytes_vec().unwrap(), peer).await;
+            }
+        });
+
+        (address, queries)
+    }
+
+    fn resolver(nameserver: SocketAddr) -> DnsResolver {
+        DnsResolver::new(DnsConfig {
+            nameservers: vec![nameserver],
+            timeout: Duration::from_secs(1),
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn lookup_returns_all_addresses() {
+        let (address, _) = nameserver(
+            vec![
+                ("node.test", Record::A(Ipv4Addr::new(127, 0, 0, 1))),
+                ("node.test", Record::A(Ipv4Addr::new(127, 0, 0, 2))),
+                ("node.test", Record::Aaaa(Ipv6Addr::LOCALHOST)),
+            ],
+            60,
+        )
+        .await;
+        let resolver = resolver(address);
+
+        let mut addresses = resolver.lookup_ip("node.test", DnsType::Dns).await.unwrap();
+        addresses.sort();
+        assert_eq!(
+            addresses,
+            vec![
+                IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+                IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2)),
+                IpAddr::V6(Ipv6Addr::LOCALHOST),
+            ]
+        );
+
+        let addresses = resolver.lookup_ip("node.test", DnsType::Dns6).await.unwrap();
+        assert_eq!(addresses, vec![IpAddr::V6(Ipv6Addr::LOCALHOST)]);
+    }
+
+    #[tokio::test]
+    async fn lookup_ip_version_mismatch() {
+        let (address, _) =
+            nameserver(vec![("node.test", Record::A(Ipv4Addr::LOCALHOST))], 60).await;
+
+        assert_eq!(
+            resolver(address).lookup_ip("node.test", DnsType::Dns6).await,
+            Err(DnsError::IpVersionMismatch)
+        );
+    }
+
+    #[tokio::test]
+    async fn lookup_unknown_host() {
+        let (address, _) = nameserver(Vec::new(), 60).await;
+
+        assert_eq!(
+            resolver(address).lookup_ip("unknown.test", DnsType::Dns).await,
+            Err(DnsError::ResolveError("unknown.test".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn records_are_cached() {
+        // both record types are served as responses without records aren't cached
+        let (address, queries) = nameserver(
+            vec![
+                ("node.test", Record::A(Ipv4Addr::LOCALHOST)),
+                ("node.test", Record::Aaaa(Ipv6Addr::LOCALHOST)),
+            ],
+            60,
+        )
+        .await;
+        let resolver = resolver(address);
+
+        resolver.lookup_ip("node.test", DnsType::Dns4).await.unwrap();
+        let count = queries.load(Ordering::Relaxed);
+        assert!(count > 0);
+
+        // clones share the cache
+        resolver.clone().lookup_ip("node.test", DnsType::Dns4).await.unwrap();
+        assert_eq!(queries.load(Ordering::Relaxed), count);
+    }
+
+    #[tokio::test]
+    async fn expired_records_are_resolved_again() {
+        let (address, queries) =
+            nameserver(vec![("node.test", Record::A(Ipv4Addr::LOCALHOST))], 1).await;
+        let resolver = resolver(address);
+
+        resolver.lookup_ip("node.test", DnsType::Dns4).await.unwrap();
+        let count = queries.load(Ordering::Relaxed);
+
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+
+        resolver.lookup_ip("node.test", DnsType::Dns4).await.unwrap();
+        assert!(queries.load(Ordering::Relaxed) > count);
+    }
+
+    #[tokio::test]
+    async fn resolve_dnsaddr_recursively() {
+        let peer1 = PeerId::random();
+        let peer2 = PeerId::random();
+
+        let (address, _) = nameserver(
+            vec![
+                (
+                    "_dnsaddr.bootstrap.test",
+                    Record::Txt("dnsaddr=/dnsaddr/nodes.bootstrap.test".to_string()),
+                ),
+                (
+                    "_dnsaddr.nodes.bootstrap.test",
+                    Record::Txt(format!("dnsaddr=/ip4/127.0.0.1/tcp/8888/p2p/{peer1}")),
+                ),
+                (
+                    "_dnsaddr.nodes.bootstrap.test",
+                    Record::Txt(format!("dnsaddr=/ip6/::1/tcp/8888/p2p/{peer1}")),
+                ),
+                (
+                    "_dnsaddr.nodes.bootstrap.test",
+                    Record::Txt(format!("dnsaddr=/ip4/127.0.0.2/tcp/8888/p2p/{peer2}")),
+                ),
+                (
+                    "_dnsaddr.nodes.bootstrap.test",
+                    Record::Txt("not a dnsaddr record".to_string()),
+                ),
+            ],
+            60,
+        )
+        .await;
+        let resolver = resolver(address);
+
+        let addresses = resolver
+            .resolve_dnsaddr(format!("/dnsaddr/bootstrap.test/p2p/{peer1}").parse().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(
+            addresses,
+            vec![
+                format!("/ip4/127.0.0.1/tcp/8888/p2p/{peer1}").parse().unwrap(),
+                format!("/ip6/::1/tcp/8888/p2p/{peer1}").parse().unwrap(),
+            ]
+        );
+
+        // without a peer ID, all addresses are returned
+        let addresses = resolver
+            .resolve_dnsaddr("/dnsaddr/bootstrap.test".parse().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(addresses.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn dnsaddr_lookup_limit() {
+        // `loop.test` points to itself
+        let (address, queries) = nameserver(
+            vec![(
+                "_dnsaddr.loop.test",
+                Record::Txt("dnsaddr=/dnsaddr/loop.test".to_string()),
+            )],
+            0,
+        )
+        .await;
+        let resolver = DnsResolver::new(DnsConfig {
+            nameservers: vec![address],
+            max_dnsaddr_lookups: 5,
+            ..Default::default()
+        });
+
+        assert!(std::matches!(
+            resolver.resolve_dnsaddr("/dnsaddr/loop.test".parse().unwrap()).await,
+            Err(DnsError::ResolveError(_))
+        ));
+        assert!(queries.load(Ordering::Relaxed) <= 5);
+    }
+
+    #[tokio::test]
+    async fn non_dnsaddr_address_is_returned_as_is() {
+        let address: Multiaddr = "/ip4/127.0.0.1/tcp/8888".parse().unwrap();
+
+        assert_eq!(
+            DnsResolver::default().resolve_dnsaddr(address.clone()).await.unwrap(),
+            vec![address]
+        );
+    }
+
+    #[tokio::test]
+    async fn resolution_timeout() {
+        // nameserver which accepts queries but never answers them
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let address = socket.local_addr().unwrap();
+        let listener = tokio::net::TcpListener::bind(address).await.unwrap();
+
+        tokio::spawn(async move {
+            let _socket = socket;
+            let mut streams = Vec::new();
+
+            while let Ok((stream, _)) = listener.accept().await {
+                streams.push(stream);
+            }
+        });
+
+        let resolver = DnsResolver::new(DnsConfig {
+            nameservers: vec![address],
+            resolution_timeout: Duration::from_millis(200),
+            ..Default::default()
+        });
+        let started = std::time::Instant::now();
+
+        assert!(std::matches!(
+            resolver.lookup_ip("node.test", DnsType::Dns).await,
+            Err(DnsError::ResolveError(_))
+        ));
+        assert!(std::matches!(
+            resolver.resolve_dnsaddr("/dnsaddr/bootstrap.test".parse().unwrap()).await,
+            Err(DnsError::ResolveError(_))
+        ));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -21,31 +21,38 @@
 //! Shared socket listener between TCP and WebSocket.
 
 use crate::{
-    error::{AddressError, DnsError},
+    error::{AddressError, DnsError, Error},
+    transport::{common::dns::DnsResolver, TransportEvent},
+    types::ListenerId,
     PeerId,
 };
 
 use futures::Stream;
-use hickory_resolver::{
-    config::{ResolverConfig, ResolverOpts},
-    TokioAsyncResolver,
-};
 use multiaddr::{Multiaddr, Protocol};
 use network_interface::{Addr, NetworkInterface, NetworkInterfaceConfig};
 use socket2::{Domain, Socket, Type};
-use tokio::net::{TcpListener as TokioTcpListener, TcpStream};
+use tokio::{
+    net::{TcpListener as TokioTcpListener, TcpStream},
+    time::{Interval, MissedTickBehavior},
+};
 
 use std::{
+    collections::VecDeque,
     io,
     net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
     pin::Pin,
     sync::Arc,
-    task::{Context, Poll},
+    task::{Context, Poll, Waker},
+    time::Duration,
 };
 
 /// Logging target for the file.
 const LOG_TARGET: &str = "litep2p::transport::listener";
 
+/// How often the network interfaces are polled for changes in the addresses of the listeners
+/// bound to an unspecified address.
+pub(crate) const INTERFACE_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
 /// Address type.
 #[derive(Debug)]
 pub enum AddressType {
@@ -72,50 +79,25 @@ pub enum DnsType {
 }
 
 impl AddressType {
-    /// Resolve the address to a concrete IP.
-    pub async fn lookup_ip(self) -> Result<SocketAddr, DnsError> {
-        let (url, port, dns_type) = match self {
+    /// Resolve the address to concrete socket addresses using `resolver`.
+    ///
+    /// All resolved addresses matching the DNS type are returned, in the order the resolver
+    /// returned them.
+    pub async fn lookup_ip(self, resolver: &DnsResolver) -> Result<Vec<SocketAddr>, DnsError> {
+        match self {
             // We already have the IP address.
-            AddressType::Socket(address) => return Ok(address),
+            AddressType::Socket(address) => Ok(vec![address]),
             AddressType::Dns {
                 address,
                 port,
                 dns_type,
-            } => (address, port, dns_type),
-        };
-
-        let lookup =
-            match TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default())
-                .lookup_ip(url.clone())
-                .await
-            {
-                Ok(lookup) => lookup,
-                Err(error) => {
-                    tracing::debug!(
-                        target: LOG_TARGET,
-                        ?error,
-                        "failed to resolve DNS address `{}`",
-                        url
-                    );
-
-                    return Err(DnsError::ResolveError(url));
-                }
-            };
-
-        let Some(ip) = lookup.iter().find(|ip| match dns_type {
-            DnsType::Dns => true,
-            DnsType::Dns4 => ip.is_ipv4(),
-            DnsType::Dns6 => ip.is_ipv6(),
-        }) else {
-            tracing::debug!(
-                target: LOG_TARGET,
-                "Multiaddr DNS type does not match IP version `{}`",
-                url
-            );
-            return Err(DnsError::IpVersionMismatch);
-        };
-
-        Ok(SocketAddr::new(ip, port))
+            } => Ok(resolver
+                .lookup_ip(&address, dns_type)
+                .await?
+                .into_iter()
+                .map(|ip| SocketAddr::new(ip, port))
+                .collect()),
+        }
     }
 }
 
@@ -161,12 +143,36 @@ impl DialAddresses {
     }
 }
 
+/// Bound TCP listener.
+struct Listener {
+    /// ID of the listener.
+    id: ListenerId,
+    /// TCP listener.
+    listener: TokioTcpListener,
+    /// Local address of the listener.
+    local_address: SocketAddr,
+    /// Addresses the listener is reachable at.
+    listen_addresses: Vec<SocketAddr>,
+}
+
 /// Socket listening to zero or more addresses.
+///
+/// Listeners bound to an unspecified address are reachable at the addresses of all network
+/// interfaces. The interfaces are polled periodically and the changes in the listen addresses
+/// are reported by [`SocketListener::poll_address_change()`].
 pub struct SocketListener {
     /// Listeners.
-    listeners: Vec<TokioTcpListener>,
+    listeners: Vec<Listener>,
     /// The index in the listeners from which the polling is resumed.
     poll_index: usize,
+    /// Waker of the task polling the listener, woken up when a new listener is started.
+    waker: Option<Waker>,
+    /// Convert socket address of the listener to `Multiaddr`.
+    socket_address_to_multiaddr: fn(&SocketAddr) -> Multiaddr,
+    /// Interval for polling the network interfaces, created on first poll.
+    interface_poll_interval: Option<Interval>,
+    /// Pending listen address changes.
+    pending_events: VecDeque<TransportEvent>,
 }
 
 /// Trait to convert between `Multiaddr` and `SocketAddr`.
@@ -226,97 +232,54 @@ impl GetSocketAddr for WebSocketAddress {
     }
 }
 
+/// Secure WebSocket helper to convert between `Multiaddr` and `SocketAddr`.
+pub struct SecureWebSocketAddress;
+
+impl GetSocketAddr for SecureWebSocketAddress {
+    fn multiaddr_to_socket_address(
+        address: &Multiaddr,
+    ) -> Result<(AddressType, Option<PeerId>), AddressError> {
+        multiaddr_to_socket_address(address, SocketListenerType::WebSocket)
+    }
+
+    fn socket_address_to_multiaddr(address: &SocketAddr) -> Multiaddr {
+        Multiaddr::empty()
+            .with(Protocol::from(address.ip()))
+            .with(Protocol::Tcp(address.port()))
+            .with(Protocol::Tls)
+            .with(Protocol::Ws(std::borrow::Cow::Borrowed("/")))
+    }
+}
+
 impl SocketListener {
     /// Create new [`SocketListener`]
     pub fn new<T: GetSocketAddr>(
-        addresses: Vec<Multiaddr>,
+        addresses: Vec<(ListenerId, Multiaddr)>,
         reuse_port: bool,
         nodelay: bool,
     ) -> (Self, Vec<Multiaddr>, DialAddresses) {
-        let (listeners, listen_addresses): (_, Vec<Vec<_>>) = addresses
+        let listeners = addresses
             .into_iter()
-            .filter_map(|address| {
-                let address = match T::multiaddr_to_socket_address(&address).ok()?.0 {
-                    AddressType::Dns { address, port, .. } => {
-                        tracing::debug!(
-                            target: LOG_TARGET,
-                            ?address,
-                            ?port,
-                            "dns not supported as bind address"
-                        );
+            .filter_map(|(listener_id, address)| {
+                let listener = bind::<T>(listener_id, &address, reuse_port, nodelay);
 
-                        return None;
-                    }
-                    AddressType::Socket(address) => address,
-                };
-
-                let socket = if address.is_ipv4() {
-                    Socket::new(Domain::IPV4, Type::STREAM, Some(socket2::Protocol::TCP)).ok()?
-                } else {
-                    let socket =
-                        Socket::new(Domain::IPV6, Type::STREAM, Some(socket2::Protocol::TCP))
-                            .ok()?;
-                    socket.set_only_v6(true).ok()?;
-                    socket
-                };
-
-                socket.set_nodelay(nodelay).ok()?;
-                socket.set_nonblocking(true).ok()?;
-                socket.set_reuse_address(true).ok()?;
-                #[cfg(unix)]
-                if reuse_port {
-                    socket.set_reuse_port(true).ok()?;
+                if let Err(error) = &listener {
+                    tracing::debug!(
+                        target: LOG_TARGET,
+                        ?address,
+                        ?error,
+                        "failed to bind listener",
+                    );
                 }
-                socket.bind(&address.into()).ok()?;
-                socket.listen(1024).ok()?;
-
-                let socket: std::net::TcpListener = socket.into();
-                let listener = TokioTcpListener::from_std(socket).ok()?;
-                let local_address = listener.local_addr().ok()?;
-
-                let listen_addresses = if address.ip().is_unspecified() {
-                    match NetworkInterface::show() {
-                        Ok(ifaces) => ifaces
-                            .into_iter()
-                            .flat_map(|record| {
-                                record.addr.into_iter().filter_map(|iface_address| {
-                                    match (iface_address, address.is_ipv4()) {
-                                        (Addr::V4(inner), true) => Some(SocketAddr::new(
-                                            IpAddr::V4(inner.ip),
-                                            local_address.port(),
-                                        )),
-                                        (Addr::V6(inner), false) =>
-                                            match inner.ip.segments().first() {
-                                                Some(0xfe80) => None,
-                                                _ => Some(SocketAddr::new(
-                                                    IpAddr::V6(inner.ip),
-                                                    local_address.port(),
-                                                )),
-                                            },
-                                        _ => None,
-                                    }
-                                })
-                            })
-                            .collect(),
-                        Err(error) => {
-                            tracing::warn!(
-                                target: LOG_TARGET,
-                                ?error,
-                                "failed to fetch network interfaces",
-                            );
-
-                            return None;
-                        }
-                    }
-                } else {
-                    vec![local_address]
-                };
 
-                Some((listener, listen_addresses))
+                listener.ok()
             })
-            .unzip();
+            .collect::<Vec<_>>();
 
-        let listen_addresses = listen_addresses.into_iter().flatten().collect::<Vec<_>>();
+        let listen_addresses = listeners
+            .iter()
+            .flat_map(|listener| listener.listen_addresses.iter().copied())
+            .collect::<Vec<_>>();
         let listen_multi_addresses =
             listen_addresses.iter().map(T::socket_address_to_multiaddr).collect();
 
@@ -332,11 +295,235 @@ impl SocketListener {
             Self {
                 listeners,
                 poll_index: 0,
+                waker: None,
+                socket_address_to_multiaddr: T::socket_address_to_multiaddr,
+                interface_poll_interval: None,
+                pending_events: VecDeque::new(),
             },
             listen_multi_addresses,
             dial_addresses,
         )
     }
+
+    /// Start listening on `address`.
+    ///
+    /// Returns the addresses the new listener is reachable at.
+    pub fn listen_on<T: GetSocketAddr>(
+        &mut self,
+        listener_id: ListenerId,
+        address: Multiaddr,
+        reuse_port: bool,
+        nodelay: bool,
+    ) -> crate::Result<Vec<Multiaddr>> {
+        let listener = bind::<T>(listener_id, &address, reuse_port, nodelay)?;
+        let listen_addresses =
+            listener.listen_addresses.iter().map(T::socket_address_to_multiaddr).collect();
+
+        self.listeners.push(listener);
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+
+        Ok(listen_addresses)
+    }
+
+    /// Stop the listener.
+    ///
+    /// Address changes of the listener which haven't been polled yet are discarded.
+    ///
+    /// Returns `false` if the listener doesn't exist.
+    pub fn stop_listening(&mut self, listener_id: ListenerId) -> bool {
+        let len = self.listeners.len();
+        self.listeners.retain(|listener| listener.id != listener_id);
+        self.pending_events.retain(|event| match event {
+            TransportEvent::NewListenAddress {
+                listener_id: id, ..
+            }
+            | TransportEvent::ListenAddressExpired {
+                listener_id: id, ..
+            } => id != &listener_id,
+            _ => true,
+        });
+
+        len != self.listeners.len()
+    }
+
+    /// Poll the next change in the addresses of the listeners bound to an unspecified address.
+    pub fn poll_address_change(&mut self, cx: &mut Context<'_>) -> Poll<TransportEvent> {
+        loop {
+            if let Some(event) = self.pending_events.pop_front() {
+                return Poll::Ready(event);
+            }
+
+            if !self
+                .listeners
+                .iter()
+                .any(|listener| listener.local_address.ip().is_unspecified())
+            {
+                self.waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+
+            let interval = self.interface_poll_interval.get_or_insert_with(|| {
+                let mut interval = tokio::time::interval_at(
+                    tokio::time::Instant::now() + INTERFACE_POLL_INTERVAL,
+                    INTERFACE_POLL_INTERVAL,
+                );
+                interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                interval
+            });
+            futures::ready!(interval.poll_tick(cx));
+
+            match NetworkInterface::show() {
+                Ok(interfaces) => self.on_interfaces(&interfaces),
+                Err(error) => tracing::debug!(
+                    target: LOG_TARGET,
+                    ?error,
+                    "failed to fetch network interfaces",
+                ),
+            }
+        }
+    }
+
+    /// Update the listen addresses of the listeners bound to an unspecified address from the
+    /// current network `interfaces`.
+    fn on_interfaces(&mut self, interfaces: &[NetworkInterface]) {
+        for listener in &mut self.listeners {
+            if !listener.local_address.ip().is_unspecified() {
+                continue;
+            }
+
+            let listen_addresses = interface_addresses(interfaces, &listener.local_address);
+
+            for address in &listen_addresses {
+                if !listener.listen_addresses.contains(address) {
+                    tracing::debug!(
+                        target: LOG_TARGET,
+                        listener_id = ?listener.id,
+                        ?address,
+                        "new listen address",
+                    );
+
+                    self.pending_events.push_back(TransportEvent::NewListenAddress {
+                        listener_id: listener.id,
+                        address: (self.socket_address_to_multiaddr)(address),
+                    });
+                }
+            }
+
+            for address in &listener.listen_addresses {
+                if !listen_addresses.contains(address) {
+                    tracing::debug!(
+                        target: LOG_TARGET,
+                        listener_id = ?listener.id,
+                        ?address,
+                        "listen address expired",
+                    );
+
+                    self.pending_events.push_back(TransportEvent::ListenAddressExpired {
+                        listener_id: listener.id,
+                        address: (self.socket_address_to_multiaddr)(address),
+                    });
+                }
+            }
+
+            listener.listen_addresses = listen_addresses;
+        }
+    }
+}
+
+/// Get the addresses of the network `interfaces` which a listener bound to the unspecified
+/// `local_address` is reachable at.
+fn interface_addresses(
+    interfaces: &[NetworkInterface],
+    local_address: &SocketAddr,
+) -> Vec<SocketAddr> {
+    interfaces
+        .iter()
+        .flat_map(|record| {
+            record.addr.iter().filter_map(|iface_address| {
+                match (iface_address, local_address.is_ipv4()) {
+                    (Addr::V4(inner), true) =>
+                        Some(SocketAddr::new(IpAddr::V4(inner.ip), local_address.port())),
+                    (Addr::V6(inner), false) => match inner.ip.segments().first() {
+                        Some(0xfe80) => None,
+                        _ => Some(SocketAddr::new(IpAddr::V6(inner.ip), local_address.port())),
+                    },
+                    _ => None,
+                }
+            })
+        })
+        .collect()
+}
+
+/// Bind TCP listener to `address`.
+///
+/// Listener bound to an unspecified address is reachable at the addresses of all network
+/// interfaces of the same IP version.
+fn bind<T: GetSocketAddr>(
+    id: ListenerId,
+    address: &Multiaddr,
+    reuse_port: bool,
+    nodelay: bool,
+) -> crate::Result<Listener> {
+    let address = match T::multiaddr_to_socket_address(address)?.0 {
+        AddressType::Dns { address, port, .. } => {
+            tracing::debug!(
+                target: LOG_TARGET,
+                ?address,
+                ?port,
+                "dns not supported as bind address"
+            );
+
+            return Err(Error::AddressError(AddressError::InvalidProtocol));
+        }
+        AddressType::Socket(address) => address,
+    };
+
+    let socket = if address.is_ipv4() {
+        Socket::new(Domain::IPV4, Type::STREAM, Some(socket2::Protocol::TCP))?
+    } else {
+        let socket = Socket::new(Domain::IPV6, Type::STREAM, Some(socket2::Protocol::TCP))?;
+        socket.set_only_v6(true)?;
+        socket
+    };
+
+    socket.set_nodelay(nodelay)?;
+    socket.set_nonblocking(true)?;
+    socket.set_reuse_address(true)?;
+    #[cfg(unix)]
+    if reuse_port {
+        socket.set_reuse_port(true)?;
+    }
+    socket.bind(&address.into())?;
+    socket.listen(1024)?;
+
+    let socket: std::net::TcpListener = socket.into();
+    let listener = TokioTcpListener::from_std(socket)?;
+    let local_address = listener.local_addr()?;
+
+    let listen_addresses = match address.ip().is_unspecified() {
+        true => interface_addresses(
+            &NetworkInterface::show().map_err(|error| {
+                tracing::warn!(
+                    target: LOG_TARGET,
+                    ?error,
+                    "failed to fetch network interfaces",
+                );
+
+                Error::Other(format!("failed to fetch network interfaces: {error}"))
+            })?,
+            &local_address,
+        ),
+        false => vec![local_address],
+    };
+
+    Ok(Listener {
+        id,
+        listener,
+        local_address,
+        listen_addresses,
+    })
 }
 
 /// The type of the socket listener.
@@ -413,15 +600,26 @@ fn multiaddr_to_socket_address(
     match ty {
         SocketListenerType::Tcp => (),
         SocketListenerType::WebSocket => {
-            // verify that `/ws`/`/wss` is part of the multi address
+            // verify that `/ws`, `/wss` or `/tls/ws` is part of the multi address
             match iter.next() {
                 Some(Protocol::Ws(_address)) => {}
                 Some(Protocol::Wss(_address)) => {}
+                Some(Protocol::Tls) => match iter.next() {
+                    Some(Protocol::Ws(_address)) => {}
+                    protocol => {
+                        tracing::error!(
+                            target: LOG_TARGET,
+                            ?protocol,
+                            "invalid protocol, expected `Ws` after `Tls`"
+                        );
+                        return Err(AddressError::InvalidProtocol);
+                    }
+                },
                 protocol => {
                     tracing::error!(
                         target: LOG_TARGET,
                         ?protocol,
-                        "invalid protocol, expected `Ws` or `Wss`"
+                        "invalid protocol, expected `Ws`, `Wss` or `Tls`"
                     );
                     return Err(AddressError::InvalidProtocol);
                 }
@@ -451,13 +649,14 @@ impl Stream for SocketListener {
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         if self.listeners.is_empty() {
+            self.waker = Some(cx.waker().clone());
             return Poll::Pending;
         }
 
         let len = self.listeners.len();
         for index in 0..len {
             let current = (self.poll_index + index) % len;
-            let listener = &mut self.listeners[current];
+            let listener = &mut self.listeners[current].listener;
 
             match listener.poll_accept(cx) {
                 Poll::Pending => {}
@@ -473,6 +672,7 @@ impl Stream for SocketListener {
         }
 
         self.poll_index = (self.poll_index + 1) % len;
+        self.waker = Some(cx.waker().clone());
         Poll::Pending
     }
 }
@@ -613,6 +813,54 @@ mod tests {
         .is_ok());
     }
 
+    #[test]
+    fn parse_multiaddresses_secure_websocket() {
+        assert!(multiaddr_to_socket_address(
+            &"/ip4/127.0.0.1/tcp/8888/wss".parse().expect("valid multiaddress"),
+            SocketListenerType::WebSocket,
+        )
+        .is_ok());
+        assert!(multiaddr_to_socket_address(
+            &"/ip6/::1/tcp/8888/tls/ws".parse().expect("valid multiaddress"),
+            SocketListenerType::WebSocket,
+        )
+        .is_ok());
+        assert!(multiaddr_to_socket_address(
+            &"/dns/hello.world/tcp/8888/tls/ws/p2p/12D3KooWT2ouvz5uMmCvHJGzAGRHiqDts5hzXR7NdoQ27pGdzp9Q"
+                .parse()
+                .expect("valid multiaddress"),
+            SocketListenerType::WebSocket,
+        )
+        .is_ok());
+        assert!(multiaddr_to_socket_address(
+            &"/ip4/127.0.0.1/tcp/8888/tls".parse().expect("valid multiaddress"),
+            SocketListenerType::WebSocket,
+        )
+        .is_err());
+        assert!(multiaddr_to_socket_address(
+            &"/ip4/127.0.0.1/tcp/8888/tls/wss".parse().expect("valid multiaddress"),
+            SocketListenerType::WebSocket,
+        )
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn secure_websocket_listen_address() {
+        let address: Multiaddr = "/ip4/127.0.0.1/tcp/0/wss".parse().unwrap();
+        let (_listener, listen_addresses, _) = SocketListener::new::<SecureWebSocketAddress>(
+            vec![(ListenerId::from(0usize), address)],
+            true,
+            false,
+        );
+
+        let mut iter = listen_addresses[0].iter();
+        assert!(std::matches!(iter.next(), Some(Protocol::Ip4(_))));
+        assert!(std::matches!(iter.next(), Some(Protocol::Tcp(port)) if port != 0));
+        assert_eq!(iter.next(), Some(Protocol::Tls));
+        assert!(std::matches!(iter.next(), Some(Protocol::Ws(_))));
+        assert_eq!(iter.next(), None);
+    }
+
     #[tokio::test]
     async fn no_listeners_tcp() {
         let (mut listener, _, _) = SocketListener::new::<TcpAddress>(Vec::new(), true, false);
@@ -638,8 +886,11 @@ mod tests {
     #[tokio::test]
     async fn one_listener_tcp() {
         let address: Multiaddr = "/ip6/::1/tcp/0".parse().unwrap();
-        let (mut listener, listen_addresses, _) =
-            SocketListener::new::<TcpAddress>(vec![address.clone()], true, false);
+        let (mut listener, listen_addresses, _) = SocketListener::new::<TcpAddress>(
+            vec![(ListenerId::from(0usize), address.clone())],
+            true,
+            false,
+        );
 
         let Some(Protocol::Tcp(port)) =
             listen_addresses.iter().next().unwrap().clone().iter().skip(1).next()
@@ -653,11 +904,117 @@ mod tests {
         assert!(res1.unwrap().is_ok() && res2.is_ok());
     }
 
+    #[tokio::test]
+    async fn listen_on_and_stop_listening() {
+        let (mut listener, _, _) = SocketListener::new::<TcpAddress>(Vec::new(), false, false);
+        let listener_id = ListenerId::from(0usize);
+
+        let listen_addresses = listener
+            .listen_on::<TcpAddress>(
+                listener_id,
+                "/ip4/127.0.0.1/tcp/0".parse().unwrap(),
+                false,
+                false,
+            )
+            .unwrap();
+        let Some(Protocol::Tcp(port)) = listen_addresses[0].iter().nth(1) else {
+            panic!("invalid address");
+        };
+
+        let (res1, res2) = tokio::join!(
+            listener.next(),
+            TcpStream::connect(format!("127.0.0.1:{port}"))
+        );
+        assert!(res1.unwrap().is_ok() && res2.is_ok());
+
+        assert!(listener.stop_listening(listener_id));
+        assert!(!listener.stop_listening(listener_id));
+        assert!(TcpStream::connect(format!("127.0.0.1:{port}")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn interface_address_changes() {
+        let (mut listener, _, _) = SocketListener::new::<TcpAddress>(
+            vec![
+                (
+                    ListenerId::from(0usize),
+                    "/ip4/0.0.0.0/tcp/0".parse().unwrap(),
+                ),
+                (
+                    ListenerId::from(1usize),
+                    "/ip4/127.0.0.1/tcp/0".parse().unwrap(),
+                ),
+            ],
+            false,
+            false,
+        );
+        let port = listener.listeners[0].local_address.port();
+        let interface = |ip: [u8; 4], index: u32| {
+            NetworkInterface::new_afinet(&format!("eth{index}"), ip.into(), None, None, index)
+        };
+        let address = |ip: [u8; 4]| {
+            Multiaddr::empty().with(Protocol::Ip4(ip.into())).with(Protocol::Tcp(port))
+        };
+
+        // replace the addresses of the actual network interfaces
+        listener.on_interfaces(&[interface([127, 0, 0, 1], 0), interface([10, 0, 0, 1], 1)]);
+        listener.pending_events.clear();
+
+        listener.on_interfaces(&[
+            interface([127, 0, 0, 1], 0),
+            interface([10, 0, 0, 2], 1),
+            NetworkInterface::new_afinet6("eth2", Ipv6Addr::LOCALHOST, None, None, 2),
+        ]);
+
+        assert_eq!(listener.pending_events.len(), 2);
+        assert!(std::matches!(
+            listener.pending_events.pop_front(),
+            Some(TransportEvent::NewListenAddress { listener_id, address: new })
+                if listener_id == ListenerId::from(0usize) && new == address([10, 0, 0, 2])
+        ));
+        assert!(std::matches!(
+            listener.pending_events.pop_front(),
+            Some(TransportEvent::ListenAddressExpired { listener_id, address: expired })
+                if listener_id == ListenerId::from(0usize) && expired == address([10, 0, 0, 1])
+        ));
+        assert_eq!(
+            listener.listeners[0].listen_addresses,
+            vec![
+                SocketAddr::new(Ipv4Addr::new(127, 0, 0, 1).into(), port),
+                SocketAddr::new(Ipv4Addr::new(10, 0, 0, 2).into(), port),
+            ]
+        );
+
+        // address changes of a stopped listener are not reported
+        listener.on_interfaces(&[interface([127, 0, 0, 1], 0)]);
+        assert_eq!(listener.pending_events.len(), 1);
+        assert!(listener.stop_listening(ListenerId::from(0usize)));
+        assert!(listener.pending_events.is_empty());
+    }
+
+    #[tokio::test]
+    async fn listen_on_dns_address() {
+        let (mut listener, _, _) = SocketListener::new::<TcpAddress>(Vec::new(), false, false);
+
+        assert!(std::matches!(
+            listener.listen_on::<TcpAddress>(
+                ListenerId::from(0usize),
+                "/dns/localhost/tcp/0".parse().unwrap(),
+                false,
+                false,
+            ),
+            Err(Error::AddressError(AddressError::InvalidProtocol))
+        ));
+    }
+
     #[tokio::test]
     async fn one_listener_websocket() {
         let address: Multiaddr = "/ip6/::1/tcp/0/ws".parse().unwrap();
-        let (mut listener, listen_addresses, _) =
-            SocketListener::new::<WebSocketAddress>(vec![address.clone()], true, false);
+        let (mut listener, listen_addresses, _) = SocketListener::new::<WebSocketAddress>(
+            vec![(ListenerId::from(0usize), address.clone())],
+            true,
+            false,
+        );
         let Some(Protocol::Tcp(port)) =
             listen_addresses.iter().next().unwrap().clone().iter().skip(1).next()
         else {
@@ -674,8 +1031,14 @@ mod tests {
     async fn two_listeners_tcp() {
         let address1: Multiaddr = "/ip6/::1/tcp/0".parse().unwrap();
         let address2: Multiaddr = "/ip4/127.0.0.1/tcp/0".parse().unwrap();
-        let (mut listener, listen_addresses, _) =
-            SocketListener::new::<TcpAddress>(vec![address1, address2], true, false);
+        let (mut listener, listen_addresses, _) = SocketListener::new::<TcpAddress>(
+            vec![
+                (ListenerId::from(0usize), address1),
+                (ListenerId::from(1usize), address2),
+            ],
+            true,
+            false,
+        );
         let Some(Protocol::Tcp(port1)) =
             listen_addresses.iter().next().unwrap().clone().iter().skip(1).next()
         else {
@@ -702,8 +1065,14 @@ mod tests {
     async fn two_listeners_websocket() {
         let address1: Multiaddr = "/ip6/::1/tcp/0/ws".parse().unwrap();
         let address2: Multiaddr = "/ip4/127.0.0.1/tcp/0/ws".parse().unwrap();
-        let (mut listener, listen_addresses, _) =
-            SocketListener::new::<WebSocketAddress>(vec![address1, address2], true, false);
+        let (mut listener, listen_addresses, _) = SocketListener::new::<WebSocketAddress>(
+            vec![
+                (ListenerId::from(0usize), address1),
+                (ListenerId::from(1usize), address2),
+            ],
+            true,
+            false,
+        );
 
         let Some(Protocol::Tcp(port1)) =
             listen_addresses.iter().next().unwrap().clone().iter().skip(1).next()
@@ -20,4 +20,8 @@
 
 //! Shared transport protocol implementation
 
+pub mod dns;
+pub(crate) mod ip;
 pub mod listener;
+pub mod muxer;
+pub mod security;
@@ -0,0 +1,224 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Stream multiplexers used by stream-based transports.
+
+use crate::{config::Role, error::SubstreamError, protocol::Direction};
+
+use futures::{AsyncRead, AsyncWrite, Stream, StreamExt};
+
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Protocol name of yamux.
+const YAMUX_PROTOCOL: &str = "/yamux/1.0.0";
+
+/// Stream multiplexer used to multiplex substreams over a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StreamMuxer {
+    /// Yamux, see <https://github.com/libp2p/specs/blob/master/yamux/README.md>.
+    Yamux,
+
+    /// mplex, see <https://github.com/libp2p/specs/blob/master/mplex/README.md>.
+    Mplex,
+}
+
+impl StreamMuxer {
+    /// Get the multistream-select protocol name of the stream muxer.
+    pub fn protocol_name(&self) -> &'static str {
+        match self {
+            Self::Yamux => YAMUX_PROTOCOL,
+            Self::Mplex => crate::mplex::PROTOCOL_NAME,
+        }
+    }
+
+    /// Get the stream muxer from its multistream-select protocol name.
+    pub(crate) fn from_protocol_name(protocol: &str) -> Option<Self> {
+        match protocol {
+            YAMUX_PROTOCOL => Some(Self::Yamux),
+            crate::mplex::PROTOCOL_NAME => Some(Self::Mplex),
+            _ => None,
+        }
+    }
+}
+
+/// Error returned by the negotiated stream muxer.
+#[derive(Debug)]
+pub(crate) enum MuxerError {
+    /// Yamux error.
+    Yamux(crate::yamux::ConnectionError),
+
+    /// mplex error.
+    Mplex(crate::mplex::ConnectionError),
+}
+
+impl MuxerError {
+    /// Convert the error into [`SubstreamError`] for a substream of `direction`.
+    pub(crate) fn into_substream_error(self, direction: Direction) -> SubstreamError {
+        match self {
+            Self::Yamux(error) => SubstreamError::YamuxError(error, direction),
+            Self::Mplex(error) => SubstreamError::MplexError(error, direction),
+        }
+    }
+}
+
+/// Connection multiplexed with one of the [`StreamMuxer`]s.
+pub(crate) enum MuxedConnection<S> {
+    /// Yamux connection.
+    Yamux(crate::yamux::ControlledConnection<S>),
+
+    /// mplex connection.
+    Mplex(crate::mplex::Connection<S>),
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin + Send + 'static> MuxedConnection<S> {
+    /// Multiplex `io` using the negotiated `muxer`.
+    pub(crate) fn new(
+        io: S,
+        muxer: StreamMuxer,
+        role: Role,
+        yamux_config: crate::yamux::Config,
+        mplex_config: crate::mplex::Config,
+    ) -> (MuxerControl, Self) {
+        match muxer {
+            StreamMuxer::Yamux => {
+                let connection = crate::yamux::Connection::new(io, yamux_config, role.into());
+                let (control, connection) = crate::yamux::Control::new(connection);
+
+                (MuxerControl::Yamux(control), Self::Yamux(connection))
+            }
+            StreamMuxer::Mplex => {
+                let connection = crate::mplex::Connection::new(io, mplex_config);
+
+                (
+                    MuxerControl::Mplex(connection.control()),
+                    Self::Mplex(connection),
+                )
+            }
+        }
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin + Send + 'static> Stream for MuxedConnection<S> {
+    type Item = Result<MuxedStream, MuxerError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match Pin::into_inner(self) {
+            Self::Yamux(connection) => connection.poll_next_unpin(cx).map(|result| {
+                result.map(|result| result.map(MuxedStream::Yamux).map_err(MuxerError::Yamux))
+            }),
+            Self::Mplex(connection) => connection.poll_next_unpin(cx).map(|result| {
+                result.map(|result| result.map(MuxedStream::Mplex).map_err(MuxerError::Mplex))
+            }),
+        }
+    }
+}
+
+/// Controller of [`MuxedConnection`].
+#[derive(Debug, Clone)]
+pub(crate) enum MuxerControl {
+    /// Yamux control.
+    Yamux(crate::yamux::Control),
+
+    /// mplex control.
+    Mplex(crate::mplex::Control),
+}
+
+impl MuxerControl {
+    /// Open a new stream to the remote.
+    pub(crate) async fn open_stream(&mut self) -> Result<MuxedStream, MuxerError> {
+        match self {
+            Self::Yamux(control) =>
+                control.open_stream().await.map(MuxedStream::Yamux).map_err(MuxerError::Yamux),
+            Self::Mplex(control) =>
+                control.open_stream().await.map(MuxedStream::Mplex).map_err(MuxerError::Mplex),
+        }
+    }
+}
+
+/// Substream of [`MuxedConnection`].
+#[derive(Debug)]
+pub(crate) enum MuxedStream {
+    /// Yamux substream.
+    Yamux(crate::yamux::Stream),
+
+    /// mplex substream.
+    Mplex(crate::mplex::Stream),
+}
+
+impl AsyncRead for MuxedStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        match Pin::into_inner(self) {
+            Self::Yamux(stream) => Pin::new(stream).poll_read(cx, buf),
+            Self::Mplex(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for MuxedStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match Pin::into_inner(self) {
+            Self::Yamux(stream) => Pin::new(stream).poll_write(cx, buf),
+            Self::Mplex(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match Pin::into_inner(self) {
+            Self::Yamux(stream) => Pin::new(stream).poll_flush(cx),
+            Self::Mplex(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match Pin::into_inner(self) {
+            Self::Yamux(stream) => Pin::new(stream).poll_close(cx),
+            Self::Mplex(stream) => Pin::new(stream).poll_close(cx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn protocol_names() {
+        for muxer in [StreamMuxer::Yamux, StreamMuxer::Mplex] {
+            assert_eq!(
+                StreamMuxer::from_protocol_name(muxer.protocol_name()),
+                Some(muxer)
+            );
+        }
+
+        assert_eq!(StreamMuxer::from_protocol_name("/noise"), None);
+    }
+}
@@ -0,0 +1,207 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Security protocols used to secure the connections of stream-based transports.
+
+use crate::{
+    config::Role,
+    crypto::{
+        ed25519::Keypair,
+        noise::{self, NoiseExtensions, NoiseSocket},
+    },
+    error::NegotiationError,
+    PeerId,
+};
+
+#[cfg(feature = "tls")]
+use crate::crypto::tls::{self, TlsStream};
+
+use futures::{AsyncRead, AsyncWrite};
+
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Protocol name of noise.
+const NOISE_PROTOCOL: &str = "/noise";
+
+/// Protocol name of TLS.
+#[cfg(feature = "tls")]
+const TLS_PROTOCOL: &str = "/tls/1.0.0";
+
+/// Security protocol used to secure a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SecurityProtocol {
+    /// Noise, see <https://github.com/libp2p/specs/blob/master/noise/README.md>.
+    Noise,
+
+    /// TLS 1.3, see <https://github.com/libp2p/specs/blob/master/tls/tls.md>.
+    #[cfg(feature = "tls")]
+    Tls,
+}
+
+impl SecurityProtocol {
+    /// Get the default security protocols, in order of preference.
+    ///
+    /// TLS is only included if the `tls` feature is enabled.
+    pub(crate) fn defaults() -> Vec<Self> {
+        vec![
+            Self::Noise,
+            #[cfg(feature = "tls")]
+            Self::Tls,
+        ]
+    }
+
+    /// Get the multistream-select protocol name of the security protocol.
+    pub fn protocol_name(&self) -> &'static str {
+        match self {
+            Self::Noise => NOISE_PROTOCOL,
+            #[cfg(feature = "tls")]
+            Self::Tls => TLS_PROTOCOL,
+        }
+    }
+
+    /// Get the security protocol from its multistream-select protocol name.
+    pub(crate) fn from_protocol_name(protocol: &str) -> Option<Self> {
+        match protocol {
+            NOISE_PROTOCOL => Some(Self::Noise),
+            #[cfg(feature = "tls")]
+            TLS_PROTOCOL => Some(Self::Tls),
+            _ => None,
+        }
+    }
+}
+
+/// Connection secured with one of the [`SecurityProtocol`]s.
+pub(crate) enum SecureStream<S> {
+    /// Connection secured with noise.
+    Noise(NoiseSocket<S>),
+
+    /// Connection secured with TLS.
+    #[cfg(feature = "tls")]
+    Tls(TlsStream<S>),
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> SecureStream<S> {
+    /// Secure `io` using the negotiated `protocol`.
+    ///
+    /// `stream_muxers` are advertised to the remote peer during the handshake, if the security
+    /// protocol supports it.
+    ///
+    /// Returns the secured stream, the peer ID of the remote peer and the stream muxer agreed on
+    /// during the handshake. If no stream muxer was agreed on, it must be negotiated separately.
+    pub(crate) async fn upgrade(
+        io: S,
+        protocol: SecurityProtocol,
+        keypair: &Keypair,
+        role: Role,
+        #[cfg_attr(not(feature = "tls"), allow(unused_variables))] dialed_peer: Option<PeerId>,
+        stream_muxers: &[&str],
+        max_read_ahead_factor: usize,
+        max_write_buffer_size: usize,
+    ) -> Result<(Self, PeerId, Option<String>), NegotiationError> {
+        match protocol {
+            SecurityProtocol::Noise => {
+                let extensions = NoiseExtensions::with_stream_muxers(stream_muxers);
+                let (stream, peer, remote_extensions) = noise::handshake(
+                    io,
+                    keypair,
+                    role,
+                    extensions.clone(),
+                    max_read_ahead_factor,
+                    max_write_buffer_size,
+                )
+                .await?;
+                let muxer = extensions.select_stream_muxer(&remote_extensions, role);
+
+                Ok((Self::Noise(stream), peer, muxer))
+            }
+            #[cfg(feature = "tls")]
+            SecurityProtocol::Tls => {
+                let (stream, peer) = tls::handshake(io, keypair, role, dialed_peer).await?;
+
+                Ok((Self::Tls(stream), peer, None))
+            }
+        }
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> AsyncRead for SecureStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        match Pin::into_inner(self) {
+            Self::Noise(stream) => Pin::new(stream).poll_read(cx, buf),
+            #[cfg(feature = "tls")]
+            Self::Tls(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> AsyncWrite for SecureStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match Pin::into_inner(self) {
+            Self::Noise(stream) => Pin::new(stream).poll_write(cx, buf),
+            #[cfg(feature = "tls")]
+            Self::Tls(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match Pin::into_inner(self) {
+            Self::Noise(stream) => Pin::new(stream).poll_flush(cx),
+            #[cfg(feature = "tls")]
+            Self::Tls(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match Pin::into_inner(self) {
+            Self::Noise(stream) => Pin::new(stream).poll_close(cx),
+            #[cfg(feature = "tls")]
+            Self::Tls(stream) => Pin::new(stream).poll_close(cx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn protocol_names() {
+        for protocol in SecurityProtocol::defaults() {
+            assert_eq!(
+                SecurityProtocol::from_protocol_name(protocol.protocol_name()),
+                Some(protocol)
+            );
+        }
+
+        assert_eq!(SecurityProtocol::from_protocol_name("/yamux/1.0.0"), None);
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! User-supplied transports.
+//!
+//! A user-supplied transport implements [`Transport`] the same way the built-in transports do and
+//! is installed with [`ConfigBuilder::with_custom_transport()`](
+//! crate::config::ConfigBuilder::with_custom_transport).
+//!
+//! Transports which produce byte streams can use [`TransportHandle::negotiate()`] to secure and
+//! multiplex the stream and [`TransportHandle::start_connection()`] to run the connection once it
+//! has been accepted.
+
+use crate::{
+    crypto::noise::{MAX_READ_AHEAD_FACTOR, MAX_WRITE_BUFFER_SIZE},
+    transport::{
+        SecurityProtocol, StreamMuxer, Transport, TransportEvent, TransportHandle,
+        SUBSTREAM_OPEN_TIMEOUT,
+    },
+};
+
+use multiaddr::Multiaddr;
+
+use std::time::Duration;
+
+/// User-supplied transport.
+pub trait CustomTransport: Send {
+    /// Get the name of the multiaddr protocol handled by the transport, e.g., `"memory"`.
+    ///
+    /// Addresses containing the protocol are dialed using the transport. A custom transport takes
+    /// precedence over the built-in transports handling the same protocol.
+    fn protocol(&self) -> &'static str;
+
+    /// Create the transport.
+    ///
+    /// Returns the transport and the addresses it listens on.
+    fn build(
+        self: Box<Self>,
+        context: TransportHandle,
+    ) -> crate::Result<(Box<dyn Transport<Item = TransportEvent>>, Vec<Multiaddr>)>;
+}
+
+/// Configuration used to negotiate a connection over a byte stream.
+///
+/// See [`TransportHandle::negotiate()`].
+#[derive(Debug, Clone)]
+pub struct UpgradeConfig {
+    /// Yamux configuration.
+    pub yamux_config: crate::yamux::Config,
+
+    /// mplex configuration.
+    pub mplex_config: crate::mplex::Config,
+
+    /// Security protocols, in order of preference.
+    ///
+    /// Defaults to `[SecurityProtocol::Noise, SecurityProtocol::Tls]` if the `tls` feature is
+    /// enabled and to `[SecurityProtocol::Noise]` otherwise.
+    pub security_protocols: Vec<SecurityProtocol>,
+
+    /// Stream muxers, in order of preference.
+    ///
+    /// Defaults to `[StreamMuxer::Yamux]`.
+    pub stream_muxers: Vec<StreamMuxer>,
+
+    /// Noise read-ahead frame count.
+    ///
+    /// See [`tcp::config::Config::noise_read_ahead_frame_count`](
+    /// crate::transport::tcp::config::Config::noise_read_ahead_frame_count).
+    pub noise_read_ahead_frame_count: usize,
+
+    /// Noise write buffer size.
+    ///
+    /// See [`tcp::config::Config::noise_write_buffer_size`](
+    /// crate::transport::tcp::config::Config::noise_write_buffer_size).
+    pub noise_write_buffer_size: usize,
+
+    /// Substream open timeout.
+    ///
+    /// How long should litep2p wait for a substream to be opened before considering
+    /// the substream rejected.
+    pub substream_open_timeout: Duration,
+}
+
+impl Default for UpgradeConfig {
+    fn default() -> Self {
+        Self {
+            yamux_config: Default::default(),
+            mplex_config: Default::default(),
+            security_protocols: SecurityProtocol::defaults(),
+            stream_muxers: vec![StreamMuxer::Yamux],
+            noise_read_ahead_frame_count: MAX_READ_AHEAD_FACTOR,
+            noise_write_buffer_size: MAX_WRITE_BUFFER_SIZE,
+            substream_open_timeout: SUBSTREAM_OPEN_TIMEOUT,
+        }
+    }
+}
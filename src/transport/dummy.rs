@@ -93,6 +93,18 @@ impl Transport for DummyTransport {
         Ok(())
     }
 
+    fn dial_with_role(
+        &mut self,
+        _: ConnectionId,
+        _: Vec<Multiaddr>,
+        _: crate::config::Role,
+    ) -> crate::Result<()> {
+        Ok(())
+    }
+
+    fn hole_punch(&mut self, _: Vec<Multiaddr>) -> crate::Result<()> {
+        Ok(())
+    }
     fn negotiate(&mut self, _: ConnectionId) -> crate::Result<()> {
         Ok(())
     }
@@ -0,0 +1,736 @@
+// Copyright 2024 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Dial scheduler.
+//!
+//! Orders and staggers the connection attempts of a dial following "Happy Eyeballs"
+//! ([RFC 8305](https://datatracker.ietf.org/doc/html/rfc8305)).
+//!
+//! The addresses of the peer are grouped by transport and the transports are opened in the order
+//! of their preference, with the addresses of each transport ordered so that IPv6 and IPv4
+//! addresses alternate. The attempts are started one by one, `connection_attempt_delay` apart,
+//! and the next attempt is started early if all started attempts have failed. Once one of the
+//! attempts succeeds, the others are canceled.
+
+use crate::{
+    transport::{
+        common::{
+            ip::{ip_address, is_private},
+            listener::INTERFACE_POLL_INTERVAL,
+        },
+        manager::types::SupportedTransport,
+    },
+    types::ConnectionId,
+};
+
+use futures::{future::BoxFuture, stream::FuturesUnordered, Future, Stream, StreamExt};
+use multiaddr::{Multiaddr, Protocol};
+use network_interface::{Addr, NetworkInterface, NetworkInterfaceConfig};
+use tokio::time::Instant;
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// Logging target for the file.
+const LOG_TARGET: &str = "litep2p::transport-manager::dial-scheduler";
+
+/// Delay between consecutive connection attempts, as recommended by RFC 8305.
+const CONNECTION_ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+
+/// Dial scheduler configuration.
+#[derive(Debug, Clone)]
+pub struct DialSchedulerConfig {
+    /// Delay between starting consecutive connection attempts.
+    ///
+    /// Connection attempts are started in the order of their rank: QUIC before TCP and, for
+    /// each transport, IPv6 and IPv4 addresses alternating, starting with IPv6. The next attempt
+    /// is started once the delay has elapsed or, if sooner, once all started attempts have
+    /// failed. If set to zero, all attempts are started at once.
+    ///
+    /// Defaults to 250 milliseconds.
+    pub connection_attempt_delay: Duration,
+
+    /// Skip private addresses of networks the local node isn't connected to.
+    ///
+    /// Private addresses, such as `192.168.0.0/16` or `fc00::/7`, are only reachable from
+    /// within their network. If enabled, they're dialed only if one of the network interfaces
+    /// of the local node is on the same network. Loopback and public addresses are always
+    /// dialed.
+    ///
+    /// Defaults to `true`.
+    pub skip_non_local_private_addresses: bool,
+}
+
+impl Default for DialSchedulerConfig {
+    fn default() -> Self {
+        Self {
+            connection_attempt_delay: CONNECTION_ATTEMPT_DELAY,
+            skip_non_local_private_addresses: true,
+        }
+    }
+}
+
+/// Transports of a dial which haven't been opened yet.
+struct Schedule {
+    /// Transports waiting to be opened and their addresses, in the order they're opened.
+    pending: VecDeque<(SupportedTransport, Vec<Multiaddr>)>,
+
+    /// Opened transports which haven't failed yet.
+    opened: HashSet<SupportedTransport>,
+
+    /// When the next transport is opened, unless the opened transports fail before that.
+    next_open: Instant,
+}
+
+/// Dial scheduler.
+///
+/// Yields the transports which must be opened next, see [`DialScheduler::schedule()`].
+pub(crate) struct DialScheduler {
+    /// Configuration.
+    config: DialSchedulerConfig,
+
+    /// Pending schedules.
+    schedules: HashMap<ConnectionId, Schedule>,
+
+    /// Timers for opening the next transport of a schedule.
+    timers: FuturesUnordered<BoxFuture<'static, ConnectionId>>,
+
+    /// Network interfaces of the local node and when they were fetched.
+    interfaces: Option<(Instant, Vec<NetworkInterface>)>,
+}
+
+impl DialScheduler {
+    /// Create new [`DialScheduler`].
+    pub(crate) fn new(config: DialSchedulerConfig) -> Self {
+        Self {
+            config,
+            schedules: HashMap::new(),
+            timers: FuturesUnordered::new(),
+            interfaces: None,
+        }
+    }
+
+    /// Get the delay between consecutive connection attempts.
+    pub(crate) fn connection_attempt_delay(&self) -> Duration {
+        self.config.connection_attempt_delay
+    }
+
+    /// Remove the private addresses which aren't reachable from the local node, if enabled.
+    ///
+    /// The network interfaces of the local node are fetched at most once per
+    /// [`INTERFACE_POLL_INTERVAL`].
+    pub(crate) fn filter_addresses(&mut self, addresses: Vec<Multiaddr>) -> Vec<Multiaddr> {
+        if !self.config.skip_non_local_private_addresses
+            || !addresses.iter().any(|address| ip_address(address).is_some_and(is_private))
+        {
+            return addresses;
+        }
+
+        let fresh = self
+            .interfaces
+            .as_ref()
+            .is_some_and(|(fetched, _)| fetched.elapsed() < INTERFACE_POLL_INTERVAL);
+
+        if !fresh {
+            match NetworkInterface::show() {
+                Ok(interfaces) => self.interfaces = Some((Instant::now(), interfaces)),
+                Err(error) => {
+                    tracing::debug!(
+                        target: LOG_TARGET,
+                        ?error,
+                        "failed to get network interfaces, dial all addresses",
+                    );
+                    return addresses;
+                }
+            }
+        }
+
+        let (_, interfaces) = self.interfaces.as_ref().expect("interfaces to exist");
+        filter_private_addresses(addresses, interfaces)
+    }
+
+    /// Schedule opening `transports` for the dial `connection_id`.
+    ///
+    /// The transports are ranked and the first one is returned, to be opened immediately. The
+    /// rest are yielded by the [`DialScheduler`] when it's their turn to be opened.
+    pub(crate) fn schedule(
+        &mut self,
+        connection_id: ConnectionId,
+        transports: impl IntoIterator<Item = (SupportedTransport, Vec<Multiaddr>)>,
+    ) -> Option<(SupportedTransport, Vec<Multiaddr>)> {
+        let mut schedule = Schedule {
+            pending: rank(transports).into(),
+            opened: HashSet::new(),
+            next_open: Instant::now(),
+        };
+        let next = self.open_next(connection_id, &mut schedule);
+
+        if !schedule.pending.is_empty() {
+            self.schedules.insert(connection_id, schedule);
+        }
+
+        next
+    }
+
+    /// Handle open failure of `transport` for the dial `connection_id`.
+    ///
+    /// Returns the transport which must be opened next, if all opened transports have failed.
+    pub(crate) fn on_open_failure(
+        &mut self,
+        connection_id: ConnectionId,
+        transport: SupportedTransport,
+    ) -> Option<(SupportedTransport, Vec<Multiaddr>)> {
+        let mut schedule = self.schedules.remove(&connection_id)?;
+
+        schedule.opened.remove(&transport);
+        schedule.pending.retain(|(pending, _)| pending != &transport);
+
+        let next = match schedule.opened.is_empty() {
+            true => self.open_next(connection_id, &mut schedule),
+            false => None,
+        };
+
+        if !schedule.pending.is_empty() {
+            self.schedules.insert(connection_id, schedule);
+        }
+
+        next
+    }
+
+    /// Remove the schedule of the dial `connection_id`.
+    pub(crate) fn remove(&mut self, connection_id: ConnectionId) {
+        self.schedules.remove(&connection_id);
+    }
+
+    /// Take the next transport of `schedule` and set the time for opening the one after it.
+    fn open_next(
+        &mut self,
+        connection_id: ConnectionId,
+        schedule: &mut Schedule,
+    ) -> Option<(SupportedTransport, Vec<Multiaddr>)> {
+        let (transport, addresses) = schedule.pending.pop_front()?;
+
+        // transports stagger their own attempts so the next transport is opened once all
+        // attempts of this transport have been started
+        let delay = self.config.connection_attempt_delay * addresses.len() as u32;
+
+        let next_open = Instant::now() + delay;
+
+        schedule.opened.insert(transport);
+        schedule.next_open = next_open;
+
+        if !schedule.pending.is_empty() {
+            self.timers.push(Box::pin(async move {
+                tokio::time::sleep_until(next_open).await;
+                connection_id
+            }));
+        }
+
+        tracing::trace!(
+            target: LOG_TARGET,
+            ?connection_id,
+            ?transport,
+            ?addresses,
+            "open transport",
+        );
+
+        Some((transport, addresses))
+    }
+}
+
+impl Stream for DialScheduler {
+    type Item = (ConnectionId, SupportedTransport, Vec<Multiaddr>);
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        while let Poll::Ready(Some(connection_id)) = self.timers.poll_next_unpin(cx) {
+            let Some(mut schedule) = self.schedules.remove(&connection_id) else {
+                continue;
+            };
+
+            // the timer is stale if the next transport was opened early
+            if schedule.next_open > Instant::now() {
+                self.schedules.insert(connection_id, schedule);
+                continue;
+            }
+
+            let next = self.open_next(connection_id, &mut schedule);
+
+            if !schedule.pending.is_empty() {
+                self.schedules.insert(connection_id, schedule);
+            }
+
+            if let Some((transport, addresses)) = next {
+                return Poll::Ready(Some((connection_id, transport, addresses)));
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+/// Drive `attempts` to completion, starting them `delay` apart.
+///
+/// The attempts are started in order and the next attempt is started once `delay` has elapsed
+/// since the previous one was started or, if sooner, once all started attempts have failed.
+/// Returns the result of the first successful attempt, dropping the others, or the errors of all
+/// attempts if none of them succeeded.
+pub(crate) async fn staggered<F, T, E>(
+    attempts: impl IntoIterator<Item = F>,
+    delay: Duration,
+) -> Result<T, Vec<E>>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    let mut attempts = attempts.into_iter().peekable();
+    let mut running = FuturesUnordered::new();
+    let mut errors = Vec::new();
+    let timer = tokio::time::sleep(delay);
+    tokio::pin!(timer);
+
+    loop {
+        if running.is_empty() {
+            let Some(attempt) = attempts.next() else {
+                return Err(errors);
+            };
+
+            running.push(attempt);
+            timer.as_mut().reset(Instant::now() + delay);
+        }
+
+        tokio::select! {
+            Some(result) = running.next() => match result {
+                Ok(value) => return Ok(value),
+                Err(error) => errors.push(error),
+            },
+            _ = &mut timer, if attempts.peek().is_some() => {
+                running.push(attempts.next().expect("attempt to exist"));
+                timer.as_mut().reset(Instant::now() + delay);
+            }
+        }
+    }
+}
+
+/// Get the rank of `transport`, transports with lower rank are opened first.
+fn transport_rank(transport: &SupportedTransport) -> usize {
+    match transport {
+        SupportedTransport::Memory => 0,
+        #[cfg(unix)]
+        SupportedTransport::Unix => 0,
+        #[cfg(feature = "quic")]
+        SupportedTransport::Quic => 1,
+        #[cfg(feature = "webrtc")]
+        SupportedTransport::WebRtc => 2,
+        SupportedTransport::Tcp => 3,
+        #[cfg(feature = "websocket")]
+        SupportedTransport::WebSocket => 4,
+        SupportedTransport::Custom(_) => 5,
+        SupportedTransport::Relay => 6,
+    }
+}
+
+/// Rank `transports` and their addresses.
+///
+/// Transports are ordered by [`transport_rank()`] and the addresses of each transport are
+/// reordered so that IPv6 and IPv4 addresses alternate, starting with IPv6. Addresses of an
+/// unknown address family are tried last. Otherwise the order of the addresses is preserved.
+fn rank(
+    transports: impl IntoIterator<Item = (SupportedTransport, Vec<Multiaddr>)>,
+) -> Vec<(SupportedTransport, Vec<Multiaddr>)> {
+    let mut transports = transports
+        .into_iter()
+        .filter(|(_, addresses)| !addresses.is_empty())
+        .map(|(transport, addresses)| (transport, interleave(addresses)))
+        .collect::<Vec<_>>();
+    transports.sort_by_key(|(transport, _)| transport_rank(transport));
+
+    transports
+}
+
+/// Interleave IPv6 and IPv4 addresses, starting with IPv6.
+fn interleave(addresses: Vec<Multiaddr>) -> Vec<Multiaddr> {
+    let mut ipv6 = VecDeque::new();
+    let mut ipv4 = VecDeque::new();
+    let mut other = Vec::new();
+
+    for address in addresses {
+        match address.iter().next() {
+            Some(Protocol::Ip6(_)) | Some(Protocol::Dns6(_)) => ipv6.push_back(address),
+            Some(Protocol::Ip4(_)) | Some(Protocol::Dns4(_)) => ipv4.push_back(address),
+            _ => other.push(address),
+        }
+    }
+
+    let mut addresses = Vec::with_capacity(ipv6.len() + ipv4.len() + other.len());
+
+    while !ipv6.is_empty() || !ipv4.is_empty() {
+        addresses.extend(ipv6.pop_front());
+        addresses.extend(ipv4.pop_front());
+    }
+    addresses.extend(other);
+
+    addresses
+}
+
+/// Remove the private addresses which don't belong to the network of any of `interfaces`.
+fn filter_private_addresses(
+    addresses: Vec<Multiaddr>,
+    interfaces: &[NetworkInterface],
+) -> Vec<Multiaddr> {
+    let is_local = |address: IpAddr| {
+        interfaces.iter().flat_map(|interface| interface.addr.iter()).any(|interface| {
+            match (interface, address) {
+                (Addr::V4(interface), IpAddr::V4(address)) => interface
+                    .netmask
+                    .is_some_and(|netmask| same_network_v4(interface.ip, address, netmask)),
+                (Addr::V6(interface), IpAddr::V6(address)) => interface
+                    .netmask
+                    .is_some_and(|netmask| same_network_v6(interface.ip, address, netmask)),
+                _ => false,
+            }
+        })
+    };
+
+    addresses
+        .into_iter()
+        .filter(|address| match ip_address(address) {
+            Some(ip) if is_private(ip) && !is_local(ip) => {
+                tracing::trace!(
+                    target: LOG_TARGET,
+                    ?address,
+                    "ignore private address of a non-local network",
+                );
+                false
+            }
+            _ => true,
+        })
+        .collect()
+}
+
+/// Check if `lhs` and `rhs` are in the same IPv4 network.
+fn same_network_v4(lhs: Ipv4Addr, rhs: Ipv4Addr, netmask: Ipv4Addr) -> bool {
+    let netmask = u32::from(netmask);
+
+    u32::from(lhs) & netmask == u32::from(rhs) & netmask
+}
+
+/// Check if `lhs` and `rhs` are in the same IPv6 network.
+fn same_network_v6(lhs: Ipv6Addr, rhs: Ipv6Addr, netmask: Ipv6Addr) -> bool {
+    let netmask = u128::from(netmask);
+
+    u128::from(lhs) & netmask == u128::from(rhs) & netmask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(
+        delay: Duration,
+        result: Result<usize, usize>,
+    ) -> BoxFuture<'static, Result<usize, usize>> {
+        Box::pin(async move {
+            tokio::time::sleep(delay).await;
+            result
+        })
+    }
+
+    #[test]
+    fn transports_are_ranked() {
+        let address = |address: &str| vec![address.parse::<Multiaddr>().unwrap()];
+
+        let ranked = rank(vec![
+            (
+                SupportedTransport::Relay,
+                address("/ip4/1.1.1.1/tcp/8888/p2p-circuit"),
+            ),
+            (SupportedTransport::Tcp, address("/ip4/1.1.1.1/tcp/8888")),
+            (
+                SupportedTransport::Custom("custom"),
+                address("/ip4/1.1.1.1/udp/8888"),
+            ),
+            (SupportedTransport::Memory, address("/memory/1")),
+            (SupportedTransport::Tcp, Vec::new()),
+        ]);
+
+        assert_eq!(
+            ranked.iter().map(|(transport, _)| *transport).collect::<Vec<_>>(),
+            vec![
+                SupportedTransport::Memory,
+                SupportedTransport::Tcp,
+                SupportedTransport::Custom("custom"),
+                SupportedTransport::Relay,
+            ],
+        );
+    }
+
+    #[test]
+    fn ipv6_and_ipv4_addresses_are_interleaved() {
+        let addresses = [
+            "/ip4/1.1.1.1/tcp/8888",
+            "/ip4/1.1.1.2/tcp/8888",
+            "/dns/example.com/tcp/8888",
+            "/ip6/2001:db8::1/tcp/8888",
+            "/dns6/example.com/tcp/8888",
+            "/ip4/1.1.1.3/tcp/8888",
+        ]
+        .into_iter()
+        .map(|address| address.parse::<Multiaddr>().unwrap())
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            interleave(addresses.clone()),
+            vec![
+                addresses[3].clone(),
+                addresses[0].clone(),
+                addresses[4].clone(),
+                addresses[1].clone(),
+                addresses[5].clone(),
+                addresses[2].clone(),
+            ],
+        );
+    }
+
+    #[test]
+    fn private_addresses_of_non_local_networks_are_removed() {
+        let interfaces = vec![
+            NetworkInterface::new_afinet(
+                "eth0",
+                Ipv4Addr::new(192, 168, 1, 10),
+                Some(Ipv4Addr::new(255, 255, 255, 0)),
+                None,
+                1,
+            ),
+            NetworkInterface::new_afinet6(
+                "eth0",
+                "fd00::10".parse().unwrap(),
+                Some("ffff:ffff:ffff:ffff::".parse().unwrap()),
+                None,
+                1,
+            ),
+        ];
+        let addresses = [
+            ("/ip4/192.168.1.5/tcp/8888", true),
+            ("/ip4/192.168.2.5/tcp/8888", false),
+            ("/ip4/10.0.0.5/tcp/8888", false),
+            ("/ip4/169.254.0.5/tcp/8888", false),
+            ("/ip4/100.64.0.5/tcp/8888", false),
+            ("/ip4/127.0.0.1/tcp/8888", true),
+            ("/ip4/1.1.1.1/tcp/8888", true),
+            ("/ip6/fd00::5/tcp/8888", true),
+            ("/ip6/fd01::5/tcp/8888", false),
+            ("/ip6/fe80::5/tcp/8888", false),
+            ("/ip6/::1/tcp/8888", true),
+            ("/ip6/2001:db8::1/tcp/8888", true),
+            ("/dns4/example.com/tcp/8888", true),
+        ]
+        .into_iter()
+        .map(|(address, dialed)| (address.parse::<Multiaddr>().unwrap(), dialed))
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            filter_private_addresses(
+                addresses.iter().map(|(address, _)| address.clone()).collect(),
+                &interfaces,
+            ),
+            addresses
+                .into_iter()
+                .filter_map(|(address, dialed)| dialed.then_some(address))
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn non_local_private_addresses_dialed_if_not_skipped() {
+        let mut scheduler = DialScheduler::new(DialSchedulerConfig {
+            skip_non_local_private_addresses: false,
+            ..Default::default()
+        });
+        let addresses = vec![
+            "/ip4/10.255.255.5/tcp/8888".parse::<Multiaddr>().unwrap(),
+            "/ip6/fd12:3456::5/tcp/8888".parse::<Multiaddr>().unwrap(),
+        ];
+
+        assert_eq!(scheduler.filter_addresses(addresses.clone()), addresses);
+        assert!(scheduler.interfaces.is_none());
+    }
+
+    #[tokio::test]
+    async fn network_interfaces_are_cached() {
+        let mut scheduler = DialScheduler::new(DialSchedulerConfig::default());
+        let addresses = vec!["/ip4/10.255.255.5/tcp/8888".parse::<Multiaddr>().unwrap()];
+
+        // the interfaces of the local node are replaced so the address is always skipped
+        scheduler.interfaces = Some((Instant::now(), Vec::new()));
+        assert!(scheduler.filter_addresses(addresses.clone()).is_empty());
+
+        // stale interfaces are fetched again
+        scheduler.interfaces = Some((Instant::now() - INTERFACE_POLL_INTERVAL, Vec::new()));
+        let _ = scheduler.filter_addresses(addresses);
+        let (fetched, _) = scheduler.interfaces.as_ref().expect("interfaces to exist");
+        assert!(fetched.elapsed() < INTERFACE_POLL_INTERVAL);
+    }
+
+    #[tokio::test]
+    async fn transports_are_opened_after_delay() {
+        let mut scheduler = DialScheduler::new(DialSchedulerConfig {
+            connection_attempt_delay: Duration::from_millis(100),
+            ..Default::default()
+        });
+        let connection_id = ConnectionId::from(0usize);
+        let tcp = vec![
+            "/ip4/1.1.1.1/tcp/8888".parse::<Multiaddr>().unwrap(),
+            "/ip4/1.1.1.2/tcp/8888".parse::<Multiaddr>().unwrap(),
+        ];
+        let relay = vec!["/ip4/1.1.1.1/tcp/8888/p2p-circuit".parse::<Multiaddr>().unwrap()];
+
+        let started = Instant::now();
+        assert_eq!(
+            scheduler.schedule(
+                connection_id,
+                vec![
+                    (SupportedTransport::Relay, relay.clone()),
+                    (SupportedTransport::Tcp, tcp.clone()),
+                ],
+            ),
+            Some((SupportedTransport::Tcp, tcp)),
+        );
+
+        // the relay is opened once both tcp attempts have been started
+        assert_eq!(
+            scheduler.next().await,
+            Some((connection_id, SupportedTransport::Relay, relay)),
+        );
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        assert!(scheduler.schedules.is_empty());
+    }
+
+    #[tokio::test]
+    async fn next_transport_opened_when_opened_transports_fail() {
+        let mut scheduler = DialScheduler::new(DialSchedulerConfig {
+            connection_attempt_delay: Duration::from_secs(10),
+            ..Default::default()
+        });
+        let connection_id = ConnectionId::from(0usize);
+        let memory = vec!["/memory/1".parse::<Multiaddr>().unwrap()];
+        let tcp = vec!["/ip4/1.1.1.1/tcp/8888".parse::<Multiaddr>().unwrap()];
+        let relay = vec!["/ip4/1.1.1.1/tcp/8888/p2p-circuit".parse::<Multiaddr>().unwrap()];
+
+        assert_eq!(
+            scheduler.schedule(
+                connection_id,
+                vec![
+                    (SupportedTransport::Memory, memory.clone()),
+                    (SupportedTransport::Tcp, tcp.clone()),
+                    (SupportedTransport::Relay, relay.clone()),
+                ],
+            ),
+            Some((SupportedTransport::Memory, memory)),
+        );
+        assert_eq!(
+            scheduler.on_open_failure(connection_id, SupportedTransport::Memory),
+            Some((SupportedTransport::Tcp, tcp)),
+        );
+
+        // the timer of the memory transport is stale
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), scheduler.next())
+                .await
+                .is_err()
+        );
+
+        // relay fails before it was opened
+        assert_eq!(
+            scheduler.on_open_failure(connection_id, SupportedTransport::Relay),
+            None
+        );
+        assert!(scheduler.schedules.is_empty());
+        assert_eq!(
+            scheduler.on_open_failure(connection_id, SupportedTransport::Tcp),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn staggered_returns_first_success() {
+        let started = Instant::now();
+        let result = staggered(
+            vec![
+                attempt(Duration::from_secs(5), Ok(0)),
+                attempt(Duration::ZERO, Ok(1)),
+            ],
+            Duration::from_millis(100),
+        )
+        .await;
+
+        assert_eq!(result, Ok(1));
+        assert!(started.elapsed() >= Duration::from_millis(100));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn staggered_starts_next_attempt_on_failure() {
+        let started = Instant::now();
+        let result = staggered(
+            vec![
+                attempt(Duration::ZERO, Err(0)),
+                attempt(Duration::from_millis(50), Err(1)),
+                attempt(Duration::ZERO, Ok(2)),
+            ],
+            Duration::from_secs(5),
+        )
+        .await;
+
+        assert_eq!(result, Ok(2));
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let result = staggered(
+            vec![
+                attempt(Duration::ZERO, Err(0)),
+                attempt(Duration::ZERO, Err(1)),
+            ],
+            Duration::from_secs(5),
+        )
+        .await;
+
+        assert_eq!(result, Err(vec![0, 1]));
+    }
+
+    #[tokio::test]
+    async fn staggered_starts_all_attempts_without_delay() {
+        let started = Instant::now();
+        let result = staggered(
+            vec![
+                attempt(Duration::from_millis(200), Ok(0)),
+                attempt(Duration::from_millis(200), Ok(1)),
+                attempt(Duration::from_millis(100), Ok(2)),
+            ],
+            Duration::ZERO,
+        )
+        .await;
+
+        assert_eq!(result, Ok(2));
+        assert!(started.elapsed() < Duration::from_millis(200));
+    }
+}
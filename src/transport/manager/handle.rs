@@ -20,30 +20,41 @@
 
 use crate::{
     addresses::PublicAddresses,
-    crypto::ed25519::Keypair,
-    error::ImmediateDialError,
+    config::Role,
+    crypto::{ed25519::Keypair, pnet::PreSharedKey},
+    error::{ImmediateDialError, NegotiationError},
     executor::Executor,
     protocol::ProtocolSet,
-    transport::manager::{
-        address::AddressRecord,
-        peer_state::StateDialResult,
-        types::{PeerContext, SupportedTransport},
-        ProtocolContext, TransportManagerEvent, LOG_TARGET,
+    transport::{
+        common::dns::DnsResolver,
+        custom::UpgradeConfig,
+        manager::{
+            address::AddressRecord,
+            peer_state::StateDialResult,
+            types::{PeerContext, SupportedTransport},
+            ProtocolContext, TransportManagerEvent, LOG_TARGET,
+        },
+        tcp::connection::{NegotiatedConnection, TcpConnection},
     },
-    types::{protocol::ProtocolName, ConnectionId},
+    types::{protocol::ProtocolName, ConnectionId, ListenerId},
     BandwidthSink, PeerId,
 };
 
 use multiaddr::{Multiaddr, Protocol};
 use parking_lot::RwLock;
-use tokio::sync::mpsc::{error::TrySendError, Sender};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    sync::mpsc::{error::TrySendError, Sender},
+};
 
 use std::{
     collections::{HashMap, HashSet},
+    future::Future,
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc,
     },
+    time::Duration,
 };
 
 /// Inner commands sent from [`TransportManagerHandle`] to
@@ -59,6 +70,27 @@ pub enum InnerTransportManagerCommand {
     DialAddress {
         /// Remote address.
         address: Multiaddr,
+
+        /// Open a secondary connection if the peer is already connected.
+        secondary: bool,
+    },
+
+    /// Dial addresses of a peer at once as one side of a simultaneous open.
+    SimultaneousOpen {
+        /// Remote peer ID.
+        peer: PeerId,
+
+        /// Addresses of the remote peer.
+        addresses: Vec<Multiaddr>,
+
+        /// Role of the local node in the connection.
+        role: Role,
+    },
+
+    /// Punch holes towards addresses of a peer.
+    HolePunch {
+        /// Addresses of the remote peer.
+        addresses: Vec<Multiaddr>,
     },
 }
 
@@ -121,9 +153,24 @@ impl TransportManagerHandle {
 
     /// Check if `address` is supported by one of the enabled transports.
     pub fn supported_transport(&self, address: &Multiaddr) -> bool {
+        if address.iter().any(|protocol| std::matches!(protocol, Protocol::P2pCircuit)) {
+            return self.supported_transport.contains(&SupportedTransport::Relay);
+        }
+
+        if SupportedTransport::custom(self.supported_transport.iter(), address).is_some() {
+            return std::matches!(address.iter().last(), Some(Protocol::P2p(_)));
+        }
+
         let mut iter = address.iter();
 
         match iter.next() {
+            Some(Protocol::Memory(_)) =>
+                return std::matches!(iter.next(), Some(Protocol::P2p(_)))
+                    && self.supported_transport.contains(&SupportedTransport::Memory),
+            #[cfg(unix)]
+            Some(Protocol::Unix(_)) =>
+                return std::matches!(iter.next(), Some(Protocol::P2p(_)))
+                    && self.supported_transport.contains(&SupportedTransport::Unix),
             Some(Protocol::Ip4(address)) =>
                 if address.is_unspecified() {
                     return false;
@@ -147,14 +194,22 @@ impl TransportManagerHandle {
                 #[cfg(feature = "websocket")]
                 Some(Protocol::Wss(_)) =>
                     self.supported_transport.contains(&SupportedTransport::WebSocket),
+                #[cfg(feature = "websocket")]
+                Some(Protocol::Tls) => match iter.next() {
+                    Some(Protocol::Ws(_)) =>
+                        self.supported_transport.contains(&SupportedTransport::WebSocket),
+                    _ => false,
+                },
                 _ => false,
             },
-            #[cfg(feature = "quic")]
-            Some(Protocol::Udp(_)) => match (
-                iter.next(),
-                self.supported_transport.contains(&SupportedTransport::Quic),
-            ) {
-                (Some(Protocol::QuicV1), true) => true,
+            #[cfg(any(feature = "quic", feature = "webrtc"))]
+            Some(Protocol::Udp(_)) => match iter.next() {
+                #[cfg(feature = "quic")]
+                Some(Protocol::QuicV1) =>
+                    self.supported_transport.contains(&SupportedTransport::Quic),
+                #[cfg(feature = "webrtc")]
+                Some(Protocol::WebRTC) =>
+                    self.supported_transport.contains(&SupportedTransport::WebRtc),
                 _ => false,
             },
             _ => false,
@@ -281,7 +336,102 @@ impl TransportManagerHandle {
         }
 
         self.cmd_tx
-            .try_send(InnerTransportManagerCommand::DialAddress { address })
+            .try_send(InnerTransportManagerCommand::DialAddress {
+                address,
+                secondary: false,
+            })
+            .map_err(|error| match error {
+                TrySendError::Full(_) => ImmediateDialError::ChannelClogged,
+                TrySendError::Closed(_) => ImmediateDialError::TaskClosed,
+            })
+    }
+
+    /// Dial peer using `Multiaddr`, opening a secondary connection if the peer is already
+    /// connected.
+    ///
+    /// Returns an error if address it not valid or the peer already has a secondary connection
+    /// (or a pending secondary dial).
+    pub fn dial_secondary_address(&self, address: Multiaddr) -> Result<(), ImmediateDialError> {
+        let Some(peer) = PeerId::try_from_multiaddr(&address) else {
+            return Err(ImmediateDialError::PeerIdMissing);
+        };
+
+        if peer == self.local_peer_id {
+            return Err(ImmediateDialError::TriedToDialSelf);
+        }
+
+        if let Some(PeerContext { state, .. }) = self.peers.read().get(&peer) {
+            match state.can_dial_secondary() {
+                StateDialResult::AlreadyConnected =>
+                    return Err(ImmediateDialError::AlreadyConnected),
+                StateDialResult::DialingInProgress => return Ok(()),
+                StateDialResult::Ok => {}
+            }
+        }
+
+        self.cmd_tx
+            .try_send(InnerTransportManagerCommand::DialAddress {
+                address,
+                secondary: true,
+            })
+            .map_err(|error| match error {
+                TrySendError::Full(_) => ImmediateDialError::ChannelClogged,
+                TrySendError::Closed(_) => ImmediateDialError::TaskClosed,
+            })
+    }
+
+    /// Dial `addresses` of a peer at once as one side of a simultaneous open, opening a secondary
+    /// connection if the peer is already connected.
+    ///
+    /// All addresses must belong to the same peer. The connection is negotiated in `role`, see
+    /// [`Transport::dial_with_role()`](crate::transport::Transport::dial_with_role).
+    pub fn simultaneous_open(
+        &self,
+        addresses: Vec<Multiaddr>,
+        role: Role,
+    ) -> Result<(), ImmediateDialError> {
+        let Some(peer) = addresses.first().and_then(PeerId::try_from_multiaddr) else {
+            return Err(ImmediateDialError::PeerIdMissing);
+        };
+
+        if addresses
+            .iter()
+            .any(|address| PeerId::try_from_multiaddr(address) != Some(peer))
+        {
+            return Err(ImmediateDialError::PeerIdMissing);
+        }
+
+        if peer == self.local_peer_id {
+            return Err(ImmediateDialError::TriedToDialSelf);
+        }
+
+        if let Some(PeerContext { state, .. }) = self.peers.read().get(&peer) {
+            match state.can_dial_secondary() {
+                StateDialResult::AlreadyConnected =>
+                    return Err(ImmediateDialError::AlreadyConnected),
+                StateDialResult::DialingInProgress => return Ok(()),
+                StateDialResult::Ok => {}
+            }
+        }
+
+        self.cmd_tx
+            .try_send(InnerTransportManagerCommand::SimultaneousOpen {
+                peer,
+                addresses,
+                role,
+            })
+            .map_err(|error| match error {
+                TrySendError::Full(_) => ImmediateDialError::ChannelClogged,
+                TrySendError::Closed(_) => ImmediateDialError::TaskClosed,
+            })
+    }
+
+    /// Punch holes towards `addresses` of a peer for the peer to dial the local node through.
+    ///
+    /// See [`Transport::hole_punch()`](crate::transport::Transport::hole_punch).
+    pub fn hole_punch(&self, addresses: Vec<Multiaddr>) -> Result<(), ImmediateDialError> {
+        self.cmd_tx
+            .try_send(InnerTransportManagerCommand::HolePunch { addresses })
             .map_err(|error| match error {
                 TrySendError::Full(_) => ImmediateDialError::ChannelClogged,
                 TrySendError::Closed(_) => ImmediateDialError::TaskClosed,
@@ -289,18 +439,27 @@ impl TransportManagerHandle {
     }
 }
 
+/// Handle given to a transport when it's created.
+///
+/// The handle gives the transport access to the local keypair and allows it to negotiate and
+/// start connections. See [`CustomTransport`](crate::transport::custom::CustomTransport) for
+/// how a user-supplied transport uses the handle.
 pub struct TransportHandle {
-    pub keypair: Keypair,
-    pub tx: Sender<TransportManagerEvent>,
-    pub protocols: HashMap<ProtocolName, ProtocolContext>,
-    pub next_connection_id: Arc<AtomicUsize>,
-    pub next_substream_id: Arc<AtomicUsize>,
-    pub bandwidth_sink: BandwidthSink,
-    pub executor: Arc<dyn Executor>,
+    pub(crate) keypair: Keypair,
+    pub(crate) pre_shared_key: Option<PreSharedKey>,
+    pub(crate) tx: Sender<TransportManagerEvent>,
+    pub(crate) protocols: HashMap<ProtocolName, ProtocolContext>,
+    pub(crate) next_connection_id: Arc<AtomicUsize>,
+    pub(crate) next_listener_id: Arc<AtomicUsize>,
+    pub(crate) next_substream_id: Arc<AtomicUsize>,
+    pub(crate) bandwidth_sink: BandwidthSink,
+    pub(crate) executor: Arc<dyn Executor>,
+    pub(crate) resolver: DnsResolver,
+    pub(crate) connection_attempt_delay: Duration,
 }
 
 impl TransportHandle {
-    pub fn protocol_set(&self, connection_id: ConnectionId) -> ProtocolSet {
+    pub(crate) fn protocol_set(&self, connection_id: ConnectionId) -> ProtocolSet {
         ProtocolSet::new(
             connection_id,
             self.tx.clone(),
@@ -315,6 +474,92 @@ impl TransportHandle {
 
         ConnectionId::from(connection_id)
     }
+
+    /// Get next listener ID.
+    ///
+    /// Transports use this to assign IDs to the listeners started from their configuration.
+    pub fn next_listener_id(&self) -> ListenerId {
+        let listener_id = self.next_listener_id.fetch_add(1usize, Ordering::Relaxed);
+
+        ListenerId::from(listener_id)
+    }
+
+    /// Get the keypair of the local node.
+    pub fn keypair(&self) -> &Keypair {
+        &self.keypair
+    }
+
+    /// Get the executor used by litep2p to run background tasks.
+    pub fn executor(&self) -> &Arc<dyn Executor> {
+        &self.executor
+    }
+
+    /// Negotiate a connection over `stream`.
+    ///
+    /// Runs the private network handshake, if enabled, and negotiates the security protocol and
+    /// the stream muxer over `stream` which was opened to or from `address`. If the stream was
+    /// opened by dialing a specific peer, `dialed_peer` must be set so the identity of the remote
+    /// peer can be verified.
+    ///
+    /// The returned future doesn't time out on its own.
+    pub fn negotiate<S>(
+        &self,
+        stream: S,
+        connection_id: ConnectionId,
+        role: Role,
+        dialed_peer: Option<PeerId>,
+        address: Multiaddr,
+        config: &UpgradeConfig,
+    ) -> impl Future<Output = Result<NegotiatedConnection<S>, NegotiationError>> + Send + 'static
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        TcpConnection::negotiate_stream(
+            stream,
+            dialed_peer,
+            connection_id,
+            self.keypair.clone(),
+            self.pre_shared_key.clone(),
+            role,
+            address,
+            config.yamux_config.clone(),
+            config.mplex_config.clone(),
+            config.security_protocols.clone(),
+            config.stream_muxers.clone(),
+            config.noise_read_ahead_frame_count,
+            config.noise_write_buffer_size,
+            config.substream_open_timeout,
+        )
+    }
+
+    /// Start the event loop of a negotiated connection.
+    ///
+    /// Must only be called after [`TransportManager`](super::TransportManager) has accepted the
+    /// connection, i.e., from [`Transport::accept()`](crate::transport::Transport::accept).
+    pub fn start_connection<S>(&self, connection: NegotiatedConnection<S>)
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let connection_id = connection.connection_id();
+        let protocol_set = self.protocol_set(connection_id);
+        let bandwidth_sink = self.bandwidth_sink.clone();
+        let next_substream_id = self.next_substream_id.clone();
+
+        self.executor.run(Box::pin(async move {
+            if let Err(error) =
+                TcpConnection::new(connection, protocol_set, bandwidth_sink, next_substream_id)
+                    .start()
+                    .await
+            {
+                tracing::debug!(
+                    target: LOG_TARGET,
+                    ?connection_id,
+                    ?error,
+                    "connection exited with error",
+                );
+            }
+        }));
+    }
 }
 
 #[cfg(test)]
@@ -349,6 +594,27 @@ mod tests {
         )
     }
 
+    #[tokio::test]
+    async fn custom_transport_supported() {
+        let (mut handle, _rx) = make_transport_manager_handle();
+        handle.supported_transport.insert(SupportedTransport::Custom("unix"));
+
+        let address =
+            "/unix/tmp%2Fnode.sock/p2p/12D3KooWKrUnV42yDR7G6DewmgHtFaVCJWLjQRi2G9t5eJD3BvTy"
+                .parse()
+                .unwrap();
+        assert!(handle.supported_transport(&address));
+
+        let address = "/unix/tmp%2Fnode.sock".parse().unwrap();
+        assert!(!handle.supported_transport(&address));
+
+        let address =
+            "/ip4/127.0.0.1/tcp/8888/p2p/12D3KooWKrUnV42yDR7G6DewmgHtFaVCJWLjQRi2G9t5eJD3BvTy"
+                .parse()
+                .unwrap();
+        assert!(!handle.supported_transport(&address));
+    }
+
     #[tokio::test]
     async fn tcp_supported() {
         let (mut handle, _rx) = make_transport_manager_handle();
@@ -374,6 +640,45 @@ mod tests {
         assert!(handle.supported_transport(&address));
     }
 
+    #[cfg(feature = "websocket")]
+    #[tokio::test]
+    async fn secure_websocket_supported() {
+        let (mut handle, _rx) = make_transport_manager_handle();
+        handle.supported_transport.insert(SupportedTransport::WebSocket);
+
+        let address =
+            "/dns4/google.com/tcp/443/tls/ws/p2p/12D3KooWKrUnV42yDR7G6DewmgHtFaVCJWLjQRi2G9t5eJD3BvTy"
+                .parse()
+                .unwrap();
+        assert!(handle.supported_transport(&address));
+
+        let address =
+            "/dns4/google.com/tcp/443/wss/p2p/12D3KooWKrUnV42yDR7G6DewmgHtFaVCJWLjQRi2G9t5eJD3BvTy"
+                .parse()
+                .unwrap();
+        assert!(handle.supported_transport(&address));
+
+        let address =
+            "/dns4/google.com/tcp/443/tls/p2p/12D3KooWKrUnV42yDR7G6DewmgHtFaVCJWLjQRi2G9t5eJD3BvTy"
+                .parse()
+                .unwrap();
+        assert!(!handle.supported_transport(&address));
+    }
+
+    #[cfg(feature = "webrtc")]
+    #[tokio::test]
+    async fn webrtc_supported() {
+        let (mut handle, _rx) = make_transport_manager_handle();
+
+        let address = "/ip4/127.0.0.1/udp/8888/webrtc-direct/certhash/uEiDDq4_xNyDorZBH3TlGazyJdOWSwvo4PUo5YHFMrvDE8g/p2p/12D3KooWKrUnV42yDR7G6DewmgHtFaVCJWLjQRi2G9t5eJD3BvTy"
+            .parse()
+            .unwrap();
+        assert!(!handle.supported_transport(&address));
+
+        handle.supported_transport.insert(SupportedTransport::WebRtc);
+        assert!(handle.supported_transport(&address));
+    }
+
     #[test]
     fn transport_not_supported() {
         let (handle, _rx) = make_transport_manager_handle();
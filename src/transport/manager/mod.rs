@@ -21,25 +21,28 @@
 use crate::{
     addresses::PublicAddresses,
     codec::ProtocolCodec,
-    crypto::ed25519::Keypair,
-    error::{AddressError, DialError, Error},
+    config::Role,
+    crypto::{ed25519::Keypair, pnet::PreSharedKey},
+    error::{AddressError, DialError, DnsError, Error},
     executor::Executor,
     protocol::{InnerTransportEvent, TransportService},
     transport::{
+        common::dns::DnsResolver,
         manager::{
             address::AddressRecord,
+            dial_scheduler::{DialScheduler, DialSchedulerConfig},
             handle::InnerTransportManagerCommand,
             peer_state::{ConnectionRecord, PeerState, StateDialResult},
             types::PeerContext,
         },
         Endpoint, Transport, TransportEvent,
     },
-    types::{protocol::ProtocolName, ConnectionId},
+    types::{protocol::ProtocolName, ConnectionId, ListenerId},
     BandwidthSink, PeerId,
 };
 
 use address::{scores, AddressStore};
-use futures::{Stream, StreamExt};
+use futures::{future::BoxFuture, stream::FuturesUnordered, Stream, StreamExt};
 use indexmap::IndexMap;
 use multiaddr::{Multiaddr, Protocol};
 use multihash::Multihash;
@@ -47,7 +50,7 @@ use parking_lot::RwLock;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     pin::Pin,
     sync::{
         atomic::{AtomicUsize, Ordering},
@@ -61,6 +64,7 @@ pub use handle::{TransportHandle, TransportManagerHandle};
 pub use types::SupportedTransport;
 
 pub(crate) mod address;
+pub mod dial_scheduler;
 pub mod limits;
 mod peer_state;
 mod types;
@@ -130,6 +134,9 @@ struct TransportContext {
 
     /// Registered transports.
     transports: IndexMap<SupportedTransport, Box<dyn Transport<Item = TransportEvent>>>,
+
+    /// Events injected on behalf of the transports.
+    pending_events: VecDeque<(SupportedTransport, TransportEvent)>,
 }
 
 impl TransportContext {
@@ -138,6 +145,7 @@ impl TransportContext {
         Self {
             index: 0usize,
             transports: IndexMap::new(),
+            pending_events: VecDeque::new(),
         }
     }
 
@@ -162,6 +170,11 @@ impl TransportContext {
     ) {
         assert!(self.transports.insert(name, transport).is_none());
     }
+
+    /// Inject `event` as if it was emitted by `transport`.
+    pub fn inject_event(&mut self, transport: SupportedTransport, event: TransportEvent) {
+        self.pending_events.push_back((transport, event));
+    }
 }
 
 impl Stream for TransportContext {
@@ -173,6 +186,10 @@ impl Stream for TransportContext {
             return Poll::Ready(None);
         }
 
+        if let Some(event) = self.pending_events.pop_front() {
+            return Poll::Ready(Some(event));
+        }
+
         let len = self.transports.len();
         self.index = (self.index + 1) % len;
         for index in 0..len {
@@ -202,6 +219,9 @@ pub struct TransportManager {
     /// Keypair.
     keypair: Keypair,
 
+    /// Pre-shared key of the private network, if the node is part of one.
+    pre_shared_key: Option<PreSharedKey>,
+
     /// Bandwidth sink.
     bandwidth_sink: BandwidthSink,
 
@@ -252,6 +272,26 @@ pub struct TransportManager {
 
     /// Opening connections errors.
     opening_errors: HashMap<ConnectionId, Vec<(Multiaddr, DialError)>>,
+
+    /// Next listener ID.
+    next_listener_id: Arc<AtomicUsize>,
+
+    /// Listeners started with [`TransportManager::listen_on()`], their transports and addresses.
+    listeners: HashMap<ListenerId, (SupportedTransport, Vec<Multiaddr>)>,
+
+    /// DNS resolver shared with the transports.
+    resolver: DnsResolver,
+
+    /// Pending `/dnsaddr` resolutions of dialed addresses.
+    pending_dnsaddr_resolutions:
+        FuturesUnordered<BoxFuture<'static, (PeerId, Multiaddr, Result<Vec<Multiaddr>, DnsError>)>>,
+
+    /// Dial scheduler.
+    dial_scheduler: DialScheduler,
+
+    /// Transports still dialing the simultaneous opens started with
+    /// [`TransportManager::simultaneous_open()`].
+    simultaneous_opens: HashMap<ConnectionId, HashSet<SupportedTransport>>,
 }
 
 impl TransportManager {
@@ -291,6 +331,7 @@ impl TransportManager {
                 listen_addresses,
                 public_addresses,
                 max_parallel_dials,
+                pre_shared_key: None,
                 protocols: HashMap::new(),
                 transports: TransportContext::new(),
                 protocol_names: HashSet::new(),
@@ -300,6 +341,12 @@ impl TransportManager {
                 next_connection_id: Arc::new(AtomicUsize::new(0usize)),
                 connection_limits: limits::ConnectionLimits::new(connection_limits_config),
                 opening_errors: HashMap::new(),
+                next_listener_id: Arc::new(AtomicUsize::new(0usize)),
+                listeners: HashMap::new(),
+                resolver: DnsResolver::default(),
+                pending_dnsaddr_resolutions: FuturesUnordered::new(),
+                dial_scheduler: DialScheduler::new(DialSchedulerConfig::default()),
+                simultaneous_opens: HashMap::new(),
             },
             handle,
         )
@@ -366,10 +413,14 @@ impl TransportManager {
             tx: self.event_tx.clone(),
             executor,
             keypair: self.keypair.clone(),
+            pre_shared_key: self.pre_shared_key.clone(),
             protocols: self.protocols.clone(),
             bandwidth_sink: self.bandwidth_sink.clone(),
             next_substream_id: self.next_substream_id.clone(),
             next_connection_id: self.next_connection_id.clone(),
+            next_listener_id: self.next_listener_id.clone(),
+            resolver: self.resolver.clone(),
+            connection_attempt_delay: self.dial_scheduler.connection_attempt_delay(),
         }
     }
 
@@ -390,6 +441,27 @@ impl TransportManager {
         self.public_addresses.clone()
     }
 
+    /// Set the pre-shared key of the private network the node is part of.
+    ///
+    /// Must be called before the transports are created.
+    pub(crate) fn set_pre_shared_key(&mut self, pre_shared_key: PreSharedKey) {
+        self.pre_shared_key = Some(pre_shared_key);
+    }
+
+    /// Set the DNS resolver used by the transports.
+    ///
+    /// Must be called before the transports are created.
+    pub(crate) fn set_dns_resolver(&mut self, resolver: DnsResolver) {
+        self.resolver = resolver;
+    }
+
+    /// Set the configuration of the dial scheduler.
+    ///
+    /// Must be called before the transports are created.
+    pub(crate) fn set_dial_scheduler_config(&mut self, config: DialSchedulerConfig) {
+        self.dial_scheduler = DialScheduler::new(config);
+    }
+
     /// Register local listen address.
     pub fn register_listen_address(&mut self, address: Multiaddr) {
         assert!(!address.iter().any(|protocol| std::matches!(protocol, Protocol::P2p(_))));
@@ -402,6 +474,75 @@ impl TransportManager {
         )));
     }
 
+    /// Unregister local listen address.
+    ///
+    /// The address is also removed from the public addresses as it's no longer reachable.
+    fn unregister_listen_address(&mut self, address: &Multiaddr) {
+        let address_with_peer = address.clone().with(Protocol::P2p(
+            Multihash::from_bytes(&self.local_peer_id.to_bytes()).unwrap(),
+        ));
+
+        let mut listen_addresses = self.listen_addresses.write();
+        listen_addresses.remove(address);
+        listen_addresses.remove(&address_with_peer);
+
+        self.public_addresses.remove_address(&address_with_peer);
+    }
+
+    /// Start listening on `address` using the transport the address belongs to.
+    ///
+    /// Returns the ID of the listener and the addresses it's reachable at.
+    pub fn listen_on(&mut self, address: Multiaddr) -> crate::Result<(ListenerId, Vec<Multiaddr>)> {
+        let listener_id =
+            ListenerId::from(self.next_listener_id.fetch_add(1usize, Ordering::Relaxed));
+        let transport = self
+            .listen_transport(&address)
+            .ok_or_else(|| Error::TransportNotSupported(address.clone()))?;
+        let listen_addresses = self
+            .transports
+            .get_mut(&transport)
+            .ok_or_else(|| Error::TransportNotSupported(address.clone()))?
+            .listen_on(listener_id, address)?;
+
+        tracing::debug!(
+            target: LOG_TARGET,
+            ?listener_id,
+            ?transport,
+            ?listen_addresses,
+            "listener started",
+        );
+
+        for address in &listen_addresses {
+            self.register_listen_address(address.clone());
+        }
+        self.listeners.insert(listener_id, (transport, listen_addresses.clone()));
+
+        Ok((listener_id, listen_addresses))
+    }
+
+    /// Stop the listener started with [`TransportManager::listen_on()`].
+    ///
+    /// Returns the addresses the listener was reachable at.
+    pub fn stop_listening(&mut self, listener_id: ListenerId) -> crate::Result<Vec<Multiaddr>> {
+        let (transport, listen_addresses) = self
+            .listeners
+            .remove(&listener_id)
+            .ok_or(Error::ListenerDoesntExist(listener_id))?;
+
+        tracing::debug!(target: LOG_TARGET, ?listener_id, ?transport, "stop listener");
+
+        self.transports
+            .get_mut(&transport)
+            .expect("transport of the listener to exist")
+            .stop_listening(listener_id)?;
+
+        for address in &listen_addresses {
+            self.unregister_listen_address(address);
+        }
+
+        Ok(listen_addresses)
+    }
+
     /// Add one or more known addresses for `peer`.
     pub fn add_known_address(
         &mut self,
@@ -411,19 +552,92 @@ impl TransportManager {
         self.transport_manager_handle.add_known_address(&peer, address)
     }
 
+    /// Get the transport for `address` if it's an address of the local process or host.
+    fn local_transport(address: &Multiaddr) -> Option<SupportedTransport> {
+        match address.iter().next() {
+            Some(Protocol::Memory(_)) => Some(SupportedTransport::Memory),
+            #[cfg(unix)]
+            Some(Protocol::Unix(_)) => Some(SupportedTransport::Unix),
+            _ => None,
+        }
+    }
+
+    /// Get the transport which can listen on `address`.
+    fn listen_transport(&self, address: &Multiaddr) -> Option<SupportedTransport> {
+        if let Some(transport) = SupportedTransport::custom(self.transports.keys(), address) {
+            return Some(transport);
+        }
+
+        if let Some(transport) = Self::local_transport(address) {
+            return Some(transport);
+        }
+
+        let mut protocol_stack = address.iter();
+        match protocol_stack.next()? {
+            Protocol::Ip4(_) | Protocol::Ip6(_) => {}
+            _ => return None,
+        }
+
+        match protocol_stack.next()? {
+            Protocol::Tcp(_) => match protocol_stack.next() {
+                None | Some(Protocol::P2p(_)) => Some(SupportedTransport::Tcp),
+                #[cfg(feature = "websocket")]
+                Some(Protocol::Ws(_)) | Some(Protocol::Wss(_)) =>
+                    Some(SupportedTransport::WebSocket),
+                #[cfg(feature = "websocket")]
+                Some(Protocol::Tls) => match protocol_stack.next() {
+                    Some(Protocol::Ws(_)) => Some(SupportedTransport::WebSocket),
+                    _ => None,
+                },
+                _ => None,
+            },
+            #[cfg(any(feature = "quic", feature = "webrtc"))]
+            Protocol::Udp(_) => match protocol_stack.next()? {
+                #[cfg(feature = "quic")]
+                Protocol::QuicV1 => Some(SupportedTransport::Quic),
+                #[cfg(feature = "webrtc")]
+                Protocol::WebRTC => Some(SupportedTransport::WebRtc),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     /// Return multiple addresses to dial on supported protocols.
     fn supported_transports_addresses(
+        &self,
         addresses: &[Multiaddr],
     ) -> HashMap<SupportedTransport, Vec<Multiaddr>> {
         let mut transports = HashMap::<SupportedTransport, Vec<Multiaddr>>::new();
 
         for address in addresses.iter().cloned() {
+            if address.iter().any(|p| std::matches!(&p, Protocol::P2pCircuit)) {
+                transports.entry(SupportedTransport::Relay).or_default().push(address);
+                continue;
+            }
+
+            if let Some(transport) = SupportedTransport::custom(self.transports.keys(), &address) {
+                transports.entry(transport).or_default().push(address);
+                continue;
+            }
+
+            if let Some(transport) = Self::local_transport(&address) {
+                transports.entry(transport).or_default().push(address);
+                continue;
+            }
+
             #[cfg(feature = "quic")]
             if address.iter().any(|p| std::matches!(&p, Protocol::QuicV1)) {
                 transports.entry(SupportedTransport::Quic).or_default().push(address);
                 continue;
             }
 
+            #[cfg(feature = "webrtc")]
+            if address.iter().any(|p| std::matches!(&p, Protocol::WebRTC)) {
+                transports.entry(SupportedTransport::WebRtc).or_default().push(address);
+                continue;
+            }
+
             #[cfg(feature = "websocket")]
             if address.iter().any(|p| std::matches!(&p, Protocol::Ws(_) | Protocol::Wss(_))) {
                 transports.entry(SupportedTransport::WebSocket).or_default().push(address);
@@ -462,7 +676,9 @@ impl TransportManager {
 
         // The addresses are sorted by score and contain the remote peer ID.
         // We double checked above that the remote peer is not the local peer.
-        let dial_addresses = context.addresses.addresses(limit);
+        let mut dial_addresses =
+            self.dial_scheduler.filter_addresses(context.addresses.addresses(usize::MAX));
+        dial_addresses.truncate(limit);
         if dial_addresses.is_empty() {
             return Err(Error::NoAddressAvailable(peer));
         }
@@ -475,7 +691,7 @@ impl TransportManager {
             "dial remote peer",
         );
 
-        let transports = Self::supported_transports_addresses(&dial_addresses);
+        let transports = self.supported_transports_addresses(&dial_addresses);
 
         // Dialing addresses will succeed because the `context.state.can_dial()` returned `Ok`.
         let result = context.state.dial_addresses(
@@ -493,16 +709,16 @@ impl TransportManager {
             );
         }
 
-        for (transport, addresses) in transports {
-            if addresses.is_empty() {
-                continue;
-            }
-
-            let Some(installed_transport) = self.transports.get_mut(&transport) else {
-                continue;
-            };
+        drop(peers);
 
-            installed_transport.open(connection_id, addresses)?;
+        // Open the most preferred transport, the rest are opened by the dial scheduler.
+        if let Some((transport, addresses)) =
+            self.dial_scheduler.schedule(connection_id, transports)
+        {
+            if let Err(error) = self.open_transport(connection_id, transport, addresses) {
+                self.dial_scheduler.remove(connection_id);
+                return Err(error);
+            }
         }
 
         self.pending_connections.insert(connection_id, peer);
@@ -510,10 +726,119 @@ impl TransportManager {
         Ok(())
     }
 
+    /// Open `transport` for the dial `connection_id`.
+    ///
+    /// If `transport` is not installed, an open failure is reported for it so the dial proceeds
+    /// to the next transport.
+    fn open_transport(
+        &mut self,
+        connection_id: ConnectionId,
+        transport: SupportedTransport,
+        addresses: Vec<Multiaddr>,
+    ) -> crate::Result<()> {
+        let Some(installed_transport) = self.transports.get_mut(&transport) else {
+            self.transports.inject_event(
+                transport,
+                TransportEvent::OpenFailure {
+                    connection_id,
+                    errors: Vec::new(),
+                },
+            );
+            return Ok(());
+        };
+
+        installed_transport.open(connection_id, addresses)
+    }
+
+    /// Open `transport` scheduled by the dial scheduler for the dial `connection_id`.
+    fn on_transport_scheduled(
+        &mut self,
+        connection_id: ConnectionId,
+        transport: SupportedTransport,
+        addresses: Vec<Multiaddr>,
+    ) {
+        if !self.pending_connections.contains_key(&connection_id) {
+            self.dial_scheduler.remove(connection_id);
+            return;
+        }
+
+        if let Err(error) = self.open_transport(connection_id, transport, addresses) {
+            tracing::debug!(
+                target: LOG_TARGET,
+                ?connection_id,
+                ?transport,
+                ?error,
+                "failed to open transport",
+            );
+
+            self.transports.inject_event(
+                transport,
+                TransportEvent::OpenFailure {
+                    connection_id,
+                    errors: Vec::new(),
+                },
+            );
+        }
+    }
+
     /// Dial peer using `Multiaddr`.
     ///
+    /// `/dnsaddr` addresses are resolved in the background and the peer is dialed using the
+    /// resolved addresses. If the address can't be resolved, the failure is reported with
+    /// [`TransportEvent::DialFailure`].
+    ///
     /// Returns an error if address it not valid.
     pub async fn dial_address(&mut self, address: Multiaddr) -> crate::Result<()> {
+        if Self::is_dnsaddr(&address) {
+            return self.start_dnsaddr_resolution(address);
+        }
+
+        self.dial_address_inner(address, false).await
+    }
+
+    /// Check if `address` is a `/dnsaddr` address.
+    fn is_dnsaddr(address: &Multiaddr) -> bool {
+        std::matches!(address.iter().next(), Some(Protocol::Dnsaddr(_)))
+    }
+
+    /// Start resolving `/dnsaddr` `address` in the background.
+    ///
+    /// The peer is dialed once the address has been resolved, see
+    /// [`TransportManager::dial_resolved_dnsaddr()`].
+    fn start_dnsaddr_resolution(&mut self, address: Multiaddr) -> crate::Result<()> {
+        let peer = PeerId::try_from_multiaddr(&address)
+            .ok_or(Error::AddressError(AddressError::PeerIdMissing))?;
+        let resolver = self.resolver.clone();
+
+        self.pending_dnsaddr_resolutions.push(Box::pin(async move {
+            let result = resolver.resolve_dnsaddr(address.clone()).await;
+            (peer, address, result)
+        }));
+
+        Ok(())
+    }
+
+    /// Dial `peer` using the addresses its `/dnsaddr` address was resolved into.
+    async fn dial_resolved_dnsaddr(
+        &mut self,
+        peer: PeerId,
+        addresses: Vec<Multiaddr>,
+    ) -> crate::Result<()> {
+        tracing::trace!(target: LOG_TARGET, ?peer, ?addresses, "`/dnsaddr` resolved");
+
+        self.add_known_address(peer, addresses.into_iter());
+        self.dial(peer).await
+    }
+
+    /// Dial peer using `Multiaddr`.
+    ///
+    /// If `secondary` is `true` and the peer is already connected, the dial is tracked as the
+    /// secondary connection of the peer.
+    async fn dial_address_inner(
+        &mut self,
+        address: Multiaddr,
+        secondary: bool,
+    ) -> crate::Result<()> {
         self.connection_limits.on_dial_address()?;
 
         let address_record = AddressRecord::from_multiaddr(address)
@@ -525,61 +850,87 @@ impl TransportManager {
 
         tracing::debug!(target: LOG_TARGET, address = ?address_record.address(), "dial address");
 
-        let mut protocol_stack = address_record.as_ref().iter();
-        match protocol_stack
-            .next()
-            .ok_or_else(|| Error::TransportNotSupported(address_record.address().clone()))?
+        // circuit addresses are dialed through the relay regardless of the relay's transport
+        let supported_transport = if address_record
+            .as_ref()
+            .iter()
+            .any(|protocol| std::matches!(protocol, Protocol::P2pCircuit))
         {
-            Protocol::Ip4(_) | Protocol::Ip6(_) => {}
-            Protocol::Dns(_) | Protocol::Dns4(_) | Protocol::Dns6(_) => {}
-            transport => {
-                tracing::error!(
-                    target: LOG_TARGET,
-                    ?transport,
-                    "invalid transport, expected `ip4`/`ip6`"
-                );
-                return Err(Error::TransportNotSupported(
-                    address_record.address().clone(),
-                ));
-            }
-        };
-
-        let supported_transport = match protocol_stack
-            .next()
-            .ok_or_else(|| Error::TransportNotSupported(address_record.address().clone()))?
+            SupportedTransport::Relay
+        } else if let Some(transport) =
+            SupportedTransport::custom(self.transports.keys(), address_record.as_ref())
         {
-            Protocol::Tcp(_) => match protocol_stack.next() {
-                #[cfg(feature = "websocket")]
-                Some(Protocol::Ws(_)) | Some(Protocol::Wss(_)) => SupportedTransport::WebSocket,
-                Some(Protocol::P2p(_)) => SupportedTransport::Tcp,
-                _ =>
+            transport
+        } else if let Some(transport) = Self::local_transport(address_record.as_ref()) {
+            transport
+        } else {
+            let mut protocol_stack = address_record.as_ref().iter();
+            match protocol_stack
+                .next()
+                .ok_or_else(|| Error::TransportNotSupported(address_record.address().clone()))?
+            {
+                Protocol::Ip4(_) | Protocol::Ip6(_) => {}
+                Protocol::Dns(_) | Protocol::Dns4(_) | Protocol::Dns6(_) => {}
+                transport => {
+                    tracing::error!(
+                        target: LOG_TARGET,
+                        ?transport,
+                        "invalid transport, expected `ip4`/`ip6`"
+                    );
                     return Err(Error::TransportNotSupported(
                         address_record.address().clone(),
-                    )),
-            },
-            #[cfg(feature = "quic")]
-            Protocol::Udp(_) => match protocol_stack
+                    ));
+                }
+            };
+
+            match protocol_stack
                 .next()
                 .ok_or_else(|| Error::TransportNotSupported(address_record.address().clone()))?
             {
-                Protocol::QuicV1 => SupportedTransport::Quic,
-                _ => {
-                    tracing::debug!(target: LOG_TARGET, address = ?address_record.address(), "expected `quic-v1`");
+                Protocol::Tcp(_) => match protocol_stack.next() {
+                    #[cfg(feature = "websocket")]
+                    Some(Protocol::Ws(_)) | Some(Protocol::Wss(_)) => SupportedTransport::WebSocket,
+                    #[cfg(feature = "websocket")]
+                    Some(Protocol::Tls) => match protocol_stack.next() {
+                        Some(Protocol::Ws(_)) => SupportedTransport::WebSocket,
+                        _ =>
+                            return Err(Error::TransportNotSupported(
+                                address_record.address().clone(),
+                            )),
+                    },
+                    Some(Protocol::P2p(_)) => SupportedTransport::Tcp,
+                    _ =>
+                        return Err(Error::TransportNotSupported(
+                            address_record.address().clone(),
+                        )),
+                },
+                #[cfg(any(feature = "quic", feature = "webrtc"))]
+                Protocol::Udp(_) => match protocol_stack
+                    .next()
+                    .ok_or_else(|| Error::TransportNotSupported(address_record.address().clone()))?
+                {
+                    #[cfg(feature = "quic")]
+                    Protocol::QuicV1 => SupportedTransport::Quic,
+                    #[cfg(feature = "webrtc")]
+                    Protocol::WebRTC => SupportedTransport::WebRtc,
+                    _ => {
+                        tracing::debug!(target: LOG_TARGET, address = ?address_record.address(), "expected `quic-v1` or `webrtc-direct`");
+                        return Err(Error::TransportNotSupported(
+                            address_record.address().clone(),
+                        ));
+                    }
+                },
+                protocol => {
+                    tracing::error!(
+                        target: LOG_TARGET,
+                        ?protocol,
+                        "invalid protocol"
+                    );
+
                     return Err(Error::TransportNotSupported(
                         address_record.address().clone(),
                     ));
                 }
-            },
-            protocol => {
-                tracing::error!(
-                    target: LOG_TARGET,
-                    ?protocol,
-                    "invalid protocol"
-                );
-
-                return Err(Error::TransportNotSupported(
-                    address_record.address().clone(),
-                ));
             }
         };
 
@@ -602,24 +953,172 @@ impl TransportManager {
             // Keep the provided record around for possible future dials.
             context.addresses.insert(address_record.clone());
 
-            match context.state.dial_single_address(dial_record) {
+            let result = match secondary {
+                true => context.state.dial_secondary_address(dial_record),
+                false => context.state.dial_single_address(dial_record),
+            };
+
+            match result {
                 StateDialResult::AlreadyConnected => return Err(Error::AlreadyConnected),
                 StateDialResult::DialingInProgress => return Ok(()),
                 StateDialResult::Ok => {}
             };
         }
 
-        self.transports
-            .get_mut(&supported_transport)
-            .ok_or(Error::TransportNotSupported(
-                address_record.address().clone(),
-            ))?
-            .dial(connection_id, address_record.address().clone())?;
+        let transport =
+            self.transports
+                .get_mut(&supported_transport)
+                .ok_or(Error::TransportNotSupported(
+                    address_record.address().clone(),
+                ))?;
+        let result = transport.dial(connection_id, address_record.address().clone());
+
+        // clear the dial record since the transport never started the dial
+        if let Err(error) = result {
+            if let Some(context) = self.peers.write().get_mut(&remote_peer_id) {
+                context.state.on_dial_failure(connection_id);
+            }
+
+            return Err(error);
+        }
         self.pending_connections.insert(connection_id, remote_peer_id);
 
         Ok(())
     }
 
+    /// Dial `addresses` of `peer` at once as one side of a simultaneous open.
+    ///
+    /// The addresses of each transport are dialed with [`Transport::dial_with_role()`] under one
+    /// connection ID which is tracked as the secondary connection of the peer. The first
+    /// transport to establish a connection wins and the dials of the other transports are
+    /// canceled.
+    fn simultaneous_open(
+        &mut self,
+        peer: PeerId,
+        addresses: Vec<Multiaddr>,
+        role: Role,
+    ) -> crate::Result<()> {
+        self.connection_limits.on_dial_address()?;
+
+        let Some(first_address) = addresses.first().cloned() else {
+            return Err(Error::NoAddressAvailable(peer));
+        };
+
+        tracing::debug!(target: LOG_TARGET, ?peer, ?addresses, ?role, "simultaneous open");
+
+        let connection_id = self.next_connection_id();
+        let dial_record = ConnectionRecord {
+            address: first_address,
+            connection_id,
+        };
+
+        match self
+            .peers
+            .write()
+            .entry(peer)
+            .or_default()
+            .state
+            .dial_secondary_address(dial_record)
+        {
+            StateDialResult::AlreadyConnected => return Err(Error::AlreadyConnected),
+            StateDialResult::DialingInProgress => return Ok(()),
+            StateDialResult::Ok => {}
+        }
+
+        let mut transports = HashSet::new();
+        let mut last_error = Error::NoAddressAvailable(peer);
+
+        for (supported_transport, addresses) in self.supported_transports_addresses(&addresses) {
+            let Some(transport) = self.transports.get_mut(&supported_transport) else {
+                last_error = Error::TransportNotSupported(addresses[0].clone());
+                continue;
+            };
+
+            match transport.dial_with_role(connection_id, addresses, role) {
+                Ok(()) => {
+                    transports.insert(supported_transport);
+                }
+                Err(error) => {
+                    tracing::debug!(
+                        target: LOG_TARGET,
+                        ?peer,
+                        ?supported_transport,
+                        ?error,
+                        "failed to start simultaneous open",
+                    );
+
+                    last_error = error;
+                }
+            }
+        }
+
+        // clear the dial record since no transport started the dial
+        if transports.is_empty() {
+            if let Some(context) = self.peers.write().get_mut(&peer) {
+                context.state.on_dial_failure(connection_id);
+            }
+
+            return Err(last_error);
+        }
+
+        self.pending_connections.insert(connection_id, peer);
+        self.simultaneous_opens.insert(connection_id, transports);
+
+        Ok(())
+    }
+
+    /// Punch holes towards `addresses` with their transports.
+    fn hole_punch(&mut self, addresses: Vec<Multiaddr>) -> crate::Result<()> {
+        for (supported_transport, addresses) in self.supported_transports_addresses(&addresses) {
+            self.transports
+                .get_mut(&supported_transport)
+                .ok_or_else(|| Error::TransportNotSupported(addresses[0].clone()))?
+                .hole_punch(addresses)?;
+        }
+
+        Ok(())
+    }
+
+    /// Handle a failed simultaneous open of `transport`.
+    ///
+    /// Returns `true` if other transports are still dialing and the failure must not be
+    /// reported yet.
+    fn on_simultaneous_open_failure(
+        &mut self,
+        connection_id: ConnectionId,
+        transport: SupportedTransport,
+    ) -> bool {
+        let Some(transports) = self.simultaneous_opens.get_mut(&connection_id) else {
+            return false;
+        };
+
+        transports.remove(&transport);
+
+        if transports.is_empty() {
+            self.simultaneous_opens.remove(&connection_id);
+            return false;
+        }
+
+        true
+    }
+
+    /// Cancel the dials of a simultaneous open after `transport` established the connection.
+    fn on_simultaneous_open_established(
+        &mut self,
+        connection_id: ConnectionId,
+        transport: SupportedTransport,
+    ) {
+        let Some(transports) = self.simultaneous_opens.remove(&connection_id) else {
+            return;
+        };
+
+        for supported_transport in transports.into_iter().filter(|other| *other != transport) {
+            if let Some(transport) = self.transports.get_mut(&supported_transport) {
+                transport.cancel(connection_id);
+            }
+        }
+    }
+
     // Update the address on a dial failure.
     fn update_address_on_dial_failure(&mut self, address: Multiaddr, error: &DialError) {
         let mut peers = self.peers.write();
@@ -832,6 +1331,7 @@ impl TransportManager {
                 //
                 // TODO: see https://github.com/paritytech/litep2p/issues/276 for more details.
                 self.pending_connections.remove(&connection_id);
+                self.dial_scheduler.remove(connection_id);
             }
 
             return Ok(ConnectionEstablishedResult::Accept);
@@ -907,6 +1407,7 @@ impl TransportManager {
                 .expect("transport to exist")
                 .cancel(connection_id);
         }
+        self.dial_scheduler.remove(connection_id);
 
         let negotiation = self
             .transports
@@ -1037,14 +1538,53 @@ impl TransportManager {
                                 tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to dial peer")
                             }
                         }
-                        InnerTransportManagerCommand::DialAddress { address } => {
-                            if let Err(error) = self.dial_address(address).await {
+                        InnerTransportManagerCommand::DialAddress { address, .. }
+                            if Self::is_dnsaddr(&address) =>
+                        {
+                            if let Err(error) = self.start_dnsaddr_resolution(address) {
                                 tracing::debug!(target: LOG_TARGET, ?error, "failed to dial peer")
                             }
                         }
+                        InnerTransportManagerCommand::DialAddress { address, secondary } => {
+                            if let Err(error) = self.dial_address_inner(address, secondary).await {
+                                tracing::debug!(target: LOG_TARGET, ?error, ?secondary, "failed to dial peer")
+                            }
+                        }
+                        InnerTransportManagerCommand::SimultaneousOpen { peer, addresses, role } => {
+                            if let Err(error) = self.simultaneous_open(peer, addresses, role) {
+                                tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to start simultaneous open")
+                            }
+                        }
+                        InnerTransportManagerCommand::HolePunch { addresses } => {
+                            if let Err(error) = self.hole_punch(addresses) {
+                                tracing::debug!(target: LOG_TARGET, ?error, "failed to punch holes")
+                            }
+                        }
+                    }
+                },
+
+                Some((peer, address, result)) = self.pending_dnsaddr_resolutions.next() => {
+                    match result {
+                        Ok(addresses) =>
+                            if let Err(error) = self.dial_resolved_dnsaddr(peer, addresses).await {
+                                tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to dial peer")
+                            },
+                        Err(error) => {
+                            tracing::debug!(target: LOG_TARGET, ?peer, ?error, "failed to resolve `/dnsaddr`");
+
+                            return Some(TransportEvent::DialFailure {
+                                connection_id: self.next_connection_id(),
+                                address,
+                                error: DialError::DnsError(error),
+                            });
+                        }
                     }
                 },
 
+                Some((connection_id, transport, addresses)) = self.dial_scheduler.next() => {
+                    self.on_transport_scheduled(connection_id, transport, addresses);
+                },
+
                 event = self.transports.next() => {
                     let Some((transport, event)) = event else {
                         tracing::error!(
@@ -1071,6 +1611,11 @@ impl TransportManager {
                             // while taking into account the error type.
                             self.update_address_on_dial_failure(address.clone(), &error);
 
+                            // other transports of a simultaneous open may still connect
+                            if self.on_simultaneous_open_failure(connection_id, transport) {
+                                continue;
+                            }
+
                             if let Ok(()) = self.on_dial_failure(connection_id) {
                                 match address.iter().last() {
                                     Some(Protocol::P2p(hash)) => match PeerId::from_multihash(hash) {
@@ -1152,6 +1697,7 @@ impl TransportManager {
                         }
                         TransportEvent::ConnectionEstablished { peer, endpoint } => {
                             self.opening_errors.remove(&endpoint.connection_id());
+                            self.on_simultaneous_open_established(endpoint.connection_id(), transport);
 
                             match self.on_connection_established(peer, &endpoint) {
                                 Err(error) => {
@@ -1222,13 +1768,18 @@ impl TransportManager {
                             }
 
                             match self.on_open_failure(transport, connection_id) {
-                                Err(error) => tracing::debug!(
-                                    target: LOG_TARGET,
-                                    ?connection_id,
-                                    ?error,
-                                    "failed to handle opened connection",
-                                ),
+                                Err(error) => {
+                                    tracing::debug!(
+                                        target: LOG_TARGET,
+                                        ?connection_id,
+                                        ?error,
+                                        "failed to handle opened connection",
+                                    );
+                                    self.dial_scheduler.remove(connection_id);
+                                }
                                 Ok(Some(peer)) => {
+                                    self.dial_scheduler.remove(connection_id);
+
                                     tracing::trace!(
                                         target: LOG_TARGET,
                                         ?peer,
@@ -1282,6 +1833,13 @@ impl TransportManager {
                                     );
 
                                     self.opening_errors.entry(connection_id).or_default().extend(errors);
+
+                                    // open the next transport early if all opened transports have failed
+                                    if let Some((transport, addresses)) =
+                                        self.dial_scheduler.on_open_failure(connection_id, transport)
+                                    {
+                                        self.on_transport_scheduled(connection_id, transport, addresses);
+                                    }
                                 }
                             }
                         },
@@ -1312,7 +1870,45 @@ impl TransportManager {
                                     .reject_pending(connection_id);
                             }
                         },
-                        event => panic!("event not supported: {event:?}"),
+                        TransportEvent::NewListenAddress { listener_id, address } => {
+                            // listeners started from the transport configuration are not tracked
+                            if let Some((_, addresses)) = self.listeners.get_mut(&listener_id) {
+                                addresses.push(address.clone());
+                            }
+
+                            tracing::debug!(
+                                target: LOG_TARGET,
+                                ?listener_id,
+                                ?address,
+                                "new listen address",
+                            );
+
+                            self.register_listen_address(address.clone());
+                            return Some(TransportEvent::NewListenAddress { listener_id, address });
+                        }
+                        TransportEvent::ListenAddressExpired { listener_id, address } => {
+                            if let Some((_, addresses)) = self.listeners.get_mut(&listener_id) {
+                                addresses.retain(|listen_address| listen_address != &address);
+                            }
+
+                            tracing::debug!(
+                                target: LOG_TARGET,
+                                ?listener_id,
+                                ?address,
+                                "listen address expired",
+                            );
+
+                            self.unregister_listen_address(&address);
+                            return Some(TransportEvent::ListenAddressExpired { listener_id, address });
+                        }
+                        event => {
+                            tracing::warn!(
+                                target: LOG_TARGET,
+                                ?transport,
+                                ?event,
+                                "transport emitted an unsupported event, ignoring",
+                            );
+                        }
                     }
                 },
             }
@@ -3155,6 +3751,11 @@ mod tests {
             8usize,
             ConnectionLimitsConfig::default(),
         );
+        // the known addresses of the peer are private addresses of a non-local network
+        manager.set_dial_scheduler_config(DialSchedulerConfig {
+            skip_non_local_private_addresses: false,
+            ..Default::default()
+        });
         let peer = PeerId::random();
         let dial_address = Multiaddr::empty()
             .with(Protocol::Ip4(Ipv4Addr::new(127, 0, 0, 1)))
@@ -3785,6 +4386,11 @@ mod tests {
             8usize,
             ConnectionLimitsConfig::default(),
         );
+        // the known addresses of the peer are private addresses of a non-local network
+        manager.set_dial_scheduler_config(DialSchedulerConfig {
+            skip_non_local_private_addresses: false,
+            ..Default::default()
+        });
         let peer = PeerId::random();
         let connection_id = ConnectionId::from(0);
 
@@ -3877,4 +4483,219 @@ mod tests {
         assert!(manager.pending_connections.is_empty());
         assert!(manager.opening_errors.is_empty());
     }
+
+    #[cfg(feature = "websocket")]
+    #[tokio::test]
+    async fn simultaneous_open_fails_after_all_transports_fail() {
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .try_init();
+
+        let (mut manager, _handle) = TransportManager::new(
+            Keypair::generate(),
+            HashSet::new(),
+            BandwidthSink::new(),
+            8usize,
+            ConnectionLimitsConfig::default(),
+        );
+        let peer = PeerId::random();
+        let connection_id = ConnectionId::from(0);
+        let dial_address_tcp = Multiaddr::empty()
+            .with(Protocol::Ip4(Ipv4Addr::new(127, 0, 0, 1)))
+            .with(Protocol::Tcp(8888))
+            .with(Protocol::P2p(Multihash::from(peer)));
+        let dial_address_ws = Multiaddr::empty()
+            .with(Protocol::Ip4(Ipv4Addr::new(127, 0, 0, 1)))
+            .with(Protocol::Tcp(8889))
+            .with(Protocol::Ws(Cow::Borrowed("/")))
+            .with(Protocol::P2p(Multihash::from(peer)));
+
+        for (transport, address) in [
+            (SupportedTransport::Tcp, &dial_address_tcp),
+            (SupportedTransport::WebSocket, &dial_address_ws),
+        ] {
+            let mut dummy = DummyTransport::new();
+            dummy.inject_event(TransportEvent::DialFailure {
+                connection_id,
+                address: address.clone(),
+                error: DialError::Timeout,
+            });
+            manager.register_transport(transport, Box::new(dummy));
+        }
+
+        manager
+            .simultaneous_open(
+                peer,
+                vec![dial_address_tcp.clone(), dial_address_ws.clone()],
+                Role::Listener,
+            )
+            .unwrap();
+        assert_eq!(
+            manager.simultaneous_opens.get(&connection_id).unwrap().len(),
+            2
+        );
+
+        // the failure is reported once after both transports have failed
+        match manager.next().await.unwrap() {
+            TransportEvent::DialFailure {
+                connection_id: failed,
+                ..
+            } => assert_eq!(failed, connection_id),
+            event => panic!("invalid event: {event:?}"),
+        }
+        assert!(manager.simultaneous_opens.is_empty());
+        assert!(manager.pending_connections.is_empty());
+        assert!(std::matches!(
+            manager.peers.read().get(&peer).unwrap().state,
+            PeerState::Disconnected { dial_record: None }
+        ));
+        assert!(tokio::time::timeout(Duration::from_secs(1), manager.next()).await.is_err());
+    }
+
+    #[cfg(feature = "websocket")]
+    #[tokio::test]
+    async fn simultaneous_open_established_by_one_transport() {
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .try_init();
+
+        let (mut manager, _handle) = TransportManager::new(
+            Keypair::generate(),
+            HashSet::new(),
+            BandwidthSink::new(),
+            8usize,
+            ConnectionLimitsConfig::default(),
+        );
+        let peer = PeerId::random();
+        let connection_id = ConnectionId::from(0);
+        let dial_address_tcp = Multiaddr::empty()
+            .with(Protocol::Ip4(Ipv4Addr::new(127, 0, 0, 1)))
+            .with(Protocol::Tcp(8888))
+            .with(Protocol::P2p(Multihash::from(peer)));
+        let dial_address_ws = Multiaddr::empty()
+            .with(Protocol::Ip4(Ipv4Addr::new(127, 0, 0, 1)))
+            .with(Protocol::Tcp(8889))
+            .with(Protocol::Ws(Cow::Borrowed("/")))
+            .with(Protocol::P2p(Multihash::from(peer)));
+
+        let mut transport = DummyTransport::new();
+        transport.inject_event(TransportEvent::ConnectionEstablished {
+            peer,
+            endpoint: Endpoint::dialer(dial_address_ws.clone(), connection_id),
+        });
+        manager.register_transport(SupportedTransport::WebSocket, Box::new(transport));
+        manager.register_transport(SupportedTransport::Tcp, Box::new(DummyTransport::new()));
+
+        manager
+            .simultaneous_open(
+                peer,
+                vec![dial_address_tcp.clone(), dial_address_ws.clone()],
+                Role::Dialer,
+            )
+            .unwrap();
+
+        match manager.next().await.unwrap() {
+            TransportEvent::ConnectionEstablished {
+                peer: connected,
+                endpoint,
+            } => {
+                assert_eq!(connected, peer);
+                assert_eq!(endpoint.address(), &dial_address_ws);
+            }
+            event => panic!("invalid event: {event:?}"),
+        }
+        assert!(manager.simultaneous_opens.is_empty());
+    }
+
+    #[tokio::test]
+    async fn listen_address_changes_update_listen_addresses() {
+        let keypair = Keypair::generate();
+        let local_peer_id = PeerId::from_public_key(&keypair.public().into());
+        let (mut manager, handle) = TransportManager::new(
+            keypair,
+            HashSet::new(),
+            BandwidthSink::new(),
+            8usize,
+            ConnectionLimitsConfig::default(),
+        );
+        let address = Multiaddr::empty()
+            .with(Protocol::Ip4(Ipv4Addr::new(192, 168, 1, 5)))
+            .with(Protocol::Tcp(8888));
+        let address_with_peer = address.clone().with(Protocol::P2p(local_peer_id.into()));
+
+        let transport = Box::new({
+            let mut transport = DummyTransport::new();
+            transport.inject_event(TransportEvent::NewListenAddress {
+                listener_id: ListenerId::from(0usize),
+                address: address.clone(),
+            });
+            transport.inject_event(TransportEvent::ListenAddressExpired {
+                listener_id: ListenerId::from(0usize),
+                address: address.clone(),
+            });
+            transport
+        });
+        manager.register_transport(SupportedTransport::Tcp, transport);
+
+        match manager.next().await.unwrap() {
+            TransportEvent::NewListenAddress {
+                listener_id,
+                address: new,
+            } => {
+                assert_eq!(listener_id, ListenerId::from(0usize));
+                assert_eq!(new, address);
+            }
+            event => panic!("invalid event: {event:?}"),
+        }
+        assert!(handle.listen_addresses().contains(&address));
+        assert!(handle.listen_addresses().contains(&address_with_peer));
+
+        manager.public_addresses().add_address(address.clone()).unwrap();
+
+        match manager.next().await.unwrap() {
+            TransportEvent::ListenAddressExpired {
+                listener_id,
+                address: expired,
+            } => {
+                assert_eq!(listener_id, ListenerId::from(0usize));
+                assert_eq!(expired, address);
+            }
+            event => panic!("invalid event: {event:?}"),
+        }
+        assert!(handle.listen_addresses().is_empty());
+        assert!(manager.public_addresses().get_addresses().is_empty());
+    }
+
+    #[tokio::test]
+    async fn unsupported_transport_event_is_ignored() {
+        let (mut manager, _handle) = TransportManager::new(
+            Keypair::generate(),
+            HashSet::new(),
+            BandwidthSink::new(),
+            8usize,
+            ConnectionLimitsConfig::default(),
+        );
+        let address = Multiaddr::empty()
+            .with(Protocol::Ip4(Ipv4Addr::new(192, 168, 1, 5)))
+            .with(Protocol::Tcp(8888));
+
+        let transport = Box::new({
+            let mut transport = DummyTransport::new();
+            transport.inject_event(TransportEvent::ConnectionClosed {
+                peer: PeerId::random(),
+                connection_id: ConnectionId::from(0),
+            });
+            transport.inject_event(TransportEvent::NewListenAddress {
+                listener_id: ListenerId::from(0usize),
+                address: address.clone(),
+            });
+            transport
+        });
+        manager.register_transport(SupportedTransport::Tcp, transport);
+
+        match manager.next().await.unwrap() {
+            TransportEvent::NewListenAddress { address: new, .. } => assert_eq!(new, address),
+            event => panic!("invalid event: {event:?}"),
+        }
+    }
 }
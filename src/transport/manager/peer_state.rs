@@ -180,6 +180,44 @@ impl PeerState {
         }
     }
 
+    /// Check if a secondary connection can be dialed to the peer.
+    ///
+    /// Unlike [`PeerState::can_dial`], a connected peer can be dialed as long as it doesn't
+    /// already have a secondary connection or a pending secondary dial.
+    pub fn can_dial_secondary(&self) -> StateDialResult {
+        match self {
+            Self::Connected {
+                secondary: None, ..
+            } => StateDialResult::Ok,
+            Self::Connected { .. } => StateDialResult::AlreadyConnected,
+            state => state.can_dial(),
+        }
+    }
+
+    /// Dial the peer on a single address, opening a secondary connection if the peer is already
+    /// connected.
+    ///
+    /// # Transitions
+    ///
+    /// - [`PeerState::Disconnected`] (without record) -> [`PeerState::Dialing`]
+    /// - [`PeerState::Connected`] (without secondary) -> [`PeerState::Connected`] (with dial
+    ///   record)
+    pub fn dial_secondary_address(&mut self, dial_record: ConnectionRecord) -> StateDialResult {
+        match self.can_dial_secondary() {
+            StateDialResult::Ok => match self {
+                Self::Connected { record, .. } => {
+                    *self = Self::Connected {
+                        record: record.clone(),
+                        secondary: Some(SecondaryOrDialing::Dialing(dial_record)),
+                    };
+                    StateDialResult::Ok
+                }
+                _ => self.dial_single_address(dial_record),
+            },
+            reason => reason,
+        }
+    }
+
     /// Dial the peer on multiple addresses.
     pub fn dial_addresses(
         &mut self,
@@ -254,10 +292,7 @@ impl PeerState {
                 secondary: Some(SecondaryOrDialing::Dialing(dial_record)),
             } =>
                 if dial_record.connection_id == connection.connection_id {
-                    *self = Self::Connected {
-                        record: record.clone(),
-                        secondary: Some(SecondaryOrDialing::Secondary(connection)),
-                    };
+                    *self = Self::with_secondary(record.clone(), connection);
 
                     return true;
                 },
@@ -267,10 +302,7 @@ impl PeerState {
                 record,
                 secondary: None,
             } => {
-                *self = Self::Connected {
-                    record: record.clone(),
-                    secondary: Some(SecondaryOrDialing::Secondary(connection)),
-                };
+                *self = Self::with_secondary(record.clone(), connection);
 
                 return true;
             }
@@ -331,6 +363,32 @@ impl PeerState {
         return false;
     }
 
+    /// Create connected state from the `primary` connection and a newly established `secondary`
+    /// connection.
+    ///
+    /// A direct connection replaces a relayed primary connection, demoting the relayed connection
+    /// to secondary.
+    fn with_secondary(primary: ConnectionRecord, secondary: ConnectionRecord) -> Self {
+        if primary.is_relayed() && !secondary.is_relayed() {
+            tracing::debug!(
+                target: LOG_TARGET,
+                relayed = ?primary,
+                direct = ?secondary,
+                "direct connection replaces relayed connection",
+            );
+
+            return Self::Connected {
+                record: secondary,
+                secondary: Some(SecondaryOrDialing::Secondary(primary)),
+            };
+        }
+
+        Self::Connected {
+            record: primary,
+            secondary: Some(SecondaryOrDialing::Secondary(secondary)),
+        }
+    }
+
     /// Returns `true` if the connection was closed.
     pub fn on_connection_closed(&mut self, connection_id: ConnectionId) -> bool {
         match self {
@@ -468,6 +526,13 @@ impl ConnectionRecord {
         }
     }
 
+    /// Is the connection relayed through a circuit relay?
+    pub fn is_relayed(&self) -> bool {
+        self.address
+            .iter()
+            .any(|protocol| std::matches!(protocol, Protocol::P2pCircuit))
+    }
+
     /// Ensures the peer ID is present in the address.
     fn ensure_peer_id(peer: PeerId, mut address: Multiaddr) -> Multiaddr {
         if let Some(Protocol::P2p(multihash)) = address.iter().last() {
@@ -550,6 +615,80 @@ mod tests {
         );
     }
 
+    #[test]
+    fn state_dial_secondary_address() {
+        let peer = PeerId::random();
+        let record = ConnectionRecord::new(
+            peer,
+            "/ip4/1.1.1.1/tcp/80".parse().unwrap(),
+            ConnectionId::from(0),
+        );
+        let dial_record = ConnectionRecord::new(
+            peer,
+            "/ip4/1.1.1.1/tcp/81".parse().unwrap(),
+            ConnectionId::from(1),
+        );
+
+        // disconnected peer is dialed normally
+        let mut state = PeerState::Disconnected { dial_record: None };
+        assert_eq!(
+            state.dial_secondary_address(dial_record.clone()),
+            StateDialResult::Ok
+        );
+        assert_eq!(
+            state,
+            PeerState::Dialing {
+                dial_record: dial_record.clone()
+            }
+        );
+
+        // connected peer is dialed for a secondary connection
+        let mut state = PeerState::Connected {
+            record: record.clone(),
+            secondary: None,
+        };
+        assert_eq!(
+            state.dial_secondary_address(dial_record.clone()),
+            StateDialResult::Ok
+        );
+        assert_eq!(
+            state,
+            PeerState::Connected {
+                record: record.clone(),
+                secondary: Some(SecondaryOrDialing::Dialing(dial_record.clone())),
+            }
+        );
+
+        // pending secondary dial is not replaced
+        assert_eq!(
+            state.dial_secondary_address(dial_record.clone()),
+            StateDialResult::AlreadyConnected
+        );
+
+        // the dial record transitions into a secondary connection
+        assert!(state.on_connection_established(dial_record.clone()));
+        assert_eq!(
+            state,
+            PeerState::Connected {
+                record,
+                secondary: Some(SecondaryOrDialing::Secondary(dial_record.clone())),
+            }
+        );
+        assert_eq!(
+            state.dial_secondary_address(dial_record.clone()),
+            StateDialResult::AlreadyConnected
+        );
+
+        // dialing in progress
+        let mut state = PeerState::Dialing {
+            dial_record: dial_record.clone(),
+        };
+        assert_eq!(
+            state.dial_secondary_address(dial_record),
+            StateDialResult::DialingInProgress
+        );
+    }
+
     #[test]
     fn state_dial_addresses() {
         let mut state = PeerState::Disconnected { dial_record: None };
@@ -854,6 +993,83 @@ mod tests {
         }
     }
 
+    #[test]
+    fn direct_connection_replaces_relayed() {
+        let peer = PeerId::random();
+        let relayed = ConnectionRecord::new(
+            peer,
+            format!("/ip4/1.1.1.1/tcp/80/p2p/{}/p2p-circuit", PeerId::random())
+                .parse()
+                .unwrap(),
+            ConnectionId::from(0),
+        );
+        let direct = ConnectionRecord::new(
+            peer,
+            "/ip4/2.2.2.2/tcp/80".parse().unwrap(),
+            ConnectionId::from(1),
+        );
+        assert!(relayed.is_relayed());
+        assert!(!direct.is_relayed());
+
+        // Direct connection established while the relayed connection is the primary.
+        {
+            let mut state = PeerState::Connected {
+                record: relayed.clone(),
+                secondary: None,
+            };
+            assert!(state.on_connection_established(direct.clone()));
+            assert_eq!(
+                state,
+                PeerState::Connected {
+                    record: direct.clone(),
+                    secondary: Some(SecondaryOrDialing::Secondary(relayed.clone())),
+                }
+            );
+
+            // Closing the relayed connection keeps the direct connection.
+            assert!(!state.on_connection_closed(ConnectionId::from(0)));
+            assert_eq!(
+                state,
+                PeerState::Connected {
+                    record: direct.clone(),
+                    secondary: None,
+                }
+            );
+        }
+
+        // Dialed direct connection replaces the relayed connection.
+        {
+            let mut state = PeerState::Connected {
+                record: relayed.clone(),
+                secondary: Some(SecondaryOrDialing::Dialing(direct.clone())),
+            };
+            assert!(state.on_connection_established(direct.clone()));
+            assert_eq!(
+                state,
+                PeerState::Connected {
+                    record: direct.clone(),
+                    secondary: Some(SecondaryOrDialing::Secondary(relayed.clone())),
+                }
+            );
+        }
+
+        // Relayed connection doesn't replace the direct connection.
+        {
+            let mut state = PeerState::Connected {
+                record: direct.clone(),
+                secondary: None,
+            };
+            assert!(state.on_connection_established(relayed.clone()));
+            assert_eq!(
+                state,
+                PeerState::Connected {
+                    record: direct.clone(),
+                    secondary: Some(SecondaryOrDialing::Secondary(relayed.clone())),
+                }
+            );
+        }
+    }
+
     #[test]
     fn check_open_failure() {
         let mut state = PeerState::Opening {
@@ -20,7 +20,10 @@
 
 use crate::transport::manager::{address::AddressStore, peer_state::PeerState};
 
+use multiaddr::Multiaddr;
+
 /// Supported protocols.
+/// Transport supported by the node.
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
 pub enum SupportedTransport {
     /// TCP.
@@ -37,6 +40,37 @@ pub enum SupportedTransport {
     /// WebSocket
     #[cfg(feature = "websocket")]
     WebSocket,
+
+    /// Circuit relay.
+    Relay,
+
+    /// In-memory transport.
+    Memory,
+
+    /// Unix domain socket.
+    #[cfg(unix)]
+    Unix,
+
+    /// User-supplied transport, identified by the multiaddr protocol it handles.
+    ///
+    /// See [`CustomTransport`](crate::transport::custom::CustomTransport).
+    Custom(&'static str),
+}
+
+impl SupportedTransport {
+    /// Find the custom transport of `transports` that handles `address`.
+    pub(crate) fn custom<'a>(
+        mut transports: impl Iterator<Item = &'a SupportedTransport>,
+        address: &Multiaddr,
+    ) -> Option<SupportedTransport> {
+        transports
+            .find(|transport| match transport {
+                SupportedTransport::Custom(tag) =>
+                    address.iter().any(|protocol| protocol.tag() == *tag),
+                _ => false,
+            })
+            .copied()
+    }
 }
 
 /// Peer context.
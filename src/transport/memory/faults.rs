@@ -0,0 +1,247 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Fault injection for the memory transport.
+
+use parking_lot::Mutex;
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    task::Waker,
+    time::Duration,
+};
+
+/// Which half of a stream is waiting for the fault state to change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum Interest {
+    /// Read half.
+    Read,
+
+    /// Write half.
+    Write,
+}
+
+#[derive(Debug, Default)]
+struct FaultState {
+    /// Delay added to every write before the data is readable by the remote peer.
+    latency: Option<Duration>,
+
+    /// Ports of the nodes the local node is partitioned from.
+    partitions: HashSet<u64>,
+
+    /// Incremented each time the connections are dropped.
+    ///
+    /// Streams created in an earlier epoch fail all reads and writes.
+    epoch: u64,
+
+    /// Next stream ID.
+    next_stream_id: u64,
+
+    /// Wakers of the streams waiting for the fault state to change.
+    wakers: HashMap<(u64, Interest), Waker>,
+}
+
+impl FaultState {
+    /// Wake all streams waiting for the fault state to change.
+    fn wake_all(&mut self) {
+        self.wakers.drain().for_each(|(_, waker)| waker.wake());
+    }
+}
+
+/// Fault injector of a memory transport.
+///
+/// The faults apply to all connections of the node and can be changed while the node is running.
+/// Clones of the injector control the same node so a test can keep a copy of the injector passed
+/// in [`Config::faults`](super::config::Config::faults).
+#[derive(Debug, Clone, Default)]
+pub struct Faults {
+    state: Arc<Mutex<FaultState>>,
+}
+
+impl Faults {
+    /// Create new [`Faults`] with no faults injected.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Delay every write of the node by `latency` before the remote peer can read it.
+    ///
+    /// The latency applies to data written after the call. Pass `None` to remove the latency.
+    pub fn set_latency(&self, latency: Option<Duration>) {
+        self.state.lock().latency = latency;
+    }
+
+    /// Partition the node from the node listening on `/memory/<port>`.
+    ///
+    /// Dials between the nodes fail and data written to open connections between them is held
+    /// back until the partition is healed, after which the data is delivered in order.
+    pub fn partition(&self, port: u64) {
+        let mut state = self.state.lock();
+        state.partitions.insert(port);
+        state.wake_all();
+    }
+
+    /// Heal the partition from the node listening on `/memory/<port>`.
+    pub fn heal(&self, port: u64) {
+        let mut state = self.state.lock();
+        state.partitions.remove(&port);
+        state.wake_all();
+    }
+
+    /// Heal all partitions of the node.
+    pub fn heal_all(&self) {
+        let mut state = self.state.lock();
+        state.partitions.clear();
+        state.wake_all();
+    }
+
+    /// Drop all open connections of the node.
+    ///
+    /// Reads and writes of the dropped connections fail with
+    /// [`ConnectionReset`](std::io::ErrorKind::ConnectionReset). Connections opened after the call
+    /// are not affected.
+    pub fn drop_connections(&self) {
+        let mut state = self.state.lock();
+        state.epoch += 1;
+        state.wake_all();
+    }
+
+    /// Get the current latency.
+    pub(super) fn latency(&self) -> Option<Duration> {
+        self.state.lock().latency
+    }
+
+    /// Check if the node is partitioned from the node listening on `port`.
+    pub(super) fn is_partitioned(&self, port: u64) -> bool {
+        self.state.lock().partitions.contains(&port)
+    }
+
+    /// Register a new stream, returning its ID and the current epoch.
+    pub(super) fn register_stream(&self) -> (u64, u64) {
+        let mut state = self.state.lock();
+        let stream_id = state.next_stream_id;
+        state.next_stream_id += 1;
+
+        (stream_id, state.epoch)
+    }
+
+    /// Unregister the wakers of a dropped stream.
+    pub(super) fn unregister_stream(&self, stream_id: u64) {
+        let mut state = self.state.lock();
+        state.wakers.remove(&(stream_id, Interest::Read));
+        state.wakers.remove(&(stream_id, Interest::Write));
+    }
+
+    /// Check the faults affecting a stream and register `waker` to be woken when they change.
+    pub(super) fn poll_stream(
+        &self,
+        stream_id: u64,
+        epoch: u64,
+        remote_port: u64,
+        interest: Interest,
+        waker: &Waker,
+    ) -> StreamState {
+        let mut state = self.state.lock();
+        state.wakers.insert((stream_id, interest), waker.clone());
+
+        if state.epoch != epoch {
+            return StreamState::Dropped;
+        }
+
+        match state.partitions.contains(&remote_port) {
+            true => StreamState::Partitioned,
+            false => StreamState::Open,
+        }
+    }
+}
+
+/// State of a stream with the faults applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum StreamState {
+    /// The stream can be read from and written to.
+    Open,
+
+    /// The stream is partitioned from the remote peer.
+    Partitioned,
+
+    /// The stream has been dropped.
+    Dropped,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::task::noop_waker;
+
+    #[test]
+    fn partition_and_heal() {
+        let faults = Faults::new();
+        let (stream_id, epoch) = faults.register_stream();
+        let waker = noop_waker();
+
+        assert_eq!(
+            faults.poll_stream(stream_id, epoch, 1, Interest::Read, &waker),
+            StreamState::Open
+        );
+
+        faults.partition(1);
+        assert!(faults.is_partitioned(1));
+        assert!(!faults.is_partitioned(2));
+        assert_eq!(
+            faults.poll_stream(stream_id, epoch, 1, Interest::Read, &waker),
+            StreamState::Partitioned
+        );
+        assert_eq!(
+            faults.poll_stream(stream_id, epoch, 2, Interest::Read, &waker),
+            StreamState::Open
+        );
+
+        faults.heal(1);
+        assert_eq!(
+            faults.poll_stream(stream_id, epoch, 1, Interest::Write, &waker),
+            StreamState::Open
+        );
+    }
+
+    #[test]
+    fn dropped_connections_stay_dropped() {
+        let faults = Faults::new();
+        let (stream_id, epoch) = faults.register_stream();
+        let waker = noop_waker();
+
+        faults.drop_connections();
+        assert_eq!(
+            faults.poll_stream(stream_id, epoch, 1, Interest::Read, &waker),
+            StreamState::Dropped
+        );
+
+        // streams registered after the drop are not affected
+        let (new_stream_id, new_epoch) = faults.register_stream();
+        assert_eq!(
+            faults.poll_stream(new_stream_id, new_epoch, 1, Interest::Read, &waker),
+            StreamState::Open
+        );
+
+        faults.unregister_stream(stream_id);
+        faults.unregister_stream(new_stream_id);
+        assert!(faults.state.lock().wakers.is_empty());
+    }
+}
@@ -0,0 +1,834 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! In-memory transport.
+//!
+//! Connects nodes running in the same process through channels, using `/memory/<port>`
+//! addresses. The connections are negotiated the same way as TCP connections, with the
+//! security protocol and stream muxer upgrades, which makes the transport suitable for
+//! deterministic multi-node tests. Latency, partitions and connection drops can be injected
+//! with [`Faults`].
+
+use crate::{
+    config::Role,
+    error::{AddressError, DialError, Error},
+    transport::{
+        manager::TransportHandle,
+        memory::{config::Config, stream::MemoryStream},
+        tcp::connection::{NegotiatedConnection, TcpConnection},
+        Transport, TransportBuilder, TransportEvent,
+    },
+    types::ConnectionId,
+    utils::futures_stream::FuturesStream,
+    PeerId,
+};
+
+use futures::{future::BoxFuture, Stream, StreamExt};
+use multiaddr::{Multiaddr, Protocol};
+use parking_lot::Mutex;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    io,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+pub use faults::Faults;
+
+mod faults;
+mod stream;
+
+pub mod config;
+
+/// Logging target for the file.
+const LOG_TARGET: &str = "litep2p::memory";
+
+/// Registry of the memory transports of the process.
+static REGISTRY: Mutex<Registry> = parking_lot::const_mutex(Registry {
+    listeners: BTreeMap::new(),
+    next_port: 1,
+});
+
+/// Inbound connection sent to a listener.
+#[derive(Debug)]
+struct InboundConnection {
+    /// Stream of the connection.
+    stream: MemoryStream,
+
+    /// Port of the dialer.
+    port: u64,
+}
+
+/// Port registered by a memory transport.
+struct Listener {
+    /// TX channel for sending inbound connections to the transport.
+    ///
+    /// `None` if the port is only used for dialing.
+    tx: Option<UnboundedSender<InboundConnection>>,
+
+    /// Faults of the node.
+    faults: Faults,
+}
+
+/// Registry of the memory transports of the process.
+struct Registry {
+    /// Registered ports.
+    listeners: BTreeMap<u64, Listener>,
+
+    /// Next port allocated for `/memory/0`.
+    next_port: u64,
+}
+
+impl Registry {
+    /// Register `port` for a transport, allocating a free port if `port` is `0`.
+    fn register(port: u64, listener: Listener) -> io::Result<u64> {
+        let mut registry = REGISTRY.lock();
+
+        let port = match port {
+            0 => loop {
+                let port = registry.next_port;
+                registry.next_port = registry.next_port.checked_add(1).unwrap_or(1);
+
+                if !registry.listeners.contains_key(&port) {
+                    break port;
+                }
+            },
+            port if registry.listeners.contains_key(&port) =>
+                return Err(io::ErrorKind::AddrInUse.into()),
+            port => port,
+        };
+
+        registry.listeners.insert(port, listener);
+        Ok(port)
+    }
+
+    /// Unregister `port`.
+    fn unregister(port: u64) {
+        REGISTRY.lock().listeners.remove(&port);
+    }
+
+    /// Connect the node using `local_port` to the node listening on `remote_port`.
+    fn connect(
+        local_port: u64,
+        local_faults: &Faults,
+        remote_port: u64,
+    ) -> Result<MemoryStream, DialError> {
+        let registry = REGISTRY.lock();
+        let Some(Listener {
+            tx: Some(tx),
+            faults: remote_faults,
+        }) = registry.listeners.get(&remote_port)
+        else {
+            return Err(io::Error::from(io::ErrorKind::ConnectionRefused).into());
+        };
+
+        // the dial of a partitioned node never reaches the remote node
+        if local_faults.is_partitioned(remote_port) || remote_faults.is_partitioned(local_port) {
+            return Err(DialError::Timeout);
+        }
+
+        let (local, remote) = MemoryStream::pair(
+            local_port,
+            local_faults.clone(),
+            remote_port,
+            remote_faults.clone(),
+        );
+
+        tx.send(InboundConnection {
+            stream: remote,
+            port: local_port,
+        })
+        .map_err(|_| io::Error::from(io::ErrorKind::ConnectionRefused))?;
+
+        Ok(local)
+    }
+}
+
+/// Extract the port and the optional peer ID from a memory address.
+fn multiaddr_to_port(address: &Multiaddr) -> Result<(u64, Option<PeerId>), AddressError> {
+    let mut iter = address.iter();
+
+    let port = match iter.next() {
+        Some(Protocol::Memory(port)) => port,
+        protocol => {
+            tracing::debug!(
+                target: LOG_TARGET,
+                ?protocol,
+                "invalid transport protocol, expected `Memory`",
+            );
+            return Err(AddressError::InvalidProtocol);
+        }
+    };
+
+    let peer = match iter.next() {
+        Some(Protocol::P2p(multihash)) =>
+            Some(PeerId::from_multihash(multihash).map_err(AddressError::InvalidPeerId)?),
+        None => None,
+        protocol => {
+            tracing::debug!(
+                target: LOG_TARGET,
+                ?protocol,
+                "invalid protocol, expected `P2p` or `None`"
+            );
+            return Err(AddressError::InvalidProtocol);
+        }
+    };
+
+    Ok((port, peer))
+}
+
+/// Memory transport.
+pub(crate) struct MemoryTransport {
+    /// Transport context.
+    context: TransportHandle,
+
+    /// Transport configuration.
+    config: Config,
+
+    /// Ports registered by the transport.
+    ports: Vec<u64>,
+
+    /// Port the transport dials from.
+    local_port: u64,
+
+    /// RX channel for receiving inbound connections.
+    rx: UnboundedReceiver<InboundConnection>,
+
+    /// Pending dials.
+    pending_dials: HashMap<ConnectionId, Multiaddr>,
+
+    /// Pending inbound connections.
+    pending_inbound_connections: HashMap<ConnectionId, InboundConnection>,
+
+    /// Pending opening connections.
+    pending_connections: FuturesStream<
+        BoxFuture<'static, Result<NegotiatedConnection<MemoryStream>, (ConnectionId, DialError)>>,
+    >,
+
+    /// Opened raw connection, waiting for approval/rejection from `TransportManager`.
+    opened_raw: HashMap<ConnectionId, (MemoryStream, Multiaddr)>,
+
+    /// Connections which have been opened and negotiated but are being validated by the
+    /// `TransportManager`.
+    pending_open: HashMap<ConnectionId, NegotiatedConnection<MemoryStream>>,
+
+    /// Pending events.
+    pending_events: VecDeque<TransportEvent>,
+
+    /// Waker of the transport.
+    waker: Option<Waker>,
+}
+
+impl MemoryTransport {
+    /// Negotiate `stream` in `role`.
+    fn negotiate_connection(
+        &mut self,
+        connection_id: ConnectionId,
+        stream: MemoryStream,
+        role: Role,
+        peer: Option<PeerId>,
+        port: u64,
+    ) {
+        let yamux_config = self.config.yamux_config.clone();
+        let mplex_config = self.config.mplex_config.clone();
+        let security_protocols = self.config.security_protocols.clone();
+        let stream_muxers = self.config.stream_muxers.clone();
+        let max_read_ahead_factor = self.config.noise_read_ahead_frame_count;
+        let max_write_buffer_size = self.config.noise_write_buffer_size;
+        let connection_open_timeout = self.config.connection_open_timeout;
+        let substream_open_timeout = self.config.substream_open_timeout;
+        let keypair = self.context.keypair.clone();
+        let pre_shared_key = self.context.pre_shared_key.clone();
+
+        self.pending_connections.push(Box::pin(async move {
+            match tokio::time::timeout(connection_open_timeout, async move {
+                TcpConnection::negotiate_stream(
+                    stream,
+                    peer,
+                    connection_id,
+                    keypair,
+                    pre_shared_key,
+                    role,
+                    Multiaddr::empty().with(Protocol::Memory(port)),
+                    yamux_config,
+                    mplex_config,
+                    security_protocols,
+                    stream_muxers,
+                    max_read_ahead_factor,
+                    max_write_buffer_size,
+                    substream_open_timeout,
+                )
+                .await
+                .map_err(|error| (connection_id, error.into()))
+            })
+            .await
+            {
+                Err(_) => Err((connection_id, DialError::Timeout)),
+                Ok(Err(error)) => Err(error),
+                Ok(Ok(connection)) => Ok(connection),
+            }
+        }));
+    }
+
+    /// Queue `event` to be returned from the transport.
+    fn push_event(&mut self, event: TransportEvent) {
+        self.pending_events.push_back(event);
+
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Drop for MemoryTransport {
+    fn drop(&mut self) {
+        for port in &self.ports {
+            Registry::unregister(*port);
+        }
+    }
+}
+
+impl TransportBuilder for MemoryTransport {
+    type Config = Config;
+    type Transport = MemoryTransport;
+
+    /// Create new [`MemoryTransport`].
+    fn new(
+        context: TransportHandle,
+        mut config: Self::Config,
+    ) -> crate::Result<(Self, Vec<Multiaddr>)> {
+        tracing::debug!(
+            target: LOG_TARGET,
+            listen_addresses = ?config.listen_addresses,
+            "start memory transport",
+        );
+
+        let (tx, rx) = unbounded_channel();
+        let mut ports = Vec::new();
+
+        let register = |port: u64, tx: Option<UnboundedSender<InboundConnection>>| {
+            Registry::register(
+                port,
+                Listener {
+                    tx,
+                    faults: config.faults.clone(),
+                },
+            )
+        };
+
+        for address in std::mem::take(&mut config.listen_addresses) {
+            let port = match multiaddr_to_port(&address)? {
+                (port, None) => port,
+                (_, Some(_)) => return Err(Error::AddressError(AddressError::InvalidProtocol)),
+            };
+
+            match register(port, Some(tx.clone())) {
+                Ok(port) => ports.push(port),
+                Err(error) => {
+                    ports.iter().for_each(|port| Registry::unregister(*port));
+                    return Err(error.into());
+                }
+            }
+        }
+        let listen_addresses = ports
+            .iter()
+            .map(|port| Multiaddr::empty().with(Protocol::Memory(*port)))
+            .collect();
+
+        // a transport without listen addresses still needs a port to dial from
+        let local_port = match ports.first() {
+            Some(port) => *port,
+            None => {
+                let port = register(0, None)?;
+                ports.push(port);
+                port
+            }
+        };
+
+        Ok((
+            Self {
+                context,
+                config,
+                ports,
+                local_port,
+                rx,
+                pending_dials: HashMap::new(),
+                pending_inbound_connections: HashMap::new(),
+                pending_connections: FuturesStream::new(),
+                opened_raw: HashMap::new(),
+                pending_open: HashMap::new(),
+                pending_events: VecDeque::new(),
+                waker: None,
+            },
+            listen_addresses,
+        ))
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn dial(&mut self, connection_id: ConnectionId, address: Multiaddr) -> crate::Result<()> {
+        tracing::debug!(target: LOG_TARGET, ?connection_id, ?address, "open connection");
+
+        let (port, peer) = multiaddr_to_port(&address)?;
+        self.pending_dials.insert(connection_id, address);
+
+        match Registry::connect(self.local_port, &self.config.faults, port) {
+            Ok(stream) =>
+                self.negotiate_connection(connection_id, stream, Role::Dialer, peer, port),
+            Err(error) => self
+                .pending_connections
+                .push(Box::pin(async move { Err((connection_id, error)) })),
+        }
+
+        Ok(())
+    }
+
+    fn accept(&mut self, connection_id: ConnectionId) -> crate::Result<()> {
+        let context = self
+            .pending_open
+            .remove(&connection_id)
+            .ok_or(Error::ConnectionDoesntExist(connection_id))?;
+
+        tracing::trace!(
+            target: LOG_TARGET,
+            ?connection_id,
+            "start connection",
+        );
+
+        self.context.start_connection(context);
+
+        Ok(())
+    }
+
+    fn accept_pending(&mut self, connection_id: ConnectionId) -> crate::Result<()> {
+        let InboundConnection { stream, port } =
+            self.pending_inbound_connections.remove(&connection_id).ok_or_else(|| {
+                tracing::error!(
+                    target: LOG_TARGET,
+                    ?connection_id,
+                    "Cannot accept non existent pending connection",
+                );
+
+                Error::ConnectionDoesntExist(connection_id)
+            })?;
+
+        self.negotiate_connection(connection_id, stream, Role::Listener, None, port);
+
+        Ok(())
+    }
+
+    fn reject_pending(&mut self, connection_id: ConnectionId) -> crate::Result<()> {
+        self.pending_inbound_connections.remove(&connection_id).map_or_else(
+            || {
+                tracing::error!(
+                    target: LOG_TARGET,
+                    ?connection_id,
+                    "Cannot reject non existent pending connection",
+                );
+
+                Err(Error::ConnectionDoesntExist(connection_id))
+            },
+            |_| Ok(()),
+        )
+    }
+
+    fn reject(&mut self, connection_id: ConnectionId) -> crate::Result<()> {
+        self.pending_open
+            .remove(&connection_id)
+            .map_or(Err(Error::ConnectionDoesntExist(connection_id)), |_| Ok(()))
+    }
+
+    fn open(
+        &mut self,
+        connection_id: ConnectionId,
+        addresses: Vec<Multiaddr>,
+    ) -> crate::Result<()> {
+        let mut errors = Vec::with_capacity(addresses.len());
+
+        // connecting to a memory transport completes immediately so the addresses are tried
+        // one by one until a connection is opened
+        for address in addresses {
+            let result =
+                multiaddr_to_port(&address).map_err(DialError::from).and_then(|(port, _)| {
+                    Registry::connect(self.local_port, &self.config.faults, port)
+                });
+
+            match result {
+                Ok(stream) => {
+                    self.opened_raw.insert(connection_id, (stream, address.clone()));
+                    self.push_event(TransportEvent::ConnectionOpened {
+                        connection_id,
+                        address,
+                    });
+
+                    return Ok(());
+                }
+                Err(error) => {
+                    tracing::debug!(
+                        target: LOG_TARGET,
+                        ?connection_id,
+                        ?address,
+                        ?error,
+                        "failed to open connection",
+                    );
+                    errors.push((address, error));
+                }
+            }
+        }
+
+        self.push_event(TransportEvent::OpenFailure {
+            connection_id,
+            errors,
+        });
+
+        Ok(())
+    }
+
+    fn negotiate(&mut self, connection_id: ConnectionId) -> crate::Result<()> {
+        let (stream, address) = self
+            .opened_raw
+            .remove(&connection_id)
+            .ok_or(Error::ConnectionDoesntExist(connection_id))?;
+        let (port, peer) = multiaddr_to_port(&address)?;
+
+        tracing::trace!(
+            target: LOG_TARGET,
+            ?peer,
+            ?connection_id,
+            ?address,
+            "negotiate connection",
+        );
+
+        self.pending_dials.insert(connection_id, address);
+        self.negotiate_connection(connection_id, stream, Role::Dialer, peer, port);
+
+        Ok(())
+    }
+
+    fn cancel(&mut self, connection_id: ConnectionId) {
+        self.opened_raw.remove(&connection_id);
+        self.pending_events.retain(|event| match event {
+            TransportEvent::ConnectionOpened {
+                connection_id: id, ..
+            }
+            | TransportEvent::OpenFailure {
+                connection_id: id, ..
+            } => *id != connection_id,
+            _ => true,
+        });
+    }
+}
+
+impl Stream for MemoryTransport {
+    type Item = TransportEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(event) = self.pending_events.pop_front() {
+            return Poll::Ready(Some(event));
+        }
+
+        // the channel is closed only if the transport doesn't listen on any port
+        if let Poll::Ready(Some(connection)) = self.rx.poll_recv(cx) {
+            let connection_id = self.context.next_connection_id();
+            tracing::trace!(
+                target: LOG_TARGET,
+                ?connection_id,
+                port = ?connection.port,
+                "pending inbound memory connection",
+            );
+
+            self.pending_inbound_connections.insert(connection_id, connection);
+
+            return Poll::Ready(Some(TransportEvent::PendingInboundConnection {
+                connection_id,
+            }));
+        }
+
+        while let Poll::Ready(Some(connection)) = self.pending_connections.poll_next_unpin(cx) {
+            match connection {
+                Ok(connection) => {
+                    let peer = connection.peer();
+                    let endpoint = connection.endpoint();
+                    self.pending_dials.remove(&connection.connection_id());
+                    self.pending_open.insert(connection.connection_id(), connection);
+
+                    return Poll::Ready(Some(TransportEvent::ConnectionEstablished {
+                        peer,
+                        endpoint,
+                    }));
+                }
+                Err((connection_id, error)) => {
+                    if let Some(address) = self.pending_dials.remove(&connection_id) {
+                        return Poll::Ready(Some(TransportEvent::DialFailure {
+                            connection_id,
+                            address,
+                            error,
+                        }));
+                    } else {
+                        tracing::debug!(target: LOG_TARGET, ?error, ?connection_id, "Pending inbound connection failed");
+                    }
+                }
+            }
+        }
+
+        self.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        codec::ProtocolCodec, crypto::ed25519::Keypair, executor::DefaultExecutor,
+        transport::manager::ProtocolContext, types::protocol::ProtocolName, BandwidthSink,
+    };
+    use std::sync::Arc;
+    use tokio::sync::mpsc::channel;
+
+    fn memory_transport(config: Config) -> (MemoryTransport, Vec<Multiaddr>, PeerId) {
+        let keypair = Keypair::generate();
+        let peer = PeerId::from_public_key(&keypair.public().into());
+        let (tx, _rx) = channel(64);
+        let (event_tx, _event_rx) = channel(64);
+
+        let handle = TransportHandle {
+            executor: Arc::new(DefaultExecutor {}),
+            next_substream_id: Default::default(),
+            next_connection_id: Default::default(),
+            next_listener_id: Default::default(),
+            keypair,
+            pre_shared_key: None,
+            resolver: Default::default(),
+            connection_attempt_delay: Default::default(),
+            tx: event_tx,
+            bandwidth_sink: BandwidthSink::new(),
+
+            protocols: HashMap::from_iter([(
+                ProtocolName::from("/notif/1"),
+                ProtocolContext {
+                    tx,
+                    codec: ProtocolCodec::Identity(32),
+                    fallback_names: Vec::new(),
+                },
+            )]),
+        };
+        let (transport, listen_addresses) = MemoryTransport::new(handle, config).unwrap();
+
+        (transport, listen_addresses, peer)
+    }
+
+    #[tokio::test]
+    async fn connect_and_accept_works() {
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .try_init();
+
+        let (mut transport1, listen_addresses1, _) = memory_transport(Default::default());
+        let (mut transport2, listen_addresses2, peer2) = memory_transport(Config {
+            listen_addresses: Vec::new(),
+            ..Default::default()
+        });
+        assert!(listen_addresses2.is_empty());
+        let (mut transport3, listen_addresses3, peer3) = memory_transport(Default::default());
+
+        transport1
+            .dial(
+                ConnectionId::from(0usize),
+                listen_addresses3[0].clone().with(Protocol::P2p(peer3.into())),
+            )
+            .unwrap();
+        transport2
+            .dial(ConnectionId::from(1usize), listen_addresses3[0].clone())
+            .unwrap();
+
+        let mut dialer_peers = Vec::new();
+        let mut listener_endpoints = Vec::new();
+
+        while dialer_peers.len() < 2 || listener_endpoints.len() < 2 {
+            tokio::select! {
+                event = transport1.next() => match event {
+                    Some(TransportEvent::ConnectionEstablished { peer, .. }) =>
+                        dialer_peers.push(peer),
+                    event => panic!("invalid event: {event:?}"),
+                },
+                event = transport2.next() => match event {
+                    Some(TransportEvent::ConnectionEstablished { peer, .. }) =>
+                        dialer_peers.push(peer),
+                    event => panic!("invalid event: {event:?}"),
+                },
+                event = transport3.next() => match event {
+                    Some(TransportEvent::PendingInboundConnection { connection_id }) =>
+                        transport3.accept_pending(connection_id).unwrap(),
+                    Some(TransportEvent::ConnectionEstablished { peer, endpoint }) =>
+                        listener_endpoints.push((peer, endpoint)),
+                    event => panic!("invalid event: {event:?}"),
+                },
+            }
+        }
+
+        assert_eq!(dialer_peers, vec![peer3, peer3]);
+        assert!(listener_endpoints.iter().all(|(_, endpoint)| endpoint.is_listener()));
+
+        // inbound connections are reported with the port the dialer dials from, which for
+        // a dialer without listen addresses is a port reserved for dialing
+        for (peer, endpoint) in listener_endpoints {
+            let address = match peer == peer2 {
+                true => Multiaddr::empty().with(Protocol::Memory(transport2.local_port)),
+                false => listen_addresses1[0].clone(),
+            };
+            assert_eq!(endpoint.address(), &address);
+        }
+    }
+
+    #[tokio::test]
+    async fn dial_unknown_port() {
+        let (mut transport, _, _) = memory_transport(Default::default());
+        let (listener, listen_addresses, _) = memory_transport(Default::default());
+        let address = listen_addresses[0].clone();
+
+        // the port is released when the transport is dropped
+        drop(listener);
+        transport.dial(ConnectionId::from(0usize), address.clone()).unwrap();
+
+        match transport.next().await {
+            Some(TransportEvent::DialFailure {
+                address: dial_address,
+                error: DialError::NegotiationError(crate::error::NegotiationError::IoError(kind)),
+                ..
+            }) => {
+                assert_eq!(dial_address, address);
+                assert_eq!(kind, io::ErrorKind::ConnectionRefused);
+            }
+            event => panic!("invalid event: {event:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn port_in_use() {
+        let (_transport, listen_addresses, _) = memory_transport(Default::default());
+
+        let keypair = Keypair::generate();
+        let (event_tx, _event_rx) = channel(64);
+        let handle = TransportHandle {
+            executor: Arc::new(DefaultExecutor {}),
+            next_substream_id: Default::default(),
+            next_connection_id: Default::default(),
+            next_listener_id: Default::default(),
+            keypair,
+            pre_shared_key: None,
+            resolver: Default::default(),
+            connection_attempt_delay: Default::default(),
+            tx: event_tx,
+            bandwidth_sink: BandwidthSink::new(),
+            protocols: HashMap::new(),
+        };
+
+        assert!(std::matches!(
+            MemoryTransport::new(
+                handle,
+                Config {
+                    listen_addresses,
+                    ..Default::default()
+                }
+            ),
+            Err(Error::IoError(io::ErrorKind::AddrInUse))
+        ));
+    }
+
+    #[tokio::test]
+    async fn partitioned_dial_fails() {
+        let faults = Faults::new();
+        let (mut transport1, listen_addresses1, _) = memory_transport(Config {
+            faults: faults.clone(),
+            ..Default::default()
+        });
+        let (mut transport2, listen_addresses2, _) = memory_transport(Default::default());
+
+        let Some(Protocol::Memory(port2)) = listen_addresses2[0].iter().next() else {
+            panic!("invalid listen address");
+        };
+        faults.partition(port2);
+
+        // partitions affect the dials of both nodes
+        transport1
+            .dial(ConnectionId::from(0usize), listen_addresses2[0].clone())
+            .unwrap();
+        transport2
+            .dial(ConnectionId::from(1usize), listen_addresses1[0].clone())
+            .unwrap();
+
+        let (res1, res2) = tokio::join!(transport1.next(), transport2.next());
+        assert!(std::matches!(
+            res1,
+            Some(TransportEvent::DialFailure {
+                error: DialError::Timeout,
+                ..
+            })
+        ));
+        assert!(std::matches!(
+            res2,
+            Some(TransportEvent::DialFailure {
+                error: DialError::Timeout,
+                ..
+            })
+        ));
+
+        faults.heal(port2);
+        transport1
+            .dial(ConnectionId::from(2usize), listen_addresses2[0].clone())
+            .unwrap();
+        assert!(std::matches!(
+            transport2.next().await,
+            Some(TransportEvent::PendingInboundConnection { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn open_tries_all_addresses() {
+        let (mut transport1, _, _) = memory_transport(Default::default());
+        let (_transport2, listen_addresses2, _) = memory_transport(Default::default());
+        let invalid = Multiaddr::empty().with(Protocol::Memory(u64::MAX));
+
+        transport1
+            .open(
+                ConnectionId::from(0usize),
+                vec![invalid.clone(), listen_addresses2[0].clone()],
+            )
+            .unwrap();
+
+        match transport1.next().await {
+            Some(TransportEvent::ConnectionOpened { address, .. }) =>
+                assert_eq!(address, listen_addresses2[0]),
+            event => panic!("invalid event: {event:?}"),
+        }
+
+        transport1.open(ConnectionId::from(1usize), vec![invalid.clone()]).unwrap();
+        match transport1.next().await {
+            Some(TransportEvent::OpenFailure { errors, .. }) => {
+                assert_eq!(errors.len(), 1);
+                assert_eq!(errors[0].0, invalid);
+            }
+            event => panic!("invalid event: {event:?}"),
+        }
+    }
+}
@@ -0,0 +1,296 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! In-memory duplex stream.
+
+use crate::transport::memory::faults::{Faults, Interest, StreamState};
+
+use bytes::{Buf, Bytes};
+use futures::{channel::mpsc, ready, SinkExt, StreamExt};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    time::{Instant, Sleep},
+};
+
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// How many writes can be buffered before the writer has to wait for the reader.
+const WRITE_BUFFER_SIZE: usize = 64;
+
+/// Data written to the stream.
+#[derive(Debug)]
+struct Chunk {
+    /// When the remote peer is allowed to read the data.
+    readable_at: Option<Instant>,
+
+    /// Data.
+    data: Bytes,
+}
+
+/// One end of an in-memory connection.
+#[derive(Debug)]
+pub(crate) struct MemoryStream {
+    /// Faults of the local node.
+    faults: Faults,
+
+    /// Stream ID, used to register wakers with [`Faults`].
+    stream_id: u64,
+
+    /// Fault epoch the stream was created in.
+    epoch: u64,
+
+    /// Port of the remote node.
+    remote_port: u64,
+
+    /// TX channel for sending data to the remote end.
+    tx: mpsc::Sender<Chunk>,
+
+    /// RX channel for receiving data from the remote end.
+    rx: mpsc::Receiver<Chunk>,
+
+    /// Data received from the remote end that hasn't been read yet.
+    read_buffer: Bytes,
+
+    /// Delay until `read_buffer` becomes readable.
+    delay: Option<Pin<Box<Sleep>>>,
+}
+
+impl MemoryStream {
+    /// Create a connected pair of streams.
+    ///
+    /// The first stream belongs to the node listening on `local_port` and the second to the node
+    /// listening on `remote_port`. Each stream is subject to the faults of its own node.
+    pub(crate) fn pair(
+        local_port: u64,
+        local_faults: Faults,
+        remote_port: u64,
+        remote_faults: Faults,
+    ) -> (Self, Self) {
+        let (local_tx, remote_rx) = mpsc::channel(WRITE_BUFFER_SIZE);
+        let (remote_tx, local_rx) = mpsc::channel(WRITE_BUFFER_SIZE);
+
+        (
+            Self::new(local_faults, remote_port, local_tx, local_rx),
+            Self::new(remote_faults, local_port, remote_tx, remote_rx),
+        )
+    }
+
+    fn new(
+        faults: Faults,
+        remote_port: u64,
+        tx: mpsc::Sender<Chunk>,
+        rx: mpsc::Receiver<Chunk>,
+    ) -> Self {
+        let (stream_id, epoch) = faults.register_stream();
+
+        Self {
+            faults,
+            stream_id,
+            epoch,
+            remote_port,
+            tx,
+            rx,
+            read_buffer: Bytes::new(),
+            delay: None,
+        }
+    }
+
+    /// Apply the faults of the local node to the stream.
+    ///
+    /// Returns `Poll::Pending` while the node is partitioned from the remote node.
+    fn poll_faults(&self, interest: Interest, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.faults.poll_stream(
+            self.stream_id,
+            self.epoch,
+            self.remote_port,
+            interest,
+            cx.waker(),
+        ) {
+            StreamState::Open => Poll::Ready(Ok(())),
+            StreamState::Partitioned => Poll::Pending,
+            StreamState::Dropped => Poll::Ready(Err(io::ErrorKind::ConnectionReset.into())),
+        }
+    }
+}
+
+impl Drop for MemoryStream {
+    fn drop(&mut self) {
+        self.faults.unregister_stream(self.stream_id);
+    }
+}
+
+impl AsyncRead for MemoryStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        loop {
+            ready!(self.poll_faults(Interest::Read, cx))?;
+
+            if let Some(delay) = self.delay.as_mut() {
+                ready!(delay.as_mut().poll(cx));
+                self.delay = None;
+            }
+
+            if !self.read_buffer.is_empty() {
+                let len = std::cmp::min(self.read_buffer.len(), buf.remaining());
+                buf.put_slice(&self.read_buffer[..len]);
+                self.read_buffer.advance(len);
+
+                return Poll::Ready(Ok(()));
+            }
+
+            match ready!(self.rx.poll_next_unpin(cx)) {
+                None => return Poll::Ready(Ok(())),
+                Some(Chunk { readable_at, data }) => {
+                    self.delay = readable_at
+                        .filter(|readable_at| *readable_at > Instant::now())
+                        .map(|readable_at| Box::pin(tokio::time::sleep_until(readable_at)));
+                    self.read_buffer = data;
+                }
+            }
+        }
+    }
+}
+
+impl AsyncWrite for MemoryStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        ready!(self.poll_faults(Interest::Write, cx))?;
+        ready!(self.tx.poll_ready_unpin(cx)).map_err(|_| io::ErrorKind::BrokenPipe)?;
+
+        let chunk = Chunk {
+            readable_at: self.faults.latency().map(|latency| Instant::now() + latency),
+            data: Bytes::copy_from_slice(buf),
+        };
+        self.tx.start_send_unpin(chunk).map_err(|_| io::ErrorKind::BrokenPipe)?;
+
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_faults(Interest::Write, cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.tx.close_channel();
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn read_and_write() {
+        let (mut stream1, mut stream2) = MemoryStream::pair(1, Faults::new(), 2, Faults::new());
+
+        stream1.write_all(b"hello, world").await.unwrap();
+
+        let mut buffer = [0u8; 5];
+        stream2.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b"hello");
+
+        let mut buffer = [0u8; 7];
+        stream2.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b", world");
+
+        stream2.shutdown().await.unwrap();
+        assert_eq!(stream1.read(&mut buffer).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn latency_delays_reads() {
+        let faults = Faults::new();
+        faults.set_latency(Some(Duration::from_millis(300)));
+
+        let (mut stream1, mut stream2) = MemoryStream::pair(1, faults, 2, Faults::new());
+        let started = Instant::now();
+
+        stream1.write_all(b"hello").await.unwrap();
+
+        let mut buffer = [0u8; 5];
+        stream2.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b"hello");
+        assert!(started.elapsed() >= Duration::from_millis(300));
+
+        // the remote node has no latency configured
+        let started = Instant::now();
+        stream2.write_all(b"world").await.unwrap();
+        stream1.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b"world");
+        assert!(started.elapsed() < Duration::from_millis(300));
+    }
+
+    #[tokio::test]
+    async fn partition_holds_data_back() {
+        let faults = Faults::new();
+        let (mut stream1, mut stream2) = MemoryStream::pair(1, faults.clone(), 2, Faults::new());
+
+        stream2.write_all(b"hello").await.unwrap();
+        faults.partition(2);
+
+        let mut buffer = [0u8; 5];
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), stream1.read(&mut buffer))
+                .await
+                .is_err()
+        );
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), stream1.write(b"world"))
+                .await
+                .is_err()
+        );
+
+        faults.heal(2);
+        stream1.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b"hello");
+    }
+
+    #[tokio::test]
+    async fn dropped_stream_is_reset() {
+        let faults = Faults::new();
+        let (mut stream1, mut stream2) = MemoryStream::pair(1, faults.clone(), 2, Faults::new());
+
+        faults.drop_connections();
+
+        assert_eq!(
+            stream1.write_all(b"hello").await.unwrap_err().kind(),
+            io::ErrorKind::ConnectionReset
+        );
+
+        drop(stream1);
+        let mut buffer = [0u8; 5];
+        assert_eq!(stream2.read(&mut buffer).await.unwrap(), 0);
+    }
+}
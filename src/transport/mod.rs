@@ -20,17 +20,26 @@
 
 //! Transport protocol implementations provided by [`Litep2p`](`crate::Litep2p`).
 
-use crate::{error::DialError, transport::manager::TransportHandle, types::ConnectionId, PeerId};
+use crate::{
+    config::Role,
+    error::{DialError, Error},
+    types::{ConnectionId, ListenerId},
+    PeerId,
+};
 
 use futures::Stream;
-use multiaddr::Multiaddr;
+use multiaddr::{Multiaddr, Protocol};
 
 use std::{fmt::Debug, time::Duration};
 
 pub(crate) mod common;
+pub mod custom;
+pub mod memory;
 #[cfg(feature = "quic")]
 pub mod quic;
 pub mod tcp;
+#[cfg(unix)]
+pub mod unix;
 #[cfg(feature = "webrtc")]
 pub mod webrtc;
 #[cfg(feature = "websocket")]
@@ -38,8 +47,15 @@ pub mod websocket;
 
 pub(crate) mod dummy;
 pub(crate) mod manager;
+pub(crate) mod relay;
 
-pub use manager::limits::{ConnectionLimitsConfig, ConnectionLimitsError};
+pub use common::{dns::DnsConfig, muxer::StreamMuxer, security::SecurityProtocol};
+pub use manager::{
+    dial_scheduler::DialSchedulerConfig,
+    limits::{ConnectionLimitsConfig, ConnectionLimitsError},
+    SupportedTransport, TransportHandle,
+};
+pub use tcp::connection::NegotiatedConnection;
 
 /// Timeout for opening a connection.
 pub(crate) const CONNECTION_OPEN_TIMEOUT: Duration = Duration::from_secs(10);
@@ -85,7 +101,7 @@ impl Endpoint {
     }
 
     /// Crate dialer.
-    pub(crate) fn dialer(address: Multiaddr, connection_id: ConnectionId) -> Self {
+    pub fn dialer(address: Multiaddr, connection_id: ConnectionId) -> Self {
         Endpoint::Dialer {
             address,
             connection_id,
@@ -93,7 +109,7 @@ impl Endpoint {
     }
 
     /// Create listener.
-    pub(crate) fn listener(address: Multiaddr, connection_id: ConnectionId) -> Self {
+    pub fn listener(address: Multiaddr, connection_id: ConnectionId) -> Self {
         Endpoint::Listener {
             address,
             connection_id,
@@ -112,11 +128,22 @@ impl Endpoint {
     pub fn is_listener(&self) -> bool {
         std::matches!(self, Self::Listener { .. })
     }
+
+    /// Is the connection relayed through a circuit relay?
+    pub fn is_relayed(&self) -> bool {
+        self.address()
+            .iter()
+            .any(|protocol| std::matches!(protocol, Protocol::P2pCircuit))
+    }
 }
 
 /// Transport event.
+///
+/// Connections are closed through the connection's protocol set, so transports must not emit
+/// [`TransportEvent::ConnectionClosed`]. Such events are ignored.
 #[derive(Debug)]
-pub(crate) enum TransportEvent {
+#[non_exhaustive]
+pub enum TransportEvent {
     /// Fully negotiated connection established to remote peer.
     ConnectionEstablished {
         /// Peer ID.
@@ -126,6 +153,10 @@ pub(crate) enum TransportEvent {
         endpoint: Endpoint,
     },
 
+    /// Inbound connection received but not yet negotiated.
+    ///
+    /// The connection is negotiated only if it's accepted with
+    /// [`Transport::accept_pending()`].
     PendingInboundConnection {
         /// Connection ID.
         connection_id: ConnectionId,
@@ -141,7 +172,6 @@ pub(crate) enum TransportEvent {
     },
 
     /// Connection closed to remote peer.
-    #[allow(unused)]
     ConnectionClosed {
         /// Peer ID.
         peer: PeerId,
@@ -170,10 +200,36 @@ pub(crate) enum TransportEvent {
         /// Errors.
         errors: Vec<(Multiaddr, DialError)>,
     },
+
+    /// Listener is reachable at a new address.
+    ///
+    /// Reported when the addresses of a listener bound to an unspecified address change.
+    /// Listeners started from the transport configuration get their IDs from
+    /// [`TransportHandle::next_listener_id()`].
+    NewListenAddress {
+        /// ID of the listener.
+        listener_id: ListenerId,
+
+        /// Listen address.
+        address: Multiaddr,
+    },
+
+    /// Listener is no longer reachable at the address.
+    ListenAddressExpired {
+        /// ID of the listener.
+        listener_id: ListenerId,
+
+        /// Listen address.
+        address: Multiaddr,
+    },
 }
 
-pub(crate) trait TransportBuilder {
+/// Builder for a transport.
+pub trait TransportBuilder {
+    /// Transport configuration.
     type Config: Debug;
+
+    /// Transport type.
     type Transport: Transport;
 
     /// Create new [`Transport`] object.
@@ -182,10 +238,61 @@ pub(crate) trait TransportBuilder {
         Self: Sized;
 }
 
-pub(crate) trait Transport: Stream + Unpin + Send {
+/// Transport.
+///
+/// The transport reports its connections to [`Litep2p`](crate::Litep2p) as [`TransportEvent`]s
+/// and [`Litep2p`](crate::Litep2p) decides, using the connection limits and the state of the
+/// remote peer, whether the connections are accepted or rejected.
+///
+/// Outbound connections go through the following steps:
+///  1. [`Transport::dial()`] is called for a single address or [`Transport::open()`] for a set of
+///     addresses, the latter of which reports [`TransportEvent::ConnectionOpened`] or
+///     [`TransportEvent::OpenFailure`]. An opened connection is negotiated once
+///     [`Transport::negotiate()`] is called.
+///  2. The negotiated connection is reported with [`TransportEvent::ConnectionEstablished`] or the
+///     failure with [`TransportEvent::DialFailure`].
+///  3. The connection is started with [`Transport::accept()`] or dropped with
+///     [`Transport::reject()`].
+///
+/// Inbound connections are reported with [`TransportEvent::PendingInboundConnection`] and are
+/// negotiated if they're accepted with [`Transport::accept_pending()`], after which they follow
+/// step 2 onwards.
+pub trait Transport: Stream + Unpin + Send {
     /// Dial `address` and negotiate connection.
     fn dial(&mut self, connection_id: ConnectionId, address: Multiaddr) -> crate::Result<()>;
 
+    /// Dial `addresses` of a peer at once as one side of a simultaneous open and negotiate the
+    /// connection in `role`.
+    ///
+    /// Used for hole punching where both peers dial each other at the same time and must agree
+    /// on who acts as the dialer of the resulting connection. The first connection negotiated
+    /// over any of the addresses is reported with [`TransportEvent::ConnectionEstablished`] and
+    /// the rest are dropped. If all addresses fail, the failure is reported with
+    /// [`TransportEvent::DialFailure`]. The dial can be canceled with [`Transport::cancel()`].
+    ///
+    /// Transports which don't support simultaneous opens return [`Error::NotSupported`].
+    fn dial_with_role(
+        &mut self,
+        connection_id: ConnectionId,
+        addresses: Vec<Multiaddr>,
+        role: Role,
+    ) -> crate::Result<()> {
+        let _ = (connection_id, role);
+        Err(Error::NotSupported(format!(
+            "cannot dial {addresses:?} as one side of a simultaneous open"
+        )))
+    }
+
+    /// Punch holes into the NATs between the local node and `addresses` without dialing them.
+    ///
+    /// The remote peer is expected to dial the local node through the holes right after.
+    /// Transports which don't support hole punching return [`Error::NotSupported`].
+    fn hole_punch(&mut self, addresses: Vec<Multiaddr>) -> crate::Result<()> {
+        Err(Error::NotSupported(format!(
+            "cannot punch holes for {addresses:?}"
+        )))
+    }
+
     /// Accept negotiated connection.
     fn accept(&mut self, connection_id: ConnectionId) -> crate::Result<()>;
 
@@ -209,4 +316,24 @@ pub(crate) trait Transport: Stream + Unpin + Send {
     ///
     /// This is a no-op for connections that have already succeeded/canceled.
     fn cancel(&mut self, connection_id: ConnectionId);
+
+    /// Start listening on `address`.
+    ///
+    /// Returns the addresses the new listener is reachable at. Transports which can't start
+    /// listeners after they've been created return [`Error::NotSupported`].
+    fn listen_on(
+        &mut self,
+        listener_id: ListenerId,
+        address: Multiaddr,
+    ) -> crate::Result<Vec<Multiaddr>> {
+        let _ = listener_id;
+        Err(Error::NotSupported(format!(
+            "cannot start listener for `{address}`"
+        )))
+    }
+
+    /// Stop the listener started with [`Transport::listen_on()`].
+    fn stop_listening(&mut self, listener_id: ListenerId) -> crate::Result<()> {
+        Err(Error::ListenerDoesntExist(listener_id))
+    }
 }
@@ -26,10 +26,13 @@ use crate::{
 
 use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, Stream, StreamExt};
 use multiaddr::{Multiaddr, Protocol};
-use quinn::{Connecting, Endpoint, ServerConfig};
+use quinn::{
+    crypto::rustls::QuicServerConfig, Connecting, Endpoint, EndpointConfig, Incoming, ServerConfig,
+    TokioRuntime,
+};
 
 use std::{
-    net::{IpAddr, SocketAddr},
+    net::{IpAddr, SocketAddr, UdpSocket},
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
@@ -41,13 +44,16 @@ const LOG_TARGET: &str = "litep2p::quic::listener";
 /// QUIC listener.
 pub struct QuicListener {
     /// Listen addresses.
-    _listen_addresses: Vec<SocketAddr>,
+    listen_addresses: Vec<SocketAddr>,
 
     /// Listeners.
     listeners: Vec<Endpoint>,
 
+    /// Sockets of the listeners, used for hole punching.
+    sockets: Vec<UdpSocket>,
+
     /// Incoming connections.
-    incoming: FuturesUnordered<BoxFuture<'static, Option<(usize, Connecting)>>>,
+    incoming: FuturesUnordered<BoxFuture<'static, Option<(usize, Incoming)>>>,
 }
 
 impl QuicListener {
@@ -57,18 +63,27 @@ impl QuicListener {
         addresses: Vec<Multiaddr>,
     ) -> crate::Result<(Self, Vec<Multiaddr>)> {
         let mut listeners: Vec<Endpoint> = Vec::new();
+        let mut sockets = Vec::new();
         let mut listen_addresses = Vec::new();
 
         for address in addresses.into_iter() {
             let (listen_address, _) = Self::get_socket_address(&address)?;
-            let crypto_config = Arc::new(make_server_config(keypair).expect("to succeed"));
-            let server_config = ServerConfig::with_crypto(crypto_config);
-            let listener = Endpoint::server(server_config, listen_address).unwrap();
+            let crypto_config =
+                QuicServerConfig::try_from(make_server_config(keypair).expect("to succeed"))
+                    .expect("initial cipher suite is supported; qed");
+            let server_config = ServerConfig::with_crypto(Arc::new(crypto_config));
+            let socket = UdpSocket::bind(listen_address)?;
+            let listener = Endpoint::new(
+                EndpointConfig::default(),
+                Some(server_config),
+                socket.try_clone()?,
+                Arc::new(TokioRuntime),
+            )?;
 
             let listen_address = listener.local_addr()?;
             listen_addresses.push(listen_address);
             listeners.push(listener);
-            // );
+            sockets.push(socket);
         }
 
         let listen_multi_addresses = listen_addresses
@@ -89,17 +104,37 @@ impl QuicListener {
                     .enumerate()
                     .map(|(i, listener)| {
                         let inner = listener.clone();
-                        async move { inner.accept().await.map(|connecting| (i, connecting)) }
-                            .boxed()
+                        async move { inner.accept().await.map(|incoming| (i, incoming)) }.boxed()
                     })
                     .collect(),
                 listeners,
-                _listen_addresses: listen_addresses,
+                sockets,
+                listen_addresses,
             },
             listen_multi_addresses,
         ))
     }
 
+    /// Get the index of the listener with the same IP version as `remote_address`.
+    fn listener_index(&self, remote_address: &SocketAddr) -> Option<usize> {
+        self.listen_addresses
+            .iter()
+            .position(|address| address.is_ipv4() == remote_address.is_ipv4())
+    }
+
+    /// Get the listener endpoint for dialing `remote_address`.
+    ///
+    /// Connections dialed from the listener endpoint use the listen port and can therefore pass
+    /// the holes the remote peer punched towards the listen address.
+    pub fn endpoint(&self, remote_address: &SocketAddr) -> Option<&Endpoint> {
+        self.listener_index(remote_address).map(|index| &self.listeners[index])
+    }
+
+    /// Get the listener socket for punching a hole towards `remote_address`.
+    pub fn socket(&self, remote_address: &SocketAddr) -> Option<&UdpSocket> {
+        self.listener_index(remote_address).map(|index| &self.sockets[index])
+    }
+
     /// Extract socket address and `PeerId`, if found, from `address`.
     pub fn get_socket_address(
         address: &Multiaddr,
@@ -163,21 +198,30 @@ impl Stream for QuicListener {
     type Item = Connecting;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        if self.incoming.is_empty() {
-            return Poll::Pending;
-        }
+        loop {
+            if self.incoming.is_empty() {
+                return Poll::Pending;
+            }
 
-        match futures::ready!(self.incoming.poll_next_unpin(cx)) {
-            None => Poll::Ready(None),
-            Some(None) => Poll::Ready(None),
-            Some(Some((listener, future))) => {
-                let inner = self.listeners[listener].clone();
-                self.incoming.push(
-                    async move { inner.accept().await.map(|connecting| (listener, connecting)) }
-                        .boxed(),
-                );
+            match futures::ready!(self.incoming.poll_next_unpin(cx)) {
+                None => return Poll::Ready(None),
+                Some(None) => return Poll::Ready(None),
+                Some(Some((listener, incoming))) => {
+                    let inner = self.listeners[listener].clone();
+                    self.incoming.push(
+                        async move { inner.accept().await.map(|incoming| (listener, incoming)) }
+                            .boxed(),
+                    );
 
-                Poll::Ready(Some(future))
+                    match incoming.accept() {
+                        Ok(connecting) => return Poll::Ready(Some(connecting)),
+                        Err(error) => tracing::debug!(
+                            target: LOG_TARGET,
+                            ?error,
+                            "failed to accept inbound connection",
+                        ),
+                    }
+                }
             }
         }
     }
@@ -188,7 +232,7 @@ mod tests {
     use crate::crypto::tls::make_client_config;
 
     use super::*;
-    use quinn::ClientConfig;
+    use quinn::{crypto::rustls::QuicClientConfig, ClientConfig};
     use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 
     #[test]
@@ -267,9 +311,11 @@ mod tests {
             panic!("invalid address");
         };
 
-        let crypto_config =
-            Arc::new(make_client_config(&Keypair::generate(), Some(peer)).expect("to succeed"));
-        let client_config = ClientConfig::new(crypto_config);
+        let crypto_config = QuicClientConfig::try_from(
+            make_client_config(&Keypair::generate(), Some(peer)).expect("to succeed"),
+        )
+        .expect("to succeed");
+        let client_config = ClientConfig::new(Arc::new(crypto_config));
         let client =
             Endpoint::client(SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0)).unwrap();
         let connection = client
@@ -315,9 +361,11 @@ mod tests {
             panic!("invalid address");
         };
 
-        let crypto_config1 =
-            Arc::new(make_client_config(&Keypair::generate(), Some(peer)).expect("to succeed"));
-        let client_config1 = ClientConfig::new(crypto_config1);
+        let crypto_config1 = QuicClientConfig::try_from(
+            make_client_config(&Keypair::generate(), Some(peer)).expect("to succeed"),
+        )
+        .expect("to succeed");
+        let client_config1 = ClientConfig::new(Arc::new(crypto_config1));
         let client1 =
             Endpoint::client(SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0)).unwrap();
         let connection1 = client1
@@ -328,9 +376,11 @@ mod tests {
             )
             .unwrap();
 
-        let crypto_config2 =
-            Arc::new(make_client_config(&Keypair::generate(), Some(peer)).expect("to succeed"));
-        let client_config2 = ClientConfig::new(crypto_config2);
+        let crypto_config2 = QuicClientConfig::try_from(
+            make_client_config(&Keypair::generate(), Some(peer)).expect("to succeed"),
+        )
+        .expect("to succeed");
+        let client_config2 = ClientConfig::new(Arc::new(crypto_config2));
         let client2 =
             Endpoint::client(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0)).unwrap();
         let connection2 = client2
@@ -384,9 +434,11 @@ mod tests {
             panic!("invalid address");
         };
 
-        let crypto_config1 =
-            Arc::new(make_client_config(&Keypair::generate(), Some(peer)).expect("to succeed"));
-        let client_config1 = ClientConfig::new(crypto_config1);
+        let crypto_config1 = QuicClientConfig::try_from(
+            make_client_config(&Keypair::generate(), Some(peer)).expect("to succeed"),
+        )
+        .expect("to succeed");
+        let client_config1 = ClientConfig::new(Arc::new(crypto_config1));
         let client1 =
             Endpoint::client(SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0)).unwrap();
         let connection1 = client1
@@ -397,9 +449,11 @@ mod tests {
             )
             .unwrap();
 
-        let crypto_config2 =
-            Arc::new(make_client_config(&Keypair::generate(), Some(peer)).expect("to succeed"));
-        let client_config2 = ClientConfig::new(crypto_config2);
+        let crypto_config2 = QuicClientConfig::try_from(
+            make_client_config(&Keypair::generate(), Some(peer)).expect("to succeed"),
+        )
+        .expect("to succeed");
+        let client_config2 = ClientConfig::new(Arc::new(crypto_config2));
         let client2 =
             Endpoint::client(SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0)).unwrap();
         let connection2 = client2
@@ -23,10 +23,11 @@
 //! QUIC transport.
 
 use crate::{
+    config::Role,
     crypto::tls::make_client_config,
     error::{AddressError, DialError, Error, QuicError},
     transport::{
-        manager::TransportHandle,
+        manager::{dial_scheduler::staggered, TransportHandle},
         quic::{config::Config as QuicConfig, connection::QuicConnection, listener::QuicListener},
         Endpoint as Litep2pEndpoint, Transport, TransportBuilder, TransportEvent,
     },
@@ -40,7 +41,11 @@ use futures::{
     Stream, StreamExt, TryFutureExt,
 };
 use multiaddr::{Multiaddr, Protocol};
-use quinn::{ClientConfig, Connecting, Connection, Endpoint, IdleTimeout};
+use quinn::{
+    crypto::rustls::QuicClientConfig, ClientConfig, Connecting, Connection, Endpoint, IdleTimeout,
+};
+use rand::{Rng, RngCore};
+use rustls::pki_types::CertificateDer;
 
 use std::{
     collections::HashMap,
@@ -48,6 +53,7 @@ use std::{
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
+    time::Duration,
 };
 
 pub(crate) use substream::Substream;
@@ -61,6 +67,12 @@ pub mod config;
 /// Logging target for the file.
 const LOG_TARGET: &str = "litep2p::quic";
 
+/// How long random packets are sent to the remote peer when punching holes.
+const HOLE_PUNCH_DURATION: Duration = Duration::from_secs(5);
+
+/// Size of the random packets sent when punching holes.
+const HOLE_PUNCH_PACKET_SIZE: usize = 64;
+
 #[derive(Debug)]
 struct NegotiatedConnection {
     /// Remote peer ID.
@@ -122,14 +134,15 @@ pub(crate) struct QuicTransport {
 
     /// Cancel raw connections futures.
     ///
-    /// This is cancelling `Self::pending_raw_connections`.
+    /// This is cancelling `Self::pending_raw_connections` and the simultaneous opens of
+    /// `Self::pending_connections`.
     cancel_futures: HashMap<ConnectionId, AbortHandle>,
 }
 
 impl QuicTransport {
     /// Attempt to extract `PeerId` from connection certificates.
     fn extract_peer_id(connection: &Connection) -> Option<PeerId> {
-        let certificates: Box<Vec<rustls::Certificate>> =
+        let certificates: Box<Vec<CertificateDer<'static>>> =
             connection.peer_identity()?.downcast().ok()?;
         let p2p_cert = crate::crypto::tls::certificate::parse(certificates.first()?)
             .expect("the certificate was validated during TLS handshake; qed");
@@ -137,6 +150,22 @@ impl QuicTransport {
         Some(p2p_cert.peer_id())
     }
 
+    /// Create client configuration for dialing `peer`.
+    fn client_config(&self, peer: PeerId) -> ClientConfig {
+        let crypto_config = QuicClientConfig::try_from(
+            make_client_config(&self.context.keypair, Some(peer)).expect("to succeed"),
+        )
+        .expect("initial cipher suite is supported; qed");
+        let mut transport_config = quinn::TransportConfig::default();
+        let timeout =
+            IdleTimeout::try_from(self.config.connection_open_timeout).expect("to succeed");
+        transport_config.max_idle_timeout(Some(timeout));
+        let mut client_config = ClientConfig::new(Arc::new(crypto_config));
+        client_config.transport_config(Arc::new(transport_config));
+
+        client_config
+    }
+
     /// Handle inbound accepted connection.
     fn on_inbound_connection(&mut self, connection_id: ConnectionId, connection: Connecting) {
         self.pending_connections.push(Box::pin(async move {
@@ -254,15 +283,7 @@ impl Transport for QuicTransport {
             return Err(Error::AddressError(AddressError::PeerIdMissing));
         };
 
-        let crypto_config =
-            Arc::new(make_client_config(&self.context.keypair, Some(peer)).expect("to succeed"));
-        let mut transport_config = quinn::TransportConfig::default();
-        let timeout =
-            IdleTimeout::try_from(self.config.connection_open_timeout).expect("to succeed");
-        transport_config.max_idle_timeout(Some(timeout));
-        let mut client_config = ClientConfig::new(crypto_config);
-        client_config.transport_config(Arc::new(transport_config));
-
+        let client_config = self.client_config(peer);
         let client_listen_address = match address.iter().next() {
             Some(Protocol::Ip6(_)) => SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0),
             Some(Protocol::Ip4(_)) => SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0),
@@ -304,6 +325,137 @@ impl Transport for QuicTransport {
         Ok(())
     }
 
+    fn dial_with_role(
+        &mut self,
+        connection_id: ConnectionId,
+        addresses: Vec<Multiaddr>,
+        role: Role,
+    ) -> crate::Result<()> {
+        // QUIC connections can't be opened by both peers at once, the listener of the
+        // simultaneous open punches holes with `hole_punch()` for the dialer to pass instead
+        if std::matches!(role, Role::Listener) {
+            return Err(Error::NotSupported(
+                "quic punches holes instead of dialing as listener".to_string(),
+            ));
+        }
+
+        let Some(first_address) = addresses.first().cloned() else {
+            return Err(Error::AddressError(AddressError::AddressNotAvailable));
+        };
+
+        let dials = addresses
+            .iter()
+            .map(|address| {
+                let Ok((socket_address, Some(peer))) = QuicListener::get_socket_address(address)
+                else {
+                    return Err(Error::AddressError(AddressError::PeerIdMissing));
+                };
+
+                // dial from the listen port for the connection to pass the holes punched by the
+                // remote peer
+                let endpoint = match self.listener.endpoint(&socket_address) {
+                    Some(endpoint) => endpoint.clone(),
+                    None => {
+                        let client_listen_address = match socket_address {
+                            SocketAddr::V4(_) =>
+                                SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0),
+                            SocketAddr::V6(_) =>
+                                SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0),
+                        };
+
+                        Endpoint::client(client_listen_address)
+                            .map_err(|error| Error::Other(error.to_string()))?
+                    }
+                };
+                let connection = endpoint
+                    .connect_with(self.client_config(peer), socket_address, "l")
+                    .map_err(|error| Error::Other(error.to_string()))?;
+
+                Ok(Box::pin(async move {
+                    let connection = connection.await.map_err(DialError::from)?;
+
+                    match Self::extract_peer_id(&connection) {
+                        Some(peer) => Ok(NegotiatedConnection { peer, connection }),
+                        None => Err(crate::error::NegotiationError::Quic(
+                            QuicError::InvalidCertificate,
+                        )
+                        .into()),
+                    }
+                }))
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        tracing::trace!(
+            target: LOG_TARGET,
+            ?connection_id,
+            ?addresses,
+            "dial peer as one side of a simultaneous open",
+        );
+
+        // the first connection established over any of the addresses wins
+        let (future, handle) = futures::future::abortable(futures::future::select_ok(dials));
+
+        self.pending_dials.insert(connection_id, first_address);
+        self.cancel_futures.insert(connection_id, handle);
+        self.pending_connections.push(Box::pin(async move {
+            match future.await {
+                Ok(Ok((connection, _))) => (connection_id, Ok(connection)),
+                Ok(Err(error)) => (connection_id, Err(error)),
+                // a canceled dial is not reported as its pending dial is removed in `cancel()`
+                Err(_) => (connection_id, Err(DialError::Timeout)),
+            }
+        }));
+
+        Ok(())
+    }
+
+    fn hole_punch(&mut self, addresses: Vec<Multiaddr>) -> crate::Result<()> {
+        let targets = addresses
+            .iter()
+            .filter_map(|address| {
+                let (socket_address, _) = QuicListener::get_socket_address(address).ok()?;
+                let socket = self.listener.socket(&socket_address)?.try_clone().ok()?;
+
+                Some((socket, socket_address))
+            })
+            .collect::<Vec<_>>();
+
+        if targets.is_empty() {
+            return Err(Error::NotSupported(format!(
+                "no quic listener to punch holes for {addresses:?}"
+            )));
+        }
+
+        tracing::trace!(target: LOG_TARGET, ?addresses, "punch holes");
+
+        // send random packets from the listen sockets until the remote peer has had time to dial
+        // the local node through the holes they open in the NATs on the way
+        self.context.executor.run(Box::pin(async move {
+            let deadline = tokio::time::Instant::now() + HOLE_PUNCH_DURATION;
+
+            while tokio::time::Instant::now() < deadline {
+                for (socket, address) in &targets {
+                    let mut packet = [0u8; HOLE_PUNCH_PACKET_SIZE];
+                    rand::thread_rng().fill_bytes(&mut packet);
+
+                    if let Err(error) = socket.send_to(&packet, address) {
+                        tracing::trace!(
+                            target: LOG_TARGET,
+                            ?address,
+                            ?error,
+                            "failed to send hole punching packet",
+                        );
+                    }
+                }
+
+                let delay = Duration::from_millis(rand::thread_rng().gen_range(10..=200));
+                tokio::time::sleep(delay).await;
+            }
+        }));
+
+        Ok(())
+    }
+
     fn accept(&mut self, connection_id: ConnectionId) -> crate::Result<()> {
         let (connection, endpoint) = self
             .pending_open
@@ -363,8 +515,7 @@ impl Transport for QuicTransport {
         connection_id: ConnectionId,
         addresses: Vec<Multiaddr>,
     ) -> crate::Result<()> {
-        let num_addresses = addresses.len();
-        let mut futures: FuturesUnordered<_> = addresses
+        let attempts = addresses
             .into_iter()
             .map(|address| {
                 let keypair = self.context.keypair.clone();
@@ -377,13 +528,15 @@ impl Transport for QuicTransport {
                     let peer =
                         peer.ok_or_else(|| DialError::AddressError(AddressError::PeerIdMissing))?;
 
-                    let crypto_config =
-                        Arc::new(make_client_config(&keypair, Some(peer)).expect("to succeed"));
+                    let crypto_config = QuicClientConfig::try_from(
+                        make_client_config(&keypair, Some(peer)).expect("to succeed"),
+                    )
+                    .expect("initial cipher suite is supported; qed");
                     let mut transport_config = quinn::TransportConfig::default();
                     let timeout =
                         IdleTimeout::try_from(connection_open_timeout).expect("to succeed");
                     transport_config.max_idle_timeout(Some(timeout));
-                    let mut client_config = ClientConfig::new(crypto_config);
+                    let mut client_config = ClientConfig::new(Arc::new(crypto_config));
                     client_config.transport_config(Arc::new(transport_config));
 
                     let client_listen_address = match address.iter().next() {
@@ -422,36 +575,32 @@ impl Transport for QuicTransport {
 
                 async move { future.await.map(|ok| (addr.clone(), ok)).map_err(|err| (addr, err)) }
             })
-            .collect();
+            .collect::<Vec<_>>();
+
+        let connection_attempt_delay = self.context.connection_attempt_delay;
 
         // Future that will resolve to the first successful connection.
         let future = async move {
-            let mut errors = Vec::with_capacity(num_addresses);
-
-            while let Some(result) = futures.next().await {
-                match result {
-                    Ok((address, stream)) =>
-                        return RawConnectionResult::Connected {
-                            connection_id,
-                            address,
-                            stream,
-                        },
-                    Err(error) => {
-                        tracing::debug!(
-                            target: LOG_TARGET,
-                            ?connection_id,
-                            ?error,
-                            "failed to open connection",
-                        );
-                        errors.push(error)
+            match staggered(attempts, connection_attempt_delay).await {
+                Ok((address, stream)) => RawConnectionResult::Connected {
+                    connection_id,
+                    address,
+                    stream,
+                },
+                Err(errors) => {
+                    tracing::debug!(
+                        target: LOG_TARGET,
+                        ?connection_id,
+                        ?errors,
+                        "failed to open connection",
+                    );
+
+                    RawConnectionResult::Failed {
+                        connection_id,
+                        errors,
                     }
                 }
             }
-
-            RawConnectionResult::Failed {
-                connection_id,
-                errors,
-            }
         };
 
         let (fut, handle) = futures::future::abortable(future);
@@ -480,6 +629,7 @@ impl Transport for QuicTransport {
         // State clean-up happens inside the `poll_next`.
         if let Some(handle) = self.cancel_futures.get(&connection_id) {
             handle.abort();
+            self.pending_dials.remove(&connection_id);
         }
     }
 }
@@ -569,6 +719,7 @@ impl Stream for QuicTransport {
 
         while let Poll::Ready(Some(connection)) = self.pending_connections.poll_next_unpin(cx) {
             let (connection_id, result) = connection;
+            self.cancel_futures.remove(&connection_id);
 
             match self.on_connection_established(connection_id, result) {
                 Some(event) => return Poll::Ready(Some(event)),
@@ -608,7 +759,11 @@ mod tests {
             executor: Arc::new(DefaultExecutor {}),
             next_substream_id: Default::default(),
             next_connection_id: Default::default(),
+            next_listener_id: Default::default(),
             keypair: keypair1.clone(),
+            pre_shared_key: None,
+            resolver: Default::default(),
+            connection_attempt_delay: Default::default(),
             tx: event_tx1,
             bandwidth_sink: BandwidthSink::new(),
 
@@ -634,7 +789,11 @@ mod tests {
             executor: Arc::new(DefaultExecutor {}),
             next_substream_id: Default::default(),
             next_connection_id: Default::default(),
+            next_listener_id: Default::default(),
             keypair: keypair2.clone(),
+            pre_shared_key: None,
+            resolver: Default::default(),
+            connection_attempt_delay: Default::default(),
             tx: event_tx2,
             bandwidth_sink: BandwidthSink::new(),
 
@@ -676,4 +835,117 @@ mod tests {
             Some(TransportEvent::ConnectionEstablished { .. })
         ));
     }
+
+    fn quic_transport() -> (QuicTransport, Multiaddr) {
+        let keypair = Keypair::generate();
+        let peer = PeerId::from_public_key(&keypair.public().into());
+        let (tx, _rx) = channel(64);
+        let (event_tx, _event_rx) = channel(64);
+
+        let handle = TransportHandle {
+            executor: Arc::new(DefaultExecutor {}),
+            next_substream_id: Default::default(),
+            next_connection_id: Default::default(),
+            next_listener_id: Default::default(),
+            keypair,
+            pre_shared_key: None,
+            resolver: Default::default(),
+            connection_attempt_delay: Default::default(),
+            tx: event_tx,
+            bandwidth_sink: BandwidthSink::new(),
+
+            protocols: HashMap::from_iter([(
+                ProtocolName::from("/notif/1"),
+                ProtocolContext {
+                    tx,
+                    codec: ProtocolCodec::Identity(32),
+                    fallback_names: Vec::new(),
+                },
+            )]),
+        };
+        let config = QuicConfig {
+            listen_addresses: vec!["/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap()],
+            ..Default::default()
+        };
+
+        let (transport, listen_addresses) = QuicTransport::new(handle, config).unwrap();
+        let listen_address = listen_addresses[0].clone().with(Protocol::P2p(peer.into()));
+
+        (transport, listen_address)
+    }
+
+    #[tokio::test]
+    async fn simultaneous_open_dials_from_listen_port() {
+        let (mut transport1, listen_address1) = quic_transport();
+        let (mut transport2, listen_address2) = quic_transport();
+
+        // the local node can't dial as listener, it punches holes instead
+        assert!(std::matches!(
+            transport1.dial_with_role(
+                ConnectionId::from(0usize),
+                vec![listen_address2.clone()],
+                Role::Listener
+            ),
+            Err(Error::NotSupported(_))
+        ));
+        transport1.hole_punch(vec![listen_address2.clone()]).unwrap();
+
+        // the first address is not listening
+        let unreachable = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let unreachable_address = Multiaddr::empty()
+            .with(Protocol::Ip4(Ipv4Addr::LOCALHOST))
+            .with(Protocol::Udp(unreachable.local_addr().unwrap().port()))
+            .with(Protocol::QuicV1)
+            .with(Protocol::P2p(
+                PeerId::try_from_multiaddr(&listen_address1).unwrap().into(),
+            ));
+        drop(unreachable);
+
+        transport2
+            .dial_with_role(
+                ConnectionId::from(0usize),
+                vec![unreachable_address, listen_address1],
+                Role::Dialer,
+            )
+            .unwrap();
+
+        match transport1.next().await.unwrap() {
+            TransportEvent::PendingInboundConnection { connection_id } => {
+                transport1.accept_pending(connection_id).unwrap();
+            }
+            event => panic!("unexpected event: {event:?}"),
+        }
+
+        let (event1, event2) = tokio::time::timeout(Duration::from_secs(10), async {
+            tokio::join!(transport1.next(), transport2.next())
+        })
+        .await
+        .expect("connection to be established");
+
+        // the connection was dialed from the listen port of the remote peer
+        match event1 {
+            Some(TransportEvent::ConnectionEstablished { endpoint, .. }) => {
+                let mut remote = endpoint.address().iter();
+                let mut listen = listen_address2.iter();
+                assert_eq!(remote.next(), listen.next());
+                assert_eq!(remote.next(), listen.next());
+            }
+            event => panic!("unexpected event: {event:?}"),
+        }
+        assert!(std::matches!(
+            event2,
+            Some(TransportEvent::ConnectionEstablished { .. })
+        ));
+        assert!(transport2.cancel_futures.is_empty());
+    }
+
+    #[tokio::test]
+    async fn hole_punch_requires_listener() {
+        let (mut transport, _) = quic_transport();
+
+        assert!(std::matches!(
+            transport.hole_punch(vec!["/ip6/::1/udp/8888/quic-v1".parse().unwrap()]),
+            Err(Error::NotSupported(_))
+        ));
+    }
 }
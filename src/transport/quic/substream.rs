@@ -21,26 +21,39 @@
 use crate::{error::SubstreamError, BandwidthSink};
 
 use bytes::Bytes;
-use futures::{AsyncRead, AsyncWrite};
-use quinn::{RecvStream, SendStream};
+use futures::{AsyncRead, AsyncWrite, Future};
+use quinn::{RecvStream, SendStream, StoppedError, VarInt};
 use tokio::io::{AsyncRead as TokioAsyncRead, AsyncWrite as TokioAsyncWrite};
 use tokio_util::compat::{Compat, TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
 
 use std::{
-    io,
+    fmt, io,
     pin::Pin,
     task::{Context, Poll},
 };
 
 use crate::protocol::Permit;
 
+/// Future which resolves once the remote peer has acknowledged the finished send stream.
+type StoppedFuture =
+    Pin<Box<dyn Future<Output = Result<Option<VarInt>, StoppedError>> + Send + Sync>>;
+
 /// QUIC substream.
-#[derive(Debug)]
 pub struct Substream {
     _permit: Permit,
     bandwidth_sink: BandwidthSink,
     send_stream: SendStream,
     recv_stream: RecvStream,
+    stopped: Option<StoppedFuture>,
+}
+
+impl fmt::Debug for Substream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Substream")
+            .field("send_stream", &self.send_stream)
+            .field("recv_stream", &self.recv_stream)
+            .finish_non_exhaustive()
+    }
 }
 
 impl Substream {
@@ -56,6 +69,7 @@ impl Substream {
             send_stream,
             recv_stream,
             bandwidth_sink,
+            stopped: None,
         }
     }
 
@@ -100,7 +114,11 @@ impl TokioAsyncWrite for Substream {
         cx: &mut Context<'_>,
         buf: &[u8],
     ) -> Poll<Result<usize, io::Error>> {
-        match futures::ready!(Pin::new(&mut self.send_stream).poll_write(cx, buf)) {
+        match futures::ready!(TokioAsyncWrite::poll_write(
+            Pin::new(&mut self.send_stream),
+            cx,
+            buf
+        )) {
             Err(error) => Poll::Ready(Err(error)),
             Ok(nwritten) => {
                 self.bandwidth_sink.increase_outbound(nwritten);
@@ -117,7 +135,18 @@ impl TokioAsyncWrite for Substream {
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
     ) -> Poll<Result<(), io::Error>> {
-        Pin::new(&mut self.send_stream).poll_shutdown(cx)
+        // finishing the stream only queues the FIN so wait until the remote peer has acknowledged
+        // it or stopped the stream, otherwise the substream is dropped right away and writes the
+        // remote peer made before learning about the closure fail
+        if self.stopped.is_none() {
+            self.send_stream.finish()?;
+            self.stopped = Some(Box::pin(self.send_stream.stopped()));
+        }
+
+        match futures::ready!(self.stopped.as_mut().expect("future to exist").as_mut().poll(cx)) {
+            Ok(_) => Poll::Ready(Ok(())),
+            Err(error) => Poll::Ready(Err(error.into())),
+        }
     }
 }
 
@@ -0,0 +1,77 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Circuit relay transport configuration.
+
+use crate::{
+    crypto::noise::{MAX_READ_AHEAD_FACTOR, MAX_WRITE_BUFFER_SIZE},
+    transport::{
+        relay::{CircuitRequest, InboundCircuit},
+        CONNECTION_OPEN_TIMEOUT, SUBSTREAM_OPEN_TIMEOUT,
+    },
+};
+
+use tokio::sync::mpsc::{Receiver, Sender};
+
+/// Circuit relay transport configuration.
+///
+/// Circuits are opened and accepted by the relay protocol which passes them to the transport
+/// over the channels of the configuration.
+#[derive(Debug)]
+pub(crate) struct Config {
+    /// TX channel for asking the relay protocol to open a circuit.
+    pub(crate) request_tx: Sender<CircuitRequest>,
+
+    /// RX channel for receiving circuits opened by relays to the local node.
+    pub(crate) circuit_rx: Receiver<InboundCircuit>,
+
+    /// Yamux configuration.
+    pub(crate) yamux_config: crate::yamux::Config,
+
+    /// Noise read-ahead frame count.
+    pub(crate) noise_read_ahead_frame_count: usize,
+
+    /// Noise write buffer size.
+    pub(crate) noise_write_buffer_size: usize,
+
+    /// Connection open timeout, including the time it takes to open the circuit.
+    pub(crate) connection_open_timeout: std::time::Duration,
+
+    /// Substream open timeout.
+    pub(crate) substream_open_timeout: std::time::Duration,
+}
+
+impl Config {
+    /// Create new [`Config`] with default connection settings.
+    pub(crate) fn new(
+        request_tx: Sender<CircuitRequest>,
+        circuit_rx: Receiver<InboundCircuit>,
+    ) -> Self {
+        Self {
+            request_tx,
+            circuit_rx,
+            yamux_config: Default::default(),
+            noise_read_ahead_frame_count: MAX_READ_AHEAD_FACTOR,
+            noise_write_buffer_size: MAX_WRITE_BUFFER_SIZE,
+            connection_open_timeout: CONNECTION_OPEN_TIMEOUT,
+            substream_open_timeout: SUBSTREAM_OPEN_TIMEOUT,
+        }
+    }
+}
@@ -0,0 +1,662 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Circuit relay transport.
+//!
+//! The transport doesn't open any sockets itself. Circuits are opened through relays by the
+//! relay protocol, after which the transport upgrades them to `noise` + `yamux` connections
+//! the same way the other stream transports do.
+
+use crate::{
+    config::Role,
+    error::{AddressError, Error, RelayError},
+    transport::{
+        manager::{dial_scheduler::staggered, TransportHandle},
+        relay::{
+            config::Config,
+            connection::{NegotiatedConnection, RelayConnection},
+        },
+        Transport, TransportBuilder, TransportEvent,
+    },
+    types::ConnectionId,
+    utils::futures_stream::FuturesStream,
+    DialError, PeerId,
+};
+
+use futures::{future::BoxFuture, stream::AbortHandle, Stream, StreamExt, TryFutureExt};
+use multiaddr::{Multiaddr, Protocol};
+use tokio::sync::oneshot;
+
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+pub(crate) use substream::Substream;
+
+mod connection;
+mod substream;
+
+pub(crate) mod config;
+
+/// Logging target for the file.
+const LOG_TARGET: &str = "litep2p::relay";
+
+/// Request to open a circuit to `destination` through `relay`.
+#[derive(Debug)]
+pub(crate) struct CircuitRequest {
+    /// Address of the relay, ending in `/p2p/<relay peer ID>`.
+    pub(crate) relay: Multiaddr,
+
+    /// Peer ID of the destination.
+    pub(crate) destination: PeerId,
+
+    /// TX channel for returning the opened circuit.
+    pub(crate) tx: oneshot::Sender<Result<crate::substream::Substream, RelayError>>,
+}
+
+/// Circuit opened by a relay to the local node.
+#[derive(Debug)]
+pub(crate) struct InboundCircuit {
+    /// Peer ID of the relay.
+    pub(crate) relay: PeerId,
+
+    /// Peer ID of the peer which opened the circuit.
+    pub(crate) source: PeerId,
+
+    /// Circuit substream.
+    pub(crate) substream: crate::substream::Substream,
+}
+
+#[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
+enum RawConnectionResult {
+    /// The first successful circuit.
+    Connected {
+        connection_id: ConnectionId,
+        address: Multiaddr,
+        stream: crate::substream::Substream,
+    },
+
+    /// All circuit attempts failed.
+    Failed {
+        connection_id: ConnectionId,
+        errors: Vec<(Multiaddr, DialError)>,
+    },
+
+    /// Future was canceled.
+    Canceled { connection_id: ConnectionId },
+}
+
+/// Circuit relay transport.
+pub(crate) struct RelayTransport {
+    /// Transport context.
+    context: TransportHandle,
+
+    /// Transport configuration.
+    config: Config,
+
+    /// Pending dials.
+    pending_dials: HashMap<ConnectionId, Multiaddr>,
+
+    /// Pending inbound circuits.
+    pending_inbound_connections: HashMap<ConnectionId, InboundCircuit>,
+
+    /// Pending connections.
+    pending_connections:
+        FuturesStream<BoxFuture<'static, Result<NegotiatedConnection, (ConnectionId, DialError)>>>,
+
+    /// Pending raw, unnegotiated circuits.
+    pending_raw_connections: FuturesStream<BoxFuture<'static, RawConnectionResult>>,
+
+    /// Opened raw circuits, waiting for approval/rejection from `TransportManager`.
+    opened_raw: HashMap<ConnectionId, (crate::substream::Substream, Multiaddr)>,
+
+    /// Cancel raw connections futures.
+    ///
+    /// This is cancelling `Self::pending_raw_connections`.
+    cancel_futures: HashMap<ConnectionId, AbortHandle>,
+
+    /// Negotiated connections waiting validation.
+    pending_open: HashMap<ConnectionId, NegotiatedConnection>,
+}
+
+impl RelayTransport {
+    /// Split circuit address `/<relay address>/p2p/<relay>/p2p-circuit/p2p/<destination>`
+    /// into the address of the relay and the peer ID of the destination.
+    fn parse_circuit_address(address: &Multiaddr) -> Result<(Multiaddr, PeerId), AddressError> {
+        let mut relay = Multiaddr::empty();
+        let mut protocol_stack = address.iter();
+
+        loop {
+            match protocol_stack.next() {
+                Some(Protocol::P2pCircuit) => break,
+                Some(protocol) => relay.push(protocol),
+                None => return Err(AddressError::InvalidProtocol),
+            }
+        }
+
+        if !std::matches!(relay.iter().last(), Some(Protocol::P2p(_))) {
+            tracing::debug!(
+                target: LOG_TARGET,
+                ?address,
+                "relay peer ID missing from circuit address",
+            );
+            return Err(AddressError::PeerIdMissing);
+        }
+
+        let destination = match (protocol_stack.next(), protocol_stack.next()) {
+            (Some(Protocol::P2p(multihash)), None) => PeerId::from_multihash(multihash)?,
+            (None, _) => return Err(AddressError::PeerIdMissing),
+            _ => return Err(AddressError::InvalidProtocol),
+        };
+
+        Ok((relay, destination))
+    }
+
+    /// Ask the relay protocol to open a circuit over `address`.
+    async fn open_circuit(
+        request_tx: tokio::sync::mpsc::Sender<CircuitRequest>,
+        address: Multiaddr,
+        connection_open_timeout: Duration,
+    ) -> Result<crate::substream::Substream, DialError> {
+        let (relay, destination) = Self::parse_circuit_address(&address)?;
+        let (tx, rx) = oneshot::channel();
+
+        request_tx
+            .try_send(CircuitRequest {
+                relay,
+                destination,
+                tx,
+            })
+            .map_err(|_| DialError::RelayError(RelayError::RelayUnreachable))?;
+
+        match tokio::time::timeout(connection_open_timeout, rx).await {
+            Err(_) => Err(DialError::Timeout),
+            Ok(Err(_)) => Err(DialError::RelayError(RelayError::RelayUnreachable)),
+            Ok(Ok(result)) => result.map_err(From::from),
+        }
+    }
+
+    /// Get the context needed for negotiating a connection over a circuit.
+    fn negotiation_context(&self) -> NegotiationContext {
+        NegotiationContext {
+            keypair: self.context.keypair.clone(),
+            yamux_config: self.config.yamux_config.clone(),
+            max_read_ahead_factor: self.config.noise_read_ahead_frame_count,
+            max_write_buffer_size: self.config.noise_write_buffer_size,
+        }
+    }
+
+    /// Negotiate connection over an opened circuit.
+    fn negotiate_circuit(
+        &mut self,
+        connection_id: ConnectionId,
+        stream: crate::substream::Substream,
+        peer: Option<PeerId>,
+        role: Role,
+        address: Multiaddr,
+    ) {
+        let connection_open_timeout = self.config.connection_open_timeout;
+        let negotiate = self.negotiation_context();
+
+        self.pending_connections.push(Box::pin(async move {
+            match tokio::time::timeout(
+                connection_open_timeout,
+                negotiate.negotiate(connection_id, stream, peer, role, address),
+            )
+            .await
+            {
+                Err(_) => Err((connection_id, DialError::Timeout)),
+                Ok(Err(error)) => Err((connection_id, error.into())),
+                Ok(Ok(connection)) => Ok(connection),
+            }
+        }));
+    }
+}
+
+/// Context needed for negotiating a connection over a circuit.
+struct NegotiationContext {
+    /// Local keypair.
+    keypair: crate::crypto::ed25519::Keypair,
+
+    /// Yamux configuration.
+    yamux_config: crate::yamux::Config,
+
+    /// Noise read-ahead frame count.
+    max_read_ahead_factor: usize,
+
+    /// Noise write buffer size.
+    max_write_buffer_size: usize,
+}
+
+impl NegotiationContext {
+    /// Negotiate `noise` and `yamux` over `stream`.
+    async fn negotiate(
+        self,
+        connection_id: ConnectionId,
+        stream: crate::substream::Substream,
+        peer: Option<PeerId>,
+        role: Role,
+        address: Multiaddr,
+    ) -> Result<NegotiatedConnection, crate::error::NegotiationError> {
+        RelayConnection::negotiate_connection(
+            stream,
+            peer,
+            role,
+            address,
+            connection_id,
+            self.keypair,
+            self.yamux_config,
+            self.max_read_ahead_factor,
+            self.max_write_buffer_size,
+        )
+        .await
+    }
+}
+
+impl TransportBuilder for RelayTransport {
+    type Config = Config;
+    type Transport = RelayTransport;
+
+    /// Create new [`Transport`] object.
+    fn new(context: TransportHandle, config: Self::Config) -> crate::Result<(Self, Vec<Multiaddr>)>
+    where
+        Self: Sized,
+    {
+        tracing::debug!(target: LOG_TARGET, "start relay transport");
+
+        Ok((
+            Self {
+                config,
+                context,
+                opened_raw: HashMap::new(),
+                pending_open: HashMap::new(),
+                pending_dials: HashMap::new(),
+                pending_inbound_connections: HashMap::new(),
+                pending_connections: FuturesStream::new(),
+                pending_raw_connections: FuturesStream::new(),
+                cancel_futures: HashMap::new(),
+            },
+            Vec::new(),
+        ))
+    }
+}
+
+impl Transport for RelayTransport {
+    fn dial(&mut self, connection_id: ConnectionId, address: Multiaddr) -> crate::Result<()> {
+        let (_, peer) = Self::parse_circuit_address(&address)?;
+        let request_tx = self.config.request_tx.clone();
+        let connection_open_timeout = self.config.connection_open_timeout;
+        let negotiate = self.negotiation_context();
+
+        tracing::debug!(target: LOG_TARGET, ?connection_id, ?address, "open circuit");
+
+        self.pending_dials.insert(connection_id, address.clone());
+        self.pending_connections.push(Box::pin(async move {
+            match tokio::time::timeout(connection_open_timeout, async move {
+                let stream =
+                    Self::open_circuit(request_tx, address.clone(), connection_open_timeout)
+                        .await
+                        .map_err(|error| (connection_id, error))?;
+
+                negotiate
+                    .negotiate(connection_id, stream, Some(peer), Role::Dialer, address)
+                    .await
+                    .map_err(|error| (connection_id, error.into()))
+            })
+            .await
+            {
+                Err(_) => Err((connection_id, DialError::Timeout)),
+                Ok(Err(error)) => Err(error),
+                Ok(Ok(connection)) => Ok(connection),
+            }
+        }));
+
+        Ok(())
+    }
+
+    fn accept(&mut self, connection_id: ConnectionId) -> crate::Result<()> {
+        let context = self
+            .pending_open
+            .remove(&connection_id)
+            .ok_or(Error::ConnectionDoesntExist(connection_id))?;
+        let protocol_set = self.context.protocol_set(connection_id);
+        let substream_open_timeout = self.config.substream_open_timeout;
+
+        tracing::trace!(
+            target: LOG_TARGET,
+            ?connection_id,
+            "start connection",
+        );
+
+        self.context.executor.run(Box::pin(async move {
+            if let Err(error) = RelayConnection::new(context, protocol_set, substream_open_timeout)
+                .start()
+                .await
+            {
+                tracing::debug!(
+                    target: LOG_TARGET,
+                    ?connection_id,
+                    ?error,
+                    "connection exited with error",
+                );
+            }
+        }));
+
+        Ok(())
+    }
+
+    fn reject(&mut self, connection_id: ConnectionId) -> crate::Result<()> {
+        self.pending_open
+            .remove(&connection_id)
+            .map_or(Err(Error::ConnectionDoesntExist(connection_id)), |_| Ok(()))
+    }
+
+    fn accept_pending(&mut self, connection_id: ConnectionId) -> crate::Result<()> {
+        let circuit = self.pending_inbound_connections.remove(&connection_id).ok_or_else(|| {
+            tracing::error!(
+                target: LOG_TARGET,
+                ?connection_id,
+                "Cannot accept non existent pending circuit",
+            );
+
+            Error::ConnectionDoesntExist(connection_id)
+        })?;
+
+        let address = Multiaddr::empty()
+            .with(Protocol::P2p(circuit.relay.into()))
+            .with(Protocol::P2pCircuit);
+
+        self.negotiate_circuit(
+            connection_id,
+            circuit.substream,
+            Some(circuit.source),
+            Role::Listener,
+            address,
+        );
+
+        Ok(())
+    }
+
+    fn reject_pending(&mut self, connection_id: ConnectionId) -> crate::Result<()> {
+        self.pending_inbound_connections.remove(&connection_id).map_or_else(
+            || {
+                tracing::error!(
+                    target: LOG_TARGET,
+                    ?connection_id,
+                    "Cannot reject non existent pending circuit",
+                );
+
+                Err(Error::ConnectionDoesntExist(connection_id))
+            },
+            |_| Ok(()),
+        )
+    }
+
+    fn open(
+        &mut self,
+        connection_id: ConnectionId,
+        addresses: Vec<Multiaddr>,
+    ) -> crate::Result<()> {
+        let attempts = addresses
+            .into_iter()
+            .map(|address| {
+                let request_tx = self.config.request_tx.clone();
+                let connection_open_timeout = self.config.connection_open_timeout;
+
+                async move {
+                    RelayTransport::open_circuit(
+                        request_tx,
+                        address.clone(),
+                        connection_open_timeout,
+                    )
+                    .await
+                    .map(|stream| (address.clone(), stream))
+                    .map_err(|error| (address, error))
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let connection_attempt_delay = self.context.connection_attempt_delay;
+
+        // Future that will resolve to the first successful circuit.
+        let future = async move {
+            match staggered(attempts, connection_attempt_delay).await {
+                Ok((address, stream)) => RawConnectionResult::Connected {
+                    connection_id,
+                    address,
+                    stream,
+                },
+                Err(errors) => {
+                    tracing::debug!(
+                        target: LOG_TARGET,
+                        ?connection_id,
+                        ?errors,
+                        "failed to open circuit",
+                    );
+
+                    RawConnectionResult::Failed {
+                        connection_id,
+                        errors,
+                    }
+                }
+            }
+        };
+
+        let (fut, handle) = futures::future::abortable(future);
+        let fut = fut.unwrap_or_else(move |_| RawConnectionResult::Canceled { connection_id });
+        self.pending_raw_connections.push(Box::pin(fut));
+        self.cancel_futures.insert(connection_id, handle);
+
+        Ok(())
+    }
+
+    fn negotiate(&mut self, connection_id: ConnectionId) -> crate::Result<()> {
+        let (stream, address) = self
+            .opened_raw
+            .remove(&connection_id)
+            .ok_or(Error::ConnectionDoesntExist(connection_id))?;
+        let (_, peer) = Self::parse_circuit_address(&address)?;
+
+        tracing::trace!(
+            target: LOG_TARGET,
+            ?peer,
+            ?connection_id,
+            ?address,
+            "negotiate connection",
+        );
+
+        self.pending_dials.insert(connection_id, address.clone());
+        self.negotiate_circuit(connection_id, stream, Some(peer), Role::Dialer, address);
+
+        Ok(())
+    }
+
+    fn cancel(&mut self, connection_id: ConnectionId) {
+        // Cancel the future if it exists.
+        // State clean-up happens inside the `poll_next`.
+        if let Some(handle) = self.cancel_futures.get(&connection_id) {
+            handle.abort();
+        }
+    }
+}
+
+impl Stream for RelayTransport {
+    type Item = TransportEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        // the channel is closed only if the relay protocol has exited in which case
+        // the transport stays idle until the node is shut down
+        if let Poll::Ready(Some(circuit)) = self.config.circuit_rx.poll_recv(cx) {
+            let connection_id = self.context.next_connection_id();
+            tracing::trace!(
+                target: LOG_TARGET,
+                ?connection_id,
+                relay = ?circuit.relay,
+                source = ?circuit.source,
+                "pending inbound circuit",
+            );
+
+            self.pending_inbound_connections.insert(connection_id, circuit);
+
+            return Poll::Ready(Some(TransportEvent::PendingInboundConnection {
+                connection_id,
+            }));
+        }
+
+        while let Poll::Ready(Some(result)) = self.pending_raw_connections.poll_next_unpin(cx) {
+            tracing::trace!(target: LOG_TARGET, ?result, "raw connection result");
+
+            match result {
+                RawConnectionResult::Connected {
+                    connection_id,
+                    address,
+                    stream,
+                } => {
+                    let Some(handle) = self.cancel_futures.remove(&connection_id) else {
+                        tracing::warn!(
+                            target: LOG_TARGET,
+                            ?connection_id,
+                            ?address,
+                            "raw connection without a cancel handle",
+                        );
+                        continue;
+                    };
+
+                    if !handle.is_aborted() {
+                        self.opened_raw.insert(connection_id, (stream, address.clone()));
+
+                        return Poll::Ready(Some(TransportEvent::ConnectionOpened {
+                            connection_id,
+                            address,
+                        }));
+                    }
+                }
+
+                RawConnectionResult::Failed {
+                    connection_id,
+                    errors,
+                } => {
+                    let Some(handle) = self.cancel_futures.remove(&connection_id) else {
+                        tracing::warn!(
+                            target: LOG_TARGET,
+                            ?connection_id,
+                            ?errors,
+                            "raw connection without a cancel handle",
+                        );
+                        continue;
+                    };
+
+                    if !handle.is_aborted() {
+                        return Poll::Ready(Some(TransportEvent::OpenFailure {
+                            connection_id,
+                            errors,
+                        }));
+                    }
+                }
+                RawConnectionResult::Canceled { connection_id } => {
+                    if self.cancel_futures.remove(&connection_id).is_none() {
+                        tracing::warn!(
+                            target: LOG_TARGET,
+                            ?connection_id,
+                            "raw cancelled connection without a cancel handle",
+                        );
+                    }
+                }
+            }
+        }
+
+        while let Poll::Ready(Some(connection)) = self.pending_connections.poll_next_unpin(cx) {
+            match connection {
+                Ok(connection) => {
+                    let peer = connection.peer();
+                    let endpoint = connection.endpoint();
+                    self.pending_dials.remove(&connection.connection_id());
+                    self.pending_open.insert(connection.connection_id(), connection);
+
+                    return Poll::Ready(Some(TransportEvent::ConnectionEstablished {
+                        peer,
+                        endpoint,
+                    }));
+                }
+                Err((connection_id, error)) => {
+                    if let Some(address) = self.pending_dials.remove(&connection_id) {
+                        return Poll::Ready(Some(TransportEvent::DialFailure {
+                            connection_id,
+                            address,
+                            error,
+                        }));
+                    } else {
+                        tracing::debug!(target: LOG_TARGET, ?error, ?connection_id, "Pending inbound circuit failed");
+                    }
+                }
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_circuit_address() {
+        let relay = PeerId::random();
+        let destination = PeerId::random();
+        let relay_address: Multiaddr =
+            format!("/ip4/127.0.0.1/tcp/8888/p2p/{relay}").parse().unwrap();
+
+        let address = relay_address
+            .clone()
+            .with(Protocol::P2pCircuit)
+            .with(Protocol::P2p(destination.into()));
+        assert_eq!(
+            RelayTransport::parse_circuit_address(&address).unwrap(),
+            (relay_address.clone(), destination)
+        );
+
+        // destination missing
+        let address = relay_address.clone().with(Protocol::P2pCircuit);
+        assert!(std::matches!(
+            RelayTransport::parse_circuit_address(&address),
+            Err(AddressError::PeerIdMissing)
+        ));
+
+        // relay peer ID missing
+        let address: Multiaddr = format!("/ip4/127.0.0.1/tcp/8888/p2p-circuit/p2p/{destination}")
+            .parse()
+            .unwrap();
+        assert!(std::matches!(
+            RelayTransport::parse_circuit_address(&address),
+            Err(AddressError::PeerIdMissing)
+        ));
+
+        // not a circuit address
+        assert!(std::matches!(
+            RelayTransport::parse_circuit_address(&relay_address),
+            Err(AddressError::InvalidProtocol)
+        ));
+    }
+}
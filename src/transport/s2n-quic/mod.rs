@@ -389,9 +389,13 @@ mod tests {
             protocol_names: Vec::new(),
             next_substream_id: Default::default(),
             next_connection_id: Default::default(),
+            next_listener_id: Default::default(),
             tx: event_tx1,
             rx: command_rx1,
             keypair: keypair1.clone(),
+            pre_shared_key: None,
+            resolver: Default::default(),
+            connection_attempt_delay: Default::default(),
             protocols: HashMap::from_iter([(
                 ProtocolName::from("/notif/1"),
                 ProtocolContext {
@@ -422,9 +426,13 @@ mod tests {
             protocol_names: Vec::new(),
             next_substream_id: Default::default(),
             next_connection_id: Default::default(),
+            next_listener_id: Default::default(),
             tx: event_tx2,
             rx: command_rx2,
             keypair: keypair2.clone(),
+            pre_shared_key: None,
+            resolver: Default::default(),
+            connection_attempt_delay: Default::default(),
             protocols: HashMap::from_iter([(
                 ProtocolName::from("/notif/1"),
                 ProtocolContext {
@@ -22,7 +22,7 @@
 
 use crate::{
     crypto::noise::{MAX_READ_AHEAD_FACTOR, MAX_WRITE_BUFFER_SIZE},
-    transport::{CONNECTION_OPEN_TIMEOUT, SUBSTREAM_OPEN_TIMEOUT},
+    transport::{SecurityProtocol, StreamMuxer, CONNECTION_OPEN_TIMEOUT, SUBSTREAM_OPEN_TIMEOUT},
 };
 
 /// TCP transport configuration.
@@ -49,6 +49,28 @@ pub struct Config {
     /// Yamux configuration.
     pub yamux_config: crate::yamux::Config,
 
+    /// mplex configuration.
+    pub mplex_config: crate::mplex::Config,
+
+    /// Security protocols, in order of preference.
+    ///
+    /// When dialing, the protocols are proposed to the remote peer in this order and when
+    /// listening, the first protocol proposed by the remote peer that is also in this list
+    /// is accepted.
+    ///
+    /// Defaults to `[SecurityProtocol::Noise, SecurityProtocol::Tls]` if the `tls` feature is
+    /// enabled and to `[SecurityProtocol::Noise]` otherwise.
+    pub security_protocols: Vec<SecurityProtocol>,
+
+    /// Stream muxers, in order of preference.
+    ///
+    /// The muxers are negotiated the same way as [`Config::security_protocols`]. mplex has no
+    /// flow control and should only be enabled for compatibility with peers that don't support
+    /// yamux.
+    ///
+    /// Defaults to `[StreamMuxer::Yamux]`.
+    pub stream_muxers: Vec<StreamMuxer>,
+
     /// Noise read-ahead frame count.
     ///
     /// Specifies how many Noise frames are read per call to the underlying socket.
@@ -92,6 +114,9 @@ impl Default for Config {
             reuse_port: true,
             nodelay: false,
             yamux_config: Default::default(),
+            mplex_config: Default::default(),
+            security_protocols: SecurityProtocol::defaults(),
+            stream_muxers: vec![StreamMuxer::Yamux],
             noise_read_ahead_frame_count: MAX_READ_AHEAD_FACTOR,
             noise_write_buffer_size: MAX_WRITE_BUFFER_SIZE,
             connection_open_timeout: CONNECTION_OPEN_TIMEOUT,
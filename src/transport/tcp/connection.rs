@@ -22,14 +22,18 @@ use crate::{
     config::Role,
     crypto::{
         ed25519::Keypair,
-        noise::{self, NoiseSocket},
+        pnet::{self, PnetStream, PreSharedKey},
     },
     error::{Error, NegotiationError, SubstreamError},
     multistream_select::{dialer_select_proto, listener_select_proto, Negotiated, Version},
     protocol::{Direction, Permit, ProtocolCommand, ProtocolSet},
     substream,
     transport::{
-        common::listener::{AddressType, DnsType},
+        common::{
+            listener::{AddressType, DnsType},
+            muxer::{MuxedConnection, MuxedStream, MuxerControl, MuxerError, StreamMuxer},
+            security::{SecureStream, SecurityProtocol},
+        },
         tcp::substream::Substream,
         Endpoint,
     },
@@ -73,8 +77,8 @@ pub struct NegotiatedSubstream {
     /// Protocol name.
     protocol: ProtocolName,
 
-    /// Yamux substream.
-    io: crate::yamux::Stream,
+    /// Multiplexed substream.
+    io: MuxedStream,
 
     /// Permit.
     permit: Permit,
@@ -106,12 +110,12 @@ enum ConnectionError {
 }
 
 /// Connection context for an opened connection that hasn't yet started its event loop.
-pub struct NegotiatedConnection {
-    /// Yamux connection.
-    connection: crate::yamux::ControlledConnection<NoiseSocket<Compat<TcpStream>>>,
+pub struct NegotiatedConnection<S = TcpStream> {
+    /// Multiplexed connection.
+    connection: MuxedConnection<SecureStream<PnetStream<Compat<S>>>>,
 
-    /// Yamux control.
-    control: crate::yamux::Control,
+    /// Stream muxer control.
+    control: MuxerControl,
 
     /// Remote peer ID.
     peer: PeerId,
@@ -123,7 +127,7 @@ pub struct NegotiatedConnection {
     substream_open_timeout: Duration,
 }
 
-impl NegotiatedConnection {
+impl<S> NegotiatedConnection<S> {
     /// Get `ConnectionId` of the negotiated connection.
     pub fn connection_id(&self) -> ConnectionId {
         self.endpoint.connection_id()
@@ -141,15 +145,18 @@ impl NegotiatedConnection {
 }
 
 /// TCP connection.
-pub struct TcpConnection {
+///
+/// The connection is generic over the underlying socket so other stream-oriented transports
+/// can reuse the TCP connection pipeline.
+pub struct TcpConnection<S = TcpStream> {
     /// Protocol context.
     protocol_set: ProtocolSet,
 
-    /// Yamux connection.
-    connection: crate::yamux::ControlledConnection<NoiseSocket<Compat<TcpStream>>>,
+    /// Multiplexed connection.
+    connection: MuxedConnection<SecureStream<PnetStream<Compat<S>>>>,
 
-    /// Yamux control.
-    control: crate::yamux::Control,
+    /// Stream muxer control.
+    control: MuxerControl,
 
     /// Remote peer ID.
     peer: PeerId,
@@ -171,7 +178,7 @@ pub struct TcpConnection {
         FuturesUnordered<BoxFuture<'static, Result<NegotiatedSubstream, ConnectionError>>>,
 }
 
-impl fmt::Debug for TcpConnection {
+impl<S> fmt::Debug for TcpConnection<S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("TcpConnection")
             .field("peer", &self.peer)
@@ -180,10 +187,13 @@ impl fmt::Debug for TcpConnection {
     }
 }
 
-impl TcpConnection {
+impl<S> TcpConnection<S>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+{
     /// Create new [`TcpConnection`] from [`NegotiatedConnection`].
-    pub(super) fn new(
-        context: NegotiatedConnection,
+    pub(crate) fn new(
+        context: NegotiatedConnection<S>,
         protocol_set: ProtocolSet,
         bandwidth_sink: BandwidthSink,
         next_substream_id: Arc<AtomicUsize>,
@@ -214,15 +224,19 @@ impl TcpConnection {
     pub(super) async fn open_connection(
         connection_id: ConnectionId,
         keypair: Keypair,
-        stream: TcpStream,
+        pre_shared_key: Option<PreSharedKey>,
+        stream: S,
         address: AddressType,
         peer: Option<PeerId>,
         yamux_config: crate::yamux::Config,
+        mplex_config: crate::mplex::Config,
+        security_protocols: Vec<SecurityProtocol>,
+        stream_muxers: Vec<StreamMuxer>,
         max_read_ahead_factor: usize,
         max_write_buffer_size: usize,
         connection_open_timeout: Duration,
         substream_open_timeout: Duration,
-    ) -> Result<NegotiatedConnection, NegotiationError> {
+    ) -> Result<NegotiatedConnection<S>, NegotiationError> {
         tracing::debug!(
             target: LOG_TARGET,
             ?address,
@@ -236,9 +250,13 @@ impl TcpConnection {
                 peer,
                 connection_id,
                 keypair,
+                pre_shared_key,
                 Role::Dialer,
                 address,
                 yamux_config,
+                mplex_config,
+                security_protocols,
+                stream_muxers,
                 max_read_ahead_factor,
                 max_write_buffer_size,
                 substream_open_timeout,
@@ -257,7 +275,7 @@ impl TcpConnection {
 
     /// Open substream for `protocol`.
     pub(super) async fn open_substream(
-        mut control: crate::yamux::Control,
+        mut control: MuxerControl,
         substream_id: SubstreamId,
         permit: Permit,
         protocol: ProtocolName,
@@ -278,10 +296,7 @@ impl TcpConnection {
                     ?error,
                     "failed to open substream"
                 );
-                return Err(SubstreamError::YamuxError(
-                    error,
-                    Direction::Outbound(substream_id),
-                ));
+                return Err(error.into_substream_error(Direction::Outbound(substream_id)));
             }
         };
 
@@ -305,16 +320,20 @@ impl TcpConnection {
 
     /// Accept a new connection.
     pub(super) async fn accept_connection(
-        stream: TcpStream,
+        stream: S,
         connection_id: ConnectionId,
         keypair: Keypair,
+        pre_shared_key: Option<PreSharedKey>,
         address: SocketAddr,
         yamux_config: crate::yamux::Config,
+        mplex_config: crate::mplex::Config,
+        security_protocols: Vec<SecurityProtocol>,
+        stream_muxers: Vec<StreamMuxer>,
         max_read_ahead_factor: usize,
         max_write_buffer_size: usize,
         connection_open_timeout: Duration,
         substream_open_timeout: Duration,
-    ) -> Result<NegotiatedConnection, NegotiationError> {
+    ) -> Result<NegotiatedConnection<S>, NegotiationError> {
         tracing::debug!(target: LOG_TARGET, ?address, "accept connection");
 
         match tokio::time::timeout(connection_open_timeout, async move {
@@ -323,9 +342,13 @@ impl TcpConnection {
                 None,
                 connection_id,
                 keypair,
+                pre_shared_key,
                 Role::Listener,
                 AddressType::Socket(address),
                 yamux_config,
+                mplex_config,
+                security_protocols,
+                stream_muxers,
                 max_read_ahead_factor,
                 max_write_buffer_size,
                 substream_open_timeout,
@@ -341,7 +364,7 @@ impl TcpConnection {
 
     /// Accept substream.
     pub(super) async fn accept_substream(
-        stream: crate::yamux::Stream,
+        stream: MuxedStream,
         permit: Permit,
         substream_id: SubstreamId,
         protocols: Vec<ProtocolName>,
@@ -373,12 +396,12 @@ impl TcpConnection {
     }
 
     /// Negotiate protocol.
-    async fn negotiate_protocol<S: AsyncRead + AsyncWrite + Unpin>(
-        stream: S,
+    async fn negotiate_protocol<T: AsyncRead + AsyncWrite + Unpin>(
+        stream: T,
         role: &Role,
         protocols: Vec<&str>,
         substream_open_timeout: Duration,
-    ) -> Result<(Negotiated<S>, ProtocolName), NegotiationError> {
+    ) -> Result<(Negotiated<T>, ProtocolName), NegotiationError> {
         tracing::trace!(target: LOG_TARGET, ?protocols, "negotiating protocols");
 
         match tokio::time::timeout(substream_open_timeout, async move {
@@ -399,19 +422,82 @@ impl TcpConnection {
         }
     }
 
-    /// Negotiate noise + yamux for the connection.
+    /// Negotiate security protocol + stream muxer for the connection.
     pub(super) async fn negotiate_connection(
-        stream: TcpStream,
+        stream: S,
         dialed_peer: Option<PeerId>,
         connection_id: ConnectionId,
         keypair: Keypair,
+        pre_shared_key: Option<PreSharedKey>,
         role: Role,
         address: AddressType,
         yamux_config: crate::yamux::Config,
+        mplex_config: crate::mplex::Config,
+        security_protocols: Vec<SecurityProtocol>,
+        stream_muxers: Vec<StreamMuxer>,
+        max_read_ahead_factor: usize,
+        max_write_buffer_size: usize,
+        substream_open_timeout: Duration,
+    ) -> Result<NegotiatedConnection<S>, NegotiationError> {
+        let address = match address {
+            AddressType::Socket(address) => Multiaddr::empty()
+                .with(Protocol::from(address.ip()))
+                .with(Protocol::Tcp(address.port())),
+            AddressType::Dns {
+                address,
+                port,
+                dns_type,
+            } => match dns_type {
+                DnsType::Dns => Multiaddr::empty()
+                    .with(Protocol::Dns(Cow::Owned(address)))
+                    .with(Protocol::Tcp(port)),
+                DnsType::Dns4 => Multiaddr::empty()
+                    .with(Protocol::Dns4(Cow::Owned(address)))
+                    .with(Protocol::Tcp(port)),
+                DnsType::Dns6 => Multiaddr::empty()
+                    .with(Protocol::Dns6(Cow::Owned(address)))
+                    .with(Protocol::Tcp(port)),
+            },
+        };
+
+        Self::negotiate_stream(
+            stream,
+            dialed_peer,
+            connection_id,
+            keypair,
+            pre_shared_key,
+            role,
+            address,
+            yamux_config,
+            mplex_config,
+            security_protocols,
+            stream_muxers,
+            max_read_ahead_factor,
+            max_write_buffer_size,
+            substream_open_timeout,
+        )
+        .await
+    }
+
+    /// Negotiate security protocol + stream muxer for a connection to `address` over `stream`.
+    ///
+    /// `address` is the address reported in the [`Endpoint`] of the connection.
+    pub(crate) async fn negotiate_stream(
+        stream: S,
+        dialed_peer: Option<PeerId>,
+        connection_id: ConnectionId,
+        keypair: Keypair,
+        pre_shared_key: Option<PreSharedKey>,
+        role: Role,
+        address: Multiaddr,
+        yamux_config: crate::yamux::Config,
+        mplex_config: crate::mplex::Config,
+        security_protocols: Vec<SecurityProtocol>,
+        stream_muxers: Vec<StreamMuxer>,
         max_read_ahead_factor: usize,
         max_write_buffer_size: usize,
         substream_open_timeout: Duration,
-    ) -> Result<NegotiatedConnection, NegotiationError> {
+    ) -> Result<NegotiatedConnection<S>, NegotiationError> {
         tracing::trace!(
             target: LOG_TARGET,
             ?role,
@@ -420,21 +506,56 @@ impl TcpConnection {
 
         let stream = TokioAsyncReadCompatExt::compat(stream).into_inner();
         let stream = TokioAsyncWriteCompatExt::compat_write(stream);
-
-        // negotiate `noise`
-        let (stream, _) =
-            Self::negotiate_protocol(stream, &role, vec!["/noise"], substream_open_timeout).await?;
+        let is_private = pre_shared_key.is_some();
+
+        // wrap the connection in the private network cipher if the node is part of one
+        let stream = pnet::handshake(stream, pre_shared_key.as_ref()).await?;
+
+        // negotiate security protocol
+        //
+        // if the node is part of a private network, a garbled security protocol negotiation
+        // means the remote peer is using a different pre-shared key
+        let protocols =
+            security_protocols.iter().map(|protocol| protocol.protocol_name()).collect();
+        let (stream, protocol) = match Self::negotiate_protocol(
+            stream,
+            &role,
+            protocols,
+            substream_open_timeout,
+        )
+        .await
+        {
+            Ok(result) => result,
+            Err(error) if is_private && pnet::is_key_mismatch(&error) => {
+                tracing::debug!(
+                    target: LOG_TARGET,
+                    ?connection_id,
+                    ?error,
+                    "failed to negotiate security protocol over private network",
+                );
+                return Err(NegotiationError::PreSharedKeyMismatch);
+            }
+            Err(error) => return Err(error),
+        };
+        let protocol = SecurityProtocol::from_protocol_name(&protocol)
+            .expect("negotiated protocol to be one of the security protocols; qed");
 
         tracing::trace!(
             target: LOG_TARGET,
-            "`multistream-select` and `noise` negotiated",
+            ?protocol,
+            "`multistream-select` and security protocol negotiated",
         );
 
-        // perform noise handshake
-        let (stream, peer) = noise::handshake(
+        // perform security handshake
+        let stream_muxers =
+            stream_muxers.iter().map(|muxer| muxer.protocol_name()).collect::<Vec<_>>();
+        let (stream, peer, muxer) = SecureStream::upgrade(
             stream.inner(),
+            protocol,
             &keypair,
             role,
+            dialed_peer,
+            &stream_muxers,
             max_read_ahead_factor,
             max_write_buffer_size,
         )
@@ -447,38 +568,26 @@ impl TcpConnection {
             }
         }
 
-        tracing::trace!(target: LOG_TARGET, "noise handshake done");
-        let stream: NoiseSocket<Compat<TcpStream>> = stream;
-
-        // negotiate `yamux`
-        let (stream, _) =
-            Self::negotiate_protocol(stream, &role, vec!["/yamux/1.0.0"], substream_open_timeout)
-                .await?;
-        tracing::trace!(target: LOG_TARGET, "`yamux` negotiated");
+        tracing::trace!(target: LOG_TARGET, ?protocol, "security handshake done");
+        let stream: SecureStream<PnetStream<Compat<S>>> = stream;
 
-        let connection = crate::yamux::Connection::new(stream.inner(), yamux_config, role.into());
-        let (control, connection) = crate::yamux::Control::new(connection);
+        // negotiate stream muxer unless it was already agreed on during the security handshake
+        let (stream, muxer) = match muxer {
+            Some(muxer) => (stream, StreamMuxer::from_protocol_name(&muxer)),
+            None => {
+                let (stream, protocol) =
+                    Self::negotiate_protocol(stream, &role, stream_muxers, substream_open_timeout)
+                        .await?;
 
-        let address = match address {
-            AddressType::Socket(address) => Multiaddr::empty()
-                .with(Protocol::from(address.ip()))
-                .with(Protocol::Tcp(address.port())),
-            AddressType::Dns {
-                address,
-                port,
-                dns_type,
-            } => match dns_type {
-                DnsType::Dns => Multiaddr::empty()
-                    .with(Protocol::Dns(Cow::Owned(address)))
-                    .with(Protocol::Tcp(port)),
-                DnsType::Dns4 => Multiaddr::empty()
-                    .with(Protocol::Dns4(Cow::Owned(address)))
-                    .with(Protocol::Tcp(port)),
-                DnsType::Dns6 => Multiaddr::empty()
-                    .with(Protocol::Dns6(Cow::Owned(address)))
-                    .with(Protocol::Tcp(port)),
-            },
+                (stream.inner(), StreamMuxer::from_protocol_name(&protocol))
+            }
         };
+        let muxer = muxer.expect("negotiated protocol to be one of the stream muxers; qed");
+        tracing::trace!(target: LOG_TARGET, ?muxer, "stream muxer negotiated");
+
+        let (control, connection) =
+            MuxedConnection::new(stream, muxer, role, yamux_config, mplex_config);
+
         let endpoint = match role {
             Role::Dialer => Endpoint::dialer(address, connection_id),
             Role::Listener => Endpoint::listener(address, connection_id),
@@ -493,12 +602,12 @@ impl TcpConnection {
         })
     }
 
-    /// Handles the yamux substream.
+    /// Handles the multiplexed substream.
     ///
     /// Returns `true` if the connection handler should exit.
-    async fn handle_yamux_substream(
+    async fn handle_muxed_substream(
         &mut self,
-        substream: Option<Result<crate::yamux::Stream, crate::yamux::ConnectionError>>,
+        substream: Option<Result<MuxedStream, MuxerError>>,
     ) -> crate::Result<bool> {
         match substream {
             Some(Ok(stream)) => {
@@ -728,7 +837,7 @@ impl TcpConnection {
         loop {
             tokio::select! {
                 substream = self.connection.next() => {
-                    if self.handle_yamux_substream(substream).await? {
+                    if self.handle_muxed_substream(substream).await? {
                         return Ok(());
                     }
                 },
@@ -747,7 +856,10 @@ impl TcpConnection {
 
 #[cfg(test)]
 mod tests {
-    use crate::transport::tcp::TcpTransport;
+    use crate::{
+        crypto::noise::{self, NoiseExtensions, NoiseSocket},
+        transport::tcp::TcpTransport,
+    };
 
     use super::*;
     use tokio::{io::AsyncWriteExt, net::TcpListener};
@@ -772,7 +884,9 @@ mod tests {
                 .with(Protocol::Tcp(address.port())),
             Default::default(),
             Duration::from_secs(10),
+            Duration::ZERO,
             false,
+            Default::default(),
         )
         .await
         .unwrap();
@@ -780,10 +894,14 @@ mod tests {
         match TcpConnection::open_connection(
             ConnectionId::from(0usize),
             Keypair::generate(),
+            None,
             stream,
             AddressType::Socket(address),
             None,
             Default::default(),
+            Default::default(),
+            vec![SecurityProtocol::Noise],
+            vec![StreamMuxer::Yamux],
             5,
             2,
             Duration::from_secs(10),
@@ -824,8 +942,12 @@ mod tests {
             stream,
             ConnectionId::from(0usize),
             Keypair::generate(),
+            None,
             dialer_address,
             Default::default(),
+            Default::default(),
+            vec![SecurityProtocol::Noise],
+            vec![StreamMuxer::Yamux],
             5,
             2,
             Duration::from_secs(10),
@@ -867,7 +989,9 @@ mod tests {
                 .with(Protocol::Tcp(address.port())),
             Default::default(),
             Duration::from_secs(10),
+            Duration::ZERO,
             false,
+            Default::default(),
         )
         .await
         .unwrap();
@@ -875,10 +999,14 @@ mod tests {
         match TcpConnection::open_connection(
             ConnectionId::from(0usize),
             Keypair::generate(),
+            None,
             stream,
             AddressType::Socket(address),
             None,
             Default::default(),
+            Default::default(),
+            vec![SecurityProtocol::Noise],
+            vec![StreamMuxer::Yamux],
             5,
             2,
             Duration::from_secs(10),
@@ -921,8 +1049,12 @@ mod tests {
             listener,
             ConnectionId::from(0usize),
             Keypair::generate(),
+            None,
             dialer_address,
             Default::default(),
+            Default::default(),
+            vec![SecurityProtocol::Noise],
+            vec![StreamMuxer::Yamux],
             5,
             2,
             Duration::from_secs(10),
@@ -968,8 +1100,12 @@ mod tests {
             listener,
             ConnectionId::from(0usize),
             Keypair::generate(),
+            None,
             dialer_address,
             Default::default(),
+            Default::default(),
+            vec![SecurityProtocol::Noise],
+            vec![StreamMuxer::Yamux],
             5,
             2,
             Duration::from_secs(10),
@@ -1009,7 +1145,9 @@ mod tests {
                 .with(Protocol::Tcp(address.port())),
             Default::default(),
             Duration::from_secs(10),
+            Duration::ZERO,
             false,
+            Default::default(),
         )
         .await
         .unwrap();
@@ -1017,10 +1155,14 @@ mod tests {
         match TcpConnection::open_connection(
             ConnectionId::from(0usize),
             Keypair::generate(),
+            None,
             stream,
             AddressType::Socket(address),
             None,
             Default::default(),
+            Default::default(),
+            vec![SecurityProtocol::Noise],
+            vec![StreamMuxer::Yamux],
             5,
             2,
             Duration::from_secs(10),
@@ -1055,7 +1197,9 @@ mod tests {
                 .with(Protocol::Tcp(address.port())),
             Default::default(),
             Duration::from_secs(10),
+            Duration::ZERO,
             false,
+            Default::default(),
         )
         .await
         .unwrap();
@@ -1063,10 +1207,14 @@ mod tests {
         match TcpConnection::open_connection(
             ConnectionId::from(0usize),
             Keypair::generate(),
+            None,
             stream,
             AddressType::Socket(address),
             None,
             Default::default(),
+            Default::default(),
+            vec![SecurityProtocol::Noise],
+            vec![StreamMuxer::Yamux],
             5,
             2,
             Duration::from_secs(10),
@@ -1105,8 +1253,12 @@ mod tests {
             listener,
             ConnectionId::from(0usize),
             Keypair::generate(),
+            None,
             dialer_address,
             Default::default(),
+            Default::default(),
+            vec![SecurityProtocol::Noise],
+            vec![StreamMuxer::Yamux],
             5,
             2,
             Duration::from_secs(10),
@@ -1146,8 +1298,16 @@ mod tests {
             let keypair = Keypair::generate();
 
             // do a noise handshake
-            let (stream, _peer) =
-                noise::handshake(stream.inner(), &keypair, Role::Dialer, 5, 2).await.unwrap();
+            let (stream, _peer, _) = noise::handshake(
+                stream.inner(),
+                &keypair,
+                Role::Dialer,
+                NoiseExtensions::default(),
+                5,
+                2,
+            )
+            .await
+            .unwrap();
             let stream: NoiseSocket<Compat<TcpStream>> = stream;
 
             // after the handshake, try to negotiate some random protocol instead of yamux
@@ -1160,8 +1320,12 @@ mod tests {
             listener,
             ConnectionId::from(0usize),
             Keypair::generate(),
+            None,
             dialer_address,
             Default::default(),
+            Default::default(),
+            vec![SecurityProtocol::Noise],
+            vec![StreamMuxer::Yamux],
             5,
             2,
             Duration::from_secs(10),
@@ -1196,8 +1360,16 @@ mod tests {
 
             // do a noise handshake
             let keypair = Keypair::generate();
-            let (stream, _peer) =
-                noise::handshake(stream.inner(), &keypair, Role::Listener, 5, 2).await.unwrap();
+            let (stream, _peer, _) = noise::handshake(
+                stream.inner(),
+                &keypair,
+                Role::Listener,
+                NoiseExtensions::default(),
+                5,
+                2,
+            )
+            .await
+            .unwrap();
             let stream: NoiseSocket<Compat<TcpStream>> = stream;
 
             // after the handshake, try to negotiate some random protocol instead of yamux
@@ -1210,7 +1382,9 @@ mod tests {
                 .with(Protocol::Tcp(address.port())),
             Default::default(),
             Duration::from_secs(10),
+            Duration::ZERO,
             false,
+            Default::default(),
         )
         .await
         .unwrap();
@@ -1218,10 +1392,14 @@ mod tests {
         match TcpConnection::open_connection(
             ConnectionId::from(0usize),
             Keypair::generate(),
+            None,
             stream,
             AddressType::Socket(address),
             None,
             Default::default(),
+            Default::default(),
+            vec![SecurityProtocol::Noise],
+            vec![StreamMuxer::Yamux],
             5,
             2,
             Duration::from_secs(10),
@@ -1262,8 +1440,16 @@ mod tests {
 
             // do a noise handshake
             let keypair = Keypair::generate();
-            let (stream, _peer) =
-                noise::handshake(stream.inner(), &keypair, Role::Dialer, 5, 2).await.unwrap();
+            let (stream, _peer, _) = noise::handshake(
+                stream.inner(),
+                &keypair,
+                Role::Dialer,
+                NoiseExtensions::default(),
+                5,
+                2,
+            )
+            .await
+            .unwrap();
             let _stream: NoiseSocket<Compat<TcpStream>> = stream;
 
             tokio::time::sleep(std::time::Duration::from_secs(60)).await;
@@ -1273,8 +1459,12 @@ mod tests {
             listener,
             ConnectionId::from(0usize),
             Keypair::generate(),
+            None,
             dialer_address,
             Default::default(),
+            Default::default(),
+            vec![SecurityProtocol::Noise],
+            vec![StreamMuxer::Yamux],
             5,
             2,
             Duration::from_secs(10),
@@ -1307,8 +1497,16 @@ mod tests {
 
             // do a noise handshake
             let keypair = Keypair::generate();
-            let (stream, _peer) =
-                noise::handshake(stream.inner(), &keypair, Role::Listener, 5, 2).await.unwrap();
+            let (stream, _peer, _) = noise::handshake(
+                stream.inner(),
+                &keypair,
+                Role::Listener,
+                NoiseExtensions::default(),
+                5,
+                2,
+            )
+            .await
+            .unwrap();
             let _stream: NoiseSocket<Compat<TcpStream>> = stream;
 
             tokio::time::sleep(std::time::Duration::from_secs(60)).await;
@@ -1320,7 +1518,9 @@ mod tests {
                 .with(Protocol::Tcp(address.port())),
             Default::default(),
             Duration::from_secs(10),
+            Duration::ZERO,
             false,
+            Default::default(),
         )
         .await
         .unwrap();
@@ -1328,10 +1528,14 @@ mod tests {
         match TcpConnection::open_connection(
             ConnectionId::from(0usize),
             Keypair::generate(),
+            None,
             stream,
             AddressType::Socket(address),
             None,
             Default::default(),
+            Default::default(),
+            vec![SecurityProtocol::Noise],
+            vec![StreamMuxer::Yamux],
             5,
             2,
             Duration::from_secs(10),
@@ -1344,4 +1548,225 @@ mod tests {
             Err(error) => panic!("invalid error: {error:?}"),
         }
     }
+
+    #[cfg(feature = "tls")]
+    #[tokio::test]
+    async fn tls_negotiated() {
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .try_init();
+
+        let listener = TcpListener::bind("[::1]:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let (Ok(dialer), Ok((stream, dialer_address))) =
+            tokio::join!(TcpStream::connect(address.clone()), listener.accept(),)
+        else {
+            panic!("failed to establish connection");
+        };
+
+        let dialer_keypair = Keypair::generate();
+        let listener_keypair = Keypair::generate();
+        let dialer_peer = PeerId::from_public_key(&dialer_keypair.public().into());
+        let listener_peer = PeerId::from_public_key(&listener_keypair.public().into());
+
+        let (dialer, listener) = tokio::join!(
+            TcpConnection::open_connection(
+                ConnectionId::from(0usize),
+                dialer_keypair,
+                None,
+                dialer,
+                AddressType::Socket(address),
+                Some(listener_peer),
+                Default::default(),
+                Default::default(),
+                vec![SecurityProtocol::Tls, SecurityProtocol::Noise],
+                vec![StreamMuxer::Yamux],
+                5,
+                2,
+                Duration::from_secs(10),
+                Duration::from_secs(10),
+            ),
+            TcpConnection::accept_connection(
+                stream,
+                ConnectionId::from(1usize),
+                listener_keypair,
+                None,
+                dialer_address,
+                Default::default(),
+                Default::default(),
+                vec![SecurityProtocol::Noise, SecurityProtocol::Tls],
+                vec![StreamMuxer::Yamux],
+                5,
+                2,
+                Duration::from_secs(10),
+                Duration::from_secs(10),
+            ),
+        );
+
+        assert_eq!(dialer.unwrap().peer(), listener_peer);
+        assert_eq!(listener.unwrap().peer(), dialer_peer);
+    }
+
+    #[tokio::test]
+    async fn stream_muxer_negotiated() {
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .try_init();
+
+        // noise agrees on the stream muxer during the handshake and tls uses multistream-select
+        for security_protocol in SecurityProtocol::defaults() {
+            let listener = TcpListener::bind("[::1]:0").await.unwrap();
+            let address = listener.local_addr().unwrap();
+
+            let (Ok(dialer), Ok((stream, dialer_address))) =
+                tokio::join!(TcpStream::connect(address.clone()), listener.accept(),)
+            else {
+                panic!("failed to establish connection");
+            };
+
+            let (dialer, listener) = tokio::join!(
+                TcpConnection::open_connection(
+                    ConnectionId::from(0usize),
+                    Keypair::generate(),
+                    None,
+                    dialer,
+                    AddressType::Socket(address),
+                    None,
+                    Default::default(),
+                    Default::default(),
+                    vec![security_protocol],
+                    vec![StreamMuxer::Mplex, StreamMuxer::Yamux],
+                    5,
+                    2,
+                    Duration::from_secs(10),
+                    Duration::from_secs(10),
+                ),
+                TcpConnection::accept_connection(
+                    stream,
+                    ConnectionId::from(1usize),
+                    Keypair::generate(),
+                    None,
+                    dialer_address,
+                    Default::default(),
+                    Default::default(),
+                    vec![security_protocol],
+                    vec![StreamMuxer::Yamux, StreamMuxer::Mplex],
+                    5,
+                    2,
+                    Duration::from_secs(10),
+                    Duration::from_secs(10),
+                ),
+            );
+
+            // dialer's preference wins
+            assert!(std::matches!(
+                dialer.unwrap().connection,
+                MuxedConnection::Mplex(_)
+            ));
+            assert!(std::matches!(
+                listener.unwrap().connection,
+                MuxedConnection::Mplex(_)
+            ));
+        }
+    }
+
+    async fn negotiate_private_network(
+        dialer_key: Option<PreSharedKey>,
+        listener_key: Option<PreSharedKey>,
+    ) -> (
+        Result<NegotiatedConnection, NegotiationError>,
+        Result<NegotiatedConnection, NegotiationError>,
+    ) {
+        let listener = TcpListener::bind("[::1]:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let (Ok(dialer), Ok((stream, dialer_address))) =
+            tokio::join!(TcpStream::connect(address.clone()), listener.accept(),)
+        else {
+            panic!("failed to establish connection");
+        };
+
+        tokio::join!(
+            TcpConnection::open_connection(
+                ConnectionId::from(0usize),
+                Keypair::generate(),
+                dialer_key,
+                dialer,
+                AddressType::Socket(address),
+                None,
+                Default::default(),
+                Default::default(),
+                vec![SecurityProtocol::Noise],
+                vec![StreamMuxer::Yamux],
+                5,
+                2,
+                Duration::from_secs(10),
+                Duration::from_secs(2),
+            ),
+            TcpConnection::accept_connection(
+                stream,
+                ConnectionId::from(1usize),
+                Keypair::generate(),
+                listener_key,
+                dialer_address,
+                Default::default(),
+                Default::default(),
+                vec![SecurityProtocol::Noise],
+                vec![StreamMuxer::Yamux],
+                5,
+                2,
+                Duration::from_secs(10),
+                Duration::from_secs(2),
+            ),
+        )
+    }
+
+    #[tokio::test]
+    async fn private_network_same_key() {
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .try_init();
+
+        let key = PreSharedKey::generate();
+        let (dialer, listener) = negotiate_private_network(Some(key.clone()), Some(key)).await;
+
+        assert!(dialer.is_ok());
+        assert!(listener.is_ok());
+    }
+
+    #[tokio::test]
+    async fn private_network_different_key() {
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .try_init();
+
+        let (dialer, listener) = negotiate_private_network(
+            Some(PreSharedKey::generate()),
+            Some(PreSharedKey::generate()),
+        )
+        .await;
+
+        // depending on how the garbled multistream-select messages are parsed, a peer either
+        // detects the mismatch, gets the connection reset by the remote peer or times out
+        // waiting for the rest of a message
+        assert!(dialer.is_err());
+        assert!(listener.is_err());
+    }
+
+    #[tokio::test]
+    async fn private_network_dials_public_node() {
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .try_init();
+
+        let (dialer, listener) =
+            negotiate_private_network(Some(PreSharedKey::generate()), None).await;
+
+        assert!(std::matches!(
+            dialer,
+            Err(NegotiationError::PreSharedKeyMismatch)
+        ));
+        assert!(listener.is_err());
+    }
 }
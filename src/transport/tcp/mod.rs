@@ -23,23 +23,26 @@
 
 use crate::{
     config::Role,
-    error::{DialError, Error},
+    error::{AddressError, DialError, Error},
     transport::{
-        common::listener::{DialAddresses, GetSocketAddr, SocketListener, TcpAddress},
-        manager::TransportHandle,
+        common::{
+            dns::DnsResolver,
+            listener::{DialAddresses, GetSocketAddr, SocketListener, TcpAddress},
+        },
+        manager::{dial_scheduler::staggered, TransportHandle},
         tcp::{
             config::Config,
             connection::{NegotiatedConnection, TcpConnection},
         },
         Transport, TransportBuilder, TransportEvent,
     },
-    types::ConnectionId,
+    types::{ConnectionId, ListenerId},
     utils::futures_stream::FuturesStream,
 };
 
 use futures::{
     future::BoxFuture,
-    stream::{AbortHandle, FuturesUnordered, Stream, StreamExt},
+    stream::{AbortHandle, Stream, StreamExt},
     TryFutureExt,
 };
 use multiaddr::Multiaddr;
@@ -56,7 +59,7 @@ use std::{
 
 pub(crate) use substream::Substream;
 
-mod connection;
+pub(crate) mod connection;
 mod substream;
 
 pub mod config;
@@ -64,6 +67,10 @@ pub mod config;
 /// Logging target for the file.
 const LOG_TARGET: &str = "litep2p::tcp";
 
+/// How long the listener side of a simultaneous open waits for the remote peer to start the
+/// negotiation before it starts the negotiation itself.
+const SIMULTANEOUS_OPEN_TIMEOUT: Duration = Duration::from_secs(2);
+
 /// Pending inbound connection.
 struct PendingInboundConnection {
     /// Socket address of the remote peer.
@@ -140,11 +147,15 @@ impl TcpTransport {
         address: SocketAddr,
     ) {
         let yamux_config = self.config.yamux_config.clone();
+        let mplex_config = self.config.mplex_config.clone();
+        let security_protocols = self.config.security_protocols.clone();
+        let stream_muxers = self.config.stream_muxers.clone();
         let max_read_ahead_factor = self.config.noise_read_ahead_frame_count;
         let max_write_buffer_size = self.config.noise_write_buffer_size;
         let connection_open_timeout = self.config.connection_open_timeout;
         let substream_open_timeout = self.config.substream_open_timeout;
         let keypair = self.context.keypair.clone();
+        let pre_shared_key = self.context.pre_shared_key.clone();
 
         tracing::trace!(
             target: LOG_TARGET,
@@ -158,8 +169,12 @@ impl TcpTransport {
                 connection,
                 connection_id,
                 keypair,
+                pre_shared_key,
                 address,
                 yamux_config,
+                mplex_config,
+                security_protocols,
+                stream_muxers,
                 max_read_ahead_factor,
                 max_write_buffer_size,
                 connection_open_timeout,
@@ -171,29 +186,80 @@ impl TcpTransport {
     }
 
     /// Dial remote peer
+    ///
+    /// If `address` is a DNS address, the resolved IP addresses are connected to
+    /// `connection_attempt_delay` apart and the first connection to succeed is used. All attempts
+    /// together are bounded by `connection_open_timeout`, the resolution itself by the resolution
+    /// timeout of `resolver`.
     async fn dial_peer(
         address: Multiaddr,
         dial_addresses: DialAddresses,
         connection_open_timeout: Duration,
+        connection_attempt_delay: Duration,
         nodelay: bool,
+        resolver: DnsResolver,
     ) -> Result<(Multiaddr, TcpStream), DialError> {
         let (socket_address, _) = TcpAddress::multiaddr_to_socket_address(&address)?;
+        let remote_addresses = socket_address.lookup_ip(&resolver).await?;
 
-        let remote_address =
-            match tokio::time::timeout(connection_open_timeout, socket_address.lookup_ip()).await {
-                Err(_) => {
-                    tracing::debug!(
-                        target: LOG_TARGET,
-                        ?address,
-                        ?connection_open_timeout,
-                        "failed to resolve address within timeout",
-                    );
-                    return Err(DialError::Timeout);
+        let attempts = remote_addresses.into_iter().map(|remote_address| {
+            let dial_addresses = &dial_addresses;
+            let address = &address;
+
+            async move {
+                match Self::connect(remote_address, dial_addresses, nodelay).await {
+                    Ok(stream) => {
+                        tracing::debug!(
+                            target: LOG_TARGET,
+                            ?address,
+                            ?remote_address,
+                            "connected",
+                        );
+
+                        Ok(stream)
+                    }
+                    Err(error) => {
+                        tracing::debug!(
+                            target: LOG_TARGET,
+                            ?address,
+                            ?remote_address,
+                            ?error,
+                            "failed to connect",
+                        );
+
+                        Err(error)
+                    }
                 }
-                Ok(Err(error)) => return Err(error.into()),
-                Ok(Ok(address)) => address,
-            };
+            }
+        });
 
+        match tokio::time::timeout(
+            connection_open_timeout,
+            staggered(attempts, connection_attempt_delay),
+        )
+        .await
+        {
+            Err(_) => {
+                tracing::debug!(
+                    target: LOG_TARGET,
+                    ?address,
+                    ?connection_open_timeout,
+                    "failed to connect within timeout",
+                );
+                Err(DialError::Timeout)
+            }
+            Ok(Err(mut errors)) =>
+                Err(errors.pop().expect("resolver to return at least one address")),
+            Ok(Ok(stream)) => Ok((address, stream)),
+        }
+    }
+
+    /// Open TCP connection to `remote_address`.
+    async fn connect(
+        remote_address: SocketAddr,
+        dial_addresses: &DialAddresses,
+        nodelay: bool,
+    ) -> Result<TcpStream, DialError> {
         let domain = match remote_address.is_ipv4() {
             true => Domain::IPV4,
             false => Domain::IPV6,
@@ -222,42 +288,44 @@ impl TcpTransport {
             }
         }
 
-        let future = async move {
-            match socket.connect(&remote_address.into()) {
-                Ok(()) => {}
-                Err(err) if err.raw_os_error() == Some(libc::EINPROGRESS) => {}
-                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {}
-                Err(err) => return Err(err),
-            }
+        match socket.connect(&remote_address.into()) {
+            Ok(()) => {}
+            Err(err) if err.raw_os_error() == Some(libc::EINPROGRESS) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {}
+            Err(err) => return Err(err.into()),
+        }
 
-            let stream = TcpStream::try_from(Into::<std::net::TcpStream>::into(socket))?;
-            stream.writable().await?;
+        let stream = TcpStream::try_from(Into::<std::net::TcpStream>::into(socket))?;
+        stream.writable().await?;
 
-            if let Some(e) = stream.take_error()? {
-                return Err(e);
-            }
+        if let Some(e) = stream.take_error()? {
+            return Err(e.into());
+        }
 
-            Ok((address, stream))
-        };
+        Ok(stream)
+    }
 
-        match tokio::time::timeout(connection_open_timeout, future).await {
+    /// Resolve the role of a connection dialed as the listener of a simultaneous open.
+    ///
+    /// If both peers dialed each other, the dials merge into one connection and the remote peer,
+    /// acting as the dialer, starts the negotiation right away. If the local dial reached the
+    /// listener of the remote peer instead, the remote peer waits for the local node to start the
+    /// negotiation and the local node must act as the dialer of the connection.
+    ///
+    /// If the connection is closed or fails before the remote peer starts the negotiation, the
+    /// error is returned and the connection must be aborted.
+    async fn simultaneous_open_role(stream: &TcpStream) -> std::io::Result<Role> {
+        match tokio::time::timeout(SIMULTANEOUS_OPEN_TIMEOUT, stream.peek(&mut [0u8; 1])).await {
+            Ok(Ok(0)) => Err(std::io::ErrorKind::UnexpectedEof.into()),
+            Ok(Ok(_)) => Ok(Role::Listener),
+            Ok(Err(error)) => Err(error),
             Err(_) => {
                 tracing::debug!(
                     target: LOG_TARGET,
-                    ?connection_open_timeout,
-                    "failed to connect within timeout",
-                );
-                Err(DialError::Timeout)
-            }
-            Ok(Err(error)) => Err(error.into()),
-            Ok(Ok((address, stream))) => {
-                tracing::debug!(
-                    target: LOG_TARGET,
-                    ?address,
-                    "connected",
+                    "remote peer didn't start negotiation, act as dialer",
                 );
 
-                Ok((address, stream))
+                Ok(Role::Dialer)
             }
         }
     }
@@ -280,7 +348,10 @@ impl TransportBuilder for TcpTransport {
 
         // start tcp listeners for all listen addresses
         let (listener, listen_addresses, dial_addresses) = SocketListener::new::<TcpAddress>(
-            std::mem::take(&mut config.listen_addresses),
+            std::mem::take(&mut config.listen_addresses)
+                .into_iter()
+                .map(|address| (context.next_listener_id(), address))
+                .collect(),
             config.reuse_port,
             config.nodelay,
         );
@@ -310,28 +381,44 @@ impl Transport for TcpTransport {
 
         let (socket_address, peer) = TcpAddress::multiaddr_to_socket_address(&address)?;
         let yamux_config = self.config.yamux_config.clone();
+        let mplex_config = self.config.mplex_config.clone();
+        let security_protocols = self.config.security_protocols.clone();
+        let stream_muxers = self.config.stream_muxers.clone();
         let max_read_ahead_factor = self.config.noise_read_ahead_frame_count;
         let max_write_buffer_size = self.config.noise_write_buffer_size;
         let connection_open_timeout = self.config.connection_open_timeout;
         let substream_open_timeout = self.config.substream_open_timeout;
         let dial_addresses = self.dial_addresses.clone();
         let keypair = self.context.keypair.clone();
+        let pre_shared_key = self.context.pre_shared_key.clone();
         let nodelay = self.config.nodelay;
+        let resolver = self.context.resolver.clone();
+        let connection_attempt_delay = self.context.connection_attempt_delay;
 
         self.pending_dials.insert(connection_id, address.clone());
         self.pending_connections.push(Box::pin(async move {
-            let (_, stream) =
-                TcpTransport::dial_peer(address, dial_addresses, connection_open_timeout, nodelay)
-                    .await
-                    .map_err(|error| (connection_id, error))?;
+            let (_, stream) = TcpTransport::dial_peer(
+                address,
+                dial_addresses,
+                connection_open_timeout,
+                connection_attempt_delay,
+                nodelay,
+                resolver,
+            )
+            .await
+            .map_err(|error| (connection_id, error))?;
 
             TcpConnection::open_connection(
                 connection_id,
                 keypair,
+                pre_shared_key,
                 stream,
                 socket_address,
                 peer,
                 yamux_config,
+                mplex_config,
+                security_protocols,
+                stream_muxers,
                 max_read_ahead_factor,
                 max_write_buffer_size,
                 connection_open_timeout,
@@ -344,6 +431,134 @@ impl Transport for TcpTransport {
         Ok(())
     }
 
+    fn dial_with_role(
+        &mut self,
+        connection_id: ConnectionId,
+        addresses: Vec<Multiaddr>,
+        role: Role,
+    ) -> crate::Result<()> {
+        // the outbound sockets must be bound to the listen port for the dials of both peers to
+        // meet each other
+        if std::matches!(role, Role::Listener)
+            && std::matches!(self.dial_addresses, DialAddresses::NoReuse)
+        {
+            tracing::debug!(
+                target: LOG_TARGET,
+                ?connection_id,
+                ?addresses,
+                "cannot dial as listener without `reuse_port`",
+            );
+
+            return Err(Error::NotSupported(
+                "simultaneous open requires `reuse_port`".to_string(),
+            ));
+        }
+
+        let Some(first_address) = addresses.first().cloned() else {
+            return Err(Error::AddressError(AddressError::AddressNotAvailable));
+        };
+
+        tracing::debug!(
+            target: LOG_TARGET,
+            ?connection_id,
+            ?addresses,
+            ?role,
+            "open connection as one side of a simultaneous open",
+        );
+
+        let dials = addresses
+            .into_iter()
+            .map(|address| {
+                let (socket_address, peer) = TcpAddress::multiaddr_to_socket_address(&address)?;
+                let yamux_config = self.config.yamux_config.clone();
+                let mplex_config = self.config.mplex_config.clone();
+                let security_protocols = self.config.security_protocols.clone();
+                let stream_muxers = self.config.stream_muxers.clone();
+                let max_read_ahead_factor = self.config.noise_read_ahead_frame_count;
+                let max_write_buffer_size = self.config.noise_write_buffer_size;
+                let connection_open_timeout = self.config.connection_open_timeout;
+                let substream_open_timeout = self.config.substream_open_timeout;
+                let dial_addresses = self.dial_addresses.clone();
+                let keypair = self.context.keypair.clone();
+                let pre_shared_key = self.context.pre_shared_key.clone();
+                let nodelay = self.config.nodelay;
+                let resolver = self.context.resolver.clone();
+                let connection_attempt_delay = self.context.connection_attempt_delay;
+
+                Ok(Box::pin(async move {
+                    let (address, stream) = TcpTransport::dial_peer(
+                        address,
+                        dial_addresses,
+                        connection_open_timeout,
+                        connection_attempt_delay,
+                        nodelay,
+                        resolver,
+                    )
+                    .await?;
+
+                    let role = match role {
+                        Role::Dialer => Role::Dialer,
+                        Role::Listener => TcpTransport::simultaneous_open_role(&stream)
+                            .await
+                            .inspect_err(|error| {
+                                tracing::debug!(
+                                    target: LOG_TARGET,
+                                    ?connection_id,
+                                    ?address,
+                                    ?error,
+                                    "connection closed before negotiation",
+                                );
+                            })?,
+                    };
+
+                    match tokio::time::timeout(
+                        connection_open_timeout,
+                        TcpConnection::negotiate_connection(
+                            stream,
+                            peer,
+                            connection_id,
+                            keypair,
+                            pre_shared_key,
+                            role,
+                            socket_address,
+                            yamux_config,
+                            mplex_config,
+                            security_protocols,
+                            stream_muxers,
+                            max_read_ahead_factor,
+                            max_write_buffer_size,
+                            substream_open_timeout,
+                        ),
+                    )
+                    .await
+                    {
+                        Err(_) => Err(DialError::Timeout),
+                        Ok(Err(error)) => Err(error.into()),
+                        Ok(Ok(connection)) => Ok(connection),
+                    }
+                }))
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        // the first connection negotiated over any of the addresses wins
+        let future = async move {
+            futures::future::select_ok(dials)
+                .await
+                .map(|(connection, _)| connection)
+                .map_err(|error| (connection_id, error))
+        };
+
+        // a canceled dial is not reported as its pending dial is removed in `cancel()`
+        let (future, handle) = futures::future::abortable(future);
+        let future = future.unwrap_or_else(move |_| Err((connection_id, DialError::Timeout)));
+
+        self.pending_dials.insert(connection_id, first_address);
+        self.pending_connections.push(Box::pin(future));
+        self.cancel_futures.insert(connection_id, handle);
+
+        Ok(())
+    }
+
     fn accept(&mut self, connection_id: ConnectionId) -> crate::Result<()> {
         let context = self
             .pending_open
@@ -419,54 +634,52 @@ impl Transport for TcpTransport {
         connection_id: ConnectionId,
         addresses: Vec<Multiaddr>,
     ) -> crate::Result<()> {
-        let num_addresses = addresses.len();
-        let mut futures: FuturesUnordered<_> = addresses
+        let connection_attempt_delay = self.context.connection_attempt_delay;
+        let attempts = addresses
             .into_iter()
             .map(|address| {
                 let dial_addresses = self.dial_addresses.clone();
                 let connection_open_timeout = self.config.connection_open_timeout;
                 let nodelay = self.config.nodelay;
+                let resolver = self.context.resolver.clone();
 
                 async move {
                     TcpTransport::dial_peer(
                         address.clone(),
                         dial_addresses,
                         connection_open_timeout,
+                        connection_attempt_delay,
                         nodelay,
+                        resolver,
                     )
                     .await
                     .map_err(|error| (address, error))
                 }
             })
-            .collect();
+            .collect::<Vec<_>>();
 
         // Future that will resolve to the first successful connection.
         let future = async move {
-            let mut errors = Vec::with_capacity(num_addresses);
-            while let Some(result) = futures.next().await {
-                match result {
-                    Ok((address, stream)) =>
-                        return RawConnectionResult::Connected {
-                            connection_id,
-                            address,
-                            stream,
-                        },
-                    Err(error) => {
-                        tracing::debug!(
-                            target: LOG_TARGET,
-                            ?connection_id,
-                            ?error,
-                            "failed to open connection",
-                        );
-                        errors.push(error)
+            match staggered(attempts, connection_attempt_delay).await {
+                Ok((address, stream)) => RawConnectionResult::Connected {
+                    connection_id,
+                    address,
+                    stream,
+                },
+                Err(errors) => {
+                    tracing::debug!(
+                        target: LOG_TARGET,
+                        ?connection_id,
+                        ?errors,
+                        "failed to open connection",
+                    );
+
+                    RawConnectionResult::Failed {
+                        connection_id,
+                        errors,
                     }
                 }
             }
-
-            RawConnectionResult::Failed {
-                connection_id,
-                errors,
-            }
         };
 
         let (fut, handle) = futures::future::abortable(future);
@@ -485,11 +698,15 @@ impl Transport for TcpTransport {
 
         let (socket_address, peer) = TcpAddress::multiaddr_to_socket_address(&address)?;
         let yamux_config = self.config.yamux_config.clone();
+        let mplex_config = self.config.mplex_config.clone();
+        let security_protocols = self.config.security_protocols.clone();
+        let stream_muxers = self.config.stream_muxers.clone();
         let max_read_ahead_factor = self.config.noise_read_ahead_frame_count;
         let max_write_buffer_size = self.config.noise_write_buffer_size;
         let connection_open_timeout = self.config.connection_open_timeout;
         let substream_open_timeout = self.config.substream_open_timeout;
         let keypair = self.context.keypair.clone();
+        let pre_shared_key = self.context.pre_shared_key.clone();
 
         tracing::trace!(
             target: LOG_TARGET,
@@ -507,9 +724,13 @@ impl Transport for TcpTransport {
                     peer,
                     connection_id,
                     keypair,
+                    pre_shared_key,
                     Role::Dialer,
                     socket_address,
                     yamux_config,
+                    mplex_config,
+                    security_protocols,
+                    stream_muxers,
                     max_read_ahead_factor,
                     max_write_buffer_size,
                     substream_open_timeout,
@@ -533,6 +754,31 @@ impl Transport for TcpTransport {
         // State clean-up happens inside the `poll_next`.
         if let Some(handle) = self.cancel_futures.get(&connection_id) {
             handle.abort();
+            self.pending_dials.remove(&connection_id);
+        }
+    }
+
+    fn listen_on(
+        &mut self,
+        listener_id: ListenerId,
+        address: Multiaddr,
+    ) -> crate::Result<Vec<Multiaddr>> {
+        tracing::debug!(target: LOG_TARGET, ?listener_id, ?address, "start tcp listener");
+
+        self.listener.listen_on::<TcpAddress>(
+            listener_id,
+            address,
+            self.config.reuse_port,
+            self.config.nodelay,
+        )
+    }
+
+    fn stop_listening(&mut self, listener_id: ListenerId) -> crate::Result<()> {
+        tracing::debug!(target: LOG_TARGET, ?listener_id, "stop tcp listener");
+
+        match self.listener.stop_listening(listener_id) {
+            true => Ok(()),
+            false => Err(Error::ListenerDoesntExist(listener_id)),
         }
     }
 }
@@ -541,6 +787,10 @@ impl Stream for TcpTransport {
     type Item = TransportEvent;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Poll::Ready(event) = self.listener.poll_address_change(cx) {
+            return Poll::Ready(Some(event));
+        }
+
         if let Poll::Ready(event) = self.listener.poll_next_unpin(cx) {
             return match event {
                 None => {
@@ -652,6 +902,7 @@ impl Stream for TcpTransport {
                     let peer = connection.peer();
                     let endpoint = connection.endpoint();
                     self.pending_dials.remove(&connection.connection_id());
+                    self.cancel_futures.remove(&connection.connection_id());
                     self.pending_open.insert(connection.connection_id(), connection);
 
                     return Poll::Ready(Some(TransportEvent::ConnectionEstablished {
@@ -660,6 +911,8 @@ impl Stream for TcpTransport {
                     }));
                 }
                 Err((connection_id, error)) => {
+                    self.cancel_futures.remove(&connection_id);
+
                     if let Some(address) = self.pending_dials.remove(&connection_id) {
                         return Poll::Ready(Some(TransportEvent::DialFailure {
                             connection_id,
@@ -684,8 +937,12 @@ mod tests {
         codec::ProtocolCodec,
         crypto::ed25519::Keypair,
         executor::DefaultExecutor,
-        transport::manager::{
-            limits::ConnectionLimitsConfig, ProtocolContext, SupportedTransport, TransportManager,
+        transport::{
+            manager::{
+                limits::ConnectionLimitsConfig, ProtocolContext, SupportedTransport,
+                TransportManager,
+            },
+            Endpoint,
         },
         types::protocol::ProtocolName,
         BandwidthSink, PeerId,
@@ -710,7 +967,11 @@ mod tests {
             executor: Arc::new(DefaultExecutor {}),
             next_substream_id: Default::default(),
             next_connection_id: Default::default(),
+            next_listener_id: Default::default(),
             keypair: keypair1.clone(),
+            pre_shared_key: None,
+            resolver: Default::default(),
+            connection_attempt_delay: Default::default(),
             tx: event_tx1,
             bandwidth_sink: bandwidth_sink.clone(),
 
@@ -740,7 +1001,11 @@ mod tests {
             executor: Arc::new(DefaultExecutor {}),
             next_substream_id: Default::default(),
             next_connection_id: Default::default(),
+            next_listener_id: Default::default(),
             keypair: keypair2.clone(),
+            pre_shared_key: None,
+            resolver: Default::default(),
+            connection_attempt_delay: Default::default(),
             tx: event_tx2,
             bandwidth_sink: bandwidth_sink.clone(),
 
@@ -803,7 +1068,11 @@ mod tests {
             executor: Arc::new(DefaultExecutor {}),
             next_substream_id: Default::default(),
             next_connection_id: Default::default(),
+            next_listener_id: Default::default(),
             keypair: keypair1.clone(),
+            pre_shared_key: None,
+            resolver: Default::default(),
+            connection_attempt_delay: Default::default(),
             tx: event_tx1,
             bandwidth_sink: bandwidth_sink.clone(),
 
@@ -833,7 +1102,11 @@ mod tests {
             executor: Arc::new(DefaultExecutor {}),
             next_substream_id: Default::default(),
             next_connection_id: Default::default(),
+            next_listener_id: Default::default(),
             keypair: keypair2.clone(),
+            pre_shared_key: None,
+            resolver: Default::default(),
+            connection_attempt_delay: Default::default(),
             tx: event_tx2,
             bandwidth_sink: bandwidth_sink.clone(),
 
@@ -891,7 +1164,11 @@ mod tests {
             executor: Arc::new(DefaultExecutor {}),
             next_substream_id: Default::default(),
             next_connection_id: Default::default(),
+            next_listener_id: Default::default(),
             keypair: keypair1.clone(),
+            pre_shared_key: None,
+            resolver: Default::default(),
+            connection_attempt_delay: Default::default(),
             tx: event_tx1,
             bandwidth_sink: bandwidth_sink.clone(),
 
@@ -915,6 +1192,8 @@ mod tests {
                     TransportEvent::ConnectionOpened { .. } => {}
                     TransportEvent::OpenFailure { .. } => {}
                     TransportEvent::PendingInboundConnection { .. } => {}
+                    TransportEvent::NewListenAddress { .. } => {}
+                    TransportEvent::ListenAddressExpired { .. } => {}
                 }
             }
         });
@@ -927,7 +1206,11 @@ mod tests {
             executor: Arc::new(DefaultExecutor {}),
             next_substream_id: Default::default(),
             next_connection_id: Default::default(),
+            next_listener_id: Default::default(),
             keypair: keypair2.clone(),
+            pre_shared_key: None,
+            resolver: Default::default(),
+            connection_attempt_delay: Default::default(),
             tx: event_tx2,
             bandwidth_sink: bandwidth_sink.clone(),
 
@@ -1023,4 +1306,252 @@ mod tests {
         ));
         assert!(transport.pending_dials.is_empty());
     }
+
+    fn tcp_transport(reuse_port: bool) -> (TcpTransport, Multiaddr) {
+        let (tx, _rx) = channel(64);
+        let (event_tx, _event_rx) = channel(64);
+
+        let handle = crate::transport::manager::TransportHandle {
+            executor: Arc::new(DefaultExecutor {}),
+            next_substream_id: Default::default(),
+            next_connection_id: Default::default(),
+            next_listener_id: Default::default(),
+            keypair: Keypair::generate(),
+            pre_shared_key: None,
+            resolver: Default::default(),
+            connection_attempt_delay: Default::default(),
+            tx: event_tx,
+            bandwidth_sink: BandwidthSink::new(),
+
+            protocols: HashMap::from_iter([(
+                ProtocolName::from("/notif/1"),
+                ProtocolContext {
+                    tx,
+                    codec: ProtocolCodec::Identity(32),
+                    fallback_names: Vec::new(),
+                },
+            )]),
+        };
+        let config = Config {
+            listen_addresses: vec!["/ip4/127.0.0.1/tcp/0".parse().unwrap()],
+            reuse_port,
+            ..Default::default()
+        };
+
+        let (transport, listen_addresses) = TcpTransport::new(handle, config).unwrap();
+        (transport, listen_addresses[0].clone())
+    }
+
+    /// Poll `transport` until a connection is established, accepting inbound connections.
+    async fn wait_connection(transport: &mut TcpTransport) -> Endpoint {
+        loop {
+            match transport.next().await.unwrap() {
+                TransportEvent::PendingInboundConnection { connection_id } => {
+                    transport.accept_pending(connection_id).unwrap();
+                }
+                TransportEvent::ConnectionEstablished { endpoint, .. } => return endpoint,
+                TransportEvent::DialFailure { .. } => {}
+                event => panic!("unexpected event: {event:?}"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn dial_as_listener_requires_reuse_port() {
+        let (mut transport, _) = tcp_transport(false);
+
+        assert!(std::matches!(
+            transport.dial_with_role(
+                ConnectionId::from(0usize),
+                vec!["/ip4/127.0.0.1/tcp/8888".parse().unwrap()],
+                Role::Listener,
+            ),
+            Err(Error::NotSupported(_))
+        ));
+        assert!(transport.pending_dials.is_empty());
+    }
+
+    #[tokio::test]
+    async fn dial_as_listener_falls_back_to_dialer() {
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .try_init();
+
+        let (mut transport1, listen_address) = tcp_transport(true);
+        let (mut transport2, _) = tcp_transport(true);
+
+        // the remote peer accepts the connection as listener and waits for negotiation to start
+        transport2
+            .dial_with_role(
+                ConnectionId::from(0usize),
+                vec![listen_address],
+                Role::Listener,
+            )
+            .unwrap();
+
+        let (endpoint1, endpoint2) = tokio::time::timeout(Duration::from_secs(10), async {
+            tokio::join!(
+                wait_connection(&mut transport1),
+                wait_connection(&mut transport2)
+            )
+        })
+        .await
+        .expect("connection to be established");
+
+        assert!(endpoint1.is_listener());
+        assert!(!endpoint2.is_listener());
+    }
+
+    #[tokio::test]
+    async fn simultaneous_open() {
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .try_init();
+
+        let (mut transport1, listen_address1) = tcp_transport(true);
+        let (mut transport2, listen_address2) = tcp_transport(true);
+
+        transport1.dial(ConnectionId::from(0usize), listen_address2).unwrap();
+        transport2
+            .dial_with_role(
+                ConnectionId::from(1usize),
+                vec![listen_address1],
+                Role::Listener,
+            )
+            .unwrap();
+
+        // depending on which dial reaches the other peer first, the connection is either a
+        // merged simultaneous open or a regular connection accepted by one of the listeners but
+        // the peers must always end up in opposite roles
+        let (endpoint1, endpoint2) = tokio::time::timeout(Duration::from_secs(10), async {
+            tokio::join!(
+                wait_connection(&mut transport1),
+                wait_connection(&mut transport2)
+            )
+        })
+        .await
+        .expect("connection to be established");
+
+        assert_ne!(endpoint1.is_listener(), endpoint2.is_listener());
+    }
+
+    #[tokio::test]
+    async fn dial_as_listener_races_addresses() {
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .try_init();
+
+        let (mut transport1, listen_address) = tcp_transport(true);
+        let (mut transport2, _) = tcp_transport(true);
+
+        // the first address refuses the connection
+        let unreachable = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let unreachable_address = Multiaddr::empty()
+            .with(Protocol::Ip4(std::net::Ipv4Addr::LOCALHOST))
+            .with(Protocol::Tcp(unreachable.local_addr().unwrap().port()));
+        drop(unreachable);
+
+        transport2
+            .dial_with_role(
+                ConnectionId::from(0usize),
+                vec![unreachable_address, listen_address],
+                Role::Listener,
+            )
+            .unwrap();
+
+        let (endpoint1, endpoint2) = tokio::time::timeout(Duration::from_secs(10), async {
+            tokio::join!(
+                wait_connection(&mut transport1),
+                wait_connection(&mut transport2)
+            )
+        })
+        .await
+        .expect("connection to be established");
+
+        assert!(endpoint1.is_listener());
+        assert!(!endpoint2.is_listener());
+        assert!(transport2.pending_dials.is_empty());
+        assert!(transport2.cancel_futures.is_empty());
+    }
+
+    #[tokio::test]
+    async fn dial_as_listener_fails_if_all_addresses_fail() {
+        let (mut transport, _) = tcp_transport(true);
+
+        let mut addresses = Vec::new();
+        for _ in 0..2 {
+            let unreachable = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            addresses.push(
+                Multiaddr::empty()
+                    .with(Protocol::Ip4(std::net::Ipv4Addr::LOCALHOST))
+                    .with(Protocol::Tcp(unreachable.local_addr().unwrap().port())),
+            );
+        }
+
+        transport
+            .dial_with_role(
+                ConnectionId::from(0usize),
+                addresses.clone(),
+                Role::Listener,
+            )
+            .unwrap();
+
+        match tokio::time::timeout(Duration::from_secs(10), transport.next())
+            .await
+            .expect("dial to fail")
+        {
+            Some(TransportEvent::DialFailure {
+                connection_id,
+                address,
+                ..
+            }) => {
+                assert_eq!(connection_id, ConnectionId::from(0usize));
+                assert_eq!(address, addresses[0]);
+            }
+            event => panic!("unexpected event: {event:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn canceled_dial_as_listener_not_reported() {
+        let (mut transport, _) = tcp_transport(true);
+        let unreachable = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = Multiaddr::empty()
+            .with(Protocol::Ip4(std::net::Ipv4Addr::LOCALHOST))
+            .with(Protocol::Tcp(unreachable.local_addr().unwrap().port()));
+        drop(unreachable);
+
+        transport
+            .dial_with_role(ConnectionId::from(0usize), vec![address], Role::Listener)
+            .unwrap();
+        transport.cancel(ConnectionId::from(0usize));
+
+        assert!(tokio::time::timeout(Duration::from_secs(2), transport.next()).await.is_err());
+        assert!(transport.pending_dials.is_empty());
+        assert!(transport.cancel_futures.is_empty());
+    }
+
+    #[tokio::test]
+    async fn simultaneous_open_aborted_if_connection_closes() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        // remote peer closes the connection
+        let stream = TcpStream::connect(address).await.unwrap();
+        let (remote, _) = listener.accept().await.unwrap();
+        drop(remote);
+
+        assert_eq!(
+            TcpTransport::simultaneous_open_role(&stream).await.unwrap_err().kind(),
+            std::io::ErrorKind::UnexpectedEof,
+        );
+
+        // remote peer resets the connection
+        let stream = TcpStream::connect(address).await.unwrap();
+        let (remote, _) = listener.accept().await.unwrap();
+        socket2::SockRef::from(&remote).set_linger(Some(Duration::ZERO)).unwrap();
+        drop(remote);
+
+        assert!(TcpTransport::simultaneous_open_role(&stream).await.is_err());
+    }
 }
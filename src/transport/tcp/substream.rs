@@ -18,7 +18,7 @@
 // FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
 // DEALINGS IN THE SOFTWARE.
 
-use crate::{protocol::Permit, BandwidthSink};
+use crate::{protocol::Permit, transport::common::muxer::MuxedStream, BandwidthSink};
 
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio_util::compat::Compat;
@@ -36,7 +36,7 @@ use std::{
 #[derive(Debug)]
 pub struct Substream {
     /// Underlying socket.
-    io: Compat<crate::yamux::Stream>,
+    io: Compat<MuxedStream>,
 
     /// Bandwidth sink.
     bandwidth_sink: BandwidthSink,
@@ -47,11 +47,7 @@ pub struct Substream {
 
 impl Substream {
     /// Create new [`Substream`].
-    pub fn new(
-        io: Compat<crate::yamux::Stream>,
-        bandwidth_sink: BandwidthSink,
-        _permit: Permit,
-    ) -> Self {
+    pub fn new(io: Compat<MuxedStream>, bandwidth_sink: BandwidthSink, _permit: Permit) -> Self {
         Self {
             io,
             bandwidth_sink,
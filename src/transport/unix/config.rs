@@ -0,0 +1,112 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Unix domain socket transport configuration.
+
+use crate::{
+    crypto::noise::{MAX_READ_AHEAD_FACTOR, MAX_WRITE_BUFFER_SIZE},
+    transport::{SecurityProtocol, StreamMuxer, CONNECTION_OPEN_TIMEOUT, SUBSTREAM_OPEN_TIMEOUT},
+};
+
+/// Unix domain socket transport configuration.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Listen addresses for the transport.
+    ///
+    /// The path of the socket is percent-encoded, e.g., `/unix/%2Frun%2Fnode.sock` listens on
+    /// `/run/node.sock`.
+    ///
+    /// Default is no listen addresses.
+    pub listen_addresses: Vec<multiaddr::Multiaddr>,
+
+    /// Permissions of the socket files, e.g., `0o660` to allow only the owner and the group of
+    /// the node to connect to it.
+    ///
+    /// If `None`, the permissions are determined by the umask of the process.
+    ///
+    /// Defaults to `None`.
+    pub socket_permissions: Option<u32>,
+
+    /// Whether to remove a socket file left behind by a process which didn't shut down cleanly.
+    ///
+    /// The file is removed only if no process accepts connections on it.
+    ///
+    /// Defaults to `true`.
+    pub remove_stale_socket: bool,
+
+    /// Yamux configuration.
+    pub yamux_config: crate::yamux::Config,
+
+    /// mplex configuration.
+    pub mplex_config: crate::mplex::Config,
+
+    /// Security protocols, in order of preference.
+    ///
+    /// Defaults to `[SecurityProtocol::Noise, SecurityProtocol::Tls]` if the `tls` feature is
+    /// enabled and to `[SecurityProtocol::Noise]` otherwise.
+    pub security_protocols: Vec<SecurityProtocol>,
+
+    /// Stream muxers, in order of preference.
+    ///
+    /// Defaults to `[StreamMuxer::Yamux]`.
+    pub stream_muxers: Vec<StreamMuxer>,
+
+    /// Noise read-ahead frame count.
+    ///
+    /// See [`tcp::config::Config::noise_read_ahead_frame_count`](
+    /// crate::transport::tcp::config::Config::noise_read_ahead_frame_count).
+    pub noise_read_ahead_frame_count: usize,
+
+    /// Noise write buffer size.
+    ///
+    /// See [`tcp::config::Config::noise_write_buffer_size`](
+    /// crate::transport::tcp::config::Config::noise_write_buffer_size).
+    pub noise_write_buffer_size: usize,
+
+    /// Connection open timeout.
+    ///
+    /// How long should litep2p wait for a connection to be opened and negotiated before the
+    /// peer is deemed unreachable.
+    pub connection_open_timeout: std::time::Duration,
+
+    /// Substream open timeout.
+    ///
+    /// How long should litep2p wait for a substream to be opened before considering
+    /// the substream rejected.
+    pub substream_open_timeout: std::time::Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            listen_addresses: Vec::new(),
+            socket_permissions: None,
+            remove_stale_socket: true,
+            yamux_config: Default::default(),
+            mplex_config: Default::default(),
+            security_protocols: SecurityProtocol::defaults(),
+            stream_muxers: vec![StreamMuxer::Yamux],
+            noise_read_ahead_frame_count: MAX_READ_AHEAD_FACTOR,
+            noise_write_buffer_size: MAX_WRITE_BUFFER_SIZE,
+            connection_open_timeout: CONNECTION_OPEN_TIMEOUT,
+            substream_open_timeout: SUBSTREAM_OPEN_TIMEOUT,
+        }
+    }
+}
@@ -0,0 +1,921 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Unix domain socket transport.
+//!
+//! Connects nodes running on the same host through Unix domain sockets, using `/unix/<path>`
+//! addresses where `<path>` is the percent-encoded path of the socket file. The connections are
+//! negotiated the same way as TCP connections.
+
+use crate::{
+    config::Role,
+    error::{AddressError, DialError, Error},
+    transport::{
+        manager::{dial_scheduler::staggered, TransportHandle},
+        tcp::connection::{NegotiatedConnection, TcpConnection},
+        unix::config::Config,
+        Transport, TransportBuilder, TransportEvent,
+    },
+    types::ConnectionId,
+    utils::futures_stream::FuturesStream,
+    PeerId,
+};
+
+use futures::{
+    future::BoxFuture,
+    stream::{AbortHandle, Stream, StreamExt},
+    TryFutureExt,
+};
+use multiaddr::{Multiaddr, Protocol};
+use tokio::net::{UnixListener, UnixStream};
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    io,
+    os::unix::fs::{FileTypeExt, PermissionsExt},
+    path::{Path, PathBuf},
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+pub mod config;
+
+/// Logging target for the file.
+const LOG_TARGET: &str = "litep2p::unix";
+
+/// Pending inbound connection.
+struct PendingInboundConnection {
+    /// Stream of the connection.
+    connection: UnixStream,
+
+    /// Listen address the connection was accepted on.
+    address: Multiaddr,
+}
+
+#[derive(Debug)]
+enum RawConnectionResult {
+    /// The first successful connection.
+    Connected {
+        connection_id: ConnectionId,
+        address: Multiaddr,
+        stream: UnixStream,
+    },
+
+    /// All connection attempts failed.
+    Failed {
+        connection_id: ConnectionId,
+        errors: Vec<(Multiaddr, DialError)>,
+    },
+
+    /// Future was canceled.
+    Canceled { connection_id: ConnectionId },
+}
+
+/// Socket listener.
+struct Listener {
+    /// Unix listener.
+    listener: UnixListener,
+
+    /// Path of the socket file.
+    path: PathBuf,
+
+    /// Listen address.
+    address: Multiaddr,
+}
+
+/// Decode the percent-encoded socket path of a `/unix/<path>` address.
+fn decode_path(path: &str) -> Result<PathBuf, AddressError> {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0usize;
+
+    while index < bytes.len() {
+        if bytes[index] != b'%' {
+            decoded.push(bytes[index]);
+            index += 1;
+            continue;
+        }
+
+        let byte = bytes
+            .get(index + 1..index + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            .ok_or(AddressError::InvalidProtocol)?;
+        decoded.push(byte);
+        index += 3;
+    }
+
+    String::from_utf8(decoded)
+        .map(PathBuf::from)
+        .map_err(|_| AddressError::InvalidProtocol)
+}
+
+/// Percent-encode `path` for a `/unix/<path>` address.
+fn encode_path(path: &Path) -> Result<String, AddressError> {
+    let path = path.to_str().ok_or(AddressError::InvalidProtocol)?;
+
+    Ok(path.replace('%', "%25").replace('/', "%2F"))
+}
+
+/// Extract the socket path and the optional peer ID from a Unix address.
+fn multiaddr_to_path(address: &Multiaddr) -> Result<(PathBuf, Option<PeerId>), AddressError> {
+    let mut iter = address.iter();
+
+    let path = match iter.next() {
+        Some(Protocol::Unix(path)) => decode_path(&path)?,
+        protocol => {
+            tracing::debug!(
+                target: LOG_TARGET,
+                ?protocol,
+                "invalid transport protocol, expected `Unix`",
+            );
+            return Err(AddressError::InvalidProtocol);
+        }
+    };
+
+    let peer = match iter.next() {
+        Some(Protocol::P2p(multihash)) =>
+            Some(PeerId::from_multihash(multihash).map_err(AddressError::InvalidPeerId)?),
+        None => None,
+        protocol => {
+            tracing::debug!(
+                target: LOG_TARGET,
+                ?protocol,
+                "invalid protocol, expected `P2p` or `None`"
+            );
+            return Err(AddressError::InvalidProtocol);
+        }
+    };
+
+    Ok((path, peer))
+}
+
+/// Create a `/unix/<path>` address for `path`.
+fn path_to_multiaddr(path: &Path) -> Result<Multiaddr, AddressError> {
+    Ok(Multiaddr::empty().with(Protocol::Unix(Cow::Owned(encode_path(path)?))))
+}
+
+/// Unix domain socket transport.
+pub(crate) struct UnixTransport {
+    /// Transport context.
+    context: TransportHandle,
+
+    /// Transport configuration.
+    config: Config,
+
+    /// Socket listeners.
+    listeners: Vec<Listener>,
+
+    /// Pending dials.
+    pending_dials: HashMap<ConnectionId, Multiaddr>,
+
+    /// Pending inbound connections.
+    pending_inbound_connections: HashMap<ConnectionId, PendingInboundConnection>,
+
+    /// Pending opening connections.
+    pending_connections: FuturesStream<
+        BoxFuture<'static, Result<NegotiatedConnection<UnixStream>, (ConnectionId, DialError)>>,
+    >,
+
+    /// Pending raw, unnegotiated connections.
+    pending_raw_connections: FuturesStream<BoxFuture<'static, RawConnectionResult>>,
+
+    /// Opened raw connection, waiting for approval/rejection from `TransportManager`.
+    opened_raw: HashMap<ConnectionId, (UnixStream, Multiaddr)>,
+
+    /// Cancel raw connections futures.
+    ///
+    /// This is cancelling `Self::pending_raw_connections`.
+    cancel_futures: HashMap<ConnectionId, AbortHandle>,
+
+    /// Connections which have been opened and negotiated but are being validated by the
+    /// `TransportManager`.
+    pending_open: HashMap<ConnectionId, NegotiatedConnection<UnixStream>>,
+}
+
+impl UnixTransport {
+    /// Bind a listener to `path`.
+    ///
+    /// If `remove_stale_socket` is `true` and `path` is a socket file nobody accepts connections
+    /// on, the file is removed and the bind is retried.
+    fn bind(path: &Path, remove_stale_socket: bool) -> io::Result<UnixListener> {
+        match UnixListener::bind(path) {
+            Err(error) if error.kind() == io::ErrorKind::AddrInUse && remove_stale_socket => {
+                // never remove a file which isn't a socket
+                if !std::fs::metadata(path)?.file_type().is_socket() {
+                    return Err(error);
+                }
+
+                match std::os::unix::net::UnixStream::connect(path) {
+                    Err(error) if error.kind() == io::ErrorKind::ConnectionRefused => {
+                        tracing::debug!(
+                            target: LOG_TARGET,
+                            ?path,
+                            "remove stale socket file",
+                        );
+
+                        std::fs::remove_file(path)?;
+                        UnixListener::bind(path)
+                    }
+                    _ => Err(error),
+                }
+            }
+            result => result,
+        }
+    }
+
+    /// Negotiate `stream` in `role`.
+    fn negotiate_connection(
+        &mut self,
+        connection_id: ConnectionId,
+        stream: UnixStream,
+        role: Role,
+        peer: Option<PeerId>,
+        address: Multiaddr,
+    ) {
+        let yamux_config = self.config.yamux_config.clone();
+        let mplex_config = self.config.mplex_config.clone();
+        let security_protocols = self.config.security_protocols.clone();
+        let stream_muxers = self.config.stream_muxers.clone();
+        let max_read_ahead_factor = self.config.noise_read_ahead_frame_count;
+        let max_write_buffer_size = self.config.noise_write_buffer_size;
+        let connection_open_timeout = self.config.connection_open_timeout;
+        let substream_open_timeout = self.config.substream_open_timeout;
+        let keypair = self.context.keypair.clone();
+        let pre_shared_key = self.context.pre_shared_key.clone();
+
+        self.pending_connections.push(Box::pin(async move {
+            match tokio::time::timeout(connection_open_timeout, async move {
+                TcpConnection::negotiate_stream(
+                    stream,
+                    peer,
+                    connection_id,
+                    keypair,
+                    pre_shared_key,
+                    role,
+                    address,
+                    yamux_config,
+                    mplex_config,
+                    security_protocols,
+                    stream_muxers,
+                    max_read_ahead_factor,
+                    max_write_buffer_size,
+                    substream_open_timeout,
+                )
+                .await
+                .map_err(|error| (connection_id, error.into()))
+            })
+            .await
+            {
+                Err(_) => Err((connection_id, DialError::Timeout)),
+                Ok(Err(error)) => Err(error),
+                Ok(Ok(connection)) => Ok(connection),
+            }
+        }));
+    }
+
+    /// Connect to the socket of `address`.
+    async fn dial_peer(
+        address: Multiaddr,
+        connection_open_timeout: Duration,
+    ) -> Result<(Multiaddr, UnixStream), DialError> {
+        let (path, _) = multiaddr_to_path(&address)?;
+
+        match tokio::time::timeout(connection_open_timeout, UnixStream::connect(&path)).await {
+            Err(_) => {
+                tracing::debug!(
+                    target: LOG_TARGET,
+                    ?connection_open_timeout,
+                    "failed to connect within timeout",
+                );
+                Err(DialError::Timeout)
+            }
+            Ok(Err(error)) => Err(error.into()),
+            Ok(Ok(stream)) => {
+                tracing::debug!(target: LOG_TARGET, ?address, "connected");
+
+                Ok((address, stream))
+            }
+        }
+    }
+}
+
+impl Drop for UnixTransport {
+    fn drop(&mut self) {
+        for Listener { path, .. } in &self.listeners {
+            if let Err(error) = std::fs::remove_file(path) {
+                tracing::debug!(
+                    target: LOG_TARGET,
+                    ?path,
+                    ?error,
+                    "failed to remove socket file",
+                );
+            }
+        }
+    }
+}
+
+impl TransportBuilder for UnixTransport {
+    type Config = Config;
+    type Transport = UnixTransport;
+
+    /// Create new [`UnixTransport`].
+    fn new(
+        context: TransportHandle,
+        config: Self::Config,
+    ) -> crate::Result<(Self, Vec<Multiaddr>)> {
+        tracing::debug!(
+            target: LOG_TARGET,
+            listen_addresses = ?config.listen_addresses,
+            "start unix transport",
+        );
+
+        let mut transport = Self {
+            context,
+            config,
+            listeners: Vec::new(),
+            pending_dials: HashMap::new(),
+            pending_inbound_connections: HashMap::new(),
+            pending_connections: FuturesStream::new(),
+            pending_raw_connections: FuturesStream::new(),
+            opened_raw: HashMap::new(),
+            cancel_futures: HashMap::new(),
+            pending_open: HashMap::new(),
+        };
+
+        // the transport is dropped on error which removes the socket files bound so far
+        for address in std::mem::take(&mut transport.config.listen_addresses) {
+            let path = match multiaddr_to_path(&address)? {
+                (path, None) => path,
+                (_, Some(_)) => return Err(Error::AddressError(AddressError::InvalidProtocol)),
+            };
+            let address = path_to_multiaddr(&path)?;
+            let listener = Self::bind(&path, transport.config.remove_stale_socket)?;
+
+            transport.listeners.push(Listener {
+                listener,
+                path: path.clone(),
+                address,
+            });
+
+            if let Some(mode) = transport.config.socket_permissions {
+                std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode))?;
+            }
+        }
+
+        let listen_addresses =
+            transport.listeners.iter().map(|listener| listener.address.clone()).collect();
+
+        Ok((transport, listen_addresses))
+    }
+}
+
+impl Transport for UnixTransport {
+    fn dial(&mut self, connection_id: ConnectionId, address: Multiaddr) -> crate::Result<()> {
+        tracing::debug!(target: LOG_TARGET, ?connection_id, ?address, "open connection");
+
+        let (_, peer) = multiaddr_to_path(&address)?;
+        let connection_open_timeout = self.config.connection_open_timeout;
+        let endpoint_address = address.clone();
+
+        self.pending_dials.insert(connection_id, address.clone());
+
+        let yamux_config = self.config.yamux_config.clone();
+        let mplex_config = self.config.mplex_config.clone();
+        let security_protocols = self.config.security_protocols.clone();
+        let stream_muxers = self.config.stream_muxers.clone();
+        let max_read_ahead_factor = self.config.noise_read_ahead_frame_count;
+        let max_write_buffer_size = self.config.noise_write_buffer_size;
+        let substream_open_timeout = self.config.substream_open_timeout;
+        let keypair = self.context.keypair.clone();
+        let pre_shared_key = self.context.pre_shared_key.clone();
+
+        self.pending_connections.push(Box::pin(async move {
+            let (_, stream) = UnixTransport::dial_peer(address, connection_open_timeout)
+                .await
+                .map_err(|error| (connection_id, error))?;
+
+            match tokio::time::timeout(
+                connection_open_timeout,
+                TcpConnection::negotiate_stream(
+                    stream,
+                    peer,
+                    connection_id,
+                    keypair,
+                    pre_shared_key,
+                    Role::Dialer,
+                    endpoint_address.iter().take(1).collect(),
+                    yamux_config,
+                    mplex_config,
+                    security_protocols,
+                    stream_muxers,
+                    max_read_ahead_factor,
+                    max_write_buffer_size,
+                    substream_open_timeout,
+                ),
+            )
+            .await
+            {
+                Err(_) => Err((connection_id, DialError::Timeout)),
+                Ok(Err(error)) => Err((connection_id, error.into())),
+                Ok(Ok(connection)) => Ok(connection),
+            }
+        }));
+
+        Ok(())
+    }
+
+    fn accept(&mut self, connection_id: ConnectionId) -> crate::Result<()> {
+        let context = self
+            .pending_open
+            .remove(&connection_id)
+            .ok_or(Error::ConnectionDoesntExist(connection_id))?;
+
+        tracing::trace!(
+            target: LOG_TARGET,
+            ?connection_id,
+            "start connection",
+        );
+
+        self.context.start_connection(context);
+
+        Ok(())
+    }
+
+    fn accept_pending(&mut self, connection_id: ConnectionId) -> crate::Result<()> {
+        let PendingInboundConnection {
+            connection,
+            address,
+        } = self.pending_inbound_connections.remove(&connection_id).ok_or_else(|| {
+            tracing::error!(
+                target: LOG_TARGET,
+                ?connection_id,
+                "Cannot accept non existent pending connection",
+            );
+
+            Error::ConnectionDoesntExist(connection_id)
+        })?;
+
+        self.negotiate_connection(connection_id, connection, Role::Listener, None, address);
+
+        Ok(())
+    }
+
+    fn reject_pending(&mut self, connection_id: ConnectionId) -> crate::Result<()> {
+        self.pending_inbound_connections.remove(&connection_id).map_or_else(
+            || {
+                tracing::error!(
+                    target: LOG_TARGET,
+                    ?connection_id,
+                    "Cannot reject non existent pending connection",
+                );
+
+                Err(Error::ConnectionDoesntExist(connection_id))
+            },
+            |_| Ok(()),
+        )
+    }
+
+    fn reject(&mut self, connection_id: ConnectionId) -> crate::Result<()> {
+        self.pending_open
+            .remove(&connection_id)
+            .map_or(Err(Error::ConnectionDoesntExist(connection_id)), |_| Ok(()))
+    }
+
+    fn open(
+        &mut self,
+        connection_id: ConnectionId,
+        addresses: Vec<Multiaddr>,
+    ) -> crate::Result<()> {
+        let connection_open_timeout = self.config.connection_open_timeout;
+        let attempts = addresses
+            .into_iter()
+            .map(|address| async move {
+                UnixTransport::dial_peer(address.clone(), connection_open_timeout)
+                    .await
+                    .map_err(|error| (address, error))
+            })
+            .collect::<Vec<_>>();
+
+        let connection_attempt_delay = self.context.connection_attempt_delay;
+
+        // Future that will resolve to the first successful connection.
+        let future = async move {
+            match staggered(attempts, connection_attempt_delay).await {
+                Ok((address, stream)) => RawConnectionResult::Connected {
+                    connection_id,
+                    address,
+                    stream,
+                },
+                Err(errors) => {
+                    tracing::debug!(
+                        target: LOG_TARGET,
+                        ?connection_id,
+                        ?errors,
+                        "failed to open connection",
+                    );
+
+                    RawConnectionResult::Failed {
+                        connection_id,
+                        errors,
+                    }
+                }
+            }
+        };
+
+        let (fut, handle) = futures::future::abortable(future);
+        let fut = fut.unwrap_or_else(move |_| RawConnectionResult::Canceled { connection_id });
+        self.pending_raw_connections.push(Box::pin(fut));
+        self.cancel_futures.insert(connection_id, handle);
+
+        Ok(())
+    }
+
+    fn negotiate(&mut self, connection_id: ConnectionId) -> crate::Result<()> {
+        let (stream, address) = self
+            .opened_raw
+            .remove(&connection_id)
+            .ok_or(Error::ConnectionDoesntExist(connection_id))?;
+        let (_, peer) = multiaddr_to_path(&address)?;
+
+        tracing::trace!(
+            target: LOG_TARGET,
+            ?peer,
+            ?connection_id,
+            ?address,
+            "negotiate connection",
+        );
+
+        let endpoint_address = address.iter().take(1).collect();
+        self.pending_dials.insert(connection_id, address);
+        self.negotiate_connection(connection_id, stream, Role::Dialer, peer, endpoint_address);
+
+        Ok(())
+    }
+
+    fn cancel(&mut self, connection_id: ConnectionId) {
+        // Cancel the future if it exists.
+        // State clean-up happens inside the `poll_next`.
+        if let Some(handle) = self.cancel_futures.get(&connection_id) {
+            handle.abort();
+        }
+    }
+}
+
+impl Stream for UnixTransport {
+    type Item = TransportEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut accepted = None;
+
+        for listener in &self.listeners {
+            match listener.listener.poll_accept(cx) {
+                Poll::Pending => {}
+                Poll::Ready(Err(error)) => {
+                    tracing::debug!(
+                        target: LOG_TARGET,
+                        path = ?listener.path,
+                        ?error,
+                        "failed to accept connection",
+                    );
+                }
+                Poll::Ready(Ok((connection, _))) => {
+                    accepted = Some(PendingInboundConnection {
+                        connection,
+                        address: listener.address.clone(),
+                    });
+                    break;
+                }
+            }
+        }
+
+        if let Some(connection) = accepted {
+            let connection_id = self.context.next_connection_id();
+            tracing::trace!(
+                target: LOG_TARGET,
+                ?connection_id,
+                address = ?connection.address,
+                "pending inbound unix connection",
+            );
+
+            self.pending_inbound_connections.insert(connection_id, connection);
+
+            return Poll::Ready(Some(TransportEvent::PendingInboundConnection {
+                connection_id,
+            }));
+        }
+
+        while let Poll::Ready(Some(result)) = self.pending_raw_connections.poll_next_unpin(cx) {
+            tracing::trace!(target: LOG_TARGET, ?result, "raw connection result");
+
+            match result {
+                RawConnectionResult::Connected {
+                    connection_id,
+                    address,
+                    stream,
+                } => {
+                    let Some(handle) = self.cancel_futures.remove(&connection_id) else {
+                        tracing::warn!(
+                            target: LOG_TARGET,
+                            ?connection_id,
+                            ?address,
+                            "raw connection without a cancel handle",
+                        );
+                        continue;
+                    };
+
+                    if !handle.is_aborted() {
+                        self.opened_raw.insert(connection_id, (stream, address.clone()));
+
+                        return Poll::Ready(Some(TransportEvent::ConnectionOpened {
+                            connection_id,
+                            address,
+                        }));
+                    }
+                }
+                RawConnectionResult::Failed {
+                    connection_id,
+                    errors,
+                } => {
+                    let Some(handle) = self.cancel_futures.remove(&connection_id) else {
+                        tracing::warn!(
+                            target: LOG_TARGET,
+                            ?connection_id,
+                            ?errors,
+                            "raw connection without a cancel handle",
+                        );
+                        continue;
+                    };
+
+                    if !handle.is_aborted() {
+                        return Poll::Ready(Some(TransportEvent::OpenFailure {
+                            connection_id,
+                            errors,
+                        }));
+                    }
+                }
+                RawConnectionResult::Canceled { connection_id } => {
+                    if self.cancel_futures.remove(&connection_id).is_none() {
+                        tracing::warn!(
+                            target: LOG_TARGET,
+                            ?connection_id,
+                            "raw cancelled connection without a cancel handle",
+                        );
+                    }
+                }
+            }
+        }
+
+        while let Poll::Ready(Some(connection)) = self.pending_connections.poll_next_unpin(cx) {
+            match connection {
+                Ok(connection) => {
+                    let peer = connection.peer();
+                    let endpoint = connection.endpoint();
+                    self.pending_dials.remove(&connection.connection_id());
+                    self.pending_open.insert(connection.connection_id(), connection);
+
+                    return Poll::Ready(Some(TransportEvent::ConnectionEstablished {
+                        peer,
+                        endpoint,
+                    }));
+                }
+                Err((connection_id, error)) => {
+                    if let Some(address) = self.pending_dials.remove(&connection_id) {
+                        return Poll::Ready(Some(TransportEvent::DialFailure {
+                            connection_id,
+                            address,
+                            error,
+                        }));
+                    } else {
+                        tracing::debug!(target: LOG_TARGET, ?error, ?connection_id, "Pending inbound connection failed");
+                    }
+                }
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        codec::ProtocolCodec, crypto::ed25519::Keypair, executor::DefaultExecutor,
+        transport::manager::ProtocolContext, types::protocol::ProtocolName, BandwidthSink,
+    };
+    use std::sync::Arc;
+    use tokio::sync::mpsc::channel;
+
+    fn socket_path() -> PathBuf {
+        std::env::temp_dir().join(format!("litep2p-{}.sock", rand::random::<u64>()))
+    }
+
+    fn unix_transport(config: Config) -> crate::Result<(UnixTransport, Vec<Multiaddr>, PeerId)> {
+        let keypair = Keypair::generate();
+        let peer = PeerId::from_public_key(&keypair.public().into());
+        let (tx, _rx) = channel(64);
+        let (event_tx, _event_rx) = channel(64);
+
+        let handle = TransportHandle {
+            executor: Arc::new(DefaultExecutor {}),
+            next_substream_id: Default::default(),
+            next_connection_id: Default::default(),
+            next_listener_id: Default::default(),
+            keypair,
+            pre_shared_key: None,
+            resolver: Default::default(),
+            connection_attempt_delay: Default::default(),
+            tx: event_tx,
+            bandwidth_sink: BandwidthSink::new(),
+
+            protocols: HashMap::from_iter([(
+                ProtocolName::from("/notif/1"),
+                ProtocolContext {
+                    tx,
+                    codec: ProtocolCodec::Identity(32),
+                    fallback_names: Vec::new(),
+                },
+            )]),
+        };
+        let (transport, listen_addresses) = UnixTransport::new(handle, config)?;
+
+        Ok((transport, listen_addresses, peer))
+    }
+
+    fn listen_on(path: &Path) -> Config {
+        Config {
+            listen_addresses: vec![path_to_multiaddr(path).unwrap()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn path_encoding() {
+        let path = Path::new("/tmp/100%/node.sock");
+        let address = path_to_multiaddr(path).unwrap();
+
+        assert_eq!(address.to_string(), "/unix/%2Ftmp%2F100%25%2Fnode.sock");
+        assert_eq!(
+            multiaddr_to_path(&address.to_string().parse().unwrap()).unwrap(),
+            (path.to_path_buf(), None)
+        );
+
+        // unencoded paths are accepted as well
+        let address = Multiaddr::empty().with(Protocol::Unix(Cow::Borrowed("/tmp/node.sock")));
+        assert_eq!(
+            multiaddr_to_path(&address).unwrap().0,
+            PathBuf::from("/tmp/node.sock")
+        );
+
+        let address = Multiaddr::empty().with(Protocol::Unix(Cow::Borrowed("%2")));
+        assert!(multiaddr_to_path(&address).is_err());
+    }
+
+    #[tokio::test]
+    async fn connect_and_accept_works() {
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .try_init();
+
+        let path = socket_path();
+        let (mut transport1, _, peer1) = unix_transport(Default::default()).unwrap();
+        let (mut transport2, listen_addresses, peer2) = unix_transport(listen_on(&path)).unwrap();
+
+        transport1
+            .dial(
+                ConnectionId::from(0usize),
+                listen_addresses[0].clone().with(Protocol::P2p(peer2.into())),
+            )
+            .unwrap();
+
+        let mut dialer_event = None;
+        let mut listener_event = None;
+
+        while dialer_event.is_none() || listener_event.is_none() {
+            tokio::select! {
+                event = transport1.next() => dialer_event = event,
+                event = transport2.next() => match event {
+                    Some(TransportEvent::PendingInboundConnection { connection_id }) =>
+                        transport2.accept_pending(connection_id).unwrap(),
+                    event => listener_event = event,
+                },
+            }
+        }
+
+        match dialer_event {
+            Some(TransportEvent::ConnectionEstablished { peer, endpoint }) => {
+                assert_eq!(peer, peer2);
+                assert_eq!(endpoint.address(), &listen_addresses[0]);
+            }
+            event => panic!("invalid event: {event:?}"),
+        }
+        match listener_event {
+            Some(TransportEvent::ConnectionEstablished { peer, endpoint }) => {
+                assert_eq!(peer, peer1);
+                assert!(endpoint.is_listener());
+                assert_eq!(endpoint.address(), &listen_addresses[0]);
+            }
+            event => panic!("invalid event: {event:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn socket_file_removed_on_shutdown() {
+        let path = socket_path();
+        let (transport, _, _) = unix_transport(listen_on(&path)).unwrap();
+        assert!(path.exists());
+
+        drop(transport);
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn socket_permissions_applied() {
+        let path = socket_path();
+        let (_transport, _, _) = unix_transport(Config {
+            socket_permissions: Some(0o600),
+            ..listen_on(&path)
+        })
+        .unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[tokio::test]
+    async fn stale_socket_file_is_replaced() {
+        let path = socket_path();
+
+        // socket file left behind by a listener which is gone
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+
+        assert!(std::matches!(
+            unix_transport(Config {
+                remove_stale_socket: false,
+                ..listen_on(&path)
+            }),
+            Err(Error::IoError(io::ErrorKind::AddrInUse))
+        ));
+
+        let (_transport, _, _) = unix_transport(listen_on(&path)).unwrap();
+
+        // a socket in use is never removed
+        assert!(std::matches!(
+            unix_transport(listen_on(&path)),
+            Err(Error::IoError(io::ErrorKind::AddrInUse))
+        ));
+        assert!(path.exists());
+    }
+
+    #[tokio::test]
+    async fn regular_file_is_not_removed() {
+        let path = socket_path();
+        std::fs::write(&path, b"hello").unwrap();
+
+        assert!(unix_transport(listen_on(&path)).is_err());
+        assert_eq!(std::fs::read(&path).unwrap(), b"hello");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn dial_missing_socket() {
+        let (mut transport, _, _) = unix_transport(Default::default()).unwrap();
+        let address = path_to_multiaddr(&socket_path()).unwrap();
+
+        transport.dial(ConnectionId::from(0usize), address.clone()).unwrap();
+
+        match transport.next().await {
+            Some(TransportEvent::DialFailure {
+                address: dial_address,
+                error: DialError::NegotiationError(crate::error::NegotiationError::IoError(kind)),
+                ..
+            }) => {
+                assert_eq!(dial_address, address);
+                assert_eq!(kind, io::ErrorKind::NotFound);
+            }
+            event => panic!("invalid event: {event:?}"),
+        }
+    }
+}
@@ -20,8 +20,12 @@
 
 //! WebRTC transport configuration.
 
+use crate::transport::CONNECTION_OPEN_TIMEOUT;
+
 use multiaddr::Multiaddr;
 
+use std::time::Duration;
+
 /// WebRTC transport configuration.
 #[derive(Debug)]
 pub struct Config {
@@ -32,6 +36,12 @@ pub struct Config {
     ///
     /// How many datagrams can the buffer between `WebRtcTransport` and a connection handler hold.
     pub datagram_buffer_size: usize,
+
+    /// Connection open timeout.
+    ///
+    /// How long should litep2p wait for an outbound connection to be opened before the host
+    /// is deemed unreachable.
+    pub connection_open_timeout: Duration,
 }
 
 impl Default for Config {
@@ -41,6 +51,7 @@ impl Default for Config {
                 .parse()
                 .expect("valid multiaddress")],
             datagram_buffer_size: 2048,
+            connection_open_timeout: CONNECTION_OPEN_TIMEOUT,
         }
     }
 }
@@ -21,7 +21,8 @@
 //! WebRTC transport.
 
 use crate::{
-    error::{AddressError, Error},
+    config::Role,
+    error::{AddressError, DialError, Error, NegotiationError},
     transport::{
         manager::TransportHandle,
         webrtc::{config::Config, connection::WebRtcConnection, opening::OpeningWebRtcConnection},
@@ -31,12 +32,13 @@ use crate::{
     PeerId,
 };
 
-use futures::{future::BoxFuture, Future, Stream};
+use futures::{future::BoxFuture, stream::FuturesUnordered, Stream, StreamExt};
 use futures_timer::Delay;
 use multiaddr::{multihash::Multihash, Multiaddr, Protocol};
+use rand::{distributions::Alphanumeric, Rng};
 use socket2::{Domain, Socket, Type};
 use str0m::{
-    change::DtlsCert,
+    change::{DtlsCert, Fingerprint},
     channel::{ChannelConfig, ChannelId},
     ice::IceCreds,
     net::{DatagramRecv, Protocol as Str0mProtocol, Receive},
@@ -50,6 +52,7 @@ use tokio::{
 
 use std::{
     collections::{HashMap, VecDeque},
+    io::ErrorKind,
     net::{IpAddr, SocketAddr},
     pin::Pin,
     sync::Arc,
@@ -58,6 +61,8 @@ use std::{
 };
 
 pub(crate) use substream::Substream;
+#[cfg(test)]
+pub(crate) use util::WebRtcMessage;
 
 mod connection;
 mod opening;
@@ -83,6 +88,9 @@ const LOG_TARGET: &str = "litep2p::webrtc";
 const REMOTE_FINGERPRINT: &str =
     "sha-256 FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF:FF";
 
+/// Prefix of the ICE username fragment used for outbound connections.
+const UFRAG_PREFIX: &str = "libp2p+webrtc+v1/";
+
 /// Connection context.
 struct ConnectionContext {
     /// Remote peer ID.
@@ -95,6 +103,27 @@ struct ConnectionContext {
     tx: Sender<Vec<u8>>,
 }
 
+/// Outbound connection which is being opened.
+struct PendingDial {
+    /// Connection ID.
+    connection_id: ConnectionId,
+
+    /// Dialed address.
+    address: Multiaddr,
+
+    /// Whether the connection was opened with [`Transport::open()`].
+    open: bool,
+}
+
+/// Context of a connection opened with [`Transport::open()`].
+struct PendingOpen {
+    /// Number of dialed addresses.
+    num_addresses: usize,
+
+    /// Errors of the failed dials.
+    errors: Vec<(Multiaddr, DialError)>,
+}
+
 /// Events received from opening connections that are handled
 /// by the [`WebRtcTransport`] event loop.
 enum ConnectionEvent {
@@ -110,6 +139,12 @@ enum ConnectionEvent {
     /// Connection to peer closed.
     ConnectionClosed,
 
+    /// Connection failed to negotiate.
+    ConnectionFailed {
+        /// Negotiation error.
+        error: NegotiationError,
+    },
+
     /// Timeout.
     Timeout {
         /// Timeout duration.
@@ -134,6 +169,9 @@ pub(crate) struct WebRtcTransport {
     /// Datagram buffer size.
     datagram_buffer_size: usize,
 
+    /// Connection open timeout.
+    connection_open_timeout: Duration,
+
     /// Connected peers.
     open: HashMap<SocketAddr, ConnectionContext>,
 
@@ -146,6 +184,15 @@ pub(crate) struct WebRtcTransport {
     /// Pending timeouts.
     timeouts: HashMap<SocketAddr, BoxFuture<'static, ()>>,
 
+    /// Pending outbound connections.
+    pending_dials: HashMap<SocketAddr, PendingDial>,
+
+    /// Connections opened with [`Transport::open()`] which haven't succeeded or failed yet.
+    pending_opens: HashMap<ConnectionId, PendingOpen>,
+
+    /// Timeouts of the pending outbound connections.
+    dial_timeouts: FuturesUnordered<BoxFuture<'static, (SocketAddr, ConnectionId)>>,
+
     /// Pending events.
     pending_events: VecDeque<TransportEvent>,
 }
@@ -197,7 +244,13 @@ impl WebRtcTransport {
             }
         }
 
-        let maybe_peer = match iter.next() {
+        // certificate hash is only used when dialing and it's parsed separately
+        let mut next = iter.next();
+        if std::matches!(next, Some(Protocol::Certhash(_))) {
+            next = iter.next();
+        }
+
+        let maybe_peer = match next {
             Some(Protocol::P2p(multihash)) => Some(PeerId::from_multihash(multihash)?),
             None => None,
             protocol => {
@@ -213,6 +266,56 @@ impl WebRtcTransport {
         Ok((socket_address, maybe_peer))
     }
 
+    /// Extract the fingerprint of remote's DTLS certificate from `address`.
+    fn get_remote_fingerprint(address: &Multiaddr) -> Result<Fingerprint, AddressError> {
+        const MULTIHASH_SHA256_CODE: u64 = 0x12;
+
+        let certhash = address
+            .iter()
+            .find_map(|protocol| match protocol {
+                Protocol::Certhash(certhash) => Some(certhash),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                tracing::debug!(target: LOG_TARGET, ?address, "certificate hash missing");
+                AddressError::InvalidProtocol
+            })?;
+
+        if certhash.code() != MULTIHASH_SHA256_CODE || certhash.digest().len() != 32 {
+            tracing::debug!(
+                target: LOG_TARGET,
+                ?address,
+                code = ?certhash.code(),
+                "unsupported certificate hash",
+            );
+            return Err(AddressError::InvalidProtocol);
+        }
+
+        Ok(Fingerprint {
+            hash_func: "sha-256".to_string(),
+            bytes: certhash.digest().to_vec(),
+        })
+    }
+
+    /// Get the local address used to send datagrams to `remote_address`.
+    ///
+    /// If the transport listens on an unspecified address, the address of the interface which
+    /// routes to `remote_address` is used.
+    fn local_address_for(&self, remote_address: SocketAddr) -> std::io::Result<SocketAddr> {
+        if !self.listen_address.ip().is_unspecified() {
+            return Ok(self.listen_address);
+        }
+
+        // connecting a UDP socket doesn't send anything but makes the OS select the interface
+        let socket = std::net::UdpSocket::bind(SocketAddr::new(self.listen_address.ip(), 0))?;
+        socket.connect(remote_address)?;
+
+        Ok(SocketAddr::new(
+            socket.local_addr()?.ip(),
+            self.listen_address.port(),
+        ))
+    }
+
     /// Create RTC client and open channel for Noise handshake.
     fn make_rtc_client(
         &self,
@@ -253,6 +356,250 @@ impl WebRtcTransport {
         (rtc, noise_channel_id)
     }
 
+    /// Create RTC client for an outbound connection and open channel for Noise handshake.
+    ///
+    /// The remote peer is an ICE-lite agent so the local node is the controlling ICE agent and the
+    /// DTLS client of the connection. The same random `ufrag` is used as the username fragment and
+    /// the password of both peers.
+    fn make_rtc_dialer(
+        &self,
+        ufrag: &str,
+        remote_fingerprint: Fingerprint,
+        local_address: SocketAddr,
+        remote_address: SocketAddr,
+    ) -> Result<(Rtc, ChannelId), DialError> {
+        let local_candidate = Candidate::host(local_address, Str0mProtocol::Udp)
+            .map_err(|_| AddressError::InvalidProtocol)?;
+        let remote_candidate = Candidate::host(remote_address, Str0mProtocol::Udp)
+            .map_err(|_| AddressError::InvalidProtocol)?;
+
+        let mut rtc = Rtc::builder()
+            .set_dtls_cert(self.dtls_cert.clone())
+            .set_fingerprint_verification(true)
+            .build();
+        rtc.add_local_candidate(local_candidate);
+        rtc.add_remote_candidate(remote_candidate);
+        rtc.direct_api().set_remote_fingerprint(remote_fingerprint);
+
+        let credentials = IceCreds {
+            ufrag: ufrag.to_owned(),
+            pass: ufrag.to_owned(),
+        };
+        rtc.direct_api().set_remote_ice_credentials(credentials.clone());
+        rtc.direct_api().set_local_ice_credentials(credentials);
+        rtc.direct_api().set_ice_controlling(true);
+        rtc.direct_api().start_dtls(true).expect("dtls to be started only once");
+        rtc.direct_api().start_sctp(true);
+
+        let noise_channel_id = rtc.direct_api().create_data_channel(ChannelConfig {
+            label: "noise".to_string(),
+            ordered: false,
+            reliability: Default::default(),
+            negotiated: Some(0),
+            protocol: "".to_string(),
+        });
+
+        Ok((rtc, noise_channel_id))
+    }
+
+    /// Start opening an outbound connection to `address`.
+    fn start_dial(
+        &mut self,
+        connection_id: ConnectionId,
+        address: Multiaddr,
+        open: bool,
+    ) -> Result<(), DialError> {
+        let (remote_address, dialed_peer) =
+            Self::get_socket_address(&address).map_err(|error| match error {
+                Error::AddressError(error) => error,
+                _ => AddressError::InvalidProtocol,
+            })?;
+        let remote_fingerprint = Self::get_remote_fingerprint(&address)?;
+
+        if remote_address.is_ipv4() != self.listen_address.is_ipv4() {
+            tracing::debug!(
+                target: LOG_TARGET,
+                ?connection_id,
+                ?address,
+                "ip version of the address doesn't match the listen address",
+            );
+            return Err(AddressError::InvalidProtocol.into());
+        }
+
+        // datagrams are dispatched to connections by their source address
+        // so only one connection can exist for each remote address
+        if self.open.contains_key(&remote_address) || self.opening.contains_key(&remote_address) {
+            tracing::debug!(
+                target: LOG_TARGET,
+                ?connection_id,
+                ?address,
+                "connection to address already exists",
+            );
+            return Err(NegotiationError::IoError(ErrorKind::AddrInUse).into());
+        }
+
+        let local_address = self.local_address_for(remote_address)?;
+        let ufrag = format!(
+            "{UFRAG_PREFIX}{}",
+            rand::thread_rng()
+                .sample_iter(&Alphanumeric)
+                .take(32)
+                .map(char::from)
+                .collect::<String>()
+        );
+        let (rtc, noise_channel_id) =
+            self.make_rtc_dialer(&ufrag, remote_fingerprint, local_address, remote_address)?;
+
+        let connection = OpeningWebRtcConnection::new(
+            rtc,
+            Role::Dialer,
+            connection_id,
+            noise_channel_id,
+            self.context.keypair.clone(),
+            dialed_peer,
+            remote_address,
+            local_address,
+        );
+        self.opening.insert(remote_address, connection);
+        self.pending_dials.insert(
+            remote_address,
+            PendingDial {
+                connection_id,
+                address,
+                open,
+            },
+        );
+
+        // poll the connection right away so it starts the connectivity checks
+        self.timeouts.insert(remote_address, Box::pin(futures::future::ready(())));
+
+        let connection_open_timeout = self.connection_open_timeout;
+        self.dial_timeouts.push(Box::pin(async move {
+            Delay::new(connection_open_timeout).await;
+            (remote_address, connection_id)
+        }));
+
+        Ok(())
+    }
+
+    /// Cancel all pending outbound connections of `connection_id`.
+    fn cancel_dials(&mut self, connection_id: ConnectionId) {
+        let sources = self
+            .pending_dials
+            .iter()
+            .filter_map(|(source, dial)| (dial.connection_id == connection_id).then_some(*source))
+            .collect::<Vec<_>>();
+
+        for source in sources {
+            self.pending_dials.remove(&source);
+            self.opening.remove(&source);
+            self.timeouts.remove(&source);
+        }
+    }
+
+    /// Handle opening connection which finished the Noise handshake.
+    ///
+    /// Connections opened with [`Transport::open()`] are reported as opened, cancelling the
+    /// other dials of the connection, and are reported as established once they're negotiated.
+    fn on_connection_established(
+        &mut self,
+        source: SocketAddr,
+        peer: PeerId,
+        endpoint: Endpoint,
+    ) -> TransportEvent {
+        let connection_id = endpoint.connection_id();
+        self.connections.insert(connection_id, (peer, source, endpoint.clone()));
+
+        match self.pending_dials.remove(&source) {
+            Some(PendingDial {
+                address,
+                open: true,
+                ..
+            }) => {
+                self.pending_opens.remove(&connection_id);
+                self.cancel_dials(connection_id);
+
+                TransportEvent::ConnectionOpened {
+                    connection_id,
+                    address,
+                }
+            }
+            _ => TransportEvent::ConnectionEstablished { peer, endpoint },
+        }
+    }
+
+    /// Handle closed opening connection.
+    ///
+    /// If the connection was dialed, the failure is reported once all dials of the connection
+    /// have failed.
+    fn on_connection_closed(
+        &mut self,
+        source: SocketAddr,
+        error: DialError,
+    ) -> Option<TransportEvent> {
+        self.opening.remove(&source);
+        self.timeouts.remove(&source);
+
+        let PendingDial {
+            connection_id,
+            address,
+            open,
+        } = self.pending_dials.remove(&source)?;
+
+        if !open {
+            return Some(TransportEvent::DialFailure {
+                connection_id,
+                address,
+                error,
+            });
+        }
+
+        let context = self.pending_opens.get_mut(&connection_id)?;
+        context.errors.push((address, error));
+
+        if context.errors.len() < context.num_addresses {
+            return None;
+        }
+
+        let context = self.pending_opens.remove(&connection_id).expect("context to exist");
+        Some(TransportEvent::OpenFailure {
+            connection_id,
+            errors: context.errors,
+        })
+    }
+
+    /// Poll opening connection until it registers a timeout or closes.
+    fn drive_connection(&mut self, source: SocketAddr) {
+        loop {
+            match self.poll_connection(&source) {
+                ConnectionEvent::ConnectionEstablished { peer, endpoint } => {
+                    // keep polling the connection until it registers a timeout
+                    let event = self.on_connection_established(source, peer, endpoint);
+                    self.pending_events.push_back(event);
+                }
+                ConnectionEvent::ConnectionClosed => {
+                    let error = NegotiationError::IoError(ErrorKind::ConnectionRefused);
+
+                    if let Some(event) = self.on_connection_closed(source, error.into()) {
+                        self.pending_events.push_back(event);
+                    }
+                    break;
+                }
+                ConnectionEvent::ConnectionFailed { error } => {
+                    if let Some(event) = self.on_connection_closed(source, error.into()) {
+                        self.pending_events.push_back(event);
+                    }
+                    break;
+                }
+                ConnectionEvent::Timeout { duration } => {
+                    self.timeouts
+                        .insert(source, Box::pin(async move { Delay::new(duration).await }));
+                    break;
+                }
+            }
+        }
+    }
+
     /// Poll opening connection.
     fn poll_connection(&mut self, source: &SocketAddr) -> ConnectionEvent {
         let Some(connection) = self.opening.get_mut(source) else {
@@ -299,6 +646,8 @@ impl WebRtcTransport {
                         );
                     },
                 opening::WebRtcEvent::ConnectionClosed => return ConnectionEvent::ConnectionClosed,
+                opening::WebRtcEvent::ConnectionFailed { error } =>
+                    return ConnectionEvent::ConnectionFailed { error },
                 opening::WebRtcEvent::ConnectionOpened { peer, endpoint } => {
                     return ConnectionEvent::ConnectionEstablished { peer, endpoint };
                 }
@@ -344,21 +693,13 @@ impl WebRtcTransport {
             return Err(Error::InvalidData);
         }
 
-        // if the peer doesn't exist, decode the message and expect to receive `Stun`
-        // so that a new connection can be initialized
         let contents: DatagramRecv =
             buffer.as_slice().try_into().map_err(|_| Error::InvalidData)?;
 
-        // Handle non stun packets.
-        if !is_stun_packet(&buffer) {
-            tracing::debug!(
-                target: LOG_TARGET,
-                ?source,
-                "received non-stun message"
-            );
-
-            if let Err(error) = self.opening.get_mut(&source).expect("to exist").on_input(contents)
-            {
+        // datagrams of an opening connection, including the STUN messages
+        // of the connectivity checks, are handled by the connection
+        if let Some(connection) = self.opening.get_mut(&source) {
+            if let Err(error) = connection.on_input(contents) {
                 tracing::error!(
                     target: LOG_TARGET,
                     ?error,
@@ -369,6 +710,17 @@ impl WebRtcTransport {
             return Ok(true);
         }
 
+        // if the peer doesn't exist, expect to receive `Stun`
+        // so that a new connection can be initialized
+        if !is_stun_packet(&buffer) {
+            tracing::debug!(
+                target: LOG_TARGET,
+                ?source,
+                "received non-stun message from unknown peer"
+            );
+            return Err(Error::InvalidData);
+        }
+
         let stun_message =
             str0m::ice::StunMessage::parse(&buffer).map_err(|_| Error::InvalidData)?;
         let Some((ufrag, pass)) = stun_message.split_username() else {
@@ -406,9 +758,11 @@ impl WebRtcTransport {
         let connection_id = self.context.next_connection_id();
         let connection = OpeningWebRtcConnection::new(
             rtc,
+            Role::Listener,
             connection_id,
             noise_channel_id,
             self.context.keypair.clone(),
+            None,
             source,
             self.listen_address,
         );
@@ -479,8 +833,12 @@ impl TransportBuilder for WebRtcTransport {
                 connections: HashMap::new(),
                 socket: Arc::new(socket),
                 timeouts: HashMap::new(),
+                pending_dials: HashMap::new(),
+                pending_opens: HashMap::new(),
+                dial_timeouts: FuturesUnordered::new(),
                 pending_events: VecDeque::new(),
                 datagram_buffer_size: config.datagram_buffer_size,
+                connection_open_timeout: config.connection_open_timeout,
             },
             listen_multi_addresses,
         ))
@@ -489,15 +847,13 @@ impl TransportBuilder for WebRtcTransport {
 
 impl Transport for WebRtcTransport {
     fn dial(&mut self, connection_id: ConnectionId, address: Multiaddr) -> crate::Result<()> {
-        tracing::warn!(
-            target: LOG_TARGET,
-            ?connection_id,
-            ?address,
-            "webrtc cannot dial",
-        );
+        tracing::debug!(target: LOG_TARGET, ?connection_id, ?address, "open connection");
 
-        debug_assert!(false);
-        Err(Error::NotSupported("webrtc cannot dial peers".to_string()))
+        self.start_dial(connection_id, address, false).map_err(|error| match error {
+            DialError::AddressError(error) => Error::AddressError(error),
+            DialError::NegotiationError(error) => Error::NegotiationError(error),
+            error => Error::TransportError(error.to_string()),
+        })
     }
 
     fn accept_pending(&mut self, connection_id: ConnectionId) -> crate::Result<()> {
@@ -554,6 +910,7 @@ impl Transport for WebRtcTransport {
             Error::InvalidState
         })?;
 
+        let local_address = connection.local_address();
         let rtc = connection.on_accept()?;
         let (tx, rx) = channel(self.datagram_buffer_size);
         let protocol_set = self.context.protocol_set(connection_id);
@@ -563,7 +920,7 @@ impl Transport for WebRtcTransport {
             rtc,
             peer,
             source,
-            self.listen_address,
+            local_address,
             Arc::clone(&self.socket),
             protocol_set,
             endpoint,
@@ -618,17 +975,63 @@ impl Transport for WebRtcTransport {
 
     fn open(
         &mut self,
-        _connection_id: ConnectionId,
-        _addresses: Vec<Multiaddr>,
+        connection_id: ConnectionId,
+        addresses: Vec<Multiaddr>,
     ) -> crate::Result<()> {
+        let num_addresses = addresses.len();
+        let mut errors = Vec::new();
+
+        for address in addresses {
+            if let Err(error) = self.start_dial(connection_id, address.clone(), true) {
+                tracing::debug!(
+                    target: LOG_TARGET,
+                    ?connection_id,
+                    ?address,
+                    ?error,
+                    "failed to open connection",
+                );
+                errors.push((address, error));
+            }
+        }
+
+        if errors.len() == num_addresses {
+            self.pending_events.push_back(TransportEvent::OpenFailure {
+                connection_id,
+                errors,
+            });
+            return Ok(());
+        }
+
+        self.pending_opens.insert(
+            connection_id,
+            PendingOpen {
+                num_addresses,
+                errors,
+            },
+        );
+
         Ok(())
     }
 
-    fn negotiate(&mut self, _connection_id: ConnectionId) -> crate::Result<()> {
+    fn negotiate(&mut self, connection_id: ConnectionId) -> crate::Result<()> {
+        // the noise handshake was already done when the connection was opened
+        let (peer, _, endpoint) = self
+            .connections
+            .get(&connection_id)
+            .ok_or(Error::ConnectionDoesntExist(connection_id))?;
+
+        self.pending_events.push_back(TransportEvent::ConnectionEstablished {
+            peer: *peer,
+            endpoint: endpoint.clone(),
+        });
+
         Ok(())
     }
 
-    fn cancel(&mut self, _connection_id: ConnectionId) {}
+    fn cancel(&mut self, connection_id: ConnectionId) {
+        self.pending_opens.remove(&connection_id);
+        self.cancel_dials(connection_id);
+    }
 }
 
 impl Stream for WebRtcTransport {
@@ -662,35 +1065,7 @@ impl Stream for WebRtcTransport {
 
                     match this.on_socket_input(source, buf) {
                         Ok(false) => {}
-                        Ok(true) => loop {
-                            match this.poll_connection(&source) {
-                                ConnectionEvent::ConnectionEstablished { peer, endpoint } => {
-                                    this.connections.insert(
-                                        endpoint.connection_id(),
-                                        (peer, source, endpoint.clone()),
-                                    );
-
-                                    // keep polling the connection until it registers a timeout
-                                    this.pending_events.push_back(
-                                        TransportEvent::ConnectionEstablished { peer, endpoint },
-                                    );
-                                }
-                                ConnectionEvent::ConnectionClosed => {
-                                    this.opening.remove(&source);
-                                    this.timeouts.remove(&source);
-
-                                    break;
-                                }
-                                ConnectionEvent::Timeout { duration } => {
-                                    this.timeouts.insert(
-                                        source,
-                                        Box::pin(async move { Delay::new(duration).await }),
-                                    );
-
-                                    break;
-                                }
-                            }
-                        },
+                        Ok(true) => this.drive_connection(source),
                         Err(error) => {
                             tracing::debug!(
                                 target: LOG_TARGET,
@@ -704,52 +1079,52 @@ impl Stream for WebRtcTransport {
             }
         }
 
+        while let Poll::Ready(Some((source, connection_id))) =
+            this.dial_timeouts.poll_next_unpin(cx)
+        {
+            if this
+                .pending_dials
+                .get(&source)
+                .is_some_and(|dial| dial.connection_id == connection_id)
+            {
+                tracing::debug!(
+                    target: LOG_TARGET,
+                    ?source,
+                    ?connection_id,
+                    "failed to open connection in time",
+                );
+
+                if let Some(event) = this.on_connection_closed(source, DialError::Timeout) {
+                    this.pending_events.push_back(event);
+                }
+            }
+        }
+
         // go over all pending timeouts to see if any of them have expired
         // and if any of them have, poll the connection until it registers another timeout
-        let pending_events = this
-            .timeouts
-            .iter_mut()
-            .filter_map(|(source, mut delay)| match Pin::new(&mut delay).poll(cx) {
-                Poll::Pending => None,
-                Poll::Ready(_) => Some(*source),
-            })
-            .collect::<Vec<_>>()
-            .into_iter()
-            .filter_map(|source| {
-                let mut pending_event = None;
-
-                loop {
-                    match this.poll_connection(&source) {
-                        ConnectionEvent::ConnectionEstablished { peer, endpoint } => {
-                            this.connections
-                                .insert(endpoint.connection_id(), (peer, source, endpoint.clone()));
-
-                            // keep polling the connection until it registers a timeout
-                            pending_event =
-                                Some(TransportEvent::ConnectionEstablished { peer, endpoint });
-                        }
-                        ConnectionEvent::ConnectionClosed => {
-                            this.opening.remove(&source);
-                            return None;
-                        }
-                        ConnectionEvent::Timeout { duration } => {
-                            this.timeouts.insert(
-                                source,
-                                Box::pin(async move {
-                                    Delay::new(duration);
-                                }),
-                            );
-                            break;
-                        }
-                    }
-                }
+        loop {
+            let expired = this
+                .timeouts
+                .iter_mut()
+                .filter_map(|(source, delay)| match delay.as_mut().poll(cx) {
+                    Poll::Pending => None,
+                    Poll::Ready(_) => Some(*source),
+                })
+                .collect::<Vec<_>>();
+
+            if expired.is_empty() {
+                break;
+            }
 
-                pending_event
-            })
-            .collect::<VecDeque<_>>();
+            for source in expired {
+                this.timeouts.remove(&source);
+
+                if this.opening.contains_key(&source) {
+                    this.drive_connection(source);
+                }
+            }
+        }
 
-        this.timeouts.retain(|source, _| this.opening.contains_key(source));
-        this.pending_events.extend(pending_events);
         this.pending_events
             .pop_front()
             .map_or(Poll::Pending, |event| Poll::Ready(Some(event)))
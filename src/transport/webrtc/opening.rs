@@ -23,6 +23,7 @@
 use crate::{
     config::Role,
     crypto::{ed25519::Keypair, noise::NoiseContext},
+    error::NegotiationError,
     transport::{webrtc::util::WebRtcMessage, Endpoint},
     types::ConnectionId,
     Error, PeerId,
@@ -42,17 +43,33 @@ use std::{net::SocketAddr, time::Instant};
 const LOG_TARGET: &str = "litep2p::webrtc::connection";
 
 /// Create Noise prologue.
-fn noise_prologue(local_fingerprint: Vec<u8>, remote_fingerprint: Vec<u8>) -> Vec<u8> {
+///
+/// The prologue is the same for both peers and contains the fingerprint of the client (dialer)
+/// followed by the fingerprint of the server (listener).
+fn noise_prologue(client_fingerprint: Vec<u8>, server_fingerprint: Vec<u8>) -> Vec<u8> {
     const PREFIX: &[u8] = b"libp2p-webrtc-noise:";
     let mut prologue =
-        Vec::with_capacity(PREFIX.len() + local_fingerprint.len() + remote_fingerprint.len());
+        Vec::with_capacity(PREFIX.len() + client_fingerprint.len() + server_fingerprint.len());
     prologue.extend_from_slice(PREFIX);
-    prologue.extend_from_slice(&remote_fingerprint);
-    prologue.extend_from_slice(&local_fingerprint);
+    prologue.extend_from_slice(&client_fingerprint);
+    prologue.extend_from_slice(&server_fingerprint);
 
     prologue
 }
 
+/// Verify that the peer authenticated by the Noise handshake is the peer that was dialed.
+fn verify_remote_peer(
+    dialed_peer: Option<PeerId>,
+    remote_peer_id: PeerId,
+) -> Result<(), NegotiationError> {
+    match dialed_peer {
+        Some(dialed_peer) if dialed_peer != remote_peer_id => Err(
+            NegotiationError::PeerIdMismatch(dialed_peer, remote_peer_id),
+        ),
+        _ => Ok(()),
+    }
+}
+
 /// WebRTC connection event.
 #[derive(Debug)]
 pub enum WebRtcEvent {
@@ -74,6 +91,12 @@ pub enum WebRtcEvent {
     /// Connection closed.
     ConnectionClosed,
 
+    /// Connection failed to negotiate.
+    ConnectionFailed {
+        /// Negotiation error.
+        error: NegotiationError,
+    },
+
     /// Connection established.
     ConnectionOpened {
         /// Remote peer ID.
@@ -89,10 +112,16 @@ pub enum WebRtcEvent {
 /// This object is used to track an opening connection which starts with a Noise handshake.
 /// After the handshake is done, this object is destroyed and a new WebRTC connection object
 /// is created which implements a normal connection event loop dealing with substreams.
+///
+/// The roles in the Noise handshake are reversed: the listener of the connection initiates the
+/// handshake and the dialer responds to it.
 pub struct OpeningWebRtcConnection {
     /// WebRTC object
     rtc: Rtc,
 
+    /// Role of the local node in the connection.
+    role: Role,
+
     /// Connection state.
     state: State,
 
@@ -105,6 +134,9 @@ pub struct OpeningWebRtcConnection {
     /// Local keypair.
     id_keypair: Keypair,
 
+    /// Peer ID of the dialed peer, if the connection was dialed to a known peer.
+    dialed_peer: Option<PeerId>,
+
     /// Peer address
     peer_address: SocketAddr,
 
@@ -146,9 +178,11 @@ impl OpeningWebRtcConnection {
     /// Create new [`OpeningWebRtcConnection`].
     pub fn new(
         rtc: Rtc,
+        role: Role,
         connection_id: ConnectionId,
         noise_channel_id: ChannelId,
         id_keypair: Keypair,
+        dialed_peer: Option<PeerId>,
         peer_address: SocketAddr,
         local_address: SocketAddr,
     ) -> OpeningWebRtcConnection {
@@ -156,20 +190,28 @@ impl OpeningWebRtcConnection {
             target: LOG_TARGET,
             ?connection_id,
             ?peer_address,
+            ?role,
             "new connection opened",
         );
 
         Self {
             rtc,
+            role,
             state: State::Closed,
             connection_id,
             noise_channel_id,
             id_keypair,
+            dialed_peer,
             peer_address,
             local_address,
         }
     }
 
+    /// Get the local address of the connection.
+    pub fn local_address(&self) -> SocketAddr {
+        self.local_address
+    }
+
     /// Get remote fingerprint to bytes.
     fn remote_fingerprint(&mut self) -> Vec<u8> {
         let fingerprint = self
@@ -194,11 +236,40 @@ impl OpeningWebRtcConnection {
             .to_bytes()
     }
 
+    /// Get the address of the remote peer.
+    fn remote_address(&mut self, peer: PeerId) -> Multiaddr {
+        let remote_fingerprint = self
+            .rtc
+            .direct_api()
+            .remote_dtls_fingerprint()
+            .clone()
+            .expect("fingerprint to exist")
+            .bytes;
+
+        const MULTIHASH_SHA256_CODE: u64 = 0x12;
+        let certificate = Multihash::wrap(MULTIHASH_SHA256_CODE, &remote_fingerprint)
+            .expect("fingerprint's len to be 32 bytes");
+
+        Multiaddr::empty()
+            .with(Protocol::from(self.peer_address.ip()))
+            .with(Protocol::Udp(self.peer_address.port()))
+            .with(Protocol::WebRTC)
+            .with(Protocol::Certhash(certificate))
+            .with(Protocol::P2p(peer.into()))
+    }
+
     /// Once a Noise data channel has been opened, even though the light client was the dialer,
     /// the WebRTC server will act as the dialer as per the specification.
     ///
     /// Create the first Noise handshake message and send it to remote peer.
+    ///
+    /// If the local node dialed the connection, wait for the remote peer to send the first message.
     fn on_noise_channel_open(&mut self) -> crate::Result<()> {
+        if std::matches!(self.role, Role::Dialer) {
+            tracing::trace!(target: LOG_TARGET, "wait for initial noise handshake");
+            return Ok(());
+        }
+
         tracing::trace!(target: LOG_TARGET, "send initial noise handshake");
 
         let State::Opened { mut context } = std::mem::replace(&mut self.state, State::Poisoned)
@@ -244,7 +315,11 @@ impl OpeningWebRtcConnection {
     ///
     /// If the peer is accepted, [`OpeningWebRtcConnection::on_accept()`] is called which creates
     /// the final Noise message and sends it to the remote peer, concluding the handshake.
-    fn on_noise_channel_data(&mut self, data: Vec<u8>) -> crate::Result<WebRtcEvent> {
+    fn on_noise_channel_data(&mut self, data: Vec<u8>) -> crate::Result<Option<WebRtcEvent>> {
+        if std::matches!(self.role, Role::Dialer) {
+            return self.on_noise_channel_data_dialer(data);
+        }
+
         tracing::trace!(target: LOG_TARGET, "handle noise handshake reply");
 
         let State::HandshakeSent { mut context } =
@@ -265,33 +340,78 @@ impl OpeningWebRtcConnection {
 
         self.state = State::Validating { context };
 
-        let remote_fingerprint = self
-            .rtc
-            .direct_api()
-            .remote_dtls_fingerprint()
-            .clone()
-            .expect("fingerprint to exist")
-            .bytes;
+        Ok(Some(WebRtcEvent::ConnectionOpened {
+            peer: remote_peer_id,
+            endpoint: Endpoint::listener(self.remote_address(remote_peer_id), self.connection_id),
+        }))
+    }
 
-        const MULTIHASH_SHA256_CODE: u64 = 0x12;
-        let certificate = Multihash::wrap(MULTIHASH_SHA256_CODE, &remote_fingerprint)
-            .expect("fingerprint's len to be 32 bytes");
+    /// Handle Noise handshake message of a dialed connection.
+    ///
+    /// The first message received from the remote peer is answered with a message containing the
+    /// local peer ID. The second message contains remote's peer ID and once it has been received,
+    /// the handshake is complete and the connection is validated by the `TransportManager`.
+    ///
+    /// If the remote peer ID doesn't match the peer ID of the dialed address, the connection
+    /// fails with [`NegotiationError::PeerIdMismatch`].
+    fn on_noise_channel_data_dialer(
+        &mut self,
+        data: Vec<u8>,
+    ) -> crate::Result<Option<WebRtcEvent>> {
+        let message = WebRtcMessage::decode(&data)?.payload.ok_or(Error::InvalidData)?;
 
-        let address = Multiaddr::empty()
-            .with(Protocol::from(self.peer_address.ip()))
-            .with(Protocol::Udp(self.peer_address.port()))
-            .with(Protocol::WebRTC)
-            .with(Protocol::Certhash(certificate))
-            .with(Protocol::P2p(PeerId::from(public_key).into()));
+        match std::mem::replace(&mut self.state, State::Poisoned) {
+            State::Opened { mut context } => {
+                tracing::trace!(target: LOG_TARGET, "handle initial noise handshake");
 
-        Ok(WebRtcEvent::ConnectionOpened {
-            peer: remote_peer_id,
-            endpoint: Endpoint::listener(address, self.connection_id),
-        })
+                context.read_first_message(&message)?;
+                let payload = WebRtcMessage::encode(context.second_message()?);
+
+                self.rtc
+                    .channel(self.noise_channel_id)
+                    .ok_or(Error::ChannelDoesntExist)?
+                    .write(true, payload.as_slice())
+                    .map_err(Error::WebRtc)?;
+
+                self.state = State::HandshakeSent { context };
+                Ok(None)
+            }
+            State::HandshakeSent { mut context } => {
+                tracing::trace!(target: LOG_TARGET, "handle final noise handshake");
+
+                let public_key = context.get_remote_public_key(&message)?;
+                let remote_peer_id = PeerId::from_public_key(&public_key);
+
+                tracing::trace!(
+                    target: LOG_TARGET,
+                    ?remote_peer_id,
+                    "remote handshake parsed successfully",
+                );
+
+                if let Err(error) = verify_remote_peer(self.dialed_peer, remote_peer_id) {
+                    self.state = State::Closed;
+
+                    return Ok(Some(WebRtcEvent::ConnectionFailed { error }));
+                }
+
+                self.state = State::Validating { context };
+
+                Ok(Some(WebRtcEvent::ConnectionOpened {
+                    peer: remote_peer_id,
+                    endpoint: Endpoint::dialer(
+                        self.remote_address(remote_peer_id),
+                        self.connection_id,
+                    ),
+                }))
+            }
+            _ => Err(Error::InvalidState),
+        }
     }
 
     /// Accept connection by sending the final Noise handshake message
     /// and return the `Rtc` object for further use.
+    ///
+    /// If the local node dialed the connection, the handshake has already been concluded.
     pub fn on_accept(mut self) -> crate::Result<Rtc> {
         tracing::trace!(target: LOG_TARGET, "accept webrtc connection");
 
@@ -300,13 +420,16 @@ impl OpeningWebRtcConnection {
             return Err(Error::InvalidState);
         };
 
-        // create second noise handshake message and send it to remote
-        let payload = WebRtcMessage::encode(context.second_message()?);
+        if std::matches!(self.role, Role::Listener) {
+            // create second noise handshake message and send it to remote
+            let payload = WebRtcMessage::encode(context.second_message()?);
 
-        let mut channel =
-            self.rtc.channel(self.noise_channel_id).ok_or(Error::ChannelDoesntExist)?;
+            let mut channel =
+                self.rtc.channel(self.noise_channel_id).ok_or(Error::ChannelDoesntExist)?;
+
+            channel.write(true, payload.as_slice()).map_err(Error::WebRtc)?;
+        }
 
-        channel.write(true, payload.as_slice()).map_err(Error::WebRtc)?;
         self.rtc.direct_api().close_data_channel(self.noise_channel_id);
 
         Ok(self.rtc)
@@ -410,8 +533,16 @@ impl OpeningWebRtcConnection {
                             continue;
                         }
 
-                        // TODO: https://github.com/paritytech/litep2p/issues/350 no expect
-                        self.on_noise_channel_open().expect("to succeed");
+                        if let Err(error) = self.on_noise_channel_open() {
+                            tracing::debug!(
+                                target: LOG_TARGET,
+                                connection_id = ?self.connection_id,
+                                ?error,
+                                "failed to send noise handshake",
+                            );
+
+                            return WebRtcEvent::ConnectionClosed;
+                        }
                     }
                     Event::ChannelData(data) => {
                         tracing::trace!(
@@ -429,8 +560,20 @@ impl OpeningWebRtcConnection {
                             continue;
                         }
 
-                        // TODO: https://github.com/paritytech/litep2p/issues/350 no expect
-                        return self.on_noise_channel_data(data.data).expect("to succeed");
+                        match self.on_noise_channel_data(data.data) {
+                            Ok(Some(event)) => return event,
+                            Ok(None) => {}
+                            Err(error) => {
+                                tracing::debug!(
+                                    target: LOG_TARGET,
+                                    connection_id = ?self.connection_id,
+                                    ?error,
+                                    "failed to handle noise handshake",
+                                );
+
+                                return WebRtcEvent::ConnectionClosed;
+                            }
+                        }
                     }
                     Event::ChannelClose(channel_id) => {
                         tracing::debug!(target: LOG_TARGET, ?channel_id, "channel closed");
@@ -440,9 +583,22 @@ impl OpeningWebRtcConnection {
                             let remote_fingerprint = self.remote_fingerprint();
                             let local_fingerprint = self.local_fingerprint();
 
+                            // the listener initiates the noise handshake
+                            let (prologue, noise_role) = match self.role {
+                                Role::Dialer => (
+                                    noise_prologue(local_fingerprint, remote_fingerprint),
+                                    Role::Listener,
+                                ),
+                                Role::Listener => (
+                                    noise_prologue(remote_fingerprint, local_fingerprint),
+                                    Role::Dialer,
+                                ),
+                            };
+
                             let context = match NoiseContext::with_prologue(
                                 &self.id_keypair,
-                                noise_prologue(local_fingerprint, remote_fingerprint),
+                                prologue,
+                                noise_role,
                             ) {
                                 Ok(context) => context,
                                 Err(err) => {
@@ -482,3 +638,21 @@ impl OpeningWebRtcConnection {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remote_peer_verified() {
+        let peer = PeerId::random();
+        let other = PeerId::random();
+
+        assert!(verify_remote_peer(None, peer).is_ok());
+        assert!(verify_remote_peer(Some(peer), peer).is_ok());
+        assert!(std::matches!(
+            verify_remote_peer(Some(other), peer),
+            Err(NegotiationError::PeerIdMismatch(expected, got)) if expected == other && got == peer
+        ));
+    }
+}
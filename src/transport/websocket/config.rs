@@ -22,17 +22,70 @@
 
 use crate::{
     crypto::noise::{MAX_READ_AHEAD_FACTOR, MAX_WRITE_BUFFER_SIZE},
-    transport::{CONNECTION_OPEN_TIMEOUT, SUBSTREAM_OPEN_TIMEOUT},
+    transport::{SecurityProtocol, StreamMuxer, CONNECTION_OPEN_TIMEOUT, SUBSTREAM_OPEN_TIMEOUT},
 };
 
+use std::{path::PathBuf, time::Duration};
+
+/// Default interval for checking whether the TLS certificate has changed on disk.
+const TLS_RELOAD_INTERVAL: Duration = Duration::from_secs(60);
+
+/// TLS configuration for secure WebSocket listeners.
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    /// Path to the PEM-encoded certificate chain, leaf certificate first.
+    pub certificate_chain: PathBuf,
+
+    /// Path to the PEM-encoded private key of the leaf certificate.
+    ///
+    /// PKCS#8, PKCS#1 and SEC1 keys are supported.
+    pub private_key: PathBuf,
+
+    /// How often the certificate chain and the private key are checked for changes.
+    ///
+    /// If either file has been modified, both are loaded again and used for all new
+    /// connections. Established connections are not affected. If the new files can't be
+    /// loaded, the previous certificate is kept. `None` disables reloading.
+    ///
+    /// Defaults to `60` seconds.
+    pub reload_interval: Option<Duration>,
+}
+
+impl TlsConfig {
+    /// Create new [`TlsConfig`] from paths to a PEM-encoded certificate chain and private key.
+    pub fn new(certificate_chain: impl Into<PathBuf>, private_key: impl Into<PathBuf>) -> Self {
+        Self {
+            certificate_chain: certificate_chain.into(),
+            private_key: private_key.into(),
+            reload_interval: Some(TLS_RELOAD_INTERVAL),
+        }
+    }
+}
+
 /// WebSocket transport configuration.
 #[derive(Debug)]
 pub struct Config {
     /// Listen address address for the transport.
     ///
     /// Default listen addreses are ["/ip4/0.0.0.0/tcp/0/ws", "/ip6/::/tcp/0/ws"].
+    ///
+    /// Secure listen addresses, `/tls/ws` or `/wss`, require [`Config::tls_config`] to be set
+    /// and are reported back as `/tls/ws` addresses.
     pub listen_addresses: Vec<multiaddr::Multiaddr>,
 
+    /// TLS configuration for secure listen addresses.
+    ///
+    /// Defaults to `None`.
+    pub tls_config: Option<TlsConfig>,
+
+    /// Path to a PEM file with additional root certificates.
+    ///
+    /// The certificates are trusted, in addition to the platform's native root certificates,
+    /// when dialing `/tls/ws` and `/wss` addresses.
+    ///
+    /// Defaults to `None`.
+    pub tls_root_certificates: Option<PathBuf>,
+
     /// Whether to set `SO_REUSEPORT` and bind a socket to the listen address port for outbound
     /// connections.
     ///
@@ -49,6 +102,28 @@ pub struct Config {
     /// Yamux configuration.
     pub yamux_config: crate::yamux::Config,
 
+    /// mplex configuration.
+    pub mplex_config: crate::mplex::Config,
+
+    /// Security protocols, in order of preference.
+    ///
+    /// When dialing, the protocols are proposed to the remote peer in this order and when
+    /// listening, the first protocol proposed by the remote peer that is also in this list
+    /// is accepted.
+    ///
+    /// Defaults to `[SecurityProtocol::Noise, SecurityProtocol::Tls]` if the `tls` feature is
+    /// enabled and to `[SecurityProtocol::Noise]` otherwise.
+    pub security_protocols: Vec<SecurityProtocol>,
+
+    /// Stream muxers, in order of preference.
+    ///
+    /// The muxers are negotiated the same way as [`Config::security_protocols`]. mplex has no
+    /// flow control and should only be enabled for compatibility with peers that don't support
+    /// yamux.
+    ///
+    /// Defaults to `[StreamMuxer::Yamux]`.
+    pub stream_muxers: Vec<StreamMuxer>,
+
     /// Noise read-ahead frame count.
     ///
     /// Specifies how many Noise frames are read per call to the underlying socket.
@@ -89,9 +164,14 @@ impl Default for Config {
                 "/ip4/0.0.0.0/tcp/0/ws".parse().expect("valid address"),
                 "/ip6/::/tcp/0/ws".parse().expect("valid address"),
             ],
+            tls_config: None,
+            tls_root_certificates: None,
             reuse_port: true,
             nodelay: false,
             yamux_config: Default::default(),
+            mplex_config: Default::default(),
+            security_protocols: SecurityProtocol::defaults(),
+            stream_muxers: vec![StreamMuxer::Yamux],
             noise_read_ahead_frame_count: MAX_READ_AHEAD_FACTOR,
             noise_write_buffer_size: MAX_WRITE_BUFFER_SIZE,
             connection_open_timeout: CONNECTION_OPEN_TIMEOUT,
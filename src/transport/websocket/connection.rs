@@ -22,14 +22,18 @@ use crate::{
     config::Role,
     crypto::{
         ed25519::Keypair,
-        noise::{self, NoiseSocket},
+        pnet::{self, PnetStream, PreSharedKey},
     },
     error::{Error, NegotiationError, SubstreamError},
     multistream_select::{dialer_select_proto, listener_select_proto, Negotiated, Version},
     protocol::{Direction, Permit, ProtocolCommand, ProtocolSet},
     substream,
     transport::{
-        websocket::{stream::BufferedStream, substream::Substream},
+        common::{
+            muxer::{MuxedConnection, MuxedStream, MuxerControl, StreamMuxer},
+            security::{SecureStream, SecurityProtocol},
+        },
+        websocket::{stream::BufferedStream, substream::Substream, tls::MaybeTlsStream},
         Endpoint,
     },
     types::{protocol::ProtocolName, ConnectionId, SubstreamId},
@@ -38,8 +42,7 @@ use crate::{
 
 use futures::{future::BoxFuture, stream::FuturesUnordered, AsyncRead, AsyncWrite, StreamExt};
 use multiaddr::{multihash::Multihash, Multiaddr, Protocol};
-use tokio::net::TcpStream;
-use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tokio_tungstenite::WebSocketStream;
 use tokio_util::compat::FuturesAsyncReadCompatExt;
 use url::Url;
 
@@ -65,8 +68,8 @@ pub struct NegotiatedSubstream {
     /// Protocol name.
     protocol: ProtocolName,
 
-    /// Yamux substream.
-    io: crate::yamux::Stream,
+    /// Multiplexed substream.
+    io: MuxedStream,
 
     /// Permit.
     permit: Permit,
@@ -105,12 +108,11 @@ pub(super) struct NegotiatedConnection {
     /// Endpoint.
     endpoint: Endpoint,
 
-    /// Yamux connection.
-    connection:
-        crate::yamux::ControlledConnection<NoiseSocket<BufferedStream<MaybeTlsStream<TcpStream>>>>,
+    /// Multiplexed connection.
+    connection: MuxedConnection<SecureStream<PnetStream<BufferedStream<MaybeTlsStream>>>>,
 
-    /// Yamux control.
-    control: crate::yamux::Control,
+    /// Stream muxer control.
+    control: MuxerControl,
 }
 
 impl NegotiatedConnection {
@@ -135,12 +137,11 @@ pub(crate) struct WebSocketConnection {
     /// Protocol context.
     protocol_set: ProtocolSet,
 
-    /// Yamux connection.
-    connection:
-        crate::yamux::ControlledConnection<NoiseSocket<BufferedStream<MaybeTlsStream<TcpStream>>>>,
+    /// Multiplexed connection.
+    connection: MuxedConnection<SecureStream<PnetStream<BufferedStream<MaybeTlsStream>>>>,
 
-    /// Yamux control.
-    control: crate::yamux::Control,
+    /// Stream muxer control.
+    control: MuxerControl,
 
     /// Remote peer ID.
     peer: PeerId,
@@ -213,11 +214,15 @@ impl WebSocketConnection {
     pub(super) async fn open_connection(
         connection_id: ConnectionId,
         keypair: Keypair,
-        stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        pre_shared_key: Option<PreSharedKey>,
+        stream: WebSocketStream<MaybeTlsStream>,
         address: Multiaddr,
         dialed_peer: PeerId,
         ws_address: Url,
         yamux_config: crate::yamux::Config,
+        mplex_config: crate::mplex::Config,
+        security_protocols: Vec<SecurityProtocol>,
+        stream_muxers: Vec<StreamMuxer>,
         max_read_ahead_factor: usize,
         max_write_buffer_size: usize,
     ) -> Result<NegotiatedConnection, NegotiationError> {
@@ -236,7 +241,11 @@ impl WebSocketConnection {
             address,
             connection_id,
             keypair,
+            pre_shared_key,
             yamux_config,
+            mplex_config,
+            security_protocols,
+            stream_muxers,
             max_read_ahead_factor,
             max_write_buffer_size,
         )
@@ -245,16 +254,18 @@ impl WebSocketConnection {
 
     /// Accept WebSocket connection.
     pub(super) async fn accept_connection(
-        stream: TcpStream,
+        stream: MaybeTlsStream,
         connection_id: ConnectionId,
         keypair: Keypair,
+        pre_shared_key: Option<PreSharedKey>,
         address: Multiaddr,
         yamux_config: crate::yamux::Config,
+        mplex_config: crate::mplex::Config,
+        security_protocols: Vec<SecurityProtocol>,
+        stream_muxers: Vec<StreamMuxer>,
         max_read_ahead_factor: usize,
         max_write_buffer_size: usize,
     ) -> Result<NegotiatedConnection, NegotiationError> {
-        let stream = MaybeTlsStream::Plain(stream);
-
         Self::negotiate_connection(
             tokio_tungstenite::accept_async(stream)
                 .await
@@ -264,7 +275,11 @@ impl WebSocketConnection {
             address,
             connection_id,
             keypair,
+            pre_shared_key,
             yamux_config,
+            mplex_config,
+            security_protocols,
+            stream_muxers,
             max_read_ahead_factor,
             max_write_buffer_size,
         )
@@ -273,13 +288,17 @@ impl WebSocketConnection {
 
     /// Negotiate WebSocket connection.
     pub(super) async fn negotiate_connection(
-        stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        stream: WebSocketStream<MaybeTlsStream>,
         dialed_peer: Option<PeerId>,
         role: Role,
         address: Multiaddr,
         connection_id: ConnectionId,
         keypair: Keypair,
+        pre_shared_key: Option<PreSharedKey>,
         yamux_config: crate::yamux::Config,
+        mplex_config: crate::mplex::Config,
+        security_protocols: Vec<SecurityProtocol>,
+        stream_muxers: Vec<StreamMuxer>,
         max_read_ahead_factor: usize,
         max_write_buffer_size: usize,
     ) -> Result<NegotiatedConnection, NegotiationError> {
@@ -292,20 +311,49 @@ impl WebSocketConnection {
             "negotiate connection"
         );
         let stream = BufferedStream::new(stream);
-
-        // negotiate `noise`
-        let (stream, _) = Self::negotiate_protocol(stream, &role, vec!["/noise"]).await?;
+        let is_private = pre_shared_key.is_some();
+
+        // wrap the connection in the private network cipher if the node is part of one
+        let stream = pnet::handshake(stream, pre_shared_key.as_ref()).await?;
+
+        // negotiate security protocol
+        //
+        // if the node is part of a private network, a garbled security protocol negotiation
+        // means the remote peer is using a different pre-shared key
+        let protocols =
+            security_protocols.iter().map(|protocol| protocol.protocol_name()).collect();
+        let (stream, protocol) = match Self::negotiate_protocol(stream, &role, protocols).await {
+            Ok(result) => result,
+            Err(error) if is_private && pnet::is_key_mismatch(&error) => {
+                tracing::debug!(
+                    target: LOG_TARGET,
+                    ?connection_id,
+                    ?error,
+                    "failed to negotiate security protocol over private network",
+                );
+                return Err(NegotiationError::PreSharedKeyMismatch);
+            }
+            Err(error) => return Err(error),
+        };
+        let protocol = SecurityProtocol::from_protocol_name(&protocol)
+            .expect("negotiated protocol to be one of the security protocols; qed");
 
         tracing::trace!(
             target: LOG_TARGET,
-            "`multistream-select` and `noise` negotiated"
+            ?protocol,
+            "`multistream-select` and security protocol negotiated"
         );
 
-        // perform noise handshake
-        let (stream, peer) = noise::handshake(
+        // perform security handshake
+        let stream_muxers =
+            stream_muxers.iter().map(|muxer| muxer.protocol_name()).collect::<Vec<_>>();
+        let (stream, peer, muxer) = SecureStream::upgrade(
             stream.inner(),
+            protocol,
             &keypair,
             role,
+            dialed_peer,
+            &stream_muxers,
             max_read_ahead_factor,
             max_write_buffer_size,
         )
@@ -317,15 +365,24 @@ impl WebSocketConnection {
             }
         }
 
-        let stream: NoiseSocket<BufferedStream<_>> = stream;
-        tracing::trace!(target: LOG_TARGET, "noise handshake done");
+        let stream: SecureStream<PnetStream<BufferedStream<_>>> = stream;
+        tracing::trace!(target: LOG_TARGET, ?protocol, "security handshake done");
 
-        // negotiate `yamux`
-        let (stream, _) = Self::negotiate_protocol(stream, &role, vec!["/yamux/1.0.0"]).await?;
-        tracing::trace!(target: LOG_TARGET, "`yamux` negotiated");
+        // negotiate stream muxer unless it was already agreed on during the security handshake
+        let (stream, muxer) = match muxer {
+            Some(muxer) => (stream, StreamMuxer::from_protocol_name(&muxer)),
+            None => {
+                let (stream, protocol) =
+                    Self::negotiate_protocol(stream, &role, stream_muxers).await?;
+
+                (stream.inner(), StreamMuxer::from_protocol_name(&protocol))
+            }
+        };
+        let muxer = muxer.expect("negotiated protocol to be one of the stream muxers; qed");
+        tracing::trace!(target: LOG_TARGET, ?muxer, "stream muxer negotiated");
 
-        let connection = crate::yamux::Connection::new(stream.inner(), yamux_config, role.into());
-        let (control, connection) = crate::yamux::Control::new(connection);
+        let (control, connection) =
+            MuxedConnection::new(stream, muxer, role, yamux_config, mplex_config);
 
         let address = match role {
             Role::Dialer => address,
@@ -345,7 +402,7 @@ impl WebSocketConnection {
 
     /// Accept substream.
     pub async fn accept_substream(
-        stream: crate::yamux::Stream,
+        stream: MuxedStream,
         permit: Permit,
         substream_id: SubstreamId,
         protocols: Vec<ProtocolName>,
@@ -376,7 +433,7 @@ impl WebSocketConnection {
 
     /// Open substream for `protocol`.
     pub async fn open_substream(
-        mut control: crate::yamux::Control,
+        mut control: MuxerControl,
         permit: Permit,
         substream_id: SubstreamId,
         protocol: ProtocolName,
@@ -396,10 +453,7 @@ impl WebSocketConnection {
                     ?error,
                     "failed to open substream"
                 );
-                return Err(SubstreamError::YamuxError(
-                    error,
-                    Direction::Outbound(substream_id),
-                ));
+                return Err(error.into_substream_error(Direction::Outbound(substream_id)));
             }
         };
 
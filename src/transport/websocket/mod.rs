@@ -24,31 +24,35 @@ use crate::{
     config::Role,
     error::{AddressError, Error, NegotiationError},
     transport::{
-        common::listener::{DialAddresses, GetSocketAddr, SocketListener, WebSocketAddress},
-        manager::TransportHandle,
+        common::{
+            dns::DnsResolver,
+            listener::{
+                DialAddresses, GetSocketAddr, SecureWebSocketAddress, SocketListener,
+                WebSocketAddress,
+            },
+        },
+        manager::{dial_scheduler::staggered, TransportHandle},
         websocket::{
             config::Config,
             connection::{NegotiatedConnection, WebSocketConnection},
+            tls::{MaybeTlsStream, TlsListener},
         },
         Transport, TransportBuilder, TransportEvent,
     },
-    types::ConnectionId,
+    types::{ConnectionId, ListenerId},
     utils::futures_stream::FuturesStream,
     DialError, PeerId,
 };
 
-use futures::{
-    future::BoxFuture,
-    stream::{AbortHandle, FuturesUnordered},
-    Stream, StreamExt, TryFutureExt,
-};
+use futures::{future::BoxFuture, stream::AbortHandle, Stream, StreamExt, TryFutureExt};
 use multiaddr::{Multiaddr, Protocol};
 use socket2::{Domain, Socket, Type};
 use std::net::SocketAddr;
 use tokio::net::TcpStream;
-use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tokio_rustls::{rustls::pki_types::ServerName, TlsConnector};
+use tokio_tungstenite::WebSocketStream;
 
-use url::Url;
+use url::{Host, Url};
 
 use std::{
     collections::HashMap,
@@ -62,6 +66,7 @@ pub(crate) use substream::Substream;
 mod connection;
 mod stream;
 mod substream;
+mod tls;
 
 pub mod config;
 
@@ -74,6 +79,9 @@ struct PendingInboundConnection {
     connection: TcpStream,
     /// Address of the remote peer.
     address: SocketAddr,
+
+    /// Whether the connection was accepted by the secure listener.
+    secure: bool,
 }
 
 #[derive(Debug)]
@@ -82,7 +90,7 @@ enum RawConnectionResult {
     Connected {
         connection_id: ConnectionId,
         address: Multiaddr,
-        stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        stream: WebSocketStream<MaybeTlsStream>,
     },
 
     /// All connection attempts failed.
@@ -106,6 +114,15 @@ pub(crate) struct WebSocketTransport {
     /// WebSocket listener.
     listener: SocketListener,
 
+    /// Secure WebSocket listener.
+    secure_listener: SocketListener,
+
+    /// TLS acceptor for connections of the secure listener.
+    tls_listener: Option<TlsListener>,
+
+    /// TLS connector for outbound secure WebSocket connections.
+    tls_connector: TlsConnector,
+
     /// Dial addresses.
     dial_addresses: DialAddresses,
 
@@ -123,7 +140,7 @@ pub(crate) struct WebSocketTransport {
     pending_raw_connections: FuturesStream<BoxFuture<'static, RawConnectionResult>>,
 
     /// Opened raw connection, waiting for approval/rejection from `TransportManager`.
-    opened_raw: HashMap<ConnectionId, (WebSocketStream<MaybeTlsStream<TcpStream>>, Multiaddr)>,
+    opened_raw: HashMap<ConnectionId, (WebSocketStream<MaybeTlsStream>, Multiaddr)>,
 
     /// Cancel raw connections futures.
     ///
@@ -141,25 +158,48 @@ impl WebSocketTransport {
         connection_id: ConnectionId,
         connection: TcpStream,
         address: SocketAddr,
+        secure: bool,
     ) {
         let keypair = self.context.keypair.clone();
+        let pre_shared_key = self.context.pre_shared_key.clone();
         let yamux_config = self.config.yamux_config.clone();
+        let mplex_config = self.config.mplex_config.clone();
+        let security_protocols = self.config.security_protocols.clone();
+        let stream_muxers = self.config.stream_muxers.clone();
         let connection_open_timeout = self.config.connection_open_timeout;
         let max_read_ahead_factor = self.config.noise_read_ahead_frame_count;
         let max_write_buffer_size = self.config.noise_write_buffer_size;
-        let address = Multiaddr::empty()
-            .with(Protocol::from(address.ip()))
-            .with(Protocol::Tcp(address.port()))
-            .with(Protocol::Ws(std::borrow::Cow::Borrowed("/")));
+        let acceptor = match secure {
+            true => self.tls_listener.as_ref().map(TlsListener::acceptor),
+            false => None,
+        };
+        let address = match secure {
+            true => SecureWebSocketAddress::socket_address_to_multiaddr(&address),
+            false => WebSocketAddress::socket_address_to_multiaddr(&address),
+        };
 
         self.pending_connections.push(Box::pin(async move {
             match tokio::time::timeout(connection_open_timeout, async move {
+                let connection = match acceptor {
+                    Some(acceptor) => MaybeTlsStream::Server(Box::new(
+                        acceptor
+                            .accept(connection)
+                            .await
+                            .map_err(|error| (connection_id, DialError::from(error)))?,
+                    )),
+                    None => MaybeTlsStream::Plain(connection),
+                };
+
                 WebSocketConnection::accept_connection(
                     connection,
                     connection_id,
                     keypair,
+                    pre_shared_key,
                     address,
                     yamux_config,
+                    mplex_config,
+                    security_protocols,
+                    stream_muxers,
                     max_read_ahead_factor,
                     max_write_buffer_size,
                 )
@@ -192,6 +232,10 @@ impl WebSocketTransport {
             Protocol::Tcp(port) => match protocol_stack.next() {
                 Some(Protocol::Ws(_)) => format!("ws://{dial_address}:{port}/"),
                 Some(Protocol::Wss(_)) => format!("wss://{dial_address}:{port}/"),
+                Some(Protocol::Tls) => match protocol_stack.next() {
+                    Some(Protocol::Ws(_)) => format!("wss://{dial_address}:{port}/"),
+                    _ => return Err(AddressError::InvalidProtocol),
+                },
                 _ => return Err(AddressError::InvalidProtocol),
             },
             _ => return Err(AddressError::InvalidProtocol),
@@ -217,22 +261,91 @@ impl WebSocketTransport {
     }
 
     /// Dial remote peer over `address`.
+    ///
+    /// If `address` is a DNS address, the resolved IP addresses are connected to
+    /// `connection_attempt_delay` apart and the first connection to succeed is used. All attempts
+    /// together are bounded by `connection_open_timeout`, the resolution itself by the resolution
+    /// timeout of `resolver`.
     async fn dial_peer(
         address: Multiaddr,
         dial_addresses: DialAddresses,
         connection_open_timeout: Duration,
+        connection_attempt_delay: Duration,
         nodelay: bool,
-    ) -> Result<(Multiaddr, WebSocketStream<MaybeTlsStream<TcpStream>>), DialError> {
+        tls_connector: TlsConnector,
+        resolver: DnsResolver,
+    ) -> Result<(Multiaddr, WebSocketStream<MaybeTlsStream>), DialError> {
         let (url, _) = Self::multiaddr_into_url(address.clone())?;
+        let server_name = match url.scheme() {
+            "wss" => Some(match url.host() {
+                Some(Host::Domain(domain)) => ServerName::try_from(domain.to_string())
+                    .map_err(|_| DialError::AddressError(AddressError::InvalidUrl))?,
+                Some(Host::Ipv4(address)) => ServerName::from(std::net::IpAddr::V4(address)),
+                Some(Host::Ipv6(address)) => ServerName::from(std::net::IpAddr::V6(address)),
+                None => return Err(DialError::AddressError(AddressError::InvalidUrl)),
+            }),
+            _ => None,
+        };
 
         let (socket_address, _) = WebSocketAddress::multiaddr_to_socket_address(&address)?;
-        let remote_address =
-            match tokio::time::timeout(connection_open_timeout, socket_address.lookup_ip()).await {
-                Err(_) => return Err(DialError::Timeout),
-                Ok(Err(error)) => return Err(error.into()),
-                Ok(Ok(address)) => address,
-            };
+        let remote_addresses = socket_address.lookup_ip(&resolver).await?;
+
+        let attempts = remote_addresses.into_iter().map(|remote_address| {
+            let future = Self::connect(
+                remote_address,
+                &dial_addresses,
+                nodelay,
+                url.clone(),
+                server_name.clone(),
+                tls_connector.clone(),
+            );
+            let address = &address;
+
+            async move {
+                future.await.map_err(|error| {
+                    tracing::debug!(
+                        target: LOG_TARGET,
+                        ?address,
+                        ?remote_address,
+                        ?error,
+                        "failed to connect",
+                    );
+
+                    error
+                })
+            }
+        });
+
+        match tokio::time::timeout(
+            connection_open_timeout,
+            staggered(attempts, connection_attempt_delay),
+        )
+        .await
+        {
+            Err(_) => {
+                tracing::debug!(
+                    target: LOG_TARGET,
+                    ?address,
+                    ?connection_open_timeout,
+                    "failed to connect within timeout",
+                );
+                Err(DialError::Timeout)
+            }
+            Ok(Err(mut errors)) =>
+                Err(errors.pop().expect("resolver to return at least one address")),
+            Ok(Ok(stream)) => Ok((address, stream)),
+        }
+    }
 
+    /// Open WebSocket connection to `remote_address`.
+    async fn connect(
+        remote_address: SocketAddr,
+        dial_addresses: &DialAddresses,
+        nodelay: bool,
+        url: Url,
+        server_name: Option<ServerName<'static>>,
+        tls_connector: TlsConnector,
+    ) -> Result<WebSocketStream<MaybeTlsStream>, DialError> {
         let domain = match remote_address.is_ipv4() {
             true => Domain::IPV4,
             false => Domain::IPV6,
@@ -261,34 +374,29 @@ impl WebSocketTransport {
             }
         }
 
-        let future = async move {
-            match socket.connect(&remote_address.into()) {
-                Ok(()) => {}
-                Err(error) if error.raw_os_error() == Some(libc::EINPROGRESS) => {}
-                Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => {}
-                Err(err) => return Err(DialError::from(err)),
-            }
+        match socket.connect(&remote_address.into()) {
+            Ok(()) => {}
+            Err(error) if error.raw_os_error() == Some(libc::EINPROGRESS) => {}
+            Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => {}
+            Err(err) => return Err(DialError::from(err)),
+        }
 
-            let stream = TcpStream::try_from(Into::<std::net::TcpStream>::into(socket))?;
-            stream.writable().await?;
-            if let Some(e) = stream.take_error()? {
-                return Err(DialError::from(e));
-            }
+        let stream = TcpStream::try_from(Into::<std::net::TcpStream>::into(socket))?;
+        stream.writable().await?;
+        if let Some(e) = stream.take_error()? {
+            return Err(DialError::from(e));
+        }
 
-            Ok((
-                address,
-                tokio_tungstenite::client_async_tls(url, stream)
-                    .await
-                    .map_err(NegotiationError::WebSocket)?
-                    .0,
-            ))
+        let stream = match server_name {
+            Some(server_name) =>
+                MaybeTlsStream::Client(Box::new(tls_connector.connect(server_name, stream).await?)),
+            None => MaybeTlsStream::Plain(stream),
         };
 
-        match tokio::time::timeout(connection_open_timeout, future).await {
-            Err(_) => Err(DialError::Timeout),
-            Ok(Err(error)) => Err(error),
-            Ok(Ok((address, stream))) => Ok((address, stream)),
-        }
+        Ok(tokio_tungstenite::client_async(url, stream)
+            .await
+            .map_err(NegotiationError::WebSocket)?
+            .0)
     }
 }
 
@@ -309,15 +417,49 @@ impl TransportBuilder for WebSocketTransport {
             listen_addresses = ?config.listen_addresses,
             "start websocket transport",
         );
-        let (listener, listen_addresses, dial_addresses) = SocketListener::new::<WebSocketAddress>(
-            std::mem::take(&mut config.listen_addresses),
-            config.reuse_port,
-            config.nodelay,
-        );
+        let (secure_addresses, addresses): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut config.listen_addresses)
+                .into_iter()
+                .map(|address| (context.next_listener_id(), address))
+                .partition(|(_, address)| {
+                    address
+                        .iter()
+                        .any(|protocol| std::matches!(protocol, Protocol::Tls | Protocol::Wss(_)))
+                });
+
+        // secure listeners can also be started later with `listen_on()` if `tls_config` exists
+        let tls_listener = match (secure_addresses.is_empty(), config.tls_config.take()) {
+            (_, Some(tls_config)) => Some(TlsListener::new(tls_config, &context.executor)?),
+            (true, None) => None,
+            (false, None) =>
+                return Err(Error::NotSupported(
+                    "secure websocket listen address requires `tls_config`".to_string(),
+                )),
+        };
+        let tls_connector = tls::tls_connector(config.tls_root_certificates.as_deref())?;
+
+        let (listener, mut listen_addresses, dial_addresses) =
+            SocketListener::new::<WebSocketAddress>(addresses, config.reuse_port, config.nodelay);
+        let (secure_listener, secure_listen_addresses, secure_dial_addresses) =
+            SocketListener::new::<SecureWebSocketAddress>(
+                secure_addresses,
+                config.reuse_port,
+                config.nodelay,
+            );
+
+        // bind outbound connections to the port of the plain listener, if there is one
+        let dial_addresses = match listen_addresses.is_empty() {
+            true => secure_dial_addresses,
+            false => dial_addresses,
+        };
+        listen_addresses.extend(secure_listen_addresses);
 
         Ok((
             Self {
                 listener,
+                secure_listener,
+                tls_listener,
+                tls_connector,
                 config,
                 context,
                 dial_addresses,
@@ -337,13 +479,20 @@ impl TransportBuilder for WebSocketTransport {
 impl Transport for WebSocketTransport {
     fn dial(&mut self, connection_id: ConnectionId, address: Multiaddr) -> crate::Result<()> {
         let yamux_config = self.config.yamux_config.clone();
+        let mplex_config = self.config.mplex_config.clone();
+        let security_protocols = self.config.security_protocols.clone();
+        let stream_muxers = self.config.stream_muxers.clone();
         let keypair = self.context.keypair.clone();
+        let pre_shared_key = self.context.pre_shared_key.clone();
         let (ws_address, peer) = Self::multiaddr_into_url(address.clone())?;
         let connection_open_timeout = self.config.connection_open_timeout;
         let max_read_ahead_factor = self.config.noise_read_ahead_frame_count;
         let max_write_buffer_size = self.config.noise_write_buffer_size;
         let dial_addresses = self.dial_addresses.clone();
         let nodelay = self.config.nodelay;
+        let tls_connector = self.tls_connector.clone();
+        let resolver = self.context.resolver.clone();
+        let connection_attempt_delay = self.context.connection_attempt_delay;
 
         self.pending_dials.insert(connection_id, address.clone());
 
@@ -354,7 +503,10 @@ impl Transport for WebSocketTransport {
                 address.clone(),
                 dial_addresses,
                 connection_open_timeout,
+                connection_attempt_delay,
                 nodelay,
+                tls_connector,
+                resolver,
             )
             .await
             .map_err(|error| (connection_id, error))?;
@@ -362,11 +514,15 @@ impl Transport for WebSocketTransport {
             WebSocketConnection::open_connection(
                 connection_id,
                 keypair,
+                pre_shared_key,
                 stream,
                 address,
                 peer,
                 ws_address,
                 yamux_config,
+                mplex_config,
+                security_protocols,
+                stream_muxers,
                 max_read_ahead_factor,
                 max_write_buffer_size,
             )
@@ -439,7 +595,12 @@ impl Transport for WebSocketTransport {
             Error::ConnectionDoesntExist(connection_id)
         })?;
 
-        self.on_inbound_connection(connection_id, pending.connection, pending.address);
+        self.on_inbound_connection(
+            connection_id,
+            pending.connection,
+            pending.address,
+            pending.secure,
+        );
 
         Ok(())
     }
@@ -464,54 +625,54 @@ impl Transport for WebSocketTransport {
         connection_id: ConnectionId,
         addresses: Vec<Multiaddr>,
     ) -> crate::Result<()> {
-        let num_addresses = addresses.len();
-        let mut futures: FuturesUnordered<_> = addresses
+        let connection_attempt_delay = self.context.connection_attempt_delay;
+        let attempts = addresses
             .into_iter()
             .map(|address| {
                 let connection_open_timeout = self.config.connection_open_timeout;
                 let dial_addresses = self.dial_addresses.clone();
                 let nodelay = self.config.nodelay;
+                let tls_connector = self.tls_connector.clone();
+                let resolver = self.context.resolver.clone();
 
                 async move {
                     WebSocketTransport::dial_peer(
                         address.clone(),
                         dial_addresses,
                         connection_open_timeout,
+                        connection_attempt_delay,
                         nodelay,
+                        tls_connector,
+                        resolver,
                     )
                     .await
                     .map_err(|error| (address, error))
                 }
             })
-            .collect();
+            .collect::<Vec<_>>();
 
         // Future that will resolve to the first successful connection.
         let future = async move {
-            let mut errors = Vec::with_capacity(num_addresses);
-            while let Some(result) = futures.next().await {
-                match result {
-                    Ok((address, stream)) =>
-                        return RawConnectionResult::Connected {
-                            connection_id,
-                            address,
-                            stream,
-                        },
-                    Err(error) => {
-                        tracing::debug!(
-                            target: LOG_TARGET,
-                            ?connection_id,
-                            ?error,
-                            "failed to open connection",
-                        );
-                        errors.push(error)
+            match staggered(attempts, connection_attempt_delay).await {
+                Ok((address, stream)) => RawConnectionResult::Connected {
+                    connection_id,
+                    address,
+                    stream,
+                },
+                Err(errors) => {
+                    tracing::debug!(
+                        target: LOG_TARGET,
+                        ?connection_id,
+                        ?errors,
+                        "failed to open connection",
+                    );
+
+                    RawConnectionResult::Failed {
+                        connection_id,
+                        errors,
                     }
                 }
             }
-
-            RawConnectionResult::Failed {
-                connection_id,
-                errors,
-            }
         };
 
         let (fut, handle) = futures::future::abortable(future);
@@ -533,10 +694,14 @@ impl Transport for WebSocketTransport {
             _ => return Err(Error::InvalidState),
         };
         let yamux_config = self.config.yamux_config.clone();
+        let mplex_config = self.config.mplex_config.clone();
+        let security_protocols = self.config.security_protocols.clone();
+        let stream_muxers = self.config.stream_muxers.clone();
         let max_read_ahead_factor = self.config.noise_read_ahead_frame_count;
         let max_write_buffer_size = self.config.noise_write_buffer_size;
         let connection_open_timeout = self.config.connection_open_timeout;
         let keypair = self.context.keypair.clone();
+        let pre_shared_key = self.context.pre_shared_key.clone();
 
         tracing::trace!(
             target: LOG_TARGET,
@@ -556,7 +721,11 @@ impl Transport for WebSocketTransport {
                     address,
                     connection_id,
                     keypair,
+                    pre_shared_key,
                     yamux_config,
+                    mplex_config,
+                    security_protocols,
+                    stream_muxers,
                     max_read_ahead_factor,
                     max_write_buffer_size,
                 )
@@ -581,53 +750,116 @@ impl Transport for WebSocketTransport {
             handle.abort();
         }
     }
+
+    fn listen_on(
+        &mut self,
+        listener_id: ListenerId,
+        address: Multiaddr,
+    ) -> crate::Result<Vec<Multiaddr>> {
+        tracing::debug!(target: LOG_TARGET, ?listener_id, ?address, "start websocket listener");
+
+        let secure = address
+            .iter()
+            .any(|protocol| std::matches!(protocol, Protocol::Tls | Protocol::Wss(_)));
+
+        match secure {
+            false => self.listener.listen_on::<WebSocketAddress>(
+                listener_id,
+                address,
+                self.config.reuse_port,
+                self.config.nodelay,
+            ),
+            true => {
+                if self.tls_listener.is_none() {
+                    return Err(Error::NotSupported(
+                        "secure websocket listen address requires `tls_config`".to_string(),
+                    ));
+                }
+
+                self.secure_listener.listen_on::<SecureWebSocketAddress>(
+                    listener_id,
+                    address,
+                    self.config.reuse_port,
+                    self.config.nodelay,
+                )
+            }
+        }
+    }
+
+    fn stop_listening(&mut self, listener_id: ListenerId) -> crate::Result<()> {
+        tracing::debug!(target: LOG_TARGET, ?listener_id, "stop websocket listener");
+
+        match self.listener.stop_listening(listener_id)
+            || self.secure_listener.stop_listening(listener_id)
+        {
+            true => Ok(()),
+            false => Err(Error::ListenerDoesntExist(listener_id)),
+        }
+    }
 }
 
 impl Stream for WebSocketTransport {
     type Item = TransportEvent;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        if let Poll::Ready(event) = self.listener.poll_next_unpin(cx) {
-            return match event {
-                None => {
-                    tracing::error!(
-                        target: LOG_TARGET,
-                        "Websocket listener terminated, ignore if the node is stopping",
-                    );
+        if let Poll::Ready(event) = self.listener.poll_address_change(cx) {
+            return Poll::Ready(Some(event));
+        }
 
-                    Poll::Ready(None)
-                }
-                Some(Err(error)) => {
-                    tracing::error!(
-                        target: LOG_TARGET,
-                        ?error,
-                        "Websocket listener terminated with error",
-                    );
+        if let Poll::Ready(event) = self.secure_listener.poll_address_change(cx) {
+            return Poll::Ready(Some(event));
+        }
 
-                    Poll::Ready(None)
-                }
-                Some(Ok((connection, address))) => {
-                    let connection_id = self.context.next_connection_id();
-                    tracing::trace!(
-                        target: LOG_TARGET,
-                        ?connection_id,
-                        ?address,
-                        "pending inbound Websocket connection",
-                    );
+        for secure in [false, true] {
+            let listener = match secure {
+                false => &mut self.listener,
+                true => &mut self.secure_listener,
+            };
 
-                    self.pending_inbound_connections.insert(
-                        connection_id,
-                        PendingInboundConnection {
-                            connection,
-                            address,
-                        },
-                    );
+            if let Poll::Ready(event) = listener.poll_next_unpin(cx) {
+                return match event {
+                    None => {
+                        tracing::error!(
+                            target: LOG_TARGET,
+                            "Websocket listener terminated, ignore if the node is stopping",
+                        );
 
-                    Poll::Ready(Some(TransportEvent::PendingInboundConnection {
-                        connection_id,
-                    }))
-                }
-            };
+                        Poll::Ready(None)
+                    }
+                    Some(Err(error)) => {
+                        tracing::error!(
+                            target: LOG_TARGET,
+                            ?error,
+                            "Websocket listener terminated with error",
+                        );
+
+                        Poll::Ready(None)
+                    }
+                    Some(Ok((connection, address))) => {
+                        let connection_id = self.context.next_connection_id();
+                        tracing::trace!(
+                            target: LOG_TARGET,
+                            ?connection_id,
+                            ?address,
+                            ?secure,
+                            "pending inbound Websocket connection",
+                        );
+
+                        self.pending_inbound_connections.insert(
+                            connection_id,
+                            PendingInboundConnection {
+                                connection,
+                                address,
+                                secure,
+                            },
+                        );
+
+                        Poll::Ready(Some(TransportEvent::PendingInboundConnection {
+                            connection_id,
+                        }))
+                    }
+                };
+            }
         }
 
         while let Poll::Ready(Some(result)) = self.pending_raw_connections.poll_next_unpin(cx) {
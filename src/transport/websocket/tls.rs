@@ -0,0 +1,402 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! TLS support for secure WebSocket connections.
+
+use crate::{error::Error, executor::Executor, transport::websocket::config::TlsConfig};
+
+use parking_lot::RwLock;
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::TcpStream,
+};
+use tokio_rustls::{
+    rustls::{
+        crypto::{ring, CryptoProvider},
+        pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer},
+        server::{ClientHello, ResolvesServerCert},
+        sign::CertifiedKey,
+        ClientConfig, RootCertStore, ServerConfig,
+    },
+    TlsAcceptor, TlsConnector,
+};
+
+use std::{
+    io,
+    path::Path,
+    pin::Pin,
+    sync::{Arc, Weak},
+    task::{Context, Poll},
+    time::{Duration, SystemTime},
+};
+
+/// Logging target for the file.
+const LOG_TARGET: &str = "litep2p::websocket::tls";
+
+/// Stream carrying a WebSocket connection, optionally secured with TLS.
+#[derive(Debug)]
+pub(super) enum MaybeTlsStream {
+    /// Plain TCP stream.
+    Plain(TcpStream),
+
+    /// TLS stream of an outbound connection.
+    Client(Box<tokio_rustls::client::TlsStream<TcpStream>>),
+
+    /// TLS stream of an inbound connection.
+    Server(Box<tokio_rustls::server::TlsStream<TcpStream>>),
+}
+
+impl AsyncRead for MaybeTlsStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Plain(stream) => Pin::new(stream).poll_read(cx, buf),
+            Self::Client(stream) => Pin::new(stream).poll_read(cx, buf),
+            Self::Server(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for MaybeTlsStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Self::Plain(stream) => Pin::new(stream).poll_write(cx, buf),
+            Self::Client(stream) => Pin::new(stream).poll_write(cx, buf),
+            Self::Server(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Plain(stream) => Pin::new(stream).poll_flush(cx),
+            Self::Client(stream) => Pin::new(stream).poll_flush(cx),
+            Self::Server(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Plain(stream) => Pin::new(stream).poll_shutdown(cx),
+            Self::Client(stream) => Pin::new(stream).poll_shutdown(cx),
+            Self::Server(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}
+
+/// Certificate resolver which always returns the most recently loaded certificate.
+#[derive(Debug)]
+struct CertificateResolver {
+    /// Current certificate chain and signing key.
+    certified_key: RwLock<Arc<CertifiedKey>>,
+}
+
+impl ResolvesServerCert for CertificateResolver {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(self.certified_key.read().clone())
+    }
+}
+
+/// TLS acceptor for secure WebSocket listeners.
+pub(super) struct TlsListener {
+    /// TLS acceptor.
+    acceptor: TlsAcceptor,
+}
+
+impl TlsListener {
+    /// Create new [`TlsListener`].
+    ///
+    /// Loads the certificate chain and private key specified in `config` and, if reloading is
+    /// enabled, starts a background task which checks the files for changes.
+    pub(super) fn new(config: TlsConfig, executor: &Arc<dyn Executor>) -> crate::Result<Self> {
+        let modified = modified_times(&config);
+        let resolver = Arc::new(CertificateResolver {
+            certified_key: RwLock::new(Arc::new(load_certified_key(&config)?)),
+        });
+
+        let mut server_config = ServerConfig::builder_with_provider(crypto_provider())
+            .with_safe_default_protocol_versions()
+            .map_err(|error| Error::TransportError(error.to_string()))?
+            .with_no_client_auth()
+            .with_cert_resolver(resolver.clone());
+        server_config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+        if let Some(interval) = config.reload_interval {
+            executor.run(Box::pin(reload_certificate(
+                Arc::downgrade(&resolver),
+                config,
+                interval,
+                modified,
+            )));
+        }
+
+        Ok(Self {
+            acceptor: TlsAcceptor::from(Arc::new(server_config)),
+        })
+    }
+
+    /// Get TLS acceptor for an inbound connection.
+    pub(super) fn acceptor(&self) -> TlsAcceptor {
+        self.acceptor.clone()
+    }
+}
+
+/// Create TLS connector for outbound connections.
+///
+/// The connector trusts the platform's native root certificates and the certificates found in
+/// `root_certificates`, if specified.
+pub(super) fn tls_connector(root_certificates: Option<&Path>) -> crate::Result<TlsConnector> {
+    let mut roots = RootCertStore::empty();
+    let native = rustls_native_certs::load_native_certs();
+
+    if !native.errors.is_empty() {
+        tracing::debug!(
+            target: LOG_TARGET,
+            errors = ?native.errors,
+            "failed to load some native root certificates",
+        );
+    }
+    roots.add_parsable_certificates(native.certs);
+
+    if let Some(path) = root_certificates {
+        let certificates = CertificateDer::pem_file_iter(path)
+            .and_then(|certificates| certificates.collect::<Result<Vec<_>, _>>())
+            .map_err(|error| pem_error(path, error))?;
+
+        for certificate in certificates {
+            roots.add(certificate).map_err(|error| {
+                tracing::debug!(
+                    target: LOG_TARGET,
+                    ?path,
+                    ?error,
+                    "invalid root certificate",
+                );
+
+                Error::InvalidCertificate
+            })?;
+        }
+    }
+
+    let client_config = ClientConfig::builder_with_provider(crypto_provider())
+        .with_safe_default_protocol_versions()
+        .map_err(|error| Error::TransportError(error.to_string()))?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+
+    Ok(TlsConnector::from(Arc::new(client_config)))
+}
+
+/// Get the crypto provider used for secure WebSocket connections.
+fn crypto_provider() -> Arc<CryptoProvider> {
+    Arc::new(ring::default_provider())
+}
+
+/// Convert PEM error into [`Error`].
+fn pem_error(path: &Path, error: tokio_rustls::rustls::pki_types::pem::Error) -> Error {
+    tracing::debug!(target: LOG_TARGET, ?path, ?error, "failed to read pem file");
+
+    match error {
+        tokio_rustls::rustls::pki_types::pem::Error::Io(error) => Error::IoError(error.kind()),
+        _ => Error::InvalidCertificate,
+    }
+}
+
+/// Load certificate chain and private key specified in `config`.
+fn load_certified_key(config: &TlsConfig) -> crate::Result<CertifiedKey> {
+    let certificate_chain = CertificateDer::pem_file_iter(&config.certificate_chain)
+        .and_then(|certificates| certificates.collect::<Result<Vec<_>, _>>())
+        .map_err(|error| pem_error(&config.certificate_chain, error))?;
+
+    if certificate_chain.is_empty() {
+        tracing::debug!(
+            target: LOG_TARGET,
+            path = ?config.certificate_chain,
+            "certificate chain is empty",
+        );
+        return Err(Error::InvalidCertificate);
+    }
+
+    let private_key = PrivateKeyDer::from_pem_file(&config.private_key)
+        .map_err(|error| pem_error(&config.private_key, error))?;
+
+    CertifiedKey::from_der(certificate_chain, private_key, &crypto_provider()).map_err(|error| {
+        tracing::debug!(
+            target: LOG_TARGET,
+            ?error,
+            "invalid private key or private key doesn't match the certificate",
+        );
+
+        Error::InvalidCertificate
+    })
+}
+
+/// Get modification times of the certificate chain and private key files.
+fn modified_times(config: &TlsConfig) -> Option<(SystemTime, SystemTime)> {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|metadata| metadata.modified());
+
+    Some((
+        modified(&config.certificate_chain).ok()?,
+        modified(&config.private_key).ok()?,
+    ))
+}
+
+/// Periodically check if the certificate files have changed and if so, reload them.
+///
+/// Exits once the listener has been dropped.
+async fn reload_certificate(
+    resolver: Weak<CertificateResolver>,
+    config: TlsConfig,
+    interval: Duration,
+    mut modified: Option<(SystemTime, SystemTime)>,
+) {
+    loop {
+        tokio::time::sleep(interval).await;
+
+        let Some(resolver) = resolver.upgrade() else {
+            return;
+        };
+
+        // files may be missing temporarily while they're being replaced
+        let current = modified_times(&config);
+        if current.is_none() || current == modified {
+            continue;
+        }
+
+        match load_certified_key(&config) {
+            Ok(certified_key) => {
+                tracing::info!(
+                    target: LOG_TARGET,
+                    path = ?config.certificate_chain,
+                    "tls certificate reloaded",
+                );
+
+                *resolver.certified_key.write() = Arc::new(certified_key);
+                modified = current;
+            }
+            Err(error) => tracing::warn!(
+                target: LOG_TARGET,
+                path = ?config.certificate_chain,
+                ?error,
+                "failed to reload tls certificate, using previous certificate",
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Write `contents` to a new temporary file.
+    fn write_file(contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("litep2p-{}.pem", rand::random::<u64>()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    /// Generate self-signed certificate and return paths to the certificate and its key.
+    fn self_signed() -> (PathBuf, PathBuf) {
+        let certificate = rcgen::generate_simple_self_signed(vec!["localhost".into()]).unwrap();
+
+        (
+            write_file(&certificate.serialize_pem().unwrap()),
+            write_file(&certificate.serialize_private_key_pem()),
+        )
+    }
+
+    #[test]
+    fn load_certificate() {
+        let (certificate_chain, private_key) = self_signed();
+
+        assert!(load_certified_key(&TlsConfig::new(certificate_chain, private_key)).is_ok());
+    }
+
+    #[test]
+    fn private_key_mismatch() {
+        let (certificate_chain, _) = self_signed();
+        let (_, private_key) = self_signed();
+
+        assert!(std::matches!(
+            load_certified_key(&TlsConfig::new(certificate_chain, private_key)),
+            Err(Error::InvalidCertificate)
+        ));
+    }
+
+    #[test]
+    fn missing_files() {
+        let (certificate_chain, _) = self_signed();
+        let private_key = std::env::temp_dir().join("litep2p-doesnt-exist.pem");
+
+        assert!(std::matches!(
+            load_certified_key(&TlsConfig::new(certificate_chain, private_key)),
+            Err(Error::IoError(io::ErrorKind::NotFound))
+        ));
+    }
+
+    #[test]
+    fn empty_certificate_chain() {
+        let (_, private_key) = self_signed();
+        let certificate_chain = write_file("");
+
+        assert!(std::matches!(
+            load_certified_key(&TlsConfig::new(certificate_chain, private_key)),
+            Err(Error::InvalidCertificate)
+        ));
+    }
+
+    #[tokio::test]
+    async fn certificate_reloaded() {
+        let (certificate_chain, private_key) = self_signed();
+        let config = TlsConfig {
+            reload_interval: Some(Duration::from_millis(50)),
+            ..TlsConfig::new(certificate_chain.clone(), private_key.clone())
+        };
+
+        let resolver = Arc::new(CertificateResolver {
+            certified_key: RwLock::new(Arc::new(load_certified_key(&config).unwrap())),
+        });
+        let original = resolver.certified_key.read().cert.clone();
+
+        tokio::spawn(reload_certificate(
+            Arc::downgrade(&resolver),
+            config.clone(),
+            Duration::from_millis(50),
+            modified_times(&config),
+        ));
+
+        // write a mismatching private key first and verify the old certificate is kept
+        let new = rcgen::generate_simple_self_signed(vec!["localhost".into()]).unwrap();
+        std::fs::write(&certificate_chain, new.serialize_pem().unwrap()).unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(resolver.certified_key.read().cert, original);
+
+        std::fs::write(&private_key, new.serialize_private_key_pem()).unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        assert_ne!(resolver.certified_key.read().cert, original);
+    }
+}
@@ -93,3 +93,15 @@ impl From<usize> for ConnectionId {
         ConnectionId(value)
     }
 }
+
+/// Listener ID.
+///
+/// Identifies a listener started with [`Litep2p::listen_on()`](crate::Litep2p::listen_on).
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ListenerId(usize);
+
+impl From<usize> for ListenerId {
+    fn from(value: usize) -> Self {
+        ListenerId(value)
+    }
+}
@@ -18,10 +18,15 @@
 // FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
 // DEALINGS IN THE SOFTWARE.
 
-use litep2p::{config::ConfigBuilder, transport::tcp::config::Config as TcpConfig};
+use litep2p::{
+    config::ConfigBuilder,
+    transport::{memory::config::Config as MemoryConfig, tcp::config::Config as TcpConfig},
+};
 
 #[cfg(feature = "quic")]
 use litep2p::transport::quic::config::Config as QuicConfig;
+#[cfg(feature = "webrtc")]
+use litep2p::transport::webrtc::config::Config as WebRtcConfig;
 #[cfg(feature = "websocket")]
 use litep2p::transport::websocket::config::Config as WebSocketConfig;
 
@@ -31,6 +36,9 @@ pub(crate) enum Transport {
     Quic(QuicConfig),
     #[cfg(feature = "websocket")]
     WebSocket(WebSocketConfig),
+    #[cfg(feature = "webrtc")]
+    WebRtc(WebRtcConfig),
+    Memory(MemoryConfig),
 }
 
 pub(crate) fn add_transport(config: ConfigBuilder, transport: Transport) -> ConfigBuilder {
@@ -40,5 +48,8 @@ pub(crate) fn add_transport(config: ConfigBuilder, transport: Transport) -> Conf
         Transport::Quic(transport) => config.with_quic(transport),
         #[cfg(feature = "websocket")]
         Transport::WebSocket(transport) => config.with_websocket(transport),
+        #[cfg(feature = "webrtc")]
+        Transport::WebRtc(transport) => config.with_webrtc(transport),
+        Transport::Memory(transport) => config.with_memory(transport),
     }
 }
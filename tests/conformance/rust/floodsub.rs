@@ -0,0 +1,152 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use futures::StreamExt;
+use libp2p::{
+    floodsub::{self, Floodsub, FloodsubEvent, Topic},
+    identity,
+    swarm::{SwarmBuilder, SwarmEvent},
+    PeerId, Swarm,
+};
+use litep2p::{
+    config::ConfigBuilder,
+    crypto::ed25519::Keypair,
+    protocol::libp2p::floodsub::{Config as FloodsubConfig, PubsubEvent, PubsubHandle},
+    transport::tcp::config::Config as TcpConfig,
+    Litep2p,
+};
+
+use std::time::Duration;
+
+const TOPIC: &str = "litep2p-topic";
+
+fn initialize_litep2p() -> (Litep2p, PubsubHandle) {
+    let (floodsub_config, handle) = FloodsubConfig::default();
+
+    let litep2p = Litep2p::new(
+        ConfigBuilder::new()
+            .with_keypair(Keypair::generate())
+            .with_tcp(TcpConfig {
+                listen_addresses: vec!["/ip6/::1/tcp/0".parse().unwrap()],
+                ..Default::default()
+            })
+            .with_libp2p_floodsub(floodsub_config)
+            .build(),
+    )
+    .unwrap();
+
+    (litep2p, handle)
+}
+
+fn initialize_libp2p() -> Swarm<Floodsub> {
+    let local_key = identity::Keypair::generate_ed25519();
+    let local_peer_id = PeerId::from(local_key.public());
+
+    let mut behaviour = Floodsub::new(local_peer_id);
+    behaviour.subscribe(Topic::new(TOPIC));
+
+    let transport = libp2p::tokio_development_transport(local_key).unwrap();
+    let mut swarm = SwarmBuilder::with_tokio_executor(transport, behaviour, local_peer_id).build();
+
+    swarm.listen_on("/ip6/::1/tcp/0".parse().unwrap()).unwrap();
+
+    swarm
+}
+
+#[tokio::test]
+async fn litep2p_and_libp2p_exchange_messages() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let mut libp2p = initialize_libp2p();
+    let (mut litep2p, mut handle) = initialize_litep2p();
+    let litep2p_peer = *litep2p.local_peer_id();
+    let address = litep2p.listen_addresses().next().unwrap().clone();
+
+    handle.subscribe(TOPIC).await;
+    libp2p.dial(address).unwrap();
+
+    // libp2p only sends messages to the peers in its partial view
+    libp2p
+        .behaviour_mut()
+        .add_node_to_partial_view(PeerId::from_bytes(&litep2p_peer.to_bytes()).unwrap());
+
+    let libp2p_peer = *libp2p.local_peer_id();
+    let mut libp2p_subscribed = false;
+    let mut litep2p_subscribed = false;
+    let mut libp2p_published = false;
+    let mut litep2p_published = false;
+    let mut libp2p_received = false;
+
+    tokio::time::timeout(Duration::from_secs(20), async {
+        loop {
+            tokio::select! {
+                _ = litep2p.next_event() => {}
+                event = libp2p.select_next_some() => match event {
+                    SwarmEvent::Behaviour(FloodsubEvent::Subscribed { peer_id, topic }) => {
+                        assert_eq!(peer_id.to_bytes(), litep2p_peer.to_bytes());
+                        assert_eq!(topic.id(), TOPIC);
+                        libp2p_subscribed = true;
+                    }
+                    SwarmEvent::Behaviour(FloodsubEvent::Message(floodsub::FloodsubMessage {
+                        source,
+                        data,
+                        ..
+                    })) => {
+                        assert_eq!(source.to_bytes(), litep2p_peer.to_bytes());
+                        assert_eq!(data, b"hello from litep2p".to_vec());
+                        libp2p_received = true;
+                    }
+                    _ => {}
+                },
+                event = handle.next() => match event.unwrap() {
+                    PubsubEvent::Subscribed { peer, topic } => {
+                        assert_eq!(peer.to_bytes(), libp2p_peer.to_bytes());
+                        assert_eq!(topic, TOPIC);
+                        litep2p_subscribed = true;
+                    }
+                    PubsubEvent::Message { topic, source, data } => {
+                        assert_eq!(topic, TOPIC);
+                        assert_eq!(source.to_bytes(), libp2p_peer.to_bytes());
+                        assert_eq!(data, b"hello from libp2p".to_vec());
+
+                        if !litep2p_published {
+                            handle.publish(TOPIC, b"hello from litep2p".to_vec()).await;
+                            litep2p_published = true;
+                        }
+                    }
+                    _ => {}
+                },
+            }
+
+            if libp2p_subscribed && litep2p_subscribed && !libp2p_published {
+                libp2p.behaviour_mut().publish(Topic::new(TOPIC), b"hello from libp2p".to_vec());
+                libp2p_published = true;
+            }
+
+            if libp2p_received {
+                break;
+            }
+        }
+    })
+    .await
+    .expect("messages to be exchanged in time");
+}
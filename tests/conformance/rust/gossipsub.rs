@@ -0,0 +1,232 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use futures::StreamExt;
+use libp2p::{
+    gossipsub, identity,
+    swarm::{SwarmBuilder, SwarmEvent},
+    PeerId, Swarm,
+};
+use litep2p::{
+    config::ConfigBuilder,
+    crypto::ed25519::Keypair,
+    protocol::libp2p::gossipsub::{
+        ConfigBuilder as GossipsubConfigBuilder, GossipsubEvent, GossipsubHandle, TopicHash,
+    },
+    transport::tcp::config::Config as TcpConfig,
+    Litep2p,
+};
+
+use std::time::Duration;
+
+const TOPIC: &str = "litep2p-topic";
+
+fn initialize_litep2p() -> (Litep2p, GossipsubHandle) {
+    let (gossipsub_config, handle) = GossipsubConfigBuilder::new()
+        .with_heartbeat_interval(Duration::from_millis(100))
+        .build();
+
+    let litep2p = Litep2p::new(
+        ConfigBuilder::new()
+            .with_keypair(Keypair::generate())
+            .with_tcp(TcpConfig {
+                listen_addresses: vec!["/ip6/::1/tcp/0".parse().unwrap()],
+                ..Default::default()
+            })
+            .with_libp2p_gossipsub(gossipsub_config)
+            .build(),
+    )
+    .unwrap();
+
+    (litep2p, handle)
+}
+
+fn initialize_libp2p(flood_publish: bool) -> Swarm<gossipsub::Behaviour> {
+    let local_key = identity::Keypair::generate_ed25519();
+    let local_peer_id = PeerId::from(local_key.public());
+
+    let config = gossipsub::ConfigBuilder::default()
+        .heartbeat_interval(Duration::from_millis(100))
+        .flood_publish(flood_publish)
+        .validation_mode(gossipsub::ValidationMode::Strict)
+        .build()
+        .unwrap();
+    let mut behaviour = gossipsub::Behaviour::new(
+        gossipsub::MessageAuthenticity::Signed(local_key.clone()),
+        config,
+    )
+    .unwrap();
+    behaviour.subscribe(&gossipsub::IdentTopic::new(TOPIC)).unwrap();
+
+    let transport = libp2p::tokio_development_transport(local_key).unwrap();
+    let mut swarm = SwarmBuilder::with_tokio_executor(transport, behaviour, local_peer_id).build();
+
+    swarm.listen_on("/ip6/::1/tcp/0".parse().unwrap()).unwrap();
+
+    swarm
+}
+
+/// Drive `litep2p` until it's closed.
+fn spawn_litep2p(mut litep2p: Litep2p) {
+    tokio::spawn(async move { while litep2p.next_event().await.is_some() {} });
+}
+
+/// Wait until `handle` reports that `count` peers have subscribed to the topic.
+async fn wait_subscriptions(handle: &mut GossipsubHandle, count: usize) {
+    let mut subscribed = 0usize;
+
+    while subscribed < count {
+        if let Some(GossipsubEvent::Subscribed { .. }) = handle.next().await {
+            subscribed += 1;
+        }
+    }
+}
+
+/// Wait for the next message received by `handle`.
+async fn next_message(handle: &mut GossipsubHandle) -> (litep2p::PeerId, Vec<u8>) {
+    loop {
+        if let Some(GossipsubEvent::Message { message, .. }) = handle.next().await {
+            return (message.source, message.data);
+        }
+    }
+}
+
+#[tokio::test]
+async fn litep2p_and_libp2p_exchange_messages() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let mut libp2p = initialize_libp2p(true);
+    let (litep2p, mut handle) = initialize_litep2p();
+    let litep2p_peer = *litep2p.local_peer_id();
+    let address = litep2p.listen_addresses().next().unwrap().clone();
+
+    handle.subscribe(TopicHash::from(TOPIC)).await;
+    spawn_litep2p(litep2p);
+    libp2p.dial(address).unwrap();
+
+    let libp2p_peer = *libp2p.local_peer_id();
+    let mut libp2p_subscribed = false;
+    let mut litep2p_subscribed = false;
+    let mut libp2p_published = false;
+    let mut litep2p_published = false;
+    let mut libp2p_received = false;
+
+    tokio::time::timeout(Duration::from_secs(20), async {
+        loop {
+            tokio::select! {
+                event = libp2p.select_next_some() => match event {
+                    SwarmEvent::Behaviour(gossipsub::Event::Subscribed { peer_id, .. }) => {
+                        assert_eq!(peer_id.to_bytes(), litep2p_peer.to_bytes());
+                        libp2p_subscribed = true;
+                    }
+                    SwarmEvent::Behaviour(gossipsub::Event::Message { message, .. }) => {
+                        assert_eq!(message.source.unwrap().to_bytes(), litep2p_peer.to_bytes());
+                        assert_eq!(message.data, b"hello from litep2p".to_vec());
+                        libp2p_received = true;
+                    }
+                    _ => {}
+                },
+                event = handle.next() => match event.unwrap() {
+                    GossipsubEvent::Subscribed { peer, topic } => {
+                        assert_eq!(peer.to_bytes(), libp2p_peer.to_bytes());
+                        assert_eq!(topic, TopicHash::from(TOPIC));
+                        litep2p_subscribed = true;
+                    }
+                    GossipsubEvent::Message { propagation_source, message, .. } => {
+                        assert_eq!(propagation_source.to_bytes(), libp2p_peer.to_bytes());
+                        assert_eq!(message.source.to_bytes(), libp2p_peer.to_bytes());
+                        assert_eq!(message.data, b"hello from libp2p".to_vec());
+
+                        if !litep2p_published {
+                            handle
+                                .publish(TopicHash::from(TOPIC), b"hello from litep2p".to_vec())
+                                .await
+                                .unwrap();
+                            litep2p_published = true;
+                        }
+                    }
+                    _ => {}
+                },
+            }
+
+            if libp2p_subscribed && litep2p_subscribed && !libp2p_published {
+                libp2p
+                    .behaviour_mut()
+                    .publish(
+                        gossipsub::IdentTopic::new(TOPIC),
+                        b"hello from libp2p".to_vec(),
+                    )
+                    .unwrap();
+                libp2p_published = true;
+            }
+
+            if libp2p_received {
+                break;
+            }
+        }
+    })
+    .await
+    .expect("messages to be exchanged in time");
+}
+
+#[tokio::test]
+async fn message_forwarded_through_libp2p_mesh() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    // litep2p1 <-> libp2p <-> litep2p2, where libp2p only forwards to its mesh peers
+    let mut libp2p = initialize_libp2p(false);
+    let (litep2p1, mut handle1) = initialize_litep2p();
+    let (litep2p2, mut handle2) = initialize_litep2p();
+    let litep2p1_peer = *litep2p1.local_peer_id();
+
+    handle1.subscribe(TopicHash::from(TOPIC)).await;
+    handle2.subscribe(TopicHash::from(TOPIC)).await;
+
+    libp2p.dial(litep2p1.listen_addresses().next().unwrap().clone()).unwrap();
+    libp2p.dial(litep2p2.listen_addresses().next().unwrap().clone()).unwrap();
+    spawn_litep2p(litep2p1);
+    spawn_litep2p(litep2p2);
+
+    tokio::spawn(async move {
+        loop {
+            let _ = libp2p.select_next_some().await;
+        }
+    });
+
+    tokio::time::timeout(Duration::from_secs(20), async {
+        wait_subscriptions(&mut handle1, 1).await;
+        wait_subscriptions(&mut handle2, 1).await;
+
+        // give libp2p time to graft both peers in its heartbeat
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        handle1.publish(TopicHash::from(TOPIC), b"hello".to_vec()).await.unwrap();
+
+        let (source, data) = next_message(&mut handle2).await;
+        assert_eq!(source, litep2p1_peer);
+        assert_eq!(data, b"hello".to_vec());
+    })
+    .await
+    .expect("message to be forwarded in time");
+}
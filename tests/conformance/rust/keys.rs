@@ -0,0 +1,57 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use libp2p::identity;
+use litep2p::{
+    crypto::{ed25519, Keypair},
+    PeerId,
+};
+
+#[test]
+fn decode_libp2p_private_key() {
+    let libp2p_keypair = identity::Keypair::generate_ed25519();
+    let libp2p_peer = libp2p::PeerId::from(libp2p_keypair.public());
+    let encoded = libp2p_keypair.to_protobuf_encoding().unwrap();
+
+    let keypair = Keypair::from_protobuf_encoding(&encoded).unwrap();
+    assert_eq!(
+        keypair.to_peer_id(),
+        PeerId::from_bytes(&libp2p_peer.to_bytes()).unwrap()
+    );
+
+    let keypair = ed25519::Keypair::from_protobuf_encoding(&encoded).unwrap();
+    assert_eq!(
+        keypair.public().to_peer_id(),
+        PeerId::from_bytes(&libp2p_peer.to_bytes()).unwrap()
+    );
+}
+
+#[test]
+fn encode_private_key_for_libp2p() {
+    let keypair = ed25519::Keypair::generate();
+    let peer = keypair.public().to_peer_id();
+
+    let libp2p_keypair =
+        identity::Keypair::from_protobuf_encoding(&keypair.to_protobuf_encoding()).unwrap();
+    assert_eq!(
+        libp2p::PeerId::from(libp2p_keypair.public()).to_bytes(),
+        peer.to_bytes()
+    );
+}
@@ -18,11 +18,21 @@
 // FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
 // DEALINGS IN THE SOFTWARE.
 
+#[cfg(test)]
+mod floodsub;
+#[cfg(test)]
+mod gossipsub;
 #[cfg(test)]
 mod identify;
 #[cfg(test)]
 mod kademlia;
 #[cfg(test)]
+mod keys;
+#[cfg(test)]
+mod mplex;
+#[cfg(test)]
 mod ping;
 #[cfg(all(test, feature = "quic"))]
 mod quic_ping;
+#[cfg(test)]
+mod rendezvous;
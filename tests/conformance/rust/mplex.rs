@@ -0,0 +1,168 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use futures::{Stream, StreamExt};
+use libp2p::{
+    core::upgrade,
+    identity, noise, ping,
+    swarm::{keep_alive, NetworkBehaviour, SwarmBuilder, SwarmEvent},
+    tcp, PeerId, Swarm, Transport,
+};
+use libp2p_mplex::MplexConfig;
+use litep2p::{
+    config::ConfigBuilder,
+    crypto::ed25519::Keypair,
+    protocol::libp2p::ping::{Config as PingConfig, PingEvent},
+    transport::{tcp::config::Config as TcpConfig, StreamMuxer},
+    types::multiaddr::Protocol,
+    Litep2p,
+};
+
+#[derive(NetworkBehaviour, Default)]
+struct Behaviour {
+    keep_alive: keep_alive::Behaviour,
+    ping: ping::Behaviour,
+}
+
+// initialize litep2p with ping support, using mplex as the only stream muxer
+fn initialize_litep2p() -> (Litep2p, Box<dyn Stream<Item = PingEvent> + Send + Unpin>) {
+    let keypair = Keypair::generate();
+    let (ping_config, ping_event_stream) = PingConfig::default();
+    let litep2p = Litep2p::new(
+        ConfigBuilder::new()
+            .with_keypair(keypair)
+            .with_tcp(TcpConfig {
+                listen_addresses: vec!["/ip6/::1/tcp/0".parse().unwrap()],
+                stream_muxers: vec![StreamMuxer::Mplex],
+                ..Default::default()
+            })
+            .with_libp2p_ping(ping_config)
+            .build(),
+    )
+    .unwrap();
+
+    (litep2p, ping_event_stream)
+}
+
+// initialize libp2p with a transport which only supports mplex
+fn initialize_libp2p() -> Swarm<Behaviour> {
+    let local_key = identity::Keypair::generate_ed25519();
+    let local_peer_id = PeerId::from(local_key.public());
+
+    let transport = tcp::tokio::Transport::new(tcp::Config::default())
+        .upgrade(upgrade::Version::V1)
+        .authenticate(noise::Config::new(&local_key).unwrap())
+        .multiplex(MplexConfig::default())
+        .boxed();
+    let mut swarm =
+        SwarmBuilder::with_tokio_executor(transport, Behaviour::default(), local_peer_id).build();
+
+    swarm.listen_on("/ip6/::1/tcp/0".parse().unwrap()).unwrap();
+
+    swarm
+}
+
+async fn ping_over_mplex(
+    mut libp2p: Swarm<Behaviour>,
+    mut ping_event_stream: Box<dyn Stream<Item = PingEvent> + Send + Unpin>,
+) {
+    let libp2p_peer = litep2p::PeerId::from_bytes(&libp2p.local_peer_id().to_bytes()).unwrap();
+    let mut libp2p_done = false;
+    let mut litep2p_done = false;
+
+    loop {
+        tokio::select! {
+            event = libp2p.select_next_some() => {
+                if let SwarmEvent::Behaviour(BehaviourEvent::Ping(ping::Event {
+                    result: Ok(_),
+                    ..
+                })) = event {
+                    libp2p_done = true;
+
+                    if libp2p_done && litep2p_done {
+                        break
+                    }
+                }
+            }
+            event = ping_event_stream.next() => {
+                let Some(PingEvent::Ping { peer, .. }) = event else {
+                    panic!("invalid event: {event:?}");
+                };
+                assert_eq!(peer, libp2p_peer);
+                litep2p_done = true;
+
+                if libp2p_done && litep2p_done {
+                    break
+                }
+            }
+            _ = tokio::time::sleep(std::time::Duration::from_secs(10)) => {
+                panic!("failed to receive ping in time");
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn libp2p_dials() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let mut libp2p = initialize_libp2p();
+    let (mut litep2p, ping_event_stream) = initialize_litep2p();
+    let address = litep2p.listen_addresses().next().unwrap().clone();
+
+    libp2p.dial(address).unwrap();
+
+    tokio::spawn(async move {
+        loop {
+            let _ = litep2p.next_event().await;
+        }
+    });
+
+    ping_over_mplex(libp2p, ping_event_stream).await;
+}
+
+#[tokio::test]
+async fn litep2p_dials() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let mut libp2p = initialize_libp2p();
+    let (mut litep2p, ping_event_stream) = initialize_litep2p();
+    let peer_id = *libp2p.local_peer_id();
+
+    let address = loop {
+        if let SwarmEvent::NewListenAddr { address, .. } = libp2p.select_next_some().await {
+            break address.with(Protocol::P2p(peer_id.into()));
+        }
+    };
+
+    litep2p.dial_address(address).await.unwrap();
+
+    tokio::spawn(async move {
+        loop {
+            let _ = litep2p.next_event().await;
+        }
+    });
+
+    ping_over_mplex(libp2p, ping_event_stream).await;
+}
@@ -58,8 +58,7 @@ fn initialize_litep2p() -> (Litep2p, Box<dyn Stream<Item = PingEvent> + Send + U
     (litep2p, ping_event_stream)
 }
 
-fn initialize_libp2p() -> Swarm<Behaviour> {
-    let local_key = identity::Keypair::generate_ed25519();
+fn initialize_libp2p(local_key: identity::Keypair) -> Swarm<Behaviour> {
     let local_peer_id = PeerId::from(local_key.public());
 
     tracing::debug!("Local peer id: {local_peer_id:?}");
@@ -75,11 +74,34 @@ fn initialize_libp2p() -> Swarm<Behaviour> {
 
 #[tokio::test]
 async fn libp2p_dials() {
+    libp2p_dials_with_key(identity::Keypair::generate_ed25519()).await
+}
+
+#[tokio::test]
+async fn libp2p_with_secp256k1_key_dials() {
+    libp2p_dials_with_key(identity::Keypair::generate_secp256k1()).await
+}
+
+#[tokio::test]
+async fn libp2p_with_ecdsa_key_dials() {
+    libp2p_dials_with_key(identity::Keypair::generate_ecdsa()).await
+}
+
+#[tokio::test]
+async fn libp2p_with_rsa_key_dials() {
+    let mut pkcs8 = include_bytes!("test_assets/rsa-2048.pk8").to_vec();
+
+    libp2p_dials_with_key(identity::Keypair::rsa_from_pkcs8(&mut pkcs8).unwrap()).await
+}
+
+async fn libp2p_dials_with_key(local_key: identity::Keypair) {
     let _ = tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
         .try_init();
 
-    let mut libp2p = initialize_libp2p();
+    let libp2p_peer =
+        litep2p::PeerId::from_bytes(&PeerId::from(local_key.public()).to_bytes()).unwrap();
+    let mut libp2p = initialize_libp2p(local_key);
     let (mut litep2p, mut ping_event_stream) = initialize_litep2p();
     let address = litep2p.listen_addresses().next().unwrap().clone();
 
@@ -111,7 +133,11 @@ async fn libp2p_dials() {
                     _ => {}
                 }
             }
-            _event = ping_event_stream.next() => {
+            event = ping_event_stream.next() => {
+                let Some(PingEvent::Ping { peer, .. }) = event else {
+                    panic!("invalid event: {event:?}");
+                };
+                assert_eq!(peer, libp2p_peer);
                 litep2p_done = true;
 
                 if libp2p_done && litep2p_done {
@@ -0,0 +1,176 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use futures::StreamExt;
+use libp2p::{
+    identity, rendezvous,
+    swarm::{AddressScore, SwarmBuilder, SwarmEvent},
+    PeerId, Swarm,
+};
+use litep2p::{
+    config::ConfigBuilder,
+    crypto::ed25519::Keypair,
+    protocol::libp2p::rendezvous::{ConfigBuilder as RendezvousConfigBuilder, RendezvousHandle},
+    transport::tcp::config::Config as TcpConfig,
+    Litep2p,
+};
+
+use std::time::Duration;
+
+const NAMESPACE: &str = "litep2p-namespace";
+
+fn initialize_litep2p(server: bool) -> (Litep2p, RendezvousHandle) {
+    let (rendezvous_config, handle) = RendezvousConfigBuilder::new().with_server(server).build();
+
+    let litep2p = Litep2p::new(
+        ConfigBuilder::new()
+            .with_keypair(Keypair::generate())
+            .with_tcp(TcpConfig {
+                listen_addresses: vec!["/ip6/::1/tcp/0".parse().unwrap()],
+                ..Default::default()
+            })
+            .with_libp2p_rendezvous(rendezvous_config)
+            .build(),
+    )
+    .unwrap();
+
+    (litep2p, handle)
+}
+
+fn initialize_libp2p<B: libp2p::swarm::NetworkBehaviour>(
+    behaviour: impl FnOnce(&identity::Keypair) -> B,
+) -> Swarm<B> {
+    let local_key = identity::Keypair::generate_ed25519();
+    let local_peer_id = PeerId::from(local_key.public());
+    let behaviour = behaviour(&local_key);
+
+    let transport = libp2p::tokio_development_transport(local_key).unwrap();
+    let mut swarm = SwarmBuilder::with_tokio_executor(transport, behaviour, local_peer_id).build();
+
+    swarm.listen_on("/ip6/::1/tcp/0".parse().unwrap()).unwrap();
+
+    swarm
+}
+
+/// Drive `litep2p` until it's closed.
+fn spawn_litep2p(mut litep2p: Litep2p) {
+    tokio::spawn(async move { while litep2p.next_event().await.is_some() {} });
+}
+
+#[tokio::test]
+async fn litep2p_registers_to_libp2p_rendezvous_point() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let mut libp2p = initialize_libp2p(|_| rendezvous::server::Behaviour::new(Default::default()));
+    let (mut litep2p, handle) = initialize_litep2p(false);
+    let litep2p_peer = *litep2p.local_peer_id();
+    let point = litep2p::PeerId::from_bytes(&libp2p.local_peer_id().to_bytes()).unwrap();
+
+    let address = loop {
+        if let SwarmEvent::NewListenAddr { address, .. } = libp2p.select_next_some().await {
+            break address;
+        }
+    };
+    let address = format!("{address}/p2p/{point}").parse().unwrap();
+    assert_eq!(
+        litep2p.add_known_address(point, std::iter::once(address)),
+        1
+    );
+    spawn_litep2p(litep2p);
+
+    tokio::spawn(async move {
+        loop {
+            if let SwarmEvent::Behaviour(rendezvous::server::Event::PeerRegistered {
+                peer, ..
+            }) = libp2p.select_next_some().await
+            {
+                assert_eq!(peer.to_bytes(), litep2p_peer.to_bytes());
+            }
+        }
+    });
+
+    tokio::time::timeout(Duration::from_secs(10), async {
+        let ttl = handle.register(point, NAMESPACE, None).await.unwrap();
+        assert_eq!(ttl, Duration::from_secs(2 * 60 * 60));
+
+        let (registrations, _) =
+            handle.discover(point, Some(NAMESPACE.to_owned()), None, None).await.unwrap();
+        assert_eq!(registrations.len(), 1);
+        assert_eq!(registrations[0].peer, litep2p_peer);
+        assert_eq!(registrations[0].namespace, NAMESPACE);
+        assert!(!registrations[0].addresses.is_empty());
+    })
+    .await
+    .expect("registration to succeed");
+}
+
+#[tokio::test]
+async fn libp2p_registers_to_litep2p_rendezvous_point() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let mut libp2p = initialize_libp2p(|key| rendezvous::client::Behaviour::new(key.clone()));
+    let (litep2p, _handle) = initialize_litep2p(true);
+    let point = PeerId::from_bytes(&litep2p.local_peer_id().to_bytes()).unwrap();
+    let address = litep2p.listen_addresses().next().unwrap().clone();
+    let namespace = rendezvous::Namespace::from_static(NAMESPACE);
+
+    spawn_litep2p(litep2p);
+    libp2p.dial(address).unwrap();
+
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            match libp2p.select_next_some().await {
+                SwarmEvent::NewListenAddr { address, .. } => {
+                    libp2p.add_external_address(address, AddressScore::Infinite);
+                }
+                SwarmEvent::ConnectionEstablished { peer_id, .. } => {
+                    assert_eq!(peer_id, point);
+                    libp2p.behaviour_mut().register(namespace.clone(), point, None);
+                }
+                SwarmEvent::Behaviour(rendezvous::client::Event::Registered {
+                    rendezvous_node,
+                    ttl,
+                    ..
+                }) => {
+                    assert_eq!(rendezvous_node, point);
+                    assert_eq!(ttl, 2 * 60 * 60);
+                    libp2p.behaviour_mut().discover(Some(namespace.clone()), None, None, point);
+                }
+                SwarmEvent::Behaviour(rendezvous::client::Event::Discovered {
+                    registrations,
+                    ..
+                }) => {
+                    assert_eq!(registrations.len(), 1);
+                    assert_eq!(registrations[0].record.peer_id(), *libp2p.local_peer_id());
+                    assert_eq!(registrations[0].namespace, namespace);
+                    break;
+                }
+                SwarmEvent::Behaviour(event) => panic!("unexpected event: {event:?}"),
+                _ => {}
+            }
+        }
+    })
+    .await
+    .expect("registration to succeed");
+}
@@ -0,0 +1,374 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Custom transport connecting nodes of the same process over [`tokio::io::duplex()`] streams.
+//!
+//! The transport handles `/memory/<port>` addresses and is implemented using only the public
+//! transport interface of litep2p.
+
+use litep2p::{
+    config::{ConfigBuilder, Role},
+    crypto::ed25519::Keypair,
+    error::{AddressError, DialError, Error, NegotiationError},
+    protocol::libp2p::ping::{Config as PingConfig, PingEvent},
+    transport::{
+        custom::{CustomTransport, UpgradeConfig},
+        tcp::config::Config as TcpConfig,
+        NegotiatedConnection, Transport, TransportEvent, TransportHandle,
+    },
+    types::ConnectionId,
+    Litep2p, Litep2pEvent, PeerId,
+};
+
+use futures::{future::BoxFuture, stream::FuturesUnordered, Stream, StreamExt};
+use multiaddr::{Multiaddr, Protocol};
+use tokio::{
+    io::DuplexStream,
+    sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+};
+
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+/// Listeners of the duplex transports, keyed by port.
+static LISTENERS: Mutex<BTreeMap<u64, UnboundedSender<DuplexStream>>> = Mutex::new(BTreeMap::new());
+
+/// Next port allocated to a duplex transport.
+static NEXT_PORT: AtomicU64 = AtomicU64::new(1);
+
+type PendingNegotiation = BoxFuture<
+    'static,
+    (
+        ConnectionId,
+        Result<NegotiatedConnection<DuplexStream>, NegotiationError>,
+    ),
+>;
+
+/// Builder of [`DuplexTransport`].
+struct Duplex;
+
+impl CustomTransport for Duplex {
+    fn protocol(&self) -> &'static str {
+        "memory"
+    }
+
+    fn build(
+        self: Box<Self>,
+        context: TransportHandle,
+    ) -> litep2p::Result<(Box<dyn Transport<Item = TransportEvent>>, Vec<Multiaddr>)> {
+        let port = NEXT_PORT.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = unbounded_channel();
+        LISTENERS.lock().unwrap().insert(port, tx);
+
+        Ok((
+            Box::new(DuplexTransport {
+                context,
+                rx,
+                pending_dials: HashMap::new(),
+                pending_inbound: HashMap::new(),
+                pending_negotiations: FuturesUnordered::new(),
+                negotiated: HashMap::new(),
+                pending_events: VecDeque::new(),
+                waker: None,
+            }),
+            vec![Multiaddr::empty().with(Protocol::Memory(port))],
+        ))
+    }
+}
+
+struct DuplexTransport {
+    context: TransportHandle,
+    rx: UnboundedReceiver<DuplexStream>,
+    pending_dials: HashMap<ConnectionId, Multiaddr>,
+    pending_inbound: HashMap<ConnectionId, DuplexStream>,
+    pending_negotiations: FuturesUnordered<PendingNegotiation>,
+    negotiated: HashMap<ConnectionId, NegotiatedConnection<DuplexStream>>,
+    pending_events: VecDeque<TransportEvent>,
+    waker: Option<Waker>,
+}
+
+impl DuplexTransport {
+    fn negotiate(
+        &mut self,
+        connection_id: ConnectionId,
+        stream: DuplexStream,
+        role: Role,
+        dialed_peer: Option<PeerId>,
+        address: Multiaddr,
+    ) {
+        let future = self.context.negotiate(
+            stream,
+            connection_id,
+            role,
+            dialed_peer,
+            address,
+            &UpgradeConfig::default(),
+        );
+        self.pending_negotiations
+            .push(Box::pin(async move { (connection_id, future.await) }));
+        self.wake();
+    }
+
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Transport for DuplexTransport {
+    fn dial(&mut self, connection_id: ConnectionId, address: Multiaddr) -> litep2p::Result<()> {
+        let mut iter = address.iter();
+        let (Some(Protocol::Memory(port)), Some(Protocol::P2p(peer))) = (iter.next(), iter.next())
+        else {
+            return Err(Error::AddressError(AddressError::InvalidProtocol));
+        };
+        let peer = PeerId::from_multihash(peer).ok();
+        let address = Multiaddr::empty().with(Protocol::Memory(port));
+
+        let Some(listener) = LISTENERS.lock().unwrap().get(&port).cloned() else {
+            self.pending_events.push_back(TransportEvent::DialFailure {
+                connection_id,
+                address,
+                error: DialError::Timeout,
+            });
+            self.wake();
+            return Ok(());
+        };
+
+        let (local, remote) = tokio::io::duplex(64 * 1024);
+        let _ = listener.send(remote);
+        self.pending_dials.insert(connection_id, address.clone());
+        self.negotiate(connection_id, local, Role::Dialer, peer, address);
+
+        Ok(())
+    }
+
+    fn accept(&mut self, connection_id: ConnectionId) -> litep2p::Result<()> {
+        let connection = self
+            .negotiated
+            .remove(&connection_id)
+            .ok_or(Error::ConnectionDoesntExist(connection_id))?;
+        self.context.start_connection(connection);
+
+        Ok(())
+    }
+
+    fn accept_pending(&mut self, connection_id: ConnectionId) -> litep2p::Result<()> {
+        let stream = self
+            .pending_inbound
+            .remove(&connection_id)
+            .ok_or(Error::ConnectionDoesntExist(connection_id))?;
+        self.negotiate(
+            connection_id,
+            stream,
+            Role::Listener,
+            None,
+            Multiaddr::empty().with(Protocol::Memory(0)),
+        );
+
+        Ok(())
+    }
+
+    fn reject_pending(&mut self, connection_id: ConnectionId) -> litep2p::Result<()> {
+        self.pending_inbound
+            .remove(&connection_id)
+            .map(|_| ())
+            .ok_or(Error::ConnectionDoesntExist(connection_id))
+    }
+
+    fn reject(&mut self, connection_id: ConnectionId) -> litep2p::Result<()> {
+        self.negotiated
+            .remove(&connection_id)
+            .map(|_| ())
+            .ok_or(Error::ConnectionDoesntExist(connection_id))
+    }
+
+    fn open(
+        &mut self,
+        _connection_id: ConnectionId,
+        _addresses: Vec<Multiaddr>,
+    ) -> litep2p::Result<()> {
+        Err(Error::NotSupported("`open()` is not supported".to_string()))
+    }
+
+    fn negotiate(&mut self, _connection_id: ConnectionId) -> litep2p::Result<()> {
+        Err(Error::NotSupported(
+            "`negotiate()` is not supported".to_string(),
+        ))
+    }
+
+    fn cancel(&mut self, _connection_id: ConnectionId) {}
+}
+
+impl Stream for DuplexTransport {
+    type Item = TransportEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(event) = self.pending_events.pop_front() {
+            return Poll::Ready(Some(event));
+        }
+
+        if let Poll::Ready(Some(stream)) = self.rx.poll_recv(cx) {
+            let connection_id = self.context.next_connection_id();
+            self.pending_inbound.insert(connection_id, stream);
+
+            return Poll::Ready(Some(TransportEvent::PendingInboundConnection {
+                connection_id,
+            }));
+        }
+
+        while let Poll::Ready(Some((connection_id, result))) =
+            self.pending_negotiations.poll_next_unpin(cx)
+        {
+            let dialed_address = self.pending_dials.remove(&connection_id);
+
+            match result {
+                Ok(connection) => {
+                    let event = TransportEvent::ConnectionEstablished {
+                        peer: connection.peer(),
+                        endpoint: connection.endpoint(),
+                    };
+                    self.negotiated.insert(connection_id, connection);
+
+                    return Poll::Ready(Some(event));
+                }
+                Err(error) => {
+                    // failures of inbound connections are not reported
+                    if let Some(address) = dialed_address {
+                        return Poll::Ready(Some(TransportEvent::DialFailure {
+                            connection_id,
+                            address,
+                            error: error.into(),
+                        }));
+                    }
+                }
+            }
+        }
+
+        self.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+fn make_litep2p() -> (Litep2p, Box<dyn Stream<Item = PingEvent> + Send + Unpin>) {
+    let (ping_config, ping_event_stream) = PingConfig::default();
+    let litep2p = Litep2p::new(
+        ConfigBuilder::new()
+            .with_keypair(Keypair::generate())
+            .with_libp2p_ping(ping_config)
+            .with_custom_transport(Box::new(Duplex))
+            .build(),
+    )
+    .unwrap();
+
+    (litep2p, ping_event_stream)
+}
+
+#[tokio::test]
+async fn custom_transport_works() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (mut litep2p1, mut ping_event_stream1) = make_litep2p();
+    let (mut litep2p2, _ping_event_stream2) = make_litep2p();
+    let peer2 = *litep2p2.local_peer_id();
+
+    let address = litep2p2.listen_addresses().next().unwrap().clone();
+    assert!(std::matches!(
+        address.iter().next(),
+        Some(Protocol::Memory(_))
+    ));
+    litep2p1.dial_address(address).await.unwrap();
+
+    let (res1, res2) = tokio::join!(litep2p1.next_event(), litep2p2.next_event());
+    match res1 {
+        Some(Litep2pEvent::ConnectionEstablished { peer, .. }) => assert_eq!(peer, peer2),
+        event => panic!("invalid event: {event:?}"),
+    }
+    assert!(std::matches!(
+        res2,
+        Some(Litep2pEvent::ConnectionEstablished { .. })
+    ));
+
+    tokio::spawn(async move {
+        loop {
+            let _ = litep2p1.next_event().await;
+        }
+    });
+    tokio::spawn(async move {
+        loop {
+            let _ = litep2p2.next_event().await;
+        }
+    });
+
+    // protocols run over the connections of the custom transport
+    match tokio::time::timeout(Duration::from_secs(10), ping_event_stream1.next()).await {
+        Ok(Some(PingEvent::Ping { peer, .. })) => assert_eq!(peer, peer2),
+        event => panic!("invalid event: {event:?}"),
+    }
+}
+
+#[tokio::test]
+async fn custom_transport_coexists_with_builtin_transports() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let litep2p = Litep2p::new(
+        ConfigBuilder::new()
+            .with_tcp(TcpConfig {
+                listen_addresses: vec!["/ip4/127.0.0.1/tcp/0".parse().unwrap()],
+                ..Default::default()
+            })
+            .with_custom_transport(Box::new(Duplex))
+            .build(),
+    )
+    .unwrap();
+
+    let addresses = litep2p.listen_addresses().cloned().collect::<Vec<_>>();
+    assert!(addresses
+        .iter()
+        .any(|address| std::matches!(address.iter().next(), Some(Protocol::Memory(_)))));
+    assert!(addresses
+        .iter()
+        .any(|address| std::matches!(address.iter().next(), Some(Protocol::Ip4(_)))));
+}
+
+#[tokio::test]
+async fn duplicate_custom_transports_are_rejected() {
+    let result = Litep2p::new(
+        ConfigBuilder::new()
+            .with_custom_transport(Box::new(Duplex))
+            .with_custom_transport(Box::new(Duplex))
+            .build(),
+    );
+
+    assert!(std::matches!(result, Err(Error::Other(_))));
+}
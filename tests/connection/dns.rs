@@ -0,0 +1,244 @@
+// Copyright 2024 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use litep2p::{
+    config::ConfigBuilder,
+    crypto::ed25519::Keypair,
+    error::{DialError, Error},
+    protocol::libp2p::ping::Config as PingConfig,
+    transport::{tcp::config::Config as TcpConfig, DnsConfig},
+    Litep2p, Litep2pEvent, PeerId,
+};
+
+use multiaddr::{Multiaddr, Protocol};
+use simple_dns::{
+    rdata::{RData, A, TXT},
+    Packet, ResourceRecord, CLASS, QTYPE, TYPE,
+};
+use tokio::net::UdpSocket;
+
+use std::{
+    collections::HashMap,
+    net::{Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+/// DNS record served by the test nameserver.
+enum Record {
+    A(Ipv4Addr),
+    Txt(String),
+}
+
+/// Start nameserver answering queries from `records`.
+async fn nameserver(records: Vec<(&'static str, Record)>) -> SocketAddr {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let address = socket.local_addr().unwrap();
+
+    let mut zone = HashMap::<&'static str, Vec<Record>>::new();
+    for (name, record) in records {
+        zone.entry(name).or_default().push(record);
+    }
+
+    tokio::spawn(async move {
+        let mut buffer = vec![0u8; 4096];
+
+        while let Ok((nread, peer)) = socket.recv_from(&mut buffer).await {
+            let Ok(query) = Packet::parse(&buffer[..nread]) else {
+                continue;
+            };
+
+            let mut reply = Packet::new_reply(query.id());
+            reply.questions = query.questions.clone();
+
+            for question in &query.questions {
+                let name = question.qname.to_string();
+                let Some(records) = zone.get(name.trim_end_matches('.')) else {
+                    continue;
+                };
+
+                for record in records {
+                    let rdata = match (record, question.qtype) {
+                        (Record::A(ip), QTYPE::TYPE(TYPE::A)) => RData::A(A::from(*ip)),
+                        (Record::Txt(text), QTYPE::TYPE(TYPE::TXT)) => {
+                            let mut txt = TXT::new();
+                            txt.add_string(text).unwrap();
+                            RData::TXT(txt)
+                        }
+                        _ => continue,
+                    };
+
+                    reply.answers.push(ResourceRecord::new(
+                        question.qname.clone(),
+                        CLASS::IN,
+                        60,
+                        rdata,
+                    ));
+                }
+            }
+
+            let _ = socket.send_to(&reply.build_bytes_vec().unwrap(), peer).await;
+        }
+    });
+
+    address
+}
+
+fn make_litep2p(nameserver: SocketAddr) -> Litep2p {
+    let (ping_config, _ping_event_stream) = PingConfig::default();
+
+    Litep2p::new(
+        ConfigBuilder::new()
+            .with_keypair(Keypair::generate())
+            .with_libp2p_ping(ping_config)
+            .with_tcp(TcpConfig {
+                listen_addresses: vec!["/ip4/127.0.0.1/tcp/0".parse().unwrap()],
+                ..Default::default()
+            })
+            .with_dns(DnsConfig {
+                nameservers: vec![nameserver],
+                timeout: Duration::from_secs(1),
+                ..Default::default()
+            })
+            .build(),
+    )
+    .unwrap()
+}
+
+/// Get the TCP port `litep2p` is listening on.
+fn listen_port(litep2p: &Litep2p) -> u16 {
+    litep2p
+        .listen_addresses()
+        .find_map(|address| {
+            address.iter().find_map(|protocol| match protocol {
+                Protocol::Tcp(port) => Some(port),
+                _ => None,
+            })
+        })
+        .unwrap()
+}
+
+/// Poll both nodes until `dialer` establishes a connection to `peer` or fails to dial.
+async fn wait_for_connection(dialer: &mut Litep2p, listener: &mut Litep2p, peer: PeerId) -> bool {
+    loop {
+        tokio::select! {
+            event = dialer.next_event() => match event {
+                Some(Litep2pEvent::ConnectionEstablished { peer: remote, .. }) => {
+                    assert_eq!(remote, peer);
+                    return true;
+                }
+                Some(Litep2pEvent::DialFailure { .. }) => return false,
+                _ => {}
+            },
+            _ = listener.next_event() => {}
+            _ = tokio::time::sleep(Duration::from_secs(10)) => panic!("dial timed out"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn dial_dns_address_tries_all_resolved_addresses() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    // nothing listens on `127.0.0.2` so the first resolved address fails
+    let nameserver = nameserver(vec![
+        ("node.test", Record::A(Ipv4Addr::new(127, 0, 0, 2))),
+        ("node.test", Record::A(Ipv4Addr::LOCALHOST)),
+    ])
+    .await;
+
+    let mut listener = make_litep2p(nameserver);
+    let mut dialer = make_litep2p(nameserver);
+    let peer = *listener.local_peer_id();
+
+    let address: Multiaddr = format!("/dns4/node.test/tcp/{}/p2p/{peer}", listen_port(&listener))
+        .parse()
+        .unwrap();
+
+    dialer.dial_address(address).await.unwrap();
+    assert!(wait_for_connection(&mut dialer, &mut listener, peer).await);
+}
+
+#[tokio::test]
+async fn dial_dnsaddr() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let mut listener = make_litep2p("127.0.0.1:1".parse().unwrap());
+    let peer = *listener.local_peer_id();
+    let port = listen_port(&listener);
+
+    let nameserver = nameserver(vec![
+        (
+            "_dnsaddr.bootstrap.test",
+            Record::Txt("dnsaddr=/dnsaddr/nodes.bootstrap.test".to_string()),
+        ),
+        (
+            "_dnsaddr.nodes.bootstrap.test",
+            Record::Txt(format!(
+                "dnsaddr=/ip4/127.0.0.1/tcp/{port}/p2p/{}",
+                PeerId::random()
+            )),
+        ),
+        (
+            "_dnsaddr.nodes.bootstrap.test",
+            Record::Txt(format!("dnsaddr=/ip4/127.0.0.1/tcp/{port}/p2p/{peer}")),
+        ),
+    ])
+    .await;
+    let mut dialer = make_litep2p(nameserver);
+
+    dialer
+        .dial_address(format!("/dnsaddr/bootstrap.test/p2p/{peer}").parse().unwrap())
+        .await
+        .unwrap();
+    assert!(wait_for_connection(&mut dialer, &mut listener, peer).await);
+}
+
+#[tokio::test]
+async fn dial_unresolvable_dnsaddr() {
+    let nameserver = nameserver(Vec::new()).await;
+    let mut dialer = make_litep2p(nameserver);
+    let address: Multiaddr =
+        format!("/dnsaddr/unknown.test/p2p/{}", PeerId::random()).parse().unwrap();
+
+    dialer.dial_address(address.clone()).await.unwrap();
+
+    match tokio::time::timeout(Duration::from_secs(10), dialer.next_event()).await {
+        Ok(Some(Litep2pEvent::DialFailure {
+            address: failed_address,
+            error: DialError::DnsError(_),
+        })) => assert_eq!(failed_address, address),
+        event => panic!("unexpected event: {event:?}"),
+    }
+}
+
+#[tokio::test]
+async fn dial_dnsaddr_without_peer_id() {
+    let nameserver = nameserver(Vec::new()).await;
+    let mut dialer = make_litep2p(nameserver);
+
+    assert!(std::matches!(
+        dialer.dial_address("/dnsaddr/bootstrap.test".parse().unwrap()).await,
+        Err(Error::AddressError(_))
+    ));
+}
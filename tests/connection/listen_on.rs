@@ -0,0 +1,261 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+use litep2p::{
+    config::ConfigBuilder,
+    crypto::ed25519::Keypair,
+    error::Error,
+    protocol::libp2p::identify::{Config as IdentifyConfig, IdentifyEvent},
+    transport::tcp::config::Config as TcpConfig,
+    types::ListenerId,
+    Litep2p, Litep2pEvent,
+};
+
+use futures::{Stream, StreamExt};
+use multiaddr::Multiaddr;
+
+use std::time::Duration;
+
+fn make_litep2p() -> (
+    Litep2p,
+    Box<dyn Stream<Item = IdentifyEvent> + Send + Unpin>,
+) {
+    let (identify_config, identify_event_stream) =
+        IdentifyConfig::new("1.0.0".to_string(), Some("litep2p/1.0.0".to_string()));
+
+    let litep2p = Litep2p::new(
+        ConfigBuilder::new()
+            .with_keypair(Keypair::generate())
+            .with_libp2p_identify(identify_config)
+            .with_tcp(TcpConfig {
+                listen_addresses: Vec::new(),
+                ..Default::default()
+            })
+            .build(),
+    )
+    .unwrap();
+
+    (litep2p, identify_event_stream)
+}
+
+/// Start listener on `address` and return its ID and the first address it's reachable at.
+async fn listen_on(litep2p: &mut Litep2p, address: &str) -> (ListenerId, Multiaddr) {
+    let listener_id = litep2p.listen_on(address.parse().unwrap()).unwrap();
+
+    match litep2p.next_event().await {
+        Some(Litep2pEvent::NewListenAddress {
+            listener_id: id,
+            address,
+        }) => {
+            assert_eq!(id, listener_id);
+            (listener_id, address)
+        }
+        event => panic!("invalid event: {event:?}"),
+    }
+}
+
+/// Dial `address` and return `true` if the connection was established.
+async fn connect(dialer: &mut Litep2p, listener: &mut Litep2p, address: Multiaddr) -> bool {
+    if dialer.dial_address(address).await.is_err() {
+        return false;
+    }
+
+    loop {
+        tokio::select! {
+            event = dialer.next_event() => match event {
+                Some(Litep2pEvent::ConnectionEstablished { .. }) => return true,
+                Some(Litep2pEvent::DialFailure { .. }) => return false,
+                _ => {}
+            },
+            _ = listener.next_event() => {}
+            _ = tokio::time::sleep(Duration::from_secs(10)) => panic!("dial timed out"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn listen_on_running_transport() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (mut litep2p1, _identify_event_stream1) = make_litep2p();
+    let (mut litep2p2, _identify_event_stream2) = make_litep2p();
+    assert_eq!(litep2p2.listen_addresses().count(), 0);
+
+    let (_, address) = listen_on(&mut litep2p2, "/ip4/127.0.0.1/tcp/0").await;
+    assert!(litep2p2.listen_addresses().any(|listen_address| listen_address == &address));
+    assert!(connect(&mut litep2p1, &mut litep2p2, address).await);
+}
+
+#[tokio::test]
+async fn stop_listening() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (mut litep2p1, _identify_event_stream1) = make_litep2p();
+    let (mut litep2p2, _identify_event_stream2) = make_litep2p();
+
+    let (listener_id, address) = listen_on(&mut litep2p2, "/ip4/127.0.0.1/tcp/0").await;
+    assert!(litep2p2.public_addresses().add_address(address.clone()).unwrap());
+
+    litep2p2.stop_listening(listener_id).unwrap();
+    match litep2p2.next_event().await {
+        Some(Litep2pEvent::ListenAddressExpired {
+            listener_id: id,
+            address: expired,
+        }) => {
+            assert_eq!(id, listener_id);
+            assert_eq!(expired, address);
+        }
+        event => panic!("invalid event: {event:?}"),
+    }
+
+    assert_eq!(litep2p2.listen_addresses().count(), 0);
+    assert!(litep2p2.public_addresses().get_addresses().is_empty());
+    assert!(!connect(&mut litep2p1, &mut litep2p2, address).await);
+}
+
+#[tokio::test]
+async fn stop_unknown_listener() {
+    let (mut litep2p, _identify_event_stream) = make_litep2p();
+    let (listener_id, _) = listen_on(&mut litep2p, "/ip4/127.0.0.1/tcp/0").await;
+
+    litep2p.stop_listening(listener_id).unwrap();
+    assert!(std::matches!(
+        litep2p.stop_listening(listener_id),
+        Err(Error::ListenerDoesntExist(id)) if id == listener_id
+    ));
+}
+
+#[tokio::test]
+async fn startup_listener_cannot_be_stopped() {
+    let mut litep2p = Litep2p::new(
+        ConfigBuilder::new()
+            .with_keypair(Keypair::generate())
+            .with_tcp(TcpConfig {
+                listen_addresses: vec!["/ip4/127.0.0.1/tcp/0".parse().unwrap()],
+                ..Default::default()
+            })
+            .build(),
+    )
+    .unwrap();
+
+    // the startup listener got the first ID
+    let (listener_id, _) = listen_on(&mut litep2p, "/ip4/127.0.0.1/tcp/0").await;
+    assert_eq!(listener_id, ListenerId::from(1usize));
+
+    assert!(std::matches!(
+        litep2p.stop_listening(ListenerId::from(0usize)),
+        Err(Error::ListenerDoesntExist(_))
+    ));
+    assert_eq!(litep2p.listen_addresses().count(), 2);
+}
+
+#[tokio::test]
+async fn listen_on_disabled_transport() {
+    let (mut litep2p, _identify_event_stream) = make_litep2p();
+
+    assert!(std::matches!(
+        litep2p.listen_on("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap()),
+        Err(Error::TransportNotSupported(_))
+    ));
+    assert!(std::matches!(
+        litep2p.listen_on("/memory/1337".parse().unwrap()),
+        Err(Error::TransportNotSupported(_))
+    ));
+}
+
+#[tokio::test]
+async fn identify_reports_new_listen_address() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (mut litep2p1, mut identify_event_stream1) = make_litep2p();
+    let (mut litep2p2, mut identify_event_stream2) = make_litep2p();
+
+    let (_, address) = listen_on(&mut litep2p2, "/ip4/127.0.0.1/tcp/0").await;
+    litep2p1.dial_address(address.clone()).await.unwrap();
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = litep2p2.next_event() => {}
+                _ = identify_event_stream2.next() => {}
+            }
+        }
+    });
+
+    let future = async {
+        loop {
+            tokio::select! {
+                _ = litep2p1.next_event() => {}
+                event = identify_event_stream1.next() => {
+                    if let Some(IdentifyEvent::PeerIdentified { listen_addresses, .. }) = event {
+                        return listen_addresses;
+                    }
+                }
+            }
+        }
+    };
+
+    let listen_addresses = tokio::time::timeout(Duration::from_secs(10), future)
+        .await
+        .expect("peer to be identified");
+    assert!(listen_addresses.contains(&address));
+}
+
+#[cfg(feature = "websocket")]
+#[tokio::test]
+async fn listen_on_websocket() {
+    use litep2p::transport::websocket::config::Config as WebSocketConfig;
+
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let make_litep2p = || {
+        Litep2p::new(
+            ConfigBuilder::new()
+                .with_keypair(Keypair::generate())
+                .with_websocket(WebSocketConfig {
+                    listen_addresses: Vec::new(),
+                    ..Default::default()
+                })
+                .build(),
+        )
+        .unwrap()
+    };
+    let mut litep2p1 = make_litep2p();
+    let mut litep2p2 = make_litep2p();
+
+    let (listener_id, address) = listen_on(&mut litep2p2, "/ip4/127.0.0.1/tcp/0/ws").await;
+    assert!(connect(&mut litep2p1, &mut litep2p2, address).await);
+
+    // secure listeners require `tls_config`
+    assert!(std::matches!(
+        litep2p2.listen_on("/ip4/127.0.0.1/tcp/0/tls/ws".parse().unwrap()),
+        Err(Error::NotSupported(_))
+    ));
+
+    litep2p2.stop_listening(listener_id).unwrap();
+    assert_eq!(litep2p2.listen_addresses().count(), 0);
+}
@@ -0,0 +1,168 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use litep2p::{
+    config::ConfigBuilder,
+    crypto::ed25519::Keypair,
+    error::DialError,
+    protocol::libp2p::ping::{Config as PingConfig, PingEvent},
+    transport::memory::{config::Config as MemoryConfig, Faults},
+    Litep2p, Litep2pEvent,
+};
+
+use futures::{Stream, StreamExt};
+use multiaddr::{Multiaddr, Protocol};
+
+use std::time::Duration;
+
+fn make_litep2p(faults: Faults) -> (Litep2p, Box<dyn Stream<Item = PingEvent> + Send + Unpin>) {
+    let (ping_config, ping_event_stream) = PingConfig::default();
+    let litep2p = Litep2p::new(
+        ConfigBuilder::new()
+            .with_keypair(Keypair::generate())
+            .with_libp2p_ping(ping_config)
+            .with_memory(MemoryConfig {
+                faults,
+                ..Default::default()
+            })
+            .build(),
+    )
+    .unwrap();
+
+    (litep2p, ping_event_stream)
+}
+
+fn memory_port(address: &Multiaddr) -> u64 {
+    match address.iter().next() {
+        Some(Protocol::Memory(port)) => port,
+        _ => panic!("invalid memory address: {address:?}"),
+    }
+}
+
+#[tokio::test]
+async fn latency_is_applied() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let faults1 = Faults::new();
+    let faults2 = Faults::new();
+    faults1.set_latency(Some(Duration::from_millis(100)));
+    faults2.set_latency(Some(Duration::from_millis(150)));
+
+    let (mut litep2p1, mut ping_event_stream1) = make_litep2p(faults1);
+    let (mut litep2p2, _ping_event_stream2) = make_litep2p(faults2);
+
+    let address = litep2p2.listen_addresses().next().unwrap().clone();
+    litep2p1.dial_address(address).await.unwrap();
+
+    tokio::spawn(async move {
+        loop {
+            let _ = litep2p2.next_event().await;
+        }
+    });
+    tokio::spawn(async move {
+        loop {
+            let _ = litep2p1.next_event().await;
+        }
+    });
+
+    // the round-trip time is at least the sum of the latencies of the nodes
+    let Some(PingEvent::Ping { ping, .. }) = ping_event_stream1.next().await else {
+        panic!("ping event stream terminated");
+    };
+    assert!(ping >= Duration::from_millis(250), "ping: {ping:?}");
+}
+
+#[tokio::test]
+async fn partitioned_nodes_cannot_connect() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let faults = Faults::new();
+    let (mut litep2p1, _ping_event_stream1) = make_litep2p(Faults::new());
+    let (mut litep2p2, _ping_event_stream2) = make_litep2p(faults.clone());
+
+    let address1 = litep2p1.listen_addresses().next().unwrap().clone();
+    let address2 = litep2p2.listen_addresses().next().unwrap().clone();
+
+    // partitioning the listener also blocks dials from the other node
+    faults.partition(memory_port(&address1));
+    litep2p1.dial_address(address2.clone()).await.unwrap();
+
+    match litep2p1.next_event().await {
+        Some(Litep2pEvent::DialFailure {
+            error: DialError::Timeout,
+            ..
+        }) => {}
+        event => panic!("invalid event: {event:?}"),
+    }
+
+    faults.heal_all();
+    litep2p1.dial_address(address2).await.unwrap();
+
+    let (res1, res2) = tokio::join!(litep2p1.next_event(), litep2p2.next_event());
+    assert!(std::matches!(
+        res1,
+        Some(Litep2pEvent::ConnectionEstablished { .. })
+    ));
+    assert!(std::matches!(
+        res2,
+        Some(Litep2pEvent::ConnectionEstablished { .. })
+    ));
+}
+
+#[tokio::test]
+async fn dropped_connections_are_closed() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let faults = Faults::new();
+    let (mut litep2p1, _ping_event_stream1) = make_litep2p(faults.clone());
+    let (mut litep2p2, _ping_event_stream2) = make_litep2p(Faults::new());
+    let peer2 = *litep2p2.local_peer_id();
+
+    let address = litep2p2.listen_addresses().next().unwrap().clone();
+    litep2p1.dial_address(address).await.unwrap();
+
+    let (res1, res2) = tokio::join!(litep2p1.next_event(), litep2p2.next_event());
+    assert!(std::matches!(
+        res1,
+        Some(Litep2pEvent::ConnectionEstablished { .. })
+    ));
+    assert!(std::matches!(
+        res2,
+        Some(Litep2pEvent::ConnectionEstablished { .. })
+    ));
+
+    faults.drop_connections();
+
+    let (res1, res2) = tokio::join!(litep2p1.next_event(), litep2p2.next_event());
+    match res1 {
+        Some(Litep2pEvent::ConnectionClosed { peer, .. }) => assert_eq!(peer, peer2),
+        event => panic!("invalid event: {event:?}"),
+    }
+    assert!(std::matches!(
+        res2,
+        Some(Litep2pEvent::ConnectionClosed { .. })
+    ));
+}
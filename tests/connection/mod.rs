@@ -27,6 +27,8 @@ use litep2p::{
     Litep2p, Litep2pEvent, PeerId,
 };
 
+#[cfg(feature = "webrtc")]
+use litep2p::transport::webrtc::config::Config as WebRtcConfig;
 #[cfg(feature = "websocket")]
 use litep2p::transport::websocket::config::Config as WebSocketConfig;
 #[cfg(feature = "quic")]
@@ -42,8 +44,20 @@ use tokio::net::UdpSocket;
 
 use crate::common::{add_transport, Transport};
 
+#[cfg(test)]
+mod custom;
+#[cfg(test)]
+mod dns;
+#[cfg(test)]
+mod listen_on;
+#[cfg(test)]
+mod memory;
 #[cfg(test)]
 mod protocol_dial_invalid_address;
+#[cfg(unix)]
+mod unix;
+#[cfg(feature = "websocket")]
+mod websocket_tls;
 
 #[tokio::test]
 async fn two_litep2ps_work_tcp() {
@@ -86,6 +100,32 @@ async fn two_litep2ps_work_websocket() {
     .await;
 }
 
+#[cfg(feature = "webrtc")]
+#[tokio::test]
+#[ignore = "str0m 0.6 rejects the DTLS certificate of a litep2p dialer on the listener side"]
+async fn two_litep2ps_work_webrtc() {
+    two_litep2ps_work(
+        Transport::WebRtc(WebRtcConfig {
+            listen_addresses: vec!["/ip4/127.0.0.1/udp/0/webrtc-direct".parse().unwrap()],
+            ..Default::default()
+        }),
+        Transport::WebRtc(WebRtcConfig {
+            listen_addresses: vec!["/ip4/127.0.0.1/udp/0/webrtc-direct".parse().unwrap()],
+            ..Default::default()
+        }),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn two_litep2ps_work_memory() {
+    two_litep2ps_work(
+        Transport::Memory(Default::default()),
+        Transport::Memory(Default::default()),
+    )
+    .await;
+}
+
 async fn two_litep2ps_work(transport1: Transport, transport2: Transport) {
     let _ = tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
@@ -181,6 +221,40 @@ async fn dial_failure_websocket() {
     .await;
 }
 
+#[cfg(feature = "webrtc")]
+#[tokio::test]
+async fn dial_failure_webrtc() {
+    dial_failure(
+        Transport::WebRtc(WebRtcConfig {
+            listen_addresses: vec!["/ip4/127.0.0.1/udp/0/webrtc-direct".parse().unwrap()],
+            connection_open_timeout: std::time::Duration::from_secs(2),
+            ..Default::default()
+        }),
+        Transport::WebRtc(WebRtcConfig {
+            listen_addresses: vec!["/ip4/127.0.0.1/udp/0/webrtc-direct".parse().unwrap()],
+            ..Default::default()
+        }),
+        Multiaddr::empty()
+            .with(Protocol::Ip4(std::net::Ipv4Addr::new(127, 0, 0, 1)))
+            .with(Protocol::Udp(1))
+            .with(Protocol::WebRTC)
+            .with(Protocol::Certhash(
+                Multihash::wrap(0x12, &[0u8; 32]).unwrap(),
+            )),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn dial_failure_memory() {
+    dial_failure(
+        Transport::Memory(Default::default()),
+        Transport::Memory(Default::default()),
+        Multiaddr::empty().with(Protocol::Memory(u64::MAX)),
+    )
+    .await;
+}
+
 async fn dial_failure(transport1: Transport, transport2: Transport, dial_address: Multiaddr) {
     let _ = tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
@@ -221,6 +295,67 @@ async fn dial_failure(transport1: Transport, transport2: Transport, dial_address
     ));
 }
 
+#[cfg(feature = "webrtc")]
+#[tokio::test]
+#[ignore = "str0m 0.6 rejects the DTLS certificate of a litep2p dialer on the listener side"]
+async fn dial_webrtc_with_wrong_peer_id() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let transport = || {
+        Transport::WebRtc(WebRtcConfig {
+            listen_addresses: vec!["/ip4/127.0.0.1/udp/0/webrtc-direct".parse().unwrap()],
+            ..Default::default()
+        })
+    };
+
+    let (ping_config1, _ping_event_stream1) = PingConfig::default();
+    let config1 = ConfigBuilder::new()
+        .with_keypair(Keypair::generate())
+        .with_libp2p_ping(ping_config1);
+    let config1 = add_transport(config1, transport()).build();
+
+    let (ping_config2, _ping_event_stream2) = PingConfig::default();
+    let config2 = ConfigBuilder::new()
+        .with_keypair(Keypair::generate())
+        .with_libp2p_ping(ping_config2);
+    let config2 = add_transport(config2, transport()).build();
+
+    let mut litep2p1 = Litep2p::new(config1).unwrap();
+    let mut litep2p2 = Litep2p::new(config2).unwrap();
+    let peer2 = *litep2p2.local_peer_id();
+    let wrong_peer = PeerId::random();
+
+    let address = litep2p2
+        .listen_addresses()
+        .next()
+        .unwrap()
+        .iter()
+        .filter(|protocol| !std::matches!(protocol, Protocol::P2p(_)))
+        .collect::<Multiaddr>()
+        .with(Protocol::P2p(Multihash::from(wrong_peer)));
+
+    litep2p1.dial_address(address).await.unwrap();
+
+    tokio::spawn(async move {
+        loop {
+            let _ = litep2p2.next_event().await;
+        }
+    });
+
+    match tokio::time::timeout(std::time::Duration::from_secs(10), litep2p1.next_event()).await {
+        Ok(Some(Litep2pEvent::DialFailure {
+            error: DialError::NegotiationError(NegotiationError::PeerIdMismatch(expected, got)),
+            ..
+        })) => {
+            assert_eq!(expected, wrong_peer);
+            assert_eq!(got, peer2);
+        }
+        event => panic!("unexpected event: {event:?}"),
+    }
+}
+
 #[tokio::test]
 async fn connect_over_dns() {
     let _ = tracing_subscriber::fmt()
@@ -546,6 +681,15 @@ async fn keep_alive_timeout_websocket() {
     .await;
 }
 
+#[tokio::test]
+async fn keep_alive_timeout_memory() {
+    keep_alive_timeout(
+        Transport::Memory(Default::default()),
+        Transport::Memory(Default::default()),
+    )
+    .await;
+}
+
 async fn keep_alive_timeout(transport1: Transport, transport2: Transport) {
     let _ = tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
@@ -1015,14 +1159,7 @@ async fn make_dummy_litep2p(
         .with_keypair(Keypair::generate())
         .with_libp2p_ping(ping_config);
 
-    let litep2p_config = match transport {
-        Transport::Tcp(config) => litep2p_config.with_tcp(config),
-        #[cfg(feature = "quic")]
-        Transport::Quic(config) => litep2p_config.with_quic(config),
-        #[cfg(feature = "websocket")]
-        Transport::WebSocket(config) => litep2p_config.with_websocket(config),
-    }
-    .build();
+    let litep2p_config = add_transport(litep2p_config, transport).build();
 
     (Litep2p::new(litep2p_config).unwrap(), ping_event_stream)
 }
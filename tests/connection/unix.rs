@@ -0,0 +1,113 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use litep2p::{
+    config::ConfigBuilder,
+    crypto::ed25519::Keypair,
+    protocol::libp2p::ping::{Config as PingConfig, PingEvent},
+    transport::unix::config::Config as UnixConfig,
+    Litep2p, Litep2pEvent,
+};
+
+use futures::{Stream, StreamExt};
+use multiaddr::Multiaddr;
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+fn socket_path() -> PathBuf {
+    std::env::temp_dir().join(format!("litep2p-{}.sock", rand::random::<u64>()))
+}
+
+fn make_litep2p(
+    listen_addresses: Vec<Multiaddr>,
+) -> (Litep2p, Box<dyn Stream<Item = PingEvent> + Send + Unpin>) {
+    let (ping_config, ping_event_stream) = PingConfig::default();
+    let litep2p = Litep2p::new(
+        ConfigBuilder::new()
+            .with_keypair(Keypair::generate())
+            .with_libp2p_ping(ping_config)
+            .with_unix(UnixConfig {
+                listen_addresses,
+                ..Default::default()
+            })
+            .build(),
+    )
+    .unwrap();
+
+    (litep2p, ping_event_stream)
+}
+
+fn unix_address(path: &Path) -> Multiaddr {
+    format!("/unix/{}", path.to_str().unwrap().replace('/', "%2F")).parse().unwrap()
+}
+
+#[tokio::test]
+async fn two_litep2ps_work_unix() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let path = socket_path();
+    let (mut litep2p1, mut ping_event_stream1) = make_litep2p(Vec::new());
+    let (mut litep2p2, _ping_event_stream2) = make_litep2p(vec![unix_address(&path)]);
+    let peer2 = *litep2p2.local_peer_id();
+
+    let address = litep2p2.listen_addresses().next().unwrap().clone();
+    litep2p1.dial_address(address).await.unwrap();
+
+    let (res1, res2) = tokio::join!(litep2p1.next_event(), litep2p2.next_event());
+    match res1 {
+        Some(Litep2pEvent::ConnectionEstablished { peer, .. }) => assert_eq!(peer, peer2),
+        event => panic!("invalid event: {event:?}"),
+    }
+    assert!(std::matches!(
+        res2,
+        Some(Litep2pEvent::ConnectionEstablished { .. })
+    ));
+
+    tokio::spawn(async move {
+        loop {
+            let _ = litep2p1.next_event().await;
+        }
+    });
+    tokio::spawn(async move {
+        loop {
+            let _ = litep2p2.next_event().await;
+        }
+    });
+
+    match tokio::time::timeout(Duration::from_secs(10), ping_event_stream1.next()).await {
+        Ok(Some(PingEvent::Ping { peer, .. })) => assert_eq!(peer, peer2),
+        event => panic!("invalid event: {event:?}"),
+    }
+}
+
+#[tokio::test]
+async fn socket_file_removed_when_litep2p_is_dropped() {
+    let path = socket_path();
+    let (litep2p, _ping_event_stream) = make_litep2p(vec![unix_address(&path)]);
+    assert!(path.exists());
+
+    drop(litep2p);
+    assert!(!path.exists());
+}
@@ -0,0 +1,221 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use litep2p::{
+    config::{ConfigBuilder, Litep2pConfig},
+    crypto::ed25519::Keypair,
+    error::Error,
+    protocol::libp2p::ping::Config as PingConfig,
+    transport::websocket::config::{Config as WebSocketConfig, TlsConfig},
+    Litep2p, Litep2pEvent,
+};
+
+use multiaddr::{Multiaddr, Protocol};
+use rcgen::{BasicConstraints, Certificate, CertificateParams, DnType, IsCa, SanType};
+
+use std::{
+    borrow::Cow,
+    net::{IpAddr, Ipv4Addr},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Get path to a new temporary file.
+fn temp_path() -> PathBuf {
+    std::env::temp_dir().join(format!("litep2p-{}.pem", rand::random::<u64>()))
+}
+
+/// Generate self-signed certificate authority and write its certificate into a temporary file.
+fn certificate_authority() -> (Certificate, PathBuf) {
+    let mut params = CertificateParams::new(Vec::new());
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params.distinguished_name.push(DnType::CommonName, "litep2p test ca");
+
+    let certificate = Certificate::from_params(params).unwrap();
+    let path = temp_path();
+    std::fs::write(&path, certificate.serialize_pem().unwrap()).unwrap();
+
+    (certificate, path)
+}
+
+/// Issue certificate for `127.0.0.1`, signed by `authority`, and write it and its private key
+/// into `certificate_chain` and `private_key`.
+fn issue_certificate(authority: &Certificate, certificate_chain: &Path, private_key: &Path) {
+    let mut params = CertificateParams::new(Vec::new());
+    params.subject_alt_names = vec![SanType::IpAddress(IpAddr::V4(Ipv4Addr::LOCALHOST))];
+    params.distinguished_name.push(DnType::CommonName, "litep2p test node");
+
+    let certificate = Certificate::from_params(params).unwrap();
+    std::fs::write(
+        certificate_chain,
+        certificate.serialize_pem_with_signer(authority).unwrap(),
+    )
+    .unwrap();
+    std::fs::write(private_key, certificate.serialize_private_key_pem()).unwrap();
+}
+
+/// Create TLS configuration with a certificate signed by `authority`.
+fn tls_config(authority: &Certificate) -> TlsConfig {
+    let config = TlsConfig::new(temp_path(), temp_path());
+    issue_certificate(authority, &config.certificate_chain, &config.private_key);
+
+    config
+}
+
+fn make_config(config: WebSocketConfig) -> Litep2pConfig {
+    let (ping_config, _ping_event_stream) = PingConfig::default();
+
+    ConfigBuilder::new()
+        .with_keypair(Keypair::generate())
+        .with_libp2p_ping(ping_config)
+        .with_websocket(config)
+        .build()
+}
+
+/// Create listener which accepts secure WebSocket connections on `listen_address`.
+fn make_listener(listen_address: &str, tls_config: TlsConfig) -> Litep2p {
+    Litep2p::new(make_config(WebSocketConfig {
+        listen_addresses: vec![listen_address.parse().unwrap()],
+        tls_config: Some(tls_config),
+        ..Default::default()
+    }))
+    .unwrap()
+}
+
+/// Create dialer which trusts the certificate authority stored in `root_certificates`.
+fn make_dialer(root_certificates: Option<PathBuf>) -> Litep2p {
+    Litep2p::new(make_config(WebSocketConfig {
+        listen_addresses: vec!["/ip4/127.0.0.1/tcp/0/ws".parse().unwrap()],
+        tls_root_certificates: root_certificates,
+        ..Default::default()
+    }))
+    .unwrap()
+}
+
+/// Dial `address` and return `true` if the connection was established.
+async fn connect(dialer: &mut Litep2p, listener: &mut Litep2p, address: Multiaddr) -> bool {
+    dialer.dial_address(address).await.unwrap();
+
+    loop {
+        tokio::select! {
+            event = dialer.next_event() => match event {
+                Some(Litep2pEvent::ConnectionEstablished { .. }) => return true,
+                Some(Litep2pEvent::DialFailure { .. }) => return false,
+                _ => {}
+            },
+            _ = listener.next_event() => {}
+            _ = tokio::time::sleep(Duration::from_secs(10)) => panic!("dial timed out"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn two_litep2ps_work_secure_websocket() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (authority, root_certificates) = certificate_authority();
+    let mut listener = make_listener("/ip4/127.0.0.1/tcp/0/tls/ws", tls_config(&authority));
+    let mut dialer = make_dialer(Some(root_certificates));
+
+    let address = listener.listen_addresses().next().unwrap().clone();
+    assert!(address.iter().any(|protocol| protocol == Protocol::Tls));
+    assert!(connect(&mut dialer, &mut listener, address).await);
+}
+
+#[tokio::test]
+async fn wss_listen_address() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (authority, root_certificates) = certificate_authority();
+    let mut listener = make_listener("/ip4/127.0.0.1/tcp/0/wss", tls_config(&authority));
+    let mut dialer = make_dialer(Some(root_certificates));
+
+    // listen address is reported as `/tls/ws`, dial it using the equivalent `/wss` form
+    let address: Multiaddr = listener
+        .listen_addresses()
+        .next()
+        .unwrap()
+        .iter()
+        .filter_map(|protocol| match protocol {
+            Protocol::Tls => None,
+            Protocol::Ws(_) => Some(Protocol::Wss(Cow::Borrowed("/"))),
+            protocol => Some(protocol),
+        })
+        .collect();
+
+    assert!(connect(&mut dialer, &mut listener, address).await);
+}
+
+#[tokio::test]
+async fn untrusted_certificate_rejected() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (authority, _) = certificate_authority();
+    let mut listener = make_listener("/ip4/127.0.0.1/tcp/0/tls/ws", tls_config(&authority));
+    let mut dialer = make_dialer(None);
+
+    let address = listener.listen_addresses().next().unwrap().clone();
+    assert!(!connect(&mut dialer, &mut listener, address).await);
+}
+
+#[tokio::test]
+async fn certificate_reloaded() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (old_authority, _) = certificate_authority();
+    let (new_authority, root_certificates) = certificate_authority();
+    let tls_config = TlsConfig {
+        reload_interval: Some(Duration::from_millis(100)),
+        ..tls_config(&old_authority)
+    };
+    let certificate_chain = tls_config.certificate_chain.clone();
+    let private_key = tls_config.private_key.clone();
+
+    let mut listener = make_listener("/ip4/127.0.0.1/tcp/0/tls/ws", tls_config);
+    let mut dialer = make_dialer(Some(root_certificates));
+    let address = listener.listen_addresses().next().unwrap().clone();
+
+    // dialer only trusts the new certificate authority
+    assert!(!connect(&mut dialer, &mut listener, address.clone()).await);
+
+    issue_certificate(&new_authority, &certificate_chain, &private_key);
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    assert!(connect(&mut dialer, &mut listener, address).await);
+}
+
+#[tokio::test]
+async fn secure_listen_address_requires_tls_config() {
+    assert!(std::matches!(
+        Litep2p::new(make_config(WebSocketConfig {
+            listen_addresses: vec!["/ip4/127.0.0.1/tcp/0/tls/ws".parse().unwrap()],
+            ..Default::default()
+        })),
+        Err(Error::NotSupported(_))
+    ));
+}
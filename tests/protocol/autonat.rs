@@ -0,0 +1,201 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use futures::StreamExt;
+use litep2p::{
+    config::ConfigBuilder,
+    protocol::libp2p::autonat::{
+        AutoNatEvent, AutoNatHandle, ConfigBuilder as AutoNatConfigBuilder, Reachability,
+    },
+    transport::tcp::config::Config as TcpConfig,
+    Litep2p, Litep2pEvent,
+};
+use multiaddr::{Multiaddr, Protocol};
+
+use std::{collections::HashMap, time::Duration};
+
+fn create_client() -> (Litep2p, AutoNatHandle) {
+    let (autonat_config, handle) = AutoNatConfigBuilder::new()
+        .with_servers_per_probe(1)
+        .with_confirmations(1)
+        .build();
+    let config = ConfigBuilder::new()
+        .with_tcp(TcpConfig {
+            listen_addresses: vec!["/ip4/127.0.0.1/tcp/0".parse().unwrap()],
+            ..Default::default()
+        })
+        .with_libp2p_autonat(autonat_config)
+        .build();
+
+    (Litep2p::new(config).unwrap(), handle)
+}
+
+fn create_server() -> (Litep2p, AutoNatHandle) {
+    let (autonat_config, handle) =
+        AutoNatConfigBuilder::new().with_server().with_only_global_ips(false).build();
+    let config = ConfigBuilder::new()
+        .with_tcp(TcpConfig {
+            listen_addresses: vec!["/ip4/127.0.0.1/tcp/0".parse().unwrap()],
+            // the dial-back must not reuse the port of the connection opened by the client
+            reuse_port: false,
+            ..Default::default()
+        })
+        .with_libp2p_autonat(autonat_config)
+        .build();
+
+    (Litep2p::new(config).unwrap(), handle)
+}
+
+fn strip_peer_id(address: &Multiaddr) -> Multiaddr {
+    address
+        .iter()
+        .filter(|protocol| !matches!(protocol, Protocol::P2p(_)))
+        .collect()
+}
+
+/// Connect `client` to `server`, probe the addresses of `client` and wait until the reachability
+/// of `expected` addresses has been concluded.
+async fn probe(
+    client: &mut Litep2p,
+    client_handle: &mut AutoNatHandle,
+    mut server: Litep2p,
+    expected: usize,
+) -> HashMap<Multiaddr, Reachability> {
+    let address = server.listen_addresses().next().unwrap().clone();
+    tokio::spawn(async move { while server.next_event().await.is_some() {} });
+
+    client.dial_address(address).await.unwrap();
+
+    loop {
+        if let Some(Litep2pEvent::ConnectionEstablished { .. }) = client.next_event().await {
+            break;
+        }
+    }
+
+    client_handle.probe().await;
+
+    let mut results = HashMap::new();
+    tokio::time::timeout(Duration::from_secs(20), async {
+        while results.len() < expected {
+            tokio::select! {
+                _ = client.next_event() => {}
+                event = client_handle.next() => match event.unwrap() {
+                    AutoNatEvent::ReachabilityChanged { address, reachability } => {
+                        results.insert(address, reachability);
+                    }
+                    AutoNatEvent::DialResult { .. } => {}
+                },
+            }
+        }
+    })
+    .await
+    .expect("reachability to be concluded");
+
+    results
+}
+
+#[tokio::test]
+async fn listen_address_is_public() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (mut client, mut client_handle) = create_client();
+    let (server, _server_handle) = create_server();
+    let listen_address = strip_peer_id(client.listen_addresses().next().unwrap());
+
+    let results = probe(&mut client, &mut client_handle, server, 1).await;
+
+    assert_eq!(results.get(&listen_address), Some(&Reachability::Public));
+    assert_eq!(
+        client_handle.reachability(&listen_address),
+        Reachability::Public
+    );
+    assert!(client
+        .public_addresses()
+        .get_addresses()
+        .iter()
+        .any(|address| strip_peer_id(address) == listen_address));
+}
+
+#[tokio::test]
+async fn unreachable_address_is_private() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (mut client, mut client_handle) = create_client();
+    let (server, _server_handle) = create_server();
+    let listen_address = strip_peer_id(client.listen_addresses().next().unwrap());
+
+    // nothing listens on the address and its IP differs from the observed address of the client
+    // so the server requests dial data before dialing it
+    let unreachable: Multiaddr = "/ip4/127.0.0.2/tcp/1".parse().unwrap();
+    client.public_addresses().add_address(unreachable.clone()).unwrap();
+
+    let results = probe(&mut client, &mut client_handle, server, 2).await;
+
+    assert_eq!(results.get(&listen_address), Some(&Reachability::Public));
+    assert_eq!(results.get(&unreachable), Some(&Reachability::Private));
+    assert_eq!(
+        client_handle.reachability(&unreachable),
+        Reachability::Private
+    );
+    assert!(!client
+        .public_addresses()
+        .get_addresses()
+        .iter()
+        .any(|address| strip_peer_id(address) == unreachable));
+}
+
+#[tokio::test]
+async fn server_disabled() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (mut client, mut client_handle) = create_client();
+    let (mut peer, _peer_handle) = create_client();
+    let address = peer.listen_addresses().next().unwrap().clone();
+    tokio::spawn(async move { while peer.next_event().await.is_some() {} });
+
+    client.dial_address(address).await.unwrap();
+
+    loop {
+        if let Some(Litep2pEvent::ConnectionEstablished { .. }) = client.next_event().await {
+            break;
+        }
+    }
+
+    client_handle.probe().await;
+
+    loop {
+        tokio::select! {
+            _ = client.next_event() => {}
+            event = client_handle.next() => match event.unwrap() {
+                AutoNatEvent::DialResult { reachability, .. } => {
+                    assert_eq!(reachability, Reachability::Unknown);
+                    break;
+                }
+                event => panic!("unexpected event: {event:?}"),
+            },
+        }
+    }
+}
@@ -0,0 +1,237 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use futures::{Stream, StreamExt};
+use litep2p::{
+    config::ConfigBuilder,
+    error::DcutrError,
+    protocol::libp2p::{
+        dcutr::{ConfigBuilder as DcutrConfigBuilder, DcutrEvent},
+        ping::ConfigBuilder as PingConfigBuilder,
+        relay::{ConfigBuilder as RelayConfigBuilder, RelayEvent, RelayHandle},
+    },
+    transport::tcp::config::Config as TcpConfig,
+    Litep2p, Litep2pEvent,
+};
+use multiaddr::{Multiaddr, Protocol};
+
+use std::{pin::Pin, time::Duration};
+
+type DcutrEvents = Box<dyn Stream<Item = DcutrEvent> + Send + Unpin>;
+
+fn tcp_config() -> TcpConfig {
+    TcpConfig {
+        listen_addresses: vec!["/ip4/127.0.0.1/tcp/0".parse().unwrap()],
+        ..Default::default()
+    }
+}
+
+/// Start a relay and return its address along with its event stream.
+fn start_relay() -> (Multiaddr, RelayHandle) {
+    let (relay_config, handle) = RelayConfigBuilder::new().with_server().build();
+    let mut relay = Litep2p::new(
+        ConfigBuilder::new()
+            .with_tcp(tcp_config())
+            .with_libp2p_relay(relay_config)
+            .build(),
+    )
+    .unwrap();
+    let address = relay.listen_addresses().next().unwrap().clone();
+
+    tokio::spawn(async move { while relay.next_event().await.is_some() {} });
+
+    (address, handle)
+}
+
+/// Create relay client, with DCUtR enabled if `dcutr` is `true`.
+fn create_client(dcutr: bool) -> (Litep2p, RelayHandle, Option<DcutrEvents>) {
+    let (relay_config, handle) = RelayConfigBuilder::new().build();
+    let (ping_config, _ping_events) = PingConfigBuilder::new().build();
+    let config = ConfigBuilder::new()
+        .with_tcp(tcp_config())
+        .with_libp2p_relay(relay_config)
+        .with_libp2p_ping(ping_config);
+
+    let (config, events) = match dcutr {
+        true => {
+            let (dcutr_config, events) = DcutrConfigBuilder::new().build();
+            (config.with_libp2p_dcutr(dcutr_config), Some(events))
+        }
+        false => (config, None),
+    };
+
+    (Litep2p::new(config.build()).unwrap(), handle, events)
+}
+
+/// Make a reservation with the relay at `address` and return the circuit address of `client`.
+async fn reserve(client: &mut Litep2p, handle: &mut RelayHandle, address: Multiaddr) -> Multiaddr {
+    handle.reserve(address).await.unwrap();
+
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            tokio::select! {
+                _ = client.next_event() => {}
+                event = handle.next() => match event.unwrap() {
+                    RelayEvent::ReservationAccepted { mut addresses, .. } =>
+                        return addresses.pop().unwrap(),
+                    event => panic!("unexpected event: {event:?}"),
+                },
+            }
+        }
+    })
+    .await
+    .expect("reservation to be accepted")
+}
+
+async fn next_dcutr_event(events: &mut Option<DcutrEvents>) -> DcutrEvent {
+    match events.as_mut() {
+        Some(events) => Pin::new(events).next().await.unwrap(),
+        None => futures::future::pending().await,
+    }
+}
+
+#[tokio::test]
+async fn relayed_connection_upgraded() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (relay_address, mut relay_handle) = start_relay();
+
+    let (mut litep2p1, _handle1, mut dcutr1) = create_client(true);
+    let (mut litep2p2, mut handle2, mut dcutr2) = create_client(true);
+    let peer1 = *litep2p1.local_peer_id();
+    let peer2 = *litep2p2.local_peer_id();
+
+    let address = reserve(&mut litep2p2, &mut handle2, relay_address).await;
+    litep2p1.dial_address(address).await.unwrap();
+
+    let mut upgraded1 = false;
+    let mut upgraded2 = false;
+    let mut direct1 = false;
+    let mut direct2 = false;
+
+    tokio::time::timeout(Duration::from_secs(30), async {
+        while !upgraded1 || !upgraded2 || !direct1 || !direct2 {
+            tokio::select! {
+                event = litep2p1.next_event() => match event.unwrap() {
+                    Litep2pEvent::ConnectionEstablished { peer, endpoint }
+                        if peer == peer2 && !endpoint.is_relayed() => direct1 = true,
+                    Litep2pEvent::ConnectionClosed { peer, .. } if peer == peer2 =>
+                        panic!("connection closed"),
+                    _ => {}
+                },
+                event = litep2p2.next_event() => match event.unwrap() {
+                    Litep2pEvent::ConnectionEstablished { peer, endpoint }
+                        if peer == peer1 && !endpoint.is_relayed() => direct2 = true,
+                    Litep2pEvent::ConnectionClosed { peer, .. } if peer == peer1 =>
+                        panic!("connection closed"),
+                    _ => {}
+                },
+                _ = handle2.next() => {}
+                event = next_dcutr_event(&mut dcutr1) => match event {
+                    DcutrEvent::ConnectionUpgraded { peer, address } => {
+                        assert_eq!(peer, peer2);
+                        assert!(!address.iter().any(|protocol| protocol == Protocol::P2pCircuit));
+                        upgraded1 = true;
+                    }
+                    event => panic!("unexpected event: {event:?}"),
+                },
+                event = next_dcutr_event(&mut dcutr2) => match event {
+                    DcutrEvent::ConnectionUpgraded { peer, address } => {
+                        assert_eq!(peer, peer1);
+                        assert!(!address.iter().any(|protocol| protocol == Protocol::P2pCircuit));
+                        upgraded2 = true;
+                    }
+                    event => panic!("unexpected event: {event:?}"),
+                },
+            }
+        }
+    })
+    .await
+    .expect("relayed connection to be upgraded");
+
+    // the idle relayed connection is closed since the direct connection replaced it while the
+    // peers stay connected over the direct connection
+    tokio::time::timeout(Duration::from_secs(20), async {
+        loop {
+            tokio::select! {
+                event = litep2p1.next_event() => match event.unwrap() {
+                    Litep2pEvent::ConnectionClosed { peer, .. } if peer == peer2 =>
+                        panic!("direct connection closed"),
+                    _ => {}
+                },
+                event = litep2p2.next_event() => match event.unwrap() {
+                    Litep2pEvent::ConnectionClosed { peer, .. } if peer == peer1 =>
+                        panic!("direct connection closed"),
+                    _ => {}
+                },
+                _ = handle2.next() => {}
+                event = relay_handle.next() => {
+                    if let RelayEvent::CircuitClosed { source, destination } = event.unwrap() {
+                        assert_eq!(source, peer1);
+                        assert_eq!(destination, peer2);
+                        break;
+                    }
+                }
+            }
+        }
+    })
+    .await
+    .expect("relayed connection to be closed");
+}
+
+#[tokio::test]
+async fn upgrade_fails_if_remote_does_not_support_dcutr() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (relay_address, _relay_handle) = start_relay();
+
+    let (mut litep2p1, _handle1, _) = create_client(false);
+    let (mut litep2p2, mut handle2, mut dcutr2) = create_client(true);
+    let peer1 = *litep2p1.local_peer_id();
+
+    let address = reserve(&mut litep2p2, &mut handle2, relay_address).await;
+    litep2p1.dial_address(address).await.unwrap();
+
+    tokio::time::timeout(Duration::from_secs(20), async {
+        loop {
+            tokio::select! {
+                _ = litep2p1.next_event() => {}
+                _ = litep2p2.next_event() => {}
+                _ = handle2.next() => {}
+                event = next_dcutr_event(&mut dcutr2) => {
+                    assert_eq!(
+                        event,
+                        DcutrEvent::UpgradeFailed {
+                            peer: peer1,
+                            error: DcutrError::SubstreamClosed,
+                        }
+                    );
+                    break;
+                }
+            }
+        }
+    })
+    .await
+    .expect("upgrade to fail");
+}
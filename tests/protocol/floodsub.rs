@@ -0,0 +1,194 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use futures::StreamExt;
+use litep2p::{
+    config::ConfigBuilder,
+    protocol::libp2p::floodsub::{Config as FloodsubConfig, PubsubEvent, PubsubHandle},
+    Litep2p, PeerId,
+};
+
+use crate::common::{add_transport, Transport};
+
+use std::{collections::HashSet, time::Duration};
+
+fn initialize_litep2p(transport: Transport) -> (Litep2p, PubsubHandle) {
+    let (floodsub_config, handle) = FloodsubConfig::default();
+    let config = ConfigBuilder::new().with_libp2p_floodsub(floodsub_config);
+
+    (
+        Litep2p::new(add_transport(config, transport).build()).unwrap(),
+        handle,
+    )
+}
+
+/// Drive `litep2p` until it's closed.
+fn spawn_litep2p(mut litep2p: Litep2p) {
+    tokio::spawn(async move { while litep2p.next_event().await.is_some() {} });
+}
+
+/// Wait until `handle` reports the subscription change of each peer in `peers`.
+async fn wait_subscriptions(handle: &mut PubsubHandle, peers: &[PeerId], subscribed: bool) {
+    let mut pending = peers.iter().copied().collect::<HashSet<_>>();
+
+    while !pending.is_empty() {
+        match handle.next().await.unwrap() {
+            PubsubEvent::Subscribed { peer, .. } if subscribed => {
+                pending.remove(&peer);
+            }
+            PubsubEvent::Unsubscribed { peer, .. } if !subscribed => {
+                pending.remove(&peer);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[tokio::test]
+async fn message_flooded_to_subscribed_peers_tcp() {
+    message_flooded_to_subscribed_peers(
+        Transport::Tcp(Default::default()),
+        Transport::Tcp(Default::default()),
+        Transport::Tcp(Default::default()),
+    )
+    .await;
+}
+
+#[cfg(feature = "websocket")]
+#[tokio::test]
+async fn message_flooded_to_subscribed_peers_websocket() {
+    message_flooded_to_subscribed_peers(
+        Transport::WebSocket(Default::default()),
+        Transport::WebSocket(Default::default()),
+        Transport::WebSocket(Default::default()),
+    )
+    .await;
+}
+
+#[cfg(feature = "quic")]
+#[tokio::test]
+async fn message_flooded_to_subscribed_peers_quic() {
+    message_flooded_to_subscribed_peers(
+        Transport::Quic(Default::default()),
+        Transport::Quic(Default::default()),
+        Transport::Quic(Default::default()),
+    )
+    .await;
+}
+
+async fn message_flooded_to_subscribed_peers(
+    transport1: Transport,
+    transport2: Transport,
+    transport3: Transport,
+) {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    // litep2p1 <-> litep2p2 <-> litep2p3, litep2p2 forwards the message to litep2p3
+    let (mut litep2p1, mut handle1) = initialize_litep2p(transport1);
+    let (litep2p2, mut handle2) = initialize_litep2p(transport2);
+    let (mut litep2p3, mut handle3) = initialize_litep2p(transport3);
+    let peer1 = *litep2p1.local_peer_id();
+    let peer2 = *litep2p2.local_peer_id();
+    let peer3 = *litep2p3.local_peer_id();
+    let address = litep2p2.listen_addresses().next().unwrap().clone();
+
+    for handle in [&handle1, &handle2, &handle3] {
+        handle.subscribe("topic").await;
+    }
+
+    litep2p1.dial_address(address.clone()).await.unwrap();
+    litep2p3.dial_address(address).await.unwrap();
+    spawn_litep2p(litep2p1);
+    spawn_litep2p(litep2p2);
+    spawn_litep2p(litep2p3);
+
+    tokio::time::timeout(Duration::from_secs(10), async {
+        wait_subscriptions(&mut handle1, &[peer2], true).await;
+        wait_subscriptions(&mut handle3, &[peer2], true).await;
+        wait_subscriptions(&mut handle2, &[peer1, peer3], true).await;
+
+        handle1.publish("topic", vec![1, 3, 3, 7]).await;
+
+        for handle in [&mut handle2, &mut handle3] {
+            loop {
+                if let PubsubEvent::Message {
+                    topic,
+                    source,
+                    data,
+                } = handle.next().await.unwrap()
+                {
+                    assert_eq!(topic, "topic");
+                    assert_eq!(source, peer1);
+                    assert_eq!(data, vec![1, 3, 3, 7]);
+                    break;
+                }
+            }
+        }
+    })
+    .await
+    .expect("message to be flooded");
+}
+
+#[tokio::test]
+async fn unsubscribed_peer_does_not_receive_messages() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (mut litep2p1, mut handle1) = initialize_litep2p(Transport::Tcp(Default::default()));
+    let (litep2p2, mut handle2) = initialize_litep2p(Transport::Tcp(Default::default()));
+    let peer1 = *litep2p1.local_peer_id();
+    let peer2 = *litep2p2.local_peer_id();
+    let address = litep2p2.listen_addresses().next().unwrap().clone();
+
+    handle1.subscribe("topic").await;
+    handle2.subscribe("topic").await;
+
+    litep2p1.dial_address(address).await.unwrap();
+    spawn_litep2p(litep2p1);
+    spawn_litep2p(litep2p2);
+
+    tokio::time::timeout(Duration::from_secs(10), async {
+        wait_subscriptions(&mut handle1, &[peer2], true).await;
+        wait_subscriptions(&mut handle2, &[peer1], true).await;
+
+        handle2.unsubscribe("topic").await;
+        wait_subscriptions(&mut handle1, &[peer2], false).await;
+
+        // the message isn't sent to `litep2p2` which is no longer subscribed
+        handle1.publish("topic", vec![1]).await;
+
+        // subscribe again and verify that only the second message is received
+        handle2.subscribe("topic").await;
+        wait_subscriptions(&mut handle1, &[peer2], true).await;
+        handle1.publish("topic", vec![2]).await;
+
+        loop {
+            if let PubsubEvent::Message { data, .. } = handle2.next().await.unwrap() {
+                assert_eq!(data, vec![2]);
+                break;
+            }
+        }
+    })
+    .await
+    .expect("message to be received");
+}
@@ -0,0 +1,203 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use futures::StreamExt;
+use litep2p::{
+    config::ConfigBuilder,
+    error::GossipsubError,
+    protocol::libp2p::gossipsub::{
+        ConfigBuilder as GossipsubConfigBuilder, GossipsubEvent, GossipsubHandle, TopicHash,
+    },
+    Litep2p, PeerId,
+};
+
+use crate::common::{add_transport, Transport};
+
+use std::{collections::HashSet, time::Duration};
+
+fn initialize_litep2p(transport: Transport, flood_publish: bool) -> (Litep2p, GossipsubHandle) {
+    let (gossipsub_config, handle) = GossipsubConfigBuilder::new()
+        .with_heartbeat_interval(Duration::from_millis(100))
+        .with_flood_publish(flood_publish)
+        .build();
+    let config = ConfigBuilder::new().with_libp2p_gossipsub(gossipsub_config);
+
+    (
+        Litep2p::new(add_transport(config, transport).build()).unwrap(),
+        handle,
+    )
+}
+
+/// Drive `litep2p` until it's closed.
+fn spawn_litep2p(mut litep2p: Litep2p) {
+    tokio::spawn(async move { while litep2p.next_event().await.is_some() {} });
+}
+
+/// Wait until `handle` reports the subscription change of each peer in `peers`.
+async fn wait_subscriptions(handle: &mut GossipsubHandle, peers: &[PeerId], subscribed: bool) {
+    let mut pending = peers.iter().copied().collect::<HashSet<_>>();
+
+    while !pending.is_empty() {
+        match handle.next().await.unwrap() {
+            GossipsubEvent::Subscribed { peer, .. } if subscribed => {
+                pending.remove(&peer);
+            }
+            GossipsubEvent::Unsubscribed { peer, .. } if !subscribed => {
+                pending.remove(&peer);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[tokio::test]
+async fn message_propagates_through_mesh_tcp() {
+    message_propagates_through_mesh(
+        Transport::Tcp(Default::default()),
+        Transport::Tcp(Default::default()),
+        Transport::Tcp(Default::default()),
+    )
+    .await;
+}
+
+#[cfg(feature = "websocket")]
+#[tokio::test]
+async fn message_propagates_through_mesh_websocket() {
+    message_propagates_through_mesh(
+        Transport::WebSocket(Default::default()),
+        Transport::WebSocket(Default::default()),
+        Transport::WebSocket(Default::default()),
+    )
+    .await;
+}
+
+#[cfg(feature = "quic")]
+#[tokio::test]
+async fn message_propagates_through_mesh_quic() {
+    message_propagates_through_mesh(
+        Transport::Quic(Default::default()),
+        Transport::Quic(Default::default()),
+        Transport::Quic(Default::default()),
+    )
+    .await;
+}
+
+async fn message_propagates_through_mesh(
+    transport1: Transport,
+    transport2: Transport,
+    transport3: Transport,
+) {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    // litep2p1 <-> litep2p2 <-> litep2p3, where only mesh peers receive forwarded messages
+    let (mut litep2p1, mut handle1) = initialize_litep2p(transport1, false);
+    let (litep2p2, mut handle2) = initialize_litep2p(transport2, false);
+    let (mut litep2p3, mut handle3) = initialize_litep2p(transport3, false);
+    let peer1 = *litep2p1.local_peer_id();
+    let peer2 = *litep2p2.local_peer_id();
+    let peer3 = *litep2p3.local_peer_id();
+    let address = litep2p2.listen_addresses().next().unwrap().clone();
+    let topic = TopicHash::from("topic");
+
+    for handle in [&handle1, &handle2, &handle3] {
+        handle.subscribe(topic.clone()).await;
+    }
+
+    litep2p1.dial_address(address.clone()).await.unwrap();
+    litep2p3.dial_address(address).await.unwrap();
+    spawn_litep2p(litep2p1);
+    spawn_litep2p(litep2p2);
+    spawn_litep2p(litep2p3);
+
+    tokio::time::timeout(Duration::from_secs(10), async {
+        wait_subscriptions(&mut handle1, &[peer2], true).await;
+        wait_subscriptions(&mut handle3, &[peer2], true).await;
+        wait_subscriptions(&mut handle2, &[peer1, peer3], true).await;
+
+        let message_id = handle1.publish(topic.clone(), vec![1, 3, 3, 7]).await.unwrap();
+
+        loop {
+            if let GossipsubEvent::Message {
+                propagation_source,
+                message_id: received_id,
+                message,
+            } = handle3.next().await.unwrap()
+            {
+                assert_eq!(propagation_source, peer2);
+                assert_eq!(received_id, message_id);
+                assert_eq!(message.source, peer1);
+                assert_eq!(message.topic, topic);
+                assert_eq!(message.data, vec![1, 3, 3, 7]);
+                break;
+            }
+        }
+    })
+    .await
+    .expect("message to propagate in time");
+}
+
+#[tokio::test]
+async fn publish_requires_subscribed_peers() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (mut litep2p1, mut handle1) = initialize_litep2p(Transport::Tcp(Default::default()), true);
+    let (litep2p2, mut handle2) = initialize_litep2p(Transport::Tcp(Default::default()), true);
+    let peer2 = *litep2p2.local_peer_id();
+    let topic = TopicHash::from("topic");
+
+    litep2p1
+        .dial_address(litep2p2.listen_addresses().next().unwrap().clone())
+        .await
+        .unwrap();
+    spawn_litep2p(litep2p1);
+    spawn_litep2p(litep2p2);
+
+    assert_eq!(
+        handle1.publish(topic.clone(), vec![1]).await,
+        Err(GossipsubError::InsufficientPeers)
+    );
+
+    tokio::time::timeout(Duration::from_secs(10), async {
+        handle2.subscribe(topic.clone()).await;
+        wait_subscriptions(&mut handle1, &[peer2], true).await;
+
+        handle1.publish(topic.clone(), vec![2]).await.unwrap();
+        loop {
+            if let GossipsubEvent::Message { message, .. } = handle2.next().await.unwrap() {
+                assert_eq!(message.data, vec![2]);
+                break;
+            }
+        }
+
+        handle2.unsubscribe(topic.clone()).await;
+        wait_subscriptions(&mut handle1, &[peer2], false).await;
+    })
+    .await
+    .expect("subscriptions to change in time");
+
+    assert_eq!(
+        handle1.publish(topic, vec![3]).await,
+        Err(GossipsubError::InsufficientPeers)
+    );
+}
@@ -18,6 +18,14 @@
 // FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
 // DEALINGS IN THE SOFTWARE.
 
+#[cfg(test)]
+mod autonat;
+#[cfg(test)]
+mod dcutr;
+#[cfg(test)]
+mod floodsub;
+#[cfg(test)]
+mod gossipsub;
 #[cfg(test)]
 mod identify;
 #[cfg(test)]
@@ -25,6 +33,12 @@ mod kademlia;
 #[cfg(test)]
 mod notification;
 #[cfg(test)]
+mod perf;
+#[cfg(test)]
 mod ping;
 #[cfg(test)]
+mod relay;
+#[cfg(test)]
+mod rendezvous;
+#[cfg(test)]
 mod request_response;
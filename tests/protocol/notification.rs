@@ -827,6 +827,9 @@ async fn set_new_handshake(transport1: Transport, transport2: Transport) {
         Transport::Quic(config) => config1.with_quic(config),
         #[cfg(feature = "websocket")]
         Transport::WebSocket(config) => config1.with_websocket(config),
+        #[cfg(feature = "webrtc")]
+        Transport::WebRtc(config) => config1.with_webrtc(config),
+        Transport::Memory(config) => config1.with_memory(config),
     }
     .build();
 
@@ -3439,6 +3442,7 @@ async fn dial_failure(transport1: Transport, transport2: Transport) {
             .with(Protocol::Ip6(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1)))
             .with(Protocol::Tcp(5))
             .with(Protocol::Ws(std::borrow::Cow::Owned("/".to_string()))),
+        _ => unimplemented!("transport not supported by the test"),
     };
 
     let config2 = add_transport(config2, transport2).build();
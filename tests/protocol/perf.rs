@@ -0,0 +1,212 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use futures::StreamExt;
+use litep2p::{
+    config::ConfigBuilder,
+    error::PerfError,
+    protocol::libp2p::perf::{
+        ConfigBuilder as PerfConfigBuilder, PerfEvent, PerfHandle, RunParams,
+    },
+    Litep2p, Litep2pEvent, PeerId,
+};
+
+use crate::common::{add_transport, Transport};
+
+use std::time::Duration;
+
+fn initialize_litep2p(transport: Transport, server: bool) -> (Litep2p, PerfHandle) {
+    let (perf_config, handle) = PerfConfigBuilder::new()
+        .with_server(server)
+        .with_progress_interval(Duration::from_millis(1))
+        .build();
+    let config = ConfigBuilder::new().with_libp2p_perf(perf_config);
+
+    (
+        Litep2p::new(add_transport(config, transport).build()).unwrap(),
+        handle,
+    )
+}
+
+/// Connect `litep2p1` to `litep2p2` and drive both in the background.
+async fn connect(mut litep2p1: Litep2p, mut litep2p2: Litep2p) {
+    let address = litep2p2.listen_addresses().next().unwrap().clone();
+    let peer2 = *litep2p2.local_peer_id();
+    litep2p1.dial_address(address).await.unwrap();
+
+    loop {
+        tokio::select! {
+            event = litep2p1.next_event() => if let Some(Litep2pEvent::ConnectionEstablished { peer, .. }) = event {
+                if peer == peer2 {
+                    break;
+                }
+            },
+            _ = litep2p2.next_event() => {}
+        }
+    }
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = litep2p1.next_event() => {}
+                _ = litep2p2.next_event() => {}
+            }
+        }
+    });
+}
+
+#[tokio::test]
+async fn perf_run_tcp() {
+    perf_run(
+        Transport::Tcp(Default::default()),
+        Transport::Tcp(Default::default()),
+    )
+    .await;
+}
+
+#[cfg(feature = "websocket")]
+#[tokio::test]
+async fn perf_run_websocket() {
+    perf_run(
+        Transport::WebSocket(Default::default()),
+        Transport::WebSocket(Default::default()),
+    )
+    .await;
+}
+
+#[cfg(feature = "quic")]
+#[tokio::test]
+async fn perf_run_quic() {
+    perf_run(
+        Transport::Quic(Default::default()),
+        Transport::Quic(Default::default()),
+    )
+    .await;
+}
+
+#[cfg(feature = "webrtc")]
+#[tokio::test]
+#[ignore = "str0m 0.6 rejects the DTLS certificate of a litep2p dialer on the listener side"]
+async fn perf_run_webrtc() {
+    use litep2p::transport::webrtc::config::Config as WebRtcConfig;
+
+    perf_run(
+        Transport::WebRtc(WebRtcConfig {
+            listen_addresses: vec!["/ip4/127.0.0.1/udp/0/webrtc-direct".parse().unwrap()],
+            ..Default::default()
+        }),
+        Transport::WebRtc(WebRtcConfig {
+            listen_addresses: vec!["/ip4/127.0.0.1/udp/0/webrtc-direct".parse().unwrap()],
+            ..Default::default()
+        }),
+    )
+    .await;
+}
+
+async fn perf_run(transport1: Transport, transport2: Transport) {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (litep2p1, mut handle1) = initialize_litep2p(transport1, false);
+    let (litep2p2, _handle2) = initialize_litep2p(transport2, true);
+    let peer2 = *litep2p2.local_peer_id();
+    connect(litep2p1, litep2p2).await;
+
+    let params = RunParams {
+        to_send: 4 * 1024 * 1024,
+        to_receive: 8 * 1024 * 1024,
+    };
+    let run = handle1.run(peer2, params).await;
+    let mut progress_reported = false;
+
+    tokio::time::timeout(Duration::from_secs(20), async {
+        loop {
+            match handle1.next().await.unwrap() {
+                PerfEvent::Progress {
+                    run: progress_run,
+                    peer,
+                    progress,
+                } => {
+                    assert_eq!(progress_run, run);
+                    assert_eq!(peer, peer2);
+                    assert!(progress.sent <= params.to_send);
+                    assert!(progress.received <= params.to_receive);
+                    progress_reported = true;
+                }
+                PerfEvent::Finished {
+                    run: finished_run,
+                    peer,
+                    stats,
+                } => {
+                    assert_eq!(finished_run, run);
+                    assert_eq!(peer, peer2);
+                    assert_eq!(stats.sent, params.to_send);
+                    assert_eq!(stats.received, params.to_receive);
+                    break;
+                }
+                event => panic!("unexpected event: {event:?}"),
+            }
+        }
+    })
+    .await
+    .expect("perf run to finish");
+
+    assert!(progress_reported);
+}
+
+#[tokio::test]
+async fn perf_run_fails_if_server_disabled() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (litep2p1, mut handle1) = initialize_litep2p(Transport::Tcp(Default::default()), false);
+    let (litep2p2, _handle2) = initialize_litep2p(Transport::Tcp(Default::default()), false);
+    let peer2 = *litep2p2.local_peer_id();
+    connect(litep2p1, litep2p2).await;
+
+    let params = RunParams {
+        to_send: 1024,
+        to_receive: 1024,
+    };
+    handle1.run(peer2, params).await;
+
+    match tokio::time::timeout(Duration::from_secs(10), handle1.next())
+        .await
+        .unwrap()
+        .unwrap()
+    {
+        PerfEvent::Failed { peer, .. } => assert_eq!(peer, peer2),
+        event => panic!("unexpected event: {event:?}"),
+    }
+
+    // no known address for the peer
+    handle1.run(PeerId::random(), params).await;
+
+    match tokio::time::timeout(Duration::from_secs(10), handle1.next())
+        .await
+        .unwrap()
+        .unwrap()
+    {
+        PerfEvent::Failed { error, .. } => assert_eq!(error, PerfError::DialFailure),
+        event => panic!("unexpected event: {event:?}"),
+    }
+}
@@ -20,11 +20,15 @@
 
 use futures::StreamExt;
 use litep2p::{
-    config::ConfigBuilder, protocol::libp2p::ping::ConfigBuilder as PingConfigBuilder, Litep2p,
+    config::ConfigBuilder,
+    protocol::libp2p::ping::{ConfigBuilder as PingConfigBuilder, PingEvent},
+    Litep2p,
 };
 
 use crate::common::{add_transport, Transport};
 
+use std::time::Duration;
+
 #[tokio::test]
 async fn ping_supported_tcp() {
     ping_supported(
@@ -54,6 +58,25 @@ async fn ping_supported_quic() {
     .await;
 }
 
+#[cfg(feature = "webrtc")]
+#[tokio::test]
+#[ignore = "str0m 0.6 rejects the DTLS certificate of a litep2p dialer on the listener side"]
+async fn ping_supported_webrtc() {
+    use litep2p::transport::webrtc::config::Config as WebRtcConfig;
+
+    ping_supported(
+        Transport::WebRtc(WebRtcConfig {
+            listen_addresses: vec!["/ip4/127.0.0.1/udp/0/webrtc-direct".parse().unwrap()],
+            ..Default::default()
+        }),
+        Transport::WebRtc(WebRtcConfig {
+            listen_addresses: vec!["/ip4/127.0.0.1/udp/0/webrtc-direct".parse().unwrap()],
+            ..Default::default()
+        }),
+    )
+    .await;
+}
+
 async fn ping_supported(transport1: Transport, transport2: Transport) {
     let _ = tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
@@ -100,3 +123,61 @@ async fn ping_supported(transport1: Transport, transport2: Transport) {
         }
     }
 }
+
+#[tokio::test]
+async fn ping_latency_statistics_tcp() {
+    ping_latency_statistics(
+        Transport::Tcp(Default::default()),
+        Transport::Tcp(Default::default()),
+    )
+    .await;
+}
+
+async fn ping_latency_statistics(transport1: Transport, transport2: Transport) {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (ping_config1, mut ping_event_stream1) = PingConfigBuilder::new()
+        .with_ping_interval(Duration::from_millis(200))
+        .with_latency_window(4)
+        .build();
+    let ping_handle1 = ping_config1.handle();
+    let config1 = ConfigBuilder::new().with_libp2p_ping(ping_config1);
+    let config1 = add_transport(config1, transport1).build();
+
+    let (ping_config2, _ping_event_stream2) = PingConfigBuilder::new().build();
+    let config2 = ConfigBuilder::new().with_libp2p_ping(ping_config2);
+    let config2 = add_transport(config2, transport2).build();
+
+    let mut litep2p1 = Litep2p::new(config1).unwrap();
+    let mut litep2p2 = Litep2p::new(config2).unwrap();
+    let peer2 = *litep2p2.local_peer_id();
+    let address = litep2p2.listen_addresses().next().unwrap().clone();
+
+    litep2p1.dial_address(address).await.unwrap();
+
+    let mut pings = 0usize;
+
+    while pings < 6 {
+        tokio::select! {
+            _event = litep2p1.next_event() => {}
+            _event = litep2p2.next_event() => {}
+            event = ping_event_stream1.next() => match event {
+                Some(PingEvent::Ping { peer, .. }) => {
+                    assert_eq!(peer, peer2);
+                    pings += 1;
+                }
+                None => panic!("ping event stream closed"),
+            },
+            _ = tokio::time::sleep(Duration::from_secs(10)) => panic!("ping timed out"),
+        }
+    }
+
+    let stats = ping_handle1.latency(&peer2).unwrap();
+    assert_eq!(stats.samples, 4);
+    assert!(stats.ewma > Duration::ZERO);
+    assert!(stats.min <= stats.last && stats.last <= stats.max);
+    assert!(stats.jitter <= stats.max - stats.min);
+    assert_eq!(ping_handle1.peers_by_latency()[0].0, peer2);
+}
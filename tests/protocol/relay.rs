@@ -0,0 +1,355 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use futures::StreamExt;
+use litep2p::{
+    config::ConfigBuilder,
+    error::{DialError, RelayError},
+    protocol::libp2p::{
+        ping::{ConfigBuilder as PingConfigBuilder, PingEvent},
+        relay::{ConfigBuilder as RelayConfigBuilder, RelayEvent, RelayHandle},
+    },
+    transport::tcp::config::Config as TcpConfig,
+    Litep2p, Litep2pEvent, PeerId,
+};
+use multiaddr::{Multiaddr, Protocol};
+
+use std::time::Duration;
+
+fn tcp_config() -> TcpConfig {
+    TcpConfig {
+        listen_addresses: vec!["/ip4/127.0.0.1/tcp/0".parse().unwrap()],
+        ..Default::default()
+    }
+}
+
+fn create_relay(config: RelayConfigBuilder) -> (Litep2p, Multiaddr) {
+    let (relay_config, _handle) = config.with_server().build();
+    let config = ConfigBuilder::new()
+        .with_tcp(tcp_config())
+        .with_libp2p_relay(relay_config)
+        .build();
+
+    let litep2p = Litep2p::new(config).unwrap();
+    let address = litep2p.listen_addresses().next().unwrap().clone();
+
+    (litep2p, address)
+}
+
+fn create_client() -> (Litep2p, RelayHandle) {
+    let (relay_config, handle) = RelayConfigBuilder::new().build();
+    let config = ConfigBuilder::new()
+        .with_tcp(tcp_config())
+        .with_libp2p_relay(relay_config)
+        .build();
+
+    (Litep2p::new(config).unwrap(), handle)
+}
+
+/// Make a reservation with the relay at `address` and wait until it's answered.
+async fn reserve(
+    client: &mut Litep2p,
+    handle: &mut RelayHandle,
+    address: Multiaddr,
+) -> Result<Vec<Multiaddr>, RelayError> {
+    handle.reserve(address).await.unwrap();
+
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            tokio::select! {
+                _ = client.next_event() => {}
+                event = handle.next() => match event.unwrap() {
+                    RelayEvent::ReservationAccepted { addresses, .. } => return Ok(addresses),
+                    RelayEvent::ReservationFailed { error, .. } => return Err(error),
+                    event => panic!("unexpected event: {event:?}"),
+                },
+            }
+        }
+    })
+    .await
+    .expect("reservation to be answered")
+}
+
+fn circuit_address(relay_address: &Multiaddr, destination: PeerId) -> Multiaddr {
+    relay_address
+        .clone()
+        .with(Protocol::P2pCircuit)
+        .with(Protocol::P2p(destination.into()))
+}
+
+#[tokio::test]
+async fn connection_through_relay() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (mut relay, relay_address) = create_relay(RelayConfigBuilder::new());
+    tokio::spawn(async move { while relay.next_event().await.is_some() {} });
+
+    let (ping_config1, mut ping_events1) = PingConfigBuilder::new().build();
+    let (relay_config1, _handle1) = RelayConfigBuilder::new().build();
+    let mut litep2p1 = Litep2p::new(
+        ConfigBuilder::new()
+            .with_tcp(tcp_config())
+            .with_libp2p_relay(relay_config1)
+            .with_libp2p_ping(ping_config1)
+            .build(),
+    )
+    .unwrap();
+
+    let (ping_config2, _ping_events2) = PingConfigBuilder::new().build();
+    let (relay_config2, mut handle2) = RelayConfigBuilder::new().build();
+    let mut litep2p2 = Litep2p::new(
+        ConfigBuilder::new()
+            .with_tcp(tcp_config())
+            .with_libp2p_relay(relay_config2)
+            .with_libp2p_ping(ping_config2)
+            .build(),
+    )
+    .unwrap();
+    let peer2 = *litep2p2.local_peer_id();
+
+    let addresses = reserve(&mut litep2p2, &mut handle2, relay_address.clone()).await.unwrap();
+    let expected = circuit_address(&relay_address, peer2);
+    assert_eq!(addresses, vec![expected.clone()]);
+    assert!(litep2p2.public_addresses().get_addresses().contains(&expected));
+
+    litep2p1.dial_address(expected).await.unwrap();
+
+    let mut connected1 = false;
+    let mut connected2 = false;
+    let mut pinged = false;
+
+    tokio::time::timeout(Duration::from_secs(20), async {
+        while !connected1 || !connected2 || !pinged {
+            tokio::select! {
+                event = litep2p1.next_event() => match event.unwrap() {
+                    Litep2pEvent::ConnectionEstablished { peer, endpoint } if peer == peer2 => {
+                        assert!(endpoint.is_relayed());
+                        assert!(!endpoint.is_listener());
+                        connected1 = true;
+                    }
+                    Litep2pEvent::DialFailure { address, error } =>
+                        panic!("failed to dial {address}: {error:?}"),
+                    _ => {}
+                },
+                event = litep2p2.next_event() => match event.unwrap() {
+                    Litep2pEvent::ConnectionEstablished { endpoint, .. } if endpoint.is_relayed() => {
+                        assert!(endpoint.is_listener());
+                        connected2 = true;
+                    }
+                    _ => {}
+                },
+                _ = handle2.next() => {}
+                event = ping_events1.next() => match event.unwrap() {
+                    PingEvent::Ping { peer, .. } if peer == peer2 => pinged = true,
+                    _ => {}
+                },
+            }
+        }
+    })
+    .await
+    .expect("relayed connection to be established");
+}
+
+#[tokio::test]
+async fn destination_without_reservation() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (mut relay, relay_address) = create_relay(RelayConfigBuilder::new());
+    tokio::spawn(async move { while relay.next_event().await.is_some() {} });
+
+    let (mut litep2p, _handle) = create_client();
+    let address = circuit_address(&relay_address, PeerId::random());
+
+    litep2p.dial_address(address.clone()).await.unwrap();
+
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            if let Litep2pEvent::DialFailure {
+                address: dialed,
+                error,
+            } = litep2p.next_event().await.unwrap()
+            {
+                assert_eq!(dialed, address);
+                assert!(std::matches!(
+                    error,
+                    DialError::RelayError(RelayError::NoReservation)
+                ));
+                break;
+            }
+        }
+    })
+    .await
+    .expect("dial to fail");
+}
+
+#[tokio::test]
+async fn reservation_limit_exceeded() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (mut relay, relay_address) =
+        create_relay(RelayConfigBuilder::new().with_max_reservations(0));
+    tokio::spawn(async move { while relay.next_event().await.is_some() {} });
+
+    let (mut litep2p, mut handle) = create_client();
+
+    assert_eq!(
+        reserve(&mut litep2p, &mut handle, relay_address.clone()).await,
+        Err(RelayError::ResourceLimitExceeded)
+    );
+    assert!(litep2p.public_addresses().get_addresses().is_empty());
+}
+
+#[tokio::test]
+async fn relay_disabled() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (mut relay, _relay_handle) = create_client();
+    let relay_address = relay.listen_addresses().next().unwrap().clone();
+    tokio::spawn(async move { while relay.next_event().await.is_some() {} });
+
+    let (mut litep2p, mut handle) = create_client();
+
+    assert_eq!(
+        reserve(&mut litep2p, &mut handle, relay_address).await,
+        Err(RelayError::PermissionDenied)
+    );
+}
+
+#[tokio::test]
+async fn reservation_renewed_and_cancelled() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (mut relay, relay_address) =
+        create_relay(RelayConfigBuilder::new().with_reservation_duration(Duration::from_secs(4)));
+    let relay_peer = *relay.local_peer_id();
+    tokio::spawn(async move { while relay.next_event().await.is_some() {} });
+
+    let (mut litep2p, mut handle) = create_client();
+
+    let addresses = reserve(&mut litep2p, &mut handle, relay_address.clone()).await.unwrap();
+
+    // reservation is renewed before it expires
+    assert_eq!(reserve_renewal(&mut litep2p, &mut handle).await, addresses);
+
+    handle.cancel_reservation(relay_peer).await;
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            tokio::select! {
+                _ = litep2p.next_event() => {}
+                event = handle.next() => match event.unwrap() {
+                    RelayEvent::ReservationClosed { relay } => {
+                        assert_eq!(relay, relay_peer);
+                        break;
+                    }
+                    event => panic!("unexpected event: {event:?}"),
+                },
+            }
+        }
+    })
+    .await
+    .expect("reservation to be closed");
+
+    assert!(litep2p.public_addresses().get_addresses().is_empty());
+}
+
+/// Wait until the reservation is renewed.
+async fn reserve_renewal(litep2p: &mut Litep2p, handle: &mut RelayHandle) -> Vec<Multiaddr> {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            tokio::select! {
+                _ = litep2p.next_event() => {}
+                event = handle.next() => match event.unwrap() {
+                    RelayEvent::ReservationAccepted { addresses, .. } => return addresses,
+                    event => panic!("unexpected event: {event:?}"),
+                },
+            }
+        }
+    })
+    .await
+    .expect("reservation to be renewed")
+}
+
+#[tokio::test]
+async fn circuit_closed_after_duration_limit() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (relay_config, mut relay_handle) = RelayConfigBuilder::new()
+        .with_server()
+        .with_circuit_limits(Duration::from_secs(1), 1 << 20)
+        .build();
+    let mut relay = Litep2p::new(
+        ConfigBuilder::new()
+            .with_tcp(tcp_config())
+            .with_libp2p_relay(relay_config)
+            .build(),
+    )
+    .unwrap();
+    let relay_address = relay.listen_addresses().next().unwrap().clone();
+    tokio::spawn(async move { while relay.next_event().await.is_some() {} });
+
+    let (mut litep2p1, _handle1) = create_client();
+    let (mut litep2p2, mut handle2) = create_client();
+    let peer1 = *litep2p1.local_peer_id();
+    let peer2 = *litep2p2.local_peer_id();
+
+    reserve(&mut litep2p2, &mut handle2, relay_address.clone()).await.unwrap();
+    litep2p1.dial_address(circuit_address(&relay_address, peer2)).await.unwrap();
+
+    let mut opened = None;
+    let mut closed = false;
+
+    tokio::time::timeout(Duration::from_secs(20), async {
+        while !closed {
+            tokio::select! {
+                _ = litep2p1.next_event() => {}
+                _ = litep2p2.next_event() => {}
+                _ = handle2.next() => {}
+                event = relay_handle.next() => match event.unwrap() {
+                    RelayEvent::CircuitOpened { source, destination } => {
+                        assert_eq!((source, destination), (peer1, peer2));
+                        opened = Some(std::time::Instant::now());
+                    }
+                    RelayEvent::CircuitClosed { source, destination } => {
+                        // closed by the relay before the keep-alive timeout closes the connection
+                        assert!(opened.unwrap().elapsed() < Duration::from_secs(4));
+                        assert_eq!((source, destination), (peer1, peer2));
+                        closed = true;
+                    }
+                    event => panic!("unexpected event: {event:?}"),
+                },
+            }
+        }
+    })
+    .await
+    .expect("circuit to be closed");
+}
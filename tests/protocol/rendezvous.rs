@@ -0,0 +1,188 @@
+// Copyright 2025 litep2p developers
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use litep2p::{
+    config::ConfigBuilder,
+    error::RendezvousError,
+    protocol::libp2p::rendezvous::{ConfigBuilder as RendezvousConfigBuilder, RendezvousHandle},
+    transport::tcp::config::Config as TcpConfig,
+    Litep2p, Litep2pEvent, PeerId,
+};
+
+use std::{future::Future, time::Duration};
+
+fn initialize_litep2p(server: bool) -> (Litep2p, RendezvousHandle) {
+    let (rendezvous_config, handle) = RendezvousConfigBuilder::new().with_server(server).build();
+
+    let litep2p = Litep2p::new(
+        ConfigBuilder::new()
+            .with_tcp(TcpConfig {
+                listen_addresses: vec!["/ip4/127.0.0.1/tcp/0".parse().unwrap()],
+                ..Default::default()
+            })
+            .with_libp2p_rendezvous(rendezvous_config)
+            .build(),
+    )
+    .unwrap();
+
+    (litep2p, handle)
+}
+
+/// Drive `litep2p` until it's closed.
+fn spawn_litep2p(mut litep2p: Litep2p) {
+    tokio::spawn(async move { while litep2p.next_event().await.is_some() {} });
+}
+
+/// Drive `litep2p` until `future` completes.
+async fn drive<T>(litep2p: &mut Litep2p, future: impl Future<Output = T>) -> T {
+    tokio::pin!(future);
+
+    loop {
+        tokio::select! {
+            result = &mut future => return result,
+            _ = litep2p.next_event() => {}
+        }
+    }
+}
+
+/// Create rendezvous point and return its peer ID after adding its address to `litep2ps`.
+fn spawn_rendezvous_point(litep2ps: &mut [&mut Litep2p]) -> (PeerId, RendezvousHandle) {
+    let (point, handle) = initialize_litep2p(true);
+    let peer = *point.local_peer_id();
+    let address = point.listen_addresses().next().unwrap().clone();
+
+    for litep2p in litep2ps {
+        litep2p.add_known_address(peer, std::iter::once(address.clone()));
+    }
+    spawn_litep2p(point);
+
+    (peer, handle)
+}
+
+#[tokio::test]
+async fn register_and_discover_through_rendezvous_point() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (mut alice, alice_handle) = initialize_litep2p(false);
+    let (mut bob, bob_handle) = initialize_litep2p(false);
+    let alice_peer = *alice.local_peer_id();
+    let mut alice_address = alice.listen_addresses().next().unwrap().clone();
+    alice_address.pop();
+    let (point, _point_handle) = spawn_rendezvous_point(&mut [&mut alice, &mut bob]);
+    spawn_litep2p(alice);
+
+    tokio::time::timeout(Duration::from_secs(10), async {
+        let ttl = alice_handle.register(point, "namespace", None).await.unwrap();
+        assert_eq!(ttl, Duration::from_secs(2 * 60 * 60));
+
+        let (registrations, cookie) = drive(
+            &mut bob,
+            bob_handle.discover(point, Some("namespace".to_owned()), None, None),
+        )
+        .await
+        .unwrap();
+        assert_eq!(registrations.len(), 1);
+        assert_eq!(registrations[0].peer, alice_peer);
+        assert_eq!(registrations[0].namespace, "namespace");
+        assert!(registrations[0].addresses.contains(&alice_address));
+
+        // no new registrations since the previous discovery
+        let (registrations, _) = drive(
+            &mut bob,
+            bob_handle.discover(point, Some("namespace".to_owned()), None, Some(cookie)),
+        )
+        .await
+        .unwrap();
+        assert!(registrations.is_empty());
+
+        // the discovered address of alice allows dialing her by peer ID
+        bob.dial(&alice_peer).await.unwrap();
+        loop {
+            if let Some(Litep2pEvent::ConnectionEstablished { peer, .. }) = bob.next_event().await {
+                if peer == alice_peer {
+                    break;
+                }
+            }
+        }
+        spawn_litep2p(bob);
+
+        alice_handle.unregister(point, "namespace").await.unwrap();
+        loop {
+            let (registrations, _) = bob_handle
+                .discover(point, Some("namespace".to_owned()), None, None)
+                .await
+                .unwrap();
+
+            if registrations.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("peer to be discovered");
+}
+
+#[tokio::test]
+async fn invalid_registration_is_rejected() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (mut alice, alice_handle) = initialize_litep2p(false);
+    let (point, _point_handle) = spawn_rendezvous_point(&mut [&mut alice]);
+    spawn_litep2p(alice);
+
+    assert_eq!(
+        alice_handle.register(point, "namespace", Some(Duration::from_secs(60))).await,
+        Err(RendezvousError::InvalidTtl),
+    );
+    assert_eq!(
+        alice_handle.register(point, "", None).await,
+        Err(RendezvousError::InvalidNamespace),
+    );
+}
+
+#[tokio::test]
+async fn requests_fail_if_peer_is_not_rendezvous_point() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .try_init();
+
+    let (mut alice, alice_handle) = initialize_litep2p(false);
+    let (bob, _bob_handle) = initialize_litep2p(false);
+    let bob_peer = *bob.local_peer_id();
+    let address = bob.listen_addresses().next().unwrap().clone();
+
+    alice.add_known_address(bob_peer, std::iter::once(address));
+    spawn_litep2p(alice);
+    spawn_litep2p(bob);
+
+    assert_eq!(
+        alice_handle.discover(bob_peer, None, None, None).await,
+        Err(RendezvousError::ConnectionFailed),
+    );
+    assert_eq!(
+        alice_handle.discover(PeerId::random(), None, None, None).await,
+        Err(RendezvousError::ConnectionFailed),
+    );
+}
@@ -2334,6 +2334,7 @@ async fn dial_failure(transport: Transport) {
             .with(Protocol::Tcp(5))
             .with(Protocol::Ws(std::borrow::Cow::Owned("/".to_string())))
             .with(Protocol::P2p(Multihash::from(peer))),
+        _ => unimplemented!("transport not supported by the test"),
     };
 
     let config = add_transport(litep2p_config, transport).build();